@@ -0,0 +1,359 @@
+//! Minimal Arrow IPC stream writer for Float64 columns
+//!
+//! Writes the encapsulated streaming format (a schema message, one record
+//! batch per call, then the end-of-stream marker) with hand-built
+//! flatbuffers, so the tool needs no Arrow dependency. Every column is a
+//! non-nullable Float64, which is all a waveform chunk needs.
+//!
+//! Flatbuffer objects are laid out front to back: each table is preceded by
+//! its vtable and followed by the objects it references, so every `uoffset`
+//! points forward as the format requires.
+
+use std::io::{self, Write};
+
+const CONTINUATION: u32 = 0xFFFF_FFFF;
+/// `MetadataVersion::V5`
+const METADATA_V5: i16 = 4;
+/// `MessageHeader` union tags
+const HEADER_SCHEMA: u8 = 1;
+const HEADER_RECORD_BATCH: u8 = 3;
+/// `Type::FloatingPoint` union tag
+const TYPE_FLOATING_POINT: u8 = 3;
+/// `Precision::DOUBLE`
+const PRECISION_DOUBLE: i16 = 2;
+
+// ============================================================================
+// Flatbuffer Builder
+// ============================================================================
+
+/// Flatbuffer value; a table's fields are indexed by field id
+enum Value {
+    U8(u8),
+    I16(i16),
+    I64(i64),
+    Str(String),
+    Table(Vec<Option<Value>>),
+    Tables(Vec<Value>),
+    /// Vector of 16-byte structs (`FieldNode`, `Buffer`)
+    Structs(Vec<[i64; 2]>),
+}
+
+impl Value {
+    /// Inline size in a table; references are 4-byte offsets
+    fn inline_size(&self) -> usize {
+        match self {
+            Value::U8(_) => 1,
+            Value::I16(_) => 2,
+            Value::I64(_) => 8,
+            _ => 4,
+        }
+    }
+}
+
+struct Builder {
+    buf: Vec<u8>,
+}
+
+impl Builder {
+    /// Serialize `root`, padded to 8 bytes as IPC metadata must be
+    fn finish(root: &Value) -> Vec<u8> {
+        let mut builder = Builder { buf: vec![0; 4] };
+        let pos = builder.write(root);
+        builder.patch(0, pos);
+        builder.pad_for(0, 8);
+        builder.buf
+    }
+
+    /// Pad so that `extra` more bytes end on an `align` boundary
+    fn pad_for(&mut self, extra: usize, align: usize) {
+        while !(self.buf.len() + extra).is_multiple_of(align) {
+            self.buf.push(0);
+        }
+    }
+
+    /// Point the `uoffset` at `at` to `target`
+    fn patch(&mut self, at: usize, target: usize) {
+        let offset = (target - at) as u32;
+        self.buf[at..at + 4].copy_from_slice(&offset.to_le_bytes());
+    }
+
+    fn write(&mut self, value: &Value) -> usize {
+        match value {
+            Value::Str(s) => {
+                self.pad_for(0, 4);
+                let pos = self.buf.len();
+                self.buf.extend((s.len() as u32).to_le_bytes());
+                self.buf.extend(s.as_bytes());
+                self.buf.push(0);
+                pos
+            }
+            Value::Structs(items) => {
+                // Elements must be 8-aligned, so the length sits just before
+                self.pad_for(4, 8);
+                let pos = self.buf.len();
+                self.buf.extend((items.len() as u32).to_le_bytes());
+                for [a, b] in items {
+                    self.buf.extend(a.to_le_bytes());
+                    self.buf.extend(b.to_le_bytes());
+                }
+                pos
+            }
+            Value::Tables(items) => {
+                self.pad_for(0, 4);
+                let pos = self.buf.len();
+                self.buf.extend((items.len() as u32).to_le_bytes());
+                let first_slot = self.buf.len();
+                self.buf.resize(first_slot + 4 * items.len(), 0);
+                for (i, item) in items.iter().enumerate() {
+                    let target = self.write(item);
+                    self.patch(first_slot + 4 * i, target);
+                }
+                pos
+            }
+            Value::Table(fields) => self.write_table(fields),
+            Value::U8(_) | Value::I16(_) | Value::I64(_) => {
+                unreachable!("scalars are written inline")
+            }
+        }
+    }
+
+    fn write_table(&mut self, fields: &[Option<Value>]) -> usize {
+        // Largest fields first: with the body starting 8-aligned after the
+        // 4-byte vtable offset, every field lands on its natural alignment
+        let mut order: Vec<usize> = (0..fields.len()).filter(|&i| fields[i].is_some()).collect();
+        order.sort_by_key(|&i| std::cmp::Reverse(fields[i].as_ref().map_or(0, Value::inline_size)));
+
+        let mut field_offsets = vec![0u16; fields.len()];
+        let mut table_size = 4;
+        for &i in &order {
+            field_offsets[i] = table_size as u16;
+            table_size += fields[i].as_ref().map_or(0, Value::inline_size);
+        }
+
+        self.pad_for(0, 2);
+        let vtable = self.buf.len();
+        self.buf
+            .extend(((4 + 2 * fields.len()) as u16).to_le_bytes());
+        self.buf.extend((table_size as u16).to_le_bytes());
+        for offset in &field_offsets {
+            self.buf.extend(offset.to_le_bytes());
+        }
+
+        self.pad_for(4, 8);
+        let table = self.buf.len();
+        self.buf.extend(((table - vtable) as i32).to_le_bytes());
+        let mut references = Vec::new();
+        for &i in &order {
+            match fields[i].as_ref() {
+                Some(Value::U8(v)) => self.buf.push(*v),
+                Some(Value::I16(v)) => self.buf.extend(v.to_le_bytes()),
+                Some(Value::I64(v)) => self.buf.extend(v.to_le_bytes()),
+                Some(child) => {
+                    references.push((self.buf.len(), child));
+                    self.buf.extend([0; 4]);
+                }
+                None => {}
+            }
+        }
+
+        for (slot, child) in references {
+            let target = self.write(child);
+            self.patch(slot, target);
+        }
+        table
+    }
+}
+
+// ============================================================================
+// Stream Writer
+// ============================================================================
+
+/// Arrow IPC stream of Float64 columns
+pub struct ArrowStreamWriter<W: Write> {
+    inner: W,
+    num_columns: usize,
+}
+
+impl<W: Write> ArrowStreamWriter<W> {
+    /// Start a stream, writing the schema for `columns`
+    pub fn new(mut inner: W, columns: &[String]) -> io::Result<Self> {
+        let fields = columns
+            .iter()
+            .map(|name| {
+                Value::Table(vec![
+                    Some(Value::Str(name.clone())),
+                    Some(Value::U8(0)),
+                    Some(Value::U8(TYPE_FLOATING_POINT)),
+                    Some(Value::Table(vec![Some(Value::I16(PRECISION_DOUBLE))])),
+                    None,
+                    // Readers expect `children` even when empty
+                    Some(Value::Tables(Vec::new())),
+                ])
+            })
+            .collect();
+        let schema = Value::Table(vec![Some(Value::I16(0)), Some(Value::Tables(fields))]);
+        write_message(&mut inner, HEADER_SCHEMA, schema, &[])?;
+        Ok(Self {
+            inner,
+            num_columns: columns.len(),
+        })
+    }
+
+    /// Write one record batch; every column must have the same length
+    pub fn write_batch(&mut self, columns: &[&[f64]]) -> io::Result<()> {
+        assert_eq!(columns.len(), self.num_columns, "column count");
+        let rows = columns.first().map_or(0, |c| c.len());
+
+        let mut nodes = Vec::with_capacity(columns.len());
+        let mut buffers = Vec::with_capacity(2 * columns.len());
+        let mut body = Vec::with_capacity(rows * 8 * columns.len());
+        for column in columns {
+            assert_eq!(column.len(), rows, "column length");
+            nodes.push([rows as i64, 0]);
+            // Empty validity bitmap: no nulls
+            buffers.push([body.len() as i64, 0]);
+            buffers.push([body.len() as i64, (rows * 8) as i64]);
+            for v in column.iter() {
+                body.extend(v.to_le_bytes());
+            }
+        }
+
+        let batch = Value::Table(vec![
+            Some(Value::I64(rows as i64)),
+            Some(Value::Structs(nodes)),
+            Some(Value::Structs(buffers)),
+        ]);
+        write_message(&mut self.inner, HEADER_RECORD_BATCH, batch, &body)
+    }
+
+    /// Write the end-of-stream marker and return the inner writer
+    pub fn finish(mut self) -> io::Result<W> {
+        self.inner.write_all(&CONTINUATION.to_le_bytes())?;
+        self.inner.write_all(&0u32.to_le_bytes())?;
+        Ok(self.inner)
+    }
+}
+
+/// Write one encapsulated message: continuation, metadata length,
+/// `Message` flatbuffer, body
+fn write_message<W: Write>(
+    w: &mut W,
+    header_type: u8,
+    header: Value,
+    body: &[u8],
+) -> io::Result<()> {
+    let message = Value::Table(vec![
+        Some(Value::I16(METADATA_V5)),
+        Some(Value::U8(header_type)),
+        Some(header),
+        Some(Value::I64(body.len() as i64)),
+    ]);
+    let metadata = Builder::finish(&message);
+    w.write_all(&CONTINUATION.to_le_bytes())?;
+    w.write_all(&(metadata.len() as i32).to_le_bytes())?;
+    w.write_all(&metadata)?;
+    w.write_all(body)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u32_at(buf: &[u8], pos: usize) -> usize {
+        u32::from_le_bytes(buf[pos..pos + 4].try_into().unwrap()) as usize
+    }
+
+    /// Position of field `id` in the table at `table`, following its vtable
+    fn field(buf: &[u8], table: usize, id: usize) -> Option<usize> {
+        let soffset = i32::from_le_bytes(buf[table..table + 4].try_into().unwrap());
+        let vtable = (table as i64 - soffset as i64) as usize;
+        let vtable_len = u16::from_le_bytes([buf[vtable], buf[vtable + 1]]) as usize;
+        if 4 + 2 * id >= vtable_len {
+            return None;
+        }
+        let at = vtable + 4 + 2 * id;
+        match u16::from_le_bytes([buf[at], buf[at + 1]]) {
+            0 => None,
+            offset => Some(table + offset as usize),
+        }
+    }
+
+    fn deref(buf: &[u8], pos: usize) -> usize {
+        pos + u32_at(buf, pos)
+    }
+
+    /// Split a stream into `(metadata, body)` messages, checking framing
+    fn messages(stream: &[u8]) -> Vec<(&[u8], &[u8])> {
+        let mut out = Vec::new();
+        let mut pos = 0;
+        loop {
+            assert_eq!(u32_at(stream, pos), CONTINUATION as usize);
+            let len = u32_at(stream, pos + 4);
+            pos += 8;
+            if len == 0 {
+                assert_eq!(pos, stream.len());
+                return out;
+            }
+            assert_eq!(len % 8, 0);
+            let metadata = &stream[pos..pos + len];
+            let message = deref(metadata, 0);
+            let body_len = field(metadata, message, 3)
+                .map(|p| i64::from_le_bytes(metadata[p..p + 8].try_into().unwrap()))
+                .unwrap() as usize;
+            pos += len;
+            out.push((metadata, &stream[pos..pos + body_len]));
+            pos += body_len;
+        }
+    }
+
+    #[test]
+    fn test_stream_layout() {
+        let columns = ["TIME".to_string(), "v(out)".to_string()];
+        let mut writer = ArrowStreamWriter::new(Vec::new(), &columns).unwrap();
+        writer
+            .write_batch(&[&[0.0, 1.0, 2.0], &[0.5, 0.25, 0.125]])
+            .unwrap();
+        writer.write_batch(&[&[3.0], &[-1.0]]).unwrap();
+        let stream = writer.finish().unwrap();
+
+        let messages = messages(&stream);
+        assert_eq!(messages.len(), 3);
+
+        // Schema: second field is named v(out) and typed Float64
+        let (schema_msg, body) = messages[0];
+        assert!(body.is_empty());
+        let message = deref(schema_msg, 0);
+        assert_eq!(
+            schema_msg[field(schema_msg, message, 1).unwrap()],
+            HEADER_SCHEMA
+        );
+        let schema = deref(schema_msg, field(schema_msg, message, 2).unwrap());
+        let fields = deref(schema_msg, field(schema_msg, schema, 1).unwrap());
+        assert_eq!(u32_at(schema_msg, fields), 2);
+        let second = deref(schema_msg, fields + 8);
+        let name = deref(schema_msg, field(schema_msg, second, 0).unwrap());
+        let len = u32_at(schema_msg, name);
+        assert_eq!(&schema_msg[name + 4..name + 4 + len], b"v(out)");
+        assert_eq!(
+            schema_msg[field(schema_msg, second, 2).unwrap()],
+            TYPE_FLOATING_POINT
+        );
+
+        // First batch: 3 rows, values buffer of column 1 holds v(out)
+        let (batch_msg, body) = messages[1];
+        let message = deref(batch_msg, 0);
+        let batch = deref(batch_msg, field(batch_msg, message, 2).unwrap());
+        let rows = field(batch_msg, batch, 0).unwrap();
+        assert_eq!(batch_msg[rows], 3);
+        let buffers = deref(batch_msg, field(batch_msg, batch, 2).unwrap());
+        assert_eq!((buffers + 4) % 8, 0, "struct elements are 8-aligned");
+        assert_eq!(u32_at(batch_msg, buffers), 4);
+        let values = buffers + 4 + 3 * 16;
+        let offset = u32_at(batch_msg, values);
+        assert_eq!(body[offset..offset + 8], 0.5f64.to_le_bytes());
+    }
+}
@@ -0,0 +1,216 @@
+//! `cat`: print data rows as tab-separated text, optionally following a
+//! file the simulator is still writing
+//!
+//! Following reopens the file every poll interval, seeks to the last row
+//! printed and prints what has been added since. Rows are printed as soon
+//! as their data block is complete; a block still being written is picked
+//! up on a later poll. Following ends once the end-of-data marker arrives.
+//! The scale must increase through the file, so swept files are printed
+//! table after table only without `--follow`.
+
+use crate::stream::chunk_signals;
+use hspice_core::{HspiceStreamReader, Result, VectorData, DEFAULT_CHUNK_SIZE};
+use std::io::Write;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+use tracing::debug;
+
+/// Print `path` to `out`; with `follow`, keep polling at that interval
+pub fn run<W: Write>(
+    path: &str,
+    signals: Option<Vec<String>>,
+    follow: Option<Duration>,
+    mut out: W,
+) -> Result<()> {
+    // One block per chunk when following, so rows appear without waiting
+    // for a full chunk
+    let chunk_size = if follow.is_some() {
+        1
+    } else {
+        DEFAULT_CHUNK_SIZE
+    };
+    let open = || -> Result<HspiceStreamReader> {
+        let mut reader = HspiceStreamReader::open(path, chunk_size)?;
+        reader.set_signals(signals.clone());
+        Ok(reader)
+    };
+
+    let mut reader = match follow {
+        Some(interval) => wait_for_header(path, interval, open)?,
+        None => open()?,
+    };
+    let mut header = false;
+    let mut last: Option<f64> = None;
+    let mut table = 0;
+
+    loop {
+        let metadata = reader.metadata();
+        for chunk in reader.by_ref() {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                // The simulator has not finished writing this block yet
+                Err(e) if follow.is_some() => {
+                    debug!(error = %e, "Stopping at incomplete data");
+                    break;
+                }
+                Err(e) => return Err(e),
+            };
+            // The scale starts over with every sweep table
+            if std::mem::replace(&mut table, chunk.table_index) != chunk.table_index {
+                last = None;
+            }
+            let columns: Vec<(&String, &VectorData)> = chunk_signals(&metadata, &chunk).collect();
+            if !std::mem::replace(&mut header, true) {
+                write_header(&columns, &mut out)?;
+            }
+            last = write_rows(&columns, last, &mut out)?.or(last);
+            if follow.is_some() {
+                out.flush()?;
+            }
+        }
+
+        let Some(interval) = follow else { break };
+        if reader.is_complete() {
+            break;
+        }
+        thread::sleep(interval);
+        reader = open()?;
+        if let Some(last) = last {
+            reader.seek_time(last)?;
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Open the file once its header has been written
+fn wait_for_header(
+    path: &str,
+    interval: Duration,
+    open: impl Fn() -> Result<HspiceStreamReader>,
+) -> Result<HspiceStreamReader> {
+    loop {
+        match open() {
+            Ok(reader) => return Ok(reader),
+            // A missing file is a mistake, not a simulation still starting
+            Err(e) if !Path::new(path).exists() => return Err(e),
+            Err(e) => debug!(error = %e, "Waiting for header"),
+        }
+        thread::sleep(interval);
+    }
+}
+
+fn write_header<W: Write>(columns: &[(&String, &VectorData)], out: &mut W) -> Result<()> {
+    let names: Vec<String> = columns
+        .iter()
+        .flat_map(|(name, vector)| match vector {
+            VectorData::Complex(_) => vec![format!("{}.re", name), format!("{}.im", name)],
+            _ => vec![name.to_string()],
+        })
+        .collect();
+    writeln!(out, "{}", names.join("\t"))?;
+    Ok(())
+}
+
+/// Write the rows whose scale is past `after`; returns the scale
+/// of the last row written
+fn write_rows<W: Write>(
+    columns: &[(&String, &VectorData)],
+    after: Option<f64>,
+    out: &mut W,
+) -> Result<Option<f64>> {
+    let Some((_, scale)) = columns.first() else {
+        return Ok(None);
+    };
+    let mut last = None;
+    for row in 0..scale.len() {
+        let t = scale.real_at(row).unwrap_or(f64::NAN);
+        if after.is_some_and(|after| t <= after) {
+            continue;
+        }
+        for (i, (_, vector)) in columns.iter().enumerate() {
+            if i > 0 {
+                write!(out, "\t")?;
+            }
+            match vector {
+                VectorData::Complex(v) => write!(out, "{}\t{}", v[row].re, v[row].im)?,
+                other => write!(out, "{}", other.real_at(row).unwrap_or(f64::NAN))?,
+            }
+        }
+        writeln!(out)?;
+        last = Some(t);
+    }
+    Ok(last)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hspice_core::testing::{temp_path, SyntheticWaveform};
+
+    #[test]
+    fn test_cat_rows() {
+        let path = temp_path("cli_cat.tr0");
+        SyntheticWaveform::new()
+            .signals(2)
+            .points(40)
+            .write_tr0(&path)
+            .unwrap();
+        let path = path.to_str().unwrap();
+
+        let mut out = Vec::new();
+        run(path, Some(Vec::new()), None, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text.lines().next(), Some("TIME"));
+        assert_eq!(text.lines().count(), 41);
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_follow_growing_file() {
+        let full = temp_path("cli_follow_full.tr0");
+        SyntheticWaveform::new()
+            .signals(1)
+            .points(300)
+            .block_size(32)
+            .write_tr0(&full)
+            .unwrap();
+        let bytes = std::fs::read(&full).unwrap();
+
+        // Start with a partial file and let a writer thread finish it
+        let growing = temp_path("cli_follow_growing.tr0");
+        let cut = bytes.len() / 2;
+        std::fs::write(&growing, &bytes[..cut]).unwrap();
+        let writer = {
+            let growing = growing.clone();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(50));
+                let mut file = std::fs::OpenOptions::new()
+                    .append(true)
+                    .open(growing)
+                    .unwrap();
+                file.write_all(&bytes[cut..]).unwrap();
+            })
+        };
+
+        let mut out = Vec::new();
+        run(
+            growing.to_str().unwrap(),
+            None,
+            Some(Duration::from_millis(10)),
+            &mut out,
+        )
+        .unwrap();
+        writer.join().unwrap();
+
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text.lines().count(), 301);
+        std::fs::remove_file(full).ok();
+        std::fs::remove_file(growing).ok();
+    }
+}
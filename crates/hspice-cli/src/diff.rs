@@ -0,0 +1,103 @@
+//! `diff`: compare a candidate run against a golden run
+//!
+//! Built on `hspice_core::compare`. The exit status says whether the runs
+//! match, so the command can gate regression scripts directly.
+
+use hspice_core::compare::{compare_files, CompareReport, Tolerance};
+use hspice_core::Result;
+use std::io::Write;
+
+/// Compare the files and write the report; returns whether they match
+pub fn run<W: Write>(
+    golden: &str,
+    candidate: &str,
+    tolerance: Tolerance,
+    json: bool,
+    out: &mut W,
+) -> Result<bool> {
+    let report = compare_files(golden, candidate, tolerance)?;
+    if json {
+        writeln!(out, "{}", report.to_json())?;
+    } else {
+        write_text(&report, out)?;
+    }
+    Ok(report.passed())
+}
+
+fn write_text<W: Write>(report: &CompareReport, out: &mut W) -> Result<()> {
+    for diff in report.failures() {
+        writeln!(
+            out,
+            "FAIL {} [table {}]: {}/{} points out of tolerance, max error {:e} at {:e}",
+            diff.name, diff.table, diff.violations, diff.points, diff.max_error, diff.max_error_at
+        )?;
+    }
+    for name in &report.missing {
+        writeln!(out, "MISSING {}", name)?;
+    }
+    for note in &report.notes {
+        writeln!(out, "NOTE {}", note)?;
+    }
+
+    let failed = report.failures().count();
+    if report.passed() {
+        writeln!(out, "{} signal(s) within tolerance", report.signals.len())?;
+    } else {
+        writeln!(
+            out,
+            "{} of {} signal(s) differ, {} missing",
+            failed,
+            report.signals.len(),
+            report.missing.len()
+        )?;
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hspice_core::testing::{temp_path, SyntheticWaveform};
+
+    #[test]
+    fn test_diff_status() {
+        let golden = temp_path("cli_diff_golden.tr0");
+        let other = temp_path("cli_diff_other.tr0");
+        SyntheticWaveform::new()
+            .signals(2)
+            .points(30)
+            .write_tr0(&golden)
+            .unwrap();
+        SyntheticWaveform::new()
+            .signals(3)
+            .points(30)
+            .write_tr0(&other)
+            .unwrap();
+        let (golden, other) = (golden.to_str().unwrap(), other.to_str().unwrap());
+        let tolerance = Tolerance::new(1e-9, 0.0);
+
+        let mut out = Vec::new();
+        assert!(run(golden, golden, tolerance, false, &mut out).unwrap());
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .contains("2 signal(s) within tolerance"));
+
+        // The golden run lacks the third signal, so only the candidate
+        // direction reports it missing
+        let mut out = Vec::new();
+        assert!(!run(other, golden, tolerance, false, &mut out).unwrap());
+        assert!(String::from_utf8(out).unwrap().contains("MISSING"));
+
+        let mut out = Vec::new();
+        run(other, golden, tolerance, true, &mut out).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(json["passed"], false);
+
+        std::fs::remove_file(golden).ok();
+        std::fs::remove_file(other).ok();
+    }
+}
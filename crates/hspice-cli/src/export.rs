@@ -0,0 +1,374 @@
+//! `export` and `convert`: write a file's signals as CSV, JSON, NumPy .npz,
+//! MATLAB .mat or SPICE3 raw
+//!
+//! Any input `read_any` accepts can be exported. `--signals` and `--table`
+//! narrow the output before it is written; the scale is always kept.
+//! `convert` from an HSPICE file to CSV or JSON Lines streams the file
+//! through `hspice_core::convert_reader` instead of loading it.
+
+use crate::progress;
+use hspice_core::mat::to_mat;
+use hspice_core::npz::to_npz;
+use hspice_core::{
+    convert_reader, detect_format, read_stream, write_spice3_raw_with, ConvertFormat, FileFormat,
+    RawEncoding, Result, VectorData, WaveformError, WaveformResult,
+};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use tracing::info;
+
+/// Output format of `export` and `convert`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Mat,
+    Npz,
+    Raw,
+    /// SPICE3 raw with an ASCII `Values:` section
+    RawAscii {
+        precision: usize,
+    },
+}
+
+impl ExportFormat {
+    pub const NAMES: [&'static str; 6] = ["csv", "json", "mat", "npz", "raw", "raw-ascii"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            "mat" => Some(Self::Mat),
+            "npz" => Some(Self::Npz),
+            "raw" => Some(Self::Raw),
+            "raw-ascii" => Some(Self::RawAscii {
+                precision: RawEncoding::DEFAULT_PRECISION,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Format named by the extension of `path`
+    pub fn from_path(path: &str) -> Option<Self> {
+        let ext = Path::new(path).extension()?.to_str()?;
+        Self::from_name(&ext.to_ascii_lowercase())
+    }
+}
+
+/// What to export
+#[derive(Debug, Clone, Default)]
+pub struct Selection {
+    /// Signals to keep (all if `None`)
+    pub signals: Option<Vec<String>>,
+    /// Sweep table to keep (all if `None`)
+    pub table: Option<usize>,
+}
+
+/// Export `path` in `format` to `output`, or to `stdout` if `output` is
+/// `None` (not possible for raw, which is written by path)
+pub fn run<W: Write>(
+    path: &str,
+    format: ExportFormat,
+    selection: &Selection,
+    output: Option<&str>,
+    show_progress: bool,
+    stdout: W,
+) -> Result<()> {
+    let result = select(progress::read(path, show_progress)?, selection)?;
+
+    match (format, output) {
+        (ExportFormat::Raw | ExportFormat::RawAscii { .. }, Some(output)) => {
+            let encoding = match format {
+                ExportFormat::RawAscii { precision } => RawEncoding::Ascii { precision },
+                _ => RawEncoding::Binary,
+            };
+            let report = write_spice3_raw_with(&result, output, encoding)?;
+            for warning in &report.warnings {
+                tracing::warn!("{}", warning);
+            }
+        }
+        (ExportFormat::Raw | ExportFormat::RawAscii { .. }, None) => {
+            return Err(WaveformError::ParseError(
+                "raw export needs an output file (--output)".into(),
+            ))
+        }
+        (_, Some(output)) => {
+            let mut writer = BufWriter::new(File::create(output)?);
+            write_output(&result, format, &mut writer)?;
+            writer.flush()?;
+        }
+        (_, None) => {
+            let mut stdout = stdout;
+            write_output(&result, format, &mut stdout)?;
+            stdout.flush()?;
+        }
+    }
+
+    info!(file = %path, ?format, signals = result.num_vars(), "Export written");
+    Ok(())
+}
+
+/// `convert` `input` to `output`, the format taken from its extension
+pub fn convert(input: &str, output: &str, show_progress: bool) -> Result<()> {
+    if let Some(format) = ConvertFormat::from_path(output) {
+        if detect_format(input)? == FileFormat::Hspice {
+            let reader = progress::stream(read_stream(input)?, show_progress);
+            let report = convert_reader(reader, output, format)?;
+            info!(file = %input, points = report.points, "Converted by streaming");
+            return Ok(());
+        }
+    }
+    let format = ExportFormat::from_path(output).ok_or_else(|| {
+        WaveformError::ParseError(format!(
+            "{}: unknown output format (use .csv, .jsonl, .json, .mat, .npz or .raw)",
+            output
+        ))
+    })?;
+    run(
+        input,
+        format,
+        &Selection::default(),
+        Some(output),
+        show_progress,
+        io::sink(),
+    )
+}
+
+fn write_output<W: Write>(
+    result: &WaveformResult,
+    format: ExportFormat,
+    out: &mut W,
+) -> Result<()> {
+    match format {
+        ExportFormat::Csv => result.write_csv(out),
+        ExportFormat::Json => result.write_json(out),
+        ExportFormat::Mat => to_mat(result, out),
+        ExportFormat::Npz => to_npz(result, out),
+        ExportFormat::Raw | ExportFormat::RawAscii { .. } => {
+            unreachable!("raw is written by path")
+        }
+    }
+}
+
+/// Keep the scale, the selected signals and the selected table
+pub fn select(mut result: WaveformResult, selection: &Selection) -> Result<WaveformResult> {
+    if let Some(table) = selection.table {
+        if table >= result.tables.len() {
+            return Err(WaveformError::ParseError(format!(
+                "table {} out of range ({} tables)",
+                table,
+                result.tables.len()
+            )));
+        }
+        let kept = result.tables.swap_remove(table);
+        result.tables = vec![kept];
+    }
+
+    if let Some(names) = &selection.signals {
+        let mut indices = vec![0];
+        for name in names {
+            let index = result
+                .var_index(name)
+                .ok_or_else(|| WaveformError::ParseError(format!("signal {} not found", name)))?;
+            if !indices.contains(&index) {
+                indices.push(index);
+            }
+        }
+        result.variables = indices
+            .iter()
+            .map(|&i| result.variables[i].clone())
+            .collect();
+        for table in &mut result.tables {
+            let mut vectors: Vec<Option<VectorData>> = std::mem::take(&mut table.vectors)
+                .into_iter()
+                .map(Some)
+                .collect();
+            table.vectors = indices.iter().filter_map(|&i| vectors[i].take()).collect();
+        }
+    }
+    Ok(result)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hspice_core::read_any;
+    use hspice_core::testing::{temp_path, SyntheticWaveform};
+
+    fn export(path: &str, format: ExportFormat, selection: &Selection) -> String {
+        let mut out = Vec::new();
+        run(path, format, selection, None, false, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_csv_selection() {
+        let path = temp_path("cli_export.sw0");
+        let synth = SyntheticWaveform::new()
+            .signals(3)
+            .points(20)
+            .sweep("temp", vec![25.0, 85.0]);
+        synth.write_tr0(&path).unwrap();
+        let expected = synth.expected();
+        let name = expected.variables[2].name.clone();
+        let path = path.to_str().unwrap();
+
+        let csv = export(path, ExportFormat::Csv, &Selection::default());
+        let lines: Vec<_> = csv.lines().collect();
+        assert!(lines[0].starts_with("temp,TIME,"));
+        assert_eq!(lines.len(), 1 + 2 * 20);
+
+        let selection = Selection {
+            signals: Some(vec![name.clone()]),
+            table: Some(1),
+        };
+        let csv = export(path, ExportFormat::Csv, &selection);
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines[0], format!("TIME,{}", name));
+        assert_eq!(lines.len(), 21);
+        let value = expected.tables[1].vectors[2].real_at(0).unwrap();
+        assert_eq!(lines[1].split(',').nth(1).unwrap(), value.to_string());
+
+        let selection = Selection {
+            signals: Some(vec!["v(missing)".into()]),
+            table: None,
+        };
+        assert!(run(path, ExportFormat::Csv, &selection, None, false, Vec::new()).is_err());
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_json_and_raw() {
+        let path = temp_path("cli_export.ac0");
+        SyntheticWaveform::new()
+            .signals(2)
+            .points(10)
+            .complex(true)
+            .write_tr0(&path)
+            .unwrap();
+        let path = path.to_str().unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&export(path, ExportFormat::Json, &Selection::default())).unwrap();
+        assert_eq!(json["variables"].as_array().unwrap().len(), 3);
+        let signals = &json["tables"][0]["signals"];
+        assert_eq!(signals["n0"][0].as_array().unwrap().len(), 2);
+
+        assert!(run(
+            path,
+            ExportFormat::Raw,
+            &Selection::default(),
+            None,
+            false,
+            Vec::new()
+        )
+        .is_err());
+        std::fs::remove_file(path).ok();
+
+        let path = temp_path("cli_export_raw.tr0");
+        SyntheticWaveform::new()
+            .signals(2)
+            .points(10)
+            .write_tr0(&path)
+            .unwrap();
+        let path = path.to_str().unwrap();
+        let raw = temp_path("cli_export.raw");
+        let raw = raw.to_str().unwrap();
+        run(
+            path,
+            ExportFormat::Raw,
+            &Selection::default(),
+            Some(raw),
+            false,
+            Vec::new(),
+        )
+        .unwrap();
+        assert_eq!(read_any(raw).unwrap().len(), 10);
+
+        assert_eq!(ExportFormat::from_path("out.CSV"), Some(ExportFormat::Csv));
+        assert_eq!(ExportFormat::from_path("out.npz"), Some(ExportFormat::Npz));
+        assert_eq!(ExportFormat::from_path("out.h5"), None);
+
+        let mut npz = Vec::new();
+        run(
+            path,
+            ExportFormat::Npz,
+            &Selection::default(),
+            None,
+            false,
+            &mut npz,
+        )
+        .unwrap();
+        assert!(npz.starts_with(b"PK\x03\x04"));
+
+        let mut mat = Vec::new();
+        run(
+            path,
+            ExportFormat::Mat,
+            &Selection::default(),
+            None,
+            false,
+            &mut mat,
+        )
+        .unwrap();
+        assert!(mat.starts_with(b"MATLAB 5.0 MAT-file"));
+        std::fs::remove_file(path).ok();
+        std::fs::remove_file(raw).ok();
+    }
+
+    #[test]
+    fn test_streamed_convert_matches_export() {
+        let path = temp_path("cli_convert.sw0");
+        SyntheticWaveform::new()
+            .signals(2)
+            .points(50)
+            .sweep("temp", vec![25.0, 85.0])
+            .write_tr0(&path)
+            .unwrap();
+        let path = path.to_str().unwrap();
+        let csv = temp_path("cli_convert.csv");
+        let csv = csv.to_str().unwrap();
+
+        convert(path, csv, false).unwrap();
+        let streamed = std::fs::read_to_string(csv).unwrap();
+        assert_eq!(
+            streamed,
+            export(path, ExportFormat::Csv, &Selection::default())
+        );
+        assert!(convert(path, "out.h5", false).is_err());
+        std::fs::remove_file(path).ok();
+        std::fs::remove_file(csv).ok();
+    }
+
+    #[test]
+    fn test_raw_ascii() {
+        let path = temp_path("cli_export_ascii.tr0");
+        SyntheticWaveform::new()
+            .signals(1)
+            .points(5)
+            .write_tr0(&path)
+            .unwrap();
+        let path = path.to_str().unwrap();
+        let raw = temp_path("cli_export_ascii.raw");
+        let raw = raw.to_str().unwrap();
+        run(
+            path,
+            ExportFormat::RawAscii { precision: 4 },
+            &Selection::default(),
+            Some(raw),
+            false,
+            Vec::new(),
+        )
+        .unwrap();
+        let text = std::fs::read_to_string(raw).unwrap();
+        assert!(text.contains("Values:\n 0\t0.0000e+00\n"));
+        assert_eq!(read_any(raw).unwrap().len(), 5);
+        std::fs::remove_file(path).ok();
+        std::fs::remove_file(raw).ok();
+    }
+}
@@ -0,0 +1,207 @@
+//! `info` and `signals`: what a file holds, without decoding its data
+//!
+//! HSPICE files are opened with `LazyWaveform`, which reads only the header
+//! and block layout. SPICE3 raw files have no such index and are read in
+//! full. `info --stats` is the exception: it reads the data to summarize
+//! each signal.
+
+use hspice_core::{
+    detect_format, read_raw, AnalysisType, FileFormat, LazyWaveform, Result, Variable,
+    WaveformResult,
+};
+use std::io::Write;
+
+/// Header-level description of a waveform file
+#[derive(Debug, Clone)]
+pub struct Summary {
+    pub format: FileFormat,
+    pub title: String,
+    pub date: String,
+    pub analysis: AnalysisType,
+    /// All variables, scale first
+    pub variables: Vec<Variable>,
+    /// Points in the first table
+    pub points: usize,
+    pub sweep_param: Option<String>,
+    pub sweep_values: Vec<Option<f64>>,
+}
+
+impl Summary {
+    pub fn load(path: &str) -> Result<Self> {
+        let format = detect_format(path)?;
+        Ok(match format {
+            FileFormat::Hspice => {
+                let wave = LazyWaveform::open(path)?;
+                Self {
+                    format,
+                    title: wave.title().to_string(),
+                    date: wave.date().to_string(),
+                    analysis: wave.analysis(),
+                    variables: wave.variables().to_vec(),
+                    points: wave.len(),
+                    sweep_param: wave.sweep_param().map(str::to_string),
+                    sweep_values: wave.sweep_values(),
+                }
+            }
+            FileFormat::SpiceRaw => {
+                let result = read_raw(path)?;
+                Self {
+                    format,
+                    points: result.len(),
+                    sweep_values: result.tables.iter().map(|t| t.sweep_value).collect(),
+                    title: result.title,
+                    date: result.date,
+                    analysis: result.analysis,
+                    variables: result.variables,
+                    sweep_param: result.sweep_param,
+                }
+            }
+        })
+    }
+}
+
+/// Write the `info` report
+pub fn write_info<W: Write>(path: &str, summary: &Summary, out: &mut W) -> Result<()> {
+    let format = match summary.format {
+        FileFormat::Hspice => "HSPICE binary",
+        FileFormat::SpiceRaw => "SPICE3 raw",
+    };
+    writeln!(out, "file:      {}", path)?;
+    writeln!(out, "format:    {}", format)?;
+    writeln!(out, "title:     {}", summary.title)?;
+    writeln!(out, "date:      {}", summary.date)?;
+    writeln!(out, "analysis:  {:?}", summary.analysis)?;
+    if let Some(scale) = summary.variables.first() {
+        writeln!(out, "scale:     {}", scale.name)?;
+    }
+    writeln!(
+        out,
+        "signals:   {}",
+        summary.variables.len().saturating_sub(1)
+    )?;
+    writeln!(out, "points:    {}", summary.points)?;
+    write!(out, "tables:    {}", summary.sweep_values.len())?;
+    if let Some(param) = &summary.sweep_param {
+        let values: Vec<String> = summary
+            .sweep_values
+            .iter()
+            .map(|v| v.map_or_else(|| "?".to_string(), |v| v.to_string()))
+            .collect();
+        write!(out, " ({} = {})", param, values.join(", "))?;
+    }
+    writeln!(out)?;
+    Ok(())
+}
+
+/// Write min/max/mean/RMS/std/peak-to-peak of every signal over all tables
+///
+/// Complex signals are summarized by magnitude, marked `|name|`.
+pub fn write_stats<W: Write>(result: &WaveformResult, out: &mut W) -> Result<()> {
+    let rows: Vec<(String, _)> = result
+        .variables
+        .iter()
+        .zip(result.stats())
+        .skip(1)
+        .filter_map(|(var, stats)| {
+            let stats = stats?;
+            let name = if stats.magnitude {
+                format!("|{}|", var.name)
+            } else {
+                var.name.clone()
+            };
+            Some((name, stats))
+        })
+        .collect();
+    let width = rows
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0)
+        .max(6);
+
+    writeln!(
+        out,
+        "{:<width$}  {:>11}  {:>11}  {:>11}  {:>11}  {:>11}  {:>11}",
+        "signal", "min", "max", "mean", "rms", "std", "p-p"
+    )?;
+    for (name, s) in rows {
+        writeln!(
+            out,
+            "{:<width$}  {:>11.4e}  {:>11.4e}  {:>11.4e}  {:>11.4e}  {:>11.4e}  {:>11.4e}",
+            name,
+            s.min,
+            s.max,
+            s.mean,
+            s.rms,
+            s.std,
+            s.peak_to_peak()
+        )?;
+    }
+    Ok(())
+}
+
+/// Write one signal per line, optionally with its type
+pub fn write_signals<W: Write>(summary: &Summary, long: bool, out: &mut W) -> Result<()> {
+    for var in summary.variables.iter().skip(1) {
+        if long {
+            writeln!(out, "{}\t{}", var.name, var.var_type)?;
+        } else {
+            writeln!(out, "{}", var.name)?;
+        }
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hspice_core::testing::{temp_path, SyntheticWaveform};
+
+    #[test]
+    fn test_info_and_signals() {
+        let path = temp_path("cli_info.sw0");
+        SyntheticWaveform::new()
+            .signals(3)
+            .points(50)
+            .sweep("temp", vec![25.0, 85.0])
+            .write_tr0(&path)
+            .unwrap();
+        let path = path.to_str().unwrap();
+
+        let summary = Summary::load(path).unwrap();
+        let mut out = Vec::new();
+        write_info(path, &summary, &mut out).unwrap();
+        let info = String::from_utf8(out).unwrap();
+        assert!(info.contains("signals:   3"));
+        assert!(info.contains("points:    50"));
+        assert!(info.contains("tables:    2 (temp = 25, 85)"));
+
+        let mut out = Vec::new();
+        write_signals(&summary, true, &mut out).unwrap();
+        let lines: Vec<_> = std::str::from_utf8(&out).unwrap().lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[0],
+            format!("{}\t{}", summary.variables[1].name, "voltage")
+        );
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_stats_table() {
+        let synth = SyntheticWaveform::new().signals(2).points(20).complex(true);
+        let mut out = Vec::new();
+        write_stats(&synth.expected(), &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<_> = text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("signal") && lines[0].ends_with("p-p"));
+        assert!(lines[1].starts_with("|n0|"));
+        assert_eq!(lines[1].split_whitespace().count(), 7);
+    }
+}
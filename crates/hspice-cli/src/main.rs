@@ -0,0 +1,542 @@
+//! `hspice-tool`: command-line access to HSPICE waveform files
+//!
+//! Subcommands write to stdout so their output can be piped into other
+//! programs; logs go to stderr (`--log-level`). `diff` exits with status 1
+//! when the runs differ, `verify` when the file is damaged.
+
+mod arrow_ipc;
+mod cat;
+mod diff;
+mod export;
+mod inspect;
+mod measure;
+mod measures;
+mod progress;
+mod stream;
+mod vcd;
+
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
+use export::{ExportFormat, Selection};
+use hspice_core::compare::Tolerance;
+use hspice_core::vcd::Thresholds;
+use hspice_core::{Result, WaveformError, DEFAULT_CHUNK_SIZE};
+use measure::Measurement;
+use std::io::{self, BufWriter, Write};
+use std::process::ExitCode;
+use std::time::Duration;
+use stream::StreamFormat;
+
+/// `--signals`: comma-separated signal names
+fn signals_arg() -> Arg {
+    Arg::new("signals")
+        .long("signals")
+        .value_delimiter(',')
+        .num_args(1..)
+        .help("Only these signals (comma-separated); the scale is always included")
+}
+
+/// `--table`: one sweep table
+fn table_arg() -> Arg {
+    Arg::new("table")
+        .long("table")
+        .value_parser(value_parser!(usize))
+        .help("Only this sweep table (0-based) [default: all]")
+}
+
+fn fraction_arg(name: &'static str, default: &'static str, help: &'static str) -> Arg {
+    Arg::new(name)
+        .long(name)
+        .value_parser(value_parser!(f64))
+        .default_value(default)
+        .help(help)
+}
+
+fn cli() -> Command {
+    Command::new("hspice-tool")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("Read and convert HSPICE binary waveform files")
+        .subcommand_required(true)
+        .arg(
+            Arg::new("log-level")
+                .long("log-level")
+                .global(true)
+                .default_value("warn")
+                .help("Log filter for stderr (trace, debug, info, warn, error)"),
+        )
+        .arg(
+            Arg::new("progress")
+                .long("progress")
+                .global(true)
+                .action(ArgAction::SetTrue)
+                .help("Show a progress bar on stderr while reading (export, convert, vcd, stream, info --stats)"),
+        )
+        .subcommand(
+            Command::new("info")
+                .about("Print a header summary")
+                .arg(Arg::new("file").required(true).help("Waveform file"))
+                .arg(
+                    Arg::new("stats")
+                        .long("stats")
+                        .action(ArgAction::SetTrue)
+                        .help("Also read the data and print min/max/mean/rms/std per signal"),
+                ),
+        )
+        .subcommand(
+            Command::new("signals")
+                .about("List signal names, one per line")
+                .arg(Arg::new("file").required(true).help("Waveform file"))
+                .arg(
+                    Arg::new("long")
+                        .short('l')
+                        .long("long")
+                        .action(ArgAction::SetTrue)
+                        .help("Also print each signal's type"),
+                ),
+        )
+        .subcommand(
+            Command::new("export")
+                .about("Write signals as CSV, JSON, NumPy .npz, MATLAB .mat or SPICE3 raw")
+                .arg(Arg::new("file").required(true).help("Waveform file"))
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .value_parser(ExportFormat::NAMES)
+                        .default_value("csv")
+                        .help("Output format"),
+                )
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .help("Output file [default: stdout; required for raw]"),
+                )
+                .arg(
+                    Arg::new("precision")
+                        .long("precision")
+                        .value_parser(value_parser!(usize))
+                        .help("Digits after the decimal point for raw-ascii [default: 16]"),
+                )
+                .arg(signals_arg())
+                .arg(table_arg()),
+        )
+        .subcommand(
+            Command::new("convert")
+                .about("Convert a file, taking the format from the output extension")
+                .arg(Arg::new("input").required(true).help("Waveform file"))
+                .arg(
+                    Arg::new("output")
+                        .required(true)
+                        .help("Output file (.csv, .jsonl, .json, .mat, .npz or .raw)"),
+                ),
+        )
+        .subcommand(
+            Command::new("measure")
+                .about("Measure rise time, fall time or delay in each sweep table")
+                .arg(
+                    Arg::new("kind")
+                        .required(true)
+                        .value_parser(Measurement::NAMES)
+                        .help("Measurement"),
+                )
+                .arg(Arg::new("file").required(true).help("Waveform file"))
+                .arg(
+                    Arg::new("signal")
+                        .required(true)
+                        .num_args(1..=2)
+                        .help("Signal to measure (delay: trigger and target)"),
+                )
+                .arg(fraction_arg(
+                    "low",
+                    "0.1",
+                    "Low level as a fraction of the swing",
+                ))
+                .arg(fraction_arg(
+                    "high",
+                    "0.9",
+                    "High level as a fraction of the swing",
+                ))
+                .arg(fraction_arg(
+                    "level",
+                    "0.5",
+                    "Delay level as a fraction of the swing",
+                ))
+                .arg(table_arg()),
+        )
+        .subcommand(
+            Command::new("measures")
+                .about("Print an HSPICE measure file (.mt0, .ma0, .ms0)")
+                .arg(Arg::new("file").required(true).help("Measure file"))
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .action(ArgAction::SetTrue)
+                        .help("Print the table as JSON"),
+                ),
+        )
+        .subcommand(
+            Command::new("vcd")
+                .about("Digitize signals against logic thresholds and write a VCD")
+                .arg(
+                    Arg::new("file")
+                        .required(true)
+                        .help("Transient waveform file"),
+                )
+                .arg(
+                    Arg::new("vdd")
+                        .long("vdd")
+                        .value_parser(value_parser!(f64))
+                        .required_unless_present("vil")
+                        .conflicts_with("vil")
+                        .help("Supply voltage; thresholds at 30% and 70% of it"),
+                )
+                .arg(
+                    Arg::new("vil")
+                        .long("vil")
+                        .value_parser(value_parser!(f64))
+                        .requires("vih")
+                        .help("Highest voltage read as 0"),
+                )
+                .arg(
+                    Arg::new("vih")
+                        .long("vih")
+                        .value_parser(value_parser!(f64))
+                        .requires("vil")
+                        .help("Lowest voltage read as 1"),
+                )
+                .arg(signals_arg())
+                .arg(table_arg()),
+        )
+        .subcommand(
+            Command::new("diff")
+                .about("Compare a candidate run against a golden run")
+                .arg(Arg::new("golden").required(true).help("Reference file"))
+                .arg(Arg::new("candidate").required(true).help("File to check"))
+                .arg(
+                    Arg::new("abs")
+                        .long("abs")
+                        .value_parser(value_parser!(f64))
+                        .default_value("1e-9")
+                        .help("Absolute tolerance"),
+                )
+                .arg(
+                    Arg::new("rel")
+                        .long("rel")
+                        .value_parser(value_parser!(f64))
+                        .default_value("1e-3")
+                        .help("Relative tolerance"),
+                )
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .action(ArgAction::SetTrue)
+                        .help("Print the report as JSON"),
+                ),
+        )
+        .subcommand(
+            Command::new("cat")
+                .about("Print data rows as tab-separated text")
+                .arg(Arg::new("file").required(true).help("HSPICE file to read"))
+                .arg(signals_arg())
+                .arg(
+                    Arg::new("follow")
+                        .short('f')
+                        .long("follow")
+                        .action(ArgAction::SetTrue)
+                        .help("Keep printing rows as the simulator writes them (unswept runs)"),
+                )
+                .arg(
+                    Arg::new("interval")
+                        .long("interval")
+                        .value_parser(value_parser!(u64))
+                        .default_value("500")
+                        .help("Poll interval in milliseconds for --follow"),
+                ),
+        )
+        .subcommand(
+            Command::new("stream")
+                .about("Write the file to stdout in chunks as ndjson or Arrow IPC")
+                .arg(Arg::new("file").required(true).help("HSPICE file to read"))
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .value_parser(StreamFormat::NAMES)
+                        .default_value("ndjson")
+                        .help("Output encoding"),
+                )
+                .arg(
+                    Arg::new("chunk-size")
+                        .long("chunk-size")
+                        .value_parser(value_parser!(usize))
+                        .help("Minimum points per chunk [default: 10000]"),
+                )
+                .arg(signals_arg()),
+        )
+        .subcommand(
+            Command::new("verify")
+                .about("Check every data block; exit status 1 if the file is damaged")
+                .arg(Arg::new("file").required(true).help("HSPICE file to check")),
+        )
+        .subcommand(
+            Command::new("index")
+                .about("Write a .idx sidecar so later reads can seek without scanning")
+                .arg(Arg::new("file").required(true).help("HSPICE file to index")),
+        )
+}
+
+fn init_logging(level: &str) {
+    use tracing_subscriber::EnvFilter;
+    let filter = EnvFilter::try_new(level).unwrap_or_else(|_| EnvFilter::new("warn"));
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_target(false)
+        .with_writer(io::stderr)
+        .init();
+}
+
+fn run(matches: &ArgMatches) -> Result<ExitCode> {
+    let mut stdout = BufWriter::new(io::stdout().lock());
+    let file = |args: &ArgMatches| args.get_one::<String>("file").unwrap().clone();
+    let show_progress = matches.get_flag("progress");
+    let signals = |args: &ArgMatches| {
+        args.get_many::<String>("signals")
+            .map(|names| names.cloned().collect::<Vec<_>>())
+    };
+    match matches.subcommand() {
+        Some(("info", args)) => {
+            let path = file(args);
+            let summary = inspect::Summary::load(&path)?;
+            inspect::write_info(&path, &summary, &mut stdout)?;
+            if args.get_flag("stats") {
+                writeln!(stdout)?;
+                inspect::write_stats(&progress::read(&path, show_progress)?, &mut stdout)?;
+            }
+        }
+        Some(("signals", args)) => {
+            let summary = inspect::Summary::load(&file(args))?;
+            inspect::write_signals(&summary, args.get_flag("long"), &mut stdout)?;
+        }
+        Some(("export", args)) => {
+            let format = args.get_one::<String>("format").unwrap();
+            let selection = Selection {
+                signals: signals(args),
+                table: args.get_one::<usize>("table").copied(),
+            };
+            let mut format = ExportFormat::from_name(format).unwrap();
+            if let (ExportFormat::RawAscii { precision }, Some(&digits)) =
+                (&mut format, args.get_one::<usize>("precision"))
+            {
+                *precision = digits;
+            }
+            export::run(
+                &file(args),
+                format,
+                &selection,
+                args.get_one::<String>("output").map(String::as_str),
+                show_progress,
+                &mut stdout,
+            )?;
+        }
+        Some(("convert", args)) => {
+            export::convert(
+                args.get_one::<String>("input").unwrap(),
+                args.get_one::<String>("output").unwrap(),
+                show_progress,
+            )?;
+        }
+        Some(("measure", args)) => {
+            let fraction = |name: &str| *args.get_one::<f64>(name).unwrap();
+            let measurement = match args.get_one::<String>("kind").unwrap().as_str() {
+                "rise" => Measurement::Rise {
+                    low: fraction("low"),
+                    high: fraction("high"),
+                },
+                "fall" => Measurement::Fall {
+                    high: fraction("high"),
+                    low: fraction("low"),
+                },
+                _ => Measurement::Delay {
+                    level: fraction("level"),
+                },
+            };
+            let names: Vec<String> = args
+                .get_many::<String>("signal")
+                .unwrap()
+                .cloned()
+                .collect();
+            measure::run(
+                &file(args),
+                measurement,
+                &names,
+                args.get_one::<usize>("table").copied(),
+                &mut stdout,
+            )?;
+        }
+        Some(("measures", args)) => {
+            measures::run(&file(args), args.get_flag("json"), &mut stdout)?;
+        }
+        Some(("vcd", args)) => {
+            let volts = |name: &str| *args.get_one::<f64>(name).unwrap();
+            let thresholds = if args.contains_id("vdd") {
+                Thresholds::from_vdd(volts("vdd"))
+            } else {
+                Thresholds::new(volts("vil"), volts("vih"))
+            };
+            let selection = Selection {
+                signals: signals(args),
+                table: args.get_one::<usize>("table").copied(),
+            };
+            vcd::run(
+                &file(args),
+                &thresholds,
+                &selection,
+                show_progress,
+                &mut stdout,
+            )?;
+        }
+        Some(("diff", args)) => {
+            let tolerance = Tolerance::new(
+                *args.get_one::<f64>("abs").unwrap(),
+                *args.get_one::<f64>("rel").unwrap(),
+            );
+            let passed = diff::run(
+                args.get_one::<String>("golden").unwrap(),
+                args.get_one::<String>("candidate").unwrap(),
+                tolerance,
+                args.get_flag("json"),
+                &mut stdout,
+            )?;
+            stdout.flush()?;
+            if !passed {
+                return Ok(ExitCode::from(1));
+            }
+        }
+        Some(("cat", args)) => {
+            let follow = args
+                .get_flag("follow")
+                .then(|| Duration::from_millis(*args.get_one::<u64>("interval").unwrap()));
+            cat::run(&file(args), signals(args), follow, &mut stdout)?;
+        }
+        Some(("stream", args)) => {
+            let format = args.get_one::<String>("format").unwrap();
+            stream::run(
+                &file(args),
+                StreamFormat::from_name(format).unwrap(),
+                args.get_one::<usize>("chunk-size")
+                    .copied()
+                    .unwrap_or(DEFAULT_CHUNK_SIZE),
+                signals(args),
+                show_progress,
+                &mut stdout,
+            )?;
+        }
+        Some(("verify", args)) => {
+            let report = hspice_core::integrity::verify(file(args))?;
+            writeln!(
+                stdout,
+                "{} blocks, {} of {} tables, {} rows",
+                report.blocks, report.tables, report.expected_tables, report.rows
+            )?;
+            if report.trailing_bytes > 0 {
+                writeln!(
+                    stdout,
+                    "{} bytes after the last table",
+                    report.trailing_bytes
+                )?;
+            }
+            for issue in &report.issues {
+                writeln!(stdout, "{}", issue)?;
+            }
+            stdout.flush()?;
+            if !report.is_ok() {
+                return Ok(ExitCode::from(1));
+            }
+        }
+        Some(("index", args)) => {
+            let path = file(args);
+            let index = hspice_core::index::build_index(&path)?;
+            let marks: usize = index.tables.iter().map(|t| t.marks.len()).sum();
+            writeln!(
+                stdout,
+                "{}: {} tables, {} seek marks",
+                hspice_core::index::index_path(&path).display(),
+                index.tables.len(),
+                marks
+            )?;
+        }
+        _ => unreachable!("subcommand is required"),
+    }
+    stdout.flush()?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn main() -> ExitCode {
+    let matches = cli().get_matches();
+    init_logging(matches.get_one::<String>("log-level").unwrap());
+
+    match run(&matches) {
+        Ok(code) => code,
+        // The consumer closed the pipe (e.g. `| head`): not an error
+        Err(e) if is_broken_pipe(&e) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("hspice-tool: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn is_broken_pipe(error: &WaveformError) -> bool {
+    matches!(error.root_cause(), WaveformError::IoError(e) if e.kind() == io::ErrorKind::BrokenPipe)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_definition() {
+        cli().debug_assert();
+        let matches = cli()
+            .try_get_matches_from([
+                "hspice-tool",
+                "stream",
+                "a.tr0",
+                "--format",
+                "arrow-ipc",
+                "--signals",
+                "v(a),v(b)",
+            ])
+            .unwrap();
+        let (_, args) = matches.subcommand().unwrap();
+        assert_eq!(args.get_many::<String>("signals").unwrap().count(), 2);
+        assert!(cli()
+            .try_get_matches_from(["hspice-tool", "stream", "a.tr0", "--format", "csv"])
+            .is_err());
+
+        let matches = cli()
+            .try_get_matches_from([
+                "hspice-tool",
+                "measure",
+                "delay",
+                "a.tr0",
+                "v(in)",
+                "v(out)",
+            ])
+            .unwrap();
+        let (_, args) = matches.subcommand().unwrap();
+        assert_eq!(args.get_many::<String>("signal").unwrap().count(), 2);
+        assert_eq!(args.get_one::<f64>("level"), Some(&0.5));
+        assert!(cli()
+            .try_get_matches_from(["hspice-tool", "export", "a.tr0", "--format", "hdf5"])
+            .is_err());
+
+        assert!(cli()
+            .try_get_matches_from(["hspice-tool", "vcd", "a.tr0", "--vdd", "1.8"])
+            .is_ok());
+        assert!(cli()
+            .try_get_matches_from(["hspice-tool", "vcd", "a.tr0", "--vil", "0.5"])
+            .is_err());
+        assert!(cli()
+            .try_get_matches_from(["hspice-tool", "vcd", "a.tr0"])
+            .is_err());
+    }
+}
@@ -0,0 +1,156 @@
+//! `measure`: rise time, fall time and delay for each sweep table
+//!
+//! Prints one value per line; swept files prefix each value with the sweep
+//! value of its table. A measurement that cannot be made (flat signal, no
+//! complete transition) prints `n/a`.
+
+use hspice_core::{measure, read_any, Result, WaveformError, WaveformResult};
+use std::io::Write;
+use tracing::warn;
+
+/// Measurement to make
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Measurement {
+    /// Rise time between two fractions of the swing
+    Rise { low: f64, high: f64 },
+    /// Fall time between two fractions of the swing
+    Fall { high: f64, low: f64 },
+    /// Delay from the first signal to the second at a fraction of each swing
+    Delay { level: f64 },
+}
+
+impl Measurement {
+    pub const NAMES: [&'static str; 3] = ["rise", "fall", "delay"];
+
+    /// Signals the measurement takes
+    pub fn num_signals(&self) -> usize {
+        match self {
+            Self::Delay { .. } => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// Measure `signals` in every table of `path` (or only `table`)
+pub fn run<W: Write>(
+    path: &str,
+    measurement: Measurement,
+    signals: &[String],
+    table: Option<usize>,
+    out: &mut W,
+) -> Result<()> {
+    if signals.len() != measurement.num_signals() {
+        return Err(WaveformError::ParseError(format!(
+            "{:?} takes {} signal(s), got {}",
+            measurement,
+            measurement.num_signals(),
+            signals.len()
+        )));
+    }
+    let result = read_any(path)?;
+    let swept = result.sweep_param.is_some() && result.tables.len() > 1;
+
+    let tables: Vec<usize> = match table {
+        Some(t) => vec![t],
+        None => (0..result.tables.len()).collect(),
+    };
+    for t in tables {
+        let value = measure_table(&result, measurement, signals, t)?;
+        if swept {
+            let sweep = result.tables[t].sweep_value.unwrap_or(f64::NAN);
+            write!(out, "{}\t", sweep)?;
+        }
+        match value {
+            Some(value) => writeln!(out, "{}", value)?,
+            None => {
+                warn!(table = t, "Measurement not possible");
+                writeln!(out, "n/a")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn measure_table(
+    result: &WaveformResult,
+    measurement: Measurement,
+    signals: &[String],
+    table: usize,
+) -> Result<Option<f64>> {
+    let data = result.tables.get(table).ok_or_else(|| {
+        WaveformError::ParseError(format!(
+            "table {} out of range ({} tables)",
+            table,
+            result.tables.len()
+        ))
+    })?;
+    let real = |name: &str| {
+        let index = result
+            .var_index(name)
+            .ok_or_else(|| WaveformError::ParseError(format!("signal {} not found", name)))?;
+        data.vectors[index]
+            .to_real()
+            .ok_or_else(|| WaveformError::ParseError(format!("{} is complex", name)))
+    };
+
+    let x = data.vectors[0]
+        .to_real()
+        .ok_or_else(|| WaveformError::ParseError("complex scale".into()))?;
+    let y = real(&signals[0])?;
+    Ok(match measurement {
+        Measurement::Rise { low, high } => measure::rise_time(&x, &y, low, high),
+        Measurement::Fall { high, low } => measure::fall_time(&x, &y, high, low),
+        Measurement::Delay { level } => measure::delay(&x, &y, &real(&signals[1])?, level),
+    })
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hspice_core::testing::{temp_path, SyntheticWaveform};
+
+    #[test]
+    fn test_measure_per_table() {
+        let path = temp_path("cli_measure.sw0");
+        let synth = SyntheticWaveform::new()
+            .signals(2)
+            .points(200)
+            .sweep("temp", vec![25.0, 85.0]);
+        synth.write_tr0(&path).unwrap();
+        let expected = synth.expected();
+        let names: Vec<String> = expected.variables[1..]
+            .iter()
+            .map(|v| v.name.clone())
+            .collect();
+        let path = path.to_str().unwrap();
+
+        let rise = Measurement::Rise {
+            low: 0.1,
+            high: 0.9,
+        };
+        let mut out = Vec::new();
+        run(path, rise, &names[..1], None, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<_> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("25\t"));
+
+        let mut out = Vec::new();
+        run(
+            path,
+            Measurement::Delay { level: 0.5 },
+            &names,
+            Some(0),
+            &mut out,
+        )
+        .unwrap();
+        assert!(String::from_utf8(out).unwrap().starts_with("25\t"));
+
+        assert!(run(path, rise, &names, None, &mut Vec::new()).is_err());
+        std::fs::remove_file(path).ok();
+    }
+}
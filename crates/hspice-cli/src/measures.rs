@@ -0,0 +1,85 @@
+//! `measures`: print an HSPICE measure file (.mt0, .ma0, .ms0)
+//!
+//! Prints the table tab-separated with a header row, one row per sweep
+//! point, so it lines up with `measure` and `export` output for the
+//! matching waveform file. Failed measurements print as `failed`.
+
+use hspice_core::measure_file::{read_measure, MeasureResult};
+use hspice_core::Result;
+use std::io::Write;
+
+/// Read `path` and write its table as text or JSON
+pub fn run<W: Write>(path: &str, json: bool, out: &mut W) -> Result<()> {
+    let measures = read_measure(path)?;
+    if json {
+        write_json(&measures, out)
+    } else {
+        write_text(&measures, out)
+    }
+}
+
+fn write_text<W: Write>(measures: &MeasureResult, out: &mut W) -> Result<()> {
+    writeln!(out, "{}", measures.names.join("\t"))?;
+    for row in &measures.rows {
+        let fields: Vec<String> = row
+            .iter()
+            .map(|v| v.map_or_else(|| "failed".to_string(), |v| v.to_string()))
+            .collect();
+        writeln!(out, "{}", fields.join("\t"))?;
+    }
+    Ok(())
+}
+
+fn write_json<W: Write>(measures: &MeasureResult, out: &mut W) -> Result<()> {
+    let doc = serde_json::json!({
+        "title": measures.title,
+        "analysis": measures.analysis.to_string(),
+        "param_count": measures.param_count,
+        "names": measures.names,
+        "rows": measures.rows,
+    });
+    writeln!(out, "{}", doc)?;
+    Ok(())
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hspice_core::testing::temp_path;
+
+    #[test]
+    fn test_text_and_json() {
+        let path = temp_path("cli_measures.mt0");
+        std::fs::write(
+            &path,
+            "$DATA1 SOURCE='HSPICE' VERSION='2019.06' PARAM_COUNT=0\n\
+             .TITLE 'inv'\n tpd temper alter#\n 1e-10 25 1\n failed 85 1\n",
+        )
+        .unwrap();
+        let path = path.to_str().unwrap();
+
+        let mut out = Vec::new();
+        run(path, false, &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<_> = text.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "tpd\ttemper\talter#",
+                "0.0000000001\t25\t1",
+                "failed\t85\t1"
+            ]
+        );
+
+        let mut out = Vec::new();
+        run(path, true, &mut out).unwrap();
+        let doc: serde_json::Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(doc["analysis"], "transient");
+        assert!(doc["rows"][1][0].is_null());
+        std::fs::remove_file(path).ok();
+    }
+}
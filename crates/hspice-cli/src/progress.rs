@@ -0,0 +1,110 @@
+//! `--progress`: a progress bar on stderr while a file is read
+//!
+//! Only HSPICE files report progress; SPICE3 raw files are read without a
+//! bar.
+
+use hspice_core::{
+    detect_format, read_any, read_with_progress, FileFormat, HspiceStreamReader, Progress,
+    ReadOptions, Result, WaveformResult,
+};
+use std::io::{self, Write};
+
+/// Width of the bar in characters
+const BAR_WIDTH: usize = 30;
+
+/// Redraws one stderr line whenever the percentage changes
+#[derive(Debug, Default)]
+pub struct ProgressBar {
+    percent: Option<u32>,
+}
+
+impl ProgressBar {
+    pub fn draw(&mut self, progress: &Progress) {
+        let percent = (progress.fraction() * 100.0) as u32;
+        if self.percent == Some(percent) {
+            return;
+        }
+        self.percent = Some(percent);
+        let mut stderr = io::stderr().lock();
+        let _ = write!(stderr, "\r{}", render(progress));
+        let _ = stderr.flush();
+    }
+
+    /// End the bar's line, if one was drawn
+    pub fn finish(&mut self) {
+        if self.percent.take().is_some() {
+            eprintln!();
+        }
+    }
+}
+
+/// `[#######.......]  45%  12.3/27.1 MB  1234 rows`
+fn render(progress: &Progress) -> String {
+    let fraction = progress.fraction();
+    let filled = (fraction * BAR_WIDTH as f64) as usize;
+    format!(
+        "[{}{}] {:3}%  {:.1}/{:.1} MB  {} rows",
+        "#".repeat(filled),
+        ".".repeat(BAR_WIDTH - filled),
+        (fraction * 100.0) as u32,
+        progress.bytes as f64 / 1e6,
+        progress.total_bytes as f64 / 1e6,
+        progress.rows
+    )
+}
+
+/// `read_any`, drawing a bar while an HSPICE file is read if `show` is set
+pub fn read(path: &str, show: bool) -> Result<WaveformResult> {
+    if !show || detect_format(path)? != FileFormat::Hspice {
+        return read_any(path);
+    }
+    let mut bar = ProgressBar::default();
+    let result = read_with_progress(path, &ReadOptions::default(), |p| bar.draw(p));
+    bar.finish();
+    result
+}
+
+/// Draw a bar as `reader` yields chunks if `show` is set
+///
+/// The line is ended by the last chunk, which reaches 100%.
+pub fn stream(reader: HspiceStreamReader, show: bool) -> HspiceStreamReader {
+    if !show {
+        return reader;
+    }
+    let mut bar = ProgressBar::default();
+    reader.with_progress(move |p| {
+        bar.draw(p);
+        if p.bytes >= p.total_bytes {
+            bar.finish();
+        }
+    })
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let progress = Progress {
+            bytes: 13_500_000,
+            total_bytes: 27_000_000,
+            blocks: 1650,
+            rows: 1234,
+        };
+        assert_eq!(
+            render(&progress),
+            "[###############...............]  50%  13.5/27.0 MB  1234 rows"
+        );
+        let done = Progress {
+            bytes: 10,
+            total_bytes: 10,
+            ..progress
+        };
+        assert!(render(&done).starts_with(&format!("[{}] 100%", "#".repeat(BAR_WIDTH))));
+    }
+}
@@ -0,0 +1,208 @@
+//! `stream`: write chunks to stdout for consumers in any language
+//!
+//! ndjson output is `hspice_core::json`'s: one metadata line followed by
+//! one line per chunk.
+//! Arrow IPC output is a stream whose record batches are the chunks; a
+//! complex signal becomes two columns, `<name>.re` and `<name>.im`.
+
+use crate::arrow_ipc::ArrowStreamWriter;
+use crate::progress;
+use hspice_core::json::write_ndjson;
+use hspice_core::{read_stream_chunked, DataChunk, Result, StreamMetadata, VectorData};
+use std::io::Write;
+use tracing::info;
+
+/// Output encoding of the `stream` subcommand
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamFormat {
+    Ndjson,
+    ArrowIpc,
+}
+
+impl StreamFormat {
+    pub const NAMES: [&'static str; 2] = ["ndjson", "arrow-ipc"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "ndjson" => Some(Self::Ndjson),
+            "arrow-ipc" => Some(Self::ArrowIpc),
+            _ => None,
+        }
+    }
+}
+
+/// Stream `path` to `out` in `format`
+pub fn run<W: Write>(
+    path: &str,
+    format: StreamFormat,
+    chunk_size: usize,
+    signals: Option<Vec<String>>,
+    show_progress: bool,
+    out: W,
+) -> Result<()> {
+    let mut reader = progress::stream(read_stream_chunked(path, chunk_size)?, show_progress);
+    if let Some(signals) = signals {
+        reader = reader.with_signals(signals);
+    }
+    let metadata = reader.metadata();
+
+    let chunks = match format {
+        StreamFormat::Ndjson => {
+            let mut out = out;
+            write_ndjson(reader, &mut out)?
+        }
+        StreamFormat::ArrowIpc => {
+            // The schema comes from the first chunk, which says which
+            // signals are complex
+            let mut reader = reader.peekable();
+            let columns = match reader.peek() {
+                Some(Ok(chunk)) => arrow_columns(&metadata, chunk),
+                _ => std::iter::once(metadata.scale_name.clone())
+                    .chain(metadata.signal_names.iter().cloned())
+                    .map(|name| (name, None))
+                    .collect(),
+            };
+            let names: Vec<String> = columns.iter().map(|(name, _)| name.clone()).collect();
+            let mut writer = ArrowStreamWriter::new(out, &names)?;
+            let mut chunks = 0;
+            for chunk in reader {
+                let chunk = chunk?;
+                let values: Vec<Vec<f64>> = columns
+                    .iter()
+                    .map(|(name, part)| column_values(&chunk, name, *part))
+                    .collect();
+                let slices: Vec<&[f64]> = values.iter().map(Vec::as_slice).collect();
+                writer.write_batch(&slices)?;
+                chunks += 1;
+            }
+            writer.finish()?.flush()?;
+            chunks
+        }
+    };
+
+    info!(file = %path, chunks, "Stream written");
+    Ok(())
+}
+
+/// Part of a complex signal held by a column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Part {
+    Re,
+    Im,
+}
+
+/// Signals of a chunk in file order, scale first
+pub fn chunk_signals<'a>(
+    metadata: &'a StreamMetadata,
+    chunk: &'a DataChunk,
+) -> impl Iterator<Item = (&'a String, &'a VectorData)> {
+    std::iter::once(&metadata.scale_name)
+        .chain(&metadata.signal_names)
+        .filter_map(|name| chunk.data.get(name).map(|v| (name, v)))
+}
+
+/// Arrow columns for the signals in `chunk`, keyed back to their signal
+fn arrow_columns(metadata: &StreamMetadata, chunk: &DataChunk) -> Vec<(String, Option<Part>)> {
+    let mut columns = Vec::new();
+    for (name, vector) in chunk_signals(metadata, chunk) {
+        if matches!(vector, VectorData::Complex(_)) {
+            columns.push((format!("{}.re", name), Some(Part::Re)));
+            columns.push((format!("{}.im", name), Some(Part::Im)));
+        } else {
+            columns.push((name.clone(), None));
+        }
+    }
+    columns
+}
+
+/// Values of one Arrow column, undoing the `.re`/`.im` suffix
+fn column_values(chunk: &DataChunk, column: &str, part: Option<Part>) -> Vec<f64> {
+    let name = match part {
+        Some(_) => &column[..column.len() - 3],
+        None => column,
+    };
+    match (chunk.data.get(name), part) {
+        (Some(VectorData::Complex(v)), Some(Part::Re)) => v.iter().map(|c| c.re).collect(),
+        (Some(VectorData::Complex(v)), Some(Part::Im)) => v.iter().map(|c| c.im).collect(),
+        (Some(vector), _) => vector.to_real().map(|v| v.into_owned()).unwrap_or_default(),
+        (None, _) => Vec::new(),
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hspice_core::testing::{temp_path, SyntheticWaveform};
+
+    #[test]
+    fn test_ndjson_lines() {
+        let path = temp_path("cli_stream.tr0");
+        SyntheticWaveform::new()
+            .signals(2)
+            .points(25)
+            .write_tr0(&path)
+            .unwrap();
+
+        let mut out = Vec::new();
+        run(
+            path.to_str().unwrap(),
+            StreamFormat::Ndjson,
+            10,
+            None,
+            false,
+            &mut out,
+        )
+        .unwrap();
+        let lines: Vec<serde_json::Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+
+        assert_eq!(lines[0]["type"], "metadata");
+        assert_eq!(lines[0]["signals"].as_array().unwrap().len(), 2);
+        let points: usize = lines[1..]
+            .iter()
+            .map(|l| l["data"]["TIME"].as_array().unwrap().len())
+            .sum();
+        assert_eq!(points, 25);
+        assert_eq!(lines[1]["chunk_index"], 0);
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_complex_columns() {
+        let path = temp_path("cli_stream.ac0");
+        SyntheticWaveform::new()
+            .signals(2)
+            .points(12)
+            .complex(true)
+            .write_tr0(&path)
+            .unwrap();
+
+        let mut reader = read_stream_chunked(&path, 100).unwrap();
+        let metadata = reader.metadata();
+        let chunk = reader.next().unwrap().unwrap();
+        let columns = arrow_columns(&metadata, &chunk);
+        let names: Vec<&str> = columns.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names[1..3], ["n0.re", "n0.im"]);
+        assert_eq!(column_values(&chunk, "n0.im", Some(Part::Im)).len(), 12);
+
+        let mut out = Vec::new();
+        run(
+            path.to_str().unwrap(),
+            StreamFormat::ArrowIpc,
+            100,
+            None,
+            false,
+            &mut out,
+        )
+        .unwrap();
+        assert_eq!(out[out.len() - 8..], [0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0]);
+        std::fs::remove_file(path).ok();
+    }
+}
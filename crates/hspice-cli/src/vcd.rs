@@ -0,0 +1,64 @@
+//! `vcd`: digitize a transient file into a VCD for logic viewers
+//!
+//! Takes the same `--signals` and `--table` selection as `export`; the
+//! selected table is the one written.
+
+use crate::export::{self, Selection};
+use crate::progress;
+use hspice_core::vcd::{to_vcd, Thresholds};
+use hspice_core::Result;
+use std::io::Write;
+
+/// Read `path`, digitize the selected signals and write the VCD to `out`
+pub fn run<W: Write>(
+    path: &str,
+    thresholds: &Thresholds,
+    selection: &Selection,
+    show_progress: bool,
+    out: &mut W,
+) -> Result<()> {
+    let result = export::select(progress::read(path, show_progress)?, selection)?;
+    to_vcd(&result, thresholds, out)?;
+    out.flush()?;
+    Ok(())
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hspice_core::testing::{temp_path, SyntheticWaveform};
+
+    #[test]
+    fn test_selected_signal() {
+        let path = temp_path("cli_vcd.tr0");
+        SyntheticWaveform::new()
+            .signals(3)
+            .points(50)
+            .write_tr0(&path)
+            .unwrap();
+        let path = path.to_str().unwrap();
+
+        let selection = Selection {
+            signals: Some(vec!["n1".into()]),
+            table: None,
+        };
+        let mut out = Vec::new();
+        run(
+            path,
+            &Thresholds::new(-0.5, 0.5),
+            &selection,
+            false,
+            &mut out,
+        )
+        .unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text.matches("$var wire 1").count(), 1);
+        assert!(text.contains("$var wire 1 ! n1 $end"));
+        assert!(text.contains("$dumpvars"));
+        std::fs::remove_file(path).ok();
+    }
+}
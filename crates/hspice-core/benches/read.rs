@@ -0,0 +1,209 @@
+//! Read- and write-path benchmarks on synthetic files
+//!
+//! Covers header parsing, block decoding for both post versions and byte
+//! orders, full reads from disk, streaming, SPICE3 raw writing of real,
+//! complex, and stepped results, and conversion. Inputs come from
+//! `SyntheticWaveform`, so no example files are needed:
+//!
+//! ```bash
+//! cargo bench -p hspice-core --features bench
+//! cargo bench -p hspice-core --features bench -- decode   # one group
+//! ```
+//!
+//! Throughput is reported in bytes of input, so runs with different sizes
+//! compare directly.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use hspice_core::testing::{temp_path, SyntheticWaveform};
+use hspice_core::{
+    convert_stream, parse_header_only, read, read_and_convert, read_bytes, read_bytes_with_options,
+    read_stream_chunked, write_spice3_raw, ConvertFormat, ConvertOptions, Endian, ParserLimits,
+    PostVersion, ReadOptions, VectorData, WaveformResult,
+};
+use std::fs::File;
+use std::hint::black_box;
+use std::path::PathBuf;
+
+/// Signals per file, besides the scale
+const SIGNALS: usize = 8;
+
+/// Points per file: about 7 MB of float32 data
+const POINTS: usize = 200_000;
+
+fn synth(version: PostVersion, endian: Endian) -> SyntheticWaveform {
+    SyntheticWaveform::new()
+        .signals(SIGNALS)
+        .points(POINTS)
+        .version(version)
+        .endian(endian)
+}
+
+/// A 9601 little-endian file on disk, removed when dropped
+struct TempFile(PathBuf);
+
+impl TempFile {
+    fn new(name: &str) -> Self {
+        let path = temp_path(name);
+        synth(PostVersion::V9601, Endian::Little)
+            .write_tr0(&path)
+            .expect("writing the benchmark input");
+        Self(path)
+    }
+
+    fn path(&self) -> &str {
+        self.0.to_str().expect("temp paths are UTF-8")
+    }
+
+    fn len(&self) -> u64 {
+        std::fs::metadata(&self.0).map_or(0, |m| m.len())
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        std::fs::remove_file(&self.0).ok();
+    }
+}
+
+fn bench_header(c: &mut Criterion) {
+    let file = TempFile::new("bench_header.tr0");
+    let mmap = unsafe { memmap2::Mmap::map(&File::open(file.path()).unwrap()) }.unwrap();
+    c.bench_function("header/parse", |b| {
+        b.iter(|| parse_header_only(black_box(&mmap)).unwrap())
+    });
+}
+
+fn bench_decode(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode");
+    for (version, label) in [(PostVersion::V9601, "f32"), (PostVersion::V2001, "f64")] {
+        for endian in [Endian::Little, Endian::Big] {
+            let bytes = synth(version, endian).to_tr0_bytes();
+            group.throughput(Throughput::Bytes(bytes.len() as u64));
+            let id = BenchmarkId::new(label, format!("{:?}", endian).to_lowercase());
+            group.bench_with_input(id, &bytes, |b, bytes| {
+                b.iter(|| read_bytes(black_box(bytes), &ParserLimits::unlimited()).unwrap())
+            });
+        }
+    }
+
+    // The two-pass decode through one buffer of raw values, for comparison
+    let bytes = synth(PostVersion::V9601, Endian::Little).to_tr0_bytes();
+    let options = ReadOptions::new()
+        .limits(ParserLimits::unlimited())
+        .buffered_decode(true);
+    group.throughput(Throughput::Bytes(bytes.len() as u64));
+    group.bench_with_input(BenchmarkId::new("f32", "buffered"), &bytes, |b, bytes| {
+        b.iter(|| read_bytes_with_options(black_box(bytes), &options).unwrap())
+    });
+    group.finish();
+}
+
+fn bench_read(c: &mut Criterion) {
+    let file = TempFile::new("bench_read.tr0");
+    let mut group = c.benchmark_group("read");
+    group.throughput(Throughput::Bytes(file.len()));
+    group.bench_function("mmap", |b| b.iter(|| read(file.path()).unwrap()));
+    group.finish();
+}
+
+fn bench_stream(c: &mut Criterion) {
+    let file = TempFile::new("bench_stream.tr0");
+    let mut group = c.benchmark_group("stream");
+    group.throughput(Throughput::Bytes(file.len()));
+    for chunk_size in [1_000, 10_000, 100_000] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(chunk_size),
+            &chunk_size,
+            |b, &chunk_size| {
+                b.iter(|| {
+                    read_stream_chunked(file.path(), chunk_size)
+                        .unwrap()
+                        .map(|chunk| chunk.unwrap().data.len())
+                        .sum::<usize>()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+/// Samples each signal value is held for in the stepped write case
+const HOLD: usize = 1_000;
+
+/// Store every signal of `result` as `VectorData::Stepped` where it compacts
+fn compact_signals(mut result: WaveformResult) -> WaveformResult {
+    for table in &mut result.tables {
+        for vector in table.vectors.iter_mut().skip(1) {
+            if let VectorData::Real(values) = vector {
+                *vector = VectorData::compact(values.to_vec());
+            }
+        }
+    }
+    result
+}
+
+fn bench_write(c: &mut Criterion) {
+    let real = synth(PostVersion::V2001, Endian::Little).expected();
+    let complex = synth(PostVersion::V2001, Endian::Little)
+        .complex(true)
+        .expected();
+    let stepped = compact_signals(
+        synth(PostVersion::V2001, Endian::Little)
+            .hold(HOLD)
+            .expected(),
+    );
+    let raw = temp_path("bench_write.raw");
+    let mut group = c.benchmark_group("write");
+    group.sample_size(10);
+    // Output size: one double per real value, two per complex value
+    let values = ((SIGNALS + 1) * POINTS * std::mem::size_of::<f64>()) as u64;
+    for (label, result, bytes) in [
+        ("real", &real, values),
+        ("complex", &complex, 2 * values),
+        ("stepped", &stepped, values),
+    ] {
+        group.throughput(Throughput::Bytes(bytes));
+        group.bench_with_input(BenchmarkId::new("raw", label), result, |b, result| {
+            b.iter(|| write_spice3_raw(black_box(result), raw.to_str().unwrap()).unwrap())
+        });
+    }
+    group.finish();
+    std::fs::remove_file(raw).ok();
+}
+
+fn bench_convert(c: &mut Criterion) {
+    let file = TempFile::new("bench_convert.tr0");
+    let raw = temp_path("bench_convert.raw");
+    let csv = temp_path("bench_convert.csv");
+    let mut group = c.benchmark_group("convert");
+    group.throughput(Throughput::Bytes(file.len()));
+    group.sample_size(10);
+    group.bench_function("raw", |b| {
+        b.iter(|| read_and_convert(file.path(), raw.to_str().unwrap()).unwrap())
+    });
+    group.bench_function("csv", |b| {
+        b.iter(|| {
+            convert_stream(
+                file.path(),
+                csv.to_str().unwrap(),
+                ConvertFormat::Csv,
+                &ConvertOptions::default(),
+            )
+            .unwrap()
+        })
+    });
+    group.finish();
+    std::fs::remove_file(raw).ok();
+    std::fs::remove_file(csv).ok();
+}
+
+criterion_group!(
+    benches,
+    bench_header,
+    bench_decode,
+    bench_read,
+    bench_stream,
+    bench_write,
+    bench_convert
+);
+criterion_main!(benches);
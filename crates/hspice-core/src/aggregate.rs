@@ -0,0 +1,222 @@
+//! One-pass aggregation over a stream
+//!
+//! [`HspiceStreamReader::fold_signals`] and [`HspiceStreamReader::summarize`]
+//! consume the reader and fold every point of every signal into a
+//! per-signal result, so summary metrics of a file too large to load come
+//! out of a single pass without handing chunks to the caller. Combine with
+//! `filter_time`, `map_signals` and `set_signals` to narrow what is folded.
+//!
+//! ```rust,no_run
+//! let summary = hspice_core::read_stream("huge.tr0")
+//!     .unwrap()
+//!     .filter_time(1e-6, 2e-6)
+//!     .summarize()
+//!     .unwrap();
+//! let vdd = &summary["v(vdd)"];
+//! println!("min {} max {} mean {}", vdd.min, vdd.max, vdd.mean());
+//! ```
+
+use crate::stream::{DataChunk, HspiceStreamReader};
+use crate::types::{Result, VectorData};
+use std::collections::HashMap;
+
+/// Min, max and integral of one signal over a stream
+///
+/// NaN values are skipped. Complex signals are summarized by their
+/// magnitude, as in [`SignalStats`](crate::SignalStats).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SignalSummary {
+    /// Number of values summarized
+    pub count: usize,
+    pub min: f64,
+    pub max: f64,
+    /// Trapezoidal integral over the scale, summed over sweep tables
+    pub integral: f64,
+    /// Scale covered by the integral, summed over sweep tables
+    pub span: f64,
+}
+
+impl SignalSummary {
+    /// Time-weighted mean, `integral / span`; NaN over an empty span
+    pub fn mean(&self) -> f64 {
+        if self.span > 0.0 {
+            self.integral / self.span
+        } else {
+            f64::NAN
+        }
+    }
+}
+
+impl Default for SignalSummary {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            integral: 0.0,
+            span: 0.0,
+        }
+    }
+}
+
+/// Summary of one signal with the last point, to integrate across chunks
+#[derive(Default)]
+struct Running {
+    summary: SignalSummary,
+    last: Option<(f64, f64)>,
+}
+
+impl Running {
+    fn push(&mut self, t: f64, x: f64) {
+        if x.is_nan() {
+            return;
+        }
+        let s = &mut self.summary;
+        s.count += 1;
+        s.min = s.min.min(x);
+        s.max = s.max.max(x);
+        if let Some((t0, x0)) = self.last {
+            s.integral += 0.5 * (x0 + x) * (t - t0);
+            s.span += t - t0;
+        }
+        self.last = Some((t, x));
+    }
+}
+
+/// Value of `vector` at `row`, or its magnitude if complex
+fn value_at(vector: &VectorData, row: usize) -> f64 {
+    match vector {
+        VectorData::Complex(v) => v.get(row).map_or(f64::NAN, |z| z.norm()),
+        other => other.real_at(row).unwrap_or(f64::NAN),
+    }
+}
+
+/// Call `f(name, scale, value)` for each point of each signal in `chunk`
+fn for_each_point(chunk: &DataChunk, scale_name: &str, mut f: impl FnMut(&str, f64, f64)) {
+    let Some(scale) = chunk.data.get(scale_name) else {
+        return;
+    };
+    for (name, vector) in &chunk.data {
+        if name == scale_name {
+            continue;
+        }
+        for row in 0..vector.len() {
+            f(
+                name,
+                scale.real_at(row).unwrap_or(f64::NAN),
+                value_at(vector, row),
+            );
+        }
+    }
+}
+
+impl HspiceStreamReader {
+    /// Fold every point of every signal into a per-signal accumulator
+    ///
+    /// Each signal starts from a clone of `init`, and `f(acc, scale, value)`
+    /// is called for its points in file order, sweep tables one after
+    /// another. Complex values are passed as magnitudes. Stops at the first
+    /// error.
+    pub fn fold_signals<A, F>(self, init: A, mut f: F) -> Result<HashMap<String, A>>
+    where
+        A: Clone,
+        F: FnMut(&mut A, f64, f64),
+    {
+        let scale_name = self.metadata().scale_name;
+        let mut results: HashMap<String, A> = HashMap::new();
+        for chunk in self {
+            for_each_point(&chunk?, &scale_name, |name, t, x| {
+                let acc = match results.get_mut(name) {
+                    Some(acc) => acc,
+                    None => results.entry(name.to_string()).or_insert(init.clone()),
+                };
+                f(acc, t, x);
+            });
+        }
+        Ok(results)
+    }
+
+    /// Min, max and integral of every signal in one pass (see
+    /// [`SignalSummary`])
+    ///
+    /// The integral restarts at each sweep table, so the area between the
+    /// end of one table and the start of the next is not counted.
+    pub fn summarize(self) -> Result<HashMap<String, SignalSummary>> {
+        let scale_name = self.metadata().scale_name;
+        let mut running: HashMap<String, Running> = HashMap::new();
+        let mut table = 0;
+        for chunk in self {
+            let chunk = chunk?;
+            if std::mem::replace(&mut table, chunk.table_index) != chunk.table_index {
+                running.values_mut().for_each(|r| r.last = None);
+            }
+            for_each_point(&chunk, &scale_name, |name, t, x| {
+                match running.get_mut(name) {
+                    Some(r) => r,
+                    None => running.entry(name.to_string()).or_default(),
+                }
+                .push(t, x)
+            });
+        }
+        Ok(running
+            .into_iter()
+            .map(|(name, r)| (name, r.summary))
+            .collect())
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{temp_path, SyntheticWaveform};
+
+    #[test]
+    fn test_summarize_matches_full_read() {
+        let path = temp_path("aggregate_summary.tr0");
+        let synth = SyntheticWaveform::new()
+            .signals(2)
+            .points(200)
+            .block_size(17)
+            .sweep("temp", vec![25.0, 85.0]);
+        synth.write_tr0(&path).unwrap();
+        let expected = synth.expected();
+
+        let summary = HspiceStreamReader::open(&path, 32)
+            .unwrap()
+            .summarize()
+            .unwrap();
+        let name = &expected.variables[1].name;
+        let s = summary[name];
+
+        let mut integral = 0.0;
+        let mut min = f64::INFINITY;
+        let mut max = f64::NEG_INFINITY;
+        for table in &expected.tables {
+            let t = table.vectors[0].to_real().unwrap();
+            let x = table.vectors[1].to_real().unwrap();
+            for i in 0..t.len() {
+                min = min.min(x[i]);
+                max = max.max(x[i]);
+                if i > 0 {
+                    integral += 0.5 * (x[i - 1] + x[i]) * (t[i] - t[i - 1]);
+                }
+            }
+        }
+        assert_eq!(s.count, 400);
+        assert_eq!((s.min, s.max), (min, max));
+        assert!((s.integral - integral).abs() <= 1e-12 * integral.abs().max(1.0));
+
+        let counts = HspiceStreamReader::open(&path, 32)
+            .unwrap()
+            .fold_signals(0usize, |n, _, _| *n += 1)
+            .unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(counts.len(), 2);
+        assert!(counts.values().all(|&n| n == 400));
+    }
+}
@@ -0,0 +1,187 @@
+//! Reading many files at once
+//!
+//! [`read_many`] reads a list of files on a pool of threads and returns one
+//! result per file, in order. A file that cannot be read (or makes the
+//! parser panic) gets its own error without stopping the others, so a
+//! regression farm can load hundreds of runs in one call:
+//!
+//! ```rust,no_run
+//! use hspice_core::{read_many_with_progress, ReadOptions};
+//!
+//! let paths = ["tt.tr0", "ff.tr0", "ss.tr0"];
+//! let results = read_many_with_progress(&paths, &ReadOptions::new(), 0, |p| {
+//!     eprint!("\r{}/{} files ({} failed)", p.done, p.total, p.failed);
+//! });
+//! for (path, result) in paths.iter().zip(results) {
+//!     match result {
+//!         Ok(result) => println!("{}: {} points", path, result.len()),
+//!         Err(e) => println!("{}: {}", path, e),
+//!     }
+//! }
+//! ```
+
+use crate::options::ReadOptions;
+use crate::types::{Result, WaveformError, WaveformResult};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::channel;
+use tracing::{debug, instrument};
+
+/// How far a [`read_many_with_progress`] call has got
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BatchProgress {
+    /// Index in `paths` of the file just finished
+    pub index: usize,
+    /// Whether that file was read
+    pub succeeded: bool,
+    /// Files finished so far
+    pub done: usize,
+    /// Files finished with an error so far
+    pub failed: usize,
+    /// Number of files
+    pub total: usize,
+}
+
+/// Read every file in `paths` with `options`, on one thread per CPU
+///
+/// Returns one result per path, in the order of `paths`. Errors carry the
+/// path as context.
+pub fn read_many<P: AsRef<Path> + Sync>(
+    paths: &[P],
+    options: &ReadOptions,
+) -> Vec<Result<WaveformResult>> {
+    read_many_with_progress(paths, options, 0, |_| {})
+}
+
+/// [`read_many`] on up to `threads` threads (0: one per CPU), calling
+/// `progress` on the calling thread as each file finishes
+#[instrument(skip_all, fields(files = paths.len()))]
+pub fn read_many_with_progress<P, F>(
+    paths: &[P],
+    options: &ReadOptions,
+    threads: usize,
+    mut progress: F,
+) -> Vec<Result<WaveformResult>>
+where
+    P: AsRef<Path> + Sync,
+    F: FnMut(&BatchProgress),
+{
+    let threads = match threads {
+        0 => std::thread::available_parallelism().map_or(1, Into::into),
+        n => n,
+    }
+    .min(paths.len());
+    debug!(threads, "Reading files");
+
+    let next = AtomicUsize::new(0);
+    let (sender, receiver) = channel();
+    let mut results: Vec<Option<Result<WaveformResult>>> = Vec::new();
+    results.resize_with(paths.len(), || None);
+    let mut state = BatchProgress {
+        total: paths.len(),
+        ..BatchProgress::default()
+    };
+
+    std::thread::scope(|scope| {
+        for _ in 0..threads {
+            let sender = sender.clone();
+            let next = &next;
+            scope.spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(path) = paths.get(index) else {
+                    break;
+                };
+                if sender
+                    .send((index, read_one(path.as_ref(), options)))
+                    .is_err()
+                {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        for (index, result) in receiver {
+            state.index = index;
+            state.succeeded = result.is_ok();
+            state.done += 1;
+            state.failed += usize::from(result.is_err());
+            results[index] = Some(result);
+            progress(&state);
+        }
+    });
+
+    results
+        .into_iter()
+        .map(|result| result.expect("every index is read once"))
+        .collect()
+}
+
+/// Read one file, turning a parser panic into an error
+fn read_one(path: &Path, options: &ReadOptions) -> Result<WaveformResult> {
+    let name = path.to_string_lossy();
+    catch_unwind(AssertUnwindSafe(|| {
+        crate::read_with_options(&name, options)
+    }))
+    .unwrap_or_else(|_| {
+        Err(WaveformError::ParseError(
+            "Parser panicked while reading the file".into(),
+        ))
+    })
+    .map_err(|e| e.context(name.into_owned()))
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{temp_path, SyntheticWaveform};
+
+    #[test]
+    fn test_results_in_order_with_errors_isolated() {
+        let paths: Vec<_> = (0..5)
+            .map(|i| temp_path(&format!("batch_{}.tr0", i)))
+            .collect();
+        for (i, path) in paths.iter().enumerate() {
+            if i != 2 {
+                SyntheticWaveform::new()
+                    .signals(1)
+                    .points(10 + i)
+                    .write_tr0(path)
+                    .unwrap();
+            }
+        }
+
+        let mut reports = Vec::new();
+        let results = read_many_with_progress(&paths, &ReadOptions::new(), 3, |p| reports.push(*p));
+
+        assert_eq!(results.len(), 5);
+        for (i, result) in results.iter().enumerate() {
+            match result {
+                Ok(result) => assert_eq!(result.len(), 10 + i),
+                Err(e) => {
+                    assert_eq!(i, 2);
+                    assert!(e.to_string().contains("batch_2.tr0"), "{}", e);
+                }
+            }
+        }
+        assert_eq!(reports.len(), 5);
+        assert_eq!(reports.last().unwrap().done, 5);
+        assert_eq!(reports.last().unwrap().failed, 1);
+        assert!(reports.iter().any(|p| p.index == 2 && !p.succeeded));
+
+        for path in &paths {
+            std::fs::remove_file(path).ok();
+        }
+    }
+
+    #[test]
+    fn test_no_files() {
+        let paths: [&str; 0] = [];
+        assert!(read_many(&paths, &ReadOptions::new()).is_empty());
+    }
+}
@@ -3,8 +3,11 @@
 //! Unifies block reading logic from parser.rs and stream.rs.
 //! Follows the "Single Source of Truth" principle for all data block reads.
 
-use crate::reader::MmapReader;
-use crate::types::{PostVersion, Result, END_MARKER_2001, END_MARKER_9601};
+use crate::decode::{decode_values, is_end_marker, Block, Blocks, DecodeError};
+use crate::limits::ParserLimits;
+use crate::options::ReadOptions;
+use crate::types::{HspiceError, PostVersion, Result};
+use tracing::warn;
 
 // ============================================================================
 // Core Structures
@@ -22,33 +25,65 @@ pub struct BlockData {
 /// Data block reader
 ///
 /// Provides unified interface for reading HSPICE binary file data blocks.
-/// Supports two formats:
-/// - V9601: 4-byte float32
+/// Supports two value widths:
+/// - V9007, V9601: 4-byte float32
 /// - V2001: 8-byte float64
+///
+/// The framing is [`Blocks`]'; this adds the value limit, the repair
+/// warnings and 64-bit error offsets.
 pub struct BlockReader<'a> {
-    reader: MmapReader<'a>,
+    blocks: Blocks<'a>,
+    /// Offset of the data within the whole input, for error reporting
+    base: u64,
     version: PostVersion,
     /// Number of blocks read so far
     block_count: usize,
+    /// Number of values read so far
+    values_read: usize,
+    /// Maximum number of values to read before failing
+    max_values: usize,
+    /// Whether the end marker was found
+    found_end: bool,
 }
 
 impl<'a> BlockReader<'a> {
     /// Create a new block reader from the given data slice
     pub fn new(data: &'a [u8], version: PostVersion) -> Self {
         Self {
-            reader: MmapReader::new(data),
+            blocks: Blocks::new(data, 0),
+            base: 0,
             version,
             block_count: 0,
+            values_read: 0,
+            max_values: usize::MAX,
+            found_end: false,
         }
     }
 
+    /// Report error offsets relative to an input that `data` starts `offset`
+    /// bytes into
+    pub fn with_base_offset(mut self, offset: u64) -> Self {
+        self.base = offset;
+        self
+    }
+
+    /// Read block headers and trailers as `options` say (byte order,
+    /// lenient checking, recovery of a cut-off last block)
+    pub fn with_options(mut self, options: &ReadOptions) -> Self {
+        self.blocks = self.blocks.with_framing(options.framing());
+        self
+    }
+
+    /// Fail with `LimitExceeded` once more than `max_values` values are read
+    pub fn with_value_limit(mut self, max_values: usize) -> Self {
+        self.max_values = max_values;
+        self
+    }
+
     /// Get item size in bytes
     #[inline]
     fn item_size(&self) -> usize {
-        match self.version {
-            PostVersion::V9601 => 4,
-            PostVersion::V2001 => 8,
-        }
+        self.version.value_size()
     }
 
     /// Read the next data block
@@ -56,58 +91,64 @@ impl<'a> BlockReader<'a> {
     /// Returns `None` if end of file or read failure.
     /// Returns `Some(BlockData)` containing data and end-of-data flag.
     pub fn next_block(&mut self) -> Result<Option<BlockData>> {
-        if self.reader.remaining() == 0 {
-            return Ok(None);
-        }
-
-        let item_size = self.item_size();
-
-        // Read block header
-        let (num_items, trailer) = match self.reader.read_block_header(item_size) {
-            Ok(r) => r,
-            Err(_) => return Ok(None),
-        };
+        let mut values = Vec::new();
+        Ok(self
+            .next_block_into(&mut values)?
+            .map(|is_end| BlockData { values, is_end }))
+    }
 
-        // Read data and detect end marker
-        let mut values = Vec::with_capacity(num_items);
-        let is_end = match self.version {
-            PostVersion::V9601 => {
-                self.reader
-                    .read_floats_as_f64_into(num_items, &mut values)?;
-                values
-                    .last()
-                    .map(|&v| v as f32 >= END_MARKER_9601)
-                    .unwrap_or(false)
-            }
-            PostVersion::V2001 => {
-                self.reader.read_doubles_into(num_items, &mut values)?;
-                values
-                    .last()
-                    .map(|&v| v >= END_MARKER_2001)
-                    .unwrap_or(false)
+    /// Like `next_block`, but appending the values to `values`
+    ///
+    /// Returns the end-of-data flag, or `None` as `next_block` does. Lets
+    /// callers reuse one buffer for every block.
+    pub fn next_block_into(&mut self, values: &mut Vec<f64>) -> Result<Option<bool>> {
+        let start = self.blocks.position();
+        let block = match self.blocks.next() {
+            Some(Ok(block)) => block,
+            Some(Err(e)) if !ends_data(&e, start) => {
+                return Err(HspiceError::from(e).shifted(self.base))
             }
+            _ => return Ok(None),
         };
+        warn_repairs(&block, self.base);
 
-        // Read block trailer
-        if self.reader.read_block_trailer(trailer).is_err() {
-            return Ok(None);
-        }
+        // Validate the count before allocating for it
+        let num_items = block.payload.len() / self.item_size();
+        let values_read =
+            self.values_read
+                .checked_add(num_items)
+                .ok_or(HspiceError::SizeOverflow {
+                    what: "data value count",
+                    offset: self.base + block.offset as u64,
+                })?;
+        ParserLimits::check("data values", values_read, self.max_values)?;
+
+        // Read data and detect end marker
+        let start = values.len();
+        decode_values(block.payload, self.version, block.endian, values);
+        let is_end = values[start..]
+            .last()
+            .is_some_and(|&v| is_end_marker(v, self.version));
 
         self.block_count += 1;
+        self.values_read = values_read;
+        self.found_end |= is_end;
 
-        Ok(Some(BlockData { values, is_end }))
+        Ok(Some(is_end))
     }
 
-    /// Read all data blocks into a single Vec
+    /// Read all data blocks into a single Vec, calling `on_block` with the
+    /// bytes consumed and values read so far after each block
     ///
     /// Used for one-shot reading scenarios (e.g., parser.rs).
-    pub fn read_all(&mut self) -> Result<Vec<f64>> {
-        let estimated = self.reader.remaining() / self.estimate_divisor();
+    pub fn read_all(&mut self, mut on_block: impl FnMut(usize, usize)) -> Result<Vec<f64>> {
+        let estimated =
+            (self.blocks.remaining().len() / self.estimate_divisor()).min(self.max_values);
         let mut all_data = Vec::with_capacity(estimated);
 
-        while let Some(block) = self.next_block()? {
-            all_data.extend(block.values);
-            if block.is_end {
+        while let Some(is_end) = self.next_block_into(&mut all_data)? {
+            on_block(self.bytes_consumed(), all_data.len());
+            if is_end {
                 break;
             }
         }
@@ -115,6 +156,12 @@ impl<'a> BlockReader<'a> {
         Ok(all_data)
     }
 
+    /// Whether the end marker was read, so the data is complete
+    #[inline]
+    pub fn found_end(&self) -> bool {
+        self.found_end
+    }
+
     /// Get the number of blocks read
     #[inline]
     pub fn block_count(&self) -> usize {
@@ -125,7 +172,7 @@ impl<'a> BlockReader<'a> {
     #[inline]
     pub fn format_name(&self) -> &'static str {
         match self.version {
-            PostVersion::V9601 => "f32",
+            PostVersion::V9007 | PostVersion::V9601 => "f32",
             PostVersion::V2001 => "f64",
         }
     }
@@ -141,15 +188,43 @@ impl<'a> BlockReader<'a> {
     #[inline]
     fn estimate_divisor(&self) -> usize {
         match self.version {
-            PostVersion::V9601 => 5, // 4 bytes (f32) + overhead
-            PostVersion::V2001 => 9, // 8 bytes (f64) + overhead
+            PostVersion::V9007 | PostVersion::V9601 => 5, // 4 bytes (f32) + overhead
+            PostVersion::V2001 => 9,                      // 8 bytes (f64) + overhead
         }
     }
 
     /// Get the number of bytes consumed
     #[inline]
     pub fn bytes_consumed(&self) -> usize {
-        self.reader.position()
+        self.blocks.position()
+    }
+}
+
+/// Whether a framing `error` from the block at `offset` just ends the data
+///
+/// A malformed head or trailer ends the data; only a block cut off after
+/// a sound head is an error.
+pub(crate) fn ends_data(error: &DecodeError, offset: usize) -> bool {
+    !matches!(error, DecodeError::TruncatedBlock { offset: at, .. } if *at > offset)
+}
+
+/// Log the damage a lenient read went past in `block`, whose data starts
+/// `base` bytes into the input
+pub(crate) fn warn_repairs(block: &Block<'_>, base: u64) {
+    if block.damaged_markers {
+        warn!(
+            offset = base + block.offset as u64,
+            endian = ?block.endian,
+            "Block header markers damaged, reading anyway"
+        );
+    }
+    if let Some(found) = block.bad_trailer {
+        warn!(
+            offset = base + block.trailer_offset() as u64,
+            expected = block.payload.len(),
+            found,
+            "Block trailer mismatch ignored"
+        );
     }
 }
 
@@ -176,6 +251,7 @@ impl<'a> Iterator for BlockReader<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::END_MARKER_9601;
 
     #[test]
     fn test_block_reader_format_name() {
@@ -187,4 +263,49 @@ mod tests {
         let reader_2001 = BlockReader::new(empty, PostVersion::V2001);
         assert_eq!(reader_2001.format_name(), "f64");
     }
+
+    /// Build one little-endian 9601 block holding `values`
+    fn block_9601(values: &[f32]) -> Vec<u8> {
+        let nbytes = (values.len() * 4) as i32;
+        let mut buf = Vec::new();
+        for v in [4, values.len() as i32, 4, nbytes] {
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+        for v in values {
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+        buf.extend_from_slice(&nbytes.to_le_bytes());
+        buf
+    }
+
+    #[test]
+    fn test_block_larger_than_data_is_rejected() {
+        let mut data = block_9601(&[1.0, 2.0]);
+        // Claim 1 GiB of payload in the header
+        data[12..16].copy_from_slice(&(1i32 << 30).to_le_bytes());
+        let mut reader = BlockReader::new(&data, PostVersion::V9601).with_base_offset(100);
+        assert!(matches!(
+            reader.next_block(),
+            Err(HspiceError::TruncatedBlock {
+                offset: 116,
+                needed: 0x4000_0000,
+                available: 12
+            })
+        ));
+    }
+
+    #[test]
+    fn test_value_limit() {
+        let mut data = block_9601(&[1.0, 2.0, 3.0]);
+        data.extend(block_9601(&[4.0, END_MARKER_9601]));
+
+        let mut reader = BlockReader::new(&data, PostVersion::V9601).with_value_limit(4);
+        assert!(matches!(
+            reader.read_all(|_, _| {}),
+            Err(HspiceError::LimitExceeded { found: 5, .. })
+        ));
+
+        let mut reader = BlockReader::new(&data, PostVersion::V9601).with_value_limit(5);
+        assert_eq!(reader.read_all(|_, _| {}).unwrap().len(), 5);
+    }
 }
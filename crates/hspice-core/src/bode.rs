@@ -0,0 +1,248 @@
+//! Bode analysis of AC results (gain, phase, margins, bandwidth)
+//!
+//! ```rust,no_run
+//! use hspice_core::bode::ac_gain_phase;
+//!
+//! let result = hspice_core::read("opamp.ac0").unwrap();
+//! let bode = ac_gain_phase(&result, "out", "in").unwrap();
+//! println!("UGF {:?} Hz, PM {:?} deg", bode.unity_gain_frequency(), bode.phase_margin());
+//! ```
+//!
+//! Crossover frequencies are interpolated linearly in log frequency.
+
+use crate::measure::{crossings, Edge};
+use crate::types::{Result, VectorData, WaveformError, WaveformResult};
+use num_complex::Complex64;
+
+/// Gain in dB and unwrapped phase in degrees against frequency
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bode {
+    /// Frequency in Hz, ascending
+    pub frequency: Vec<f64>,
+    /// `20 log10 |out / in|`
+    pub gain_db: Vec<f64>,
+    /// Phase of `out / in` in degrees, unwrapped so that neighbouring
+    /// points differ by less than 180 degrees. The first point is in
+    /// (-180, 180]
+    pub phase_deg: Vec<f64>,
+}
+
+impl Bode {
+    /// Bode data of `out / input`, or of `out` alone if `input` is None
+    ///
+    /// Points at zero or negative frequency are dropped, as are points past
+    /// the end of the shortest slice.
+    pub fn new(frequency: &[f64], out: &[Complex64], input: Option<&[Complex64]>) -> Self {
+        let mut bode = Bode {
+            frequency: Vec::new(),
+            gain_db: Vec::new(),
+            phase_deg: Vec::new(),
+        };
+        let mut previous: Option<f64> = None;
+        for (i, (&f, &o)) in frequency.iter().zip(out).enumerate() {
+            let h = match input {
+                Some(input) => match input.get(i) {
+                    Some(&x) => o / x,
+                    None => break,
+                },
+                None => o,
+            };
+            if f.is_nan() || f <= 0.0 {
+                continue;
+            }
+            let mut phase = h.arg().to_degrees();
+            if let Some(p) = previous {
+                phase += ((p - phase) / 360.0).round() * 360.0;
+            }
+            previous = Some(phase);
+            bode.frequency.push(f);
+            bode.gain_db.push(20.0 * h.norm().log10());
+            bode.phase_deg.push(phase);
+        }
+        bode
+    }
+
+    /// Frequency where the gain first falls through 0 dB
+    pub fn unity_gain_frequency(&self) -> Option<f64> {
+        self.falls_through(&self.gain_db, 0.0)
+    }
+
+    /// `180 + phase` at the unity gain frequency, in degrees
+    pub fn phase_margin(&self) -> Option<f64> {
+        let f = self.unity_gain_frequency()?;
+        Some(180.0 + self.at(&self.phase_deg, f))
+    }
+
+    /// Frequency where the phase first falls through -180 degrees
+    pub fn phase_crossover_frequency(&self) -> Option<f64> {
+        self.falls_through(&self.phase_deg, -180.0)
+    }
+
+    /// Minus the gain at the phase crossover frequency, in dB
+    pub fn gain_margin(&self) -> Option<f64> {
+        let f = self.phase_crossover_frequency()?;
+        Some(-self.at(&self.gain_db, f))
+    }
+
+    /// Frequency where the gain first falls 3 dB below its value at the
+    /// lowest frequency
+    pub fn bandwidth(&self) -> Option<f64> {
+        let reference = *self.gain_db.first()?;
+        self.falls_through(&self.gain_db, reference - 10.0 * 2f64.log10())
+    }
+
+    /// `log10` of each frequency
+    fn log_frequency(&self) -> Vec<f64> {
+        self.frequency.iter().map(|f| f.log10()).collect()
+    }
+
+    /// First frequency where `values` falls through `level`
+    fn falls_through(&self, values: &[f64], level: f64) -> Option<f64> {
+        let x = crossings(&self.log_frequency(), values, level, Edge::Falling);
+        x.first().map(|x| 10f64.powf(*x))
+    }
+
+    /// `values` interpolated at frequency `f`
+    fn at(&self, values: &[f64], f: f64) -> f64 {
+        let x = f.log10();
+        let log_f = self.log_frequency();
+        let i = log_f.partition_point(|&v| v < x).clamp(1, log_f.len() - 1);
+        let (x0, x1) = (log_f[i - 1], log_f[i]);
+        if x1 == x0 {
+            return values[i];
+        }
+        values[i - 1] + (values[i] - values[i - 1]) * (x - x0) / (x1 - x0)
+    }
+}
+
+/// Bode data of `out / input` from the first table of an AC result
+///
+/// Real vectors are taken as complex with zero imaginary part. Returns an
+/// error if either signal is missing or the scale is not real.
+pub fn ac_gain_phase(result: &WaveformResult, out: &str, input: &str) -> Result<Bode> {
+    let signal = |name: &str| {
+        let vector = result
+            .get(name)
+            .ok_or_else(|| WaveformError::ParseError(format!("signal {} not found", name)))?;
+        Ok::<_, WaveformError>(match vector {
+            VectorData::Complex(v) => v.clone(),
+            other => other
+                .to_real()
+                .unwrap_or_default()
+                .iter()
+                .map(|&re| Complex64::new(re, 0.0))
+                .collect(),
+        })
+    };
+    let frequency = result
+        .scale()
+        .and_then(VectorData::to_real)
+        .ok_or_else(|| WaveformError::ParseError("scale is not real".into()))?;
+    let (out, input) = (signal(out)?, signal(input)?);
+    Ok(Bode::new(&frequency, &out, Some(&input)))
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{AnalysisType, DataTable, Variable};
+
+    /// Frequencies from 1 Hz to 10 GHz, 100 per decade
+    fn sweep() -> Vec<f64> {
+        (0..=1000).map(|i| 10f64.powf(i as f64 / 100.0)).collect()
+    }
+
+    /// `gain / ((1 + s/p1)(1 + s/p2)(1 + s/p3))`
+    fn three_pole(f: &[f64], gain: f64, poles: [f64; 3]) -> Vec<Complex64> {
+        f.iter()
+            .map(|&f| {
+                let s = Complex64::new(0.0, f);
+                let d: Complex64 = poles.iter().map(|&p| 1.0 + s / p).product();
+                gain / d
+            })
+            .collect()
+    }
+
+    fn close(a: f64, b: f64, rel: f64) -> bool {
+        (a - b).abs() <= rel * b.abs()
+    }
+
+    #[test]
+    fn test_single_pole() {
+        let f = sweep();
+        let h: Vec<Complex64> = f
+            .iter()
+            .map(|&f| 100.0 / Complex64::new(1.0, f / 1e3))
+            .collect();
+        let bode = Bode::new(&f, &h, None);
+
+        assert!(close(bode.gain_db[0], 40.0, 1e-3));
+        assert!(close(bode.bandwidth().unwrap(), 1e3, 1e-3));
+        // Gain-bandwidth product
+        assert!(close(bode.unity_gain_frequency().unwrap(), 1e5, 1e-3));
+        assert!(close(bode.phase_margin().unwrap(), 90.6, 1e-2));
+        assert_eq!(bode.gain_margin(), None);
+    }
+
+    #[test]
+    fn test_phase_is_unwrapped() {
+        let f = sweep();
+        let h = three_pole(&f, 1e4, [1e2, 1e5, 1e6]);
+        let bode = Bode::new(&f, &h, None);
+
+        // Three poles take the phase to -270 without jumping back by 360
+        assert!(bode.phase_deg.last().unwrap() < &-260.0);
+        assert!(bode
+            .phase_deg
+            .windows(2)
+            .all(|w| (w[1] - w[0]).abs() < 10.0));
+
+        // Check the crossover against the analytic phase and gain
+        let fc = bode.phase_crossover_frequency().unwrap();
+        let phase: f64 = [1e2, 1e5, 1e6]
+            .iter()
+            .map(|p| -(fc / p).atan().to_degrees())
+            .sum();
+        assert!((phase + 180.0).abs() < 0.1);
+        let gain = 20.0 * three_pole(&[fc], 1e4, [1e2, 1e5, 1e6])[0].norm().log10();
+        assert!((bode.gain_margin().unwrap() + gain).abs() < 0.05);
+        assert!(bode.phase_margin().unwrap() < 90.0);
+    }
+
+    #[test]
+    fn test_ac_gain_phase() {
+        let f = sweep();
+        let out = three_pole(&f, 10.0, [1e3, 1e12, 1e12]);
+        let input = vec![Complex64::new(0.5, 0.0); f.len()];
+        let result = WaveformResult {
+            title: String::new(),
+            date: String::new(),
+            analysis: AnalysisType::AC,
+            variables: vec![
+                Variable::new("HERTZ"),
+                Variable::new("out"),
+                Variable::new("in"),
+            ],
+            sweep_param: None,
+            tables: vec![DataTable {
+                sweep_value: None,
+                vectors: vec![
+                    VectorData::Real(f.clone().into()),
+                    VectorData::Complex(out.into()),
+                    VectorData::Complex(input.into()),
+                ],
+            }],
+            truncated: false,
+        };
+
+        let bode = ac_gain_phase(&result, "out", "in").unwrap();
+        assert_eq!(bode.frequency, f);
+        assert!(close(bode.gain_db[0], 26.02, 1e-3));
+        assert!(close(bode.bandwidth().unwrap(), 1e3, 1e-3));
+        assert!(ac_gain_phase(&result, "out", "nope").is_err());
+    }
+}
@@ -0,0 +1,309 @@
+//! Building a [`WaveformResult`] from your own data
+//!
+//! [`WaveformResultBuilder`] checks every table and point against the
+//! declared variables as they are added, so the result it builds can go
+//! to any exporter in this crate (SPICE3 raw, JSON, .npz, .mat, VCD) just
+//! like one read from a file.
+//!
+//! ```rust
+//! use hspice_core::{Variable, WaveformResultBuilder};
+//!
+//! let mut builder = WaveformResultBuilder::new("rc step");
+//! builder.add_variable(Variable::new("TIME"))?;
+//! builder.add_variable(Variable::new("v(out)"))?;
+//! for i in 0..100 {
+//!     let t = i as f64 * 1e-9;
+//!     builder.push_point(&[t, 1.0 - (-t / 20e-9).exp()])?;
+//! }
+//! let result = builder.build()?;
+//! assert_eq!(result.len(), 100);
+//! # Ok::<(), hspice_core::WaveformError>(())
+//! ```
+
+use crate::types::{
+    AnalysisType, DataTable, Result, Variable, VectorData, WaveformError, WaveformResult,
+};
+use num_complex::Complex64;
+
+/// Incremental, validated construction of a [`WaveformResult`]
+///
+/// The first variable added is the scale and must be real. All variables
+/// come before any data. Data goes in a whole table at a time with
+/// [`add_table`](Self::add_table), or a point at a time with
+/// [`push_point`](Self::push_point) into the table opened last by
+/// [`start_table`](Self::start_table). Tables carry a sweep value exactly
+/// when a sweep parameter is set.
+#[derive(Debug, Clone, Default)]
+pub struct WaveformResultBuilder {
+    title: String,
+    date: String,
+    analysis: Option<AnalysisType>,
+    sweep_param: Option<String>,
+    variables: Vec<Variable>,
+    /// Whether each variable holds complex values
+    complex: Vec<bool>,
+    tables: Vec<DataTable>,
+}
+
+fn invalid(message: impl Into<String>) -> WaveformError {
+    WaveformError::InvalidResult(message.into())
+}
+
+impl WaveformResultBuilder {
+    /// Start an empty result with `title`
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Set the simulation date (empty by default)
+    pub fn date(&mut self, date: impl Into<String>) -> &mut Self {
+        self.date = date.into();
+        self
+    }
+
+    /// Set the analysis type (by default inferred from the scale name)
+    pub fn analysis(&mut self, analysis: AnalysisType) -> &mut Self {
+        self.analysis = Some(analysis);
+        self
+    }
+
+    /// Name the sweep parameter; every table then needs a sweep value
+    pub fn sweep_param(&mut self, name: impl Into<String>) -> &mut Self {
+        self.sweep_param = Some(name.into());
+        self
+    }
+
+    /// Declare a real variable; the first one is the scale
+    pub fn add_variable(&mut self, variable: Variable) -> Result<&mut Self> {
+        self.declare(variable, false)
+    }
+
+    /// Declare a complex variable (not allowed for the scale)
+    pub fn add_complex_variable(&mut self, variable: Variable) -> Result<&mut Self> {
+        self.declare(variable, true)
+    }
+
+    fn declare(&mut self, variable: Variable, complex: bool) -> Result<&mut Self> {
+        if !self.tables.is_empty() {
+            return Err(invalid("variables must be added before any data"));
+        }
+        if self.variables.is_empty() && complex {
+            return Err(invalid(format!("scale {} must be real", variable.name)));
+        }
+        if self.variables.iter().any(|v| v.name == variable.name) {
+            return Err(invalid(format!("duplicate variable {}", variable.name)));
+        }
+        self.variables.push(variable);
+        self.complex.push(complex);
+        Ok(self)
+    }
+
+    /// Add a whole table, one vector per variable in declaration order
+    ///
+    /// Vectors must all have the same length and match their variable's
+    /// type; stepped vectors count as real.
+    pub fn add_table(
+        &mut self,
+        sweep_value: Option<f64>,
+        vectors: Vec<VectorData>,
+    ) -> Result<&mut Self> {
+        self.check_sweep_value(sweep_value)?;
+        if vectors.len() != self.variables.len() {
+            return Err(invalid(format!(
+                "table has {} vectors for {} variables",
+                vectors.len(),
+                self.variables.len()
+            )));
+        }
+        let len = vectors.first().map_or(0, VectorData::len);
+        for ((vector, variable), &complex) in vectors.iter().zip(&self.variables).zip(&self.complex)
+        {
+            if vector.len() != len {
+                return Err(invalid(format!(
+                    "{} has {} points, the scale {}",
+                    variable.name,
+                    vector.len(),
+                    len
+                )));
+            }
+            if vector.is_complex() != complex {
+                let kind = if complex { "complex" } else { "real" };
+                return Err(invalid(format!("{} must be {}", variable.name, kind)));
+            }
+        }
+        self.tables.push(DataTable {
+            sweep_value,
+            vectors,
+        });
+        Ok(self)
+    }
+
+    /// Open an empty table for [`push_point`](Self::push_point)
+    pub fn start_table(&mut self, sweep_value: Option<f64>) -> Result<&mut Self> {
+        let vectors = self
+            .complex
+            .iter()
+            .map(|&complex| {
+                if complex {
+                    VectorData::Complex(Vec::new().into())
+                } else {
+                    VectorData::Real(Vec::new().into())
+                }
+            })
+            .collect();
+        self.add_table(sweep_value, vectors)
+    }
+
+    /// Append one point to the last table
+    ///
+    /// `values` holds the scale and then each variable in declaration
+    /// order, a complex variable taking two values (real, imaginary). The
+    /// first point of an unswept result opens its table.
+    pub fn push_point(&mut self, values: &[f64]) -> Result<&mut Self> {
+        let width = self
+            .complex
+            .iter()
+            .map(|&c| if c { 2 } else { 1 })
+            .sum::<usize>();
+        if width == 0 {
+            return Err(invalid("no variables declared"));
+        }
+        if values.len() != width {
+            return Err(invalid(format!(
+                "point has {} values, expected {}",
+                values.len(),
+                width
+            )));
+        }
+        if self.tables.is_empty() {
+            self.start_table(None)?;
+        }
+        let table = self.tables.last_mut().expect("a table was just opened");
+        let mut values = values.iter().copied();
+        for vector in &mut table.vectors {
+            vector.materialize();
+            match vector {
+                VectorData::Complex(v) => {
+                    let re = values.next().unwrap_or(f64::NAN);
+                    let im = values.next().unwrap_or(f64::NAN);
+                    v.push(Complex64::new(re, im));
+                }
+                VectorData::Real(v) => v.push(values.next().unwrap_or(f64::NAN)),
+                VectorData::Real32(_) | VectorData::Stepped { .. } => {
+                    unreachable!("materialized above")
+                }
+            }
+        }
+        Ok(self)
+    }
+
+    fn check_sweep_value(&self, sweep_value: Option<f64>) -> Result<()> {
+        if self.variables.is_empty() {
+            return Err(invalid("no variables declared"));
+        }
+        match (&self.sweep_param, sweep_value) {
+            (Some(name), None) => Err(invalid(format!("table needs a value of {}", name))),
+            (None, Some(_)) => Err(invalid("sweep value given without a sweep parameter")),
+            _ => Ok(()),
+        }
+    }
+
+    /// The result built so far
+    ///
+    /// A result without tables gets one empty table, unless it is swept.
+    pub fn build(mut self) -> Result<WaveformResult> {
+        let Some(scale) = self.variables.first() else {
+            return Err(invalid("no variables declared"));
+        };
+        let analysis = self
+            .analysis
+            .unwrap_or_else(|| AnalysisType::from_scale_name(&scale.name));
+        if self.tables.is_empty() && self.sweep_param.is_none() {
+            self.start_table(None)?;
+        }
+        Ok(WaveformResult {
+            title: self.title,
+            date: self.date,
+            analysis,
+            variables: self.variables,
+            sweep_param: self.sweep_param,
+            tables: self.tables,
+            truncated: false,
+        })
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::temp_path;
+
+    #[test]
+    fn test_points_round_trip_through_raw() {
+        let mut builder = WaveformResultBuilder::new("built");
+        builder.sweep_param("temp");
+        builder.add_variable(Variable::new("TIME")).unwrap();
+        builder.add_variable(Variable::new("v(out)")).unwrap();
+        for (t, temp) in [25.0, 85.0].into_iter().enumerate() {
+            builder.start_table(Some(temp)).unwrap();
+            for i in 0..10 {
+                let x = i as f64;
+                builder.push_point(&[x * 1e-9, x + t as f64]).unwrap();
+            }
+        }
+        let result = builder.build().unwrap();
+        assert_eq!(result.analysis, AnalysisType::Transient);
+        assert_eq!(result.tables.len(), 2);
+
+        let path = temp_path("builder_round_trip.raw");
+        crate::write_spice3_raw(&result, path.to_str().unwrap()).unwrap();
+        let back = crate::read_raw(path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(path).ok();
+        assert_eq!(back.var_names(), ["TIME", "v(out)"]);
+        assert_eq!(back.get("v(out)").unwrap().real_at(9), Some(9.0));
+    }
+
+    #[test]
+    fn test_validation() {
+        let mut builder = WaveformResultBuilder::new("bad");
+        assert!(builder.push_point(&[0.0]).is_err());
+        assert!(builder
+            .add_complex_variable(Variable::new("HERTZ"))
+            .is_err());
+        builder.add_variable(Variable::new("HERTZ")).unwrap();
+        builder
+            .add_complex_variable(Variable::new("v(out)"))
+            .unwrap();
+        assert!(builder.add_variable(Variable::new("v(out)")).is_err());
+
+        // Length and type mismatches
+        let scale = VectorData::Real(vec![1.0, 2.0].into());
+        let short = VectorData::Complex(vec![Complex64::new(0.0, 0.0)].into());
+        assert!(builder.add_table(None, vec![scale.clone(), short]).is_err());
+        assert!(builder
+            .add_table(
+                None,
+                vec![scale.clone(), VectorData::Real(vec![0.0; 2].into())]
+            )
+            .is_err());
+        assert!(builder.add_table(Some(1.0), vec![scale.clone()]).is_err());
+
+        assert!(builder.push_point(&[1.0, 0.5]).is_err());
+        builder.push_point(&[1.0, 0.5, -0.5]).unwrap();
+        assert!(builder.add_variable(Variable::new("v(in)")).is_err());
+
+        let result = builder.build().unwrap();
+        assert_eq!(result.analysis, AnalysisType::AC);
+        let VectorData::Complex(v) = &result.tables[0].vectors[1] else {
+            panic!("v(out) should be complex");
+        };
+        assert_eq!(v[0], Complex64::new(0.5, -0.5));
+    }
+}
@@ -0,0 +1,373 @@
+//! Decoded window cache for waveform viewers
+//!
+//! A viewer that pans and zooms asks for the same few time windows over and
+//! over. [`ChunkCache`] keeps decoded windows in memory, keyed by the file's
+//! content hash, the signal set, the window and the decimation, and evicts
+//! the least recently used windows once a byte budget is exceeded. Keying on
+//! the content rather than the path means copies of one file share entries.
+//!
+//! ```rust,no_run
+//! use hspice_core::cache::{file_hash, ChunkCache, ChunkKey};
+//!
+//! let cache = ChunkCache::new(256 << 20);
+//! let hash = file_hash("big.tr0").unwrap();
+//! let key = ChunkKey::new(hash, &["out"], (1e-9, 2e-9), 4);
+//! let window = cache.get_or_load(&key, "big.tr0").unwrap();
+//! println!("{} points", window.data["TIME"].len());
+//! ```
+
+pub use crate::checksum::{file_hash, FileHash};
+use crate::stream::{DataChunk, HspiceStreamReader, DEFAULT_CHUNK_SIZE};
+use crate::types::{Result, VectorData};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tracing::{debug, trace};
+
+/// Identity of one decoded window
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ChunkKey {
+    pub file: FileHash,
+    /// Sorted, deduplicated signal names; empty means all signals (the
+    /// scale is always included)
+    pub signals: Vec<String>,
+    /// Window bounds as `f64::to_bits`, so keys hash exactly
+    window: (u64, u64),
+    /// Keep every nth point (1 = all)
+    pub decimation: usize,
+}
+
+impl ChunkKey {
+    pub fn new(file: FileHash, signals: &[&str], window: (f64, f64), decimation: usize) -> Self {
+        let mut signals: Vec<String> = signals.iter().map(|s| s.to_string()).collect();
+        signals.sort();
+        signals.dedup();
+        Self {
+            file,
+            signals,
+            window: (window.0.to_bits(), window.1.to_bits()),
+            decimation: decimation.max(1),
+        }
+    }
+
+    /// Window bounds (start, end) on the scale
+    pub fn window(&self) -> (f64, f64) {
+        (f64::from_bits(self.window.0), f64::from_bits(self.window.1))
+    }
+}
+
+/// Hit and size counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+    pub bytes: usize,
+}
+
+struct Entry {
+    chunk: Arc<DataChunk>,
+    bytes: usize,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct CacheState {
+    entries: HashMap<ChunkKey, Entry>,
+    clock: u64,
+    stats: CacheStats,
+}
+
+/// LRU cache of decoded windows, bounded by the bytes of sample data held
+///
+/// Safe to share between request handler threads. Loads happen outside the
+/// lock, so two threads missing on the same key may both decode it.
+pub struct ChunkCache {
+    max_bytes: usize,
+    state: Mutex<CacheState>,
+}
+
+impl ChunkCache {
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            max_bytes,
+            state: Mutex::default(),
+        }
+    }
+
+    /// Cached window for `key`, if present
+    pub fn get(&self, key: &ChunkKey) -> Option<Arc<DataChunk>> {
+        let mut state = self.lock();
+        state.clock += 1;
+        let clock = state.clock;
+        match state.entries.get_mut(key) {
+            Some(entry) => {
+                entry.last_used = clock;
+                let chunk = entry.chunk.clone();
+                state.stats.hits += 1;
+                Some(chunk)
+            }
+            None => {
+                state.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Store a window, evicting least recently used ones to stay in budget
+    ///
+    /// A window larger than the whole budget is returned but not kept.
+    pub fn insert(&self, key: ChunkKey, chunk: DataChunk) -> Arc<DataChunk> {
+        let chunk = Arc::new(chunk);
+        let bytes = chunk_bytes(&chunk);
+        if bytes > self.max_bytes {
+            debug!(bytes, "Window larger than cache, not kept");
+            return chunk;
+        }
+
+        let mut state = self.lock();
+        state.clock += 1;
+        let last_used = state.clock;
+        if let Some(old) = state.entries.insert(
+            key,
+            Entry {
+                chunk: chunk.clone(),
+                bytes,
+                last_used,
+            },
+        ) {
+            state.stats.bytes -= old.bytes;
+        }
+        state.stats.bytes += bytes;
+
+        while state.stats.bytes > self.max_bytes {
+            let Some(oldest) = state
+                .entries
+                .iter()
+                .min_by_key(|(_, e)| e.last_used)
+                .map(|(k, _)| k.clone())
+            else {
+                break;
+            };
+            if let Some(evicted) = state.entries.remove(&oldest) {
+                state.stats.bytes -= evicted.bytes;
+                trace!(bytes = evicted.bytes, "Window evicted");
+            }
+        }
+        state.stats.entries = state.entries.len();
+        chunk
+    }
+
+    /// Cached window for `key`, decoding it from `path` on a miss
+    ///
+    /// `path` must be the file `key.file` was computed from.
+    pub fn get_or_load<P: AsRef<Path>>(&self, key: &ChunkKey, path: P) -> Result<Arc<DataChunk>> {
+        if let Some(chunk) = self.get(key) {
+            return Ok(chunk);
+        }
+        let signals: Vec<&str> = key.signals.iter().map(String::as_str).collect();
+        let chunk = read_window(path, &signals, key.window(), key.decimation)?;
+        Ok(self.insert(key.clone(), chunk))
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let state = self.lock();
+        CacheStats {
+            entries: state.entries.len(),
+            ..state.stats
+        }
+    }
+
+    pub fn clear(&self) {
+        let mut state = self.lock();
+        state.entries.clear();
+        state.stats.entries = 0;
+        state.stats.bytes = 0;
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Decode `signals` (all if empty) between `window.0` and `window.1`
+/// (inclusive), keeping every `decimation`th point
+///
+/// Reading stops at the first block past the window, and rows before it are
+/// skipped without building chunks. The result is one chunk with index 0.
+/// In a swept file the window is taken from the first table.
+pub fn read_window<P: AsRef<Path>>(
+    path: P,
+    signals: &[&str],
+    window: (f64, f64),
+    decimation: usize,
+) -> Result<DataChunk> {
+    let (start, end) = window;
+    let mut reader = HspiceStreamReader::open(path, DEFAULT_CHUNK_SIZE)?;
+    if !signals.is_empty() {
+        reader.set_signals(Some(signals.iter().map(|s| s.to_string()).collect()));
+    }
+    let scale_name = reader.metadata().scale_name;
+    reader.seek_time(start)?;
+
+    let mut data: HashMap<String, VectorData> = HashMap::new();
+    let mut sweep_value = None;
+    for chunk in reader.by_ref() {
+        let chunk = chunk?;
+        if chunk.table_index > 0 {
+            break;
+        }
+        sweep_value = chunk.sweep_value;
+        let done = chunk.time_range.1 >= end;
+        for (name, vector) in chunk.data {
+            match data.get_mut(&name) {
+                Some(existing) => append(existing, vector),
+                None => {
+                    data.insert(name, vector);
+                }
+            }
+        }
+        if done {
+            break;
+        }
+    }
+
+    // Cut at the window end, then decimate
+    let scale = data.get(&scale_name).and_then(|v| v.to_real());
+    let count = scale.map_or(0, |s| s.partition_point(|&t| t <= end));
+    let step = decimation.max(1);
+    for vector in data.values_mut() {
+        *vector = match std::mem::replace(vector, VectorData::Real(Vec::new().into())) {
+            VectorData::Complex(v) => {
+                VectorData::Complex(v.into_iter().take(count).step_by(step).collect())
+            }
+            other => VectorData::Real(
+                other
+                    .to_real()
+                    .unwrap_or_default()
+                    .iter()
+                    .take(count)
+                    .step_by(step)
+                    .copied()
+                    .collect(),
+            ),
+        };
+    }
+
+    let scale = data.get(&scale_name).and_then(|v| v.to_real());
+    let time_range = scale
+        .and_then(|s| Some((*s.first()?, *s.last()?)))
+        .unwrap_or((start, start));
+    Ok(DataChunk {
+        chunk_index: 0,
+        table_index: 0,
+        sweep_value,
+        time_range,
+        data,
+    })
+}
+
+/// Append `more` to `vector`; streams never mix types for one signal
+fn append(vector: &mut VectorData, more: VectorData) {
+    match (vector, more) {
+        (VectorData::Complex(a), VectorData::Complex(b)) => a.extend(b),
+        (VectorData::Real(a), b) => a.extend(b.to_real().unwrap_or_default().iter()),
+        _ => {}
+    }
+}
+
+/// Bytes of sample data held by a chunk
+fn chunk_bytes(chunk: &DataChunk) -> usize {
+    chunk
+        .data
+        .iter()
+        .map(|(name, v)| {
+            let per_point = if v.is_complex() { 16 } else { 8 };
+            name.len() + v.len() * per_point
+        })
+        .sum()
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{temp_path, SyntheticWaveform};
+
+    fn synthetic(name: &str) -> (std::path::PathBuf, SyntheticWaveform) {
+        let path = temp_path(name);
+        let synth = SyntheticWaveform::new().signals(2).points(1000);
+        synth.write_tr0(&path).unwrap();
+        (path, synth)
+    }
+
+    #[test]
+    fn test_read_window_cuts_and_decimates() {
+        let (path, synth) = synthetic("cache_window.tr0");
+        let expected = synth.expected();
+        let scale = expected.tables[0].vectors[0].to_real().unwrap();
+        let name = expected.variables[1].name.clone();
+
+        let window = read_window(&path, &[&name], (scale[100], scale[199]), 10).unwrap();
+        let time = window.data["TIME"].to_real().unwrap();
+        assert_eq!(time.len(), 10);
+        assert_eq!(time[0], scale[100]);
+        assert_eq!(time[9], scale[190]);
+        assert_eq!(window.data.len(), 2);
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_hits_dedupe_by_content() {
+        let (path, _) = synthetic("cache_a.tr0");
+        let copy = temp_path("cache_b.tr0");
+        std::fs::copy(&path, &copy).unwrap();
+
+        let cache = ChunkCache::new(1 << 20);
+        let key_a = ChunkKey::new(file_hash(&path).unwrap(), &[], (0.0, 1.0), 1);
+        let key_b = ChunkKey::new(file_hash(&copy).unwrap(), &[], (0.0, 1.0), 1);
+        assert_eq!(key_a, key_b);
+
+        let first = cache.get_or_load(&key_a, &path).unwrap();
+        let second = cache.get_or_load(&key_b, &copy).unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 1, 1));
+
+        std::fs::remove_file(path).ok();
+        std::fs::remove_file(copy).ok();
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let chunk = |points: usize| DataChunk {
+            chunk_index: 0,
+            table_index: 0,
+            sweep_value: None,
+            time_range: (0.0, 0.0),
+            data: HashMap::from([("t".to_string(), VectorData::Real(vec![0.0; points].into()))]),
+        };
+        let key = |i: u8| ChunkKey::new(FileHash([i; 32]), &["x"], (0.0, 1.0), 1);
+
+        // Room for two 100-point windows
+        let cache = ChunkCache::new(2 * (1 + 800));
+        cache.insert(key(1), chunk(100));
+        cache.insert(key(2), chunk(100));
+        assert!(cache.get(&key(1)).is_some());
+        cache.insert(key(3), chunk(100));
+
+        assert!(cache.get(&key(2)).is_none());
+        assert!(cache.get(&key(1)).is_some());
+        assert!(cache.get(&key(3)).is_some());
+        assert_eq!(cache.stats().bytes, 2 * 801);
+
+        // Too big to keep at all
+        cache.insert(key(4), chunk(1000));
+        assert!(cache.get(&key(4)).is_none());
+        cache.clear();
+        assert_eq!(cache.stats().entries, 0);
+    }
+}
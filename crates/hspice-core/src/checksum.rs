@@ -0,0 +1,211 @@
+//! SHA-256 checksums and `.sha256` sidecar files for exports
+//!
+//! Sidecars use the `sha256sum` format (`<hex>  <file name>`), so archival
+//! pipelines can check an export with `sha256sum -c` or [`verify_sidecar`]
+//! instead of comparing its contents.
+//!
+//! ```rust,no_run
+//! use hspice_core::checksum::write_with_sidecar;
+//! use hspice_core::overlay::{overlay, write_overlay_csv, AlignOn};
+//!
+//! # let results = vec![hspice_core::read("a.tr0").unwrap()];
+//! let traces = overlay(&results, "v(out)", AlignOn::None).unwrap();
+//! let hash = write_with_sidecar("overlay.csv", |w| write_overlay_csv(&traces, w)).unwrap();
+//! println!("overlay.csv.sha256: {hash}");
+//! ```
+
+use crate::types::{Result, WaveformError};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use tracing::{debug, info};
+
+/// Extension appended to the exported file's name
+pub const SIDECAR_EXTENSION: &str = "sha256";
+
+/// SHA-256 of a file's contents
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FileHash(pub [u8; 32]);
+
+impl std::fmt::Display for FileHash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.iter().try_for_each(|b| write!(f, "{:02x}", b))
+    }
+}
+
+/// Hash a file's contents, reading it in 1 MiB pieces
+pub fn file_hash<P: AsRef<Path>>(path: P) -> Result<FileHash> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1 << 20];
+    loop {
+        let n = file.read(&mut buffer)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+    }
+    Ok(FileHash(hasher.finalize().into()))
+}
+
+// ============================================================================
+// Hashing Writer
+// ============================================================================
+
+/// Writer that hashes everything written through it
+///
+/// Lets an export be hashed as it is written, without reading it back.
+pub struct HashingWriter<W> {
+    inner: W,
+    hasher: Sha256,
+    bytes: u64,
+}
+
+impl<W: Write> HashingWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            hasher: Sha256::new(),
+            bytes: 0,
+        }
+    }
+
+    /// Bytes written so far
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    /// Return the inner writer and the hash of everything written
+    pub fn finish(self) -> (W, FileHash) {
+        (self.inner, FileHash(self.hasher.finalize().into()))
+    }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.hasher.update(&buf[..n]);
+        self.bytes += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+// ============================================================================
+// Sidecar Files
+// ============================================================================
+
+/// Sidecar path for `path` (`out.raw` -> `out.raw.sha256`)
+pub fn sidecar_path<P: AsRef<Path>>(path: P) -> PathBuf {
+    let mut name = path.as_ref().as_os_str().to_owned();
+    name.push(".");
+    name.push(SIDECAR_EXTENSION);
+    PathBuf::from(name)
+}
+
+/// Write the sidecar for `path` holding `hash`; returns the sidecar path
+pub fn write_sidecar<P: AsRef<Path>>(path: P, hash: &FileHash) -> Result<PathBuf> {
+    let path = path.as_ref();
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy())
+        .unwrap_or_default();
+    let sidecar = sidecar_path(path);
+    std::fs::write(&sidecar, format!("{}  {}\n", hash, name))?;
+    debug!(sidecar = %sidecar.display(), "Checksum sidecar written");
+    Ok(sidecar)
+}
+
+/// Check `path` against its sidecar
+///
+/// Returns `Ok(false)` on a mismatch and an error if the sidecar is
+/// missing or malformed.
+pub fn verify_sidecar<P: AsRef<Path>>(path: P) -> Result<bool> {
+    let path = path.as_ref();
+    let sidecar = sidecar_path(path);
+    let text = std::fs::read_to_string(&sidecar)?;
+    let expected = text
+        .split_whitespace()
+        .next()
+        .filter(|h| h.len() == 64 && h.bytes().all(|b| b.is_ascii_hexdigit()))
+        .ok_or_else(|| {
+            WaveformError::ParseError(format!("{}: no SHA-256 digest", sidecar.display()))
+        })?;
+    Ok(file_hash(path)?.to_string() == expected.to_ascii_lowercase())
+}
+
+/// Create `path`, write it with `f`, and write its sidecar
+///
+/// The content is hashed as it is written. The file is flushed and synced
+/// before the sidecar is written, so a sidecar never describes a partial
+/// file.
+pub fn write_with_sidecar<F>(path: &str, f: F) -> Result<FileHash>
+where
+    F: FnOnce(&mut HashingWriter<BufWriter<File>>) -> Result<()>,
+{
+    let mut writer = HashingWriter::new(BufWriter::new(File::create(path)?));
+    f(&mut writer)?;
+    writer.flush()?;
+    let bytes = writer.bytes();
+    let (buffered, hash) = writer.finish();
+    buffered
+        .into_inner()
+        .map_err(|e| e.into_error())?
+        .sync_all()?;
+    write_sidecar(path, &hash)?;
+    info!(output = %path, bytes, sha256 = %hash, "Export written with checksum");
+    Ok(hash)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::temp_path;
+
+    #[test]
+    fn test_hashing_writer_matches_file_hash() {
+        let path = temp_path("checksum_stream.csv");
+        let path_str = path.to_str().unwrap();
+        let hash = write_with_sidecar(path_str, |w| {
+            for i in 0..1000 {
+                writeln!(w, "{},{}", i, i * i)?;
+            }
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(hash, file_hash(&path).unwrap());
+        let sidecar = std::fs::read_to_string(sidecar_path(&path)).unwrap();
+        let name = path.file_name().unwrap().to_string_lossy();
+        assert_eq!(sidecar, format!("{}  {}\n", hash, name));
+        assert!(verify_sidecar(&path).unwrap());
+
+        std::fs::write(&path, b"tampered").unwrap();
+        assert!(!verify_sidecar(&path).unwrap());
+        std::fs::remove_file(sidecar_path(&path)).ok();
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_known_digest_and_bad_sidecar() {
+        let path = temp_path("checksum_empty.bin");
+        std::fs::write(&path, b"").unwrap();
+        assert_eq!(
+            file_hash(&path).unwrap().to_string(),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+
+        std::fs::write(sidecar_path(&path), "not a digest\n").unwrap();
+        assert!(verify_sidecar(&path).is_err());
+        std::fs::remove_file(sidecar_path(&path)).ok();
+        std::fs::remove_file(path).ok();
+    }
+}
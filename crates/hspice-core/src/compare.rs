@@ -0,0 +1,327 @@
+//! Golden comparison of two waveform files
+//!
+//! Every signal in the golden run is looked up in the candidate by name and
+//! checked point by point against a tolerance. Real signals are interpolated
+//! onto the golden time axis, so runs with different time steps compare
+//! cleanly; complex signals must share the golden frequency points.
+//!
+//! The report is self-contained, so a service can run the comparison next to
+//! the files and return only the report (`CompareReport::to_json`, feature
+//! `json`).
+//!
+//! ```rust,no_run
+//! use hspice_core::compare::{compare_files, Tolerance};
+//!
+//! let report = compare_files("golden.tr0", "candidate.tr0", Tolerance::new(1e-3, 1e-2)).unwrap();
+//! for diff in report.failures() {
+//!     println!("{}: max error {} at {}", diff.name, diff.max_error, diff.max_error_at);
+//! }
+//! ```
+
+use crate::overlay::sample_at;
+use crate::types::{Result, ResultExt, VectorData, WaveformResult};
+use tracing::{info, instrument};
+
+/// Allowed deviation: `|candidate - golden| <= abs + rel * |golden|`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    pub abs: f64,
+    pub rel: f64,
+}
+
+impl Tolerance {
+    pub fn new(abs: f64, rel: f64) -> Self {
+        Self { abs, rel }
+    }
+
+    fn allows(&self, golden: f64, error: f64) -> bool {
+        error <= self.abs + self.rel * golden.abs()
+    }
+}
+
+/// Comparison of one signal in one sweep table
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignalDiff {
+    pub name: String,
+    pub table: usize,
+    /// Golden points compared
+    pub points: usize,
+    /// Points outside tolerance, including points the candidate does not cover
+    pub violations: usize,
+    /// Largest absolute error seen
+    pub max_error: f64,
+    /// Scale value where `max_error` occurs
+    pub max_error_at: f64,
+}
+
+impl SignalDiff {
+    pub fn passed(&self) -> bool {
+        self.violations == 0
+    }
+}
+
+/// Result of comparing a candidate run against a golden run
+#[derive(Debug, Clone, Default)]
+pub struct CompareReport {
+    pub signals: Vec<SignalDiff>,
+    /// Golden signals missing from the candidate
+    pub missing: Vec<String>,
+    /// Structural differences (table counts, signal kinds)
+    pub notes: Vec<String>,
+}
+
+impl CompareReport {
+    /// True if every signal is within tolerance and nothing is missing
+    pub fn passed(&self) -> bool {
+        self.missing.is_empty() && self.notes.is_empty() && self.signals.iter().all(|s| s.passed())
+    }
+
+    /// Signals with at least one violation
+    pub fn failures(&self) -> impl Iterator<Item = &SignalDiff> {
+        self.signals.iter().filter(|s| !s.passed())
+    }
+
+    /// Serialize the report as a JSON object
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> String {
+        use serde_json::json;
+
+        // NaN/inf are not valid JSON numbers
+        let number = |v: f64| if v.is_finite() { json!(v) } else { json!(null) };
+        let signals: Vec<_> = self
+            .signals
+            .iter()
+            .map(|s| {
+                json!({
+                    "name": s.name,
+                    "table": s.table,
+                    "points": s.points,
+                    "violations": s.violations,
+                    "max_error": number(s.max_error),
+                    "max_error_at": number(s.max_error_at),
+                    "passed": s.passed(),
+                })
+            })
+            .collect();
+        json!({
+            "passed": self.passed(),
+            "signals": signals,
+            "missing": self.missing,
+            "notes": self.notes,
+        })
+        .to_string()
+    }
+}
+
+/// Compare every golden signal against the candidate
+pub fn compare_results(
+    golden: &WaveformResult,
+    candidate: &WaveformResult,
+    tolerance: Tolerance,
+) -> CompareReport {
+    let mut report = CompareReport::default();
+    if golden.tables.len() != candidate.tables.len() {
+        report.notes.push(format!(
+            "golden has {} tables, candidate has {}",
+            golden.tables.len(),
+            candidate.tables.len()
+        ));
+    }
+
+    for (index, var) in golden.variables.iter().enumerate().skip(1) {
+        let Some(other) = candidate.var_index(&var.name) else {
+            report.missing.push(var.name.clone());
+            continue;
+        };
+        for (t, (gold, cand)) in golden.tables.iter().zip(&candidate.tables).enumerate() {
+            let diff = compare_signal(
+                &gold.vectors[0],
+                &gold.vectors[index],
+                &cand.vectors[0],
+                &cand.vectors[other],
+                tolerance,
+            );
+            match diff {
+                Some((points, violations, max_error, max_error_at)) => {
+                    report.signals.push(SignalDiff {
+                        name: var.name.clone(),
+                        table: t,
+                        points,
+                        violations,
+                        max_error,
+                        max_error_at,
+                    })
+                }
+                None => report.notes.push(format!(
+                    "{} (table {}): real/complex or frequency points differ",
+                    var.name, t
+                )),
+            }
+        }
+    }
+    report
+}
+
+/// Read two files (HSPICE or SPICE3 raw) and compare them
+#[instrument(skip(tolerance))]
+pub fn compare_files(golden: &str, candidate: &str, tolerance: Tolerance) -> Result<CompareReport> {
+    let golden_result = crate::read_any(golden).context("reading golden")?;
+    let candidate_result = crate::read_any(candidate).context("reading candidate")?;
+    let report = compare_results(&golden_result, &candidate_result, tolerance);
+    info!(
+        signals = report.signals.len(),
+        failures = report.failures().count(),
+        missing = report.missing.len(),
+        "Comparison complete"
+    );
+    Ok(report)
+}
+
+/// `(points, violations, max_error, max_error_at)`, or `None` if the
+/// signals cannot be compared
+fn compare_signal(
+    gold_scale: &VectorData,
+    gold: &VectorData,
+    cand_scale: &VectorData,
+    cand: &VectorData,
+    tolerance: Tolerance,
+) -> Option<(usize, usize, f64, f64)> {
+    let scale = gold_scale.to_real()?;
+    let mut violations = 0;
+    let mut max_error = 0.0;
+    let mut max_error_at = scale.first().copied().unwrap_or(0.0);
+    let mut check = |i: usize, golden: f64, error: Option<f64>| match error {
+        Some(error) => {
+            if error > max_error {
+                max_error = error;
+                max_error_at = scale[i];
+            }
+            if !tolerance.allows(golden, error) {
+                violations += 1;
+            }
+        }
+        None => violations += 1,
+    };
+
+    match (gold, cand) {
+        (VectorData::Complex(g), VectorData::Complex(c)) => {
+            let cand_scale = cand_scale.to_real()?;
+            if cand_scale.as_ref() != scale.as_ref() {
+                return None;
+            }
+            for (i, (g, c)) in g.iter().zip(c).enumerate() {
+                check(i, g.norm(), Some((c - g).norm()));
+            }
+            Some((g.len(), violations, max_error, max_error_at))
+        }
+        (VectorData::Complex(_), _) | (_, VectorData::Complex(_)) => None,
+        _ => {
+            let (gold, cand, cand_scale) =
+                (gold.to_real()?, cand.to_real()?, cand_scale.to_real()?);
+            let mut cursor = 0;
+            for (i, (&t, &g)) in scale.iter().zip(gold.iter()).enumerate() {
+                let c = sample_at(&cand_scale, &cand, t, &mut cursor);
+                check(i, g, c.map(|c| (c - g).abs()));
+            }
+            Some((gold.len(), violations, max_error, max_error_at))
+        }
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{temp_path, SyntheticWaveform};
+    use crate::types::{AnalysisType, DataTable, Variable};
+
+    fn ramp(step: f64, end: f64, offset: f64) -> WaveformResult {
+        let n = (end / step) as usize + 1;
+        let time: Vec<f64> = (0..n).map(|i| i as f64 * step).collect();
+        let out = time.iter().map(|t| t + offset).collect();
+        WaveformResult {
+            title: String::new(),
+            date: String::new(),
+            analysis: AnalysisType::Transient,
+            variables: vec![Variable::new("TIME"), Variable::new("out")],
+            sweep_param: None,
+            tables: vec![DataTable {
+                sweep_value: None,
+                vectors: vec![VectorData::Real(time.into()), VectorData::Real(out)],
+            }],
+            truncated: false,
+        }
+    }
+
+    #[test]
+    fn test_interpolates_onto_golden_axis() {
+        // Same ramp on a finer grid matches exactly after interpolation
+        let report = compare_results(
+            &ramp(1.0, 10.0, 0.0),
+            &ramp(0.25, 10.0, 0.0),
+            Tolerance::new(1e-12, 0.0),
+        );
+        assert!(report.passed(), "{:?}", report);
+
+        let report = compare_results(
+            &ramp(1.0, 10.0, 0.0),
+            &ramp(1.0, 10.0, 0.1),
+            Tolerance::new(0.05, 0.0),
+        );
+        assert!(!report.passed());
+        assert_eq!(report.signals[0].violations, 11);
+        assert!((report.signals[0].max_error - 0.1).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_uncovered_points_and_missing_signals() {
+        let report = compare_results(
+            &ramp(1.0, 10.0, 0.0),
+            &ramp(1.0, 8.0, 0.0),
+            Tolerance::new(1e-9, 0.0),
+        );
+        assert_eq!(report.signals[0].violations, 2);
+
+        let mut candidate = ramp(1.0, 10.0, 0.0);
+        candidate.variables[1] = Variable::new("other");
+        let report = compare_results(&ramp(1.0, 10.0, 0.0), &candidate, Tolerance::new(1.0, 0.0));
+        assert_eq!(report.missing, vec!["out"]);
+        assert!(!report.passed());
+    }
+
+    #[test]
+    fn test_compare_files() {
+        let synth = SyntheticWaveform::new()
+            .signals(3)
+            .points(40)
+            .sweep("temp", vec![25.0, 85.0]);
+        let golden = temp_path("compare_golden.sw0");
+        let candidate = temp_path("compare_candidate.raw");
+        synth.write_tr0(&golden).unwrap();
+        crate::write_spice3_raw(&synth.expected(), candidate.to_str().unwrap()).unwrap();
+
+        // The raw writer keeps only the first sweep table
+        let report = compare_files(
+            golden.to_str().unwrap(),
+            candidate.to_str().unwrap(),
+            Tolerance::new(0.0, 1e-6),
+        )
+        .unwrap();
+        assert_eq!(report.signals.len(), 3);
+        assert!(report.signals.iter().all(|s| s.passed()));
+        assert_eq!(report.notes.len(), 1);
+
+        #[cfg(feature = "json")]
+        {
+            let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+            assert_eq!(json["passed"], false);
+            assert_eq!(json["signals"].as_array().unwrap().len(), 3);
+        }
+
+        std::fs::remove_file(golden).ok();
+        std::fs::remove_file(candidate).ok();
+    }
+}
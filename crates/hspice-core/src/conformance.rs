@@ -0,0 +1,564 @@
+//! Conformance vectors: golden summaries of parsed files
+//!
+//! The `conformance/` directory at the repository root pairs small input
+//! files with JSON summaries of what the core parser returns for them
+//! (metadata, a SHA-256 per vector, and a few samples). Bindings and
+//! independent implementations prove they decode identically by producing
+//! the same summary.
+//!
+//! [`raw_round_trip`] checks the writer against the readers: it exports a
+//! result to SPICE3 raw, reads it back and compares every value within a
+//! tolerance in ULPs (units in the last place).
+//!
+//! Enabled with the `conformance` feature.
+//!
+//! ```rust,no_run
+//! let reports = hspice_core::conformance::verify("conformance").unwrap();
+//! for report in &reports {
+//!     assert!(report.passed(), "{}: {:?}", report.name, report.mismatches);
+//! }
+//!
+//! let result = hspice_core::read("sim.tr0").unwrap();
+//! let diffs = hspice_core::conformance::raw_round_trip(
+//!     &result,
+//!     hspice_core::RawEncoding::Binary,
+//!     0,
+//! )
+//! .unwrap();
+//! assert!(diffs.is_empty(), "{:?}", diffs);
+//! ```
+
+use crate::limits::ParserLimits;
+use crate::types::{DataTable, Result, VectorData, WaveformError, WaveformResult};
+use crate::writer::{write_spice3_raw_to, RawEncoding};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+/// Directory holding the golden JSON files, relative to the conformance root
+pub const EXPECTED_DIR: &str = "expected";
+
+/// Directory holding the input files, relative to the conformance root
+pub const INPUTS_DIR: &str = "inputs";
+
+// ============================================================================
+// Summary Types
+// ============================================================================
+
+/// Which reader an input file goes through
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InputFormat {
+    /// HSPICE binary, read with [`crate::read`]
+    Hspice,
+    /// SPICE3 raw, read with [`crate::read_raw`]
+    Spice3,
+}
+
+/// Golden summary of one parsed file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Summary {
+    /// Input path relative to the conformance root
+    pub input: String,
+    pub format: InputFormat,
+    pub title: String,
+    pub date: String,
+    /// Analysis type as displayed (`transient`, `ac`, ...)
+    pub analysis: String,
+    pub sweep_param: Option<String>,
+    pub variables: Vec<VariableSummary>,
+    pub tables: Vec<TableSummary>,
+}
+
+/// Name and type of one variable
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VariableSummary {
+    pub name: String,
+    /// Variable type as displayed (`time`, `voltage`, ...)
+    pub var_type: String,
+}
+
+/// One data table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TableSummary {
+    pub sweep_value: Option<f64>,
+    pub points: usize,
+    /// In variable order
+    pub vectors: Vec<VectorSummary>,
+}
+
+/// Hash and spot samples of one vector
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VectorSummary {
+    pub complex: bool,
+    /// SHA-256 of the values as little-endian f64 (complex: re, im pairs)
+    pub sha256: String,
+    /// First, middle and last values
+    pub samples: Vec<Sample>,
+}
+
+/// A single value at an index; `value` is `[re]` or `[re, im]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Sample {
+    pub index: usize,
+    pub value: Vec<f64>,
+}
+
+impl Summary {
+    /// Serialize as pretty-printed JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("summary is always serializable")
+    }
+
+    /// Parse a golden JSON file
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| WaveformError::ParseError(format!("Invalid conformance JSON: {}", e)))
+    }
+}
+
+// ============================================================================
+// Building and Comparing Summaries
+// ============================================================================
+
+/// Summarize a parsed result
+pub fn summarize(result: &WaveformResult, input: &str, format: InputFormat) -> Summary {
+    Summary {
+        input: input.to_string(),
+        format,
+        title: result.title.clone(),
+        date: result.date.clone(),
+        analysis: result.analysis.to_string(),
+        sweep_param: result.sweep_param.clone(),
+        variables: result
+            .variables
+            .iter()
+            .map(|v| VariableSummary {
+                name: v.name.clone(),
+                var_type: v.var_type.to_string(),
+            })
+            .collect(),
+        tables: result
+            .tables
+            .iter()
+            .map(|t| TableSummary {
+                sweep_value: t.sweep_value,
+                points: t.len(),
+                vectors: t.vectors.iter().map(summarize_vector).collect(),
+            })
+            .collect(),
+    }
+}
+
+fn summarize_vector(vector: &VectorData) -> VectorSummary {
+    let len = vector.len();
+    let mut indices = vec![0, len / 2, len.saturating_sub(1)];
+    indices.dedup();
+    if len == 0 {
+        indices.clear();
+    }
+
+    let mut hasher = Sha256::new();
+    let samples = match vector {
+        VectorData::Real(_) | VectorData::Real32(_) | VectorData::Stepped { .. } => {
+            let values = vector.to_real().unwrap_or_default();
+            for v in values.iter() {
+                hasher.update(v.to_le_bytes());
+            }
+            indices
+                .into_iter()
+                .map(|index| Sample {
+                    index,
+                    value: vec![values[index]],
+                })
+                .collect()
+        }
+        VectorData::Complex(values) => {
+            for c in values {
+                hasher.update(c.re.to_le_bytes());
+                hasher.update(c.im.to_le_bytes());
+            }
+            indices
+                .into_iter()
+                .map(|index| Sample {
+                    index,
+                    value: vec![values[index].re, values[index].im],
+                })
+                .collect()
+        }
+    };
+
+    VectorSummary {
+        complex: vector.is_complex(),
+        sha256: to_hex(&hasher.finalize()),
+        samples,
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// List every difference between two summaries (empty if they match)
+pub fn compare(actual: &Summary, expected: &Summary) -> Vec<String> {
+    let mut diffs = Vec::new();
+    macro_rules! check {
+        ($label:expr, $actual:expr, $expected:expr) => {
+            if $actual != $expected {
+                diffs.push(format!(
+                    "{}: got {:?}, expected {:?}",
+                    $label, $actual, $expected
+                ));
+            }
+        };
+    }
+
+    check!("format", actual.format, expected.format);
+    check!("title", actual.title, expected.title);
+    check!("date", actual.date, expected.date);
+    check!("analysis", actual.analysis, expected.analysis);
+    check!("sweep_param", actual.sweep_param, expected.sweep_param);
+    check!("variables", actual.variables, expected.variables);
+    check!("table count", actual.tables.len(), expected.tables.len());
+
+    for (t, (a, e)) in actual.tables.iter().zip(&expected.tables).enumerate() {
+        check!(
+            format!("table {} sweep_value", t),
+            a.sweep_value,
+            e.sweep_value
+        );
+        check!(format!("table {} points", t), a.points, e.points);
+        for (v, (av, ev)) in a.vectors.iter().zip(&e.vectors).enumerate() {
+            let name = expected
+                .variables
+                .get(v)
+                .map(|var| var.name.as_str())
+                .unwrap_or("?");
+            check!(format!("table {} {} sha256", t, name), av.sha256, ev.sha256);
+            check!(
+                format!("table {} {} samples", t, name),
+                av.samples,
+                ev.samples
+            );
+        }
+    }
+
+    diffs
+}
+
+// ============================================================================
+// Verification
+// ============================================================================
+
+/// Outcome of one conformance case
+#[derive(Debug, Clone)]
+pub struct CaseReport {
+    /// Golden file name without extension
+    pub name: String,
+    /// Differences from the golden summary (empty on success)
+    pub mismatches: Vec<String>,
+}
+
+impl CaseReport {
+    /// Whether the case matched its golden summary
+    pub fn passed(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// Parse every case under `root` with the core readers and compare against
+/// the golden summaries
+///
+/// `root` is a conformance directory containing `expected/*.json`. Read
+/// errors are reported as mismatches so one broken case does not hide the
+/// rest; only an unreadable directory or invalid JSON fails outright.
+pub fn verify<P: AsRef<Path>>(root: P) -> Result<Vec<CaseReport>> {
+    let root = root.as_ref();
+    let mut golden_files: Vec<_> = std::fs::read_dir(root.join(EXPECTED_DIR))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    golden_files.sort();
+
+    let mut reports = Vec::with_capacity(golden_files.len());
+    for path in golden_files {
+        let expected = Summary::from_json(&std::fs::read_to_string(&path)?)?;
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let mismatches = match read_input(root, &expected) {
+            Ok(result) => compare(
+                &summarize(&result, &expected.input, expected.format),
+                &expected,
+            ),
+            Err(e) => vec![format!("read failed: {}", e)],
+        };
+        reports.push(CaseReport { name, mismatches });
+    }
+
+    Ok(reports)
+}
+
+fn read_input(root: &Path, summary: &Summary) -> Result<WaveformResult> {
+    let path = root.join(&summary.input);
+    let path = path.to_string_lossy();
+    match summary.format {
+        InputFormat::Hspice => crate::read(&path),
+        InputFormat::Spice3 => crate::read_raw(&path),
+    }
+}
+
+// ============================================================================
+// Round Trips
+// ============================================================================
+
+/// Distance between two values in ULPs (units in the last place)
+///
+/// Values of opposite sign count the ULPs through zero. Two NaNs are 0
+/// apart; NaN and a number are `u64::MAX` apart.
+pub fn ulp_distance(a: f64, b: f64) -> u64 {
+    if a.is_nan() || b.is_nan() {
+        return if a.is_nan() && b.is_nan() {
+            0
+        } else {
+            u64::MAX
+        };
+    }
+    // Map the bits to integers that order like the values
+    let ordered = |x: f64| {
+        let bits = x.to_bits() as i64;
+        if bits < 0 {
+            i64::MIN - bits
+        } else {
+            bits
+        }
+    };
+    ordered(a).abs_diff(ordered(b))
+}
+
+/// List every difference in the data of two results beyond `max_ulps`
+/// (empty if they match)
+///
+/// Variable names, table count, sweep values and point counts must match
+/// exactly. Values are compared by real and imaginary part; a real vector
+/// matches a complex one whose imaginary parts are zero. Each vector
+/// reports its first mismatching point only.
+pub fn compare_data(
+    actual: &WaveformResult,
+    expected: &WaveformResult,
+    max_ulps: u64,
+) -> Vec<String> {
+    let mut diffs = compare_names(actual, expected);
+    if actual.tables.len() != expected.tables.len() {
+        diffs.push(format!(
+            "table count: got {}, expected {}",
+            actual.tables.len(),
+            expected.tables.len()
+        ));
+    }
+    for (t, (a, e)) in actual.tables.iter().zip(&expected.tables).enumerate() {
+        if a.sweep_value != e.sweep_value {
+            diffs.push(format!(
+                "table {} sweep_value: got {:?}, expected {:?}",
+                t, a.sweep_value, e.sweep_value
+            ));
+        }
+        compare_table(t, expected, a, e, max_ulps, &mut diffs);
+    }
+    diffs
+}
+
+/// Write each table of `result` as SPICE3 raw in `encoding`, read it back
+/// and list the differences beyond `max_ulps` (empty if none)
+///
+/// SPICE3 raw holds one table without a sweep value, so every table makes
+/// its own file and sweep values are not compared. The title and variable
+/// names must survive too. Errors writing or reading the raw data are
+/// returned as errors, not differences.
+pub fn raw_round_trip(
+    result: &WaveformResult,
+    encoding: RawEncoding,
+    max_ulps: u64,
+) -> Result<Vec<String>> {
+    let mut diffs = Vec::new();
+    for (t, table) in result.tables.iter().enumerate() {
+        let single = WaveformResult {
+            title: result.title.clone(),
+            date: result.date.clone(),
+            analysis: result.analysis,
+            variables: result.variables.clone(),
+            sweep_param: None,
+            tables: vec![table.clone()],
+            truncated: false,
+        };
+        let mut raw = Vec::new();
+        write_spice3_raw_to(&single, &mut raw, encoding)?;
+        let back = crate::read_raw_bytes(&raw, &ParserLimits::unlimited())?;
+
+        if back.title != result.title {
+            diffs.push(format!(
+                "table {} title: got {:?}, expected {:?}",
+                t, back.title, result.title
+            ));
+        }
+        diffs.extend(compare_names(&back, result));
+        match back.tables.first() {
+            Some(back_table) => compare_table(t, result, back_table, table, max_ulps, &mut diffs),
+            None => diffs.push(format!("table {}: nothing read back", t)),
+        }
+    }
+    Ok(diffs)
+}
+
+fn compare_names(actual: &WaveformResult, expected: &WaveformResult) -> Vec<String> {
+    if actual.var_names() == expected.var_names() {
+        return Vec::new();
+    }
+    vec![format!(
+        "variables: got {:?}, expected {:?}",
+        actual.var_names(),
+        expected.var_names()
+    )]
+}
+
+fn compare_table(
+    t: usize,
+    expected: &WaveformResult,
+    a: &DataTable,
+    e: &DataTable,
+    max_ulps: u64,
+    diffs: &mut Vec<String>,
+) {
+    if a.len() != e.len() {
+        diffs.push(format!(
+            "table {} points: got {}, expected {}",
+            t,
+            a.len(),
+            e.len()
+        ));
+        return;
+    }
+    for (v, (av, ev)) in a.vectors.iter().zip(&e.vectors).enumerate() {
+        let name = expected
+            .variables
+            .get(v)
+            .map(|var| var.name.as_str())
+            .unwrap_or("?");
+        let mismatch = (0..e.len()).find_map(|i| {
+            let (got, want) = (parts_at(av, i), parts_at(ev, i));
+            let ulps = ulp_distance(got.0, want.0).max(ulp_distance(got.1, want.1));
+            (ulps > max_ulps).then_some((i, got, want, ulps))
+        });
+        if let Some((i, got, want, ulps)) = mismatch {
+            diffs.push(format!(
+                "table {} {} point {}: got {:?}, expected {:?} ({} ULPs apart)",
+                t, name, i, got, want, ulps
+            ));
+        }
+    }
+}
+
+/// Real and imaginary part of `vector` at `i`
+fn parts_at(vector: &VectorData, i: usize) -> (f64, f64) {
+    match vector {
+        VectorData::Complex(v) => v.get(i).map_or((f64::NAN, f64::NAN), |c| (c.re, c.im)),
+        other => (other.real_at(i).unwrap_or(f64::NAN), 0.0),
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{DataTable, Variable};
+    use num_complex::Complex64;
+
+    fn small_result() -> WaveformResult {
+        WaveformResult {
+            title: "t".into(),
+            date: "d".into(),
+            analysis: crate::AnalysisType::AC,
+            variables: vec![Variable::new("HERTZ"), Variable::new("out")],
+            sweep_param: None,
+            tables: vec![DataTable {
+                sweep_value: None,
+                vectors: vec![
+                    VectorData::Real(vec![1.0, 2.0, 3.0].into()),
+                    VectorData::Complex(vec![Complex64::new(0.5, -0.5); 3].into()),
+                ],
+            }],
+            truncated: false,
+        }
+    }
+
+    #[test]
+    fn test_summary_json_roundtrip() {
+        let summary = summarize(&small_result(), "inputs/x.ac0", InputFormat::Hspice);
+        assert_eq!(summary.tables[0].vectors[0].samples.len(), 3);
+        assert_eq!(
+            summary.tables[0].vectors[1].samples[0].value,
+            vec![0.5, -0.5]
+        );
+
+        let parsed = Summary::from_json(&summary.to_json()).unwrap();
+        assert_eq!(parsed, summary);
+        assert!(compare(&parsed, &summary).is_empty());
+    }
+
+    #[test]
+    fn test_compare_reports_differences() {
+        let expected = summarize(&small_result(), "x", InputFormat::Hspice);
+        let mut result = small_result();
+        result.title = "other".into();
+        if let VectorData::Real(v) = &mut result.tables[0].vectors[0] {
+            v[1] = 2.5;
+        }
+        let actual = summarize(&result, "x", InputFormat::Hspice);
+
+        let diffs = compare(&actual, &expected);
+        assert_eq!(diffs.len(), 3, "{:?}", diffs);
+        assert!(diffs[0].starts_with("title"));
+        assert!(diffs[1].contains("HERTZ sha256"));
+    }
+
+    #[test]
+    fn test_ulp_distance() {
+        assert_eq!(ulp_distance(1.0, 1.0), 0);
+        assert_eq!(ulp_distance(1.0, f64::from_bits(1.0f64.to_bits() + 3)), 3);
+        assert_eq!(ulp_distance(-0.0, 0.0), 0);
+        assert_eq!(ulp_distance(-f64::MIN_POSITIVE, f64::MIN_POSITIVE), 2 << 52);
+        assert_eq!(ulp_distance(f64::NAN, f64::NAN), 0);
+        assert_eq!(ulp_distance(f64::NAN, 0.0), u64::MAX);
+    }
+
+    #[test]
+    fn test_round_trip_reports_lost_precision() {
+        let result = small_result();
+        assert!(raw_round_trip(&result, RawEncoding::Binary, 0)
+            .unwrap()
+            .is_empty());
+
+        let mut result = small_result();
+        if let VectorData::Complex(v) = &mut result.tables[0].vectors[1] {
+            v[2].im = 1.0 / 3.0;
+        }
+        let coarse = RawEncoding::Ascii { precision: 4 };
+        let diffs = raw_round_trip(&result, coarse, 0).unwrap();
+        assert_eq!(diffs.len(), 1, "{:?}", diffs);
+        assert!(diffs[0].starts_with("table 0 out point 2"));
+        assert!(raw_round_trip(&result, coarse, 1 << 40).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_empty_vector_has_no_samples() {
+        let summary = summarize_vector(&VectorData::Real(vec![].into()));
+        assert!(summary.samples.is_empty());
+        // SHA-256 of no input
+        assert!(summary.sha256.starts_with("e3b0c442"));
+    }
+}
@@ -0,0 +1,474 @@
+//! Streaming conversion to CSV and JSON Lines
+//!
+//! [`convert_stream`] feeds a stream reader straight into a chunked writer,
+//! so only one chunk is in memory at a time and files far larger than RAM
+//! convert in a single pass. The CSV layout is the one the CLI's `export`
+//! writes: a header row, a leading sweep column for swept files, and
+//! `<name>.re`/`<name>.im` columns for complex signals. JSON Lines output is
+//! the NDJSON stream of [`crate::json`] (feature `json`). A result already
+//! in memory is written in the same CSV layout by
+//! [`WaveformResult::write_csv`].
+//!
+//! ```rust,no_run
+//! use hspice_core::{convert_stream, ConvertFormat, ConvertOptions};
+//!
+//! let options = ConvertOptions {
+//!     signals: Some(vec!["v(out)".into()]),
+//!     ..Default::default()
+//! };
+//! let report = convert_stream("huge.tr0", "huge.csv", ConvertFormat::Csv, &options).unwrap();
+//! println!("{} rows, {} bytes", report.points, report.bytes);
+//! ```
+
+use crate::stream::{DataChunk, HspiceStreamReader, StreamMetadata, DEFAULT_CHUNK_SIZE};
+use crate::types::{DataTable, Result, VectorData, WaveformError, WaveformResult};
+use crate::writer::{write_context, ConversionReport};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::Instant;
+use tracing::{info, instrument};
+
+/// Output format of [`convert_stream`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConvertFormat {
+    Csv,
+    /// One metadata line, then one line per chunk (feature `json`)
+    #[cfg(feature = "json")]
+    Jsonl,
+}
+
+impl ConvertFormat {
+    /// Format named by the extension of `path` (`.csv`, `.jsonl`, `.ndjson`)
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        let ext = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "csv" => Some(Self::Csv),
+            #[cfg(feature = "json")]
+            "jsonl" | "ndjson" => Some(Self::Jsonl),
+            _ => None,
+        }
+    }
+}
+
+/// What [`convert_stream`] reads
+#[derive(Debug, Clone)]
+pub struct ConvertOptions {
+    /// Minimum rows per chunk, which bounds memory use
+    pub chunk_size: usize,
+    /// Signals to write (all if `None`); the scale is always written
+    pub signals: Option<Vec<String>>,
+    /// Only rows whose scale lies in `[start, end]`
+    pub time_range: Option<(f64, f64)>,
+}
+
+impl Default for ConvertOptions {
+    fn default() -> Self {
+        Self {
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            signals: None,
+            time_range: None,
+        }
+    }
+}
+
+/// Convert `input` to `output` in `format`, one chunk at a time
+///
+/// The report counts rows in `points` and columns of the first chunk
+/// (the scale included) in `variables`. Errors writing the output are
+/// `WriteFailed` with the output path.
+#[instrument(skip_all, fields(input = %input, output = %output))]
+pub fn convert_stream(
+    input: &str,
+    output: &str,
+    format: ConvertFormat,
+    options: &ConvertOptions,
+) -> Result<ConversionReport> {
+    let mut reader = HspiceStreamReader::open(input, options.chunk_size)?;
+    if let Some(signals) = &options.signals {
+        reader = reader.with_signals(signals.clone());
+    }
+    if let Some((start, end)) = options.time_range {
+        reader = reader.filter_time(start, end);
+    }
+    convert_reader(reader, output, format)
+}
+
+/// Write every chunk of an open `reader` to `output` in `format`
+///
+/// Use this instead of [`convert_stream`] to set up the reader yourself,
+/// e.g. with `with_progress` or `map_signals`.
+pub fn convert_reader(
+    reader: HspiceStreamReader,
+    output: &str,
+    format: ConvertFormat,
+) -> Result<ConversionReport> {
+    let started = Instant::now();
+    let header = reader.header();
+    let sweep_param = header.sweep_name.clone().filter(|_| header.sweep_size > 1);
+    let metadata = reader.metadata();
+
+    let context = |stage| move |e| write_context(output, stage, e);
+    let file = File::create(output).map_err(WaveformError::IoError)?;
+    let mut out = ChunkWriter::new(BufWriter::new(file), format, &metadata, sweep_param);
+    out.start().map_err(context("writing the header"))?;
+    for chunk in reader {
+        out.write_chunk(&chunk?).map_err(context("writing data"))?;
+    }
+    let (points, variables, writer) = out.finish().map_err(context("writing data"))?;
+
+    let file = writer
+        .into_inner()
+        .map_err(|e| context("flushing data")(e.into_error().into()))?;
+    file.sync_all()
+        .map_err(|e| context("syncing to disk")(e.into()))?;
+    let bytes = file.metadata().map_err(WaveformError::IoError)?.len();
+
+    let report = ConversionReport {
+        points,
+        variables,
+        bytes,
+        duration: started.elapsed(),
+        warnings: Vec::new(),
+        sha256: None,
+    };
+    info!(output, points, bytes, ?format, "Stream conversion complete");
+    Ok(report)
+}
+
+/// Writes chunks in one of the [`ConvertFormat`]s
+struct ChunkWriter<'a, W: Write> {
+    writer: W,
+    format: ConvertFormat,
+    metadata: &'a StreamMetadata,
+    /// Sweep column name, for a CSV of a file with several sweep tables
+    sweep_param: Option<String>,
+    /// Columns, fixed by the first chunk
+    columns: Option<Vec<Column<'a>>>,
+    points: usize,
+}
+
+/// One CSV column: a signal, or one part of a complex signal
+#[derive(Debug, Clone, Copy)]
+enum Column<'a> {
+    Real(&'a str),
+    Re(&'a str),
+    Im(&'a str),
+}
+
+impl<'a, W: Write> ChunkWriter<'a, W> {
+    fn new(
+        writer: W,
+        format: ConvertFormat,
+        metadata: &'a StreamMetadata,
+        sweep_param: Option<String>,
+    ) -> Self {
+        Self {
+            writer,
+            format,
+            metadata,
+            sweep_param,
+            columns: None,
+            points: 0,
+        }
+    }
+
+    /// Signal names in file order, scale first
+    fn names(&self) -> impl Iterator<Item = &'a String> {
+        std::iter::once(&self.metadata.scale_name).chain(&self.metadata.signal_names)
+    }
+
+    /// Write what comes before the first chunk
+    fn start(&mut self) -> Result<()> {
+        match self.format {
+            // The CSV header waits for the first chunk, which says which
+            // signals are complex
+            ConvertFormat::Csv => Ok(()),
+            #[cfg(feature = "json")]
+            ConvertFormat::Jsonl => {
+                crate::json::write_ndjson_metadata(self.metadata, &mut self.writer)
+            }
+        }
+    }
+
+    fn write_chunk(&mut self, chunk: &DataChunk) -> Result<()> {
+        if self.columns.is_none() {
+            let columns = self
+                .names()
+                .filter_map(|name| chunk.data.get(name).map(|v| (name.as_str(), v)))
+                .flat_map(|(name, vector)| match vector {
+                    VectorData::Complex(_) => vec![Column::Re(name), Column::Im(name)],
+                    _ => vec![Column::Real(name)],
+                })
+                .collect();
+            self.set_columns(columns)?;
+        }
+        self.points += self.rows(chunk);
+        match self.format {
+            ConvertFormat::Csv => self.write_csv_rows(chunk),
+            #[cfg(feature = "json")]
+            ConvertFormat::Jsonl => {
+                crate::json::write_ndjson_chunk(self.metadata, chunk, &mut self.writer)
+            }
+        }
+    }
+
+    /// Rows in `chunk`, the length of its scale
+    fn rows(&self, chunk: &DataChunk) -> usize {
+        chunk
+            .data
+            .get(&self.metadata.scale_name)
+            .map_or(0, VectorData::len)
+    }
+
+    fn set_columns(&mut self, columns: Vec<Column<'a>>) -> Result<()> {
+        if self.format == ConvertFormat::Csv {
+            let mut header: Vec<String> = self.sweep_param.iter().map(|p| csv_field(p)).collect();
+            header.extend(columns.iter().map(|column| match *column {
+                Column::Real(name) => csv_field(name),
+                Column::Re(name) => csv_field(&format!("{}.re", name)),
+                Column::Im(name) => csv_field(&format!("{}.im", name)),
+            }));
+            writeln!(self.writer, "{}", header.join(","))?;
+        }
+        self.columns = Some(columns);
+        Ok(())
+    }
+
+    fn write_csv_rows(&mut self, chunk: &DataChunk) -> Result<()> {
+        let columns = self.columns.as_deref().unwrap_or_default();
+        for row in 0..self.rows(chunk) {
+            let mut first = true;
+            if self.sweep_param.is_some() {
+                first = false;
+                if let Some(value) = chunk.sweep_value {
+                    write!(self.writer, "{}", value)?;
+                }
+            }
+            for column in columns {
+                if !std::mem::take(&mut first) {
+                    write!(self.writer, ",")?;
+                }
+                let value = match *column {
+                    Column::Real(name) => chunk.data.get(name).and_then(|v| v.real_at(row)),
+                    Column::Re(name) | Column::Im(name) => match chunk.data.get(name) {
+                        Some(VectorData::Complex(v)) => v.get(row).map(|c| match column {
+                            Column::Re(_) => c.re,
+                            _ => c.im,
+                        }),
+                        _ => None,
+                    },
+                };
+                write!(self.writer, "{}", value.unwrap_or(f64::NAN))?;
+            }
+            writeln!(self.writer)?;
+        }
+        Ok(())
+    }
+
+    /// Finish the output; returns rows and columns written, and the writer
+    fn finish(mut self) -> Result<(usize, usize, W)> {
+        if self.columns.is_none() {
+            // No rows: the CSV still gets a header, every column real
+            let columns = self.names().map(|name| Column::Real(name)).collect();
+            self.set_columns(columns)?;
+        }
+        let variables = self
+            .columns
+            .iter()
+            .flatten()
+            .filter(|c| !matches!(c, Column::Im(_)))
+            .count();
+        self.writer.flush()?;
+        Ok((self.points, variables, self.writer))
+    }
+}
+
+// ============================================================================
+// Results in memory
+// ============================================================================
+
+impl WaveformResult {
+    /// Write as CSV to `writer`, in the layout of [`convert_stream`]
+    pub fn write_csv<W: Write>(&self, writer: &mut W) -> Result<()> {
+        write_csv(self, writer)
+    }
+}
+
+/// Write `result` as CSV with a header row; a swept result gets a leading
+/// sweep column and complex signals get `<name>.re` and `<name>.im` columns
+fn write_csv<W: Write>(result: &WaveformResult, out: &mut W) -> Result<()> {
+    let swept = result
+        .sweep_param
+        .as_ref()
+        .filter(|_| result.tables.len() > 1);
+    let first = result.tables.first();
+    let is_complex = |i: usize| first.is_some_and(|t| t.vectors[i].is_complex());
+
+    let mut header = Vec::new();
+    if let Some(param) = swept {
+        header.push(csv_field(param));
+    }
+    for (i, var) in result.variables.iter().enumerate() {
+        if is_complex(i) {
+            header.push(csv_field(&format!("{}.re", var.name)));
+            header.push(csv_field(&format!("{}.im", var.name)));
+        } else {
+            header.push(csv_field(&var.name));
+        }
+    }
+    writeln!(out, "{}", header.join(","))?;
+
+    for table in &result.tables {
+        write_csv_table(table, swept.is_some(), out)?;
+    }
+    Ok(())
+}
+
+fn write_csv_table<W: Write>(table: &DataTable, swept: bool, out: &mut W) -> Result<()> {
+    let len = table.vectors.first().map_or(0, VectorData::len);
+    for row in 0..len {
+        let mut first = true;
+        let mut sep = |out: &mut W| -> std::io::Result<()> {
+            if !std::mem::take(&mut first) {
+                write!(out, ",")?;
+            }
+            Ok(())
+        };
+        if swept {
+            sep(out)?;
+            if let Some(value) = table.sweep_value {
+                write!(out, "{}", value)?;
+            }
+        }
+        for vector in &table.vectors {
+            sep(out)?;
+            match vector {
+                VectorData::Complex(v) => write!(out, "{},{}", v[row].re, v[row].im)?,
+                other => write!(out, "{}", other.real_at(row).unwrap_or(f64::NAN))?,
+            }
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{temp_path, SyntheticWaveform};
+
+    #[test]
+    fn test_csv_matches_full_read() {
+        let input = temp_path("convert_swept.tr0");
+        let output = temp_path("convert_swept.csv");
+        let synth = SyntheticWaveform::new()
+            .signals(2)
+            .points(120)
+            .block_size(25)
+            .sweep("temp", vec![25.0, 85.0]);
+        synth.write_tr0(&input).unwrap();
+        let expected = synth.expected();
+
+        let options = ConvertOptions {
+            chunk_size: 16,
+            ..Default::default()
+        };
+        let report = convert_stream(
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+            ConvertFormat::Csv,
+            &options,
+        )
+        .unwrap();
+        let csv = std::fs::read_to_string(&output).unwrap();
+        std::fs::remove_file(&input).ok();
+        std::fs::remove_file(&output).ok();
+
+        assert_eq!(report.points, 240);
+        assert_eq!(report.variables, 3);
+        assert_eq!(report.bytes, csv.len() as u64);
+        let lines: Vec<&str> = csv.lines().collect();
+        let names: Vec<&str> = expected.variables.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(lines[0], format!("temp,{}", names.join(",")));
+        assert_eq!(lines.len(), 241);
+        let last: Vec<f64> = lines[240].split(',').map(|f| f.parse().unwrap()).collect();
+        let table = &expected.tables[1];
+        assert_eq!(last[0], 85.0);
+        for (value, vector) in last[1..].iter().zip(&table.vectors) {
+            assert_eq!(Some(*value), vector.real_at(119));
+        }
+
+        // A result in memory is written the same way
+        let mut in_memory = Vec::new();
+        expected.write_csv(&mut in_memory).unwrap();
+        assert_eq!(String::from_utf8(in_memory).unwrap(), csv);
+    }
+
+    #[test]
+    fn test_complex_columns_and_jsonl() {
+        let input = temp_path("convert_ac.ac0");
+        let output = temp_path("convert_ac.csv");
+        SyntheticWaveform::new()
+            .signals(2)
+            .points(30)
+            .complex(true)
+            .write_tr0(&input)
+            .unwrap();
+
+        let options = ConvertOptions {
+            chunk_size: 8,
+            signals: Some(vec!["n1".into()]),
+            ..Default::default()
+        };
+        let input = input.to_str().unwrap().to_string();
+        let report = convert_stream(
+            &input,
+            output.to_str().unwrap(),
+            ConvertFormat::Csv,
+            &options,
+        )
+        .unwrap();
+        let csv = std::fs::read_to_string(&output).unwrap();
+        assert_eq!(report.variables, 2);
+        assert_eq!(csv.lines().next(), Some("HERTZ,n1.re,n1.im"));
+        assert_eq!(csv.lines().count(), 31);
+
+        let jsonl = output.with_extension("jsonl");
+        assert_eq!(ConvertFormat::from_path(&jsonl), Some(ConvertFormat::Jsonl));
+        let report = convert_stream(
+            &input,
+            jsonl.to_str().unwrap(),
+            ConvertFormat::Jsonl,
+            &ConvertOptions::default(),
+        )
+        .unwrap();
+        let text = std::fs::read_to_string(&jsonl).unwrap();
+        for path in [
+            input.as_str(),
+            output.to_str().unwrap(),
+            jsonl.to_str().unwrap(),
+        ] {
+            std::fs::remove_file(path).ok();
+        }
+        assert_eq!(report.points, 30);
+        let lines: Vec<serde_json::Value> = text
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines[0]["type"], "metadata");
+        assert_eq!(lines[1]["data"]["n0"][0].as_array().unwrap().len(), 2);
+    }
+}
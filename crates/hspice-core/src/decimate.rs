@@ -0,0 +1,333 @@
+//! Downsampling for plotting
+//!
+//! [`decimate`] reduces a signal to about `target_points` points of the
+//! original data. [`Method::MinMax`] and [`Method::Lttb`] keep peaks and the
+//! visual shape; [`Method::Stride`] is the cheapest but can drop narrow
+//! spikes. The first and last points are always kept.
+//!
+//! ```rust,no_run
+//! use hspice_core::decimate::{decimate, Method};
+//!
+//! let result = hspice_core::read("sim.tr0").unwrap();
+//! let time = result.get("TIME").and_then(|v| v.as_real()).unwrap();
+//! let vout = result.get("out").and_then(|v| v.as_real()).unwrap();
+//! let (x, y) = decimate(vout, time, Method::MinMax, 2000);
+//! ```
+//!
+//! Streams decimate each chunk as it is decoded with
+//! [`crate::HspiceStreamReader::with_decimation`].
+
+use crate::stream::DataChunk;
+use crate::types::VectorData;
+use std::borrow::Cow;
+
+/// Downsampling strategy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    /// The minimum and maximum of each of `target_points / 2` equal-width
+    /// scale buckets, in scale order; keeps every peak
+    MinMax,
+    /// Largest-Triangle-Three-Buckets: one point per bucket, the one that
+    /// best preserves the shape of the line
+    Lttb,
+    /// Every n-th point
+    Stride,
+}
+
+/// Indices of the points kept by `method`, ascending
+///
+/// Returns every index if the signal has no more than `target_points`
+/// points; otherwise at most `target_points` (but at least 2) indices,
+/// including the first and last. `signal` and `scale` must have the same
+/// length; the extra values of the longer one are ignored.
+pub fn decimate_indices(
+    signal: &[f64],
+    scale: &[f64],
+    method: Method,
+    target_points: usize,
+) -> Vec<usize> {
+    let n = signal.len().min(scale.len());
+    let target = target_points.max(2);
+    if n <= target {
+        return (0..n).collect();
+    }
+    match method {
+        Method::MinMax => min_max(&signal[..n], &scale[..n], target),
+        Method::Lttb => lttb(&signal[..n], &scale[..n], target),
+        Method::Stride => stride(n, target),
+    }
+}
+
+/// `(scale, signal)` of the points kept by `method` (see
+/// [`decimate_indices`])
+pub fn decimate(
+    signal: &[f64],
+    scale: &[f64],
+    method: Method,
+    target_points: usize,
+) -> (Vec<f64>, Vec<f64>) {
+    decimate_indices(signal, scale, method, target_points)
+        .into_iter()
+        .map(|i| (scale[i], signal[i]))
+        .unzip()
+}
+
+/// Decimate every vector of a stream chunk to about `1 / factor` of its rows
+///
+/// Rows are picked for each signal (complex ones by magnitude) and the
+/// union is kept for all vectors, so the chunk still shares one scale and
+/// no signal loses a peak. With many signals the union can be larger than
+/// the target.
+pub fn decimate_chunk(
+    chunk: DataChunk,
+    scale_name: &str,
+    method: Method,
+    factor: usize,
+) -> DataChunk {
+    let Some(scale) = chunk.data.get(scale_name).and_then(VectorData::to_real) else {
+        return chunk;
+    };
+    let target = scale.len().div_ceil(factor.max(1));
+    if scale.len() <= target.max(2) {
+        return chunk;
+    }
+
+    let mut keep = vec![false; scale.len()];
+    for (name, vector) in &chunk.data {
+        if name == scale_name {
+            continue;
+        }
+        let values = match vector {
+            VectorData::Complex(v) => Cow::Owned(v.iter().map(|z| z.norm()).collect()),
+            other => other.to_real().unwrap_or_default(),
+        };
+        for i in decimate_indices(&values, &scale, method, target) {
+            keep[i] = true;
+        }
+    }
+    if chunk.data.len() == 1 {
+        // Only the scale was selected
+        for i in stride(scale.len(), target) {
+            keep[i] = true;
+        }
+    }
+    let rows: Vec<usize> = (0..keep.len()).filter(|&i| keep[i]).collect();
+
+    let data = chunk
+        .data
+        .iter()
+        .map(|(name, vector)| (name.clone(), take(vector, &rows)))
+        .collect();
+    DataChunk { data, ..chunk }
+}
+
+/// The values of `vector` at `rows`
+fn take(vector: &VectorData, rows: &[usize]) -> VectorData {
+    match vector {
+        VectorData::Complex(v) => {
+            VectorData::Complex(rows.iter().filter_map(|&i| v.get(i)).copied().collect())
+        }
+        other => {
+            let values = other.to_real().unwrap_or_default();
+            VectorData::Real(
+                rows.iter()
+                    .filter_map(|&i| values.get(i))
+                    .copied()
+                    .collect(),
+            )
+        }
+    }
+}
+
+// ============================================================================
+// Strategies
+// ============================================================================
+
+/// First and last point, and every `step`-th point between them
+fn stride(n: usize, target: usize) -> Vec<usize> {
+    let step = (n - 1).div_ceil(target - 1).max(1);
+    let mut indices: Vec<usize> = (0..n).step_by(step).collect();
+    if indices.last() != Some(&(n - 1)) {
+        indices.push(n - 1);
+    }
+    indices
+}
+
+fn min_max(signal: &[f64], scale: &[f64], target: usize) -> Vec<usize> {
+    let n = signal.len();
+    if target < 4 {
+        return vec![0, n - 1];
+    }
+    let buckets = (target - 2) / 2;
+    let (x0, span) = (scale[0], scale[n - 1] - scale[0]);
+    let bucket_of = |x: f64| {
+        if span > 0.0 {
+            (((x - x0) / span * buckets as f64) as usize).min(buckets - 1)
+        } else {
+            0
+        }
+    };
+
+    let mut indices = vec![0];
+    let mut push_bucket = |lo: usize, hi: usize| {
+        let (first, second) = if lo <= hi { (lo, hi) } else { (hi, lo) };
+        indices.push(first);
+        if second != first {
+            indices.push(second);
+        }
+    };
+
+    // (bucket, index of min, index of max) of the bucket being scanned
+    let mut current: Option<(usize, usize, usize)> = None;
+    for i in 1..n - 1 {
+        let bucket = bucket_of(scale[i]);
+        match current.as_mut() {
+            Some((b, lo, hi)) if *b == bucket => {
+                if signal[i] < signal[*lo] {
+                    *lo = i;
+                }
+                if signal[i] > signal[*hi] {
+                    *hi = i;
+                }
+            }
+            _ => {
+                if let Some((_, lo, hi)) = current {
+                    push_bucket(lo, hi);
+                }
+                current = Some((bucket, i, i));
+            }
+        }
+    }
+    if let Some((_, lo, hi)) = current {
+        push_bucket(lo, hi);
+    }
+    indices.push(n - 1);
+    indices
+}
+
+fn lttb(signal: &[f64], scale: &[f64], target: usize) -> Vec<usize> {
+    let n = signal.len();
+    if target < 3 {
+        return vec![0, n - 1];
+    }
+    // Points 1..n-1 split into target - 2 buckets of equal count
+    let buckets = target - 2;
+    let bounds = |b: usize| 1 + b * (n - 2) / buckets;
+
+    let mut indices = Vec::with_capacity(target);
+    indices.push(0);
+    let mut a = 0;
+    for b in 0..buckets {
+        let (start, end) = (bounds(b), bounds(b + 1));
+        // Average of the next bucket (the last point after the last bucket)
+        let (next_start, next_end) = if b + 1 < buckets {
+            (end, bounds(b + 2))
+        } else {
+            (n - 1, n)
+        };
+        let count = (next_end - next_start) as f64;
+        let avg_x = scale[next_start..next_end].iter().sum::<f64>() / count;
+        let avg_y = signal[next_start..next_end].iter().sum::<f64>() / count;
+
+        let (ax, ay) = (scale[a], signal[a]);
+        let area =
+            |i: usize| ((ax - avg_x) * (signal[i] - ay) - (ax - scale[i]) * (avg_y - ay)).abs();
+        let mut best = start;
+        let mut best_area = -1.0;
+        for i in start..end {
+            let area = area(i);
+            if area > best_area {
+                best_area = area;
+                best = i;
+            }
+        }
+        indices.push(best);
+        a = best;
+    }
+    indices.push(n - 1);
+    indices
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_complex::Complex64;
+    use std::collections::HashMap;
+
+    fn spiky(n: usize) -> (Vec<f64>, Vec<f64>) {
+        let x: Vec<f64> = (0..n).map(|i| i as f64 * 1e-9).collect();
+        let mut y: Vec<f64> = (0..n).map(|i| (i as f64 / 50.0).sin()).collect();
+        y[4321] = 5.0;
+        y[7777] = -5.0;
+        (x, y)
+    }
+
+    #[test]
+    fn test_small_signals_are_unchanged() {
+        let (x, y) = spiky(10_000);
+        for method in [Method::MinMax, Method::Lttb, Method::Stride] {
+            assert_eq!(decimate_indices(&y[..50], &x[..50], method, 100).len(), 50);
+            assert!(decimate_indices(&[], &[], method, 100).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_bounds_and_endpoints() {
+        let (x, y) = spiky(10_000);
+        for method in [Method::MinMax, Method::Lttb, Method::Stride] {
+            for target in [0, 2, 3, 100, 999] {
+                let indices = decimate_indices(&y, &x, method, target);
+                assert!(indices.len() <= target.max(2), "{:?} {}", method, target);
+                assert_eq!(indices.first(), Some(&0));
+                assert_eq!(indices.last(), Some(&9999));
+                assert!(indices.windows(2).all(|w| w[0] < w[1]));
+            }
+        }
+        assert_eq!(decimate_indices(&y, &x, Method::Stride, 4).len(), 4);
+        assert_eq!(decimate_indices(&y, &x, Method::Lttb, 100).len(), 100);
+    }
+
+    #[test]
+    fn test_peaks_are_kept() {
+        let (x, y) = spiky(10_000);
+        for method in [Method::MinMax, Method::Lttb] {
+            let (_, kept) = decimate(&y, &x, method, 200);
+            assert!(kept.contains(&5.0), "{:?}", method);
+            assert!(kept.contains(&-5.0), "{:?}", method);
+        }
+    }
+
+    #[test]
+    fn test_chunk_shares_rows() {
+        let (x, y) = spiky(10_000);
+        let flipped: Vec<Complex64> = y.iter().map(|&v| Complex64::new(0.0, -v)).collect();
+        let chunk = DataChunk {
+            chunk_index: 3,
+            table_index: 1,
+            sweep_value: Some(85.0),
+            time_range: (x[0], x[9999]),
+            data: HashMap::from([
+                ("TIME".to_string(), VectorData::Real(x.clone().into())),
+                ("a".to_string(), VectorData::Real(y.clone().into())),
+                ("b".to_string(), VectorData::Complex(flipped.into())),
+            ]),
+        };
+        let chunk = decimate_chunk(chunk, "TIME", Method::MinMax, 100);
+        let time = chunk.data["TIME"].as_real().unwrap();
+        let a = chunk.data["a"].as_real().unwrap();
+        assert_eq!(chunk.chunk_index, 3);
+        assert_eq!((chunk.table_index, chunk.sweep_value), (1, Some(85.0)));
+        assert_eq!(chunk.data["b"].len(), time.len());
+        assert_eq!(a.len(), time.len());
+        assert!(time.len() <= 200);
+        assert!(a.contains(&5.0) && a.contains(&-5.0));
+        // Every kept row still pairs the scale with its own value
+        for (t, v) in time.iter().zip(a) {
+            assert_eq!(y[(t / 1e-9).round() as usize], *v);
+        }
+    }
+}
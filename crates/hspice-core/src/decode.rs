@@ -0,0 +1,984 @@
+//! HSPICE binary decoding over byte slices, without `std`
+//!
+//! The format logic every reader in this crate shares: block framing and
+//! byte order detection, value decoding for each post version, end markers
+//! and the fixed header fields. It only needs `core` and `alloc`, so it
+//! also builds with `default-features = false` for embedded targets and
+//! `wasm32-unknown-unknown`, where there is no file system or mmap.
+//!
+//! [`decode`] reads a whole file already in memory into row-major values:
+//!
+//! ```rust
+//! use hspice_core::decode::decode;
+//! # let data = hspice_core::testing::SyntheticWaveform::new().to_tr0_bytes();
+//!
+//! let (header, tables) = decode(&data).unwrap();
+//! let columns = header.num_columns();
+//! for row in tables[0].values.chunks_exact(columns) {
+//!     let (time, signals) = (row[0], &row[1..]);
+//! #   let _ = (time, signals);
+//! }
+//! ```
+//!
+//! The readers of the default `std` feature are built on the same
+//! [`Blocks`] and [`Header`], and add memory mapping, files, layout
+//! probing, limits, recovery and logging on top.
+
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+// ============================================================================
+// Constants (HSPICE format specific)
+// ============================================================================
+
+/// Header character positions (matching C implementation)
+pub const NUM_OF_VARIABLES_POSITION: usize = 0;
+pub const NUM_OF_PROBES_POSITION: usize = 4;
+pub const NUM_OF_SWEEPS_POSITION: usize = 8;
+pub const POST_START_POSITION1: usize = 16;
+pub const POST_START_POSITION2: usize = 20;
+pub const DATE_START_POSITION: usize = 88;
+pub const DATE_END_POSITION: usize = 112;
+pub const TITLE_START_POSITION: usize = 24;
+pub const SWEEP_SIZE_POSITION1: usize = 176;
+pub const SWEEP_SIZE_POSITION2: usize = 187;
+pub const VECTOR_DESCRIPTION_START_POSITION: usize = 256;
+
+pub const POST_STRING11: &str = "9007";
+pub const POST_STRING12: &str = "9601";
+pub const POST_STRING21: &str = "2001";
+
+pub const FREQUENCY_TYPE: i32 = 2;
+pub const VOLTAGE_TYPE: i32 = 1;
+pub const CURRENT_TYPE: i32 = 8;
+pub const COMPLEX_VAR: i32 = 1;
+pub const REAL_VAR: i32 = 0;
+
+/// End-of-data marker for 9601 format (float32 representation of ~1e30)
+#[allow(clippy::excessive_precision)]
+pub const END_MARKER_9601: f32 = 1.0000000150474662e+30_f32;
+/// End-of-data marker for 2001 format
+pub const END_MARKER_2001: f64 = 1.0e+30_f64;
+
+/// Text that ends the header, inside its last block
+pub const HEADER_END: &[u8] = b"$&%#";
+
+/// Bytes before the payload of a block: four ints, the last the payload size
+pub const BLOCK_HEADER_LEN: usize = 16;
+
+/// Width of the count and post version fields
+const FIELD_LEN: usize = 4;
+
+/// Width of the sweep size field
+const SWEEP_SIZE_LEN: usize = 10;
+
+// ============================================================================
+// Enums
+// ============================================================================
+
+/// Endianness detected from file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Endian {
+    Little,
+    Big,
+}
+
+/// Generate endian-aware read methods
+macro_rules! impl_endian_read {
+    ($fn_name:ident, $ty:ty) => {
+        #[inline]
+        pub fn $fn_name(&self, bytes: [u8; core::mem::size_of::<$ty>()]) -> $ty {
+            match self {
+                Endian::Little => <$ty>::from_le_bytes(bytes),
+                Endian::Big => <$ty>::from_be_bytes(bytes),
+            }
+        }
+    };
+}
+
+impl Endian {
+    impl_endian_read!(read_i32, i32);
+    impl_endian_read!(read_f32, f32);
+    impl_endian_read!(read_f64, f64);
+}
+
+/// Post format version - determines data precision
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PostVersion {
+    /// Legacy 9007 format: 4-byte float32, laid out like 9601; HSPICE cuts
+    /// signal names to 16 characters
+    V9007,
+    /// 9601 format: 4-byte float32
+    V9601,
+    /// 2001 format: 8-byte float64 (double precision)
+    V2001,
+}
+
+impl PostVersion {
+    /// Bytes per stored value
+    pub fn value_size(&self) -> usize {
+        match self {
+            PostVersion::V9007 | PostVersion::V9601 => 4,
+            PostVersion::V2001 => 8,
+        }
+    }
+
+    /// The identifier written in the header
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PostVersion::V9007 => POST_STRING11,
+            PostVersion::V9601 => POST_STRING12,
+            PostVersion::V2001 => POST_STRING21,
+        }
+    }
+
+    /// Version named by the two post version fields of a header (`post2`
+    /// holds it in 2001 files, `post1` in older ones)
+    pub fn from_header_fields(post1: &str, post2: &str) -> Option<Self> {
+        match (post1, post2) {
+            (_, POST_STRING21) => Some(PostVersion::V2001),
+            (POST_STRING11, _) => Some(PostVersion::V9007),
+            (POST_STRING12, _) => Some(PostVersion::V9601),
+            _ => None,
+        }
+    }
+}
+
+/// Encoding of the title, date and signal names in the header
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextEncoding {
+    /// UTF-8, invalid sequences replaced with U+FFFD
+    #[default]
+    Utf8,
+    /// ISO 8859-1, one byte per character (older Unix and Windows tools)
+    Latin1,
+}
+
+impl TextEncoding {
+    /// Decode `bytes` as text
+    pub fn decode<'a>(&self, bytes: &'a [u8]) -> Cow<'a, str> {
+        match self {
+            TextEncoding::Utf8 => String::from_utf8_lossy(bytes),
+            TextEncoding::Latin1 if bytes.is_ascii() => {
+                Cow::Borrowed(core::str::from_utf8(bytes).expect("ASCII is UTF-8"))
+            }
+            TextEncoding::Latin1 => Cow::Owned(bytes.iter().map(|&b| b as char).collect()),
+        }
+    }
+}
+
+// ============================================================================
+// Errors
+// ============================================================================
+
+/// Why [`decode`] or [`parse_header`] failed; offsets are bytes into the
+/// input
+///
+/// Converts into the [`crate::WaveformError`] variant of the same name.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecodeError {
+    /// The input ends inside a block
+    TruncatedBlock {
+        offset: usize,
+        needed: usize,
+        available: usize,
+    },
+    /// A block head is not the `[4, count, 4, size]` pattern
+    CorruptedBlockHeader { offset: usize },
+    /// A block head declares a negative payload size
+    InvalidBlockSize { size: i32, offset: usize },
+    /// A block trailer does not repeat the size from its head
+    TrailerMismatch {
+        expected: i32,
+        found: i32,
+        offset: usize,
+    },
+    /// The header carries neither a 9007/9601 nor a 2001 version string
+    UnknownPostVersion { value: String },
+    /// The header ends before the vector description section
+    HeaderTooShort { len: usize },
+    /// The header's variable/probe counts are impossible
+    InvalidVectorCount { variables: i32, probes: i32 },
+    /// The header declares a multi-dimensional sweep
+    UnsupportedSweep { count: i32 },
+    /// The header lists fewer vector names than it declares
+    MissingVectorNames { expected: usize, found: usize },
+    /// The data ends before all declared sweep tables were read
+    MissingSweepTable {
+        index: usize,
+        total: usize,
+        offset: usize,
+    },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TruncatedBlock {
+                offset,
+                needed,
+                available,
+            } => write!(
+                f,
+                "Truncated block at byte {}: need {} bytes, {} available",
+                offset, needed, available
+            ),
+            Self::CorruptedBlockHeader { offset } => {
+                write!(f, "Corrupted block header at byte {}", offset)
+            }
+            Self::InvalidBlockSize { size, offset } => {
+                write!(f, "Invalid block size {} at byte {}", size, offset)
+            }
+            Self::TrailerMismatch {
+                expected,
+                found,
+                offset,
+            } => write!(
+                f,
+                "Block trailer mismatch at byte {}: expected {}, found {}",
+                offset, expected, found
+            ),
+            Self::UnknownPostVersion { value } => write!(f, "Unknown post version {:?}", value),
+            Self::HeaderTooShort { len } => write!(f, "Header too short: {} bytes", len),
+            Self::InvalidVectorCount { variables, probes } => write!(
+                f,
+                "Invalid vector counts: {} variables, {} probes",
+                variables, probes
+            ),
+            Self::UnsupportedSweep { count } => write!(
+                f,
+                "Unsupported sweep dimension {} (only one-dimensional sweeps)",
+                count
+            ),
+            Self::MissingVectorNames { expected, found } => write!(
+                f,
+                "Header lists {} vector names, expected {}",
+                found, expected
+            ),
+            Self::MissingSweepTable {
+                index,
+                total,
+                offset,
+            } => write!(
+                f,
+                "Missing data for sweep table {} of {} at byte {}",
+                index, total, offset
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+// ============================================================================
+// Blocks and Values
+// ============================================================================
+
+/// Byte order whose first and third ints of `header` are 4, trying
+/// `endian` only
+#[inline]
+pub fn block_marked(header: &[u8], endian: Endian) -> bool {
+    let int_at =
+        |i: usize| endian.read_i32([header[i], header[i + 1], header[i + 2], header[i + 3]]);
+    header.len() >= 12 && int_at(0) == 4 && int_at(8) == 4
+}
+
+/// Byte order of a block from its header, little endian first; `None` if
+/// the markers are damaged
+#[inline]
+pub fn block_endian(header: &[u8]) -> Option<Endian> {
+    [Endian::Little, Endian::Big]
+        .into_iter()
+        .find(|&endian| block_marked(header, endian))
+}
+
+/// Whether `value`, the last of a block, is the end-of-data marker
+#[inline]
+pub fn is_end_marker(value: f64, version: PostVersion) -> bool {
+    match version {
+        PostVersion::V9007 | PostVersion::V9601 => value as f32 >= END_MARKER_9601,
+        PostVersion::V2001 => value >= END_MARKER_2001,
+    }
+}
+
+/// Append the values stored in `bytes` to `target` as f64
+///
+/// A partial value at the end of `bytes` is ignored.
+#[inline]
+pub fn decode_values(bytes: &[u8], version: PostVersion, endian: Endian, target: &mut Vec<f64>) {
+    target.reserve(bytes.len() / version.value_size());
+    match version {
+        PostVersion::V9007 | PostVersion::V9601 => {
+            // Process 2 values at a time for better pipelining
+            let chunks = bytes.chunks_exact(8);
+            let remainder = chunks.remainder();
+            for chunk in chunks {
+                let v1 = endian.read_f32([chunk[0], chunk[1], chunk[2], chunk[3]]);
+                let v2 = endian.read_f32([chunk[4], chunk[5], chunk[6], chunk[7]]);
+                target.push(v1 as f64);
+                target.push(v2 as f64);
+            }
+            if remainder.len() >= 4 {
+                let v = endian.read_f32([remainder[0], remainder[1], remainder[2], remainder[3]]);
+                target.push(v as f64);
+            }
+        }
+        PostVersion::V2001 => {
+            for chunk in bytes.chunks_exact(8) {
+                target.push(endian.read_f64([
+                    chunk[0], chunk[1], chunk[2], chunk[3], chunk[4], chunk[5], chunk[6], chunk[7],
+                ]));
+            }
+        }
+    }
+}
+
+/// Whether the last whole value of `payload` is the end-of-data marker
+#[inline]
+pub fn ends_with_marker(payload: &[u8], version: PostVersion, endian: Endian) -> bool {
+    let size = version.value_size();
+    let end = payload.len() / size * size;
+    let Some(last) = end.checked_sub(size).map(|start| &payload[start..end]) else {
+        return false;
+    };
+    let value = match version {
+        PostVersion::V9007 | PostVersion::V9601 => {
+            endian.read_f32([last[0], last[1], last[2], last[3]]) as f64
+        }
+        PostVersion::V2001 => endian.read_f64([
+            last[0], last[1], last[2], last[3], last[4], last[5], last[6], last[7],
+        ]),
+    };
+    is_end_marker(value, version)
+}
+
+/// Damage [`Blocks`] reads past instead of failing; the default is strict
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Framing {
+    /// Byte order every block must be marked in, instead of detecting it
+    /// per block
+    pub endian: Option<Endian>,
+    /// Read a block whose markers are damaged in the byte order of
+    /// `endian` or of the block before
+    pub damaged_markers: bool,
+    /// Accept a trailer that does not repeat the payload size
+    pub trailer_mismatch: bool,
+    /// Return what is left of a block cut off by the end of the input
+    pub cut_off: bool,
+}
+
+/// One block: its payload and where it starts
+#[derive(Debug, Clone, Copy)]
+pub struct Block<'a> {
+    /// Offset of the block header in the input
+    pub offset: usize,
+    pub endian: Endian,
+    pub payload: &'a [u8],
+    /// The markers were damaged; read in the byte order before
+    pub damaged_markers: bool,
+    /// The trailer, if it does not repeat the payload size
+    pub bad_trailer: Option<i32>,
+    /// The input ends inside the block; `payload` is what is left of it
+    pub cut_off: bool,
+}
+
+impl Block<'_> {
+    /// Offset of the trailer in the input
+    pub fn trailer_offset(&self) -> usize {
+        self.offset + BLOCK_HEADER_LEN + self.payload.len()
+    }
+}
+
+/// Iterator over the blocks of a slice
+///
+/// Each block is a 16-byte header marked with 4s, the payload and a
+/// trailer repeating the payload size. Iteration stops at the end of the
+/// slice or after the first error; [`Framing`] says what is not an error.
+#[derive(Debug, Clone)]
+pub struct Blocks<'a> {
+    data: &'a [u8],
+    pos: usize,
+    /// Offset of `data` in the input, for errors
+    base: usize,
+    framing: Framing,
+    /// Byte order of the last block read
+    endian: Option<Endian>,
+    failed: bool,
+}
+
+impl<'a> Blocks<'a> {
+    /// Blocks of `data`, reporting offsets as if it started `base` bytes
+    /// into the input
+    pub fn new(data: &'a [u8], base: usize) -> Self {
+        Self {
+            data,
+            pos: 0,
+            base,
+            framing: Framing::default(),
+            endian: None,
+            failed: false,
+        }
+    }
+
+    /// Read past the damage `framing` allows
+    pub fn with_framing(mut self, framing: Framing) -> Self {
+        self.framing = framing;
+        self.endian = framing.endian.or(self.endian);
+        self
+    }
+
+    /// Bytes read so far
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// The bytes not read yet
+    pub fn remaining(&self) -> &'a [u8] {
+        &self.data[self.pos..]
+    }
+
+    /// Byte order of the block head at `rest`, and whether its markers
+    /// were damaged
+    fn block_order(&self, rest: &[u8], offset: usize) -> Result<(Endian, bool), DecodeError> {
+        let detected = match self.framing.endian {
+            Some(endian) => Some(endian).filter(|&e| block_marked(rest, e)),
+            None => block_endian(rest),
+        };
+        match detected {
+            Some(endian) => Ok((endian, false)),
+            None => self
+                .endian
+                .filter(|_| self.framing.damaged_markers)
+                .map(|endian| (endian, true))
+                .ok_or(DecodeError::CorruptedBlockHeader { offset }),
+        }
+    }
+
+    fn read_block(&mut self) -> Result<Block<'a>, DecodeError> {
+        let offset = self.base + self.pos;
+        let rest = &self.data[self.pos..];
+        if rest.len() < BLOCK_HEADER_LEN {
+            return Err(DecodeError::TruncatedBlock {
+                offset,
+                needed: BLOCK_HEADER_LEN,
+                available: rest.len(),
+            });
+        }
+        let (endian, damaged_markers) = self.block_order(rest, offset)?;
+        self.endian = Some(endian);
+        let size = endian.read_i32([rest[12], rest[13], rest[14], rest[15]]);
+        let len =
+            usize::try_from(size).map_err(|_| DecodeError::InvalidBlockSize { size, offset })?;
+
+        let body = &rest[BLOCK_HEADER_LEN..];
+        let mut block = Block {
+            offset,
+            endian,
+            payload: &body[..len.min(body.len())],
+            damaged_markers,
+            bad_trailer: None,
+            cut_off: body.len() < len + 4,
+        };
+        if block.cut_off && !self.framing.cut_off {
+            // Report the part that is missing: the payload, or its trailer
+            let (offset, needed, available) = if body.len() < len {
+                (offset + BLOCK_HEADER_LEN, len, body.len())
+            } else {
+                (block.trailer_offset(), 4, body.len() - len)
+            };
+            return Err(DecodeError::TruncatedBlock {
+                offset,
+                needed,
+                available,
+            });
+        } else if block.cut_off {
+            self.pos = self.data.len();
+            return Ok(block);
+        }
+
+        let trailer = endian.read_i32([body[len], body[len + 1], body[len + 2], body[len + 3]]);
+        if trailer != size && !self.framing.trailer_mismatch {
+            return Err(DecodeError::TrailerMismatch {
+                expected: size,
+                found: trailer,
+                offset: block.trailer_offset(),
+            });
+        }
+        block.bad_trailer = Some(trailer).filter(|&t| t != size);
+        self.pos += BLOCK_HEADER_LEN + len + 4;
+        Ok(block)
+    }
+}
+
+impl<'a> Iterator for Blocks<'a> {
+    type Item = Result<Block<'a>, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.pos >= self.data.len() {
+            return None;
+        }
+        let block = self.read_block();
+        self.failed = block.is_err();
+        Some(block)
+    }
+}
+
+/// Position of `needle` in `haystack`
+#[inline]
+pub fn find_subsequence(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+// ============================================================================
+// Header
+// ============================================================================
+
+/// Byte offsets of the fixed header fields
+///
+/// Counts and post versions are 4 characters wide, the sweep size 10. The
+/// title runs up to the date; the vector descriptions run to the end of
+/// the header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeaderLayout {
+    /// Name for log messages
+    pub name: &'static str,
+    pub num_variables: usize,
+    pub num_probes: usize,
+    pub num_sweeps: usize,
+    /// Post version of 9601 files (`9007` or `9601`)
+    pub post_version1: usize,
+    /// Post version of 2001 files
+    pub post_version2: usize,
+    pub title: usize,
+    pub date: usize,
+    pub date_end: usize,
+    /// Sweep size of 9601 files
+    pub sweep_size1: usize,
+    /// Sweep size of 2001 files
+    pub sweep_size2: usize,
+    pub descriptions: usize,
+}
+
+impl HeaderLayout {
+    /// The layout HSPICE itself writes
+    pub const HSPICE: HeaderLayout = HeaderLayout {
+        name: "hspice",
+        num_variables: NUM_OF_VARIABLES_POSITION,
+        num_probes: NUM_OF_PROBES_POSITION,
+        num_sweeps: NUM_OF_SWEEPS_POSITION,
+        post_version1: POST_START_POSITION1,
+        post_version2: POST_START_POSITION2,
+        title: TITLE_START_POSITION,
+        date: DATE_START_POSITION,
+        date_end: DATE_END_POSITION,
+        sweep_size1: SWEEP_SIZE_POSITION1,
+        sweep_size2: SWEEP_SIZE_POSITION2,
+        descriptions: VECTOR_DESCRIPTION_START_POSITION,
+    };
+
+    /// This layout with every field moved `by` bytes later, as in a header
+    /// with leading padding
+    pub const fn shifted(self, name: &'static str, by: usize) -> Self {
+        HeaderLayout {
+            name,
+            num_variables: self.num_variables + by,
+            num_probes: self.num_probes + by,
+            num_sweeps: self.num_sweeps + by,
+            post_version1: self.post_version1 + by,
+            post_version2: self.post_version2 + by,
+            title: self.title + by,
+            date: self.date + by,
+            date_end: self.date_end + by,
+            sweep_size1: self.sweep_size1 + by,
+            sweep_size2: self.sweep_size2 + by,
+            descriptions: self.descriptions + by,
+        }
+    }
+
+    /// The two post version fields of `header`
+    fn post_fields(&self, header: &[u8]) -> (String, String) {
+        (
+            header_field(header, self.post_version1, self.post_version1 + FIELD_LEN),
+            header_field(header, self.post_version2, self.post_version2 + FIELD_LEN),
+        )
+    }
+
+    /// The integer field of `len` characters at `start` of `header`, 0 if
+    /// it is not a number
+    fn int(header: &[u8], start: usize, len: usize) -> i32 {
+        header_field(header, start, start + len)
+            .parse()
+            .unwrap_or(0)
+    }
+
+    /// Whether `header` looks like it was written with this layout: a
+    /// known post version string, at least one variable, and vector
+    /// descriptions inside the header
+    pub fn matches(&self, header: &[u8]) -> bool {
+        let (post1, post2) = self.post_fields(header);
+        PostVersion::from_header_fields(&post1, &post2).is_some()
+            && Self::int(header, self.num_variables, FIELD_LEN) >= 1
+            && Self::int(header, self.num_probes, FIELD_LEN) >= 0
+            && header.len() > self.descriptions
+    }
+}
+
+impl Default for HeaderLayout {
+    fn default() -> Self {
+        Self::HSPICE
+    }
+}
+
+/// Header fields
+#[derive(Debug, Clone, PartialEq)]
+pub struct Header {
+    pub title: String,
+    pub date: String,
+    pub post_version: PostVersion,
+    /// Variables, the scale included; in AC files all but the scale are
+    /// complex
+    pub num_variables: usize,
+    pub num_probes: usize,
+    pub is_complex: bool,
+    /// HSPICE's type code of each vector, the scale first: 2 for a
+    /// frequency scale; for signals 1 is a node voltage and 8 a current
+    pub types: Vec<i32>,
+    pub scale_name: String,
+    /// Names of the signals after the scale, normalized like
+    /// [`normalize_signal_name`]
+    pub names: Vec<String>,
+    pub sweep_name: Option<String>,
+    /// Number of data tables, 1 if not swept
+    pub sweep_size: usize,
+}
+
+impl Header {
+    /// Parse the header text (the payloads of the header blocks, up to the
+    /// `$&%#` marker) as HSPICE writes it
+    pub fn parse(buf: &[u8]) -> Result<Self, DecodeError> {
+        Self::parse_with(buf, &HeaderLayout::HSPICE, TextEncoding::Utf8, None)
+    }
+
+    /// Parse the header text with the fields where `layout` puts them and
+    /// the text in `encoding`, taking the post version to be
+    /// `post_version` if given
+    pub fn parse_with(
+        buf: &[u8],
+        layout: &HeaderLayout,
+        encoding: TextEncoding,
+        post_version: Option<PostVersion>,
+    ) -> Result<Self, DecodeError> {
+        if buf.len() < layout.descriptions {
+            return Err(DecodeError::HeaderTooShort { len: buf.len() });
+        }
+        let int = |start: usize, len: usize| HeaderLayout::int(buf, start, len);
+
+        let (post1, post2) = layout.post_fields(buf);
+        let post_version = post_version
+            .or_else(|| PostVersion::from_header_fields(&post1, &post2))
+            .ok_or_else(|| DecodeError::UnknownPostVersion {
+                value: if post1.is_empty() {
+                    post2.clone()
+                } else {
+                    post1.clone()
+                },
+            })?;
+
+        let num_sweeps = int(layout.num_sweeps, FIELD_LEN);
+        if !(0..=1).contains(&num_sweeps) {
+            return Err(DecodeError::UnsupportedSweep { count: num_sweeps });
+        }
+        let variables = int(layout.num_variables, FIELD_LEN);
+        let probes = int(layout.num_probes, FIELD_LEN);
+        if variables < 1 || probes < 0 {
+            return Err(DecodeError::InvalidVectorCount { variables, probes });
+        }
+        let num_vectors = (variables + probes) as usize;
+
+        let descriptions = encoding.decode(&buf[layout.descriptions..]);
+        let tokens: Vec<&str> = descriptions.split_whitespace().collect();
+        if tokens.len() < num_vectors + 1 {
+            return Err(DecodeError::MissingVectorNames {
+                expected: num_vectors + 1,
+                found: tokens.len(),
+            });
+        }
+        let types: Vec<i32> = tokens[..num_vectors]
+            .iter()
+            .map(|code| code.parse().unwrap_or(0))
+            .collect();
+        let names = tokens[num_vectors + 1..tokens.len().min(2 * num_vectors)]
+            .iter()
+            .map(|name| normalize_signal_name(name))
+            .collect();
+
+        // The size field follows the version string, not a forced version
+        let sweep_name = tokens
+            .get(2 * num_vectors)
+            .filter(|_| num_sweeps == 1)
+            .map(|name| name.to_string());
+        let sweep_size = match (&sweep_name, post2.as_str()) {
+            (None, _) => 1,
+            (Some(_), POST_STRING21) => int(layout.sweep_size2, SWEEP_SIZE_LEN),
+            (Some(_), _) => int(layout.sweep_size1, SWEEP_SIZE_LEN),
+        }
+        .max(1) as usize;
+
+        let title_end = buf
+            .get(layout.title..layout.date)
+            .unwrap_or_default()
+            .iter()
+            .rposition(|&c| c != b' ')
+            .map_or(layout.title, |i| layout.title + i + 1);
+
+        Ok(Self {
+            title: header_text(buf, layout.title, title_end, encoding),
+            date: header_text(buf, layout.date, layout.date_end, encoding),
+            post_version,
+            num_variables: variables as usize,
+            num_probes: probes as usize,
+            is_complex: types[0] == FREQUENCY_TYPE,
+            types,
+            scale_name: tokens[num_vectors].to_string(),
+            names,
+            sweep_name,
+            sweep_size,
+        })
+    }
+
+    /// Vectors per row, the scale included
+    pub fn num_vectors(&self) -> usize {
+        self.num_variables + self.num_probes
+    }
+
+    /// Complex signals, which follow the scale: every variable but the
+    /// scale in AC files, none otherwise
+    pub fn num_complex(&self) -> usize {
+        if self.is_complex {
+            self.num_variables - 1
+        } else {
+            0
+        }
+    }
+
+    /// Values per row: one per vector, two per complex signal
+    pub fn num_columns(&self) -> usize {
+        self.num_vectors() + self.num_complex()
+    }
+}
+
+/// Text of `buf[start..end]` up to the first NUL, trimmed; empty if out of
+/// range
+pub fn header_field(buf: &[u8], start: usize, end: usize) -> String {
+    header_text(buf, start, end, TextEncoding::Utf8)
+}
+
+/// Like [`header_field`], decoding the text as `encoding`
+fn header_text(buf: &[u8], start: usize, end: usize, encoding: TextEncoding) -> String {
+    let Some(slice) = buf.get(start..end) else {
+        return String::new();
+    };
+    let end = slice.iter().position(|&c| c == 0).unwrap_or(slice.len());
+    encoding.decode(&slice[..end]).trim().to_string()
+}
+
+/// Normalize a header signal name the way HSPICE tools present it
+/// (lowercase, node voltages without the `v(...)` wrapper)
+pub fn normalize_signal_name(name: &str) -> String {
+    let name = name.to_lowercase();
+    match name.strip_prefix("v(") {
+        Some(node) => node.trim_end_matches(')').to_string(),
+        None => name,
+    }
+}
+
+/// Collect the header text from the blocks at the start of `blocks`, up
+/// to the `$&%#` marker
+///
+/// `on_block` sees each block and the length of the text so far, and can
+/// stop the read with an error.
+pub fn read_header_text<E: From<DecodeError>>(
+    blocks: &mut Blocks<'_>,
+    mut on_block: impl FnMut(&Block<'_>, usize) -> Result<(), E>,
+) -> Result<Vec<u8>, E> {
+    let mut buf = Vec::new();
+    loop {
+        let end = blocks.base + blocks.data.len();
+        let block = blocks.next().unwrap_or(Err(DecodeError::TruncatedBlock {
+            offset: end,
+            needed: BLOCK_HEADER_LEN,
+            available: 0,
+        }))?;
+        buf.extend_from_slice(block.payload);
+        on_block(&block, buf.len())?;
+        if let Some(end) = find_subsequence(&buf, HEADER_END) {
+            buf.truncate(end);
+            return Ok(buf);
+        }
+    }
+}
+
+/// Parse the header blocks at the start of `data`
+///
+/// Returns the header and the offset of the first data block.
+pub fn parse_header(data: &[u8]) -> Result<(Header, usize), DecodeError> {
+    let mut blocks = Blocks::new(data, 0);
+    let text = read_header_text(&mut blocks, |_, _| Ok::<_, DecodeError>(()))?;
+    Ok((Header::parse(&text)?, blocks.position()))
+}
+
+// ============================================================================
+// Data
+// ============================================================================
+
+/// Values of one data table, row-major
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    /// Sweep parameter value, `None` if the file is not swept
+    pub sweep_value: Option<f64>,
+    /// `Header::num_columns()` values per row: the scale, then the complex
+    /// signals as re/im pairs, then the real signals
+    pub values: Vec<f64>,
+}
+
+impl Table {
+    /// Number of complete rows
+    pub fn num_rows(&self, header: &Header) -> usize {
+        self.values.len() / header.num_columns().max(1)
+    }
+}
+
+/// Decode the header and every data table of HSPICE binary data
+///
+/// Strict: a damaged block or a table without its end marker is an error.
+pub fn decode(data: &[u8]) -> Result<(Header, Vec<Table>), DecodeError> {
+    let (header, start) = parse_header(data)?;
+    let version = header.post_version;
+    let columns = header.num_columns().max(1);
+    let mut blocks = Blocks::new(&data[start..], start);
+
+    let mut tables = Vec::with_capacity(header.sweep_size.min(data.len()));
+    for table in 0..header.sweep_size {
+        let mut values = Vec::new();
+        let mut found_end = false;
+        for block in blocks.by_ref() {
+            let block = block?;
+            decode_values(block.payload, version, block.endian, &mut values);
+            if values.last().is_some_and(|&v| is_end_marker(v, version)) {
+                found_end = true;
+                break;
+            }
+        }
+        if !found_end {
+            return Err(DecodeError::MissingSweepTable {
+                index: table + 1,
+                total: header.sweep_size,
+                offset: start + blocks.position(),
+            });
+        }
+
+        values.pop();
+        let sweep_value = match header.sweep_name {
+            Some(_) if !values.is_empty() => Some(values.remove(0)),
+            _ => None,
+        };
+        values.truncate(values.len() / columns * columns);
+        tables.push(Table {
+            sweep_value,
+            values,
+        });
+    }
+    Ok((header, tables))
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::SyntheticWaveform;
+    use crate::{Endian as CrateEndian, PostVersion as CratePostVersion, VectorData};
+
+    #[test]
+    fn test_decode_matches_reader() {
+        let cases = [
+            SyntheticWaveform::new()
+                .signals(3)
+                .points(500)
+                .block_size(64),
+            SyntheticWaveform::new()
+                .signals(2)
+                .points(40)
+                .version(CratePostVersion::V2001)
+                .endian(CrateEndian::Big)
+                .sweep("temp", alloc::vec![25.0, 85.0]),
+            SyntheticWaveform::new().signals(2).points(30).complex(true),
+        ];
+        for synth in cases {
+            let expected = synth.expected();
+            let (header, tables) = decode(&synth.to_tr0_bytes()).unwrap();
+            assert_eq!(header.scale_name, expected.variables[0].name);
+            let names: Vec<&str> = expected.variables[1..]
+                .iter()
+                .map(|v| v.name.as_str())
+                .collect();
+            assert_eq!(header.names, names);
+            assert_eq!(tables.len(), expected.tables.len());
+
+            for (table, expected) in tables.iter().zip(&expected.tables) {
+                assert_eq!(table.sweep_value, expected.sweep_value);
+                assert_eq!(table.num_rows(&header), expected.len());
+                let row = 7;
+                let mut column = 0;
+                for vector in &expected.vectors {
+                    let value = &table.values[row * header.num_columns() + column..];
+                    match vector {
+                        VectorData::Complex(v) => {
+                            assert_eq!((value[0], value[1]), (v[row].re, v[row].im));
+                            column += 2;
+                        }
+                        v => {
+                            assert_eq!(value[0], v.real_at(row).unwrap());
+                            column += 1;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let data = SyntheticWaveform::new()
+            .signals(1)
+            .points(100)
+            .to_tr0_bytes();
+        assert!(matches!(
+            decode(&data[..data.len() - 10]),
+            Err(DecodeError::TruncatedBlock { .. })
+        ));
+
+        let mut damaged = data.clone();
+        damaged[0] = 7;
+        assert_eq!(
+            decode(&damaged),
+            Err(DecodeError::CorruptedBlockHeader { offset: 0 })
+        );
+        let error = crate::WaveformError::from(DecodeError::CorruptedBlockHeader { offset: 0 });
+        assert_eq!(error.offset(), Some(0));
+    }
+
+    #[test]
+    fn test_normalize_signal_name() {
+        assert_eq!(normalize_signal_name("V(OUT)"), "out");
+        assert_eq!(normalize_signal_name("I(VDD"), "i(vdd");
+    }
+}
@@ -0,0 +1,516 @@
+//! Arithmetic on signals: `db(v(out)/v(in))`, `i(vdd)*1e3`, ...
+//!
+//! An [`Expr`] is parsed once and evaluated against each table of a
+//! result. Signals are referred to by name, including the `v(...)` and
+//! `i(...)` forms; HSPICE stores node voltages bare, so `v(out)` also finds
+//! the node `out`. Complex signals stay complex through `+ - * / ^` until a
+//! function such as `db` or `mag` makes them real.
+//!
+//! | Function | Result |
+//! |----------|--------|
+//! | `mag(x)`, `abs(x)` | Magnitude |
+//! | `db(x)` | `20 * log10(mag(x))` |
+//! | `ph(x)`, `phase(x)` | Phase in degrees |
+//! | `re(x)`, `real(x)`, `im(x)`, `imag(x)` | Real and imaginary parts |
+//! | `sqrt(x)`, `exp(x)`, `ln(x)`, `log10(x)` | Point by point |
+//!
+//! ```rust,no_run
+//! let result = hspice_core::read("amp.ac0").unwrap();
+//! // One vector per sweep table
+//! let gain = result.eval("db(v(out)/v(in))").unwrap();
+//! ```
+
+use crate::types::{DataTable, Result, Variable, VectorData, WaveformError, WaveformResult};
+use num_complex::Complex64;
+
+/// A parsed expression over signals
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expr {
+    text: String,
+    root: Node,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Number(f64),
+    Signal(String),
+    Neg(Box<Node>),
+    Binary(Op, Box<Node>, Box<Node>),
+    Call(Func, Box<Node>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Func {
+    Mag,
+    Db,
+    Phase,
+    Re,
+    Im,
+    Sqrt,
+    Exp,
+    Ln,
+    Log10,
+}
+
+impl Func {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "mag" | "abs" => Some(Func::Mag),
+            "db" => Some(Func::Db),
+            "ph" | "phase" => Some(Func::Phase),
+            "re" | "real" => Some(Func::Re),
+            "im" | "imag" => Some(Func::Im),
+            "sqrt" => Some(Func::Sqrt),
+            "exp" => Some(Func::Exp),
+            "ln" => Some(Func::Ln),
+            "log10" => Some(Func::Log10),
+            _ => None,
+        }
+    }
+}
+
+impl Expr {
+    /// Parse `text`; signal names are looked up only when evaluated
+    pub fn parse(text: &str) -> Result<Expr> {
+        let mut parser = Parser { text, pos: 0 };
+        let root = parser.expr()?;
+        parser.skip_space();
+        if parser.pos < text.len() {
+            return Err(parser.error("unexpected input"));
+        }
+        Ok(Expr {
+            text: text.to_string(),
+            root,
+        })
+    }
+
+    /// The text this was parsed from
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Evaluate on one table of a result with `variables`
+    ///
+    /// The output is as long as the table, complex if any complex value
+    /// reaches it.
+    pub fn eval_table(&self, variables: &[Variable], table: &DataTable) -> Result<VectorData> {
+        let len = table.len();
+        Ok(match eval(&self.root, variables, table)? {
+            Value::Scalar(x) => VectorData::Real(vec![x; len].into()),
+            Value::Real(v) => VectorData::Real(v.into()),
+            Value::Complex(v) => VectorData::Complex(v.into()),
+        })
+    }
+}
+
+impl WaveformResult {
+    /// Evaluate `expr` (see [`crate::expr`]) on every table
+    pub fn eval(&self, expr: &str) -> Result<Vec<VectorData>> {
+        let expr = Expr::parse(expr)?;
+        self.tables
+            .iter()
+            .map(|table| expr.eval_table(&self.variables, table))
+            .collect()
+    }
+}
+
+// ============================================================================
+// Parsing
+// ============================================================================
+
+/// Characters of signal names and numbers besides letters and digits
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "_.:#$!@[]".contains(c)
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, what: &str) -> WaveformError {
+        WaveformError::ParseError(format!(
+            "expression {:?}: {} at offset {}",
+            self.text, what, self.pos
+        ))
+    }
+
+    fn skip_space(&mut self) {
+        let rest = &self.text[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_space();
+        self.text[self.pos..].chars().next()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// `term (('+' | '-') term)*`
+    fn expr(&mut self) -> Result<Node> {
+        let mut node = self.term()?;
+        loop {
+            let op = match self.peek() {
+                Some('+') => Op::Add,
+                Some('-') => Op::Sub,
+                _ => return Ok(node),
+            };
+            self.pos += 1;
+            node = Node::Binary(op, Box::new(node), Box::new(self.term()?));
+        }
+    }
+
+    /// `unary (('*' | '/') unary)*`
+    fn term(&mut self) -> Result<Node> {
+        let mut node = self.unary()?;
+        loop {
+            let op = match self.peek() {
+                Some('*') => Op::Mul,
+                Some('/') => Op::Div,
+                _ => return Ok(node),
+            };
+            self.pos += 1;
+            node = Node::Binary(op, Box::new(node), Box::new(self.unary()?));
+        }
+    }
+
+    /// `'-' unary | primary ('^' unary)?`
+    fn unary(&mut self) -> Result<Node> {
+        if self.eat('-') {
+            return Ok(Node::Neg(Box::new(self.unary()?)));
+        }
+        let base = self.primary()?;
+        if self.eat('^') {
+            return Ok(Node::Binary(
+                Op::Pow,
+                Box::new(base),
+                Box::new(self.unary()?),
+            ));
+        }
+        Ok(base)
+    }
+
+    /// A number, a parenthesized expression, a function call or a signal
+    fn primary(&mut self) -> Result<Node> {
+        if self.eat('(') {
+            let node = self.expr()?;
+            if !self.eat(')') {
+                return Err(self.error("missing ')'"));
+            }
+            return Ok(node);
+        }
+        match self.peek() {
+            Some(c) if c.is_ascii_digit() || c == '.' => self.number(),
+            Some(c) if is_name_char(c) => self.name(),
+            Some(_) => Err(self.error("expected a number, signal or '('")),
+            None => Err(self.error("unexpected end")),
+        }
+    }
+
+    fn number(&mut self) -> Result<Node> {
+        let start = self.pos;
+        let bytes = self.text.as_bytes();
+        let mut end = start;
+        while end < bytes.len() {
+            let c = bytes[end] as char;
+            let exponent_sign =
+                end > start && (c == '+' || c == '-') && matches!(bytes[end - 1], b'e' | b'E');
+            if c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E' || exponent_sign {
+                end += 1;
+            } else {
+                break;
+            }
+        }
+        let value = self.text[start..end]
+            .parse()
+            .map_err(|_| self.error("invalid number"))?;
+        self.pos = end;
+        Ok(Node::Number(value))
+    }
+
+    /// A function call, or a signal name with any `(...)` part kept whole
+    fn name(&mut self) -> Result<Node> {
+        let start = self.pos;
+        let rest = &self.text[start..];
+        let len = rest.find(|c| !is_name_char(c)).unwrap_or(rest.len());
+        let name = &rest[..len];
+        self.pos += len;
+
+        if !self.text[self.pos..].starts_with('(') {
+            return Ok(Node::Signal(name.to_string()));
+        }
+        if let Some(func) = Func::from_name(name) {
+            self.pos += 1;
+            let arg = self.expr()?;
+            if !self.eat(')') {
+                return Err(self.error("missing ')' after function argument"));
+            }
+            return Ok(Node::Call(func, Box::new(arg)));
+        }
+
+        // `v(out)`, `i(x1.m2)`: the whole reference is the signal name
+        let mut depth = 0;
+        for (i, c) in self.text[self.pos..].char_indices() {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                _ => {}
+            }
+            if depth == 0 {
+                self.pos += i + 1;
+                return Ok(Node::Signal(self.text[start..self.pos].to_string()));
+            }
+        }
+        Err(self.error("missing ')' in signal name"))
+    }
+}
+
+// ============================================================================
+// Evaluation
+// ============================================================================
+
+enum Value {
+    Scalar(f64),
+    Real(Vec<f64>),
+    Complex(Vec<Complex64>),
+}
+
+impl Value {
+    fn map_real(self, f: impl Fn(f64) -> f64) -> Value {
+        match self {
+            Value::Scalar(x) => Value::Scalar(f(x)),
+            Value::Real(v) => Value::Real(v.into_iter().map(f).collect()),
+            Value::Complex(v) => Value::Real(v.into_iter().map(|z| f(z.re)).collect()),
+        }
+    }
+
+    /// `real` on real values, `complex` on complex ones
+    fn map(self, real: impl Fn(f64) -> f64, complex: impl Fn(Complex64) -> Complex64) -> Value {
+        match self {
+            Value::Complex(v) => Value::Complex(v.into_iter().map(complex).collect()),
+            other => other.map_real(real),
+        }
+    }
+
+    /// A real function of complex values, e.g. the magnitude
+    fn map_to_real(self, real: impl Fn(f64) -> f64, complex: impl Fn(Complex64) -> f64) -> Value {
+        match self {
+            Value::Complex(v) => Value::Real(v.into_iter().map(complex).collect()),
+            other => other.map_real(real),
+        }
+    }
+
+    fn len(&self) -> Option<usize> {
+        match self {
+            Value::Scalar(_) => None,
+            Value::Real(v) => Some(v.len()),
+            Value::Complex(v) => Some(v.len()),
+        }
+    }
+
+    fn real_at(&self, i: usize) -> f64 {
+        match self {
+            Value::Scalar(x) => *x,
+            Value::Real(v) => v[i],
+            Value::Complex(v) => v[i].re,
+        }
+    }
+
+    fn complex_at(&self, i: usize) -> Complex64 {
+        match self {
+            Value::Complex(v) => v[i],
+            other => Complex64::new(other.real_at(i), 0.0),
+        }
+    }
+}
+
+fn eval(node: &Node, variables: &[Variable], table: &DataTable) -> Result<Value> {
+    Ok(match node {
+        Node::Number(x) => Value::Scalar(*x),
+        Node::Signal(name) => signal(name, variables, table)?,
+        Node::Neg(arg) => eval(arg, variables, table)?.map(|x| -x, |z| -z),
+        Node::Binary(op, a, b) => {
+            binary(*op, eval(a, variables, table)?, eval(b, variables, table)?)?
+        }
+        Node::Call(func, arg) => {
+            let arg = eval(arg, variables, table)?;
+            match func {
+                Func::Mag => arg.map_to_real(f64::abs, |z| z.norm()),
+                Func::Db => {
+                    arg.map_to_real(|x| 20.0 * x.abs().log10(), |z| 20.0 * z.norm().log10())
+                }
+                Func::Phase => arg.map_to_real(
+                    |x| if x < 0.0 { 180.0 } else { 0.0 },
+                    |z| z.arg().to_degrees(),
+                ),
+                Func::Re => arg.map_to_real(|x| x, |z| z.re),
+                Func::Im => arg.map_to_real(|_| 0.0, |z| z.im),
+                Func::Sqrt => arg.map(f64::sqrt, |z| z.sqrt()),
+                Func::Exp => arg.map(f64::exp, |z| z.exp()),
+                Func::Ln => arg.map(f64::ln, |z| z.ln()),
+                Func::Log10 => arg.map(f64::log10, |z| z.log10()),
+            }
+        }
+    })
+}
+
+/// Look `name` up as given, then for `v(node)` as the bare node name,
+/// then ignoring case
+fn signal(name: &str, variables: &[Variable], table: &DataTable) -> Result<Value> {
+    let bare = name
+        .strip_prefix("v(")
+        .or_else(|| name.strip_prefix("V("))
+        .and_then(|n| n.strip_suffix(')'));
+    let index = variables
+        .iter()
+        .position(|v| v.name == name)
+        .or_else(|| bare.and_then(|b| variables.iter().position(|v| v.name == b)))
+        .or_else(|| {
+            variables.iter().position(|v| {
+                v.name.eq_ignore_ascii_case(name)
+                    || bare.is_some_and(|b| v.name.eq_ignore_ascii_case(b))
+            })
+        })
+        .ok_or_else(|| WaveformError::ParseError(format!("signal {} not found", name)))?;
+
+    Ok(match &table.vectors[index] {
+        VectorData::Complex(v) => Value::Complex(v.to_vec()),
+        other => Value::Real(other.to_real().unwrap_or_default().into_owned()),
+    })
+}
+
+fn binary(op: Op, a: Value, b: Value) -> Result<Value> {
+    let len = match (a.len(), b.len()) {
+        (Some(x), Some(y)) if x != y => {
+            return Err(WaveformError::ParseError(format!(
+                "cannot combine vectors of {} and {} points",
+                x, y
+            )))
+        }
+        (x, y) => x.or(y),
+    };
+    let real = |x: f64, y: f64| match op {
+        Op::Add => x + y,
+        Op::Sub => x - y,
+        Op::Mul => x * y,
+        Op::Div => x / y,
+        Op::Pow => x.powf(y),
+    };
+    let complex = |x: Complex64, y: Complex64| match op {
+        Op::Add => x + y,
+        Op::Sub => x - y,
+        Op::Mul => x * y,
+        Op::Div => x / y,
+        Op::Pow => x.powc(y),
+    };
+
+    let Some(len) = len else {
+        return Ok(Value::Scalar(real(a.real_at(0), b.real_at(0))));
+    };
+    Ok(match (&a, &b) {
+        (Value::Complex(_), _) | (_, Value::Complex(_)) => Value::Complex(
+            (0..len)
+                .map(|i| complex(a.complex_at(i), b.complex_at(i)))
+                .collect(),
+        ),
+        _ => Value::Real((0..len).map(|i| real(a.real_at(i), b.real_at(i))).collect()),
+    })
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WaveformResultBuilder;
+
+    fn result() -> WaveformResult {
+        let mut builder = WaveformResultBuilder::new("expr");
+        builder.add_variable(Variable::new("HERTZ")).unwrap();
+        builder.add_variable(Variable::new("out")).unwrap();
+        builder
+            .add_complex_variable(Variable::new("v(in)"))
+            .unwrap();
+        builder
+            .add_table(
+                None,
+                vec![
+                    VectorData::Real(vec![1.0, 2.0].into()),
+                    VectorData::Real(vec![2.0, -4.0].into()),
+                    VectorData::Complex(
+                        vec![Complex64::new(0.0, 1.0), Complex64::new(2.0, 0.0)].into(),
+                    ),
+                ],
+            )
+            .unwrap();
+        builder.build().unwrap()
+    }
+
+    fn real(vector: &VectorData) -> Vec<f64> {
+        vector.to_real().unwrap().into_owned()
+    }
+
+    #[test]
+    fn test_precedence_and_numbers() {
+        let r = result();
+        assert_eq!(
+            real(&r.eval("1 + 2*out^2 - -1e1").unwrap()[0]),
+            [19.0, 43.0]
+        );
+        assert_eq!(real(&r.eval("(1 + 2) * 2.5e-1").unwrap()[0]), [0.75, 0.75]);
+        assert_eq!(real(&r.eval("2^-1").unwrap()[0]), [0.5, 0.5]);
+    }
+
+    #[test]
+    fn test_signal_names_and_functions() {
+        let r = result();
+        // `v(out)` finds the bare node, case ignored as a last resort
+        assert_eq!(real(&r.eval("mag(V(OUT))").unwrap()[0]), [2.0, 4.0]);
+        assert_eq!(
+            real(&r.eval("db(out / 2)").unwrap()[0]),
+            [0.0, 20.0 * 2f64.log10()]
+        );
+
+        let ratio = &r.eval("v(out) / v(in)").unwrap()[0];
+        assert!(ratio.is_complex());
+        assert_eq!(
+            real(&r.eval("db(v(out)/v(in))").unwrap()[0]),
+            [20.0 * 2f64.log10(), 20.0 * 2f64.log10()]
+        );
+        assert_eq!(real(&r.eval("ph(v(in))").unwrap()[0]), [90.0, 0.0]);
+        assert_eq!(
+            real(&r.eval("im(v(in)) + re(v(in))").unwrap()[0]),
+            [1.0, 2.0]
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let r = result();
+        assert!(r.eval("v(missing)").is_err());
+        assert!(r.eval("1 +").is_err());
+        assert!(r.eval("mag(out").is_err());
+        assert!(r.eval("out )").is_err());
+        assert!(Expr::parse("v(out").is_err());
+    }
+}
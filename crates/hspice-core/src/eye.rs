@@ -0,0 +1,512 @@
+//! Eye diagrams of serial data signals
+//!
+//! The signal is folded modulo the unit interval (UI) into a 2D histogram
+//! of phase against voltage, sampled by linear interpolation on an even
+//! phase grid so uneven transient time steps do not bias it. The eye is
+//! centred on the mean crossing phase and measured for height, width and
+//! crossing jitter.
+//!
+//! ```rust,no_run
+//! use hspice_core::eye::{eye, EyeOptions};
+//!
+//! let result = hspice_core::read("serdes.tr0").unwrap();
+//! let time = result.get("TIME").unwrap().to_real().unwrap();
+//! let rx = result.get("rx_p").unwrap().to_real().unwrap();
+//!
+//! let diagram = eye(&rx, &time, &EyeOptions::new(100e-12)).unwrap();
+//! println!("height {} V, width {} s", diagram.height, diagram.width);
+//! ```
+//!
+//! [`EyeBuilder`] takes the signal a piece at a time, so a stream can be
+//! folded chunk by chunk without reading the whole file:
+//!
+//! ```rust,no_run
+//! use hspice_core::eye::{EyeBuilder, EyeOptions};
+//!
+//! let options = EyeOptions::new(100e-12).range(-0.6, 0.6).recover_clock(0.05);
+//! let mut builder = EyeBuilder::new(&options).unwrap();
+//! for chunk in hspice_core::read_stream_signals("serdes.tr0", &["rx_p"], 100_000).unwrap() {
+//!     let chunk = chunk.unwrap();
+//!     let time = chunk.data["TIME"].to_real().unwrap();
+//!     builder.push(&chunk.data["rx_p"].to_real().unwrap(), &time);
+//! }
+//! let diagram = builder.finish().unwrap();
+//! ```
+
+use crate::types::Result;
+use std::f64::consts::TAU;
+use std::io::Write;
+
+/// Margin added above and below the signal when the range is not given
+const RANGE_MARGIN: f64 = 0.1;
+
+/// How an eye diagram is folded
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EyeOptions {
+    ui: f64,
+    threshold: Option<f64>,
+    range: Option<(f64, f64)>,
+    time_bins: usize,
+    voltage_bins: usize,
+    clock_gain: Option<f64>,
+}
+
+impl EyeOptions {
+    /// Fold by a fixed unit interval of `ui` (in scale units), with 100 x
+    /// 100 bins
+    pub fn new(ui: f64) -> Self {
+        Self {
+            ui,
+            threshold: None,
+            range: None,
+            time_bins: 100,
+            voltage_bins: 100,
+            clock_gain: None,
+        }
+    }
+
+    /// Decision threshold for crossings and eye height (default: middle of
+    /// the voltage range)
+    pub fn threshold(mut self, threshold: f64) -> Self {
+        self.threshold = Some(threshold);
+        self
+    }
+
+    /// Voltage range of the histogram (default for [`eye`]: the signal's
+    /// range plus 10% on each side; required by [`EyeBuilder`])
+    pub fn range(mut self, low: f64, high: f64) -> Self {
+        self.range = Some((low, high));
+        self
+    }
+
+    /// Histogram size: bins across one UI and bins across the voltage range
+    pub fn bins(mut self, time_bins: usize, voltage_bins: usize) -> Self {
+        self.time_bins = time_bins.max(2);
+        self.voltage_bins = voltage_bins.max(1);
+        self
+    }
+
+    /// Fold by a clock recovered from the data crossings instead of a fixed
+    /// one
+    ///
+    /// The clock starts at the first crossing and moves by `gain` times the
+    /// timing error of every crossing (a first-order loop, 0 < `gain` <= 1),
+    /// so slow drift and wander are tracked out of the eye. Data before the
+    /// first crossing is skipped.
+    pub fn recover_clock(mut self, gain: f64) -> Self {
+        self.clock_gain = Some(gain.clamp(0.0, 1.0));
+        self
+    }
+}
+
+/// An eye diagram and its measurements
+#[derive(Debug, Clone, PartialEq)]
+pub struct EyeDiagram {
+    /// Unit interval
+    pub ui: f64,
+    /// Voltage range covered by the histogram
+    pub voltage_range: (f64, f64),
+    /// Decision threshold
+    pub threshold: f64,
+    /// Bins across one UI
+    pub time_bins: usize,
+    /// Bins across `voltage_range`
+    pub voltage_bins: usize,
+    /// Samples per cell: `voltage_bins` rows of `time_bins`, lowest voltage
+    /// first. Column 0 starts at the eye edge, so the eye opens in the
+    /// middle columns
+    pub counts: Vec<u64>,
+    /// Eye edge (mean crossing phase) relative to the clock, modulo `ui`
+    pub edge_offset: f64,
+    /// Vertical opening at the eye centre: `(mean1 - 3 std1) - (mean0 + 3 std0)`
+    /// of the samples above and below the threshold, or 0 if closed
+    pub height: f64,
+    /// Horizontal opening: `ui` minus the peak-to-peak jitter, or 0 if closed
+    pub width: f64,
+    /// RMS deviation of the crossings from the eye edge
+    pub jitter_rms: f64,
+    /// Peak-to-peak spread of the crossings
+    pub jitter_pp: f64,
+    /// Number of threshold crossings folded
+    pub crossings: usize,
+}
+
+impl EyeDiagram {
+    /// Samples in one cell
+    pub fn count(&self, time_bin: usize, voltage_bin: usize) -> u64 {
+        self.counts[voltage_bin * self.time_bins + time_bin]
+    }
+
+    /// Write the histogram as CSV: `phase,voltage,count` with the phase in
+    /// UI and the voltage at each bin centre
+    pub fn write_csv<W: Write>(&self, writer: &mut W) -> Result<()> {
+        writeln!(writer, "phase,voltage,count")?;
+        let (low, high) = self.voltage_range;
+        let step = (high - low) / self.voltage_bins as f64;
+        for v in 0..self.voltage_bins {
+            let voltage = low + (v as f64 + 0.5) * step;
+            for t in 0..self.time_bins {
+                let phase = (t as f64 + 0.5) / self.time_bins as f64;
+                writeln!(writer, "{},{},{}", phase, voltage, self.count(t, v))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Eye diagram of `signal` over `scale`
+///
+/// Returns `None` if the UI is not positive, the slices differ in length,
+/// or fewer than two crossings are found.
+pub fn eye(signal: &[f64], scale: &[f64], options: &EyeOptions) -> Option<EyeDiagram> {
+    if signal.len() != scale.len() {
+        return None;
+    }
+    let mut options = *options;
+    if options.range.is_none() {
+        let (min, max) = signal
+            .iter()
+            .filter(|v| !v.is_nan())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| {
+                (lo.min(v), hi.max(v))
+            });
+        let margin = (max - min) * RANGE_MARGIN;
+        options.range = Some((min - margin, max + margin));
+    }
+    let mut builder = EyeBuilder::new(&options)?;
+    builder.push(signal, scale);
+    builder.finish()
+}
+
+// ============================================================================
+// Incremental Folding
+// ============================================================================
+
+/// Count, sum and sum of squares of the samples on one side of the threshold
+#[derive(Debug, Clone, Copy, Default)]
+struct Level {
+    count: f64,
+    sum: f64,
+    sum_squares: f64,
+}
+
+impl Level {
+    fn add(&mut self, v: f64) {
+        self.count += 1.0;
+        self.sum += v;
+        self.sum_squares += v * v;
+    }
+
+    /// Mean and population standard deviation
+    fn mean_std(&self) -> Option<(f64, f64)> {
+        (self.count > 0.0).then(|| {
+            let mean = self.sum / self.count;
+            let var = (self.sum_squares / self.count - mean * mean).max(0.0);
+            (mean, var.sqrt())
+        })
+    }
+}
+
+/// Folds a signal into an eye diagram a piece at a time
+///
+/// Pieces must be pushed in scale order; the last point of each piece is
+/// joined to the first of the next.
+#[derive(Debug, Clone)]
+pub struct EyeBuilder {
+    ui: f64,
+    threshold: f64,
+    range: (f64, f64),
+    time_bins: usize,
+    voltage_bins: usize,
+    clock_gain: Option<f64>,
+    /// Time of a clock edge (None until the first crossing of a recovered
+    /// clock)
+    clock: Option<f64>,
+    /// Next time on the sampling grid
+    next_sample: f64,
+    previous: Option<(f64, f64)>,
+    counts: Vec<u64>,
+    /// Samples below and above the threshold in each time bin
+    levels: Vec<[Level; 2]>,
+    /// Crossing phases relative to the clock, in UI within [0, 1)
+    phases: Vec<f64>,
+}
+
+impl EyeBuilder {
+    /// Returns `None` if the UI is not positive or the voltage range is not
+    /// set or empty
+    pub fn new(options: &EyeOptions) -> Option<Self> {
+        let (low, high) = options.range?;
+        if options.ui.is_nan() || options.ui <= 0.0 || low.is_nan() || high.is_nan() || high <= low
+        {
+            return None;
+        }
+        let fixed = options.clock_gain.is_none();
+        Some(Self {
+            ui: options.ui,
+            threshold: options.threshold.unwrap_or((low + high) / 2.0),
+            range: (low, high),
+            time_bins: options.time_bins,
+            voltage_bins: options.voltage_bins,
+            clock_gain: options.clock_gain,
+            clock: fixed.then_some(0.0),
+            next_sample: f64::NEG_INFINITY,
+            previous: None,
+            counts: vec![0; options.time_bins * options.voltage_bins],
+            levels: vec![[Level::default(); 2]; options.time_bins],
+            phases: Vec::new(),
+        })
+    }
+
+    /// Fold the next piece of the signal
+    pub fn push(&mut self, signal: &[f64], scale: &[f64]) {
+        for (&t, &v) in scale.iter().zip(signal) {
+            if t.is_nan() || v.is_nan() {
+                continue;
+            }
+            if let Some((t0, v0)) = self.previous {
+                if t > t0 {
+                    self.segment(t0, v0, t, v);
+                } else {
+                    continue;
+                }
+            }
+            self.previous = Some((t, v));
+        }
+    }
+
+    /// Sample the grid points in `(t0, t1]` and handle a crossing
+    fn segment(&mut self, t0: f64, v0: f64, t1: f64, v1: f64) {
+        if let Some(clock) = self.clock {
+            let step = self.ui / self.time_bins as f64;
+            if self.next_sample <= t0 {
+                // First segment, or after the clock was set: align the grid
+                // to bin centres
+                let k = ((t0 - clock) / step - 0.5).floor() + 1.0;
+                self.next_sample = clock + (k + 0.5) * step;
+            }
+            while self.next_sample <= t1 {
+                let t = self.next_sample;
+                let v = v0 + (v1 - v0) * (t - t0) / (t1 - t0);
+                let phase = ((t - clock) / self.ui).rem_euclid(1.0);
+                self.add_sample(phase, v);
+                self.next_sample += step;
+            }
+        }
+
+        if (v0 < self.threshold) != (v1 < self.threshold) {
+            let t = t0 + (self.threshold - v0) / (v1 - v0) * (t1 - t0);
+            self.crossing(t);
+        }
+    }
+
+    fn add_sample(&mut self, phase: f64, v: f64) {
+        let time_bin = ((phase * self.time_bins as f64) as usize).min(self.time_bins - 1);
+        let (low, high) = self.range;
+        if (low..high).contains(&v) {
+            let voltage_bin = ((v - low) / (high - low) * self.voltage_bins as f64) as usize;
+            self.counts[voltage_bin.min(self.voltage_bins - 1) * self.time_bins + time_bin] += 1;
+        }
+        self.levels[time_bin][usize::from(v >= self.threshold)].add(v);
+    }
+
+    fn crossing(&mut self, t: f64) {
+        let Some(clock) = self.clock else {
+            // Recovered clock: lock to the first crossing
+            self.clock = Some(t);
+            self.phases.push(0.0);
+            return;
+        };
+        let position = (t - clock) / self.ui;
+        self.phases.push(position.rem_euclid(1.0));
+        if let Some(gain) = self.clock_gain {
+            let edges = position.round();
+            self.clock = Some(clock + (edges + gain * (position - edges)) * self.ui);
+        }
+    }
+
+    /// The eye diagram, or `None` if fewer than two crossings were found
+    pub fn finish(self) -> Option<EyeDiagram> {
+        if self.phases.len() < 2 {
+            return None;
+        }
+
+        // Eye edge: circular mean of the crossing phases
+        let (sin, cos) = self.phases.iter().fold((0.0, 0.0), |(s, c), &p| {
+            let angle = TAU * p;
+            (s + angle.sin(), c + angle.cos())
+        });
+        let edge = (sin.atan2(cos) / TAU).rem_euclid(1.0);
+
+        // Crossing offsets from the edge, in UI within [-0.5, 0.5)
+        let offsets: Vec<f64> = self
+            .phases
+            .iter()
+            .map(|&p| (p - edge + 0.5).rem_euclid(1.0) - 0.5)
+            .collect();
+        let count = offsets.len() as f64;
+        let mean = offsets.iter().sum::<f64>() / count;
+        let rms = (offsets.iter().map(|o| (o - mean).powi(2)).sum::<f64>() / count).sqrt();
+        let (min, max) = offsets
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &o| {
+                (lo.min(o), hi.max(o))
+            });
+        let jitter_pp = (max - min) * self.ui;
+
+        // Rotate the columns so that column 0 starts at the edge
+        let t_bins = self.time_bins;
+        let shift = (edge * t_bins as f64).round() as usize % t_bins;
+        let mut counts = vec![0; self.counts.len()];
+        for (row, rotated) in self.counts.chunks(t_bins).zip(counts.chunks_mut(t_bins)) {
+            for (j, cell) in rotated.iter_mut().enumerate() {
+                *cell = row[(j + shift) % t_bins];
+            }
+        }
+
+        let [below, above] = self.levels[(t_bins / 2 + shift) % t_bins];
+        let height = match (below.mean_std(), above.mean_std()) {
+            (Some((m0, s0)), Some((m1, s1))) => ((m1 - 3.0 * s1) - (m0 + 3.0 * s0)).max(0.0),
+            _ => 0.0,
+        };
+
+        Some(EyeDiagram {
+            ui: self.ui,
+            voltage_range: self.range,
+            threshold: self.threshold,
+            time_bins: t_bins,
+            voltage_bins: self.voltage_bins,
+            counts,
+            edge_offset: edge * self.ui,
+            height,
+            width: (self.ui - jitter_pp).max(0.0),
+            jitter_rms: rms * self.ui,
+            jitter_pp,
+            crossings: offsets.len(),
+        })
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// NRZ pattern with edges at `offset + k * ui` displaced by
+    /// `jitter[k % jitter.len()]`, toggling at every UI where `bits`
+    /// changes, with linear 0.1 UI ramps
+    fn nrz(ui: f64, offset: f64, jitter: &[f64], bits: &[bool]) -> (Vec<f64>, Vec<f64>) {
+        let ramp = 0.1 * ui;
+        let mut time = vec![0.0];
+        let mut signal = vec![if bits[0] { 1.0 } else { -1.0 }];
+        for k in 1..bits.len() {
+            if bits[k] == bits[k - 1] {
+                continue;
+            }
+            let edge = offset + k as f64 * ui + jitter[k % jitter.len()];
+            let (from, to) = if bits[k] { (-1.0, 1.0) } else { (1.0, -1.0) };
+            time.extend([edge - ramp / 2.0, edge + ramp / 2.0]);
+            signal.extend([from, to]);
+        }
+        time.push(offset + bits.len() as f64 * ui);
+        signal.push(*signal.last().unwrap());
+        (time, signal)
+    }
+
+    /// Pseudo-random bits (PRBS7)
+    fn prbs(n: usize) -> Vec<bool> {
+        let mut state = 0x5au8;
+        (0..n)
+            .map(|_| {
+                let bit = ((state >> 6) ^ (state >> 5)) & 1;
+                state = ((state << 1) | bit) & 0x7f;
+                bit == 1
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_clean_eye() {
+        let ui = 1e-9;
+        let (time, signal) = nrz(ui, 0.3e-9, &[0.0], &prbs(200));
+        let diagram = eye(&signal, &time, &EyeOptions::new(ui)).unwrap();
+
+        assert!(diagram.crossings > 50);
+        assert!((diagram.edge_offset - 0.3e-9).abs() < 0.01 * ui);
+        assert!(diagram.jitter_pp < 1e-3 * ui);
+        assert!((diagram.width - ui).abs() < 1e-3 * ui);
+        assert!((diagram.height - 2.0).abs() < 1e-9, "{}", diagram.height);
+        assert_eq!(diagram.threshold, 0.0);
+
+        // Open in the middle, samples at both levels
+        let rows: Vec<u64> = (0..diagram.voltage_bins)
+            .map(|v| diagram.count(diagram.time_bins / 2, v))
+            .collect();
+        let open = &rows[diagram.voltage_bins / 2 - 20..diagram.voltage_bins / 2 + 20];
+        assert!(open.iter().all(|&c| c == 0));
+        assert!(rows[..diagram.voltage_bins / 2].iter().any(|&c| c > 0));
+        assert!(rows[diagram.voltage_bins / 2..].iter().any(|&c| c > 0));
+    }
+
+    #[test]
+    fn test_jitter_closes_eye() {
+        let ui = 1e-9;
+        let jitter = [-0.1e-9, 0.0, 0.1e-9];
+        let (time, signal) = nrz(ui, 0.5e-9, &jitter, &prbs(300));
+        let diagram = eye(&signal, &time, &EyeOptions::new(ui)).unwrap();
+
+        assert!((diagram.jitter_pp - 0.2e-9).abs() < 0.01 * ui);
+        assert!((diagram.width - 0.8e-9).abs() < 0.01 * ui);
+        assert!(diagram.jitter_rms > 0.05e-9 && diagram.jitter_rms < 0.1e-9);
+    }
+
+    #[test]
+    fn test_recovered_clock_tracks_drift() {
+        // Transmitter 0.1% slow: a fixed clock smears the crossings
+        let ui = 1e-9;
+        let (time, signal) = nrz(ui * 1.001, 0.5e-9, &[0.0], &prbs(1000));
+        let fixed = eye(&signal, &time, &EyeOptions::new(ui)).unwrap();
+        let recovered = eye(&signal, &time, &EyeOptions::new(ui).recover_clock(0.2)).unwrap();
+
+        assert!(fixed.jitter_pp > 0.5 * ui);
+        assert!(recovered.jitter_pp < 0.05 * ui, "{}", recovered.jitter_pp);
+        assert!(recovered.height > 1.9);
+    }
+
+    #[test]
+    fn test_pieces_match_whole() {
+        let ui = 1e-9;
+        let (time, signal) = nrz(ui, 0.2e-9, &[0.0, 0.05e-9], &prbs(100));
+        let options = EyeOptions::new(ui).range(-1.5, 1.5).bins(50, 40);
+        let whole = eye(&signal, &time, &options).unwrap();
+
+        let mut builder = EyeBuilder::new(&options).unwrap();
+        for (s, t) in signal.chunks(7).zip(time.chunks(7)) {
+            builder.push(s, t);
+        }
+        assert_eq!(builder.finish().unwrap(), whole);
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let (time, signal) = nrz(1.0, 0.5, &[0.0], &prbs(20));
+        assert!(eye(&signal, &time, &EyeOptions::new(0.0)).is_none());
+        assert!(eye(&signal[1..], &time, &EyeOptions::new(1.0)).is_none());
+        assert!(eye(&signal, &time, &EyeOptions::new(1.0).threshold(5.0)).is_none());
+        assert!(EyeBuilder::new(&EyeOptions::new(1.0)).is_none());
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let (time, signal) = nrz(1.0, 0.5, &[0.0], &prbs(40));
+        let diagram = eye(&signal, &time, &EyeOptions::new(1.0).bins(10, 8)).unwrap();
+        let mut csv = Vec::new();
+        diagram.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv.lines().next(), Some("phase,voltage,count"));
+        assert_eq!(csv.lines().count(), 81);
+    }
+}
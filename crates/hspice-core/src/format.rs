@@ -0,0 +1,301 @@
+//! Waveform file format detection
+//!
+//! Looks at the first bytes of a file: HSPICE binary files open with a block
+//! header whose first and third ints are 4, SPICE3/ngspice raw files open
+//! with a `Title:` line. The file extension is only consulted when the
+//! content is not conclusive.
+//!
+//! Other formats (FSDB, vendor formats, ...) can be added at run time with
+//! [`register_format`]; [`read_any`] then reads them too:
+//!
+//! ```rust,no_run
+//! use hspice_core::{register_format, read_any, Result, WaveformFormat, WaveformResult};
+//! use std::path::Path;
+//!
+//! struct Fsdb;
+//!
+//! impl WaveformFormat for Fsdb {
+//!     fn name(&self) -> &str {
+//!         "FSDB"
+//!     }
+//!     fn detect(&self, path: &Path, probe: &[u8]) -> bool {
+//!         path.extension().is_some_and(|e| e.eq_ignore_ascii_case("fsdb"))
+//!     }
+//!     fn read(&self, path: &Path) -> Result<WaveformResult> {
+//!         unimplemented!("call the vendor reader")
+//!     }
+//! }
+//!
+//! register_format(Fsdb);
+//! let result = read_any("top.fsdb").unwrap();
+//! ```
+
+use crate::types::{Result, WaveformError, WaveformResult};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use tracing::debug;
+
+/// Bytes read from the start of a file to detect its format
+const PROBE_LEN: usize = 16;
+
+/// Bytes of the start of a file passed to [`WaveformFormat::detect`]
+pub const PLUGIN_PROBE_LEN: usize = 512;
+
+/// Waveform file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    /// HSPICE binary (.tr0, .ac0, .sw0, .ns0, ...)
+    Hspice,
+    /// SPICE3/ngspice raw, binary or ASCII (.raw)
+    SpiceRaw,
+}
+
+/// Detect the format of in-memory data from its leading bytes
+///
+/// Returns `None` if the bytes match neither format.
+pub fn detect_format_bytes(data: &[u8]) -> Option<FileFormat> {
+    if data.len() >= 12 {
+        let int_at = |i: usize| [data[i], data[i + 1], data[i + 2], data[i + 3]];
+        let is_four = |b: [u8; 4]| i32::from_le_bytes(b) == 4 || i32::from_be_bytes(b) == 4;
+        if is_four(int_at(0)) && is_four(int_at(8)) {
+            return Some(FileFormat::Hspice);
+        }
+    }
+    let text = data.trim_ascii_start();
+    if text.len() >= 6 && text[..6].eq_ignore_ascii_case(b"title:") {
+        return Some(FileFormat::SpiceRaw);
+    }
+    None
+}
+
+/// Guess the format from a file extension
+fn format_from_extension(path: &Path) -> Option<FileFormat> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    if ext == "raw" {
+        return Some(FileFormat::SpiceRaw);
+    }
+    // HSPICE outputs are .tr#, .ac#, .sw#, .ns# with a run number suffix
+    let is_hspice = ["tr", "ac", "sw", "ns"].iter().any(|prefix| {
+        ext.strip_prefix(prefix)
+            .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_alphanumeric()))
+    });
+    is_hspice.then_some(FileFormat::Hspice)
+}
+
+/// Detect the format of a file by content, falling back to its extension
+pub fn detect_format(filename: &str) -> Result<FileFormat> {
+    let mut probe = Vec::with_capacity(PROBE_LEN);
+    File::open(filename)?
+        .take(PROBE_LEN as u64)
+        .read_to_end(&mut probe)?;
+    if probe.is_empty() {
+        return Err(WaveformError::EmptyFile);
+    }
+
+    detect_format_bytes(&probe)
+        .or_else(|| format_from_extension(Path::new(filename)))
+        .ok_or_else(|| {
+            WaveformError::ParseError(format!("{}: not an HSPICE or SPICE3 raw file", filename))
+        })
+}
+
+/// Read an HSPICE or SPICE3/ngspice raw file, or a file of a registered
+/// format, detecting the format
+///
+/// Registered formats are asked first, the most recently registered one
+/// first, so a plugin can also take over a built-in format.
+pub fn read_any(filename: &str) -> Result<WaveformResult> {
+    if let Some(plugin) = find_format(Path::new(filename))? {
+        debug!(file = %filename, format = plugin.name(), "Detected registered format");
+        return plugin.read(Path::new(filename));
+    }
+    let format = detect_format(filename)?;
+    debug!(file = %filename, ?format, "Detected format");
+    match format {
+        FileFormat::Hspice => crate::read(filename),
+        FileFormat::SpiceRaw => crate::read_raw(filename),
+    }
+}
+
+// ============================================================================
+// Format Plugins
+// ============================================================================
+
+/// A reader for a waveform format the crate does not implement itself
+///
+/// Implement it around a vendor or third-party reader and pass it to
+/// [`register_format`].
+pub trait WaveformFormat: Send + Sync {
+    /// Name for logs and [`registered_formats`], e.g. "FSDB"
+    fn name(&self) -> &str;
+
+    /// Whether this reader handles the file at `path`, whose first bytes
+    /// (up to [`PLUGIN_PROBE_LEN`]) are `probe`
+    fn detect(&self, path: &Path, probe: &[u8]) -> bool;
+
+    /// Read the whole file
+    fn read(&self, path: &Path) -> Result<WaveformResult>;
+}
+
+/// Registered formats, in registration order
+static FORMATS: RwLock<Vec<Arc<dyn WaveformFormat>>> = RwLock::new(Vec::new());
+
+/// Make [`read_any`] read files that `format` detects
+///
+/// Registration lasts for the life of the process; a format registered
+/// later is asked before earlier ones.
+pub fn register_format<F: WaveformFormat + 'static>(format: F) {
+    debug!(format = format.name(), "Registered waveform format");
+    FORMATS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(Arc::new(format));
+}
+
+/// Names of the registered formats, in registration order
+pub fn registered_formats() -> Vec<String> {
+    FORMATS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .map(|f| f.name().to_string())
+        .collect()
+}
+
+/// The most recently registered format that detects `path`, if any
+///
+/// The file is only opened when a format is registered.
+pub fn find_format(path: &Path) -> Result<Option<Arc<dyn WaveformFormat>>> {
+    let formats = FORMATS.read().unwrap_or_else(|e| e.into_inner()).clone();
+    if formats.is_empty() {
+        return Ok(None);
+    }
+    let mut probe = Vec::with_capacity(PLUGIN_PROBE_LEN);
+    File::open(path)?
+        .take(PLUGIN_PROBE_LEN as u64)
+        .read_to_end(&mut probe)?;
+    Ok(formats.into_iter().rev().find(|f| f.detect(path, &probe)))
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{temp_path, SyntheticWaveform};
+
+    #[test]
+    fn test_detect_by_content() {
+        let synth = SyntheticWaveform::new().signals(2).points(10);
+        let tr0 = synth.to_tr0_bytes();
+        assert_eq!(detect_format_bytes(&tr0), Some(FileFormat::Hspice));
+
+        let mut raw = Vec::new();
+        crate::writer::write_spice3_raw_to(&synth.expected(), &mut raw, crate::RawEncoding::Binary)
+            .unwrap();
+        assert_eq!(detect_format_bytes(&raw), Some(FileFormat::SpiceRaw));
+
+        assert_eq!(detect_format_bytes(b"* netlist\n"), None);
+    }
+
+    #[test]
+    fn test_detect_by_extension() {
+        assert_eq!(
+            format_from_extension(Path::new("a.tr0")),
+            Some(FileFormat::Hspice)
+        );
+        assert_eq!(
+            format_from_extension(Path::new("a.SW12")),
+            Some(FileFormat::Hspice)
+        );
+        assert_eq!(
+            format_from_extension(Path::new("a.ns0")),
+            Some(FileFormat::Hspice)
+        );
+        assert_eq!(
+            format_from_extension(Path::new("a.raw")),
+            Some(FileFormat::SpiceRaw)
+        );
+        assert_eq!(format_from_extension(Path::new("a.tr")), None);
+        assert_eq!(format_from_extension(Path::new("a.txt")), None);
+    }
+
+    #[test]
+    fn test_read_any_both_formats() {
+        let synth = SyntheticWaveform::new().signals(3).points(50);
+        let expected = synth.expected();
+
+        // Content wins over a misleading extension
+        let tr0 = temp_path("read_any_hspice.raw");
+        synth.write_tr0(&tr0).unwrap();
+        let raw = temp_path("read_any_spice.tr0");
+        crate::write_spice3_raw(&expected, raw.to_str().unwrap()).unwrap();
+
+        for path in [&tr0, &raw] {
+            let result = read_any(path.to_str().unwrap()).unwrap();
+            assert_eq!(result.len(), 50);
+            assert_eq!(
+                format!("{:?}", result.tables[0].vectors[2]),
+                format!("{:?}", expected.tables[0].vectors[2])
+            );
+            std::fs::remove_file(path).ok();
+        }
+
+        let empty = temp_path("read_any_empty.tr0");
+        std::fs::write(&empty, b"").unwrap();
+        assert!(matches!(
+            read_any(empty.to_str().unwrap()),
+            Err(WaveformError::EmptyFile)
+        ));
+        std::fs::remove_file(empty).ok();
+    }
+
+    /// Files that start with `WAVE` followed by the number of points
+    struct Wave;
+
+    impl WaveformFormat for Wave {
+        fn name(&self) -> &str {
+            "wave"
+        }
+
+        fn detect(&self, _path: &Path, probe: &[u8]) -> bool {
+            probe.starts_with(b"WAVE")
+        }
+
+        fn read(&self, path: &Path) -> Result<WaveformResult> {
+            let text = std::fs::read_to_string(path)?;
+            let points = text[4..].trim().parse().map_err(|_| {
+                WaveformError::ParseError(format!("{}: bad point count", path.display()))
+            })?;
+            Ok(SyntheticWaveform::new()
+                .signals(1)
+                .points(points)
+                .expected())
+        }
+    }
+
+    #[test]
+    fn test_registered_format() {
+        register_format(Wave);
+        assert!(registered_formats().contains(&"wave".to_string()));
+
+        let path = temp_path("plugin.fsdb");
+        std::fs::write(&path, b"WAVE 7").unwrap();
+        let result = read_any(path.to_str().unwrap()).unwrap();
+        assert_eq!(result.len(), 7);
+        let plugin = find_format(&path).unwrap().unwrap();
+        assert_eq!(plugin.name(), "wave");
+        std::fs::remove_file(&path).ok();
+
+        // Other files still go to the built-in readers
+        let tr0 = temp_path("plugin_builtin.tr0");
+        SyntheticWaveform::new().points(5).write_tr0(&tr0).unwrap();
+        assert!(find_format(&tr0).unwrap().is_none());
+        assert_eq!(read_any(tr0.to_str().unwrap()).unwrap().len(), 5);
+        std::fs::remove_file(&tr0).ok();
+    }
+}
@@ -0,0 +1,365 @@
+//! Signal groups and grouped aggregates
+//!
+//! A group collects signals by exact name or regex rule, e.g. every supply
+//! current of one power domain. [`SignalGroups::aggregate`] combines the
+//! members point by point, and [`SignalGroups::apply`] appends the results
+//! to a [`WaveformResult`] as derived signals so they export like any other.
+//!
+//! ```rust,no_run
+//! use hspice_core::groups::{Aggregate, SignalGroups};
+//!
+//! let mut result = hspice_core::read("chip.tr0").unwrap();
+//! let groups = SignalGroups::new()
+//!     .names("vdd_io", &["i(vddio1)", "i(vddio2)"])
+//!     .pattern("vdd_core", r"^i\(vdd_core")
+//!     .unwrap();
+//!
+//! groups.apply(&mut result, Aggregate::Sum).unwrap();
+//! let total = result.get("vdd_core").unwrap();
+//! ```
+
+use crate::types::{Result, VarType, Variable, VectorData, WaveformError, WaveformResult};
+use num_complex::Complex64;
+use regex::Regex;
+use tracing::warn;
+
+/// How group members are combined at each point
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregate {
+    /// Sum of the members (total current of a domain)
+    Sum,
+    /// Mean of the members
+    Mean,
+}
+
+#[derive(Debug, Clone)]
+enum Member {
+    Name(String),
+    Pattern(Regex),
+}
+
+impl Member {
+    fn matches(&self, name: &str) -> bool {
+        match self {
+            Member::Name(n) => n == name,
+            Member::Pattern(re) => re.is_match(name),
+        }
+    }
+}
+
+/// One named group and its membership rules
+#[derive(Debug, Clone)]
+pub struct SignalGroup {
+    pub name: String,
+    members: Vec<Member>,
+}
+
+/// Aggregated signal of one group
+#[derive(Debug, Clone)]
+pub struct GroupSignal {
+    /// Group name, used as the derived signal name
+    pub name: String,
+    /// Names of the signals that matched, in variable order
+    pub members: Vec<String>,
+    /// One vector per data table
+    pub tables: Vec<VectorData>,
+}
+
+/// A set of signal groups
+#[derive(Debug, Clone, Default)]
+pub struct SignalGroups {
+    groups: Vec<SignalGroup>,
+}
+
+impl SignalGroups {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add exact signal names to `group` (created if new)
+    pub fn names(mut self, group: &str, names: &[&str]) -> Self {
+        let members = self.group_mut(group);
+        members.extend(names.iter().map(|n| Member::Name(n.to_string())));
+        self
+    }
+
+    /// Add a regex rule to `group`; every signal whose name matches joins it
+    pub fn pattern(mut self, group: &str, pattern: &str) -> Result<Self> {
+        let re = Regex::new(pattern).map_err(|e| {
+            WaveformError::ParseError(format!("Invalid pattern for group {}: {}", group, e))
+        })?;
+        self.group_mut(group).push(Member::Pattern(re));
+        Ok(self)
+    }
+
+    /// Load groups from a JSON object mapping group names to members
+    ///
+    /// A value is either a list of exact names or `{"pattern": "regex"}`.
+    /// Groups are ordered by name:
+    ///
+    /// ```json
+    /// {
+    ///   "vdd_io": ["i(vddio1)", "i(vddio2)"],
+    ///   "vdd_core": {"pattern": "^i\\(vdd_core"}
+    /// }
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn from_json(json: &str) -> Result<Self> {
+        use serde_json::Value;
+
+        let invalid = |msg: String| WaveformError::ParseError(format!("Invalid groups: {}", msg));
+        let root: Value = serde_json::from_str(json).map_err(|e| invalid(e.to_string()))?;
+        let map = root
+            .as_object()
+            .ok_or_else(|| invalid("expected an object of groups".into()))?;
+
+        let mut groups = Self::new();
+        for (group, value) in map {
+            groups = match value {
+                Value::Array(names) => {
+                    let names = names
+                        .iter()
+                        .map(|n| n.as_str())
+                        .collect::<Option<Vec<_>>>()
+                        .ok_or_else(|| invalid(format!("{}: names must be strings", group)))?;
+                    groups.names(group, &names)
+                }
+                Value::Object(rule) => {
+                    let pattern = rule
+                        .get("pattern")
+                        .and_then(Value::as_str)
+                        .ok_or_else(|| invalid(format!("{}: expected \"pattern\"", group)))?;
+                    groups.pattern(group, pattern)?
+                }
+                _ => return Err(invalid(format!("{}: expected a list or object", group))),
+            };
+        }
+        Ok(groups)
+    }
+
+    fn group_mut(&mut self, group: &str) -> &mut Vec<Member> {
+        let index = match self.groups.iter().position(|g| g.name == group) {
+            Some(i) => i,
+            None => {
+                self.groups.push(SignalGroup {
+                    name: group.to_string(),
+                    members: Vec::new(),
+                });
+                self.groups.len() - 1
+            }
+        };
+        &mut self.groups[index].members
+    }
+
+    /// Group names in definition order
+    pub fn group_names(&self) -> Vec<&str> {
+        self.groups.iter().map(|g| g.name.as_str()).collect()
+    }
+
+    /// Variable indices of each group's members (the scale never matches)
+    pub fn resolve(&self, result: &WaveformResult) -> Vec<(&str, Vec<usize>)> {
+        self.groups
+            .iter()
+            .map(|group| {
+                let indices = (1..result.variables.len())
+                    .filter(|&i| {
+                        let name = &result.variables[i].name;
+                        group.members.iter().any(|m| m.matches(name))
+                    })
+                    .collect();
+                (group.name.as_str(), indices)
+            })
+            .collect()
+    }
+
+    /// Combine the members of every group, per data table
+    ///
+    /// Groups with no matching signal are skipped with a warning. Real and
+    /// complex members cannot be mixed in one group.
+    pub fn aggregate(&self, result: &WaveformResult, agg: Aggregate) -> Result<Vec<GroupSignal>> {
+        let mut signals = Vec::new();
+        for (name, indices) in self.resolve(result) {
+            if indices.is_empty() {
+                warn!(group = name, "Signal group has no members");
+                continue;
+            }
+
+            let tables = result
+                .tables
+                .iter()
+                .map(|table| {
+                    let members: Vec<&VectorData> =
+                        indices.iter().map(|&i| &table.vectors[i]).collect();
+                    combine(name, &members, agg)
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            signals.push(GroupSignal {
+                name: name.to_string(),
+                members: indices
+                    .iter()
+                    .map(|&i| result.variables[i].name.clone())
+                    .collect(),
+                tables,
+            });
+        }
+        Ok(signals)
+    }
+
+    /// Append each group's aggregate to `result` as a derived signal
+    ///
+    /// The derived variable is named after the group and keeps the members'
+    /// type when they agree. Returns the number of signals added.
+    pub fn apply(&self, result: &mut WaveformResult, agg: Aggregate) -> Result<usize> {
+        let signals = self.aggregate(result, agg)?;
+        if let Some(clash) = signals.iter().find(|s| result.var_index(&s.name).is_some()) {
+            return Err(WaveformError::ParseError(format!(
+                "Group name {} clashes with an existing signal",
+                clash.name
+            )));
+        }
+
+        for signal in &signals {
+            let types: Vec<VarType> = signal
+                .members
+                .iter()
+                .filter_map(|m| result.var_index(m))
+                .map(|i| result.variables[i].var_type)
+                .collect();
+            let var_type = if types.windows(2).all(|w| w[0] == w[1]) {
+                types[0]
+            } else {
+                VarType::Unknown
+            };
+            result
+                .variables
+                .push(Variable::with_type(&signal.name, var_type));
+        }
+        for signal in &signals {
+            for (table, vector) in result.tables.iter_mut().zip(&signal.tables) {
+                table.vectors.push(vector.clone());
+            }
+        }
+        Ok(signals.len())
+    }
+}
+
+/// Combine same-length member vectors point by point
+fn combine(group: &str, members: &[&VectorData], agg: Aggregate) -> Result<VectorData> {
+    let scale = match agg {
+        Aggregate::Sum => 1.0,
+        Aggregate::Mean => 1.0 / members.len() as f64,
+    };
+    let len = members[0].len();
+
+    if members.iter().all(|m| !m.is_complex()) {
+        let mut total = vec![0.0; len];
+        for member in members.iter().filter_map(|m| m.to_real()) {
+            for (t, v) in total.iter_mut().zip(member.iter()) {
+                *t += v;
+            }
+        }
+        total.iter_mut().for_each(|t| *t *= scale);
+        Ok(VectorData::Real(total.into()))
+    } else if members.iter().all(|m| m.is_complex()) {
+        let mut total = vec![Complex64::new(0.0, 0.0); len];
+        for member in members.iter().filter_map(|m| m.as_complex()) {
+            for (t, v) in total.iter_mut().zip(member) {
+                *t += v;
+            }
+        }
+        total.iter_mut().for_each(|t| *t *= scale);
+        Ok(VectorData::Complex(total.into()))
+    } else {
+        Err(WaveformError::ParseError(format!(
+            "Group {} mixes real and complex signals",
+            group
+        )))
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{AnalysisType, DataTable};
+
+    fn supply_result() -> WaveformResult {
+        let names = ["TIME", "i(vdd1)", "i(vdd2)", "i(vss)", "out"];
+        WaveformResult {
+            title: String::new(),
+            date: String::new(),
+            analysis: AnalysisType::Transient,
+            variables: names.iter().map(|n| Variable::new(*n)).collect(),
+            sweep_param: None,
+            tables: vec![DataTable {
+                sweep_value: None,
+                vectors: vec![
+                    VectorData::Real(vec![0.0, 1.0].into()),
+                    VectorData::Real(vec![1.0, 2.0].into()),
+                    VectorData::Real(vec![3.0, 4.0].into()),
+                    VectorData::Real(vec![-4.0, -6.0].into()),
+                    VectorData::Real(vec![0.5, 0.5].into()),
+                ],
+            }],
+            truncated: false,
+        }
+    }
+
+    #[test]
+    fn test_resolve_names_and_patterns() {
+        let groups = SignalGroups::new()
+            .names("io", &["out", "missing"])
+            .pattern("vdd", r"^i\(vdd")
+            .unwrap();
+        let resolved = groups.resolve(&supply_result());
+        assert_eq!(resolved, vec![("io", vec![4]), ("vdd", vec![1, 2])]);
+    }
+
+    #[test]
+    fn test_apply_sum_and_mean() {
+        let mut result = supply_result();
+        let groups = SignalGroups::new().pattern("vdd", r"^i\(vdd").unwrap();
+        assert_eq!(groups.apply(&mut result, Aggregate::Sum).unwrap(), 1);
+        assert_eq!(result.get("vdd").unwrap().as_real().unwrap(), &[4.0, 6.0]);
+        assert_eq!(result.variables[5].var_type, VarType::Current);
+
+        // A second apply with the same name would shadow the first
+        assert!(groups.apply(&mut result, Aggregate::Mean).is_err());
+
+        let groups = SignalGroups::new().pattern("avg", r"^i\(vdd").unwrap();
+        groups.apply(&mut result, Aggregate::Mean).unwrap();
+        assert_eq!(result.get("avg").unwrap().as_real().unwrap(), &[2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_empty_group_is_skipped() {
+        let groups = SignalGroups::new().names("none", &["nope"]);
+        assert!(groups
+            .aggregate(&supply_result(), Aggregate::Sum)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        assert!(SignalGroups::new().pattern("bad", "(").is_err());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_from_json() {
+        let groups =
+            SignalGroups::from_json(r#"{"vdd": {"pattern": "^i\\(vdd"}, "gnd": ["i(vss)"]}"#)
+                .unwrap();
+        let signals = groups.aggregate(&supply_result(), Aggregate::Sum).unwrap();
+        assert_eq!(signals.len(), 2);
+        // JSON groups come back sorted by name
+        assert_eq!(signals[0].members, vec!["i(vss)"]);
+        assert_eq!(signals[1].members, vec!["i(vdd1)", "i(vdd2)"]);
+        assert!(SignalGroups::from_json(r#"{"x": 3}"#).is_err());
+    }
+}
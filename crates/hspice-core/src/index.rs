@@ -0,0 +1,415 @@
+//! `.idx` sidecar index for random access into large files
+//!
+//! [`build_index`] scans a file once and writes `<file>.idx` next to it: the
+//! byte offset, row count and scale range of every sweep table, plus a seek
+//! mark about every MiB of data. [`crate::HspiceStreamReader`] loads the
+//! sidecar when it opens the file, so `seek_time` (and
+//! [`crate::cache::read_window`]) jump straight to the block before the
+//! target instead of decoding everything in front of it.
+//!
+//! ```rust,no_run
+//! use hspice_core::index::build_index;
+//! use hspice_core::read_stream;
+//!
+//! build_index("huge.tr0").unwrap();
+//! let mut reader = read_stream("huge.tr0").unwrap();
+//! reader.seek_time(75e-6).unwrap(); // decodes about 1 MiB
+//! ```
+//!
+//! The sidecar records the file's size and modification time; one that no
+//! longer matches is ignored.
+
+use crate::options::ReadOptions;
+use crate::parser::parse_header_with_options;
+use crate::source::{StreamSource, MAX_MAPPED_LEN};
+use crate::types::{Result, WaveformError};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use tracing::{debug, info};
+
+/// Extension appended to the indexed file's name
+pub const INDEX_EXTENSION: &str = "idx";
+
+/// Minimum distance in bytes between seek marks
+const MARK_SPACING: u64 = 1 << 20;
+
+/// First line of an index sidecar
+const MAGIC: &str = "hspice-index 1";
+
+/// Place to restart decoding within a table
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IndexMark {
+    /// File offset of a data block
+    pub offset: u64,
+    /// Values at the start of the block that finish the previous row
+    pub lead: usize,
+    /// Scale value of the first row that starts in the block
+    pub first_scale: f64,
+}
+
+/// Location and extent of one sweep table
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableIndex {
+    /// File offset of the table's first data block
+    pub offset: u64,
+    pub sweep_value: Option<f64>,
+    pub rows: usize,
+    /// First and last scale value
+    pub scale_range: (f64, f64),
+    /// Seek marks in file order, the first at `offset`
+    pub marks: Vec<IndexMark>,
+}
+
+impl TableIndex {
+    /// Last mark whose first row is at or before `scale`
+    pub fn mark_before(&self, scale: f64) -> Option<&IndexMark> {
+        let i = self.marks.partition_point(|m| m.first_scale <= scale);
+        i.checked_sub(1).map(|i| &self.marks[i])
+    }
+}
+
+/// Byte offsets of the tables and seek marks of a waveform file
+#[derive(Debug, Clone, PartialEq)]
+pub struct WaveformIndex {
+    /// Size of the indexed file
+    pub file_len: u64,
+    /// Modification time of the indexed file, in nanoseconds since the
+    /// Unix epoch, if the filesystem has one
+    pub modified: Option<u128>,
+    pub tables: Vec<TableIndex>,
+}
+
+/// `<path>.idx`
+pub fn index_path<P: AsRef<Path>>(path: P) -> PathBuf {
+    let mut name = path.as_ref().as_os_str().to_owned();
+    name.push(".");
+    name.push(INDEX_EXTENSION);
+    PathBuf::from(name)
+}
+
+/// Index `path` and write the sidecar next to it
+pub fn build_index<P: AsRef<Path>>(path: P) -> Result<WaveformIndex> {
+    let path = path.as_ref();
+    let index = WaveformIndex::scan(path)?;
+    let sidecar = index_path(path);
+    std::fs::write(&sidecar, index.to_text())?;
+    info!(
+        sidecar = %sidecar.display(),
+        tables = index.tables.len(),
+        marks = index.tables.iter().map(|t| t.marks.len()).sum::<usize>(),
+        "Index written"
+    );
+    Ok(index)
+}
+
+/// Size and modification time of `path`
+fn file_stamp(path: &Path) -> Result<(u64, Option<u128>)> {
+    let metadata = std::fs::metadata(path)?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos());
+    Ok((metadata.len(), modified))
+}
+
+impl WaveformIndex {
+    /// Index `path` without writing a sidecar
+    ///
+    /// Reads every data block once. A file cut off in the middle of a
+    /// table is indexed up to where it ends.
+    pub fn scan<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let (file_len, modified) = file_stamp(path)?;
+        let mut source = StreamSource::open(path, MAX_MAPPED_LEN)?;
+        let options = ReadOptions::default();
+        let (meta, data_start) =
+            parse_header_with_options(&source.header_bytes(options.limits.max_memory)?, &options)?;
+        let columns = meta.num_columns().max(1);
+
+        let mut position = data_start as u64;
+        let mut tables = Vec::new();
+        'tables: for _ in 0..meta.sweep_size {
+            if position >= source.len() {
+                break;
+            }
+            let mut table = TableIndex {
+                offset: position,
+                sweep_value: None,
+                rows: 0,
+                scale_range: (f64::NAN, f64::NAN),
+                marks: Vec::new(),
+            };
+            // Values of the table read so far, without the sweep value
+            let mut seen = 0;
+            let mut sweep_pending = meta.sweep_name.is_some();
+            loop {
+                let block_start = position;
+                let block = {
+                    let data = source.block_at(position)?;
+                    let mut reader =
+                        crate::block_reader::BlockReader::new(&data, meta.post_version)
+                            .with_base_offset(position)
+                            .with_options(&options);
+                    let block = reader.next_block()?;
+                    position += reader.bytes_consumed() as u64;
+                    block
+                };
+                let Some(block) = block else {
+                    tables.push(table);
+                    break 'tables;
+                };
+                let mut values = block.values;
+                if block.is_end {
+                    values.pop();
+                }
+                let mut start = 0;
+                if sweep_pending && !values.is_empty() {
+                    table.sweep_value = Some(values[0]);
+                    sweep_pending = false;
+                    start = 1;
+                }
+
+                let lead = (columns - seen % columns) % columns;
+                let mut row = start + lead;
+                if row < values.len() {
+                    let spaced = table
+                        .marks
+                        .last()
+                        .is_none_or(|m| block_start >= m.offset + MARK_SPACING);
+                    if spaced {
+                        table.marks.push(IndexMark {
+                            offset: block_start,
+                            lead,
+                            first_scale: values[row],
+                        });
+                    }
+                    if table.rows == 0 {
+                        table.scale_range.0 = values[row];
+                    }
+                    while row < values.len() {
+                        table.scale_range.1 = values[row];
+                        table.rows += 1;
+                        row += columns;
+                    }
+                }
+                seen += values.len() - start;
+                if block.is_end {
+                    break;
+                }
+            }
+            tables.push(table);
+        }
+
+        debug!(tables = tables.len(), "File indexed");
+        Ok(Self {
+            file_len,
+            modified,
+            tables,
+        })
+    }
+
+    /// The sidecar of `path`, if there is one and it matches the file
+    ///
+    /// Returns `Ok(None)` if the sidecar is missing or stale, and an error
+    /// if it cannot be parsed.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Option<Self>> {
+        let path = path.as_ref();
+        let sidecar = index_path(path);
+        let text = match std::fs::read_to_string(&sidecar) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let index =
+            Self::parse(&text).map_err(|e| e.context(format!("reading {}", sidecar.display())))?;
+        let (file_len, modified) = file_stamp(path)?;
+        if index.file_len != file_len || index.modified != modified {
+            debug!(sidecar = %sidecar.display(), "Index is stale, ignoring it");
+            return Ok(None);
+        }
+        Ok(Some(index))
+    }
+
+    /// The sidecar text
+    pub fn to_text(&self) -> String {
+        let optional = |v: Option<String>| v.unwrap_or_else(|| "-".into());
+        let mut text = format!("{}\n", MAGIC);
+        let modified = optional(self.modified.map(|m| m.to_string()));
+        let _ = writeln!(text, "file {} {}", self.file_len, modified);
+        for table in &self.tables {
+            let sweep = optional(table.sweep_value.map(|v| v.to_string()));
+            let (first, last) = table.scale_range;
+            let _ = writeln!(
+                text,
+                "table {} {} {} {} {}",
+                table.offset, sweep, table.rows, first, last
+            );
+            for mark in &table.marks {
+                let _ = writeln!(
+                    text,
+                    "mark {} {} {}",
+                    mark.offset, mark.lead, mark.first_scale
+                );
+            }
+        }
+        text
+    }
+
+    /// Parse sidecar text written by [`WaveformIndex::to_text`]
+    pub fn parse(text: &str) -> Result<Self> {
+        let invalid =
+            |line: &str| WaveformError::ParseError(format!("invalid index line {:?}", line));
+        let mut lines = text.lines();
+        if lines.next() != Some(MAGIC) {
+            return Err(WaveformError::ParseError("not an hspice index".into()));
+        }
+        let mut index = WaveformIndex {
+            file_len: 0,
+            modified: None,
+            tables: Vec::new(),
+        };
+        for line in lines {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let number = |i: usize| fields.get(i).and_then(|f| f.parse::<f64>().ok());
+            let integer = |i: usize| fields.get(i).and_then(|f| f.parse::<u64>().ok());
+            match fields.first().copied() {
+                Some("file") => {
+                    index.file_len = integer(1).ok_or_else(|| invalid(line))?;
+                    index.modified = fields.get(2).and_then(|f| f.parse().ok());
+                }
+                Some("table") => index.tables.push(TableIndex {
+                    offset: integer(1).ok_or_else(|| invalid(line))?,
+                    sweep_value: number(2),
+                    rows: integer(3).ok_or_else(|| invalid(line))? as usize,
+                    scale_range: (
+                        number(4).ok_or_else(|| invalid(line))?,
+                        number(5).ok_or_else(|| invalid(line))?,
+                    ),
+                    marks: Vec::new(),
+                }),
+                Some("mark") => {
+                    let mark = IndexMark {
+                        offset: integer(1).ok_or_else(|| invalid(line))?,
+                        lead: integer(2).ok_or_else(|| invalid(line))? as usize,
+                        first_scale: number(3).ok_or_else(|| invalid(line))?,
+                    };
+                    index
+                        .tables
+                        .last_mut()
+                        .ok_or_else(|| invalid(line))?
+                        .marks
+                        .push(mark);
+                }
+                None => {}
+                Some(_) => return Err(invalid(line)),
+            }
+        }
+        Ok(index)
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{temp_path, SyntheticWaveform};
+    use crate::PostVersion;
+
+    #[test]
+    fn test_scan_tables_and_marks() {
+        let synth = SyntheticWaveform::new()
+            .signals(3)
+            .points(100_000)
+            .sweep("temp", vec![25.0, 85.0])
+            .version(PostVersion::V2001)
+            .block_size(1001);
+        let path = temp_path("index_scan.tr0");
+        synth.write_tr0(&path).unwrap();
+        let index = WaveformIndex::scan(&path).unwrap();
+        let expected = synth.expected();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(index.tables.len(), 2);
+        for (table, data) in index.tables.iter().zip(&expected.tables) {
+            let time = data.vectors[0].to_real().unwrap();
+            assert_eq!(table.sweep_value, data.sweep_value);
+            assert_eq!(table.rows, time.len());
+            assert_eq!(table.scale_range, (time[0], time[time.len() - 1]));
+            // 4 columns of 8 bytes: 3.2 MB per table
+            assert!((3..=5).contains(&table.marks.len()), "{:?}", table.marks);
+            assert_eq!(table.marks[0].offset, table.offset);
+            for mark in &table.marks {
+                assert!(mark.lead < 4);
+                assert!(time.contains(&mark.first_scale));
+            }
+        }
+        let mark =
+            index.tables[0].mark_before(expected.tables[0].vectors[0].real_at(60_000).unwrap());
+        assert!(mark.unwrap().offset > index.tables[0].offset);
+        assert_eq!(index.tables[1].mark_before(-1.0), None);
+    }
+
+    #[test]
+    fn test_indexed_seek_matches_unindexed() {
+        // Rows of 3 values straddle the 1000-value blocks
+        let synth = SyntheticWaveform::new()
+            .signals(2)
+            .points(150_000)
+            .sweep("temp", vec![25.0])
+            .block_size(1000);
+        let path = temp_path("index_seek.tr0");
+        synth.write_tr0(&path).unwrap();
+        let index = build_index(&path).unwrap();
+        assert!(index.tables[0].marks.iter().any(|m| m.lead > 0));
+        let expected = synth.expected();
+        let scale = expected.tables[0].vectors[0].to_real().unwrap();
+
+        let mut indexed = crate::HspiceStreamReader::open(&path, 500).unwrap();
+        let mut plain = crate::HspiceStreamReader::from_bytes(synth.to_tr0_bytes(), 500).unwrap();
+        for i in [140_000, 3, 99_999, 0, 150_000 - 1] {
+            indexed.seek_time(scale[i]).unwrap();
+            plain.seek_time(scale[i]).unwrap();
+            let (a, b) = (
+                indexed.next().unwrap().unwrap(),
+                plain.next().unwrap().unwrap(),
+            );
+            assert_eq!(a.time_range.0, scale[i]);
+            assert_eq!(a.time_range, b.time_range);
+            for (name, vector) in &a.data {
+                assert_eq!(vector.to_real(), b.data[name].to_real(), "{}", name);
+            }
+        }
+        std::fs::remove_file(index_path(&path)).ok();
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_sidecar_roundtrip_and_staleness() {
+        let synth = SyntheticWaveform::new().signals(2).points(500);
+        let path = temp_path("index_sidecar.tr0");
+        synth.write_tr0(&path).unwrap();
+        assert_eq!(WaveformIndex::load(&path).unwrap(), None);
+
+        let built = build_index(&path).unwrap();
+        assert_eq!(WaveformIndex::parse(&built.to_text()).unwrap(), built);
+        assert_eq!(WaveformIndex::load(&path).unwrap(), Some(built));
+
+        // A rewritten file of another size makes the sidecar stale
+        SyntheticWaveform::new()
+            .signals(2)
+            .points(600)
+            .write_tr0(&path)
+            .unwrap();
+        assert_eq!(WaveformIndex::load(&path).unwrap(), None);
+
+        std::fs::write(index_path(&path), "garbage\n").unwrap();
+        assert!(WaveformIndex::load(&path).is_err());
+        std::fs::remove_file(index_path(&path)).ok();
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,436 @@
+//! Block-level integrity checks of HSPICE binary files
+//!
+//! [`verify`] walks every data block without keeping the data and reports
+//! what is wrong in an [`IntegrityReport`]: damaged block heads, trailers
+//! that do not repeat the block size, blocks cut off by the end of the
+//! file, tables whose values do not fill whole rows, scales that turn
+//! back, and tables without an end marker. A file truncated by a full
+//! disk or NFS, or left behind by a crashed simulator, shows up here
+//! before a flow spends time loading it.
+//!
+//! ```rust,no_run
+//! let report = hspice_core::integrity::verify("run.tr0").unwrap();
+//! if !report.is_ok() {
+//!     for issue in &report.issues {
+//!         eprintln!("{}", issue);
+//!     }
+//! }
+//! ```
+
+use crate::decode::{decode_values, is_end_marker, Blocks, Framing};
+use crate::options::ReadOptions;
+use crate::parser::read_header_bytes;
+use crate::source::{StreamSource, MAX_MAPPED_LEN};
+use crate::types::{Result, WaveformError};
+use std::fmt;
+use std::path::Path;
+use tracing::debug;
+
+/// What is wrong at one place in the file
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IssueKind {
+    /// A block head is not the `[4, count, 4, size]` pattern
+    #[error("corrupted block header")]
+    CorruptBlockHeader,
+    /// A block head declares a negative payload size
+    #[error("invalid block size {size}")]
+    InvalidBlockSize { size: i32 },
+    /// A block's payload is not a whole number of values
+    #[error("block size {size} is not a multiple of the value size")]
+    PartialValue { size: i32 },
+    /// The file ends inside a block
+    #[error("block cut off: need {needed} bytes, {available} available")]
+    TruncatedBlock { needed: u64, available: u64 },
+    /// A block trailer does not repeat the size from its head
+    #[error("block trailer {found} does not match size {expected}")]
+    TrailerMismatch { expected: i32, found: i32 },
+    /// A table's values do not fill whole rows
+    #[error("{values} values do not fill rows of {columns}")]
+    MisalignedRows { values: usize, columns: usize },
+    /// The scale turns back (or is NaN) at `row`
+    #[error("scale not monotonic at row {row}: {previous} then {value}")]
+    NonMonotonicScale {
+        row: usize,
+        previous: f64,
+        value: f64,
+    },
+    /// The data ends before the table's end marker
+    #[error("no end marker")]
+    MissingEndMarker,
+    /// Fewer complete tables than the header declares
+    #[error("{found} of {expected} sweep tables present")]
+    MissingTables { found: usize, expected: usize },
+}
+
+/// One problem found by [`verify`]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Issue {
+    /// File offset of the block or position the problem was found at
+    pub offset: u64,
+    /// Sweep table (0-based) being read
+    pub table: usize,
+    pub kind: IssueKind,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "table {} at byte {}: {}",
+            self.table, self.offset, self.kind
+        )
+    }
+}
+
+/// Outcome of [`verify`]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IntegrityReport {
+    pub file_len: u64,
+    /// Offset of the first data block
+    pub data_start: u64,
+    /// Data blocks read
+    pub blocks: usize,
+    /// Tables read up to their end marker
+    pub tables: usize,
+    /// Tables the header declares
+    pub expected_tables: usize,
+    /// Complete rows over all tables
+    pub rows: usize,
+    /// Bytes after the last declared table, such as further `.ALTER` runs
+    pub trailing_bytes: u64,
+    /// Problems in file order; empty for an intact file
+    pub issues: Vec<Issue>,
+}
+
+impl IntegrityReport {
+    /// Whether no problem was found
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Check every data block of the file at `path`
+///
+/// Returns an error only if the file cannot be opened or its header cannot
+/// be parsed; problems in the data are collected in the report.
+pub fn verify<P: AsRef<Path>>(path: P) -> Result<IntegrityReport> {
+    verify_source(StreamSource::open(path.as_ref(), MAX_MAPPED_LEN)?)
+}
+
+/// Check every data block of file contents in memory
+pub fn verify_bytes(data: Vec<u8>) -> Result<IntegrityReport> {
+    verify_source(StreamSource::Memory(data))
+}
+
+/// Values of the table being read
+struct TableCheck {
+    columns: usize,
+    /// Whether the first value, the sweep value, is still to come
+    sweep_pending: bool,
+    values: usize,
+    previous_scale: Option<f64>,
+    /// Sign of the first scale step, 0 until the scale moves
+    direction: f64,
+    monotonic: bool,
+}
+
+impl TableCheck {
+    fn new(columns: usize, swept: bool) -> Self {
+        Self {
+            columns,
+            sweep_pending: swept,
+            values: 0,
+            previous_scale: None,
+            direction: 0.0,
+            monotonic: true,
+        }
+    }
+
+    fn started(&self) -> bool {
+        self.values > 0 || self.previous_scale.is_some()
+    }
+
+    /// Take a block's values, returning the first scale that turns back
+    fn push(&mut self, values: &[f64]) -> Option<IssueKind> {
+        let mut values = values;
+        if self.sweep_pending && !values.is_empty() {
+            self.sweep_pending = false;
+            values = &values[1..];
+        }
+        let mut issue = None;
+        let first = (self.columns - self.values % self.columns) % self.columns;
+        let scales = values.iter().skip(first).step_by(self.columns);
+        for (k, &value) in scales.enumerate() {
+            if let Some(previous) = self.previous_scale {
+                if self.direction == 0.0 && value != previous {
+                    self.direction = (value - previous).signum();
+                }
+                let back = (value - previous) * self.direction < 0.0;
+                if self.monotonic && (back || value.is_nan()) {
+                    self.monotonic = false;
+                    issue = Some(IssueKind::NonMonotonicScale {
+                        row: (self.values + first) / self.columns + k,
+                        previous,
+                        value,
+                    });
+                }
+            }
+            self.previous_scale = Some(value);
+        }
+        self.values += values.len();
+        issue
+    }
+}
+
+fn verify_source(mut source: StreamSource) -> Result<IntegrityReport> {
+    let options = ReadOptions::default();
+    let (meta, data_start) =
+        read_header_bytes(&source.header_bytes(options.limits.max_memory)?, &options)?;
+    let columns = meta.num_columns().max(1);
+    let item_size = meta.post_version.value_size();
+
+    let mut report = IntegrityReport {
+        file_len: source.len(),
+        data_start: data_start as u64,
+        blocks: 0,
+        tables: 0,
+        expected_tables: meta.sweep_size,
+        rows: 0,
+        trailing_bytes: 0,
+        issues: Vec::new(),
+    };
+    let issue = |report: &mut IntegrityReport, offset: u64, kind| {
+        let table = report.tables;
+        report.issues.push(Issue {
+            offset,
+            table,
+            kind,
+        });
+    };
+
+    let mut position = data_start as u64;
+    let mut table = TableCheck::new(columns, meta.sweep_name.is_some());
+    let mut fatal = false;
+    while report.tables < report.expected_tables && position < report.file_len {
+        let block_start = position;
+        let data = source.block_at(position)?;
+        let framing = Framing {
+            trailer_mismatch: true,
+            ..Framing::default()
+        };
+        let mut blocks = Blocks::new(&data, 0).with_framing(framing);
+
+        let block = match blocks.next() {
+            Some(Ok(block)) => block,
+            Some(Err(e)) => {
+                let (offset, kind) =
+                    block_issue(WaveformError::from(e).shifted(block_start), block_start);
+                issue(&mut report, offset, kind);
+                fatal = true;
+                break;
+            }
+            None => break,
+        };
+        if !block.payload.len().is_multiple_of(item_size) {
+            let size = block.payload.len() as i32;
+            issue(&mut report, block_start, IssueKind::PartialValue { size });
+        }
+        let mut values = Vec::with_capacity(block.payload.len() / item_size);
+        decode_values(block.payload, meta.post_version, block.endian, &mut values);
+        let is_end = values
+            .last()
+            .is_some_and(|&v| is_end_marker(v, meta.post_version));
+        position += blocks.position() as u64;
+        report.blocks += 1;
+
+        if is_end {
+            values.pop();
+        }
+        if let Some(kind) = table.push(&values) {
+            issue(&mut report, block_start, kind);
+        }
+        if let Some(found) = block.bad_trailer {
+            let kind = IssueKind::TrailerMismatch {
+                expected: block.payload.len() as i32,
+                found,
+            };
+            issue(
+                &mut report,
+                block_start + block.trailer_offset() as u64,
+                kind,
+            );
+        }
+        if is_end {
+            if !table.values.is_multiple_of(columns) {
+                let kind = IssueKind::MisalignedRows {
+                    values: table.values,
+                    columns,
+                };
+                issue(&mut report, position, kind);
+            }
+            report.rows += table.values / columns;
+            report.tables += 1;
+            table = TableCheck::new(columns, meta.sweep_name.is_some());
+        }
+    }
+
+    if table.started() {
+        report.rows += table.values / columns;
+        if !fatal {
+            issue(&mut report, position, IssueKind::MissingEndMarker);
+        }
+    }
+    if report.tables < report.expected_tables {
+        let kind = IssueKind::MissingTables {
+            found: report.tables,
+            expected: report.expected_tables,
+        };
+        issue(&mut report, position, kind);
+    } else {
+        report.trailing_bytes = report.file_len.saturating_sub(position);
+    }
+
+    debug!(
+        blocks = report.blocks,
+        issues = report.issues.len(),
+        "Integrity check complete"
+    );
+    Ok(report)
+}
+
+/// Offset and kind of a block framing error
+fn block_issue(error: WaveformError, block_start: u64) -> (u64, IssueKind) {
+    match error {
+        WaveformError::CorruptedBlockHeader { offset } => (offset, IssueKind::CorruptBlockHeader),
+        WaveformError::InvalidBlockSize { size, offset } => {
+            (offset, IssueKind::InvalidBlockSize { size })
+        }
+        WaveformError::TrailerMismatch {
+            expected,
+            found,
+            offset,
+        } => (offset, IssueKind::TrailerMismatch { expected, found }),
+        WaveformError::TruncatedBlock {
+            offset,
+            needed,
+            available,
+        } => (offset, IssueKind::TruncatedBlock { needed, available }),
+        _ => (block_start, IssueKind::CorruptBlockHeader),
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{temp_path, SyntheticWaveform};
+    use crate::types::PostVersion;
+
+    fn swept() -> SyntheticWaveform {
+        SyntheticWaveform::new()
+            .signals(2)
+            .points(300)
+            .sweep("temp", vec![25.0, 85.0])
+            .block_size(100)
+    }
+
+    /// Offsets of the data blocks in `bytes`, from `start`
+    fn block_offsets(bytes: &[u8], start: usize) -> Vec<usize> {
+        let mut offsets = Vec::new();
+        let mut pos = start;
+        while pos + 16 <= bytes.len() {
+            offsets.push(pos);
+            let size = i32::from_le_bytes(bytes[pos + 12..pos + 16].try_into().unwrap());
+            pos += 16 + size as usize + 4;
+        }
+        offsets
+    }
+
+    #[test]
+    fn test_intact_file() {
+        let path = temp_path("integrity_ok.tr0");
+        swept().write_tr0(&path).unwrap();
+        let report = verify(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert!(report.is_ok(), "{:?}", report.issues);
+        assert_eq!((report.tables, report.expected_tables), (2, 2));
+        assert_eq!(report.rows, 600);
+        assert_eq!(report.blocks, 2 * 10);
+        assert_eq!(report.trailing_bytes, 0);
+    }
+
+    #[test]
+    fn test_truncated_file() {
+        let bytes = swept().to_tr0_bytes();
+        let report = verify_bytes(bytes[..bytes.len() * 3 / 4].to_vec()).unwrap();
+        assert!(!report.is_ok());
+        assert_eq!(report.tables, 1);
+        let kinds: Vec<&IssueKind> = report.issues.iter().map(|i| &i.kind).collect();
+        assert!(matches!(kinds[0], IssueKind::TruncatedBlock { .. }));
+        assert_eq!(
+            kinds[1],
+            &IssueKind::MissingTables {
+                found: 1,
+                expected: 2
+            }
+        );
+        assert_eq!(report.issues[0].table, 1);
+
+        // Cut exactly at a block boundary: no end marker
+        let synth = SyntheticWaveform::new().points(300).block_size(90);
+        let bytes = synth.to_tr0_bytes();
+        let (_, start) = read_header_bytes(&bytes, &ReadOptions::default()).unwrap();
+        let cut = block_offsets(&bytes, start)[2];
+        let report = verify_bytes(bytes[..cut].to_vec()).unwrap();
+        assert_eq!(report.issues[0].kind, IssueKind::MissingEndMarker);
+        assert_eq!(report.rows, 60);
+    }
+
+    #[test]
+    fn test_damaged_blocks_and_scale() {
+        let synth = SyntheticWaveform::new()
+            .points(300)
+            .block_size(90)
+            .version(PostVersion::V2001);
+        let mut bytes = synth.to_tr0_bytes();
+        let (_, start) = read_header_bytes(&bytes, &ReadOptions::default()).unwrap();
+        let offsets = block_offsets(&bytes, start);
+
+        // Trailer of the second block
+        let trailer = offsets[2] - 4;
+        bytes[trailer..offsets[2]].copy_from_slice(&7i32.to_le_bytes());
+        // Scale of row 40 (first value of the second block is row 30)
+        let at = offsets[1] + 16 + 10 * 3 * 8;
+        bytes[at..at + 8].copy_from_slice(&(-1.0f64).to_le_bytes());
+
+        let report = verify_bytes(bytes.clone()).unwrap();
+        assert_eq!(report.tables, 1);
+        assert_eq!(report.issues.len(), 2, "{:?}", report.issues);
+        assert!(matches!(
+            report.issues[0].kind,
+            IssueKind::NonMonotonicScale { row: 40, value, .. } if value == -1.0
+        ));
+        assert_eq!(
+            report.issues[1].kind,
+            IssueKind::TrailerMismatch {
+                expected: 720,
+                found: 7
+            }
+        );
+        assert_eq!(report.issues[1].offset, trailer as u64);
+
+        bytes[offsets[3]] = 9;
+        let report = verify_bytes(bytes).unwrap();
+        assert!(report
+            .issues
+            .iter()
+            .any(|i| i.kind == IssueKind::CorruptBlockHeader && i.offset == offsets[3] as u64));
+    }
+}
@@ -0,0 +1,225 @@
+//! Jitter bathtub curves from threshold crossings
+//!
+//! Every crossing of the decision threshold is folded into one unit interval
+//! (UI). The crossings cluster around the eye edge; the bathtub gives, for
+//! each sampling phase across the eye, the fraction of left-edge crossings
+//! that arrive after it and right-edge crossings that arrive before it.
+//!
+//! ```rust,no_run
+//! use hspice_core::jitter::bathtub;
+//!
+//! let result = hspice_core::read("serdes.tr0").unwrap();
+//! let time = result.get("TIME").unwrap().to_real().unwrap();
+//! let rx = result.get("rx_p").unwrap().to_real().unwrap();
+//!
+//! let curve = bathtub(&rx, &time, 100e-12, 0.0).unwrap();
+//! println!("opening at 1e-3: {:?} UI", curve.opening(1e-3));
+//! ```
+
+use crate::measure::{crossings, Edge};
+use crate::types::Result;
+use std::f64::consts::TAU;
+use std::io::Write;
+
+/// Number of phase steps across one UI
+const PHASE_STEPS: usize = 100;
+
+/// Bathtub curve sampled across one unit interval
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bathtub {
+    /// Sampling phase in UI, from the left eye edge (0) to the right (1)
+    pub phase: Vec<f64>,
+    /// Fraction of left-edge crossings at or after each phase
+    pub left: Vec<f64>,
+    /// Fraction of right-edge crossings at or before each phase
+    pub right: Vec<f64>,
+    /// Error probability: `transition_density * (left + right)`
+    pub ber: Vec<f64>,
+    /// Crossings per UI (0.5 for random data)
+    pub transition_density: f64,
+    /// Absolute time of the eye edge modulo `ui`
+    pub edge_offset: f64,
+}
+
+impl Bathtub {
+    /// Widest span of phases (in UI) whose error probability is at most `ber`
+    pub fn opening(&self, ber: f64) -> Option<f64> {
+        let mut best: Option<f64> = None;
+        let mut start = None;
+        for (i, &value) in self.ber.iter().enumerate() {
+            match (value <= ber, start) {
+                (true, None) => start = Some(i),
+                (false, Some(s)) => {
+                    let width = self.phase[i - 1] - self.phase[s];
+                    best = Some(best.map_or(width, |b: f64| b.max(width)));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some(s) = start {
+            let width = self.phase[self.phase.len() - 1] - self.phase[s];
+            best = Some(best.map_or(width, |b| b.max(width)));
+        }
+        best
+    }
+
+    /// Write the curve as CSV: `phase,left,right,ber`
+    pub fn write_csv<W: Write>(&self, writer: &mut W) -> Result<()> {
+        writeln!(writer, "phase,left,right,ber")?;
+        for i in 0..self.phase.len() {
+            writeln!(
+                writer,
+                "{},{},{},{}",
+                self.phase[i], self.left[i], self.right[i], self.ber[i]
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Bathtub curve of `signal` over `scale` for a unit interval of `ui`
+///
+/// Crossings of `threshold` in either direction are folded modulo `ui` and
+/// centred on their circular mean, which is taken as the eye edge. Returns
+/// `None` if `ui` is not positive, the slices differ in length, or fewer
+/// than two crossings are found.
+pub fn bathtub(signal: &[f64], scale: &[f64], ui: f64, threshold: f64) -> Option<Bathtub> {
+    if ui.is_nan() || ui <= 0.0 || signal.len() != scale.len() {
+        return None;
+    }
+    let times = crossings(scale, signal, threshold, Edge::Either);
+    if times.len() < 2 {
+        return None;
+    }
+
+    // Eye edge: circular mean of the crossing phases
+    let (sin, cos) = times.iter().fold((0.0, 0.0), |(s, c), &t| {
+        let angle = TAU * t / ui;
+        (s + angle.sin(), c + angle.cos())
+    });
+    let edge_offset = (sin.atan2(cos) / TAU).rem_euclid(1.0) * ui;
+
+    // Crossing positions relative to the edge, in UI within [-0.5, 0.5)
+    let mut offsets: Vec<f64> = times
+        .iter()
+        .map(|&t| ((t - edge_offset) / ui + 0.5).rem_euclid(1.0) - 0.5)
+        .collect();
+    offsets.sort_by(f64::total_cmp);
+    let count = offsets.len() as f64;
+
+    let span = (scale[scale.len() - 1] - scale[0]) / ui;
+    let transition_density = if span > 0.0 {
+        (count / span).min(1.0)
+    } else {
+        1.0
+    };
+
+    let phase: Vec<f64> = (0..=PHASE_STEPS)
+        .map(|i| i as f64 / PHASE_STEPS as f64)
+        .collect();
+    // Left edge crossings sit at `offset`, right edge ones one UI later
+    let left: Vec<f64> = phase
+        .iter()
+        .map(|&p| (offsets.len() - offsets.partition_point(|&o| o < p)) as f64 / count)
+        .collect();
+    let right: Vec<f64> = phase
+        .iter()
+        .map(|&p| offsets.partition_point(|&o| o + 1.0 <= p) as f64 / count)
+        .collect();
+    let ber = left
+        .iter()
+        .zip(&right)
+        .map(|(l, r)| transition_density * (l + r))
+        .collect();
+
+    Some(Bathtub {
+        phase,
+        left,
+        right,
+        ber,
+        transition_density,
+        edge_offset,
+    })
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// NRZ square wave toggling every UI with edges at `offset + k * ui`,
+    /// each displaced by `jitter[k % jitter.len()]`
+    fn nrz(ui: f64, offset: f64, jitter: &[f64], uis: usize) -> (Vec<f64>, Vec<f64>) {
+        let steps = 200;
+        let dt = ui / steps as f64;
+        let time: Vec<f64> = (0..uis * steps).map(|i| i as f64 * dt).collect();
+        let edges: Vec<f64> = (0..uis)
+            .map(|k| offset + k as f64 * ui + jitter[k % jitter.len()])
+            .collect();
+        let signal = time
+            .iter()
+            .map(|&t| {
+                let passed = edges.partition_point(|&e| e <= t);
+                if passed % 2 == 0 {
+                    -1.0
+                } else {
+                    1.0
+                }
+            })
+            .collect();
+        (time, signal)
+    }
+
+    #[test]
+    fn test_clean_eye_is_fully_open() {
+        let ui = 1e-9;
+        let (time, signal) = nrz(ui, 0.3e-9, &[0.0], 40);
+        let curve = bathtub(&signal, &time, ui, 0.0).unwrap();
+
+        assert!((curve.edge_offset - 0.3e-9).abs() < 0.01 * ui);
+        assert!((curve.transition_density - 1.0).abs() < 0.05);
+        // Zero error everywhere inside the eye
+        assert!(curve.ber[1..PHASE_STEPS].iter().all(|&b| b == 0.0));
+        assert!(curve.opening(1e-12).unwrap() >= 0.98);
+    }
+
+    #[test]
+    fn test_jitter_closes_eye() {
+        let ui = 1e-9;
+        let jitter = [-0.1e-9, 0.0, 0.1e-9];
+        let (time, signal) = nrz(ui, 0.5e-9, &jitter, 60);
+        let curve = bathtub(&signal, &time, ui, 0.0).unwrap();
+
+        // Crossings spread +/-0.1 UI around the edge
+        let at = |p: f64| (p * PHASE_STEPS as f64).round() as usize;
+        assert!(curve.left[at(0.05)] > 0.3);
+        assert_eq!(curve.left[at(0.15)], 0.0);
+        assert_eq!(curve.right[at(0.85)], 0.0);
+        assert!(curve.right[at(0.95)] > 0.3);
+        let opening = curve.opening(0.0).unwrap();
+        assert!((opening - 0.8).abs() < 0.05, "opening {}", opening);
+    }
+
+    #[test]
+    fn test_bathtub_invalid_input() {
+        let (time, signal) = nrz(1.0, 0.5, &[0.0], 4);
+        assert!(bathtub(&signal, &time, 0.0, 0.0).is_none());
+        assert!(bathtub(&signal[1..], &time, 1.0, 0.0).is_none());
+        assert!(bathtub(&signal, &time, 1.0, 5.0).is_none());
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let (time, signal) = nrz(1.0, 0.5, &[0.0], 8);
+        let curve = bathtub(&signal, &time, 1.0, 0.0).unwrap();
+        let mut csv = Vec::new();
+        curve.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv.lines().next(), Some("phase,left,right,ber"));
+        assert_eq!(csv.lines().count(), PHASE_STEPS + 2);
+    }
+}
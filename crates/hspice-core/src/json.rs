@@ -0,0 +1,270 @@
+//! JSON and NDJSON export
+//!
+//! [`write_json`] writes a whole result as one JSON document:
+//!
+//! ```text
+//! {
+//!   "schema": "hspice-waveform/1",
+//!   "title": "...", "date": "...",
+//!   "analysis": "transient",            // AnalysisType, lowercase
+//!   "scale": "TIME",
+//!   "sweep_param": null,                // or "temp"
+//!   "variables": [
+//!     {"name": "TIME", "type": "time", "unit": "s", "complex": false},
+//!     ...
+//!   ],
+//!   "tables": [
+//!     {"sweep_value": null, "signals": {"TIME": [0.0, ...], "v(out)": [...]}}
+//!   ]
+//! }
+//! ```
+//!
+//! Complex values are `[re, im]` pairs, `unit` is `null` when the type has
+//! none, and NaN/infinite values are written as `null`.
+//!
+//! [`write_ndjson`] streams the same data as newline-delimited JSON: one
+//! metadata line, then one line per chunk, so output starts before the file
+//! has been read and works with line tools such as `jq -c`:
+//!
+//! ```text
+//! {"type":"metadata","schema":"hspice-waveform/1","title":...,"scale":"TIME","signals":[...],"complex":false}
+//! {"type":"chunk","chunk_index":0,"table_index":0,"sweep_value":null,"time_range":[0.0,1e-9],"data":{"TIME":[...],...}}
+//! ```
+//!
+//! ```rust,no_run
+//! use hspice_core::json::write_ndjson;
+//! use hspice_core::read_stream;
+//!
+//! let stdout = std::io::stdout();
+//! write_ndjson(read_stream("sim.tr0").unwrap(), &mut stdout.lock()).unwrap();
+//! ```
+
+use crate::stream::{DataChunk, HspiceStreamReader, StreamMetadata};
+use crate::types::{Result, VectorData, WaveformResult};
+use serde_json::json;
+use std::io::Write;
+
+/// Schema identifier written into every document and metadata line
+pub const SCHEMA: &str = "hspice-waveform/1";
+
+impl WaveformResult {
+    /// Serialize as a JSON document (see [`crate::json`] for the schema)
+    pub fn to_json(&self) -> String {
+        let mut out = Vec::new();
+        write_json(self, &mut out).expect("writing to a Vec cannot fail");
+        String::from_utf8(out).expect("serde_json writes UTF-8")
+    }
+
+    /// Write as a JSON document to `writer`
+    pub fn write_json<W: Write>(&self, writer: &mut W) -> Result<()> {
+        write_json(self, writer)
+    }
+}
+
+/// Write `result` as one JSON document
+///
+/// Signal arrays are written straight from the vectors, so no JSON tree of
+/// the data is built in memory.
+pub fn write_json<W: Write>(result: &WaveformResult, writer: &mut W) -> Result<()> {
+    let first = result.tables.first();
+    let variables: Vec<_> = result
+        .variables
+        .iter()
+        .enumerate()
+        .map(|(i, var)| {
+            json!({
+                "name": var.name,
+                "type": var.var_type.to_string(),
+                "unit": var.unit.map(|u| u.symbol()),
+                "complex": first.is_some_and(|t| t.vectors[i].is_complex()),
+            })
+        })
+        .collect();
+
+    write!(writer, "{{\"schema\":")?;
+    to_writer(writer, SCHEMA)?;
+    for (key, value) in [
+        ("title", json!(result.title)),
+        ("date", json!(result.date)),
+        ("analysis", json!(result.analysis.to_string())),
+        ("scale", json!(result.scale_name())),
+        ("sweep_param", json!(result.sweep_param)),
+        ("variables", json!(variables)),
+    ] {
+        write!(writer, ",\"{}\":", key)?;
+        to_writer(writer, &value)?;
+    }
+
+    write!(writer, ",\"tables\":[")?;
+    for (t, table) in result.tables.iter().enumerate() {
+        if t > 0 {
+            write!(writer, ",")?;
+        }
+        write!(writer, "{{\"sweep_value\":")?;
+        to_writer(writer, &table.sweep_value)?;
+        write!(writer, ",\"signals\":")?;
+        write_signals(
+            result.variables.iter().map(|v| &v.name).zip(&table.vectors),
+            writer,
+        )?;
+        write!(writer, "}}")?;
+    }
+    writeln!(writer, "]}}")?;
+    Ok(())
+}
+
+/// Write every chunk of `reader` as NDJSON: a metadata line, then one line
+/// per chunk. Returns the number of chunks written.
+pub fn write_ndjson<W: Write>(reader: HspiceStreamReader, writer: &mut W) -> Result<usize> {
+    let metadata = reader.metadata();
+    write_ndjson_metadata(&metadata, writer)?;
+    let mut chunks = 0;
+    for chunk in reader {
+        write_ndjson_chunk(&metadata, &chunk?, writer)?;
+        chunks += 1;
+    }
+    writer.flush()?;
+    Ok(chunks)
+}
+
+/// Write the NDJSON metadata line
+pub fn write_ndjson_metadata<W: Write>(metadata: &StreamMetadata, writer: &mut W) -> Result<()> {
+    let line = json!({
+        "type": "metadata",
+        "schema": SCHEMA,
+        "title": metadata.title,
+        "date": metadata.date,
+        "scale": metadata.scale_name,
+        "signals": metadata.signal_names,
+        "complex": metadata.is_complex,
+    });
+    to_writer(writer, &line)?;
+    writeln!(writer)?;
+    Ok(())
+}
+
+/// Write one chunk as an NDJSON line, signals in file order with the scale
+/// first
+pub fn write_ndjson_chunk<W: Write>(
+    metadata: &StreamMetadata,
+    chunk: &DataChunk,
+    writer: &mut W,
+) -> Result<()> {
+    write!(
+        writer,
+        "{{\"type\":\"chunk\",\"chunk_index\":{},\"table_index\":{},\"sweep_value\":",
+        chunk.chunk_index, chunk.table_index
+    )?;
+    to_writer(writer, &chunk.sweep_value)?;
+    write!(writer, ",\"time_range\":")?;
+    to_writer(writer, &[chunk.time_range.0, chunk.time_range.1])?;
+    write!(writer, ",\"data\":")?;
+    let signals = std::iter::once(&metadata.scale_name)
+        .chain(&metadata.signal_names)
+        .filter_map(|name| chunk.data.get(name).map(|v| (name, v)));
+    write_signals(signals, writer)?;
+    writeln!(writer, "}}")?;
+    Ok(())
+}
+
+/// Write `{"name": [values], ...}`
+fn write_signals<'a, W: Write>(
+    signals: impl Iterator<Item = (&'a String, &'a VectorData)>,
+    writer: &mut W,
+) -> Result<()> {
+    write!(writer, "{{")?;
+    for (i, (name, vector)) in signals.enumerate() {
+        if i > 0 {
+            write!(writer, ",")?;
+        }
+        to_writer(writer, name)?;
+        write!(writer, ":")?;
+        match vector {
+            VectorData::Complex(v) => {
+                to_writer(writer, &v.iter().map(|c| [c.re, c.im]).collect::<Vec<_>>())?
+            }
+            other => to_writer(writer, &other.to_real().unwrap_or_default())?,
+        }
+    }
+    write!(writer, "}}")?;
+    Ok(())
+}
+
+fn to_writer<W: Write, T: serde::Serialize + ?Sized>(writer: &mut W, value: &T) -> Result<()> {
+    serde_json::to_writer(&mut *writer, value).map_err(std::io::Error::from)?;
+    Ok(())
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{temp_path, SyntheticWaveform};
+
+    #[test]
+    fn test_document_schema() {
+        let synth = SyntheticWaveform::new()
+            .signals(2)
+            .points(5)
+            .sweep("temp", vec![25.0, 85.0]);
+        let mut result = synth.expected();
+        if let VectorData::Real(v) = &mut result.tables[0].vectors[1] {
+            v[0] = f64::NAN;
+        }
+
+        let doc: serde_json::Value = serde_json::from_str(&result.to_json()).unwrap();
+        assert_eq!(doc["schema"], SCHEMA);
+        assert_eq!(doc["sweep_param"], "temp");
+        assert_eq!(doc["variables"][0]["unit"], "s");
+        assert_eq!(doc["variables"].as_array().unwrap().len(), 3);
+        assert_eq!(doc["tables"][1]["sweep_value"], 85.0);
+        let name = result.variables[1].name.as_str();
+        assert!(doc["tables"][0]["signals"][name][0].is_null());
+        assert_eq!(
+            doc["tables"][0]["signals"]["TIME"]
+                .as_array()
+                .unwrap()
+                .len(),
+            5
+        );
+    }
+
+    #[test]
+    fn test_complex_pairs_and_ndjson() {
+        let path = temp_path("json_ac.ac0");
+        SyntheticWaveform::new()
+            .signals(1)
+            .points(30)
+            .complex(true)
+            .write_tr0(&path)
+            .unwrap();
+
+        let doc: serde_json::Value =
+            serde_json::from_str(&crate::read(path.to_str().unwrap()).unwrap().to_json()).unwrap();
+        assert_eq!(doc["variables"][1]["complex"], true);
+        assert_eq!(doc["variables"][0]["unit"], "Hz");
+        let pairs = doc["tables"][0]["signals"]["n0"].as_array().unwrap();
+        assert_eq!(pairs[0].as_array().unwrap().len(), 2);
+
+        let mut out = Vec::new();
+        let chunks =
+            write_ndjson(crate::read_stream_chunked(&path, 10).unwrap(), &mut out).unwrap();
+        let lines: Vec<serde_json::Value> = std::str::from_utf8(&out)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), chunks + 1);
+        assert_eq!(lines[0]["complex"], true);
+        let points: usize = lines[1..]
+            .iter()
+            .map(|l| l["data"]["n0"].as_array().unwrap().len())
+            .sum();
+        assert_eq!(points, 30);
+
+        std::fs::remove_file(path).ok();
+    }
+}
@@ -0,0 +1,156 @@
+//! Header field offsets
+//!
+//! The fixed part of an HSPICE header is a run of ASCII fields at known
+//! byte offsets: the vector counts, the post version, title, date and sweep
+//! size, followed by the vector descriptions. Some "HSPICE-compatible"
+//! writers (FineSim, CustomSim, ...) put those fields elsewhere. A
+//! [`HeaderLayout`] describes where they are, and can be forced for one
+//! read or registered so every read probes for it:
+//!
+//! ```rust,no_run
+//! use hspice_core::{read_with_options, register_header_layout, HeaderLayout, ReadOptions};
+//!
+//! // A writer that pads the header with 8 leading bytes
+//! const PADDED: HeaderLayout = HeaderLayout::HSPICE.shifted("padded", 8);
+//!
+//! // Force it for one file...
+//! let options = ReadOptions::new().header_layout(PADDED);
+//! let result = read_with_options("finesim.tr0", &options).unwrap();
+//!
+//! // ...or let every read try it when the standard layout does not fit
+//! register_header_layout(PADDED);
+//! let result = hspice_core::read("finesim.tr0").unwrap();
+//! ```
+
+pub use crate::decode::HeaderLayout;
+use std::sync::RwLock;
+use tracing::debug;
+
+// ============================================================================
+// Registry
+// ============================================================================
+
+/// Layouts probed before [`HeaderLayout::HSPICE`], most recent last
+static LAYOUTS: RwLock<Vec<HeaderLayout>> = RwLock::new(Vec::new());
+
+/// Make reads without a forced layout also try `layout`
+///
+/// Headers are probed with the standard layout first, then with registered
+/// layouts, most recently registered first.
+pub fn register_header_layout(layout: HeaderLayout) {
+    debug!(layout = layout.name, "Registered header layout");
+    LAYOUTS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(layout);
+}
+
+/// Names of the registered layouts, in registration order
+pub fn registered_header_layouts() -> Vec<&'static str> {
+    LAYOUTS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .map(|layout| layout.name)
+        .collect()
+}
+
+/// The first layout that matches `header`, or the standard one so that
+/// its errors are reported
+pub(crate) fn probe_layout(header: &[u8]) -> HeaderLayout {
+    if HeaderLayout::HSPICE.matches(header) {
+        return HeaderLayout::HSPICE;
+    }
+    let layouts = LAYOUTS.read().unwrap_or_else(|e| e.into_inner());
+    match layouts.iter().rev().find(|layout| layout.matches(header)) {
+        Some(layout) => {
+            debug!(layout = layout.name, "Header matched a registered layout");
+            *layout
+        }
+        None => HeaderLayout::HSPICE,
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::SyntheticWaveform;
+    use crate::types::VECTOR_DESCRIPTION_START_POSITION;
+    use crate::{read_bytes_with_options, ReadOptions};
+
+    fn synth() -> SyntheticWaveform {
+        SyntheticWaveform::new().signals(2).points(16)
+    }
+
+    /// `synth()` as a tr0 file whose header text has `by` bytes of padding
+    /// in front of the fixed fields
+    fn padded_file(by: usize) -> Vec<u8> {
+        let bytes = synth().to_tr0_bytes();
+        let len = i32::from_le_bytes(bytes[12..16].try_into().unwrap()) as usize;
+        let padded_len = ((len + by) as i32).to_le_bytes();
+
+        let mut out = bytes[..12].to_vec();
+        out.extend_from_slice(&padded_len);
+        out.resize(out.len() + by, b' ');
+        out.extend_from_slice(&bytes[16..16 + len]);
+        out.extend_from_slice(&padded_len);
+        out.extend_from_slice(&bytes[16 + len + 4..]);
+        out
+    }
+
+    fn assert_read(file: &[u8], options: &ReadOptions) {
+        let result = read_bytes_with_options(file, options).unwrap();
+        let expected = synth().expected();
+        assert_eq!(result.title, expected.title);
+        let values = |r: &crate::WaveformResult| -> Vec<Vec<f64>> {
+            r.tables[0]
+                .vectors
+                .iter()
+                .map(|v| v.to_real().unwrap().into_owned())
+                .collect()
+        };
+        assert_eq!(values(&result), values(&expected));
+    }
+
+    /// The header text of `padded_file(by)`
+    fn padded_header(by: usize) -> Vec<u8> {
+        let file = padded_file(by);
+        let len = i32::from_le_bytes(file[12..16].try_into().unwrap()) as usize;
+        file[16..16 + len].to_vec()
+    }
+
+    #[test]
+    fn test_standard_layout_matches() {
+        let header = padded_header(0);
+        assert!(HeaderLayout::HSPICE.matches(&header));
+        assert!(!HeaderLayout::HSPICE.shifted("padded", 8).matches(&header));
+        assert_eq!(probe_layout(&header), HeaderLayout::HSPICE);
+    }
+
+    #[test]
+    fn test_forced_layout() {
+        let padded = HeaderLayout::HSPICE.shifted("padded-8", 8);
+        let file = padded_file(8);
+        assert!(read_bytes_with_options(&file, &ReadOptions::new()).is_err());
+
+        assert_read(&file, &ReadOptions::new().header_layout(padded));
+    }
+
+    #[test]
+    fn test_registered_layout_is_probed() {
+        let padded = HeaderLayout::HSPICE.shifted("padded-16", 16);
+        let header = padded_header(16);
+        assert!(!HeaderLayout::HSPICE.matches(&header));
+        assert!(padded.matches(&header));
+        assert_eq!(padded.descriptions, VECTOR_DESCRIPTION_START_POSITION + 16);
+
+        register_header_layout(padded);
+        assert!(registered_header_layouts().contains(&"padded-16"));
+        assert_eq!(probe_layout(&header), padded);
+        assert_read(&padded_file(16), &ReadOptions::new());
+    }
+}
@@ -0,0 +1,368 @@
+//! Lazy per-signal access to HSPICE files
+//!
+//! `LazyWaveform` parses the header and records where each data block's
+//! payload sits when the file is opened. A signal is decoded from the
+//! mapped file only when it is asked for, and only its own column is
+//! converted, so listing the signals of a 10k-signal post-layout run or
+//! pulling out a handful of them costs a fraction of a full `read()`.
+
+use crate::block_reader::ends_data;
+use crate::decode::{ends_with_marker, Blocks, Header, BLOCK_HEADER_LEN};
+use crate::limits::ParserLimits;
+use crate::options::ReadOptions;
+use crate::parser::{header_analysis, header_variables, infer_analysis_type, read_header_bytes};
+use crate::source::FileData;
+use crate::types::{
+    AnalysisType, Endian, PostVersion, Result, ResultExt, Variable, VectorData, WaveformError,
+};
+use num_complex::Complex64;
+use std::ops::Range;
+use tracing::{debug, info, instrument};
+
+/// Data blocks of one sweep table
+#[derive(Debug, Default)]
+struct TableSpan {
+    /// Payload byte range of each block
+    blocks: Vec<Range<usize>>,
+    /// Values in the table, including the sweep value and end marker
+    num_values: usize,
+}
+
+/// HSPICE file whose signals are decoded on demand
+///
+/// ```rust,no_run
+/// use hspice_core::LazyWaveform;
+///
+/// let wave = LazyWaveform::open("postlayout.tr0").unwrap();
+/// println!("{} signals", wave.variables().len());
+/// let vout = wave.get("v(out)").unwrap();
+/// ```
+pub struct LazyWaveform {
+    data: FileData,
+    meta: Header,
+    analysis: AnalysisType,
+    variables: Vec<Variable>,
+    endian: Endian,
+    num_columns: usize,
+    tables: Vec<TableSpan>,
+}
+
+impl LazyWaveform {
+    /// Open a file, reading only its header and block layout
+    pub fn open(filename: &str) -> Result<Self> {
+        Self::open_with_limits(filename, &ParserLimits::unlimited())
+    }
+
+    /// Open a file, enforcing `limits` on the header
+    #[instrument(skip_all, fields(file = %filename))]
+    pub fn open_with_limits(filename: &str, limits: &ParserLimits) -> Result<Self> {
+        let data = FileData::open(filename).with_context(|| format!("opening {}", filename))?;
+        Self::index(data, infer_analysis_type(filename), limits)
+            .with_context(|| format!("reading {}", filename))
+    }
+
+    fn index(data: FileData, fallback: AnalysisType, limits: &ParserLimits) -> Result<Self> {
+        let (meta, data_start) =
+            read_header_bytes(&data, &ReadOptions::from(*limits)).context("reading header")?;
+        let item_size = meta.post_version.value_size();
+        let num_columns = meta.num_columns();
+
+        // Walk the blocks; like the full reader, a malformed head or
+        // trailer ends the data
+        let mut blocks = Blocks::new(&data[data_start..], data_start);
+        let mut endian = Endian::Little;
+        let mut tables = Vec::new();
+        let mut table = TableSpan::default();
+        while tables.len() < meta.sweep_size {
+            let start = data_start + blocks.position();
+            let block = match blocks.next() {
+                Some(Ok(block)) => block,
+                Some(Err(e)) if !ends_data(&e, start) => return Err(e.into()),
+                _ => break,
+            };
+            let num_items = block.payload.len() / item_size;
+            let payload = block.offset + BLOCK_HEADER_LEN;
+            endian = block.endian;
+
+            table.blocks.push(payload..payload + num_items * item_size);
+            table.num_values =
+                table
+                    .num_values
+                    .checked_add(num_items)
+                    .ok_or(WaveformError::SizeOverflow {
+                        what: "data value count",
+                        offset: block.trailer_offset() as u64,
+                    })?;
+            ParserLimits::check("data values", table.num_values, limits.max_values())?;
+            if ends_with_marker(block.payload, meta.post_version, block.endian) {
+                tables.push(std::mem::take(&mut table));
+            }
+        }
+        if !table.blocks.is_empty() {
+            tables.push(table);
+        }
+        if tables.is_empty() {
+            tables.push(TableSpan::default());
+        }
+
+        let analysis = header_analysis(meta.is_complex, &meta.scale_name, fallback);
+        let variables = header_variables(&meta);
+        info!(
+            signals = meta.names.len(),
+            tables = tables.len(),
+            "Lazy waveform indexed"
+        );
+
+        Ok(Self {
+            data,
+            meta,
+            analysis,
+            variables,
+            endian,
+            num_columns,
+            tables,
+        })
+    }
+
+    /// Simulation title
+    pub fn title(&self) -> &str {
+        &self.meta.title
+    }
+
+    /// Simulation date
+    pub fn date(&self) -> &str {
+        &self.meta.date
+    }
+
+    /// Analysis type
+    pub fn analysis(&self) -> AnalysisType {
+        self.analysis
+    }
+
+    /// All variables, scale first
+    pub fn variables(&self) -> &[Variable] {
+        &self.variables
+    }
+
+    /// Scale variable name
+    pub fn scale_name(&self) -> &str {
+        &self.meta.scale_name
+    }
+
+    /// Sweep parameter name, if swept
+    pub fn sweep_param(&self) -> Option<&str> {
+        self.meta.sweep_name.as_deref()
+    }
+
+    /// Number of sweep tables (1 if not swept)
+    pub fn num_tables(&self) -> usize {
+        self.tables.len()
+    }
+
+    /// Sweep value of each table (`None` if not swept)
+    pub fn sweep_values(&self) -> Vec<Option<f64>> {
+        self.tables
+            .iter()
+            .map(|t| {
+                let first = t.blocks.first().filter(|b| !b.is_empty())?;
+                self.meta.sweep_name.as_ref()?;
+                Some(self.value_at(&self.data[first.clone()], 0))
+            })
+            .collect()
+    }
+
+    /// Get variable index by name
+    pub fn var_index(&self, name: &str) -> Option<usize> {
+        self.variables.iter().position(|v| v.name == name)
+    }
+
+    /// Number of points in the first table
+    pub fn len(&self) -> usize {
+        self.num_rows(&self.tables[0])
+    }
+
+    /// Check if the first table has no points
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Decode a signal from the first table
+    pub fn get(&self, name: &str) -> Option<VectorData> {
+        self.get_table(name, 0)
+    }
+
+    /// Decode a signal from sweep table `table`
+    pub fn get_table(&self, name: &str, table: usize) -> Option<VectorData> {
+        let index = self.var_index(name)?;
+        let span = self.tables.get(table)?;
+        debug!(signal = name, table, "Decoding signal");
+
+        // The first `num_variables - 1` signals of a complex file take two
+        // columns each
+        let num_complex = self.meta.num_complex();
+        let column = if index == 0 {
+            0
+        } else {
+            let signal = index - 1;
+            1 + signal + signal.min(num_complex)
+        };
+
+        if index > 0 && index - 1 < num_complex {
+            let re = self.column(span, column);
+            let im = self.column(span, column + 1);
+            Some(VectorData::Complex(
+                re.into_iter()
+                    .zip(im)
+                    .map(|(re, im)| Complex64::new(re, im))
+                    .collect(),
+            ))
+        } else {
+            Some(VectorData::Real(self.column(span, column).into()))
+        }
+    }
+
+    /// Complete rows in a table, leaving out the sweep value and end marker
+    fn num_rows(&self, span: &TableSpan) -> usize {
+        let skip = 1 + usize::from(self.meta.sweep_name.is_some());
+        span.num_values.saturating_sub(skip) / self.num_columns.max(1)
+    }
+
+    /// Decode every row's value in `column`, visiting only those values
+    fn column(&self, span: &TableSpan, column: usize) -> Vec<f64> {
+        let rows = self.num_rows(span);
+        let first_value = usize::from(self.meta.sweep_name.is_some());
+        let mut out = Vec::with_capacity(rows);
+
+        let mut index = column + first_value;
+        let mut block_start = 0;
+        for block in &span.blocks {
+            let payload = &self.data[block.clone()];
+            let block_values = payload.len() / self.item_size();
+            while index < block_start + block_values && out.len() < rows {
+                out.push(self.value_at(payload, index - block_start));
+                index += self.num_columns;
+            }
+            block_start += block_values;
+        }
+        out
+    }
+
+    fn item_size(&self) -> usize {
+        self.meta.post_version.value_size()
+    }
+
+    fn value_at(&self, payload: &[u8], index: usize) -> f64 {
+        match self.meta.post_version {
+            PostVersion::V9007 | PostVersion::V9601 => read_f32(payload, index, self.endian) as f64,
+            PostVersion::V2001 => read_f64(payload, index, self.endian),
+        }
+    }
+}
+
+fn read_f32(payload: &[u8], index: usize, endian: Endian) -> f32 {
+    let b = &payload[index * 4..index * 4 + 4];
+    endian.read_f32([b[0], b[1], b[2], b[3]])
+}
+
+fn read_f64(payload: &[u8], index: usize, endian: Endian) -> f64 {
+    let b = &payload[index * 8..index * 8 + 8];
+    endian.read_f64([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]])
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{temp_path, SyntheticWaveform};
+
+    fn assert_matches_read(synth: SyntheticWaveform, name: &str) {
+        let path = temp_path(name);
+        synth.write_tr0(&path).unwrap();
+        let path = path.to_str().unwrap();
+        let eager = crate::read(path).unwrap();
+        let lazy = LazyWaveform::open(path).unwrap();
+
+        assert_eq!(lazy.num_tables(), eager.tables.len());
+        assert_eq!(lazy.len(), eager.len());
+        assert_eq!(lazy.analysis(), eager.analysis);
+        assert_eq!(
+            lazy.sweep_values(),
+            eager
+                .tables
+                .iter()
+                .map(|t| t.sweep_value)
+                .collect::<Vec<_>>()
+        );
+        for (t, table) in eager.tables.iter().enumerate() {
+            for (var, vector) in eager.variables.iter().zip(&table.vectors) {
+                assert_eq!(
+                    format!("{:?}", lazy.get_table(&var.name, t).unwrap()),
+                    format!("{:?}", vector),
+                    "{} table {}",
+                    var.name,
+                    t
+                );
+            }
+        }
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_matches_full_read_across_blocks() {
+        // Small blocks so rows straddle block boundaries
+        assert_matches_read(
+            SyntheticWaveform::new().signals(5).points(97).block_size(7),
+            "lazy_blocks.tr0",
+        );
+        assert_matches_read(
+            SyntheticWaveform::new()
+                .signals(3)
+                .points(40)
+                .version(PostVersion::V2001)
+                .block_size(11),
+            "lazy_2001.tr0",
+        );
+        assert_matches_read(
+            SyntheticWaveform::new()
+                .signals(3)
+                .points(25)
+                .complex(true)
+                .endian(Endian::Big)
+                .block_size(5),
+            "lazy_complex.ac0",
+        );
+        assert_matches_read(
+            SyntheticWaveform::new().signals(2).points(60).hold(20),
+            "lazy_stepped.tr0",
+        );
+    }
+
+    #[test]
+    fn test_matches_full_read_swept() {
+        assert_matches_read(
+            SyntheticWaveform::new()
+                .signals(2)
+                .points(30)
+                .block_size(9)
+                .sweep("temp", vec![-40.0, 25.0, 125.0]),
+            "lazy_sweep.sw0",
+        );
+    }
+
+    #[test]
+    fn test_unknown_signal_and_table() {
+        let path = temp_path("lazy_missing.tr0");
+        SyntheticWaveform::new()
+            .signals(2)
+            .write_tr0(&path)
+            .unwrap();
+        let lazy = LazyWaveform::open(path.to_str().unwrap()).unwrap();
+        assert!(lazy.get("nope").is_none());
+        assert!(lazy.get_table("n0", 1).is_none());
+        assert_eq!(lazy.variables().len(), 3);
+        std::fs::remove_file(path).ok();
+    }
+}
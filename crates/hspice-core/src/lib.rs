@@ -10,10 +10,59 @@
 //! ## Features
 //!
 //! - Memory-mapped file I/O for efficient large file handling
+//! - Shared, copy-on-write signal storage: clones share values (`SignalBuf`)
 //! - Support for both 9601 (float32) and 2001 (float64) formats
-//! - Streaming reader for processing very large files
-//! - Format conversion to SPICE3 binary raw format
+//! - Streaming reader for processing very large files, by chunk or point by
+//!   point (`RowIterator`)
+//! - `.idx` sidecar index for instant seeking in large files (`index`)
+//! - On-demand decoding of single signals (`LazyWaveform`)
+//! - Format conversion to SPICE3 raw format, binary or ASCII (`RawEncoding`)
+//! - Constant-memory conversion to CSV and JSON Lines (`convert_stream`)
+//! - Validated construction of results from your own data, for the
+//!   exporters (`WaveformResultBuilder`)
+//! - Format auto-detection by content or extension (`read_any`)
+//! - Reader plugins for other formats such as FSDB (`register_format`)
+//! - Resource limits for parsing untrusted input (`ParserLimits`)
+//! - Every `.ALTER` run of a file with back-to-back result sets (`read_alters`)
+//! - Outputs split over `.tr0`, `.tr1`, ... stitched into one (`read_parts`)
+//! - Parallel reads of many files with per-file errors (`read_many`)
+//! - Byte order, precision and trailer-check overrides for damaged or
+//!   foreign files, and recovery of truncated ones (`ReadOptions`)
+//! - Header layout profiles for near-compatible writers such as FineSim
+//!   (`HeaderLayout`, `register_header_layout`)
+//! - Progress callbacks for long reads (`read_with_progress`, `Progress`)
+//! - Waveform measurements (`measure`) and spectral analysis (`dsp`)
+//! - Signal expressions such as `db(v(out)/v(in))` (`WaveformResult::eval`, `expr`)
+//! - Bode gain/phase, margins and bandwidth of AC results (`bode`)
+//! - Min-max, LTTB and stride downsampling for plotting (`decimate`)
+//! - HSPICE measure files, .mt0/.ma0/.ms0 (`measure_file`)
+//! - Operating points from HSPICE .lis listings (`read_lis`)
+//! - Noise spectra and element contributions from .lis listings (`read_noise`)
+//! - `.print` tables from .lis listings and .printtr0 files (`read_print`)
+//! - Glob/regex signal selection (`SignalPattern`, `WaveformResult::select`)
+//! - One-pass signal statistics: min/max/mean/RMS/std (`SignalStats`)
+//! - Stream aggregation: time filters, per-signal maps and folds, and
+//!   min/max/integral summaries without loading the file (`SignalSummary`)
+//! - Monte Carlo distributions and percentiles across samples (`monte_carlo`)
+//! - Signal groups with aggregated derived signals (`groups`)
+//! - Derived signals: scale/offset, dB and v*i power (`WaveformResult::add_derived`)
+//! - Jitter bathtub curves from threshold crossings (`jitter`)
+//! - Eye diagrams with height/width/jitter, fixed or recovered clock (`eye`)
+//! - Time-aligned multi-run overlay export (`overlay`)
+//! - Digitized VCD export for logic viewers (`vcd`)
+//! - Golden comparison of two runs within a tolerance (`compare`)
+//! - JSON and NDJSON export with a documented schema (`json`, feature `json`)
+//! - NumPy .npz export loadable without the native module (`npz`, feature `json`)
+//! - MATLAB .mat export with sweeps as struct arrays (`mat`, feature `mat`)
+//! - `Serialize`/`Deserialize` for results and stream chunks (feature `serde`)
+//! - Block-level integrity checks for truncated or damaged files (`integrity`)
+//! - Header repair for files with mangled title/count fields (`repair`)
+//! - LRU cache of decoded time windows for viewers (`cache`, feature `cache`)
+//! - SHA-256 sidecar files for exports (`checksum`, feature `checksum`)
 //! - Structured logging via `tracing` for diagnostics
+//! - Block and header decoding over byte slices without `std`, for embedded
+//!   and `wasm32-unknown-unknown` targets (`decode`; build with
+//!   `default-features = false`)
 //!
 //! ## Quick Start
 //!
@@ -60,15 +109,115 @@
 //! let result = hspice_core::read("simulation.tr0").unwrap();
 //! ```
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+mod aggregate;
+#[cfg(feature = "std")]
+mod batch;
+#[cfg(feature = "std")]
 mod block_reader;
+#[cfg(feature = "std")]
+pub mod bode;
+#[cfg(feature = "std")]
+mod builder;
+#[cfg(feature = "cache")]
+pub mod cache;
+#[cfg(feature = "checksum")]
+pub mod checksum;
+#[cfg(feature = "std")]
+pub mod compare;
+#[cfg(feature = "conformance")]
+pub mod conformance;
+#[cfg(feature = "std")]
+mod convert;
+#[cfg(feature = "std")]
+pub mod decimate;
+pub mod decode;
+#[cfg(feature = "std")]
+pub mod dsp;
+#[cfg(feature = "std")]
+pub mod expr;
+#[cfg(feature = "std")]
+pub mod eye;
+#[cfg(feature = "std")]
+mod format;
+#[cfg(feature = "std")]
+pub mod groups;
+#[cfg(feature = "std")]
+pub mod index;
+#[cfg(feature = "std")]
+pub mod integrity;
+#[cfg(feature = "std")]
+pub mod jitter;
+#[cfg(feature = "json")]
+pub mod json;
+#[cfg(feature = "std")]
+mod layout;
+#[cfg(feature = "std")]
+mod lazy;
+#[cfg(feature = "std")]
+mod limits;
+#[cfg(feature = "std")]
+mod lis_parser;
+#[cfg(feature = "mat")]
+pub mod mat;
+#[cfg(feature = "std")]
+pub mod measure;
+#[cfg(feature = "std")]
+pub mod measure_file;
+#[cfg(feature = "std")]
+mod monotonic;
+#[cfg(feature = "std")]
+pub mod monte_carlo;
+#[cfg(feature = "std")]
+mod noise_parser;
+#[cfg(feature = "json")]
+pub mod npz;
+#[cfg(feature = "std")]
+mod options;
+#[cfg(feature = "std")]
+pub mod overlay;
+#[cfg(feature = "std")]
 mod parser;
+#[cfg(feature = "std")]
+mod parts;
+#[cfg(feature = "std")]
+mod print_parser;
+#[cfg(feature = "std")]
+mod progress;
+#[cfg(feature = "std")]
 mod raw_parser;
+#[cfg(feature = "std")]
 mod reader;
+#[cfg(feature = "std")]
+pub mod repair;
+#[cfg(feature = "std")]
+mod select;
+#[cfg(feature = "std")]
+mod signal_buf;
+#[cfg(feature = "std")]
+mod source;
+#[cfg(feature = "std")]
+mod stats;
+#[cfg(feature = "std")]
 mod stream;
+#[cfg(feature = "std")]
+mod transform;
+#[cfg(feature = "std")]
 mod types;
+#[cfg(feature = "std")]
+pub mod vcd;
+#[cfg(feature = "std")]
 mod writer;
 
+#[cfg(feature = "testing")]
+pub mod testing;
+
 // Re-export public types
+#[cfg(feature = "std")]
 pub use types::{
     // Core result types
     AnalysisType,
@@ -81,6 +230,8 @@ pub use types::{
     PostVersion,
     // Error types
     Result,
+    ResultExt,
+    Unit,
     VarType,
     Variable,
     VectorData,
@@ -95,13 +246,69 @@ pub use types::{
 };
 
 // Re-export streaming types
+#[cfg(feature = "std")]
+pub use aggregate::SignalSummary;
+#[cfg(feature = "std")]
 pub use stream::{
-    read_stream, read_stream_chunked, read_stream_signals, DataChunk, HspiceStreamReader,
-    StreamMetadata, DEFAULT_CHUNK_SIZE,
+    read_stream, read_stream_bytes, read_stream_chunked, read_stream_signals, DataChunk,
+    HspiceStreamReader, PrefetchReader, RowIterator, StreamMetadata, DEFAULT_CHUNK_SIZE,
 };
 
+// Re-export format detection
+#[cfg(feature = "std")]
+pub use format::{
+    detect_format, detect_format_bytes, find_format, read_any, register_format, registered_formats,
+    FileFormat, WaveformFormat, PLUGIN_PROBE_LEN,
+};
+
+// Re-export split output stitching
+#[cfg(feature = "std")]
+pub use parts::{part_paths, read_parts, read_parts_stream, PartsReader};
+
+// Re-export on-demand signal access
+#[cfg(feature = "std")]
+pub use lazy::LazyWaveform;
+
+// Re-export parallel reads of many files
+#[cfg(feature = "std")]
+pub use batch::{read_many, read_many_with_progress, BatchProgress};
+
+// Re-export parser limits, read options and header layouts
+#[cfg(feature = "std")]
+pub use layout::{register_header_layout, registered_header_layouts, HeaderLayout};
+#[cfg(feature = "std")]
+pub use limits::ParserLimits;
+#[cfg(feature = "std")]
+pub use options::{NonMonotonic, ReadOptions, TextEncoding};
+#[cfg(feature = "std")]
+pub use progress::Progress;
+
+// Re-export shared signal storage
+#[cfg(feature = "std")]
+pub use signal_buf::SignalBuf;
+
+// Re-export signal selection
+#[cfg(feature = "std")]
+pub use select::SignalPattern;
+
+// Re-export signal statistics
+#[cfg(feature = "std")]
+pub use stats::SignalStats;
+
+// Re-export result construction
+#[cfg(feature = "std")]
+pub use builder::WaveformResultBuilder;
+
 // Re-export writer
-pub use writer::write_spice3_raw;
+#[cfg(feature = "std")]
+pub use writer::{
+    write_spice3_raw, write_spice3_raw_to, write_spice3_raw_with, ConversionReport, RawEncoding,
+    RawWriter,
+};
+
+// Re-export streaming conversion
+#[cfg(feature = "std")]
+pub use convert::{convert_reader, convert_stream, ConvertFormat, ConvertOptions};
 
 // ============================================================================
 // Public API Functions
@@ -127,10 +334,107 @@ pub use writer::write_spice3_raw;
 ///     println!("v(out): {} points", vout.len());
 /// }
 /// ```
+#[cfg(feature = "std")]
 pub fn read(filename: &str) -> Result<WaveformResult> {
     parser::hspice_read_impl(filename)
 }
 
+/// Read a waveform file, enforcing resource limits.
+///
+/// Use this for files from untrusted sources. Header counts and data sizes
+/// are checked against `limits` before anything is allocated for them, and
+/// a violation returns `WaveformError::LimitExceeded`.
+///
+/// # Example
+/// ```rust,no_run
+/// use hspice_core::{read_with_limits, ParserLimits};
+///
+/// let result = read_with_limits("upload.tr0", &ParserLimits::untrusted()).unwrap();
+/// ```
+#[cfg(feature = "std")]
+pub fn read_with_limits(filename: &str, limits: &ParserLimits) -> Result<WaveformResult> {
+    parser::hspice_read_with_limits(filename, limits)
+}
+
+/// Read a waveform file, overriding format detection.
+///
+/// For files auto-detection gets wrong: force the byte order or precision,
+/// accept damaged block trailers, or decode header text as Latin-1.
+/// `ReadOptions` also carries the resource limits.
+///
+/// # Example
+/// ```rust,no_run
+/// use hspice_core::{read_with_options, Endian, ReadOptions};
+///
+/// let options = ReadOptions::new().endian(Endian::Big).lenient(true);
+/// let result = read_with_options("damaged.tr0", &options).unwrap();
+/// ```
+#[cfg(feature = "std")]
+pub fn read_with_options(filename: &str, options: &ReadOptions) -> Result<WaveformResult> {
+    parser::hspice_read_with_options(filename, options)
+}
+
+/// Read a waveform file, calling `progress` as its data is read.
+///
+/// `progress` is called about once per 1/1000 of the file, and once more
+/// when the read is complete (see [`Progress`]).
+#[cfg(feature = "std")]
+pub fn read_with_progress<F: FnMut(&Progress)>(
+    filename: &str,
+    options: &ReadOptions,
+    mut progress: F,
+) -> Result<WaveformResult> {
+    parser::hspice_read_with_progress(filename, options, Some(&mut progress))
+}
+
+/// Read every `.ALTER` run of a waveform file.
+///
+/// A netlist with `.ALTER` statements can leave one complete result set per
+/// run back to back in one file; [`read`] returns only the first. This
+/// returns all of them in file order, each with its own header, variables
+/// and sweep tables.
+///
+/// # Example
+/// ```rust,no_run
+/// let runs = hspice_core::read_alters("corners.tr0").unwrap();
+/// for (i, run) in runs.iter().enumerate() {
+///     println!("run {}: {}", i, run.title);
+/// }
+/// ```
+#[cfg(feature = "std")]
+pub fn read_alters(filename: &str) -> Result<Vec<WaveformResult>> {
+    parser::hspice_read_alters(filename, &ReadOptions::default())
+}
+
+/// Read every `.ALTER` run of a waveform file, overriding format detection
+/// as `options` say (see [`read_with_options`]).
+///
+/// Limits apply to each run. With [`ReadOptions::recover`], a truncated run is
+/// the last one returned.
+#[cfg(feature = "std")]
+pub fn read_alters_with_options(
+    filename: &str,
+    options: &ReadOptions,
+) -> Result<Vec<WaveformResult>> {
+    parser::hspice_read_alters(filename, options)
+}
+
+/// Parse an HSPICE binary file held in memory, enforcing resource limits.
+///
+/// The analysis type is taken from the header only, since there is no file
+/// extension to fall back on.
+#[cfg(feature = "std")]
+pub fn read_bytes(data: &[u8], limits: &ParserLimits) -> Result<WaveformResult> {
+    parser::parse_hspice_bytes(data, AnalysisType::Unknown, &ReadOptions::from(*limits))
+}
+
+/// Parse an HSPICE binary file held in memory, overriding format detection
+/// as `options` say (see [`read_with_options`]).
+#[cfg(feature = "std")]
+pub fn read_bytes_with_options(data: &[u8], options: &ReadOptions) -> Result<WaveformResult> {
+    parser::parse_hspice_bytes(data, AnalysisType::Unknown, options)
+}
+
 /// Read a waveform file with debug output.
 ///
 /// # Deprecated
@@ -139,6 +443,7 @@ pub fn read(filename: &str) -> Result<WaveformResult> {
 /// # Arguments
 /// * `filename` - Path to the waveform file
 /// * `debug` - Debug level (ignored, use tracing levels instead)
+#[cfg(feature = "std")]
 #[deprecated(since = "1.4.0", note = "Use read() with tracing subscriber instead")]
 pub fn read_debug(filename: &str, _debug: i32) -> Result<WaveformResult> {
     parser::hspice_read_impl(filename)
@@ -151,9 +456,12 @@ pub fn read_debug(filename: &str, _debug: i32) -> Result<WaveformResult> {
 /// * `output_path` - Path for the output SPICE3 .raw file
 ///
 /// # Returns
-/// * `Ok(())` - Conversion successful
-/// * `Err(WaveformError)` - If conversion fails
-pub fn read_and_convert(input_path: &str, output_path: &str) -> Result<()> {
+/// * `Ok(ConversionReport)` - Points and bytes written, time taken and any
+///   warnings about data the raw format could not hold
+/// * `Err(WaveformError)` - If conversion fails; write failures are
+///   `WriteFailed` with the output path
+#[cfg(feature = "std")]
+pub fn read_and_convert(input_path: &str, output_path: &str) -> Result<ConversionReport> {
     writer::hspice_to_raw_impl(input_path, output_path)
 }
 
@@ -161,16 +469,32 @@ pub fn read_and_convert(input_path: &str, output_path: &str) -> Result<()> {
 ///
 /// # Deprecated
 /// This function is deprecated. Use `read_and_convert()` with a tracing subscriber instead.
+#[cfg(feature = "std")]
 #[deprecated(
     since = "1.4.0",
     note = "Use read_and_convert() with tracing subscriber instead"
 )]
-pub fn read_and_convert_debug(input_path: &str, output_path: &str, _debug: i32) -> Result<()> {
+pub fn read_and_convert_debug(
+    input_path: &str,
+    output_path: &str,
+    _debug: i32,
+) -> Result<ConversionReport> {
     writer::hspice_to_raw_impl(input_path, output_path)
 }
 
 // Re-export header parsing for advanced use
-pub use parser::{parse_header_only, HeaderMetadata};
+#[cfg(feature = "std")]
+pub use parser::{parse_header_only, parse_header_variables, HeaderMetadata};
 
 // Re-export SPICE3 raw file reader
-pub use raw_parser::{read_raw, read_raw_debug};
+#[cfg(feature = "std")]
+#[allow(deprecated)]
+pub use raw_parser::{read_raw, read_raw_bytes, read_raw_debug, read_raw_with_limits};
+
+// Re-export HSPICE listing operating-point, noise and .print readers
+#[cfg(feature = "std")]
+pub use lis_parser::{parse_lis, read_lis};
+#[cfg(feature = "std")]
+pub use noise_parser::{parse_noise, read_noise};
+#[cfg(feature = "std")]
+pub use print_parser::{parse_print, read_print};
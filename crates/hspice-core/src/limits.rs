@@ -0,0 +1,107 @@
+//! Resource limits for parsing untrusted input
+//!
+//! Header fields such as the signal count, sweep count and point count come
+//! straight from the file. A malformed or hostile file can claim billions of
+//! points; `ParserLimits` bounds what the parsers will allocate before they
+//! trust those numbers.
+
+use crate::types::{Result, WaveformError};
+
+/// Upper bounds applied while parsing
+///
+/// The default is unlimited, matching the behaviour of [`crate::read`].
+/// Use [`ParserLimits::untrusted`] for files from outside sources (uploads,
+/// fuzzing, browser input), or set individual fields:
+///
+/// ```rust
+/// use hspice_core::ParserLimits;
+///
+/// let limits = ParserLimits {
+///     max_signals: 1000,
+///     ..ParserLimits::untrusted()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParserLimits {
+    /// Maximum number of vectors (scale + signals) declared in a header
+    pub max_signals: usize,
+    /// Maximum number of points per table
+    pub max_points: usize,
+    /// Maximum number of sweep tables
+    pub max_tables: usize,
+    /// Maximum bytes of decoded sample data held at once
+    pub max_memory: usize,
+}
+
+impl Default for ParserLimits {
+    fn default() -> Self {
+        Self::unlimited()
+    }
+}
+
+impl ParserLimits {
+    /// No limits beyond the structural checks every parse performs
+    pub const fn unlimited() -> Self {
+        Self {
+            max_signals: usize::MAX,
+            max_points: usize::MAX,
+            max_tables: usize::MAX,
+            max_memory: usize::MAX,
+        }
+    }
+
+    /// Conservative limits for input that has not been vetted
+    ///
+    /// 100k signals, 10M points, 10k sweep tables and 1 GiB of sample data.
+    pub const fn untrusted() -> Self {
+        Self {
+            max_signals: 100_000,
+            max_points: 10_000_000,
+            max_tables: 10_000,
+            max_memory: 1 << 30,
+        }
+    }
+
+    /// Number of f64 values that fit in `max_memory`
+    #[inline]
+    pub(crate) fn max_values(&self) -> usize {
+        self.max_memory / std::mem::size_of::<f64>()
+    }
+
+    /// Return `LimitExceeded` if `found` is above `limit`
+    #[inline]
+    pub(crate) fn check(what: &'static str, found: usize, limit: usize) -> Result<()> {
+        if found > limit {
+            return Err(WaveformError::LimitExceeded { what, found, limit });
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_is_unlimited() {
+        assert_eq!(ParserLimits::default(), ParserLimits::unlimited());
+        assert!(ParserLimits::check("signals", usize::MAX, usize::MAX).is_ok());
+    }
+
+    #[test]
+    fn test_check_reports_values() {
+        let err = ParserLimits::check("signals", 11, 10).unwrap_err();
+        assert!(matches!(
+            err,
+            WaveformError::LimitExceeded {
+                what: "signals",
+                found: 11,
+                limit: 10
+            }
+        ));
+    }
+}
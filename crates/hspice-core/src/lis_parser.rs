@@ -0,0 +1,413 @@
+//! HSPICE listing (.lis) operating-point reader
+//!
+//! `.op` results are only written to the listing, as text blocks:
+//!
+//! ```text
+//!  **** operating point information      tnom=  25.000 temp=  25.000 *****
+//!  ***** operating point status is all       simulation time is     0.
+//!     node    =voltage      node    =voltage
+//!
+//!  +0:in      =   0.        0:out     =   1.8000
+//!
+//!  **** mosfets
+//!
+//!  subckt
+//!  element  0:mn1      0:mp1
+//!  model    0:nch      0:pch
+//!  region       Cutoff     Linear
+//!   id         1.2000p   -1.2000p
+//!   vgs        0.        -1.8000
+//!   gm         3.4000n    5.1000u
+//! ```
+//!
+//! Each block becomes one table of a [`WaveformResult`] with
+//! [`AnalysisType::Operating`]. Tables hold a single point: the scale is
+//! `TEMP`, node voltages are named after the node (`out`, like in .tr0
+//! files) and element rows are named `<row>(<element>)`, e.g. `id(mn1)` or
+//! `gm(mp1)`. The `current` and `power` rows of sources become `i(...)` and
+//! `p(...)`. Text rows (`model`, `region`) are skipped. Several `.op` times
+//! give several tables, with the simulation time as the sweep value.
+
+use crate::types::{
+    AnalysisType, DataTable, Result, ResultExt, VarType, Variable, VectorData, WaveformError,
+    WaveformResult,
+};
+use std::path::Path;
+use tracing::debug;
+
+/// Read the operating points of an HSPICE listing
+pub fn read_lis<P: AsRef<Path>>(path: P) -> Result<WaveformResult> {
+    let path = path.as_ref();
+    let text =
+        std::fs::read_to_string(path).with_context(|| format!("opening {}", path.display()))?;
+    parse_lis(&text).with_context(|| format!("reading {}", path.display()))
+}
+
+/// One `operating point information` block
+#[derive(Default)]
+struct OpBlock {
+    temp: f64,
+    time: f64,
+    values: Vec<(String, VarType, f64)>,
+}
+
+/// Where in a block the parser is
+enum Section {
+    /// Between the block header and the node table
+    Header,
+    /// `node = voltage` lines
+    Nodes,
+    /// An element section, with the names of the current column group
+    Elements(Vec<String>),
+}
+
+/// Parse the text of an HSPICE listing
+///
+/// Returns an error if the listing has no operating point.
+pub fn parse_lis(text: &str) -> Result<WaveformResult> {
+    let mut title = String::new();
+    let mut blocks: Vec<OpBlock> = Vec::new();
+    let mut section = Section::Header;
+    let mut subckt_line = "";
+    let mut in_block = false;
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if let Some(header) = trimmed.strip_prefix("****") {
+            let header = header.trim_matches('*').trim().to_ascii_lowercase();
+            if header.starts_with("operating point information") {
+                blocks.push(OpBlock {
+                    temp: keyword_value(&header, "temp=").unwrap_or(25.0),
+                    ..OpBlock::default()
+                });
+                section = Section::Header;
+                in_block = true;
+            } else if header.starts_with("operating point status") {
+                if let (Some(block), Some(time)) = (
+                    blocks.last_mut().filter(|_| in_block),
+                    keyword_value(&header, "simulation time is"),
+                ) {
+                    block.time = time;
+                }
+            } else if header.contains("analysis") || header.starts_with("job") {
+                in_block = false;
+            } else if in_block {
+                section = Section::Elements(Vec::new());
+            }
+            continue;
+        }
+        if title.is_empty() {
+            if let Some(t) = title_line(trimmed) {
+                title = t;
+                continue;
+            }
+        }
+        let Some(block) = blocks.last_mut().filter(|_| in_block) else {
+            continue;
+        };
+
+        let mut tokens = trimmed.split_whitespace();
+        let Some(first) = tokens.next() else {
+            continue;
+        };
+        match &mut section {
+            Section::Header | Section::Nodes if first.starts_with("node") => {
+                section = Section::Nodes;
+            }
+            Section::Header | Section::Nodes if first.starts_with('+') => {
+                section = Section::Nodes;
+                parse_nodes(&trimmed[1..], block)?;
+            }
+            Section::Elements(elements) => match first {
+                "subckt" => subckt_line = line,
+                "element" => {
+                    *elements = element_names(line, std::mem::take(&mut subckt_line));
+                }
+                label if !elements.is_empty() => {
+                    let values: Option<Vec<f64>> = tokens.map(parse_number).collect();
+                    match values {
+                        Some(values) if values.len() == elements.len() => {
+                            let (prefix, var_type) = element_row(label);
+                            for (element, value) in elements.iter().zip(values) {
+                                let name = format!("{}({})", prefix, element);
+                                block.values.push((name, var_type, value));
+                            }
+                        }
+                        // model, region and summary lines
+                        _ => {}
+                    }
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    if blocks.is_empty() {
+        return Err(WaveformError::ParseError(
+            "listing has no operating point information".into(),
+        ));
+    }
+    debug!(blocks = blocks.len(), "Parsed operating points");
+    Ok(into_result(title, blocks))
+}
+
+/// `+0:in = 0. 0:out = 1.8000`, with or without spaces around `=`
+fn parse_nodes(line: &str, block: &mut OpBlock) -> Result<()> {
+    let spaced = line.replace('=', " = ");
+    let tokens: Vec<&str> = spaced.split_whitespace().collect();
+    for entry in tokens.chunks(3) {
+        match entry {
+            [node, "=", value] => {
+                let value = parse_number(value).ok_or_else(|| {
+                    WaveformError::ParseError(format!("invalid voltage {:?} for {}", value, node))
+                })?;
+                block
+                    .values
+                    .push((node_name(node), VarType::Voltage, value));
+            }
+            _ => {
+                return Err(WaveformError::ParseError(format!(
+                    "malformed node voltage line {:?}",
+                    line.trim()
+                )))
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The title of a `.title 'text'` line
+pub(crate) fn title_line(line: &str) -> Option<String> {
+    (line.len() > 7 && line[..7].eq_ignore_ascii_case(".title "))
+        .then(|| line[7..].trim().trim_matches('\'').to_string())
+}
+
+/// Drop the top-level `0:` prefix HSPICE puts on node and element names
+fn node_name(name: &str) -> String {
+    name.strip_prefix("0:").unwrap_or(name).to_ascii_lowercase()
+}
+
+/// Names of an `element` row, qualified with the instance from the
+/// `subckt` row above it
+///
+/// The `subckt` row is blank over top-level elements, so its names are
+/// matched to the element columns by position.
+pub(crate) fn element_names(element_line: &str, subckt_line: &str) -> Vec<String> {
+    let columns: Vec<(usize, &str)> = token_spans(element_line).skip(1).collect();
+    let mut subckts = vec![None; columns.len()];
+    for (start, name) in token_spans(subckt_line).skip(1) {
+        let end = start + name.len();
+        if let Some(col) = columns.iter().rposition(|&(col_start, _)| col_start < end) {
+            subckts[col] = Some(name.to_ascii_lowercase());
+        }
+    }
+
+    columns
+        .iter()
+        .zip(subckts)
+        .map(|(&(_, name), subckt)| {
+            let element = name
+                .split_once(':')
+                .map_or(name, |(_, element)| element)
+                .to_ascii_lowercase();
+            match subckt {
+                Some(subckt) => format!("{}.{}", subckt, element),
+                None => element,
+            }
+        })
+        .collect()
+}
+
+/// Whitespace-separated tokens with their byte offsets
+fn token_spans(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    line.split_whitespace()
+        .map(move |token| (token.as_ptr() as usize - line.as_ptr() as usize, token))
+}
+
+/// Probe prefix and type of an element row label
+fn element_row(label: &str) -> (String, VarType) {
+    let label = label.to_ascii_lowercase();
+    match label.as_str() {
+        "current" => ("i".to_string(), VarType::Current),
+        "power" => ("p".to_string(), VarType::Power),
+        l if l.starts_with('i') => (label, VarType::Current),
+        l if l.starts_with('v') => (label, VarType::Voltage),
+        _ => (label, VarType::Unknown),
+    }
+}
+
+/// Number following `key` in a header, e.g. `temp=  25.000`
+pub(crate) fn keyword_value(header: &str, key: &str) -> Option<f64> {
+    let rest = &header[header.find(key)? + key.len()..];
+    parse_number(rest.split_whitespace().next()?)
+}
+
+/// Number with an optional HSPICE scale suffix (`1.2000m`, `3.3x`, `0.`)
+pub(crate) fn parse_number(token: &str) -> Option<f64> {
+    if let Ok(value) = token.parse::<f64>() {
+        return Some(value);
+    }
+    let lower = token.to_ascii_lowercase();
+    let (mantissa, exponent) = if let Some(m) = lower.strip_suffix("meg") {
+        (m, 6)
+    } else {
+        let exponent = match lower.chars().last()? {
+            'a' => -18,
+            'f' => -15,
+            'p' => -12,
+            'n' => -9,
+            'u' => -6,
+            'm' => -3,
+            'k' => 3,
+            'x' => 6,
+            'g' => 9,
+            't' => 12,
+            _ => return None,
+        };
+        (&lower[..lower.len() - 1], exponent)
+    };
+    // Parse as `<mantissa>e<exponent>` so `12.34u` rounds like `12.34e-6`
+    mantissa.parse::<f64>().ok()?;
+    format!("{}e{}", mantissa, exponent).parse().ok()
+}
+
+/// One table per block; signals missing from a block are NaN
+fn into_result(title: String, blocks: Vec<OpBlock>) -> WaveformResult {
+    let mut variables = vec![Variable::with_type("TEMP", VarType::Unknown)];
+    for block in &blocks {
+        for (name, var_type, _) in &block.values {
+            if !variables[1..].iter().any(|v| &v.name == name) {
+                variables.push(Variable::with_type(name.as_str(), *var_type));
+            }
+        }
+    }
+
+    let tables = blocks
+        .iter()
+        .map(|block| {
+            let mut values = vec![f64::NAN; variables.len()];
+            values[0] = block.temp;
+            for (name, _, value) in &block.values {
+                if let Some(i) = variables[1..].iter().position(|v| &v.name == name) {
+                    values[i + 1] = *value;
+                }
+            }
+            DataTable {
+                sweep_value: Some(block.time),
+                vectors: values
+                    .into_iter()
+                    .map(|v| VectorData::Real(vec![v].into()))
+                    .collect(),
+            }
+        })
+        .collect();
+
+    WaveformResult {
+        title,
+        date: String::new(),
+        analysis: AnalysisType::Operating,
+        variables,
+        sweep_param: Some("time".to_string()),
+        tables,
+        truncated: false,
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::temp_path;
+
+    const LIS: &str = " ****** HSPICE -- P-2019.06-SP1 linux64
+.title 'inverter op'
+ **** operating point information      tnom=  25.000 temp=  85.000 *****
+ ***** operating point status is all       simulation time is     0.
+    node    =voltage      node    =voltage      node    =voltage
+
+ +0:in      =   0.        0:out     =   1.7999  0:vdd     =   1.8000
+ +1:a       = 900.0000m
+
+ **** voltage sources
+
+ subckt
+ element  0:vdd      0:vin
+  volts      1.8000     0.
+  current  -12.3400u    0.
+  power     22.2120u    0.
+
+     total voltage source power dissipation=   22.2120u      watts
+
+ **** mosfets
+
+ subckt                         x1
+ element  0:mn1      1:mp1
+ model    0:nch      1:pch
+ region       Cutoff     Linear
+  id         1.2000p   -1.2000p
+  vgs        0.        -1.8000
+  gm         3.4000n    5.1000u
+
+ **** operating point information      tnom=  25.000 temp=  85.000 *****
+ ***** operating point status is all       simulation time is    10.0000n
+    node    =voltage
+
+ +0:in=1.8000   0:out=2.0000m
+
+ ***** job concluded
+";
+
+    fn value(result: &WaveformResult, table: usize, name: &str) -> f64 {
+        let i = result.var_index(name).unwrap();
+        result.tables[table].vectors[i].real_at(0).unwrap()
+    }
+
+    #[test]
+    fn test_operating_points() {
+        let path = temp_path("lis_parser.lis");
+        std::fs::write(&path, LIS).unwrap();
+        let result = read_lis(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(result.title, "inverter op");
+        assert_eq!(result.analysis, AnalysisType::Operating);
+        assert_eq!(result.scale_name(), "TEMP");
+        assert_eq!(result.tables.len(), 2);
+        assert_eq!(result.tables[1].sweep_value, Some(10e-9));
+        assert_eq!(value(&result, 0, "TEMP"), 85.0);
+
+        assert_eq!(value(&result, 0, "out"), 1.7999);
+        assert_eq!(value(&result, 0, "1:a"), 0.9);
+        assert_eq!(value(&result, 1, "out"), 2e-3);
+        assert!(value(&result, 1, "vdd").is_nan());
+
+        assert_eq!(value(&result, 0, "i(vdd)"), -12.34e-6);
+        assert_eq!(value(&result, 0, "p(vdd)"), 22.212e-6);
+        assert_eq!(value(&result, 0, "gm(x1.mp1)"), 5.1e-6);
+        assert_eq!(value(&result, 0, "id(mn1)"), 1.2e-12);
+        assert!(result.var_index("model(mn1)").is_none());
+
+        let id = &result.variables[result.var_index("id(mn1)").unwrap()];
+        assert_eq!(id.var_type, VarType::Current);
+        let p = &result.variables[result.var_index("p(vdd)").unwrap()];
+        assert_eq!(p.var_type, VarType::Power);
+    }
+
+    #[test]
+    fn test_numbers_and_errors() {
+        assert_eq!(parse_number("0."), Some(0.0));
+        assert_eq!(parse_number("3.3x"), Some(3.3e6));
+        assert_eq!(parse_number("2meg"), Some(2e6));
+        assert_eq!(parse_number("1.m"), Some(1e-3));
+        assert_eq!(parse_number("-1.5f"), Some(-1.5e-15));
+        assert_eq!(parse_number("Cutoff"), None);
+
+        assert!(parse_lis(" ***** job concluded\n").is_err());
+        let bad = " **** operating point information temp= 25.000\n +0:out = abc\n";
+        assert!(parse_lis(bad).is_err());
+    }
+}
@@ -0,0 +1,368 @@
+//! MATLAB .mat export
+//!
+//! [`write_mat`] writes a result as a Level 5 MAT-file, which MATLAB,
+//! Octave and `scipy.io.loadmat` read natively:
+//!
+//! - an unswept result stores each signal as a column vector named after
+//!   it, e.g. `v(out)` becomes `v_out_`
+//! - a swept result stores a 1xN struct array `tables`, one element per
+//!   sweep table, with a field per signal and the `sweep_value`
+//! - `info` is a struct with the `title`, `date`, `analysis`, `sweep_param`
+//!   and the cell arrays `names` (original signal names) and `fields`
+//!   (their MATLAB names, in the same order)
+//!
+//! The file is Level 5 (MATLAB v5/v7, no compression), not the
+//! HDF5-based v7.3. Level 5 stores sizes in 32 bits and MATLAB loads at
+//! most 2 GiB per variable, about 268 million points of a real signal or
+//! half that of a complex one; split larger results by time or signal
+//! first. Elements or dimensions the format cannot hold at all (over 4 GiB
+//! or 2^31 - 1 entries) fail the write with an error instead of producing
+//! a corrupt file.
+//!
+//! Names are sanitized into MATLAB identifiers: characters other than
+//! letters, digits and `_` become `_`, names not starting with a letter get
+//! an `x` prefix, names are cut to 63 characters and clashes get a numeric
+//! suffix.
+//!
+//! ```rust,no_run
+//! let result = hspice_core::read("sweep.sw0").unwrap();
+//! hspice_core::mat::write_mat(&result, "sweep.mat").unwrap();
+//! // MATLAB: load sweep.mat; plot(tables(2).TIME, tables(2).out)
+//! ```
+
+use crate::types::{Result, ResultExt, VectorData, WaveformError, WaveformResult};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// Longest MATLAB identifier
+const MAX_NAME_LEN: usize = 63;
+
+// Data types and array classes of the Level 5 format
+const MI_INT8: u32 = 1;
+const MI_UINT16: u32 = 4;
+const MI_INT32: u32 = 5;
+const MI_UINT32: u32 = 6;
+const MI_DOUBLE: u32 = 9;
+const MI_MATRIX: u32 = 14;
+const MX_CELL_CLASS: u32 = 1;
+const MX_STRUCT_CLASS: u32 = 2;
+const MX_CHAR_CLASS: u32 = 4;
+const MX_DOUBLE_CLASS: u32 = 6;
+const COMPLEX_FLAG: u32 = 0x0800;
+
+/// Write `result` as a MAT-file at `path`
+pub fn write_mat<P: AsRef<Path>>(result: &WaveformResult, path: P) -> Result<()> {
+    let path = path.as_ref();
+    let mut writer =
+        BufWriter::new(File::create(path).with_context(|| format!("creating {}", path.display()))?);
+    to_mat(result, &mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Write `result` as a MAT-file to `writer`
+pub fn to_mat<W: Write>(result: &WaveformResult, writer: &mut W) -> Result<()> {
+    let mut text = format!(
+        "MATLAB 5.0 MAT-file, Platform: hspice-core {}, Created from: {}",
+        env!("CARGO_PKG_VERSION"),
+        result.title.trim()
+    );
+    text.truncate(116);
+    let mut header = text.into_bytes();
+    header.resize(116, b' ');
+    header.extend_from_slice(&[0; 8]); // no subsystem data
+    header.extend_from_slice(&0x0100u16.to_le_bytes());
+    header.extend_from_slice(b"IM");
+    writer.write_all(&header)?;
+
+    let fields = field_names(result);
+    let swept = result.tables.len() > 1;
+    if swept {
+        let mut names = fields.clone();
+        names.push(unique_name("sweep_value", &fields));
+        let elements = result
+            .tables
+            .iter()
+            .map(|table| {
+                let sweep = VectorData::Real(table.sweep_value.into_iter().collect());
+                table
+                    .vectors
+                    .iter()
+                    .chain([&sweep])
+                    .map(|v| double_matrix("", v))
+                    .collect()
+            })
+            .collect::<Result<_>>()?;
+        writer.write_all(&struct_matrix("tables", &names, elements)?)?;
+    } else if let Some(table) = result.tables.first() {
+        for (name, vector) in fields.iter().zip(&table.vectors) {
+            writer.write_all(&double_matrix(name, vector)?)?;
+        }
+    }
+
+    let names = result
+        .variables
+        .iter()
+        .map(|v| char_matrix("", &v.name))
+        .collect::<Result<_>>()?;
+    let matlab_names = fields
+        .iter()
+        .map(|f| char_matrix("", f))
+        .collect::<Result<_>>()?;
+    let info = struct_matrix(
+        "info",
+        &[
+            "title",
+            "date",
+            "analysis",
+            "sweep_param",
+            "names",
+            "fields",
+        ]
+        .map(String::from),
+        vec![vec![
+            char_matrix("", result.title.trim())?,
+            char_matrix("", result.date.trim())?,
+            char_matrix("", &result.analysis.to_string())?,
+            char_matrix("", result.sweep_param.as_deref().unwrap_or(""))?,
+            cell_matrix("", names)?,
+            cell_matrix("", matlab_names)?,
+        ]],
+    )?;
+    writer.write_all(&info)?;
+    Ok(())
+}
+
+/// MATLAB names of the variables, unique and clear of `info` and `tables`
+fn field_names(result: &WaveformResult) -> Vec<String> {
+    let mut taken = vec!["info".to_string(), "tables".to_string()];
+    let mut fields = Vec::with_capacity(result.variables.len());
+    for var in &result.variables {
+        let name = unique_name(&sanitize(&var.name), &taken);
+        taken.push(name.clone());
+        fields.push(name);
+    }
+    fields
+}
+
+/// A valid MATLAB identifier for `name`
+fn sanitize(name: &str) -> String {
+    let mut out: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if !out.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        out.insert(0, 'x');
+    }
+    out.truncate(MAX_NAME_LEN);
+    out
+}
+
+/// `name`, or `name_2`, `name_3`, ... if it is already in `taken`
+fn unique_name(name: &str, taken: &[String]) -> String {
+    let free = |candidate: &str| !taken.iter().any(|t| t.eq_ignore_ascii_case(candidate));
+    if free(name) {
+        return name.to_string();
+    }
+    (2..)
+        .map(|n| {
+            let suffix = format!("_{}", n);
+            let mut base = name.to_string();
+            base.truncate(MAX_NAME_LEN - suffix.len());
+            base + &suffix
+        })
+        .find(|candidate| free(candidate))
+        .expect("unbounded range")
+}
+
+// ============================================================================
+// Level 5 elements
+// ============================================================================
+
+/// Error for an element or dimension the Level 5 format cannot hold
+fn too_large(what: &str) -> WaveformError {
+    WaveformError::FormatError(format!("{} is too large for a Level 5 MAT-file", what))
+}
+
+/// Tagged data element, padded to 8 bytes
+fn push_element(out: &mut Vec<u8>, data_type: u32, data: &[u8]) -> Result<()> {
+    let len = u32::try_from(data.len()).map_err(|_| too_large("An element"))?;
+    out.extend_from_slice(&data_type.to_le_bytes());
+    out.extend_from_slice(&len.to_le_bytes());
+    out.extend_from_slice(data);
+    out.resize(out.len().next_multiple_of(8), 0);
+    Ok(())
+}
+
+/// `miMATRIX` element with the array flags, dimensions and name, followed
+/// by `body`
+fn matrix(name: &str, flags: u32, dims: [usize; 2], body: &[u8]) -> Result<Vec<u8>> {
+    let mut inner = Vec::new();
+    push_element(
+        &mut inner,
+        MI_UINT32,
+        &[flags.to_le_bytes(), 0u32.to_le_bytes()].concat(),
+    )?;
+    let mut dim_bytes = Vec::with_capacity(8);
+    for d in dims {
+        let d = i32::try_from(d).map_err(|_| too_large("A dimension"))?;
+        dim_bytes.extend_from_slice(&d.to_le_bytes());
+    }
+    push_element(&mut inner, MI_INT32, &dim_bytes)?;
+    push_element(&mut inner, MI_INT8, name.as_bytes())?;
+    inner.extend_from_slice(body);
+
+    let mut out = Vec::with_capacity(8 + inner.len());
+    push_element(&mut out, MI_MATRIX, &inner).map_err(|_| too_large(display_name(name)))?;
+    Ok(out)
+}
+
+/// `name` for errors; elements of cells and structs have none
+fn display_name(name: &str) -> &str {
+    if name.is_empty() {
+        "A nested array"
+    } else {
+        name
+    }
+}
+
+/// Column vector of doubles, complex if the vector is
+fn double_matrix(name: &str, vector: &VectorData) -> Result<Vec<u8>> {
+    let mut body = Vec::new();
+    let flags = match vector {
+        VectorData::Complex(v) => {
+            let re: Vec<u8> = v.iter().flat_map(|c| c.re.to_le_bytes()).collect();
+            let im: Vec<u8> = v.iter().flat_map(|c| c.im.to_le_bytes()).collect();
+            push_element(&mut body, MI_DOUBLE, &re)?;
+            push_element(&mut body, MI_DOUBLE, &im)?;
+            MX_DOUBLE_CLASS | COMPLEX_FLAG
+        }
+        other => {
+            let data: Vec<u8> = other
+                .to_real()
+                .unwrap_or_default()
+                .iter()
+                .flat_map(|v| v.to_le_bytes())
+                .collect();
+            push_element(&mut body, MI_DOUBLE, &data)?;
+            MX_DOUBLE_CLASS
+        }
+    };
+    matrix(name, flags, [vector.len(), 1], &body)
+}
+
+/// 1xN character array
+fn char_matrix(name: &str, text: &str) -> Result<Vec<u8>> {
+    let units: Vec<u16> = text.encode_utf16().collect();
+    let data: Vec<u8> = units.iter().flat_map(|u| u.to_le_bytes()).collect();
+    let mut body = Vec::new();
+    push_element(&mut body, MI_UINT16, &data)?;
+    matrix(name, MX_CHAR_CLASS, [1, units.len()], &body)
+}
+
+/// Nx1 cell array of unnamed matrices
+fn cell_matrix(name: &str, items: Vec<Vec<u8>>) -> Result<Vec<u8>> {
+    matrix(name, MX_CELL_CLASS, [items.len(), 1], &items.concat())
+}
+
+/// 1xN struct array; `elements[i][f]` is field `f` of element `i`
+fn struct_matrix(name: &str, fields: &[String], elements: Vec<Vec<Vec<u8>>>) -> Result<Vec<u8>> {
+    let field_len = fields.iter().map(String::len).max().unwrap_or(0) + 1;
+    let mut names = vec![0u8; field_len * fields.len()];
+    for (i, field) in fields.iter().enumerate() {
+        names[i * field_len..i * field_len + field.len()].copy_from_slice(field.as_bytes());
+    }
+
+    let mut body = Vec::new();
+    push_element(&mut body, MI_INT32, &(field_len as i32).to_le_bytes())?;
+    push_element(&mut body, MI_INT8, &names)?;
+    let count = elements.len();
+    for element in elements {
+        body.extend(element.concat());
+    }
+    matrix(name, MX_STRUCT_CLASS, [1, count], &body)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{temp_path, SyntheticWaveform};
+
+    /// Name, class and dimensions of each top-level matrix
+    fn top_level(mat: &[u8]) -> Vec<(String, u32, [i32; 2])> {
+        let u32_at = |i: usize| u32::from_le_bytes(mat[i..i + 4].try_into().unwrap());
+        let mut pos = 128;
+        let mut out = Vec::new();
+        while pos < mat.len() {
+            assert_eq!(u32_at(pos), MI_MATRIX);
+            let size = u32_at(pos + 4) as usize;
+            let class = u32_at(pos + 16) & 0xff;
+            let dims = [u32_at(pos + 32) as i32, u32_at(pos + 36) as i32];
+            let name_len = u32_at(pos + 44) as usize;
+            let name = String::from_utf8(mat[pos + 48..pos + 48 + name_len].to_vec()).unwrap();
+            out.push((name, class, dims));
+            pos += 8 + size;
+        }
+        out
+    }
+
+    #[test]
+    fn test_oversized_dimension_fails() {
+        let dims = [i32::MAX as usize + 1, 1];
+        assert!(matrix("big", MX_DOUBLE_CLASS, dims, &[]).is_err());
+        assert!(matrix("ok", MX_DOUBLE_CLASS, [0, 1], &[]).is_ok());
+    }
+
+    #[test]
+    fn test_names() {
+        assert_eq!(sanitize("v(out)"), "v_out_");
+        assert_eq!(sanitize("1:a"), "x1_a");
+        assert_eq!(sanitize(&"a".repeat(80)).len(), MAX_NAME_LEN);
+        let taken = vec!["v_out_".to_string(), "v_out__2".to_string()];
+        assert_eq!(unique_name("V_OUT_", &taken), "V_OUT__3");
+        assert_eq!(unique_name("info", &[]), "info");
+    }
+
+    #[test]
+    fn test_unswept_variables() {
+        let result = SyntheticWaveform::new().signals(2).points(7).expected();
+        let path = temp_path("mat_unswept.mat");
+        write_mat(&result, &path).unwrap();
+        let mat = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert!(mat.starts_with(b"MATLAB 5.0 MAT-file"));
+        assert_eq!(&mat[124..128], b"\x00\x01IM");
+        let vars = top_level(&mat);
+        let names: Vec<_> = vars.iter().map(|(n, _, _)| n.as_str()).collect();
+        assert_eq!(names, ["TIME", "n0", "n1", "info"]);
+        assert_eq!(vars[1].1, MX_DOUBLE_CLASS);
+        assert_eq!(vars[1].2, [7, 1]);
+        assert_eq!(vars[3].1, MX_STRUCT_CLASS);
+    }
+
+    #[test]
+    fn test_sweep_struct_array() {
+        let result = SyntheticWaveform::new()
+            .signals(1)
+            .points(5)
+            .complex(true)
+            .sweep("temp", vec![25.0, 85.0, 125.0])
+            .expected();
+        let mut mat = Vec::new();
+        to_mat(&result, &mut mat).unwrap();
+
+        let vars = top_level(&mat);
+        assert_eq!(vars.len(), 2);
+        assert_eq!(vars[0], ("tables".to_string(), MX_STRUCT_CLASS, [1, 3]));
+        assert!(mat.len() % 8 == 0);
+        // Field names follow the field name length
+        let fields = String::from_utf8_lossy(&mat[128..400]);
+        assert!(fields.contains("sweep_value"));
+    }
+}
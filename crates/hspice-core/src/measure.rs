@@ -0,0 +1,171 @@
+//! Waveform measurements (crossings, rise/fall time)
+//!
+//! All functions take the scale (`x`, e.g. TIME) and signal (`y`) as plain
+//! slices so they work on table vectors, stream chunks and FFI buffers alike.
+//! Points are linearly interpolated between samples.
+
+/// Edge direction for threshold crossings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edge {
+    /// Signal passes the level going up
+    Rising,
+    /// Signal passes the level going down
+    Falling,
+    /// Either direction
+    Either,
+}
+
+/// Find every point where `y` crosses `level` in the given direction
+///
+/// Returns interpolated `x` values in ascending order. A sample exactly on
+/// the level counts as the end of a crossing, not the start of the next.
+pub fn crossings(x: &[f64], y: &[f64], level: f64, edge: Edge) -> Vec<f64> {
+    let n = x.len().min(y.len());
+    let mut result = Vec::new();
+
+    for i in 1..n {
+        let (y0, y1) = (y[i - 1], y[i]);
+        let rising = y0 < level && y1 >= level;
+        let falling = y0 > level && y1 <= level;
+        let matched = match edge {
+            Edge::Rising => rising,
+            Edge::Falling => falling,
+            Edge::Either => rising || falling,
+        };
+        if matched {
+            result.push(interpolate(x[i - 1], x[i], y0, y1, level));
+        }
+    }
+
+    result
+}
+
+/// Time for the first full rising transition between two fractions of the
+/// signal's swing (e.g. 0.1 and 0.9 for 10%-90%)
+///
+/// Levels are taken relative to the minimum and maximum of `y`. Returns
+/// `None` if the signal is flat, the slices differ in length, or no complete
+/// transition exists.
+pub fn rise_time(x: &[f64], y: &[f64], low_frac: f64, high_frac: f64) -> Option<f64> {
+    transition_time(x, y, low_frac, high_frac, Edge::Rising)
+}
+
+/// Time for the first full falling transition between two fractions of the
+/// signal's swing (`high_frac` is crossed first)
+pub fn fall_time(x: &[f64], y: &[f64], high_frac: f64, low_frac: f64) -> Option<f64> {
+    transition_time(x, y, high_frac, low_frac, Edge::Falling)
+}
+
+/// Propagation delay from `trig` to `targ`, each crossing the same
+/// fraction of its own swing (e.g. 0.5 for a 50%-50% delay)
+///
+/// Measured from the first `trig` crossing to the first `targ` crossing at
+/// or after it, on either edge. Returns `None` if either signal is flat or
+/// `targ` does not cross after `trig`.
+pub fn delay(x: &[f64], trig: &[f64], targ: &[f64], frac: f64) -> Option<f64> {
+    let level = |y: &[f64]| {
+        let (min, max) = min_max(y)?;
+        (max > min).then_some(min + frac * (max - min))
+    };
+    let start = *crossings(x, trig, level(trig)?, Edge::Either).first()?;
+    let end = crossings(x, targ, level(targ)?, Edge::Either)
+        .into_iter()
+        .find(|&t| t >= start)?;
+    Some(end - start)
+}
+
+/// Measure from the last `start` crossing before the first `end` crossing
+fn transition_time(
+    x: &[f64],
+    y: &[f64],
+    start_frac: f64,
+    end_frac: f64,
+    edge: Edge,
+) -> Option<f64> {
+    if x.len() != y.len() {
+        return None;
+    }
+    let (min, max) = min_max(y)?;
+    if max <= min {
+        return None;
+    }
+
+    let level = |frac: f64| min + frac * (max - min);
+    let end = *crossings(x, y, level(end_frac), edge).first()?;
+    let start = crossings(x, y, level(start_frac), edge)
+        .into_iter()
+        .take_while(|&t| t <= end)
+        .last()?;
+
+    Some(end - start)
+}
+
+#[inline]
+fn interpolate(x0: f64, x1: f64, y0: f64, y1: f64, level: f64) -> f64 {
+    x0 + (level - y0) * (x1 - x0) / (y1 - y0)
+}
+
+fn min_max(values: &[f64]) -> Option<(f64, f64)> {
+    let mut iter = values.iter().copied().filter(|v| !v.is_nan());
+    let first = iter.next()?;
+    Some(iter.fold((first, first), |(lo, hi), v| (lo.min(v), hi.max(v))))
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Trapezoid: 0 until t=1, ramps to 1 at t=2, holds, falls to 0 at t=5
+    fn trapezoid() -> (Vec<f64>, Vec<f64>) {
+        let x = vec![0.0, 1.0, 2.0, 4.0, 5.0, 6.0];
+        let y = vec![0.0, 0.0, 1.0, 1.0, 0.0, 0.0];
+        (x, y)
+    }
+
+    #[test]
+    fn test_crossings() {
+        let (x, y) = trapezoid();
+        assert_eq!(crossings(&x, &y, 0.5, Edge::Rising), vec![1.5]);
+        assert_eq!(crossings(&x, &y, 0.5, Edge::Falling), vec![4.5]);
+        assert_eq!(crossings(&x, &y, 0.5, Edge::Either), vec![1.5, 4.5]);
+        assert!(crossings(&x, &y, 2.0, Edge::Either).is_empty());
+    }
+
+    #[test]
+    fn test_crossing_on_sample() {
+        // Touching the level exactly is reported once
+        let x = [0.0, 1.0, 2.0];
+        let y = [0.0, 0.5, 1.0];
+        assert_eq!(crossings(&x, &y, 0.5, Edge::Rising), vec![1.0]);
+    }
+
+    #[test]
+    fn test_rise_and_fall_time() {
+        let (x, y) = trapezoid();
+        let rise = rise_time(&x, &y, 0.1, 0.9).unwrap();
+        let fall = fall_time(&x, &y, 0.9, 0.1).unwrap();
+        assert!((rise - 0.8).abs() < 1e-12);
+        assert!((fall - 0.8).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_delay() {
+        let (x, trig) = trapezoid();
+        // Same trapezoid, inverted and one unit later
+        let targ = [1.0, 1.0, 1.0, 0.0, 0.0, 1.0];
+        let d = delay(&x, &trig, &targ, 0.5).unwrap();
+        assert!((d - 1.5).abs() < 1e-12);
+        assert_eq!(delay(&x, &trig, &[1.0; 6], 0.5), None);
+    }
+
+    #[test]
+    fn test_rise_time_invalid() {
+        assert_eq!(rise_time(&[0.0, 1.0], &[1.0, 1.0], 0.1, 0.9), None);
+        assert_eq!(rise_time(&[0.0, 1.0], &[0.0], 0.1, 0.9), None);
+        assert_eq!(rise_time(&[], &[], 0.1, 0.9), None);
+    }
+}
@@ -0,0 +1,268 @@
+//! HSPICE measure output files (.mt0, .ma0, .ms0)
+//!
+//! HSPICE writes the `.measure` results of a run next to its waveform file:
+//! `.mt#` for transient, `.ma#` for AC and `.ms#` for DC analysis, one
+//! file per `.alter`. The files are ASCII tables:
+//!
+//! ```text
+//! $DATA1 SOURCE='HSPICE' VERSION='P-2019.06' PARAM_COUNT=0
+//! .TITLE '* inverter'
+//!  tpd              trise            temper           alter#
+//!  1.0211e-10       2.3000e-11       25.0000          1
+//!  1.1027e-10       failed           85.0000          1
+//! ```
+//!
+//! Names and rows wrap over several lines. There is one row per sweep point,
+//! in the same order as the tables of the matching waveform file, so row
+//! `i` belongs to `WaveformResult::tables[i]`. A measurement HSPICE could
+//! not make is written as `failed` and read as `None`.
+//!
+//! ```rust,no_run
+//! use hspice_core::measure_file::read_measure;
+//!
+//! let measures = read_measure("inv.mt0").unwrap();
+//! let waves = hspice_core::read("inv.tr0").unwrap();
+//! for (table, tpd) in waves.tables.iter().zip(measures.column("tpd").unwrap()) {
+//!     println!("{:?}: tpd = {:?}", table.sweep_value, tpd);
+//! }
+//! ```
+
+use crate::types::{AnalysisType, Result, ResultExt, WaveformError};
+use std::path::Path;
+
+/// Contents of a measure file
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MeasureResult {
+    /// Simulation title (`.TITLE`)
+    pub title: String,
+    /// Writer and version from the `$DATA1` line (e.g. `HSPICE`, `P-2019.06`)
+    pub source: String,
+    pub version: String,
+    /// Analysis the measurements belong to, from the file extension
+    pub analysis: AnalysisType,
+    /// Number of leading columns that hold swept `.DATA` parameters
+    pub param_count: usize,
+    /// Column names, including `temper` and `alter#`
+    pub names: Vec<String>,
+    /// One row per sweep point; `None` where the measurement failed
+    pub rows: Vec<Vec<Option<f64>>>,
+}
+
+impl MeasureResult {
+    /// Index of a column, ignoring case
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n.eq_ignore_ascii_case(name))
+    }
+
+    /// All values of a column, one per row
+    pub fn column(&self, name: &str) -> Option<Vec<Option<f64>>> {
+        let index = self.column_index(name)?;
+        Some(self.rows.iter().map(|row| row[index]).collect())
+    }
+
+    /// Value of `name` in `row`
+    pub fn get(&self, row: usize, name: &str) -> Option<f64> {
+        let index = self.column_index(name)?;
+        self.rows.get(row)?[index]
+    }
+
+    /// Names of the `.measure` results, without the parameter, `temper` and
+    /// `alter#` columns
+    pub fn measure_names(&self) -> Vec<&str> {
+        self.names
+            .iter()
+            .skip(self.param_count)
+            .map(String::as_str)
+            .filter(|n| !n.eq_ignore_ascii_case("temper") && !n.eq_ignore_ascii_case("alter#"))
+            .collect()
+    }
+
+    /// Number of rows
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Check if there are no rows
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+}
+
+/// Read a measure file; the analysis is taken from the extension
+pub fn read_measure<P: AsRef<Path>>(path: P) -> Result<MeasureResult> {
+    let path = path.as_ref();
+    let text =
+        std::fs::read_to_string(path).with_context(|| format!("opening {}", path.display()))?;
+    parse_measure(&text, analysis_from_path(path))
+        .with_context(|| format!("reading {}", path.display()))
+}
+
+/// Parse the contents of a measure file
+pub fn parse_measure(text: &str, analysis: AnalysisType) -> Result<MeasureResult> {
+    let mut result = MeasureResult {
+        title: String::new(),
+        source: String::new(),
+        version: String::new(),
+        analysis,
+        param_count: 0,
+        names: Vec::new(),
+        rows: Vec::new(),
+    };
+
+    let mut tokens = Vec::new();
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if let Some(header) = trimmed.strip_prefix('$') {
+            parse_data_line(header, &mut result)?;
+        } else if trimmed
+            .get(..6)
+            .is_some_and(|p| p.eq_ignore_ascii_case(".title"))
+        {
+            result.title = trimmed[6..].trim().trim_matches('\'').to_string();
+        } else {
+            tokens.extend(trimmed.split_whitespace());
+        }
+    }
+
+    let names_end = tokens
+        .iter()
+        .position(|t| parse_value(t).is_some())
+        .unwrap_or(tokens.len());
+    if names_end == 0 {
+        return Err(WaveformError::ParseError(
+            "measure file has no column names".into(),
+        ));
+    }
+    result.names = tokens[..names_end].iter().map(|t| t.to_string()).collect();
+
+    let values = &tokens[names_end..];
+    if values.len() % names_end != 0 {
+        return Err(WaveformError::ParseError(format!(
+            "measure file has {} values for {} columns",
+            values.len(),
+            names_end
+        )));
+    }
+    for row in values.chunks(names_end) {
+        let row = row
+            .iter()
+            .map(|t| {
+                parse_value(t).ok_or_else(|| {
+                    WaveformError::ParseError(format!("invalid measure value {:?}", t))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        result.rows.push(row);
+    }
+    Ok(result)
+}
+
+/// `DATA1 SOURCE='HSPICE' VERSION='...' PARAM_COUNT=0`
+fn parse_data_line(header: &str, result: &mut MeasureResult) -> Result<()> {
+    let mut rest = header;
+    while let Some(eq) = rest.find('=') {
+        let key = rest[..eq].split_whitespace().last().unwrap_or("");
+        let after = &rest[eq + 1..];
+        let (value, next) = match after.strip_prefix('\'') {
+            Some(quoted) => {
+                let end = quoted.find('\'').unwrap_or(quoted.len());
+                (&quoted[..end], quoted.get(end + 1..).unwrap_or(""))
+            }
+            None => {
+                let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                (&after[..end], &after[end..])
+            }
+        };
+        match key.to_ascii_uppercase().as_str() {
+            "SOURCE" => result.source = value.to_string(),
+            "VERSION" => result.version = value.to_string(),
+            "PARAM_COUNT" => {
+                result.param_count = value.parse().map_err(|_| {
+                    WaveformError::ParseError(format!("invalid PARAM_COUNT {:?}", value))
+                })?
+            }
+            _ => {}
+        }
+        rest = next;
+    }
+    Ok(())
+}
+
+/// A number, or `failed` as `Some(None)`; `None` for anything else
+fn parse_value(token: &str) -> Option<Option<f64>> {
+    if token.eq_ignore_ascii_case("failed") {
+        return Some(None);
+    }
+    token.parse::<f64>().ok().map(Some)
+}
+
+/// `.mt#` transient, `.ma#` AC, `.ms#` DC
+fn analysis_from_path(path: &Path) -> AnalysisType {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    match ext.get(..2) {
+        Some("mt") => AnalysisType::Transient,
+        Some("ma") => AnalysisType::AC,
+        Some("ms") => AnalysisType::DC,
+        _ => AnalysisType::Unknown,
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::temp_path;
+
+    const MT0: &str = "$DATA1 SOURCE='HSPICE' VERSION='P-2019.06-SP1 linux64' PARAM_COUNT=1
+.TITLE '* inverter delay'
+ vdd              tpd              trise            tfall
+ temper           alter#
+ 1.8000           1.0211e-10       2.3000e-11       failed
+ 25.0000          1.0000
+ 1.8000           1.1027e-10       2.5000e-11       2.2000e-11
+ 85.0000          1.0000
+";
+
+    #[test]
+    fn test_parse_wrapped_table() {
+        let path = temp_path("measure_file.mt0");
+        std::fs::write(&path, MT0).unwrap();
+        let m = read_measure(&path).unwrap();
+
+        assert_eq!(m.title, "* inverter delay");
+        assert_eq!(m.source, "HSPICE");
+        assert_eq!(m.version, "P-2019.06-SP1 linux64");
+        assert_eq!(m.analysis, AnalysisType::Transient);
+        assert_eq!(m.param_count, 1);
+        assert_eq!(m.names.len(), 6);
+        assert_eq!(m.measure_names(), ["tpd", "trise", "tfall"]);
+        assert_eq!(m.len(), 2);
+        assert_eq!(
+            m.column("TPD").unwrap(),
+            [Some(1.0211e-10), Some(1.1027e-10)]
+        );
+        assert_eq!(m.get(0, "tfall"), None);
+        assert_eq!(m.get(1, "tfall"), Some(2.2e-11));
+        assert_eq!(m.get(1, "temper"), Some(85.0));
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_malformed() {
+        assert!(parse_measure("", AnalysisType::AC).is_err());
+        assert!(parse_measure(" a b\n 1.0\n", AnalysisType::AC).is_err());
+        assert!(parse_measure("$DATA1 PARAM_COUNT=x\n a\n 1\n", AnalysisType::AC).is_err());
+        let m = parse_measure(" gain alter#\n 20.5 1\n", AnalysisType::AC).unwrap();
+        assert_eq!(m.get(0, "gain"), Some(20.5));
+        assert_eq!(analysis_from_path(Path::new("amp.ma2")), AnalysisType::AC);
+        assert_eq!(analysis_from_path(Path::new("dc.ms0")), AnalysisType::DC);
+    }
+}
@@ -0,0 +1,192 @@
+//! Cleanup of rows whose scale steps back (see [`NonMonotonic`])
+//!
+//! Used by the parser on each table and by the stream reader on each
+//! block's rows.
+
+use crate::options::NonMonotonic;
+use crate::types::{Result, WaveformError};
+use tracing::debug;
+
+/// Running cleanup state over the rows of one table
+pub(crate) struct ScaleFilter {
+    mode: NonMonotonic,
+    /// Rows seen so far
+    rows: usize,
+    /// Scale of the last row kept
+    last: Option<f64>,
+}
+
+impl ScaleFilter {
+    pub fn new(mode: NonMonotonic) -> Self {
+        Self {
+            mode,
+            rows: 0,
+            last: None,
+        }
+    }
+
+    /// Indices of the rows to keep, given the scales of the table's next
+    /// rows
+    ///
+    /// `KeepLast` only takes back rows of this batch; a row below the last
+    /// row kept from an earlier batch is dropped.
+    pub fn keep(&mut self, scales: impl IntoIterator<Item = f64>) -> Result<Vec<usize>> {
+        let before = self.last;
+        let mut kept: Vec<(usize, f64)> = Vec::new();
+        let mut seen = 0;
+        for (i, scale) in scales.into_iter().enumerate() {
+            let row = self.rows + i;
+            seen += 1;
+            let last = kept.last().map(|&(_, s)| s).or(before);
+            match last {
+                Some(previous) if scale < previous => match self.mode {
+                    NonMonotonic::Keep => kept.push((i, scale)),
+                    NonMonotonic::Drop => {}
+                    NonMonotonic::KeepLast if before.is_some_and(|b| scale < b) => {}
+                    NonMonotonic::KeepLast => {
+                        let superseded = kept.partition_point(|&(_, s)| s < scale);
+                        kept.truncate(superseded);
+                        kept.push((i, scale));
+                    }
+                    NonMonotonic::Error => {
+                        return Err(WaveformError::NonMonotonicScale {
+                            row,
+                            previous,
+                            value: scale,
+                        })
+                    }
+                },
+                _ => kept.push((i, scale)),
+            }
+        }
+
+        let dropped = seen - kept.len();
+        if dropped > 0 {
+            debug!(
+                dropped,
+                from_row = self.rows,
+                "Dropped rows whose scale steps back"
+            );
+        }
+        if let Some(&(_, scale)) = kept.last() {
+            self.last = Some(scale);
+        }
+        self.rows += seen;
+        Ok(kept.into_iter().map(|(i, _)| i).collect())
+    }
+}
+
+/// Keep only the values at `kept`, given in ascending order, in place
+pub(crate) fn keep_rows<T: Copy>(values: &mut Vec<T>, kept: &[usize]) {
+    for (to, &from) in kept.iter().enumerate() {
+        values[to] = values[from];
+    }
+    values.truncate(kept.len());
+}
+
+/// Drop the rows of one table's raw values that step back, in place
+///
+/// `raw` holds `start` leading values (the sweep value), whole rows of
+/// `columns` values, and at least `trailing` values after them (the end
+/// marker, a cut-off row), which are kept.
+pub(crate) fn clean_table(
+    raw: &mut Vec<f64>,
+    start: usize,
+    trailing: usize,
+    columns: usize,
+    mode: NonMonotonic,
+) -> Result<()> {
+    if mode == NonMonotonic::Keep || columns == 0 {
+        return Ok(());
+    }
+    let rows = raw.len().saturating_sub(start + trailing) / columns;
+    let scales = (0..rows).map(|r| raw[start + r * columns]);
+    let kept = ScaleFilter::new(mode).keep(scales.collect::<Vec<_>>())?;
+    if kept.len() == rows {
+        return Ok(());
+    }
+
+    for (to, &from) in kept.iter().enumerate() {
+        if to != from {
+            let from = start + from * columns;
+            raw.copy_within(from..from + columns, start + to * columns);
+        }
+    }
+    let end = start + rows * columns;
+    raw.drain(start + kept.len() * columns..end);
+    Ok(())
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Scale then one signal of 10 x scale, after a sweep value, with the
+    /// end marker behind
+    fn table(scales: &[f64]) -> Vec<f64> {
+        let mut raw = vec![25.0];
+        for &t in scales {
+            raw.extend([t, 10.0 * t]);
+        }
+        raw.push(1e30);
+        raw
+    }
+
+    fn cleaned(scales: &[f64], mode: NonMonotonic) -> Result<Vec<f64>> {
+        let mut raw = table(scales);
+        clean_table(&mut raw, 1, 1, 2, mode)?;
+        assert_eq!((raw[0], raw[raw.len() - 1]), (25.0, 1e30));
+        assert!(raw[1..raw.len() - 1]
+            .chunks(2)
+            .all(|row| row[1] == 10.0 * row[0]));
+        Ok(raw[1..raw.len() - 1].iter().step_by(2).copied().collect())
+    }
+
+    #[test]
+    fn test_clean_table_modes() {
+        let backtracked = [0.0, 1.0, 2.0, 3.0, 1.5, 2.5, 4.0];
+        assert_eq!(
+            cleaned(&backtracked, NonMonotonic::Keep).unwrap(),
+            backtracked
+        );
+        assert_eq!(
+            cleaned(&backtracked, NonMonotonic::Drop).unwrap(),
+            [0.0, 1.0, 2.0, 3.0, 4.0]
+        );
+        assert_eq!(
+            cleaned(&backtracked, NonMonotonic::KeepLast).unwrap(),
+            [0.0, 1.0, 1.5, 2.5, 4.0]
+        );
+        let error = cleaned(&backtracked, NonMonotonic::Error).unwrap_err();
+        assert!(matches!(
+            error,
+            WaveformError::NonMonotonicScale { row: 4, previous, value }
+                if previous == 3.0 && value == 1.5
+        ));
+        // Repeated points are not a step back
+        assert_eq!(
+            cleaned(&[0.0, 1.0, 1.0, 2.0], NonMonotonic::Error).unwrap(),
+            [0.0, 1.0, 1.0, 2.0]
+        );
+    }
+
+    #[test]
+    fn test_filter_across_batches() {
+        let mut filter = ScaleFilter::new(NonMonotonic::KeepLast);
+        assert_eq!(filter.keep([0.0, 1.0, 2.0]).unwrap(), [0, 1, 2]);
+        // 1.5 is behind the previous batch: dropped; 3.0 and 2.5 are not
+        assert_eq!(filter.keep([1.5, 3.0, 2.5, 4.0]).unwrap(), [2, 3]);
+
+        let mut filter = ScaleFilter::new(NonMonotonic::Error);
+        filter.keep([0.0, 1.0]).unwrap();
+        let error = filter.keep([2.0, 0.5]).unwrap_err();
+        assert!(matches!(
+            error,
+            WaveformError::NonMonotonicScale { row: 3, .. }
+        ));
+    }
+}
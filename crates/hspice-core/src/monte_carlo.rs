@@ -0,0 +1,286 @@
+//! Monte Carlo distributions across sweep tables
+//!
+//! HSPICE writes each Monte Carlo sample as one sweep table (sweep parameter
+//! `MONTE_CARLO`). [`WaveformResult::monte_carlo`] samples every signal at
+//! chosen scale points in every table and summarizes the spread across
+//! samples:
+//!
+//! ```rust,no_run
+//! let result = hspice_core::read("mc.tr0").unwrap();
+//! let mc = result.monte_carlo(&[1e-9, 5e-9]).unwrap();
+//! let out = mc.get("out").unwrap();
+//! for (t, d) in mc.points.iter().zip(&out.at) {
+//!     println!("{}: mean {} sigma {} p99 {:?}", t, d.mean, d.sigma, d.percentile(99.0));
+//! }
+//! ```
+//!
+//! Any swept result works the same way, with one sample per table.
+
+use crate::overlay::sample_at;
+use crate::types::{Result, VectorData, WaveformError, WaveformResult};
+use std::borrow::Cow;
+use std::io::Write;
+
+/// Values of one signal at one scale point across samples
+#[derive(Debug, Clone, PartialEq)]
+pub struct Distribution {
+    /// One value per sample, in table order; NaN where the point is outside
+    /// the sample's scale
+    pub values: Vec<f64>,
+    /// Number of non-NaN values
+    pub count: usize,
+    pub mean: f64,
+    /// Population standard deviation
+    pub sigma: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Distribution {
+    /// Summarize `values`; the statistics are NaN if every value is NaN
+    pub fn new(values: Vec<f64>) -> Self {
+        let stats = VectorData::Real(values.clone().into()).stats();
+        let nan = f64::NAN;
+        Self {
+            count: stats.map_or(0, |s| s.count),
+            mean: stats.map_or(nan, |s| s.mean),
+            sigma: stats.map_or(nan, |s| s.std),
+            min: stats.map_or(nan, |s| s.min),
+            max: stats.map_or(nan, |s| s.max),
+            values,
+        }
+    }
+
+    /// The `p`-th percentile (0 to 100) of the non-NaN values, linearly
+    /// interpolated between ranks like NumPy's default; None if there is
+    /// no value
+    pub fn percentile(&self, p: f64) -> Option<f64> {
+        let mut sorted: Vec<f64> = self
+            .values
+            .iter()
+            .copied()
+            .filter(|v| !v.is_nan())
+            .collect();
+        if sorted.is_empty() {
+            return None;
+        }
+        sorted.sort_by(f64::total_cmp);
+        let rank = p.clamp(0.0, 100.0) / 100.0 * (sorted.len() - 1) as f64;
+        let (lo, hi) = (rank.floor() as usize, rank.ceil() as usize);
+        Some(sorted[lo] + (sorted[hi] - sorted[lo]) * (rank - lo as f64))
+    }
+
+    /// The 50th percentile
+    pub fn median(&self) -> Option<f64> {
+        self.percentile(50.0)
+    }
+}
+
+/// Distributions of one signal, one per scale point
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignalDistributions {
+    pub name: String,
+    /// In the order of [`MonteCarlo::points`]
+    pub at: Vec<Distribution>,
+}
+
+/// Every signal's distribution at the chosen scale points
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonteCarlo {
+    /// Sweep value of each sample, or its table index if the table has none
+    pub samples: Vec<f64>,
+    /// Scale points the signals were sampled at
+    pub points: Vec<f64>,
+    /// One entry per signal, in variable order without the scale
+    pub signals: Vec<SignalDistributions>,
+}
+
+impl MonteCarlo {
+    /// Distributions of the signal called `name`
+    pub fn get(&self, name: &str) -> Option<&SignalDistributions> {
+        self.signals.iter().find(|s| s.name == name)
+    }
+
+    /// Write one CSV row per signal and point with its count, mean, sigma,
+    /// min, max and 1st/5th/50th/95th/99th percentiles
+    pub fn write_csv<W: Write>(&self, writer: &mut W) -> Result<()> {
+        const PERCENTILES: [f64; 5] = [1.0, 5.0, 50.0, 95.0, 99.0];
+        write!(writer, "signal,point,count,mean,sigma,min,max")?;
+        for p in PERCENTILES {
+            write!(writer, ",p{}", p)?;
+        }
+        writeln!(writer)?;
+        for signal in &self.signals {
+            for (point, d) in self.points.iter().zip(&signal.at) {
+                write!(
+                    writer,
+                    "{},{},{},{},{},{},{}",
+                    signal.name, point, d.count, d.mean, d.sigma, d.min, d.max
+                )?;
+                for p in PERCENTILES {
+                    write!(writer, ",{}", d.percentile(p).unwrap_or(f64::NAN))?;
+                }
+                writeln!(writer)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl WaveformResult {
+    /// Whether the tables are Monte Carlo samples (sweep parameter
+    /// `MONTE_CARLO`, in any case)
+    pub fn is_monte_carlo(&self) -> bool {
+        self.sweep_param
+            .as_deref()
+            .is_some_and(|p| p.eq_ignore_ascii_case("monte_carlo"))
+    }
+
+    /// Distribution of every signal at each of `points` across the tables
+    ///
+    /// Each table is one sample. Values are linearly interpolated on the
+    /// table's own scale; complex signals are taken by magnitude. Returns
+    /// an error if there is no table or a scale is not real.
+    pub fn monte_carlo(&self, points: &[f64]) -> Result<MonteCarlo> {
+        if self.tables.is_empty() {
+            return Err(WaveformError::ParseError(
+                "result has no tables to sample".into(),
+            ));
+        }
+        let signals = self.variables.len().saturating_sub(1);
+        // values[signal][point][sample]
+        let mut values = vec![vec![Vec::with_capacity(self.tables.len()); points.len()]; signals];
+        let mut samples = Vec::with_capacity(self.tables.len());
+
+        for (index, table) in self.tables.iter().enumerate() {
+            samples.push(table.sweep_value.unwrap_or(index as f64));
+            let scale = table
+                .vectors
+                .first()
+                .and_then(VectorData::to_real)
+                .ok_or_else(|| {
+                    WaveformError::ParseError(format!("table {}: scale is not real", index))
+                })?;
+            for (signal, vector) in values.iter_mut().zip(&table.vectors[1..]) {
+                let data = match vector {
+                    VectorData::Complex(v) => Cow::Owned(v.iter().map(|z| z.norm()).collect()),
+                    other => other.to_real().unwrap_or_default(),
+                };
+                let mut cursor = 0;
+                for (at, &t) in signal.iter_mut().zip(points) {
+                    if cursor > 0 && t < scale[cursor] {
+                        cursor = 0;
+                    }
+                    at.push(sample_at(&scale, &data, t, &mut cursor).unwrap_or(f64::NAN));
+                }
+            }
+        }
+
+        let signals = self
+            .variables
+            .iter()
+            .skip(1)
+            .zip(values)
+            .map(|(var, at)| SignalDistributions {
+                name: var.name.clone(),
+                at: at.into_iter().map(Distribution::new).collect(),
+            })
+            .collect();
+        Ok(MonteCarlo {
+            samples,
+            points: points.to_vec(),
+            signals,
+        })
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{AnalysisType, DataTable, Variable};
+
+    /// `out = gain * t` over 0..=4, one table per gain, the last sampled
+    /// at half the step
+    fn samples(gains: &[f64]) -> WaveformResult {
+        let tables = gains
+            .iter()
+            .enumerate()
+            .map(|(i, &gain)| {
+                let step = if i + 1 == gains.len() { 0.5 } else { 1.0 };
+                let time: Vec<f64> = (0..=(4.0 / step) as usize)
+                    .map(|k| k as f64 * step)
+                    .collect();
+                let out = time.iter().map(|t| gain * t).collect();
+                DataTable {
+                    sweep_value: Some(i as f64 + 1.0),
+                    vectors: vec![VectorData::Real(time.into()), VectorData::Real(out)],
+                }
+            })
+            .collect();
+        WaveformResult {
+            title: String::new(),
+            date: String::new(),
+            analysis: AnalysisType::Transient,
+            variables: vec![Variable::new("TIME"), Variable::new("out")],
+            sweep_param: Some("MONTE_CARLO".into()),
+            tables,
+            truncated: false,
+        }
+    }
+
+    #[test]
+    fn test_distributions_at_points() {
+        let result = samples(&[1.0, 2.0, 3.0, 4.0]);
+        assert!(result.is_monte_carlo());
+        let mc = result.monte_carlo(&[2.5, 1.0, 9.0]).unwrap();
+        assert_eq!(mc.samples, [1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(mc.signals.len(), 1);
+
+        let out = &mc.get("out").unwrap().at;
+        assert_eq!(out[0].values, [2.5, 5.0, 7.5, 10.0]);
+        assert_eq!(out[0].count, 4);
+        assert_eq!(out[0].mean, 6.25);
+        assert!((out[0].sigma - 1.25 * 5f64.sqrt()).abs() < 1e-12);
+        assert_eq!((out[0].min, out[0].max), (2.5, 10.0));
+        // Points need not be ascending
+        assert_eq!(out[1].values, [1.0, 2.0, 3.0, 4.0]);
+        // Outside every scale
+        assert_eq!(out[2].count, 0);
+        assert!(out[2].mean.is_nan());
+        assert_eq!(out[2].median(), None);
+    }
+
+    #[test]
+    fn test_percentiles_and_csv() {
+        let d = Distribution::new(vec![4.0, f64::NAN, 1.0, 3.0, 2.0, 5.0]);
+        assert_eq!(d.count, 5);
+        assert_eq!(d.percentile(0.0), Some(1.0));
+        assert_eq!(d.median(), Some(3.0));
+        assert!((d.percentile(90.0).unwrap() - 4.6).abs() < 1e-12);
+        assert_eq!(d.percentile(100.0), Some(5.0));
+
+        let mc = samples(&[1.0, 3.0]).monte_carlo(&[2.0]).unwrap();
+        let mut csv = Vec::new();
+        mc.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "signal,point,count,mean,sigma,min,max,p1,p5,p50,p95,p99"
+        );
+        assert!(lines[1].starts_with("out,2,2,4,2,2,6,"));
+        assert_eq!(lines.len(), 2);
+    }
+
+    #[test]
+    fn test_empty_result() {
+        let mut result = samples(&[1.0]);
+        assert!(result.monte_carlo(&[]).unwrap().signals[0].at.is_empty());
+        result.tables.clear();
+        assert!(result.monte_carlo(&[1.0]).is_err());
+    }
+}
@@ -0,0 +1,341 @@
+//! HSPICE listing (.lis) noise analysis reader
+//!
+//! `.noise` results are printed to the listing for every frequency of the
+//! `.ac` sweep:
+//!
+//! ```text
+//!  **** noise analysis                  tnom=  25.000 temp=  25.000 *****
+//!       frequency =   1.0000k hz
+//!
+//!  **** mosfet squared noise voltages (sq v/hz)
+//!
+//!  element   0:m1       0:m2
+//!   rd       0.         0.
+//!   id       1.2345f    2.3456f
+//!   total    1.2345f    2.3456f
+//!
+//!  **** total output noise voltage          =   3.5801f   sq v/hz
+//!                                           =  59.8338n   v/rt hz
+//!       transfer function value:
+//!       v(out)/vin                          =  10.0000
+//!       equivalent input noise at vin       =   5.9834n   /rt hz
+//! ```
+//!
+//! The points become one table of a [`WaveformResult`] with
+//! [`AnalysisType::Noise`] and the scale `HERTZ`. `onoise` is the output
+//! noise in V/rtHz, `inoise` the equivalent input noise (V/rtHz for a
+//! voltage source input, A/rtHz for a current source), and element
+//! contributions are named `<row>(<element>)` in V^2/Hz, e.g. `total(m1)`
+//! or `id(x1.m2)`. A sweep that starts again from a lower frequency
+//! (`.alter`, temperature or parameter sweeps) starts a new table, with
+//! the temperature as the sweep value.
+
+use crate::lis_parser::{element_names, keyword_value, parse_number, title_line};
+use crate::types::{
+    AnalysisType, DataTable, Result, ResultExt, Unit, VarType, Variable, VectorData, WaveformError,
+    WaveformResult,
+};
+use std::path::Path;
+use tracing::debug;
+
+/// Read the noise analysis results of an HSPICE listing
+pub fn read_noise<P: AsRef<Path>>(path: P) -> Result<WaveformResult> {
+    let path = path.as_ref();
+    let text =
+        std::fs::read_to_string(path).with_context(|| format!("opening {}", path.display()))?;
+    parse_noise(&text).with_context(|| format!("reading {}", path.display()))
+}
+
+/// One frequency point
+struct NoisePoint {
+    frequency: f64,
+    values: Vec<(String, f64)>,
+}
+
+/// Points of one sweep
+struct NoiseRun {
+    temp: f64,
+    points: Vec<NoisePoint>,
+}
+
+/// Parse the noise analysis results in the text of an HSPICE listing
+///
+/// Returns an error if the listing has no noise analysis point.
+pub fn parse_noise(text: &str) -> Result<WaveformResult> {
+    let mut title = String::new();
+    let mut runs: Vec<NoiseRun> = Vec::new();
+    let mut temp = 25.0;
+    let mut in_noise = false;
+    let mut elements: Vec<String> = Vec::new();
+    let mut subckt_line = "";
+    let mut inoise_unit = None;
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if let Some(header) = trimmed.strip_prefix("****") {
+            let header = header.trim_matches('*').trim().to_ascii_lowercase();
+            elements.clear();
+            if header.starts_with("noise analysis") {
+                temp = keyword_value(&header, "temp=").unwrap_or(25.0);
+                in_noise = true;
+            } else if header.starts_with("total output noise voltage") {
+                // The squared value; the next line repeats it per root hertz
+                let point = runs.last_mut().and_then(|r| r.points.last_mut());
+                if let (Some(point), Some(value)) = (point.filter(|_| in_noise), after_eq(&header))
+                {
+                    point.values.push(("onoise".to_string(), value.sqrt()));
+                }
+            } else if header.contains("analysis") || header.starts_with("job") {
+                in_noise = false;
+            }
+            continue;
+        }
+        if title.is_empty() {
+            if let Some(t) = title_line(trimmed) {
+                title = t;
+                continue;
+            }
+        }
+        if !in_noise {
+            continue;
+        }
+
+        let lower = trimmed.to_ascii_lowercase();
+        let mut tokens = trimmed.split_whitespace();
+        let Some(first) = tokens.next() else {
+            continue;
+        };
+        if first.eq_ignore_ascii_case("frequency") {
+            let frequency = after_eq(&lower).ok_or_else(|| {
+                WaveformError::ParseError(format!("invalid noise frequency line {:?}", trimmed))
+            })?;
+            let new_run = runs
+                .last()
+                .and_then(|r| r.points.last())
+                .is_none_or(|p| frequency <= p.frequency);
+            if new_run {
+                runs.push(NoiseRun {
+                    temp,
+                    points: Vec::new(),
+                });
+            }
+            if let Some(run) = runs.last_mut() {
+                run.points.push(NoisePoint {
+                    frequency,
+                    values: Vec::new(),
+                });
+            }
+            continue;
+        }
+        let Some(point) = runs.last_mut().and_then(|r| r.points.last_mut()) else {
+            continue;
+        };
+
+        if let Some(rest) = lower.strip_prefix("equivalent input noise at") {
+            if let Some(value) = after_eq(rest) {
+                let source = rest.split('=').next().unwrap_or("").trim();
+                let source = source.rsplit(['.', ':']).next().unwrap_or(source);
+                inoise_unit = Some(if source.starts_with('i') {
+                    Unit::AmperePerRootHertz
+                } else {
+                    Unit::VoltPerRootHertz
+                });
+                point.values.push(("inoise".to_string(), value));
+            }
+        } else if first.eq_ignore_ascii_case("subckt") {
+            subckt_line = line;
+        } else if first.eq_ignore_ascii_case("element") {
+            elements = element_names(line, std::mem::take(&mut subckt_line));
+        } else if !elements.is_empty() {
+            let values: Option<Vec<f64>> = tokens.map(parse_number).collect();
+            if let Some(values) = values.filter(|v| v.len() == elements.len()) {
+                let label = first.to_ascii_lowercase();
+                for (element, value) in elements.iter().zip(values) {
+                    point
+                        .values
+                        .push((format!("{}({})", label, element), value));
+                }
+            }
+        }
+    }
+
+    if runs.is_empty() {
+        return Err(WaveformError::ParseError(
+            "listing has no noise analysis results".into(),
+        ));
+    }
+    debug!(
+        runs = runs.len(),
+        points = runs[0].points.len(),
+        "Parsed noise analysis"
+    );
+    Ok(into_result(title, runs, inoise_unit))
+}
+
+/// The number after the first `=`, e.g. `=   3.5801f   sq v/hz`
+fn after_eq(text: &str) -> Option<f64> {
+    let (_, rest) = text.split_once('=')?;
+    parse_number(rest.split_whitespace().next()?)
+}
+
+/// One table per run; values missing at a point are NaN
+fn into_result(title: String, runs: Vec<NoiseRun>, inoise_unit: Option<Unit>) -> WaveformResult {
+    let mut variables = vec![Variable::with_type("HERTZ", VarType::Frequency)];
+    for point in runs.iter().flat_map(|r| &r.points) {
+        for (name, _) in &point.values {
+            if !variables[1..].iter().any(|v| &v.name == name) {
+                let unit = match name.as_str() {
+                    "onoise" => Some(Unit::VoltPerRootHertz),
+                    "inoise" => inoise_unit,
+                    _ => Some(Unit::SquareVoltPerHertz),
+                };
+                variables.push(Variable::with_type(name.as_str(), VarType::Noise).with_unit(unit));
+            }
+        }
+    }
+    // onoise and inoise first, in that order
+    variables[1..].sort_by_key(|v| match v.name.as_str() {
+        "onoise" => 0,
+        "inoise" => 1,
+        _ => 2,
+    });
+
+    let swept = runs.len() > 1;
+    let tables = runs
+        .iter()
+        .map(|run| {
+            let mut columns = vec![vec![f64::NAN; run.points.len()]; variables.len()];
+            for (row, point) in run.points.iter().enumerate() {
+                columns[0][row] = point.frequency;
+                for (name, value) in &point.values {
+                    if let Some(i) = variables.iter().position(|v| &v.name == name) {
+                        columns[i][row] = *value;
+                    }
+                }
+            }
+            DataTable {
+                sweep_value: swept.then_some(run.temp),
+                vectors: columns
+                    .into_iter()
+                    .map(|v| VectorData::Real(v.into()))
+                    .collect(),
+            }
+        })
+        .collect();
+
+    WaveformResult {
+        title,
+        date: String::new(),
+        analysis: AnalysisType::Noise,
+        variables,
+        sweep_param: swept.then(|| "temp".to_string()),
+        tables,
+        truncated: false,
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::temp_path;
+
+    fn block(temp: f64, frequency: &str, id: &str, inoise: &str) -> String {
+        format!(
+            " **** noise analysis                  tnom=  25.000 temp= {temp} *****
+      frequency =   {frequency} hz
+
+ **** mosfet squared noise voltages (sq v/hz)
+
+ subckt                         x1
+ element   0:m1       1:m2
+ model     0:nch      1:pch
+  rd       0.         0.
+  id       {id}       2.0000f
+  fn       1.0000f    0.
+  total    3.0000f    2.0000f
+
+ **** resistor squared noise voltages (sq v/hz)
+
+ element   0:rl
+  total    4.0000f
+
+ **** total output noise voltage          =   9.0000f   sq v/hz
+                                          =  94.8683n   v/rt hz
+      transfer function value:
+      v(out)/vin                          =  10.0000
+      equivalent input noise at vin       =   {inoise}   /rt hz
+
+"
+        )
+    }
+
+    fn listing() -> String {
+        let mut text = " ****** HSPICE -- P-2019.06-SP1 linux64\n.title 'lna noise'\n".to_string();
+        text += &block(25.0, "1.0000k", "2.0000f", "9.4868n");
+        text += &block(25.0, "10.0000k", "1.0000f", "9.0000n");
+        text += &block(85.0, "1.0000k", "3.0000f", "9.9000n");
+        text += " ***** job concluded\n";
+        text
+    }
+
+    fn column(result: &WaveformResult, table: usize, name: &str) -> Vec<f64> {
+        let i = result.var_index(name).unwrap();
+        result.tables[table].vectors[i]
+            .to_real()
+            .unwrap()
+            .into_owned()
+    }
+
+    #[test]
+    fn test_noise_listing() {
+        let path = temp_path("noise_parser.lis");
+        std::fs::write(&path, listing()).unwrap();
+        let result = read_noise(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(result.title, "lna noise");
+        assert_eq!(result.analysis, AnalysisType::Noise);
+        assert_eq!(result.scale_name(), "HERTZ");
+        assert_eq!(result.var_names()[1..3], ["onoise", "inoise"]);
+        assert_eq!(result.sweep_param.as_deref(), Some("temp"));
+        assert_eq!(result.tables.len(), 2);
+        assert_eq!(result.tables[1].sweep_value, Some(85.0));
+
+        assert_eq!(column(&result, 0, "HERTZ"), [1e3, 1e4]);
+        assert_eq!(column(&result, 0, "onoise"), [9e-15f64.sqrt(); 2]);
+        assert_eq!(column(&result, 0, "inoise"), [9.4868e-9, 9e-9]);
+        assert_eq!(column(&result, 0, "id(m1)"), [2e-15, 1e-15]);
+        assert_eq!(column(&result, 1, "id(m1)"), [3e-15]);
+        assert_eq!(column(&result, 0, "total(x1.m2)"), [2e-15, 2e-15]);
+        assert_eq!(column(&result, 0, "total(rl)"), [4e-15, 4e-15]);
+        assert!(result.var_index("model(m1)").is_none());
+
+        let unit = |name: &str| result.variables[result.var_index(name).unwrap()].unit;
+        assert_eq!(unit("onoise"), Some(Unit::VoltPerRootHertz));
+        assert_eq!(unit("inoise"), Some(Unit::VoltPerRootHertz));
+        assert_eq!(unit("fn(m1)"), Some(Unit::SquareVoltPerHertz));
+        assert_eq!(unit("HERTZ"), Some(Unit::Hertz));
+        assert!(result.variables[1..]
+            .iter()
+            .all(|v| v.var_type == VarType::Noise));
+    }
+
+    #[test]
+    fn test_current_input_and_errors() {
+        let text = block(25.0, "1.0000k", "2.0000f", "1.0000p").replace("at vin", "at iin");
+        let result = parse_noise(&text).unwrap();
+        assert!(result.sweep_param.is_none());
+        assert_eq!(result.tables[0].sweep_value, None);
+        let inoise = result.var_index("inoise").unwrap();
+        assert_eq!(
+            result.variables[inoise].unit,
+            Some(Unit::AmperePerRootHertz)
+        );
+
+        assert!(parse_noise(" **** operating point information\n").is_err());
+    }
+}
@@ -0,0 +1,327 @@
+//! NumPy `.npz` export
+//!
+//! [`write_npz`] stores every signal as a `.npy` array in an uncompressed
+//! zip, the layout `numpy.savez` writes, so results load with plain numpy
+//! and no native module:
+//!
+//! ```python
+//! import json, numpy as np
+//!
+//! npz = np.load("sim.npz")
+//! meta = json.loads(npz["metadata.json"])
+//! vout = npz["v(out)"]                    # float64, or complex128 for AC
+//! ```
+//!
+//! A single table is stored as `<signal>.npy`. Swept results store each
+//! table under its own prefix, `table<i>/<signal>.npy`, since the tables
+//! usually differ in length. `metadata.json` holds the title, date,
+//! analysis, scale, sweep parameter and values, and each variable's type,
+//! unit and array key.
+
+use crate::types::{Result, ResultExt, VectorData, WaveformError, WaveformResult};
+use serde_json::json;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// Schema identifier written into `metadata.json`
+pub const SCHEMA: &str = "hspice-npz/1";
+
+/// Write `result` as an `.npz` archive at `path`
+pub fn write_npz<P: AsRef<Path>>(result: &WaveformResult, path: P) -> Result<()> {
+    let path = path.as_ref();
+    let mut writer =
+        BufWriter::new(File::create(path).with_context(|| format!("creating {}", path.display()))?);
+    to_npz(result, &mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Write `result` as an `.npz` archive to `writer`
+pub fn to_npz<W: Write>(result: &WaveformResult, writer: &mut W) -> Result<()> {
+    let swept = result.tables.len() > 1;
+    let key = |table: usize, name: &str| {
+        let name = name.replace('/', "_");
+        if swept {
+            format!("table{}/{}", table, name)
+        } else {
+            name
+        }
+    };
+
+    let mut zip = ZipWriter::new(writer);
+    for (t, table) in result.tables.iter().enumerate() {
+        for (var, vector) in result.variables.iter().zip(&table.vectors) {
+            zip.add(&format!("{}.npy", key(t, &var.name)), &npy(vector))?;
+        }
+    }
+
+    let first = result.tables.first();
+    let variables: Vec<_> = result
+        .variables
+        .iter()
+        .enumerate()
+        .map(|(i, var)| {
+            json!({
+                "name": var.name,
+                "key": key(0, &var.name).trim_start_matches("table0/"),
+                "type": var.var_type.to_string(),
+                "unit": var.unit.map(|u| u.symbol()),
+                "complex": first.is_some_and(|t| t.vectors[i].is_complex()),
+            })
+        })
+        .collect();
+    let metadata = json!({
+        "schema": SCHEMA,
+        "title": result.title,
+        "date": result.date,
+        "analysis": result.analysis.to_string(),
+        "scale": result.scale_name(),
+        "sweep_param": result.sweep_param,
+        "sweep_values": result.tables.iter().map(|t| t.sweep_value).collect::<Vec<_>>(),
+        "tables": result.tables.len(),
+        "variables": variables,
+    });
+    zip.add("metadata.json", metadata.to_string().as_bytes())?;
+    zip.finish()
+}
+
+/// Encode a vector as a version 1.0 `.npy` file
+fn npy(vector: &VectorData) -> Vec<u8> {
+    let (descr, data) = match vector {
+        VectorData::Complex(v) => (
+            "<c16",
+            v.iter()
+                .flat_map(|c| [c.re.to_le_bytes(), c.im.to_le_bytes()])
+                .flatten()
+                .collect::<Vec<u8>>(),
+        ),
+        other => (
+            "<f8",
+            other
+                .to_real()
+                .unwrap_or_default()
+                .iter()
+                .flat_map(|v| v.to_le_bytes())
+                .collect(),
+        ),
+    };
+
+    let mut header = format!(
+        "{{'descr': '{}', 'fortran_order': False, 'shape': ({},), }}",
+        descr,
+        vector.len()
+    );
+    // Magic, version and length take 10 bytes; pad the header with spaces
+    // so the data starts on a 64-byte boundary
+    let padded = (10 + header.len() + 1).div_ceil(64) * 64 - 10;
+    header.extend(std::iter::repeat_n(' ', padded - header.len() - 1));
+    header.push('\n');
+
+    let mut out = Vec::with_capacity(10 + header.len() + data.len());
+    out.extend_from_slice(b"\x93NUMPY\x01\x00");
+    out.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    out.extend_from_slice(header.as_bytes());
+    out.extend_from_slice(&data);
+    out
+}
+
+// ============================================================================
+// Zip container
+// ============================================================================
+
+/// Minimal zip writer: stored (uncompressed) entries, no zip64
+struct ZipWriter<'a, W: Write> {
+    writer: &'a mut W,
+    offset: u64,
+    /// Name, CRC-32, size and local header offset of each entry
+    entries: Vec<(String, u32, u32, u32)>,
+}
+
+impl<'a, W: Write> ZipWriter<'a, W> {
+    fn new(writer: &'a mut W) -> Self {
+        Self {
+            writer,
+            offset: 0,
+            entries: Vec::new(),
+        }
+    }
+
+    fn add(&mut self, name: &str, data: &[u8]) -> Result<()> {
+        let too_large =
+            || WaveformError::ParseError(format!("{} is too large for an .npz archive", name));
+        let size = u32::try_from(data.len()).map_err(|_| too_large())?;
+        let offset = u32::try_from(self.offset).map_err(|_| too_large())?;
+        let crc = crc32(data);
+
+        let mut header = Vec::with_capacity(30 + name.len());
+        header.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        header.extend_from_slice(&20u16.to_le_bytes()); // version needed
+        header.extend_from_slice(&0u16.to_le_bytes()); // flags
+        header.extend_from_slice(&0u16.to_le_bytes()); // stored
+        header.extend_from_slice(&DOS_TIME.to_le_bytes());
+        header.extend_from_slice(&DOS_DATE.to_le_bytes());
+        header.extend_from_slice(&crc.to_le_bytes());
+        header.extend_from_slice(&size.to_le_bytes()); // compressed
+        header.extend_from_slice(&size.to_le_bytes()); // uncompressed
+        header.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        header.extend_from_slice(&0u16.to_le_bytes()); // extra field
+        header.extend_from_slice(name.as_bytes());
+
+        self.write(&header)?;
+        self.write(data)?;
+        self.entries.push((name.to_string(), crc, size, offset));
+        Ok(())
+    }
+
+    /// Write the central directory
+    fn finish(mut self) -> Result<()> {
+        let start = self.offset;
+        let entries = std::mem::take(&mut self.entries);
+        for (name, crc, size, offset) in &entries {
+            let mut record = Vec::with_capacity(46 + name.len());
+            record.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+            record.extend_from_slice(&20u16.to_le_bytes()); // version made by
+            record.extend_from_slice(&20u16.to_le_bytes()); // version needed
+            record.extend_from_slice(&0u16.to_le_bytes()); // flags
+            record.extend_from_slice(&0u16.to_le_bytes()); // stored
+            record.extend_from_slice(&DOS_TIME.to_le_bytes());
+            record.extend_from_slice(&DOS_DATE.to_le_bytes());
+            record.extend_from_slice(&crc.to_le_bytes());
+            record.extend_from_slice(&size.to_le_bytes());
+            record.extend_from_slice(&size.to_le_bytes());
+            record.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            record.extend_from_slice(&[0; 12]); // extra, comment, disk, attributes
+            record.extend_from_slice(&offset.to_le_bytes());
+            record.extend_from_slice(name.as_bytes());
+            self.write(&record)?;
+        }
+
+        let count = u16::try_from(entries.len()).map_err(|_| {
+            WaveformError::ParseError("too many signals for an .npz archive".into())
+        })?;
+        let too_large = || WaveformError::ParseError("result too large for an .npz archive".into());
+        let size = u32::try_from(self.offset - start).map_err(|_| too_large())?;
+        let start = u32::try_from(start).map_err(|_| too_large())?;
+
+        let mut end = Vec::with_capacity(22);
+        end.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+        end.extend_from_slice(&[0; 4]); // disk numbers
+        end.extend_from_slice(&count.to_le_bytes());
+        end.extend_from_slice(&count.to_le_bytes());
+        end.extend_from_slice(&size.to_le_bytes());
+        end.extend_from_slice(&start.to_le_bytes());
+        end.extend_from_slice(&0u16.to_le_bytes()); // comment
+        self.write(&end)
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<()> {
+        self.writer.write_all(bytes)?;
+        self.offset += bytes.len() as u64;
+        Ok(())
+    }
+}
+
+/// 1980-01-01 00:00, the earliest DOS timestamp, for reproducible output
+const DOS_TIME: u16 = 0;
+const DOS_DATE: u16 = 0x21;
+
+/// CRC-32 (IEEE) lookup table
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                0xEDB8_8320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, &b| {
+        CRC_TABLE[((crc ^ b as u32) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{temp_path, SyntheticWaveform};
+
+    /// Names and contents of the entries of a stored zip
+    fn entries(zip: &[u8]) -> Vec<(String, Vec<u8>)> {
+        let u16_at = |i: usize| u16::from_le_bytes([zip[i], zip[i + 1]]) as usize;
+        let u32_at = |i: usize| u32::from_le_bytes(zip[i..i + 4].try_into().unwrap()) as usize;
+        let end = zip.len() - 22;
+        assert_eq!(u32_at(end), 0x0605_4b50);
+        let mut record = u32_at(end + 16);
+        (0..u16_at(end + 10))
+            .map(|_| {
+                let name_len = u16_at(record + 28);
+                let name = String::from_utf8(zip[record + 46..record + 46 + name_len].to_vec());
+                let local = u32_at(record + 42);
+                let size = u32_at(local + 18);
+                let data = local + 30 + u16_at(local + 26);
+                assert_eq!(crc32(&zip[data..data + size]) as usize, u32_at(record + 16));
+                record += 46 + name_len;
+                (name.unwrap(), zip[data..data + size].to_vec())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_npy_layout() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+
+        let npy = npy(&VectorData::Real(vec![1.5, -2.0].into()));
+        assert_eq!(&npy[..8], b"\x93NUMPY\x01\x00");
+        let header_len = u16::from_le_bytes([npy[8], npy[9]]) as usize;
+        assert_eq!((10 + header_len) % 64, 0);
+        let header = std::str::from_utf8(&npy[10..10 + header_len]).unwrap();
+        assert!(header.starts_with("{'descr': '<f8', 'fortran_order': False, 'shape': (2,), }"));
+        assert!(header.ends_with('\n'));
+        assert_eq!(
+            &npy[10 + header_len..10 + header_len + 8],
+            &1.5f64.to_le_bytes()
+        );
+    }
+
+    #[test]
+    fn test_archive_entries() {
+        let synth = SyntheticWaveform::new()
+            .signals(2)
+            .points(10)
+            .sweep("temp", vec![25.0, 85.0]);
+        let result = synth.expected();
+        let path = temp_path("npz_sweep.npz");
+        write_npz(&result, &path).unwrap();
+        let zip = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let entries = entries(&zip);
+        let names: Vec<_> = entries.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names.len(), 2 * 3 + 1);
+        assert_eq!(names[0], "table0/TIME.npy");
+        assert_eq!(names[3], "table1/TIME.npy");
+        assert_eq!(names[6], "metadata.json");
+
+        let meta: serde_json::Value = serde_json::from_slice(&entries[6].1).unwrap();
+        assert_eq!(meta["schema"], SCHEMA);
+        assert_eq!(meta["sweep_values"][1], 85.0);
+        assert_eq!(meta["variables"][1]["key"], result.variables[1].name);
+    }
+}
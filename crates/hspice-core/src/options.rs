@@ -0,0 +1,212 @@
+//! Read options for files auto-detection gets wrong
+//!
+//! By default the reader detects the byte order from each block header,
+//! the precision from the post version string, requires every block
+//! trailer to repeat its header, and decodes header text as UTF-8. Files
+//! from exotic platforms or with damaged blocks can fool those checks;
+//! `ReadOptions` overrides them:
+//!
+//! ```rust,no_run
+//! use hspice_core::{read_with_options, Endian, PostVersion, ReadOptions, TextEncoding};
+//!
+//! let options = ReadOptions::new()
+//!     .endian(Endian::Big)
+//!     .post_version(PostVersion::V9601)
+//!     .lenient(true)
+//!     .recover(true)
+//!     .encoding(TextEncoding::Latin1);
+//! let result = read_with_options("sun4.tr0", &options).unwrap();
+//! ```
+
+use crate::decode::Framing;
+pub use crate::decode::TextEncoding;
+use crate::layout::HeaderLayout;
+use crate::limits::ParserLimits;
+use crate::types::{Endian, PostVersion};
+
+/// What to do with rows whose scale steps back
+///
+/// HSPICE can leave backtracked time points in transient output when it
+/// rejects a timestep: TIME runs ahead, jumps back, and runs ahead again.
+/// Interpolation, FFTs and measurements assume an ascending scale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NonMonotonic {
+    /// Keep every row as written
+    #[default]
+    Keep,
+    /// Drop rows whose scale is below the last row kept
+    Drop,
+    /// Drop the earlier rows a backtracking row supersedes, so the last
+    /// value written for a point wins
+    KeepLast,
+    /// Fail with [`crate::WaveformError::NonMonotonicScale`]
+    Error,
+}
+
+impl NonMonotonic {
+    /// Option by name: `keep`, `drop`, `keep-last` or `error`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().replace('_', "-").as_str() {
+            "keep" => Some(NonMonotonic::Keep),
+            "drop" => Some(NonMonotonic::Drop),
+            "keep-last" => Some(NonMonotonic::KeepLast),
+            "error" => Some(NonMonotonic::Error),
+            _ => None,
+        }
+    }
+}
+
+/// How an HSPICE file is read
+///
+/// The default detects everything and matches [`crate::read`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ReadOptions {
+    pub(crate) endian: Option<Endian>,
+    pub(crate) post_version: Option<PostVersion>,
+    pub(crate) lenient: bool,
+    pub(crate) recover: bool,
+    pub(crate) encoding: TextEncoding,
+    pub(crate) header_layout: Option<HeaderLayout>,
+    pub(crate) non_monotonic: NonMonotonic,
+    pub(crate) buffered_decode: bool,
+    pub(crate) store_f32: bool,
+    pub(crate) compact_steps: bool,
+    pub(crate) limits: ParserLimits,
+}
+
+impl ReadOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Byte order to use instead of detecting it from each block header
+    pub fn endian(mut self, endian: Endian) -> Self {
+        self.endian = Some(endian);
+        self
+    }
+
+    /// Precision to use instead of the header's post version string, which
+    /// is then not checked
+    pub fn post_version(mut self, version: PostVersion) -> Self {
+        self.post_version = Some(version);
+        self
+    }
+
+    /// Accept block trailers that do not match their header, and block
+    /// headers whose byte order markers are damaged
+    ///
+    /// A damaged header is read with the forced byte order, or the one
+    /// detected from the previous block. Mismatches are logged as warnings.
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    /// Salvage the complete rows of a file that ends early, as one left
+    /// by a crashed simulation does
+    ///
+    /// Instead of failing on a cut-off block or a missing end marker, the
+    /// rows read so far are returned with [`crate::WaveformResult::truncated`]
+    /// set, and a warning logs how much was recovered.
+    pub fn recover(mut self, recover: bool) -> Self {
+        self.recover = recover;
+        self
+    }
+
+    /// Encoding of the header text
+    pub fn encoding(mut self, encoding: TextEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Header field offsets to use instead of probing the standard and
+    /// registered layouts (see [`HeaderLayout`])
+    pub fn header_layout(mut self, layout: HeaderLayout) -> Self {
+        self.header_layout = Some(layout);
+        self
+    }
+
+    /// What to do with rows whose scale steps back (see [`NonMonotonic`])
+    ///
+    /// Streams apply `KeepLast` within each block; a row that steps back
+    /// behind an earlier block's rows is dropped instead.
+    pub fn clean_non_monotonic(mut self, mode: NonMonotonic) -> Self {
+        self.non_monotonic = mode;
+        self
+    }
+
+    /// Decode each table into one buffer of raw values before splitting
+    /// it into signals, as older versions did
+    ///
+    /// By default values go from each block straight into the signal
+    /// vectors. The buffered path needs about twice the memory and is kept
+    /// for comparison; results are the same.
+    pub fn buffered_decode(mut self, buffered: bool) -> Self {
+        self.buffered_decode = buffered;
+        self
+    }
+
+    /// Keep the real signals of float32 files (9007 and 9601) in single
+    /// precision, as [`crate::VectorData::Real32`]
+    ///
+    /// Halves the memory of a whole-file read without losing anything,
+    /// since the file holds float32. The scale stays `f64`, as do float64
+    /// (2001) files and streamed chunks. [`crate::VectorData::to_real`] and
+    /// the math methods widen the values.
+    pub fn store_f32(mut self, store_f32: bool) -> Self {
+        self.store_f32 = store_f32;
+        self
+    }
+
+    /// Store real signals that rarely change (digital control nets) as
+    /// [`crate::VectorData::Stepped`]
+    ///
+    /// A signal with at most one change per 16 points is kept as one
+    /// `(index, value)` pair per change; the scale stays `Real`. Off by
+    /// default, since [`crate::VectorData::as_real`] returns `None` for
+    /// stepped data: read it with [`crate::VectorData::to_real`] or
+    /// [`crate::VectorData::real_at`], or expand it with
+    /// [`crate::WaveformResult::materialize`].
+    pub fn compact_steps(mut self, compact_steps: bool) -> Self {
+        self.compact_steps = compact_steps;
+        self
+    }
+
+    /// Resource limits (see [`ParserLimits`])
+    pub fn limits(mut self, limits: ParserLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// The block damage these options read past
+    pub(crate) fn framing(&self) -> Framing {
+        Framing {
+            endian: self.endian,
+            damaged_markers: self.lenient,
+            trailer_mismatch: self.lenient,
+            cut_off: self.recover,
+        }
+    }
+}
+
+impl From<ParserLimits> for ReadOptions {
+    fn from(limits: ParserLimits) -> Self {
+        Self::new().limits(limits)
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(TextEncoding::Utf8.decode(b"v(out)"), "v(out)");
+        assert_eq!(TextEncoding::Latin1.decode(b"50\xb5A"), "50\u{b5}A");
+        assert_eq!(TextEncoding::Utf8.decode(b"50\xb5A"), "50\u{fffd}A");
+    }
+}
@@ -0,0 +1,263 @@
+//! Time-aligned overlays of one signal across many runs
+//!
+//! Each run (and each sweep table within a run) becomes one trace. The traces
+//! are shifted so a chosen reference, a fixed time or an edge of the signal
+//! itself, lands at zero, then written as one CSV with a shared time column
+//! ready for plotting.
+//!
+//! ```rust,no_run
+//! use hspice_core::measure::Edge;
+//! use hspice_core::overlay::{overlay_export, AlignOn};
+//!
+//! let runs = vec![
+//!     hspice_core::read("corner_ff.tr0").unwrap(),
+//!     hspice_core::read("corner_ss.tr0").unwrap(),
+//! ];
+//! let align = AlignOn::Edge { level: 0.9, edge: Edge::Rising, nth: 0 };
+//! overlay_export(&runs, "v(out)", align, "out_overlay.csv").unwrap();
+//! ```
+
+use crate::measure::{crossings, Edge};
+use crate::types::{Result, WaveformError, WaveformResult};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use tracing::{info, instrument};
+
+/// Reference point that is moved to time zero in every trace
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AlignOn {
+    /// Keep each run's own time axis
+    None,
+    /// Shift every trace by the same time
+    Time(f64),
+    /// Shift each trace so the `nth` (0-based) crossing of `level` is at zero
+    Edge { level: f64, edge: Edge, nth: usize },
+}
+
+/// One run's signal on its shifted time axis
+#[derive(Debug, Clone)]
+pub struct OverlayTrace {
+    /// `run<i>`, plus the sweep value for swept runs (`run1[temp=85]`)
+    pub label: String,
+    /// Time subtracted from the original scale
+    pub shift: f64,
+    pub time: Vec<f64>,
+    pub values: Vec<f64>,
+}
+
+/// Collect `signal` from every run and table, shifted per `align_on`
+///
+/// Fails if a run lacks the signal, the signal is complex, or the
+/// alignment edge is not found in some trace.
+pub fn overlay(
+    results: &[WaveformResult],
+    signal: &str,
+    align_on: AlignOn,
+) -> Result<Vec<OverlayTrace>> {
+    let mut traces = Vec::new();
+
+    for (run, result) in results.iter().enumerate() {
+        let index = result.var_index(signal).ok_or_else(|| {
+            WaveformError::ParseError(format!("run {}: signal {} not found", run, signal))
+        })?;
+
+        for table in &result.tables {
+            let label = match (&result.sweep_param, table.sweep_value) {
+                (Some(param), Some(value)) => format!("run{}[{}={}]", run, param, value),
+                _ => format!("run{}", run),
+            };
+            let time = table.vectors[0].to_real();
+            let values = table.vectors[index].to_real();
+            let (Some(time), Some(values)) = (time, values) else {
+                return Err(WaveformError::ParseError(format!(
+                    "{}: {} is not a real signal over a real scale",
+                    label, signal
+                )));
+            };
+
+            let shift = match align_on {
+                AlignOn::None => 0.0,
+                AlignOn::Time(t) => t,
+                AlignOn::Edge { level, edge, nth } => crossings(&time, &values, level, edge)
+                    .get(nth)
+                    .copied()
+                    .ok_or_else(|| {
+                        WaveformError::ParseError(format!(
+                            "{}: {} has no {:?} crossing #{} of {}",
+                            label, signal, edge, nth, level
+                        ))
+                    })?,
+            };
+
+            traces.push(OverlayTrace {
+                label,
+                shift,
+                time: time.iter().map(|t| t - shift).collect(),
+                values: values.into_owned(),
+            });
+        }
+    }
+
+    Ok(traces)
+}
+
+/// Write traces as CSV: a `time` column followed by one column per trace
+///
+/// Rows cover the union of all shifted time points. Each trace is linearly
+/// interpolated onto them and left empty outside its own time range.
+pub fn write_overlay_csv<W: Write>(traces: &[OverlayTrace], writer: &mut W) -> Result<()> {
+    write!(writer, "time")?;
+    for trace in traces {
+        write!(writer, ",{}", csv_field(&trace.label))?;
+    }
+    writeln!(writer)?;
+
+    let mut grid: Vec<f64> = traces.iter().flat_map(|t| t.time.iter().copied()).collect();
+    grid.sort_by(f64::total_cmp);
+    grid.dedup();
+
+    let mut cursors = vec![0usize; traces.len()];
+    for &t in &grid {
+        write!(writer, "{}", t)?;
+        for (trace, cursor) in traces.iter().zip(cursors.iter_mut()) {
+            match sample_at(&trace.time, &trace.values, t, cursor) {
+                Some(value) => write!(writer, ",{}", value)?,
+                None => write!(writer, ",")?,
+            }
+        }
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+/// Align `signal` across `results` and write the overlay CSV to `path`
+///
+/// Returns the traces so callers can report the shifts applied.
+#[instrument(skip(results), fields(runs = results.len()))]
+pub fn overlay_export(
+    results: &[WaveformResult],
+    signal: &str,
+    align_on: AlignOn,
+    path: &str,
+) -> Result<Vec<OverlayTrace>> {
+    let traces = overlay(results, signal, align_on)?;
+
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_overlay_csv(&traces, &mut writer)?;
+    writer.flush()?;
+
+    info!(traces = traces.len(), "Overlay written");
+    Ok(traces)
+}
+
+/// Interpolated value at `t`, advancing `cursor` through ascending queries
+pub(crate) fn sample_at(time: &[f64], values: &[f64], t: f64, cursor: &mut usize) -> Option<f64> {
+    let n = time.len().min(values.len());
+    if n == 0 || t < time[0] || t > time[n - 1] {
+        return None;
+    }
+    while *cursor + 1 < n && time[*cursor + 1] < t {
+        *cursor += 1;
+    }
+    if *cursor + 1 == n || time[*cursor] == t {
+        return Some(values[*cursor]);
+    }
+    let (x0, x1, y0, y1) = (
+        time[*cursor],
+        time[*cursor + 1],
+        values[*cursor],
+        values[*cursor + 1],
+    );
+    Some(if x1 > x0 {
+        y0 + (t - x0) * (y1 - y0) / (x1 - x0)
+    } else {
+        y0
+    })
+}
+
+/// Quote a CSV field if it contains separators or quotes
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{AnalysisType, DataTable, Variable, VectorData};
+
+    /// Ramp from 0 to 1 starting at `delay`, sampled every 1.0
+    fn run(delay: f64) -> WaveformResult {
+        let time: Vec<f64> = (0..6).map(|i| i as f64).collect();
+        let out = time.iter().map(|t| (t - delay).clamp(0.0, 1.0)).collect();
+        WaveformResult {
+            title: String::new(),
+            date: String::new(),
+            analysis: AnalysisType::Transient,
+            variables: vec![Variable::new("TIME"), Variable::new("out")],
+            sweep_param: None,
+            tables: vec![DataTable {
+                sweep_value: None,
+                vectors: vec![VectorData::Real(time.into()), VectorData::Real(out)],
+            }],
+            truncated: false,
+        }
+    }
+
+    #[test]
+    fn test_align_on_edge() {
+        let align = AlignOn::Edge {
+            level: 0.5,
+            edge: Edge::Rising,
+            nth: 0,
+        };
+        let traces = overlay(&[run(1.0), run(3.0)], "out", align).unwrap();
+        assert_eq!(traces[0].label, "run0");
+        assert_eq!(traces[0].shift, 1.5);
+        assert_eq!(traces[1].shift, 3.5);
+        assert_eq!(traces[1].time[0], -3.5);
+    }
+
+    #[test]
+    fn test_align_errors() {
+        let align = AlignOn::Edge {
+            level: 0.5,
+            edge: Edge::Falling,
+            nth: 0,
+        };
+        assert!(overlay(&[run(1.0)], "out", align).is_err());
+        assert!(overlay(&[run(1.0)], "missing", AlignOn::None).is_err());
+    }
+
+    #[test]
+    fn test_csv_union_grid() {
+        let mut traces = overlay(&[run(1.0), run(3.0)], "out", AlignOn::Time(0.5)).unwrap();
+        traces[1].time.iter_mut().for_each(|t| *t += 0.5);
+
+        let mut csv = Vec::new();
+        write_overlay_csv(&traces, &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines[0], "time,run0,run1");
+        // run1 starts half a step later, so it is empty on the first row
+        assert_eq!(lines[1], "-0.5,0,");
+        assert_eq!(lines[2], "0,0,0");
+        assert_eq!(lines[4], "1,0.5,0");
+        assert_eq!(lines.len(), 1 + 12);
+        assert!(lines[12].ends_with(",1"));
+    }
+
+    #[test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("run0"), "run0");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+    }
+}
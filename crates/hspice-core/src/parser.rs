@@ -1,10 +1,16 @@
 //! HSPICE binary file parser
 
+use crate::block_reader::{warn_repairs, BlockReader};
+use crate::decode::{read_header_text, Blocks, Framing, Header};
+use crate::layout::probe_layout;
+use crate::limits::ParserLimits;
+use crate::monotonic::{clean_table, keep_rows, ScaleFilter};
+use crate::options::{NonMonotonic, ReadOptions};
+use crate::progress::{Progress, ProgressReporter};
 use crate::reader::MmapReader;
+use crate::source::FileData;
 use crate::types::*;
-use memmap2::Mmap;
 use num_complex::Complex64;
-use std::fs::File;
 use std::path::Path;
 use tracing::{debug, info, instrument, trace, warn};
 
@@ -18,44 +24,55 @@ enum VectorBuilder {
     Complex(Vec<Complex64>),
 }
 
-/// Find subsequence in a byte slice
-#[inline]
-fn find_subsequence(haystack: &[u8], needle: &[u8]) -> Option<usize> {
-    haystack
-        .windows(needle.len())
-        .position(|window| window == needle)
+/// Read the header blocks at `reader` and parse them as `options` say
+fn read_header(reader: &mut MmapReader, options: &ReadOptions) -> Result<Header> {
+    let max_bytes = options.limits.max_memory;
+    let framing = Framing {
+        cut_off: false,
+        ..options.framing()
+    };
+    let mut blocks = Blocks::new(reader.remaining_slice(), reader.position()).with_framing(framing);
+    let text = read_header_text(&mut blocks, |block, len| {
+        warn_repairs(block, 0);
+        ParserLimits::check("header bytes", len, max_bytes)
+    })?;
+    reader.read_bytes(blocks.position())?;
+    parse_header_text(&text, options)
 }
 
-/// Read header blocks until end marker found
-fn read_header_blocks(reader: &mut MmapReader) -> Result<Vec<u8>> {
-    let mut buffer = Vec::with_capacity(4096);
-
-    loop {
-        let (num_items, trailer) = reader.read_block_header(1)?;
-        let block_data = reader.read_bytes(num_items)?;
-        reader.read_block_trailer(trailer)?;
-
-        buffer.extend_from_slice(block_data);
-
-        if let Some(pos) = find_subsequence(&buffer, b"$&%#") {
-            buffer.truncate(pos);
-            break;
-        }
-    }
-
-    Ok(buffer)
+/// Block reader over the rest of `reader`'s data
+///
+/// Advance `reader` past `bytes_consumed()` afterwards so the next sweep
+/// table starts at the right block.
+fn data_block_reader<'a>(
+    reader: &MmapReader<'a>,
+    version: PostVersion,
+    max_values: usize,
+    options: &ReadOptions,
+) -> BlockReader<'a> {
+    BlockReader::new(reader.remaining_slice(), version)
+        .with_base_offset(reader.offset())
+        .with_options(options)
+        .with_value_limit(max_values)
 }
 
 /// Read data blocks until end marker found - unified for all formats
-fn read_data_blocks(reader: &mut MmapReader, version: PostVersion) -> Result<Vec<f64>> {
-    use crate::block_reader::BlockReader;
-
-    // Get remaining bytes for BlockReader
-    let remaining = reader.remaining();
-    let data_slice = &reader.read_bytes(remaining)?;
-
-    let mut block_reader = BlockReader::new(data_slice, version);
-    let raw_data = block_reader.read_all()?;
+///
+/// Also returns whether the end marker was found, which only a recovering
+/// read can be without. `on_block` gets the file offset reached and the
+/// values read so far after each block.
+fn read_data_blocks(
+    reader: &mut MmapReader,
+    version: PostVersion,
+    max_values: usize,
+    options: &ReadOptions,
+    mut on_block: impl FnMut(u64, usize),
+) -> Result<(Vec<f64>, bool)> {
+    let base = reader.offset();
+    let mut block_reader = data_block_reader(reader, version, max_values, options);
+    let raw_data =
+        block_reader.read_all(|consumed, values| on_block(base + consumed as u64, values))?;
+    reader.read_bytes(block_reader.bytes_consumed())?;
 
     debug!(
         blocks = block_reader.block_count(),
@@ -64,28 +81,40 @@ fn read_data_blocks(reader: &mut MmapReader, version: PostVersion) -> Result<Vec
         "Read data blocks"
     );
 
-    Ok(raw_data)
+    Ok((raw_data, block_reader.found_end()))
 }
 
-// ============================================================================
-// String extraction utilities
-// ============================================================================
-
-#[inline]
-fn extract_string(buf: &[u8], start: usize, end: usize) -> String {
-    if start >= buf.len() || end > buf.len() || start >= end {
-        return String::new();
+/// Like `read_data_blocks`, but handing each block to `decoder` instead of
+/// collecting the values
+fn decode_data_blocks(
+    reader: &mut MmapReader,
+    version: PostVersion,
+    max_values: usize,
+    options: &ReadOptions,
+    mut on_block: impl FnMut(u64, usize),
+    decoder: &mut TableDecoder,
+) -> Result<bool> {
+    let base = reader.offset();
+    let mut block_reader = data_block_reader(reader, version, max_values, options);
+    let mut block = Vec::new();
+    while let Some(is_end) = block_reader.next_block_into(&mut block)? {
+        decoder.push(&block);
+        block.clear();
+        on_block(base + block_reader.bytes_consumed() as u64, decoder.values);
+        if is_end {
+            break;
+        }
     }
-    let slice = &buf[start..end];
-    let end_pos = slice.iter().position(|&c| c == 0).unwrap_or(slice.len());
-    String::from_utf8_lossy(&slice[..end_pos])
-        .trim()
-        .to_string()
-}
+    reader.read_bytes(block_reader.bytes_consumed())?;
 
-#[inline]
-fn extract_int(buf: &[u8], start: usize, end: usize) -> i32 {
-    extract_string(buf, start, end).trim().parse().unwrap_or(0)
+    debug!(
+        blocks = block_reader.block_count(),
+        format = block_reader.format_name(),
+        values = decoder.values,
+        "Decoded data blocks"
+    );
+
+    Ok(block_reader.found_end())
 }
 
 // ============================================================================
@@ -107,124 +136,90 @@ pub struct HeaderMetadata {
     pub sweep_size: i32,
 }
 
-/// Parse vector names from header buffer
-fn parse_vector_names(buf: &[u8], num_vectors: usize) -> Result<(String, Vec<String>)> {
-    if buf.len() < VECTOR_DESCRIPTION_START_POSITION {
-        return Err(WaveformError::ParseError("Buffer too short".into()));
+impl HeaderMetadata {
+    /// Whether the data is complex (AC analysis)
+    pub fn is_complex(&self) -> bool {
+        self.var_type == COMPLEX_VAR
     }
 
-    let desc_section = &buf[VECTOR_DESCRIPTION_START_POSITION..];
-    let desc_str = String::from_utf8_lossy(desc_section);
-    let tokens: Vec<&str> = desc_str.split_whitespace().collect();
-
-    if tokens.len() < num_vectors + 1 {
-        return Err(WaveformError::ParseError("Not enough vector names".into()));
+    /// Values per row: one per vector, two per complex signal
+    pub fn num_columns(&self) -> usize {
+        if self.is_complex() {
+            self.num_vectors + (self.num_variables - 1) as usize
+        } else {
+            self.num_vectors
+        }
     }
 
-    let scale_name = tokens.get(num_vectors).unwrap_or(&"time").to_string();
-
-    let names: Vec<String> = ((num_vectors + 1)..(2 * num_vectors))
-        .filter_map(|i| tokens.get(i))
-        .map(|name| {
-            let mut name = name.to_lowercase();
-            if name.starts_with("v(") {
-                name = name[2..].trim_end_matches(')').to_string();
-            }
-            name
-        })
-        .collect();
-
-    Ok((scale_name, names))
-}
-
-/// Get sweep info from header tokens
-fn get_sweep_info(buf: &[u8], tokens: &[&str], num_vectors: usize) -> Option<(String, i32)> {
-    let sweep_name = tokens.get(2 * num_vectors)?.to_string();
-    let post_str = extract_string(buf, POST_START_POSITION2, POST_START_POSITION2 + 4);
-    let sweep_size = if post_str == POST_STRING21 {
-        extract_int(buf, SWEEP_SIZE_POSITION2, SWEEP_SIZE_POSITION2 + 10)
-    } else {
-        extract_int(buf, SWEEP_SIZE_POSITION1, SWEEP_SIZE_POSITION1 + 10)
-    };
-    Some((sweep_name, sweep_size))
-}
-
-/// Parse all header metadata from buffer
-fn parse_header_metadata(header_buf: &[u8]) -> Result<HeaderMetadata> {
-    let post1 = extract_string(header_buf, POST_START_POSITION1, POST_START_POSITION1 + 4);
-    let post2 = extract_string(header_buf, POST_START_POSITION2, POST_START_POSITION2 + 4);
-
-    if post1 != POST_STRING11 && post1 != POST_STRING12 && post2 != POST_STRING21 {
-        return Err(WaveformError::FormatError("Unknown post format".into()));
+    /// Analysis type as far as the header tells: `Unknown` when neither the
+    /// data type nor the scale name decide it, as for most DC sweeps
+    pub fn analysis(&self) -> AnalysisType {
+        header_analysis(self.is_complex(), &self.scale_name, AnalysisType::Unknown)
     }
 
-    let post_version = if post2 == POST_STRING21 {
-        PostVersion::V2001
-    } else {
-        PostVersion::V9601
-    };
+    /// Upper estimate of the rows in a file of `file_len` bytes whose data
+    /// starts at `data_start`, counting block framing as data
+    pub fn estimated_rows(&self, file_len: usize, data_start: usize) -> usize {
+        let row_bytes = self.post_version.value_size() * self.num_columns().max(1);
+        file_len.saturating_sub(data_start) / row_bytes
+    }
+}
 
-    let date = extract_string(header_buf, DATE_START_POSITION, DATE_END_POSITION);
-    let title_end = {
-        let mut end = DATE_START_POSITION;
-        while end > TITLE_START_POSITION && header_buf.get(end - 1) == Some(&b' ') {
-            end -= 1;
+impl From<Header> for HeaderMetadata {
+    fn from(header: Header) -> Self {
+        Self {
+            num_variables: header.num_variables as i32,
+            num_vectors: header.num_vectors(),
+            var_type: if header.is_complex {
+                COMPLEX_VAR
+            } else {
+                REAL_VAR
+            },
+            sweep_size: header.sweep_size as i32,
+            title: header.title,
+            date: header.date,
+            post_version: header.post_version,
+            scale_name: header.scale_name,
+            names: header.names,
+            sweep_name: header.sweep_name,
         }
-        end
-    };
-    let title = extract_string(header_buf, TITLE_START_POSITION, title_end);
-
-    let num_sweeps = extract_int(
-        header_buf,
-        NUM_OF_SWEEPS_POSITION,
-        NUM_OF_SWEEPS_END_POSITION,
-    );
-    if !(0..=1).contains(&num_sweeps) {
-        return Err(WaveformError::FormatError(
-            "Only one-dimensional sweep supported".into(),
-        ));
     }
+}
 
-    let num_probes = extract_int(header_buf, NUM_OF_PROBES_POSITION, NUM_OF_SWEEPS_POSITION);
-    let num_variables = extract_int(
-        header_buf,
-        NUM_OF_VARIABLES_POSITION,
-        NUM_OF_PROBES_POSITION,
-    );
-    let num_vectors = (num_probes + num_variables) as usize;
-
-    let desc_section = &header_buf[VECTOR_DESCRIPTION_START_POSITION..];
-    let desc_str = String::from_utf8_lossy(desc_section);
-    let tokens: Vec<&str> = desc_str.split_whitespace().collect();
-    let var_type_num: i32 = tokens.first().and_then(|s| s.parse().ok()).unwrap_or(0);
-    let var_type = if var_type_num == FREQUENCY_TYPE {
-        COMPLEX_VAR
-    } else {
-        REAL_VAR
-    };
-
-    let (scale_name, names) = parse_vector_names(header_buf, num_vectors)?;
+/// The variables of `header`, the scale first
+pub(crate) fn header_variables(header: &Header) -> Vec<Variable> {
+    let codes = header.types.iter().skip(1);
+    std::iter::once(Variable::new(&header.scale_name))
+        .chain(
+            header
+                .names
+                .iter()
+                .zip(codes)
+                .map(|(name, &code)| signal_variable(name, code)),
+        )
+        .collect()
+}
 
-    let (sweep_name, sweep_size) = if num_sweeps == 1 {
-        get_sweep_info(header_buf, &tokens, num_vectors)
-            .map(|(n, s)| (Some(n), s.max(1)))
-            .unwrap_or((None, 1))
-    } else {
-        (None, 1)
-    };
+/// A header signal, typed from its name or, when the name says nothing
+/// (a node voltage `v(out)` is normalized to `out`), from its type code
+pub(crate) fn signal_variable(name: &str, code: i32) -> Variable {
+    match VarType::from_name(name) {
+        VarType::Unknown => Variable::with_type(name, VarType::from_type_code(code)),
+        _ => Variable::new(name),
+    }
+}
 
-    Ok(HeaderMetadata {
-        title,
-        date,
-        post_version,
-        num_variables,
-        num_vectors,
-        var_type,
-        scale_name,
-        names,
-        sweep_name,
-        sweep_size,
-    })
+/// Parse the header text
+///
+/// Fields are read at the offsets of the layout in `options`, or of the
+/// first layout that matches (see [`crate::register_header_layout`]).
+fn parse_header_text(text: &[u8], options: &ReadOptions) -> Result<Header> {
+    let limits = &options.limits;
+    let layout = options.header_layout.unwrap_or_else(|| probe_layout(text));
+    let header = Header::parse_with(text, &layout, options.encoding, options.post_version)?;
+    ParserLimits::check("signals", header.num_vectors(), limits.max_signals)?;
+    ParserLimits::check("sweep tables", header.sweep_size, limits.max_tables)?;
+    Ok(header)
 }
 
 // ============================================================================
@@ -240,36 +235,21 @@ struct DataLayout {
 }
 
 impl DataLayout {
-    fn new(
-        raw_data: &[f64],
-        num_vectors: usize,
-        num_variables: i32,
-        var_type: i32,
-        has_sweep: bool,
-    ) -> Self {
-        let num_columns = if var_type == COMPLEX_VAR {
-            num_vectors + (num_variables - 1) as usize
-        } else {
-            num_vectors
-        };
-        let data_offset = if has_sweep { 2 } else { 1 };
-        let num_rows = raw_data.len().saturating_sub(data_offset) / num_columns.max(1);
+    fn new(raw_data: &[f64], header: &Header, has_end: bool) -> Self {
+        let has_sweep = header.sweep_name.is_some();
+        let data_offset = has_sweep as usize + has_end as usize;
+        let num_rows = raw_data.len().saturating_sub(data_offset) / header.num_columns().max(1);
         let data_start = if has_sweep { 1 } else { 0 };
         let sweep_value = if has_sweep {
             raw_data.first().copied()
         } else {
             None
         };
-        let num_complex_signals = if var_type == COMPLEX_VAR {
-            (num_variables - 1) as usize
-        } else {
-            0
-        };
         Self {
             num_rows,
             data_start,
             sweep_value,
-            num_complex_signals,
+            num_complex_signals: header.num_complex(),
         }
     }
 
@@ -293,10 +273,29 @@ impl VectorBuilder {
         }
     }
 
-    fn into_vector_data(self) -> VectorData {
+    fn into_vector_data(self, storage: RealStorage) -> VectorData {
         match self {
-            VectorBuilder::Real(vec) => VectorData::Real(vec),
-            VectorBuilder::Complex(vec) => VectorData::Complex(vec),
+            VectorBuilder::Real(vec) => storage.vector(vec),
+            VectorBuilder::Complex(vec) => VectorData::Complex(vec.into()),
+        }
+    }
+}
+
+/// How real signals (not the scale) are stored, from `ReadOptions::store_f32`
+/// and `ReadOptions::compact_steps`
+#[derive(Debug, Clone, Copy)]
+struct RealStorage {
+    f32: bool,
+    steps: bool,
+}
+
+impl RealStorage {
+    fn vector(self, values: Vec<f64>) -> VectorData {
+        match (self.f32, self.steps) {
+            (false, false) => VectorData::Real(values.into()),
+            (false, true) => VectorData::compact(values),
+            (true, false) => VectorData::Real32(values.into_iter().map(|x| x as f32).collect()),
+            (true, true) => VectorData::compact_f32(values),
         }
     }
 }
@@ -304,12 +303,12 @@ impl VectorBuilder {
 /// Process raw data into vectors
 fn process_raw_data(
     raw_data: &[f64],
-    num_vectors: usize,
-    num_variables: i32,
-    var_type: i32,
-    has_sweep: bool,
+    header: &Header,
+    has_end: bool,
+    storage: RealStorage,
 ) -> (Option<f64>, Vec<VectorData>) {
-    let layout = DataLayout::new(raw_data, num_vectors, num_variables, var_type, has_sweep);
+    let layout = DataLayout::new(raw_data, header, has_end);
+    let num_vectors = header.num_vectors();
 
     // Pre-allocate buffers
     let mut scale_vec = Vec::with_capacity(layout.num_rows);
@@ -335,43 +334,195 @@ fn process_raw_data(
 
     // Build final vectors
     let mut vectors = Vec::with_capacity(num_vectors);
-    vectors.push(VectorData::Real(scale_vec));
-    vectors.extend(signal_bufs.into_iter().map(VectorBuilder::into_vector_data));
+    vectors.push(VectorData::Real(scale_vec.into()));
+    vectors.extend(
+        signal_bufs
+            .into_iter()
+            .map(|buf| buf.into_vector_data(storage)),
+    );
 
     (layout.sweep_value, vectors)
 }
 
+/// Where a value of a row goes in a `TableDecoder`
+#[derive(Debug, Clone, Copy)]
+enum Slot {
+    Real(usize),
+    /// Real part of a complex signal, held until its imaginary part
+    Re,
+    Im(usize),
+}
+
+/// Splits the values of one table into signal vectors as blocks are read,
+/// so the table is never held as one buffer of raw values
+struct TableDecoder {
+    /// Destination of each value of a row
+    slots: Vec<Slot>,
+    /// The scale, then the real signals
+    reals: Vec<Vec<f64>>,
+    /// The complex signals, which come before the real ones
+    complexes: Vec<Vec<Complex64>>,
+    /// Slot of the next value
+    next: usize,
+    /// Real part of the complex value being read
+    re: f64,
+    has_sweep: bool,
+    sweep_value: Option<f64>,
+    /// Precision and step compaction of the real signals
+    storage: RealStorage,
+    /// Values read, the sweep value and end marker included
+    values: usize,
+}
+
+impl TableDecoder {
+    /// Decoder for a table of `header`'s file, with room for `rows` rows
+    fn new(header: &Header, rows: usize, storage: RealStorage) -> Self {
+        let num_complex = header.num_complex();
+        let num_real = header.num_vectors() - num_complex;
+        let mut slots = vec![Slot::Real(0)];
+        for i in 0..num_complex {
+            slots.extend([Slot::Re, Slot::Im(i)]);
+        }
+        slots.extend((1..num_real).map(Slot::Real));
+
+        Self {
+            slots,
+            reals: (0..num_real).map(|_| Vec::with_capacity(rows)).collect(),
+            complexes: (0..num_complex).map(|_| Vec::with_capacity(rows)).collect(),
+            next: 0,
+            re: 0.0,
+            has_sweep: header.sweep_name.is_some(),
+            sweep_value: None,
+            storage,
+            values: 0,
+        }
+    }
+
+    /// Add the next values of the table
+    fn push(&mut self, mut values: &[f64]) {
+        if self.has_sweep && self.values == 0 {
+            if let Some((&sweep, rest)) = values.split_first() {
+                self.sweep_value = Some(sweep);
+                values = rest;
+                self.values += 1;
+            }
+        }
+        self.values += values.len();
+
+        // Rows of real values only map column for column
+        if self.complexes.is_empty() {
+            let width = self.reals.len();
+            for &value in values {
+                self.reals[self.next].push(value);
+                self.next += 1;
+                if self.next == width {
+                    self.next = 0;
+                }
+            }
+            return;
+        }
+        for &value in values {
+            match self.slots[self.next] {
+                Slot::Real(i) => self.reals[i].push(value),
+                Slot::Re => self.re = value,
+                Slot::Im(i) => self.complexes[i].push(Complex64::new(self.re, value)),
+            }
+            self.next += 1;
+            if self.next == self.slots.len() {
+                self.next = 0;
+            }
+        }
+    }
+
+    /// Sweep value and vectors of the table: its complete rows, without
+    /// the end marker if `has_end`, cleaned as `mode` says
+    fn finish(
+        mut self,
+        has_end: bool,
+        mode: NonMonotonic,
+    ) -> Result<(Option<f64>, Vec<VectorData>)> {
+        let framing = self.has_sweep as usize + has_end as usize;
+        let rows = self.values.saturating_sub(framing) / self.slots.len();
+        self.reals.iter_mut().for_each(|v| v.truncate(rows));
+        self.complexes.iter_mut().for_each(|v| v.truncate(rows));
+
+        if mode != NonMonotonic::Keep {
+            let kept = ScaleFilter::new(mode).keep(self.reals[0].iter().copied())?;
+            if kept.len() < rows {
+                self.reals.iter_mut().for_each(|v| keep_rows(v, &kept));
+                self.complexes.iter_mut().for_each(|v| keep_rows(v, &kept));
+            }
+        }
+
+        let mut reals = self.reals.into_iter();
+        let mut vectors = Vec::with_capacity(self.slots.len());
+        vectors.push(VectorData::Real(reals.next().unwrap_or_default().into()));
+        vectors.extend(
+            self.complexes
+                .into_iter()
+                .map(|v| VectorData::Complex(v.into())),
+        );
+        vectors.extend(reals.map(|v| self.storage.vector(v)));
+        Ok((self.sweep_value, vectors))
+    }
+}
+
 // ============================================================================
 // Main entry point
 // ============================================================================
 
 /// Validate file format before parsing
-fn validate_file_format(mmap: &Mmap) -> Result<()> {
-    if mmap.is_empty() {
-        return Err(WaveformError::FormatError("File is empty".into()));
+fn validate_file_format(data: &[u8]) -> Result<()> {
+    if data.is_empty() {
+        return Err(WaveformError::EmptyFile);
     }
-    if mmap[0] >= b' ' {
-        return Err(WaveformError::FormatError(
-            "File is ASCII format, only binary supported".into(),
-        ));
+    if data[0] >= b' ' {
+        return Err(WaveformError::AsciiFormat);
     }
     Ok(())
 }
 
-/// Parse only the header, return metadata and data start position
-pub fn parse_header_only(mmap: &Mmap) -> Result<(HeaderMetadata, usize)> {
-    validate_file_format(mmap)?;
+/// Parse only the header of a file's contents, mapped or in memory, and
+/// return its metadata and the position where the data starts
+pub fn parse_header_only(data: &[u8]) -> Result<(HeaderMetadata, usize)> {
+    parse_header_with_limits(data, &ParserLimits::unlimited())
+}
+
+/// Variables of a file's header, the scale first, typed as a full read
+/// types them
+pub fn parse_header_variables(data: &[u8]) -> Result<Vec<Variable>> {
+    let (header, _) = read_header_bytes(data, &ReadOptions::from(ParserLimits::unlimited()))?;
+    Ok(header_variables(&header))
+}
+
+/// Parse only the header of in-memory data, enforcing `limits`
+pub(crate) fn parse_header_with_limits(
+    data: &[u8],
+    limits: &ParserLimits,
+) -> Result<(HeaderMetadata, usize)> {
+    parse_header_with_options(data, &ReadOptions::from(*limits))
+}
 
-    let mut reader = MmapReader::new(mmap);
-    let header_buf = read_header_blocks(&mut reader)?;
-    let metadata = parse_header_metadata(&header_buf)?;
+/// Parse only the header of in-memory data as `options` say
+pub(crate) fn parse_header_with_options(
+    data: &[u8],
+    options: &ReadOptions,
+) -> Result<(HeaderMetadata, usize)> {
+    let (header, data_position) = read_header_bytes(data, options)?;
+    Ok((header.into(), data_position))
+}
 
-    let data_position = mmap.len() - reader.remaining();
-    Ok((metadata, data_position))
+/// Like [`parse_header_with_options`], returning the decoded header
+pub(crate) fn read_header_bytes(data: &[u8], options: &ReadOptions) -> Result<(Header, usize)> {
+    validate_file_format(data)?;
+
+    let mut reader = MmapReader::new(data);
+    let header = read_header(&mut reader, options)?;
+    Ok((header, reader.position()))
 }
 
 /// Infer analysis type from filename
-fn infer_analysis_type(filename: &str) -> AnalysisType {
+pub(crate) fn infer_analysis_type(filename: &str) -> AnalysisType {
     Path::new(filename)
         .extension()
         .and_then(|e| e.to_str())
@@ -379,27 +530,158 @@ fn infer_analysis_type(filename: &str) -> AnalysisType {
         .unwrap_or(AnalysisType::Unknown)
 }
 
+/// Analysis type from the header, or `fallback` if the header is not conclusive
+///
+/// Noise files have a frequency scale like AC ones, so a `Noise` fallback
+/// (from the .ns0 extension) wins over a real frequency scale.
+pub(crate) fn header_analysis(
+    is_complex: bool,
+    scale_name: &str,
+    fallback: AnalysisType,
+) -> AnalysisType {
+    if is_complex {
+        return AnalysisType::AC;
+    }
+    match AnalysisType::from_scale_name(scale_name) {
+        AnalysisType::AC if fallback == AnalysisType::Noise => fallback,
+        AnalysisType::Unknown => fallback,
+        from_scale => from_scale,
+    }
+}
+
 /// Main HSPICE file reader - returns WaveformResult
-#[instrument(skip_all, fields(file = %filename))]
 pub fn hspice_read_impl(filename: &str) -> Result<WaveformResult> {
+    hspice_read_with_options(filename, &ReadOptions::default())
+}
+
+/// HSPICE file reader enforcing `limits`
+pub fn hspice_read_with_limits(filename: &str, limits: &ParserLimits) -> Result<WaveformResult> {
+    hspice_read_with_options(filename, &ReadOptions::from(*limits))
+}
+
+/// HSPICE file reader with detection overrides and limits from `options`
+pub fn hspice_read_with_options(filename: &str, options: &ReadOptions) -> Result<WaveformResult> {
+    hspice_read_with_progress(filename, options, None)
+}
+
+/// HSPICE file reader reporting its progress to `progress`
+#[instrument(skip_all, fields(file = %filename))]
+pub fn hspice_read_with_progress(
+    filename: &str,
+    options: &ReadOptions,
+    progress: Option<&mut dyn FnMut(&Progress)>,
+) -> Result<WaveformResult> {
     info!("Reading HSPICE file");
 
-    let file = File::open(filename)?;
-    let mmap = unsafe { Mmap::map(&file)? };
+    let data = FileData::open(filename).with_context(|| format!("opening {}", filename))?;
 
-    let file_size = mmap.len();
+    let file_size = data.len();
     let file_size_mb = file_size as f64 / 1_048_576.0;
     debug!(size_bytes = file_size, size_mb = %format!("{:.2}", file_size_mb), "File mapped");
 
-    validate_file_format(&mmap)?;
+    parse_hspice_bytes_with_progress(&data, infer_analysis_type(filename), options, progress)
+        .with_context(|| format!("reading {}", filename))
+}
+
+/// Parse a complete HSPICE file held in memory
+///
+/// `fallback_analysis` is used when the header does not identify the
+/// analysis (normally inferred from the file extension).
+pub(crate) fn parse_hspice_bytes(
+    data: &[u8],
+    fallback_analysis: AnalysisType,
+    options: &ReadOptions,
+) -> Result<WaveformResult> {
+    parse_hspice_bytes_with_progress(data, fallback_analysis, options, None)
+}
+
+fn parse_hspice_bytes_with_progress(
+    data: &[u8],
+    fallback_analysis: AnalysisType,
+    options: &ReadOptions,
+    progress: Option<&mut dyn FnMut(&Progress)>,
+) -> Result<WaveformResult> {
+    validate_file_format(data)?;
+    let mut progress = ProgressReporter::new(progress, data.len() as u64);
+    let mut reader = MmapReader::new(data);
+    let mut rows_read = 0;
+    let result = read_result_set(
+        &mut reader,
+        fallback_analysis,
+        options,
+        &mut progress,
+        &mut rows_read,
+    )?;
+    progress.finish(rows_read);
+    Ok(result)
+}
+
+/// Parse every result set of an HSPICE file held in memory
+///
+/// Netlists with `.ALTER` statements can leave one complete result set
+/// (header and tables) per run back to back in a single file. Reading
+/// stops after the last one, or after a truncated one when `options`
+/// recover.
+pub(crate) fn parse_alter_bytes(
+    data: &[u8],
+    fallback_analysis: AnalysisType,
+    options: &ReadOptions,
+) -> Result<Vec<WaveformResult>> {
+    validate_file_format(data)?;
+    let mut progress = ProgressReporter::new(None, data.len() as u64);
+    let mut reader = MmapReader::new(data);
+    let mut rows_read = 0;
+    let mut results = Vec::new();
+    loop {
+        let offset = reader.offset();
+        let result = read_result_set(
+            &mut reader,
+            fallback_analysis,
+            options,
+            &mut progress,
+            &mut rows_read,
+        )
+        .with_context(|| {
+            format!(
+                "reading alter run {} at offset {:#x}",
+                results.len() + 1,
+                offset
+            )
+        })?;
+        let truncated = result.truncated;
+        results.push(result);
+        if truncated || reader.remaining() == 0 {
+            break;
+        }
+    }
+    info!(runs = results.len(), "Alter runs read");
+    Ok(results)
+}
+
+/// Every result set of an HSPICE file, one per `.ALTER` run
+pub fn hspice_read_alters(filename: &str, options: &ReadOptions) -> Result<Vec<WaveformResult>> {
+    let data = FileData::open(filename).with_context(|| format!("opening {}", filename))?;
+    parse_alter_bytes(&data, infer_analysis_type(filename), options)
+        .with_context(|| format!("reading {}", filename))
+}
 
-    let mut reader = MmapReader::new(&mmap);
-    let header_buf = read_header_blocks(&mut reader)?;
-    let meta = parse_header_metadata(&header_buf)?;
+/// Read one header and its data tables from `reader`
+///
+/// `rows_read` counts the rows of every result set read so far, for
+/// progress reports.
+fn read_result_set(
+    reader: &mut MmapReader,
+    fallback_analysis: AnalysisType,
+    options: &ReadOptions,
+    progress: &mut ProgressReporter,
+    rows_read: &mut usize,
+) -> Result<WaveformResult> {
+    let limits = &options.limits;
+    let meta = read_header(reader, options).context("reading header")?;
 
     info!(
         version = ?meta.post_version,
-        vectors = meta.num_vectors,
+        vectors = meta.num_vectors(),
         scale = %meta.scale_name,
         "Header parsed"
     );
@@ -408,29 +690,28 @@ pub fn hspice_read_impl(filename: &str) -> Result<WaveformResult> {
         info!(sweep_param = %name, sweep_points = meta.sweep_size, "Sweep detected");
     }
 
-    // Infer analysis type
-    let analysis = if meta.var_type == COMPLEX_VAR {
-        AnalysisType::AC
-    } else {
-        let from_scale = AnalysisType::from_scale_name(&meta.scale_name);
-        if from_scale != AnalysisType::Unknown {
-            from_scale
-        } else {
-            infer_analysis_type(filename)
-        }
-    };
+    let analysis = header_analysis(meta.is_complex, &meta.scale_name, fallback_analysis);
     debug!(analysis = %analysis, "Analysis type inferred");
 
-    // Build variable list
-    let mut variables = Vec::with_capacity(meta.num_vectors);
-    variables.push(Variable::new(&meta.scale_name));
-    for name in &meta.names {
-        variables.push(Variable::new(name));
-    }
+    let variables = header_variables(&meta);
     trace!(count = variables.len(), "Variables built");
 
+    // Values per table: every row plus the optional sweep value and end marker
+    let num_columns = meta.num_columns();
+    let max_table_values = limits
+        .max_points
+        .saturating_mul(num_columns)
+        .saturating_add(2);
+    let mut memory_budget = limits.max_values();
+    let storage = RealStorage {
+        f32: options.store_f32 && meta.post_version.value_size() == 4,
+        steps: options.compact_steps,
+    };
+
     // Read data tables
-    let mut tables = Vec::with_capacity(meta.sweep_size as usize);
+    let mut tables = Vec::with_capacity(meta.sweep_size.min(reader.remaining()));
+    let mut truncated = false;
+    let leading_values = meta.sweep_name.is_some() as usize;
 
     for sweep_idx in 0..meta.sweep_size {
         trace!(
@@ -439,21 +720,101 @@ pub fn hspice_read_impl(filename: &str) -> Result<WaveformResult> {
             "Reading sweep"
         );
 
-        let raw_data = read_data_blocks(&mut reader, meta.post_version)?;
-        let (sweep_value, vectors) = process_raw_data(
-            &raw_data,
-            meta.num_vectors,
-            meta.num_variables,
-            meta.var_type,
-            meta.sweep_name.is_some(),
-        );
+        if reader.remaining() == 0 && options.recover {
+            truncated = true;
+            break;
+        } else if reader.remaining() == 0 {
+            return Err(WaveformError::MissingSweepTable {
+                index: sweep_idx + 1,
+                total: meta.sweep_size,
+                offset: reader.offset(),
+            });
+        }
 
-        tables.push(DataTable {
+        let table_offset = reader.offset();
+        let max_values = max_table_values.min(memory_budget);
+        let on_block = |offset, values: usize| {
+            let rows = values.saturating_sub(leading_values) / num_columns.max(1);
+            progress.block(offset, *rows_read + rows);
+        };
+        let read_context = || {
+            if meta.sweep_size > 1 {
+                format!(
+                    "reading sweep table {} of {} at offset {:#x}",
+                    sweep_idx + 1,
+                    meta.sweep_size,
+                    table_offset
+                )
+            } else {
+                format!("reading data at offset {:#x}", table_offset)
+            }
+        };
+        let clean_context = |e: WaveformError| match meta.sweep_size {
+            1 => e,
+            n => e.context(format!("reading sweep table {} of {}", sweep_idx + 1, n)),
+        };
+
+        let (sweep_value, vectors, found_end) = if options.buffered_decode {
+            let (mut raw_data, found_end) =
+                read_data_blocks(reader, meta.post_version, max_values, options, on_block)
+                    .with_context(read_context)?;
+            memory_budget -= raw_data.len();
+            let has_end = found_end || !options.recover;
+            clean_table(
+                &mut raw_data,
+                leading_values,
+                has_end as usize,
+                num_columns,
+                options.non_monotonic,
+            )
+            .map_err(clean_context)?;
+
+            let (sweep_value, vectors) = process_raw_data(&raw_data, &meta, has_end, storage);
+            (sweep_value, vectors, found_end)
+        } else {
+            // Split what is left among the tables still to read
+            let tables_left = meta.sweep_size - sweep_idx;
+            let value_size = meta.post_version.value_size();
+            let rows = (reader.remaining() / tables_left / value_size / num_columns.max(1))
+                .min(limits.max_points);
+            let mut decoder = TableDecoder::new(&meta, rows, storage);
+            let found_end = decode_data_blocks(
+                reader,
+                meta.post_version,
+                max_values,
+                options,
+                on_block,
+                &mut decoder,
+            )
+            .with_context(read_context)?;
+            memory_budget -= decoder.values;
+            let has_end = found_end || !options.recover;
+            let (sweep_value, vectors) = decoder
+                .finish(has_end, options.non_monotonic)
+                .map_err(clean_context)?;
+            (sweep_value, vectors, found_end)
+        };
+
+        let table = DataTable {
             sweep_value,
             vectors,
-        });
+        };
+        if !found_end && options.recover {
+            // Keep a cut-off table only if it has rows, or nothing else would be left
+            *rows_read += table.len();
+            if !table.is_empty() || tables.is_empty() {
+                tables.push(table);
+            }
+            truncated = true;
+            break;
+        }
+        *rows_read += table.len();
+        tables.push(table);
     }
 
+    if truncated {
+        log_recovery(&tables, meta.sweep_size);
+    }
     info!(
         tables = tables.len(),
         points = tables.first().map(|t| t.len()).unwrap_or(0),
@@ -467,5 +828,29 @@ pub fn hspice_read_impl(filename: &str) -> Result<WaveformResult> {
         variables,
         sweep_param: meta.sweep_name,
         tables,
+        truncated,
     })
 }
+
+/// Warn how much of a truncated file was recovered
+///
+/// The fraction counts sweep tables, the cut-off one weighed by its rows
+/// against the first table; a single table has no reference and logs only
+/// its rows.
+fn log_recovery(tables: &[DataTable], sweep_size: usize) {
+    let rows = tables.last().map(DataTable::len).unwrap_or(0);
+    let complete = tables.len().saturating_sub(1);
+    if complete == 0 {
+        warn!(rows, "File truncated, recovered the complete rows");
+        return;
+    }
+    let partial = rows as f64 / tables[0].len().max(1) as f64;
+    let fraction = (complete as f64 + partial.min(1.0)) / sweep_size.max(1) as f64;
+    warn!(
+        tables = tables.len(),
+        total = sweep_size,
+        rows,
+        recovered = %format!("{:.1}%", fraction * 100.0),
+        "File truncated, recovered the complete rows"
+    );
+}
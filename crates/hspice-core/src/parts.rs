@@ -0,0 +1,311 @@
+//! Outputs split over numbered parts (`sim.tr0`, `sim.tr1`, `sim.tr2`, ...)
+//!
+//! HSPICE can split a large output over files whose extensions count up.
+//! Each part repeats the header, and a part usually starts with the last
+//! point of the one before it. [`read_parts`] and [`read_parts_stream`] find
+//! the parts after a given one, check that they hold the same signals, and
+//! stitch them together, dropping points at the start of a part that do not
+//! advance the scale past the end of the previous part.
+
+use crate::stream::{DataChunk, HspiceStreamReader};
+use crate::types::{Result, VectorData, WaveformError, WaveformResult};
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// The parts of a split output, starting with `path`
+///
+/// Parts are found by counting up the number at the end of the
+/// extension (`tr0`, `tr1`, ...; `ac9`, `ac10`, ...) until a file is
+/// missing. A path whose extension does not end in a number is its own
+/// single part.
+pub fn part_paths<P: AsRef<Path>>(path: P) -> Vec<PathBuf> {
+    let path = path.as_ref();
+    let mut parts = vec![path.to_path_buf()];
+    let Some(ext) = path.extension().and_then(|e| e.to_str()) else {
+        return parts;
+    };
+    let prefix = ext.trim_end_matches(|c: char| c.is_ascii_digit());
+    let Ok(mut number) = ext[prefix.len()..].parse::<u64>() else {
+        return parts;
+    };
+    loop {
+        number += 1;
+        let next = path.with_extension(format!("{}{}", prefix, number));
+        if !next.is_file() {
+            return parts;
+        }
+        parts.push(next);
+    }
+}
+
+/// Read a split output as one result (see the [module docs](self))
+///
+/// Every part must have the same scale, signals and number of sweep
+/// tables as the first. Tables are stitched one by one, so each sweep
+/// point runs on through all parts.
+pub fn read_parts<P: AsRef<Path>>(path: P) -> Result<WaveformResult> {
+    let paths = part_paths(path);
+    let mut result = read_part(&paths[0])?;
+    for part_path in &paths[1..] {
+        let part = read_part(part_path)?;
+        check_part(&result, &part, part_path)?;
+        for (table, more) in result.tables.iter_mut().zip(part.tables) {
+            let last = table.vectors.first().and_then(last_scale);
+            let skip = skipped_rows(&more.vectors[0], last);
+            debug!(part = %part_path.display(), skip, "Stitching part");
+            for (vector, more) in table.vectors.iter_mut().zip(more.vectors) {
+                append_from(vector, more, skip);
+            }
+        }
+    }
+    Ok(result)
+}
+
+fn read_part(path: &Path) -> Result<WaveformResult> {
+    let name = path.to_string_lossy();
+    crate::read(&name).map_err(|e| e.context(name.into_owned()))
+}
+
+/// Whether `part` can continue `first`
+fn check_part(first: &WaveformResult, part: &WaveformResult, path: &Path) -> Result<()> {
+    let names = |r: &WaveformResult| -> Vec<String> {
+        r.variables.iter().map(|v| v.name.clone()).collect()
+    };
+    if names(first) != names(part) || first.tables.len() != part.tables.len() {
+        return Err(WaveformError::FormatError(format!(
+            "{}: signals or sweep tables differ from the first part",
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
+fn last_scale(scale: &VectorData) -> Option<f64> {
+    scale.len().checked_sub(1).and_then(|i| scale.real_at(i))
+}
+
+/// Rows at the start of a part's `scale` that are not past `last`
+fn skipped_rows(scale: &VectorData, last: Option<f64>) -> usize {
+    let Some(last) = last else { return 0 };
+    (0..scale.len())
+        .find(|&i| scale.real_at(i).is_some_and(|t| t > last))
+        .unwrap_or(scale.len())
+}
+
+/// Append the rows of `more` from `skip` on to `vector`
+fn append_from(vector: &mut VectorData, more: VectorData, skip: usize) {
+    match (vector, more) {
+        (VectorData::Complex(a), VectorData::Complex(b)) => a.extend(b.into_iter().skip(skip)),
+        (vector, more) => {
+            vector.materialize();
+            if let VectorData::Real(a) = vector {
+                a.extend(more.to_real().unwrap_or_default().iter().skip(skip));
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Streaming
+// ============================================================================
+
+/// Stream a split output as one (see the [module docs](self))
+///
+/// Chunks are numbered through all parts. Parts are opened as the previous
+/// one runs out, with the first part's chunk size; a part whose signals
+/// differ from the first fails when it is reached. A swept output is read
+/// part by part, so each table's rows pick up again in the next part after
+/// the other tables of this one.
+pub fn read_parts_stream<P: AsRef<Path>>(path: P, chunk_size: usize) -> Result<PartsReader> {
+    let mut paths = part_paths(path);
+    paths.reverse();
+    let first = paths.pop().expect("part_paths includes the path itself");
+    let current = HspiceStreamReader::open(&first, chunk_size)?;
+    let metadata = current.metadata();
+    Ok(PartsReader {
+        scale_name: metadata.scale_name,
+        signal_names: metadata.signal_names,
+        current: Some(current),
+        remaining: paths,
+        chunk_size,
+        last: Vec::new(),
+        next_index: 0,
+    })
+}
+
+/// Chunks of all parts of a split output (see [`read_parts_stream`])
+pub struct PartsReader {
+    current: Option<HspiceStreamReader>,
+    /// Parts not opened yet, last part first
+    remaining: Vec<PathBuf>,
+    chunk_size: usize,
+    scale_name: String,
+    signal_names: Vec<String>,
+    /// Last scale value of each sweep table so far
+    last: Vec<Option<f64>>,
+    next_index: usize,
+}
+
+impl PartsReader {
+    /// Open the next part, if any
+    fn next_part(&mut self) -> Result<()> {
+        let Some(path) = self.remaining.pop() else {
+            return Ok(());
+        };
+        debug!(part = %path.display(), "Opening next part");
+        let reader = HspiceStreamReader::open(&path, self.chunk_size)?;
+        if reader.metadata().signal_names != self.signal_names {
+            return Err(WaveformError::FormatError(format!(
+                "{}: signals differ from the first part",
+                path.display()
+            )));
+        }
+        self.current = Some(reader);
+        Ok(())
+    }
+
+    /// Drop the rows of `chunk` that do not advance its table's scale;
+    /// None if no row is left
+    fn trim(&mut self, chunk: DataChunk) -> Option<DataChunk> {
+        let scale_name = self.scale_name.as_str();
+        if self.last.len() <= chunk.table_index {
+            self.last.resize(chunk.table_index + 1, None);
+        }
+        let last = &mut self.last[chunk.table_index];
+        let scale = chunk.data.get(scale_name)?;
+        let skip = skipped_rows(scale, *last);
+        if skip == scale.len() {
+            return None;
+        }
+        *last = last_scale(scale);
+        if skip == 0 {
+            return Some(chunk);
+        }
+
+        let data = chunk
+            .data
+            .into_iter()
+            .map(|(name, vector)| {
+                let mut rest = match vector {
+                    VectorData::Complex(_) => VectorData::Complex(Vec::new().into()),
+                    _ => VectorData::Real(Vec::new().into()),
+                };
+                append_from(&mut rest, vector, skip);
+                (name, rest)
+            })
+            .collect::<std::collections::HashMap<_, _>>();
+        let start = data.get(scale_name).and_then(|s| s.real_at(0))?;
+        Some(DataChunk {
+            time_range: (start, chunk.time_range.1),
+            data,
+            ..chunk
+        })
+    }
+}
+
+impl Iterator for PartsReader {
+    type Item = Result<DataChunk>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let reader = self.current.as_mut()?;
+            match reader.next() {
+                Some(Ok(chunk)) => {
+                    if let Some(mut chunk) = self.trim(chunk) {
+                        chunk.chunk_index = self.next_index;
+                        self.next_index += 1;
+                        return Some(Ok(chunk));
+                    }
+                }
+                Some(Err(e)) => {
+                    self.current = None;
+                    return Some(Err(e));
+                }
+                None => {
+                    self.current = None;
+                    if let Err(e) = self.next_part() {
+                        return Some(Err(e));
+                    }
+                }
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{temp_path, SyntheticWaveform};
+
+    /// A 100-point waveform split over `<name>.tr0..tr2`, each part
+    /// repeating the last point of the one before
+    fn write_parts(name: &str) -> (PathBuf, WaveformResult) {
+        let synth = || {
+            SyntheticWaveform::new()
+                .signals(2)
+                .version(crate::PostVersion::V2001)
+                .sweep("temp", vec![25.0, 85.0])
+        };
+        let base = temp_path(name);
+        for (i, (first, points)) in [(0, 40), (39, 31), (69, 31)].into_iter().enumerate() {
+            synth()
+                .first_point(first)
+                .points(points)
+                .write_tr0(base.with_extension(format!("tr{}", i)))
+                .unwrap();
+        }
+        (base.with_extension("tr0"), synth().points(100).expected())
+    }
+
+    fn remove_parts(first: &Path) {
+        for path in part_paths(first) {
+            std::fs::remove_file(path).ok();
+        }
+    }
+
+    #[test]
+    fn test_part_paths() {
+        let (first, _) = write_parts("parts_paths");
+        let paths = part_paths(&first);
+        assert_eq!(paths.len(), 3);
+        assert_eq!(paths[2].extension().unwrap(), "tr2");
+        assert_eq!(part_paths(&paths[1]).len(), 2);
+        assert_eq!(
+            part_paths("no_number.raw"),
+            [PathBuf::from("no_number.raw")]
+        );
+        remove_parts(&first);
+    }
+
+    #[test]
+    fn test_read_parts_drops_boundary_points() {
+        let (first, expected) = write_parts("parts_read");
+        let result = read_parts(&first).unwrap();
+
+        assert_eq!(result.tables.len(), 2);
+        for (table, want) in result.tables.iter().zip(&expected.tables) {
+            assert_eq!(table.len(), 100);
+            for (vector, want) in table.vectors.iter().zip(&want.vectors) {
+                assert_eq!(vector.to_real(), want.to_real());
+            }
+        }
+
+        let chunks: Vec<DataChunk> = read_parts_stream(&first, 16)
+            .unwrap()
+            .map(|chunk| chunk.unwrap())
+            .collect();
+        remove_parts(&first);
+        assert!(chunks.iter().enumerate().all(|(i, c)| c.chunk_index == i));
+        for (t, want) in expected.tables.iter().enumerate() {
+            let mut scale = Vec::new();
+            for chunk in chunks.iter().filter(|c| c.table_index == t) {
+                scale.extend_from_slice(&chunk.data["TIME"].to_real().unwrap());
+            }
+            assert_eq!(scale, *want.vectors[0].to_real().unwrap());
+        }
+    }
+}
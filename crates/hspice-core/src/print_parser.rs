@@ -0,0 +1,332 @@
+//! `.print` table reader (.lis listings and .printtr0 files)
+//!
+//! HSPICE prints `.print` results as text tables. In the listing each table
+//! sits between `x` and `y` lines under two header rows, the output type
+//! and the node or element name:
+//!
+//! ```text
+//! x
+//!
+//!     time        voltage     current
+//!                    out         vdd
+//!     0.          1.8000     -1.2340u
+//!   100.0000p     1.7999     -1.2350u
+//! y
+//! ```
+//!
+//! `.printtr0` files and other tools use one header row of full probe
+//! names (`time v(out) i(vdd)`). Both become a [`WaveformResult`]: voltages
+//! are named after the node (`out`, like in .tr0 files), currents and
+//! powers `i(...)` and `p(...)`. Wide prints that HSPICE splits over
+//! several tables with the same scale are joined into one table. A table
+//! whose scale starts over starts a new sweep table; a `name = value` line
+//! just above it (`temp= 85.000`, `*** parameter vdd = 1.8 ***`) gives the
+//! sweep parameter and value. Numeric lines without a header are ignored.
+
+use crate::lis_parser::{parse_number, title_line};
+use crate::types::{
+    AnalysisType, DataTable, Result, ResultExt, VarType, Variable, VectorData, WaveformError,
+    WaveformResult,
+};
+use std::path::Path;
+use tracing::debug;
+
+/// Read the `.print` tables of an HSPICE listing or .printtr0 file
+pub fn read_print<P: AsRef<Path>>(path: P) -> Result<WaveformResult> {
+    let path = path.as_ref();
+    let text =
+        std::fs::read_to_string(path).with_context(|| format!("opening {}", path.display()))?;
+    parse_print(&text).with_context(|| format!("reading {}", path.display()))
+}
+
+/// One printed table: its columns, scale first
+struct PrintBlock {
+    variables: Vec<Variable>,
+    columns: Vec<Vec<f64>>,
+    /// `name = value` line above the table
+    sweep: Option<(String, f64)>,
+}
+
+/// Parse the `.print` tables in `text`
+///
+/// Returns an error if no table with a header is found.
+pub fn parse_print(text: &str) -> Result<WaveformResult> {
+    let mut title = String::new();
+    let mut blocks: Vec<PrintBlock> = Vec::new();
+    // Text lines since the last table row
+    let mut headers: Vec<&str> = Vec::new();
+    let mut current: Option<PrintBlock> = None;
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let values: Option<Vec<f64>> = trimmed.split_whitespace().map(parse_number).collect();
+        let Some(values) = values else {
+            if title.is_empty() {
+                if let Some(t) = title_line(trimmed) {
+                    title = t;
+                }
+            }
+            blocks.extend(current.take());
+            headers.push(line);
+            continue;
+        };
+
+        match current.as_mut() {
+            Some(block) if block.columns.len() == values.len() => {
+                for (column, value) in block.columns.iter_mut().zip(values) {
+                    column.push(value);
+                }
+            }
+            _ => {
+                blocks.extend(current.take());
+                if let Some(variables) = column_names(&headers, values.len()) {
+                    current = Some(PrintBlock {
+                        variables,
+                        columns: values.into_iter().map(|v| vec![v]).collect(),
+                        sweep: headers.iter().rev().find_map(|h| sweep_line(h)),
+                    });
+                }
+            }
+        }
+        headers.clear();
+    }
+    blocks.extend(current);
+
+    if blocks.is_empty() {
+        return Err(WaveformError::ParseError(
+            "no .print table with a column header found".into(),
+        ));
+    }
+    debug!(blocks = blocks.len(), "Parsed print tables");
+    Ok(into_result(title, blocks))
+}
+
+/// Variables of an `n`-column table from the header lines above it
+///
+/// Takes the last line if it has `n` names, or the last two as a row of
+/// output types over a row of names that skips the scale.
+fn column_names(headers: &[&str], n: usize) -> Option<Vec<Variable>> {
+    let tokens = |line: &str| {
+        line.split_whitespace()
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    };
+    let (last, rest) = headers.split_last()?;
+    let names = tokens(last);
+    if names.len() == n && names.iter().all(|name| is_name(name)) {
+        return Some(names.into_iter().map(Variable::new).collect());
+    }
+    let types = tokens(rest.last()?);
+    if names.len() + 1 != n || types.len() != n || !types.iter().all(|t| is_name(t)) {
+        return None;
+    }
+    let mut variables = vec![Variable::new(types[0].as_str())];
+    for (output, name) in types[1..].iter().zip(names) {
+        variables.push(typed_variable(output, &name));
+    }
+    Some(variables)
+}
+
+/// Plausible column name: not a marker, keyword or number
+fn is_name(token: &str) -> bool {
+    token.len() > 1 && !token.contains('=') && !token.starts_with('*')
+}
+
+/// Variable for a name under an output type from the listing header
+fn typed_variable(output: &str, name: &str) -> Variable {
+    let name = name.strip_prefix("0:").unwrap_or(name);
+    match output.to_ascii_lowercase().as_str() {
+        "voltage" | "volt" => Variable::with_type(name, VarType::Voltage),
+        "current" | "curr" => Variable::with_type(format!("i({})", name), VarType::Current),
+        "power" => Variable::with_type(format!("p({})", name), VarType::Power),
+        _ => Variable::new(name),
+    }
+}
+
+/// `(name, value)` of a `name = value` line, ignoring `*` and the word
+/// `parameter`
+fn sweep_line(line: &str) -> Option<(String, f64)> {
+    let line = line.trim().trim_matches('*').trim();
+    let line = line.strip_prefix("parameter").unwrap_or(line);
+    let (name, value) = line.split_once('=')?;
+    let name = name.trim();
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        return None;
+    }
+    let mut value = value.split_whitespace();
+    let value = parse_number(value.next().filter(|_| value.next().is_none())?)?;
+    Some((name.to_ascii_lowercase(), value))
+}
+
+/// Sweep of a result table and its columns by variable name, scale first
+type PrintTable = (Option<(String, f64)>, Vec<(String, Vec<f64>)>);
+
+/// Join split tables, start a sweep table where the scale starts over,
+/// and align every table to the variables of all of them
+fn into_result(title: String, blocks: Vec<PrintBlock>) -> WaveformResult {
+    let mut variables: Vec<Variable> = vec![blocks[0].variables[0].clone()];
+    let mut tables: Vec<PrintTable> = Vec::new();
+
+    for block in blocks {
+        let scale = &block.columns[0];
+        let same_scale = tables
+            .last()
+            .is_some_and(|(_, columns)| &columns[0].1 == scale);
+        if !same_scale {
+            tables.push((block.sweep.clone(), vec![(String::new(), scale.clone())]));
+        }
+        let (_, columns) = tables.last_mut().expect("pushed above");
+        for (variable, column) in block.variables.into_iter().zip(block.columns).skip(1) {
+            if columns.iter().any(|(name, _)| name == &variable.name) {
+                continue;
+            }
+            if !variables.iter().any(|v| v.name == variable.name) {
+                variables.push(variable.clone());
+            }
+            columns.push((variable.name, column));
+        }
+    }
+
+    let sweep_param = tables[0]
+        .0
+        .as_ref()
+        .filter(|_| tables.len() > 1)
+        .map(|(name, _)| name.clone());
+    let tables = tables
+        .into_iter()
+        .map(|(sweep, mut columns)| {
+            let scale = std::mem::take(&mut columns[0].1);
+            let len = scale.len();
+            let mut vectors = vec![VectorData::Real(scale.into())];
+            for variable in &variables[1..] {
+                let column = columns
+                    .iter_mut()
+                    .find(|(name, _)| name == &variable.name)
+                    .map(|(_, column)| std::mem::take(column))
+                    .unwrap_or_else(|| vec![f64::NAN; len]);
+                vectors.push(VectorData::Real(column.into()));
+            }
+            DataTable {
+                sweep_value: sweep_param.as_ref().and(sweep.map(|(_, value)| value)),
+                vectors,
+            }
+        })
+        .collect();
+
+    WaveformResult {
+        title,
+        date: String::new(),
+        analysis: AnalysisType::from_scale_name(&variables[0].name),
+        variables,
+        sweep_param,
+        tables,
+        truncated: false,
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::temp_path;
+
+    const LIS: &str = " ****** HSPICE -- P-2019.06-SP1 linux64
+.title 'inverter print'
+.tran 100p 300p sweep temp 25 85 60
+ 1 2 3
+ ******  transient analysis tnom=  25.000 temp=  25.000 *****
+   temp = 25.000
+x
+
+    time        voltage     current
+                   out         vdd
+    0.          1.8000     -1.2340u
+  100.0000p     1.7999     -1.2350u
+  200.0000p   900.0000m    -3.0000m
+y
+x
+
+    time        voltage
+                   in
+    0.          0.
+  100.0000p     0.
+  200.0000p     1.8000
+y
+ ******  transient analysis tnom=  25.000 temp=  85.000 *****
+   temp = 85.000
+x
+
+    time        voltage     current
+                   out         vdd
+    0.          1.7000     -2.0000u
+  100.0000p     1.6000     -2.1000u
+y
+
+          ***** job concluded
+";
+
+    fn column(result: &WaveformResult, table: usize, name: &str) -> Vec<f64> {
+        let i = result.var_index(name).unwrap();
+        result.tables[table].vectors[i]
+            .to_real()
+            .unwrap()
+            .into_owned()
+    }
+
+    #[test]
+    fn test_listing_tables() {
+        let path = temp_path("print_parser.lis");
+        std::fs::write(&path, LIS).unwrap();
+        let result = read_print(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(result.title, "inverter print");
+        assert_eq!(result.analysis, AnalysisType::Transient);
+        assert_eq!(result.var_names(), ["time", "out", "i(vdd)", "in"]);
+        assert_eq!(result.sweep_param.as_deref(), Some("temp"));
+        assert_eq!(result.tables.len(), 2);
+        assert_eq!(result.tables[1].sweep_value, Some(85.0));
+
+        assert_eq!(column(&result, 0, "time"), [0.0, 100e-12, 200e-12]);
+        assert_eq!(column(&result, 0, "out"), [1.8, 1.7999, 0.9]);
+        assert_eq!(column(&result, 0, "in"), [0.0, 0.0, 1.8]);
+        assert_eq!(column(&result, 1, "i(vdd)"), [-2e-6, -2.1e-6]);
+        // Split off table missing from the second sweep
+        assert!(column(&result, 1, "in").iter().all(|v| v.is_nan()));
+
+        let var = |name: &str| &result.variables[result.var_index(name).unwrap()];
+        assert_eq!(var("out").var_type, VarType::Voltage);
+        assert_eq!(var("i(vdd)").var_type, VarType::Current);
+        assert_eq!(var("time").var_type, VarType::Time);
+    }
+
+    #[test]
+    fn test_single_header_row() {
+        let text = "TITLE\n time v(in) v(out)\n 0. 0. 1.8\n 1n 1.8 0.\n";
+        let result = parse_print(text).unwrap();
+        assert_eq!(result.var_names(), ["time", "v(in)", "v(out)"]);
+        assert_eq!(result.tables.len(), 1);
+        assert_eq!(result.tables[0].sweep_value, None);
+        assert_eq!(column(&result, 0, "v(out)"), [1.8, 0.0]);
+        assert_eq!(result.variables[2].var_type, VarType::Voltage);
+
+        assert!(parse_print(" 1 2 3\n 4 5 6\n").is_err());
+        assert!(parse_print("").is_err());
+    }
+
+    #[test]
+    fn test_sweep_line() {
+        assert_eq!(sweep_line("   temp = 85.000"), Some(("temp".into(), 85.0)));
+        assert_eq!(
+            sweep_line(" *** parameter vdd =  1.8000 ***"),
+            Some(("vdd".into(), 1.8))
+        );
+        assert_eq!(sweep_line(" tnom=  25.000 temp=  25.000"), None);
+    }
+}
@@ -0,0 +1,115 @@
+//! Progress reporting for long reads
+//!
+//! [`crate::read_with_progress`] calls a function with a [`Progress`] as the
+//! data blocks of a file are read, and [`crate::HspiceStreamReader::with_progress`]
+//! does the same for each chunk of a stream:
+//!
+//! ```rust,no_run
+//! use hspice_core::{read_with_progress, ReadOptions};
+//!
+//! let result = read_with_progress("big.tr0", &ReadOptions::default(), |p| {
+//!     eprint!("\r{:5.1}% ({} rows)", p.fraction() * 100.0, p.rows);
+//! })
+//! .unwrap();
+//! ```
+
+/// How far a read has got
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Progress {
+    /// Bytes of the file read so far
+    pub bytes: u64,
+    /// File size in bytes
+    pub total_bytes: u64,
+    /// Data blocks read so far
+    pub blocks: usize,
+    /// Rows (points) read so far, over all sweep tables
+    pub rows: usize,
+}
+
+impl Progress {
+    /// Fraction of the file read, from 0 to 1
+    pub fn fraction(&self) -> f64 {
+        if self.total_bytes == 0 {
+            1.0
+        } else {
+            (self.bytes as f64 / self.total_bytes as f64).min(1.0)
+        }
+    }
+}
+
+/// Reports per mille of the file, but not more often than every this many bytes
+const MIN_REPORT_BYTES: u64 = 64 * 1024;
+
+/// Calls a progress function as blocks are read, about once per 1/1000 of
+/// the file
+pub(crate) struct ProgressReporter<'a> {
+    callback: Option<&'a mut dyn FnMut(&Progress)>,
+    progress: Progress,
+    step: u64,
+    next_report: u64,
+}
+
+impl<'a> ProgressReporter<'a> {
+    pub(crate) fn new(callback: Option<&'a mut dyn FnMut(&Progress)>, total_bytes: u64) -> Self {
+        let step = (total_bytes / 1000).max(MIN_REPORT_BYTES);
+        Self {
+            callback,
+            progress: Progress {
+                total_bytes,
+                ..Progress::default()
+            },
+            step,
+            next_report: 0,
+        }
+    }
+
+    /// Record a block that ends at byte `bytes`, with `rows` read in total
+    pub(crate) fn block(&mut self, bytes: u64, rows: usize) {
+        self.progress.bytes = bytes;
+        self.progress.blocks += 1;
+        self.progress.rows = rows;
+        if bytes >= self.next_report {
+            self.next_report = bytes + self.step;
+            self.report();
+        }
+    }
+
+    /// Report the final state, with every row counted
+    pub(crate) fn finish(&mut self, rows: usize) {
+        self.progress.rows = rows;
+        self.report();
+    }
+
+    fn report(&mut self) {
+        if let Some(callback) = self.callback.as_mut() {
+            callback(&self.progress);
+        }
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reports_are_throttled() {
+        let mut seen = Vec::new();
+        let mut callback = |p: &Progress| seen.push(*p);
+        let mut reporter = ProgressReporter::new(Some(&mut callback), 1 << 20);
+        for block in 1..=64u64 {
+            reporter.block(block * 16 * 1024, block as usize * 100);
+        }
+        reporter.finish(6400);
+
+        // Every 64 KiB of the 1 MiB file, then the final report
+        assert_eq!(seen.len(), 17);
+        assert_eq!(seen[0].blocks, 1);
+        assert_eq!(seen[16].bytes, 1 << 20);
+        assert_eq!(seen[16].blocks, 64);
+        assert_eq!(seen[16].fraction(), 1.0);
+    }
+}
@@ -2,13 +2,15 @@
 //!
 //! Supports both ASCII and binary raw file formats with auto-detection.
 
+use crate::limits::ParserLimits;
 use crate::types::{
-    AnalysisType, DataTable, Result, VarType, Variable, VectorData, WaveformError, WaveformResult,
+    AnalysisType, DataTable, Result, ResultExt, Unit, VarType, Variable, VectorData, WaveformError,
+    WaveformResult,
 };
 use byteorder::{LittleEndian, ReadBytesExt};
 use num_complex::Complex64;
 use std::fs::File;
-use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::io::{BufRead, BufReader, Cursor, Read, Seek, SeekFrom};
 use tracing::{debug, info, instrument, trace};
 
 /// Raw file format type
@@ -45,15 +47,37 @@ pub fn read_raw_debug(filename: &str, _debug: i32) -> Result<WaveformResult> {
     read_raw_impl(filename)
 }
 
-#[instrument(skip_all, fields(file = %filename))]
+/// Read a SPICE3/ngspice raw file, enforcing `limits`
+pub fn read_raw_with_limits(filename: &str, limits: &ParserLimits) -> Result<WaveformResult> {
+    read_raw_file(filename, limits)
+}
+
+/// Parse a SPICE3/ngspice raw file held in memory, enforcing `limits`
+pub fn read_raw_bytes(data: &[u8], limits: &ParserLimits) -> Result<WaveformResult> {
+    parse_raw(&mut Cursor::new(data), data.len() as u64, limits)
+}
+
 fn read_raw_impl(filename: &str) -> Result<WaveformResult> {
-    info!("Reading SPICE3 raw file");
+    read_raw_file(filename, &ParserLimits::unlimited())
+}
+
+#[instrument(skip_all, fields(file = %filename))]
+fn read_raw_file(filename: &str, limits: &ParserLimits) -> Result<WaveformResult> {
+    let file = File::open(filename).with_context(|| format!("opening {}", filename))?;
+    let total_len = file.metadata()?.len();
+    parse_raw(&mut BufReader::new(file), total_len, limits)
+        .with_context(|| format!("reading {}", filename))
+}
 
-    let file = File::open(filename)?;
-    let mut reader = BufReader::new(file);
+fn parse_raw<R: BufRead + Seek>(
+    reader: &mut R,
+    total_len: u64,
+    limits: &ParserLimits,
+) -> Result<WaveformResult> {
+    info!("Reading SPICE3 raw file");
 
     // Read and parse header
-    let (header, format, data_start) = parse_header(&mut reader)?;
+    let (header, format, data_start) = parse_header(reader)?;
 
     info!(
         format = ?format,
@@ -65,14 +89,24 @@ fn read_raw_impl(filename: &str) -> Result<WaveformResult> {
 
     debug!(title = %header.title, plotname = %header.plotname, "File info");
 
+    let data_len = total_len.saturating_sub(data_start) as usize;
+    check_header_limits(&header, format, data_start as usize, data_len, limits)?;
+
     // Seek to data start
     reader.seek(SeekFrom::Start(data_start))?;
 
     // Parse data based on format
     let vectors = match format {
-        RawFormat::Binary => parse_binary_data(&mut reader, &header)?,
-        RawFormat::Ascii => parse_ascii_data(&mut reader, &header)?,
-    };
+        RawFormat::Binary => parse_binary_data(reader, &header),
+        RawFormat::Ascii => parse_ascii_data(reader, &header, data_len),
+    }
+    .with_context(|| {
+        let kind = match format {
+            RawFormat::Binary => "binary",
+            RawFormat::Ascii => "ASCII",
+        };
+        format!("reading {} data at offset {:#x}", kind, data_start)
+    })?;
 
     // Build WaveformResult
     let analysis = infer_analysis_type(&header.plotname);
@@ -94,9 +128,44 @@ fn read_raw_impl(filename: &str) -> Result<WaveformResult> {
             sweep_value: None,
             vectors,
         }],
+        truncated: false,
     })
 }
 
+/// Check header counts against `limits` and the size of the data section
+fn check_header_limits(
+    header: &RawHeader,
+    format: RawFormat,
+    data_start: usize,
+    data_len: usize,
+    limits: &ParserLimits,
+) -> Result<()> {
+    ParserLimits::check("signals", header.num_variables, limits.max_signals)?;
+    if header.variables.len() != header.num_variables {
+        return Err(WaveformError::VariableCountMismatch {
+            declared: header.num_variables,
+            listed: header.variables.len(),
+        });
+    }
+    ParserLimits::check("points", header.num_points, limits.max_points)?;
+
+    let value_size = if header.is_complex { 16 } else { 8 };
+    let data_bytes = header
+        .num_variables
+        .saturating_mul(header.num_points)
+        .saturating_mul(value_size);
+    ParserLimits::check("data bytes", data_bytes, limits.max_memory)?;
+
+    if format == RawFormat::Binary && data_bytes > data_len {
+        return Err(WaveformError::DataSizeMismatch {
+            needed: data_bytes,
+            available: data_len,
+            offset: data_start as u64,
+        });
+    }
+    Ok(())
+}
+
 fn parse_header<R: BufRead + Seek>(reader: &mut R) -> Result<(RawHeader, RawFormat, u64)> {
     let mut header = RawHeader::default();
     let mut line = String::new();
@@ -162,9 +231,7 @@ fn parse_header<R: BufRead + Seek>(reader: &mut R) -> Result<(RawHeader, RawForm
         }
     }
 
-    Err(WaveformError::ParseError(
-        "No data section found in raw file".to_string(),
-    ))
+    Err(WaveformError::MissingDataSection)
 }
 
 /// Distribute point values into column vectors (eliminates duplication in binary/ascii parsing)
@@ -201,7 +268,10 @@ fn parse_binary_data<R: Read>(reader: &mut R, header: &RawHeader) -> Result<Vec<
             distribute_to_columns(&mut vectors, values);
         }
 
-        Ok(vectors.into_iter().map(VectorData::Complex).collect())
+        Ok(vectors
+            .into_iter()
+            .map(|v| VectorData::Complex(v.into()))
+            .collect())
     } else {
         // Real data: all values are f64 (ngspice default)
         let mut vectors: Vec<Vec<f64>> = vec![Vec::with_capacity(num_points); num_vars];
@@ -213,13 +283,23 @@ fn parse_binary_data<R: Read>(reader: &mut R, header: &RawHeader) -> Result<Vec<
             distribute_to_columns(&mut vectors, values);
         }
 
-        Ok(vectors.into_iter().map(VectorData::Real).collect())
+        Ok(vectors
+            .into_iter()
+            .map(|v| VectorData::Real(v.into()))
+            .collect())
     }
 }
 
-fn parse_ascii_data<R: BufRead>(reader: &mut R, header: &RawHeader) -> Result<Vec<VectorData>> {
+fn parse_ascii_data<R: BufRead>(
+    reader: &mut R,
+    header: &RawHeader,
+    data_len: usize,
+) -> Result<Vec<VectorData>> {
     let num_vars = header.num_variables;
     let num_points = header.num_points;
+    // Every value takes at least two characters, so never reserve more
+    // points than the data section could hold
+    let capacity = num_points.min(data_len / 2);
 
     trace!(
         num_vars = num_vars,
@@ -229,7 +309,7 @@ fn parse_ascii_data<R: BufRead>(reader: &mut R, header: &RawHeader) -> Result<Ve
     );
 
     if header.is_complex {
-        let mut vectors: Vec<Vec<Complex64>> = vec![Vec::with_capacity(num_points); num_vars];
+        let mut vectors: Vec<Vec<Complex64>> = vec![Vec::with_capacity(capacity); num_vars];
         let mut line = String::new();
         let mut current_point = 0;
         let mut current_var = 0;
@@ -272,9 +352,12 @@ fn parse_ascii_data<R: BufRead>(reader: &mut R, header: &RawHeader) -> Result<Ve
             }
         }
 
-        Ok(vectors.into_iter().map(VectorData::Complex).collect())
+        Ok(vectors
+            .into_iter()
+            .map(|v| VectorData::Complex(v.into()))
+            .collect())
     } else {
-        let mut vectors: Vec<Vec<f64>> = vec![Vec::with_capacity(num_points); num_vars];
+        let mut vectors: Vec<Vec<f64>> = vec![Vec::with_capacity(capacity); num_vars];
         let mut line = String::new();
         let mut current_point = 0;
         let mut current_var = 0;
@@ -322,7 +405,10 @@ fn parse_ascii_data<R: BufRead>(reader: &mut R, header: &RawHeader) -> Result<Ve
             }
         }
 
-        Ok(vectors.into_iter().map(VectorData::Real).collect())
+        Ok(vectors
+            .into_iter()
+            .map(|v| VectorData::Real(v.into()))
+            .collect())
     }
 }
 
@@ -362,16 +448,23 @@ fn build_variables(header: &RawHeader) -> Vec<Variable> {
         .variables
         .iter()
         .map(|(name, type_str)| {
-            let var_type = match type_str.to_lowercase().as_str() {
+            let type_str = type_str.to_lowercase();
+            let var_type = match type_str.as_str() {
                 "time" => VarType::Time,
                 "frequency" => VarType::Frequency,
                 "voltage" => VarType::Voltage,
                 "current" => VarType::Current,
+                "power" => VarType::Power,
+                "parameter" => VarType::Parameter,
+                "internal" | "charge" => VarType::Internal,
+                "noise" => VarType::Noise,
                 _ => VarType::Unknown,
             };
-            Variable {
-                name: name.clone(),
-                var_type,
+            let variable = Variable::with_type(name, var_type);
+            if type_str == "charge" {
+                variable.with_unit(Some(Unit::Coulomb))
+            } else {
+                variable
             }
         })
         .collect()
@@ -399,6 +492,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_parse_complex_value() {
         assert_eq!(parse_complex_value("1.0,2.0"), (1.0, 2.0));
         assert_eq!(parse_complex_value("(1.5,-0.5)"), (1.5, -0.5));
@@ -0,0 +1,276 @@
+//! Header repair for HSPICE binary files
+//!
+//! Some simulation wrappers mangle the header text (a title overwritten
+//! with binary junk, a count field left blank) while the data blocks are
+//! intact. [`repair_header`] rewrites the fixed-width header fields in place
+//! and copies everything else byte for byte, so the block framing and all
+//! sample data are untouched.
+//!
+//! ```rust,no_run
+//! use hspice_core::repair::{repair_header, HeaderFixes};
+//!
+//! let fixes = HeaderFixes {
+//!     title: Some("pll lock sim".into()),
+//!     num_variables: Some(1),
+//!     ..Default::default()
+//! };
+//! let meta = repair_header("broken.tr0", "fixed.tr0", &fixes).unwrap();
+//! println!("{} signals", meta.names.len());
+//! ```
+
+use crate::decode::{read_header_text, Blocks, BLOCK_HEADER_LEN};
+use crate::limits::ParserLimits;
+use crate::parser::{parse_header_with_limits, HeaderMetadata};
+use crate::source::FileData;
+use crate::types::*;
+use std::ops::Range;
+use tracing::{info, instrument};
+
+/// Width of the numeric count fields at the start of the header
+const COUNT_FIELD_WIDTH: usize = 4;
+
+/// Width of the right-aligned sweep size field
+const SWEEP_SIZE_FIELD_WIDTH: usize = 10;
+
+/// Header fields to overwrite; `None` leaves a field as it is
+///
+/// Text fields longer than their slot are rejected rather than truncated.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HeaderFixes {
+    /// Simulation title (up to 64 bytes)
+    pub title: Option<String>,
+    /// Simulation date (up to 24 bytes)
+    pub date: Option<String>,
+    /// Number of variables, including the scale
+    pub num_variables: Option<u32>,
+    /// Number of probes (signals other than the variables)
+    pub num_probes: Option<u32>,
+    /// Number of sweep parameters (0 or 1)
+    pub num_sweeps: Option<u32>,
+    /// Number of sweep tables
+    pub sweep_size: Option<u32>,
+}
+
+/// Copy `input` to `output` with the header fields in `fixes` rewritten
+///
+/// The patched header is parsed before anything is written; if it still
+/// does not parse, the error is returned and `output` is not created.
+/// Returns the metadata of the repaired header.
+#[instrument(skip(fixes))]
+pub fn repair_header(input: &str, output: &str, fixes: &HeaderFixes) -> Result<HeaderMetadata> {
+    let data = FileData::open(input)?;
+    let repaired = repair_header_bytes(&data, fixes)?;
+    drop(data);
+
+    let (meta, _) = parse_header_with_limits(&repaired, &ParserLimits::unlimited())?;
+    std::fs::write(output, &repaired)?;
+
+    info!(title = %meta.title, vectors = meta.num_vectors, "Header repaired");
+    Ok(meta)
+}
+
+/// Apply `fixes` to an in-memory HSPICE file, returning the patched copy
+///
+/// Only the header block payloads change; block heads, trailers and data
+/// blocks are copied unchanged.
+pub fn repair_header_bytes(data: &[u8], fixes: &HeaderFixes) -> Result<Vec<u8>> {
+    let ranges = header_payload_ranges(data)?;
+    let mut header: Vec<u8> = ranges
+        .iter()
+        .flat_map(|r| data[r.clone()].iter().copied())
+        .collect();
+    patch_header_text(&mut header, fixes)?;
+
+    let mut repaired = data.to_vec();
+    let mut patched = &header[..];
+    for range in ranges {
+        let (block, rest) = patched.split_at(range.len());
+        repaired[range].copy_from_slice(block);
+        patched = rest;
+    }
+    Ok(repaired)
+}
+
+/// Byte ranges of the header block payloads, up to the end marker block
+fn header_payload_ranges(data: &[u8]) -> Result<Vec<Range<usize>>> {
+    let mut ranges = Vec::new();
+    read_header_text(&mut Blocks::new(data, 0), |block, _| {
+        let start = block.offset + BLOCK_HEADER_LEN;
+        ranges.push(start..start + block.payload.len());
+        Ok::<_, WaveformError>(())
+    })?;
+    Ok(ranges)
+}
+
+/// Overwrite fixed-width fields of the concatenated header text
+fn patch_header_text(header: &mut [u8], fixes: &HeaderFixes) -> Result<()> {
+    if header.len() < VECTOR_DESCRIPTION_START_POSITION {
+        return Err(WaveformError::HeaderTooShort { len: header.len() });
+    }
+
+    let counts = [
+        (
+            "num_variables",
+            NUM_OF_VARIABLES_POSITION,
+            fixes.num_variables,
+        ),
+        ("num_probes", NUM_OF_PROBES_POSITION, fixes.num_probes),
+        ("num_sweeps", NUM_OF_SWEEPS_POSITION, fixes.num_sweeps),
+    ];
+    for (what, start, value) in counts {
+        if let Some(value) = value {
+            let text = format!("{:0width$}", value, width = COUNT_FIELD_WIDTH);
+            write_field(header, what, start, start + COUNT_FIELD_WIDTH, &text)?;
+        }
+    }
+
+    if let Some(title) = &fixes.title {
+        write_field(
+            header,
+            "title",
+            TITLE_START_POSITION,
+            DATE_START_POSITION,
+            title,
+        )?;
+    }
+    if let Some(date) = &fixes.date {
+        write_field(header, "date", DATE_START_POSITION, DATE_END_POSITION, date)?;
+    }
+
+    if let Some(size) = fixes.sweep_size {
+        // The field moved between post versions; follow the header's own
+        let start = if &header[POST_START_POSITION2..POST_START_POSITION2 + 4]
+            == POST_STRING21.as_bytes()
+        {
+            SWEEP_SIZE_POSITION2
+        } else {
+            SWEEP_SIZE_POSITION1
+        };
+        let text = format!("{:>width$}", size, width = SWEEP_SIZE_FIELD_WIDTH);
+        write_field(
+            header,
+            "sweep_size",
+            start,
+            start + SWEEP_SIZE_FIELD_WIDTH,
+            &text,
+        )?;
+    }
+    Ok(())
+}
+
+/// Write `value` into `header[start..end]`, padding with spaces
+fn write_field(header: &mut [u8], what: &str, start: usize, end: usize, value: &str) -> Result<()> {
+    if value.len() > end - start {
+        return Err(WaveformError::ParseError(format!(
+            "{} '{}' does not fit in {} bytes",
+            what,
+            value,
+            end - start
+        )));
+    }
+    let field = &mut header[start..end];
+    field.fill(b' ');
+    field[..value.len()].copy_from_slice(value.as_bytes());
+    Ok(())
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{temp_path, SyntheticWaveform};
+
+    /// Header payload offset of the first header block
+    const PAYLOAD_START: usize = 16;
+
+    fn mangle(data: &mut [u8], start: usize, bytes: &[u8]) {
+        data[PAYLOAD_START + start..PAYLOAD_START + start + bytes.len()].copy_from_slice(bytes);
+    }
+
+    #[test]
+    fn test_repair_counts_and_title() {
+        let synth = SyntheticWaveform::new().signals(3).points(20).title("good");
+        let mut data = synth.to_tr0_bytes();
+        mangle(&mut data, NUM_OF_VARIABLES_POSITION, b"\0\0\0\0");
+        mangle(&mut data, TITLE_START_POSITION, &[0xff; 8]);
+        assert!(crate::read_bytes(&data, &ParserLimits::unlimited()).is_err());
+
+        let fixes = HeaderFixes {
+            title: Some("recovered".into()),
+            num_variables: Some(4),
+            ..Default::default()
+        };
+        let repaired = repair_header_bytes(&data, &fixes).unwrap();
+        assert_eq!(repaired.len(), data.len());
+
+        let result = crate::read_bytes(&repaired, &ParserLimits::unlimited()).unwrap();
+        let expected = synth.expected();
+        assert_eq!(result.title, "recovered");
+        assert_eq!(
+            format!("{:?}", result.tables),
+            format!("{:?}", expected.tables)
+        );
+
+        // Everything after the header block is copied verbatim
+        let data_start = header_payload_ranges(&data).unwrap()[0].end;
+        assert_eq!(data[data_start..], repaired[data_start..]);
+    }
+
+    #[test]
+    fn test_repair_sweep_size_v2001() {
+        let synth = SyntheticWaveform::new()
+            .version(PostVersion::V2001)
+            .sweep("temp", vec![0.0, 25.0, 85.0]);
+        let mut data = synth.to_tr0_bytes();
+        mangle(&mut data, SWEEP_SIZE_POSITION2, b"         1");
+        let result = crate::read_bytes(&data, &ParserLimits::unlimited()).unwrap();
+        assert_eq!(result.tables.len(), 1);
+
+        let fixes = HeaderFixes {
+            sweep_size: Some(3),
+            ..Default::default()
+        };
+        let repaired = repair_header_bytes(&data, &fixes).unwrap();
+        let result = crate::read_bytes(&repaired, &ParserLimits::unlimited()).unwrap();
+        assert_eq!(result.tables.len(), 3);
+        assert_eq!(result.tables[2].sweep_value, Some(85.0));
+    }
+
+    #[test]
+    fn test_repair_rejects_overlong_field() {
+        let data = SyntheticWaveform::new().to_tr0_bytes();
+        let fixes = HeaderFixes {
+            num_probes: Some(10_000),
+            ..Default::default()
+        };
+        assert!(repair_header_bytes(&data, &fixes).is_err());
+    }
+
+    #[test]
+    fn test_repair_file_validates_before_writing() {
+        let input = temp_path("repair_in.tr0");
+        let output = temp_path("repair_out.tr0");
+        SyntheticWaveform::new().write_tr0(&input).unwrap();
+        std::fs::remove_file(&output).ok();
+
+        let bad = HeaderFixes {
+            num_variables: Some(0),
+            ..Default::default()
+        };
+        let (input_str, output_str) = (input.to_str().unwrap(), output.to_str().unwrap());
+        assert!(repair_header(input_str, output_str, &bad).is_err());
+        assert!(!output.exists());
+
+        let meta = repair_header(input_str, output_str, &HeaderFixes::default()).unwrap();
+        assert_eq!(meta.num_vectors, 3);
+        assert_eq!(
+            std::fs::read(&input).unwrap(),
+            std::fs::read(&output).unwrap()
+        );
+        std::fs::remove_file(input).ok();
+        std::fs::remove_file(output).ok();
+    }
+}
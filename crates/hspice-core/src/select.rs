@@ -0,0 +1,206 @@
+//! Signal selection by glob or regex pattern
+//!
+//! A pattern is a glob unless it starts with `re:`, in which case the rest
+//! is a regular expression:
+//!
+//! - `v(x1.*)`, `i(vdd?)`, `x[12].out` - glob: `*` matches any run of
+//!   characters, `?` one character, `[...]` one of a set (`[!...]` negates).
+//!   Globs match the whole name and ignore case, like HSPICE.
+//! - `re:^x1\.(a|b)$` - regex, searched anywhere in the name unless anchored.
+//!
+//! HSPICE stores node voltages without the `v(...)` wrapper, so a bare node
+//! name also matches its wrapped form: `v(x1.*)` selects `x1.a`.
+//!
+//! ```rust,no_run
+//! let result = hspice_core::read("chip.tr0").unwrap();
+//! let x1 = result.select("v(x1.*)").unwrap();
+//! for var in &x1.variables[1..] {
+//!     println!("{}", var.name);
+//! }
+//! ```
+
+use crate::types::{DataTable, Result, WaveformError, WaveformResult};
+use regex::{Regex, RegexBuilder};
+
+/// Compiled signal name pattern
+#[derive(Debug, Clone)]
+pub struct SignalPattern {
+    pattern: String,
+    regex: Regex,
+}
+
+impl SignalPattern {
+    /// Compile `pattern`: a regex if prefixed with `re:`, otherwise a glob
+    pub fn new(pattern: &str) -> Result<Self> {
+        let regex = match pattern.strip_prefix("re:") {
+            Some(re) => Regex::new(re),
+            None => RegexBuilder::new(&glob_to_regex(pattern))
+                .case_insensitive(true)
+                .build(),
+        }
+        .map_err(|e| {
+            WaveformError::ParseError(format!("Invalid signal pattern {:?}: {}", pattern, e))
+        })?;
+        Ok(Self {
+            pattern: pattern.to_string(),
+            regex,
+        })
+    }
+
+    /// The pattern as given
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Whether `name`, or for a bare node name `v(name)`, matches
+    pub fn is_match(&self, name: &str) -> bool {
+        self.regex.is_match(name)
+            || (!name.contains('(') && self.regex.is_match(&format!("v({})", name)))
+    }
+
+    /// Names in `names` that match, in order
+    pub fn filter<'a, S: AsRef<str>>(&self, names: &'a [S]) -> Vec<&'a str> {
+        names
+            .iter()
+            .map(AsRef::as_ref)
+            .filter(|name| self.is_match(name))
+            .collect()
+    }
+}
+
+impl WaveformResult {
+    /// Keep the scale and the signals matching `pattern` (see
+    /// [`SignalPattern`]), in every table
+    ///
+    /// Returns an error if the pattern is invalid or matches no signal.
+    pub fn select(&self, pattern: &str) -> Result<WaveformResult> {
+        let pattern = SignalPattern::new(pattern)?;
+        let indices: Vec<usize> = std::iter::once(0)
+            .chain((1..self.variables.len()).filter(|&i| pattern.is_match(&self.variables[i].name)))
+            .collect();
+        if indices.len() == 1 {
+            return Err(WaveformError::ParseError(format!(
+                "No signal matches {:?}",
+                pattern.as_str()
+            )));
+        }
+
+        Ok(WaveformResult {
+            title: self.title.clone(),
+            date: self.date.clone(),
+            analysis: self.analysis,
+            variables: indices.iter().map(|&i| self.variables[i].clone()).collect(),
+            sweep_param: self.sweep_param.clone(),
+            tables: self
+                .tables
+                .iter()
+                .map(|table| DataTable {
+                    sweep_value: table.sweep_value,
+                    vectors: indices.iter().map(|&i| table.vectors[i].clone()).collect(),
+                })
+                .collect(),
+            truncated: self.truncated,
+        })
+    }
+}
+
+/// Translate a glob into an anchored regex
+fn glob_to_regex(glob: &str) -> String {
+    let mut re = String::from("^");
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => re.push_str(".*"),
+            '?' => re.push('.'),
+            '[' => {
+                let mut class = String::new();
+                if chars.peek() == Some(&'!') {
+                    chars.next();
+                    class.push('^');
+                }
+                let mut closed = false;
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        closed = true;
+                        break;
+                    }
+                    if c == '\\' || c == '[' || c == '^' {
+                        class.push('\\');
+                    }
+                    class.push(c);
+                }
+                if closed && !class.is_empty() && class != "^" {
+                    re.push('[');
+                    re.push_str(&class);
+                    re.push(']');
+                } else {
+                    // Not a class: match the text literally
+                    re.push_str(&regex::escape(&format!("[{}", class)));
+                }
+            }
+            c => re.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    re.push('$');
+    re
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{AnalysisType, Variable, VectorData};
+
+    #[test]
+    fn test_glob_and_regex() {
+        let glob = SignalPattern::new("v(x1.*)").unwrap();
+        assert!(glob.is_match("x1.a"));
+        assert!(glob.is_match("V(X1.B)"));
+        assert!(!glob.is_match("x10.a"));
+        assert!(!glob.is_match("i(x1.a)"));
+
+        let set = SignalPattern::new("i(vdd[!0-9])").unwrap();
+        assert!(set.is_match("i(vddq)"));
+        assert!(!set.is_match("i(vdd1)"));
+        assert!(SignalPattern::new("out?").unwrap().is_match("out2"));
+        assert!(SignalPattern::new("a[b").unwrap().is_match("a[b"));
+
+        let re = SignalPattern::new(r"re:^i\(vdd").unwrap();
+        assert_eq!(
+            re.filter(&["i(vdd1)", "i(vss)", "i(vdd2)"]),
+            ["i(vdd1)", "i(vdd2)"]
+        );
+        assert!(SignalPattern::new("re:(").is_err());
+    }
+
+    #[test]
+    fn test_select() {
+        let names = ["TIME", "x1.a", "x1.b", "x2.a"];
+        let result = WaveformResult {
+            title: String::new(),
+            date: String::new(),
+            analysis: AnalysisType::Transient,
+            variables: names.iter().map(|n| Variable::new(*n)).collect(),
+            sweep_param: None,
+            tables: vec![DataTable {
+                sweep_value: None,
+                vectors: (0..4)
+                    .map(|i| VectorData::Real((vec![i as f64; 3]).into()))
+                    .collect(),
+            }],
+            truncated: false,
+        };
+
+        let selected = result.select("v(x1.*)").unwrap();
+        let kept: Vec<_> = selected.variables.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(kept, ["TIME", "x1.a", "x1.b"]);
+        assert_eq!(
+            format!("{:?}", selected.tables[0].vectors[2]),
+            format!("{:?}", result.tables[0].vectors[2])
+        );
+        assert!(result.select("nothing*").is_err());
+    }
+}
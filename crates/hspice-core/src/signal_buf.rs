@@ -0,0 +1,148 @@
+//! Shared storage for signal values
+//!
+//! [`VectorData`](crate::VectorData) keeps its values in a [`SignalBuf`], a
+//! reference-counted vector. Cloning a result, a table or a vector shares
+//! the values instead of copying them, so results are cheap to clone,
+//! select from and hand to other threads or the language bindings:
+//!
+//! ```rust
+//! use hspice_core::VectorData;
+//!
+//! let vector = VectorData::Real(vec![0.0, 0.5, 1.0].into());
+//! let copy = vector.clone(); // shares the three values
+//!
+//! let mut changed = copy.clone();
+//! if let VectorData::Real(values) = &mut changed {
+//!     values.push(1.5); // copies them first; `vector` is unchanged
+//! }
+//! assert_eq!(vector.len(), 3);
+//! ```
+
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+
+/// Reference-counted, copy-on-write vector of signal values
+///
+/// Reads go through `Deref` to `Vec<T>`. Writes through `DerefMut` copy
+/// the values first if they are shared (see `Arc::make_mut`), so a clone
+/// never sees another's changes.
+pub struct SignalBuf<T>(Arc<Vec<T>>);
+
+impl<T> SignalBuf<T> {
+    /// Whether `self` and `other` share their values
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl<T: Clone> SignalBuf<T> {
+    /// The values as a plain vector, copied only if shared
+    pub fn into_vec(self) -> Vec<T> {
+        Arc::try_unwrap(self.0).unwrap_or_else(|shared| (*shared).clone())
+    }
+}
+
+impl<T> Clone for SignalBuf<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T> Default for SignalBuf<T> {
+    fn default() -> Self {
+        Self(Arc::new(Vec::new()))
+    }
+}
+
+impl<T: PartialEq> PartialEq for SignalBuf<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T> Deref for SignalBuf<T> {
+    type Target = Vec<T>;
+
+    fn deref(&self) -> &Vec<T> {
+        &self.0
+    }
+}
+
+impl<T: Clone> DerefMut for SignalBuf<T> {
+    fn deref_mut(&mut self) -> &mut Vec<T> {
+        Arc::make_mut(&mut self.0)
+    }
+}
+
+impl<T> From<Vec<T>> for SignalBuf<T> {
+    fn from(values: Vec<T>) -> Self {
+        Self(Arc::new(values))
+    }
+}
+
+impl<T> FromIterator<T> for SignalBuf<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<T>>())
+    }
+}
+
+impl<T: Clone> IntoIterator for SignalBuf<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_vec().into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SignalBuf<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SignalBuf<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for SignalBuf<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for SignalBuf<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::deserialize(deserializer).map(Self::from)
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clone_shares_until_written() {
+        let a: SignalBuf<f64> = vec![1.0, 2.0].into();
+        let mut b = a.clone();
+        assert!(a.ptr_eq(&b));
+
+        b.push(3.0);
+        assert!(!a.ptr_eq(&b));
+        assert_eq!(*a, [1.0, 2.0]);
+        assert_eq!(*b, [1.0, 2.0, 3.0]);
+        assert_eq!(b.into_vec(), [1.0, 2.0, 3.0]);
+    }
+}
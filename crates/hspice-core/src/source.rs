@@ -0,0 +1,251 @@
+//! File access for the HSPICE readers
+//!
+//! Files are memory-mapped when possible. Where mapping is not possible
+//! (32-bit targets, whose address space cannot hold multi-gigabyte files),
+//! whole-file reads fall back to a buffered copy and the streaming reader
+//! falls back to reading one block at a time with positioned reads.
+
+use crate::decode::block_endian;
+use crate::types::{Result, WaveformError};
+use memmap2::Mmap;
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::ops::Deref;
+use std::path::Path;
+use tracing::{debug, info};
+
+/// Largest file the streaming reader maps; bigger files use windowed reads
+#[cfg(target_pointer_width = "64")]
+pub(crate) const MAX_MAPPED_LEN: u64 = u64::MAX;
+#[cfg(not(target_pointer_width = "64"))]
+pub(crate) const MAX_MAPPED_LEN: u64 = 1 << 30;
+
+/// Block head (16 bytes) and trailer (4 bytes) around every block payload
+const BLOCK_HEAD_LEN: usize = 16;
+const BLOCK_TRAILER_LEN: usize = 4;
+
+/// End of the header text
+const HEADER_END_MARKER: &[u8] = b"$&%#";
+
+// ============================================================================
+// Whole-File Access
+// ============================================================================
+
+/// Contents of a whole file, mapped or copied into memory
+pub(crate) enum FileData {
+    Mapped(Mmap),
+    Buffered(Vec<u8>),
+}
+
+impl FileData {
+    /// Map `path`, or read it into memory if it cannot be mapped
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = File::open(path)?;
+        match unsafe { Mmap::map(&file) } {
+            Ok(mmap) => Ok(FileData::Mapped(mmap)),
+            Err(e) => {
+                let len = file.metadata()?.len();
+                let len = usize::try_from(len).map_err(|_| {
+                    WaveformError::FormatError(format!(
+                        "File of {} bytes does not fit in memory on this target; \
+                         use the streaming reader",
+                        len
+                    ))
+                })?;
+                info!(error = %e, bytes = len, "Memory map failed, reading into memory");
+                let mut data = Vec::with_capacity(len);
+                (&file).read_to_end(&mut data)?;
+                Ok(FileData::Buffered(data))
+            }
+        }
+    }
+}
+
+impl Deref for FileData {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            FileData::Mapped(mmap) => mmap,
+            FileData::Buffered(data) => data,
+        }
+    }
+}
+
+// ============================================================================
+// Streaming Access
+// ============================================================================
+
+/// Source of bytes for the streaming reader
+pub(crate) enum StreamSource {
+    Mapped(Mmap),
+    /// Positioned reads of one block at a time; file offsets are u64 so
+    /// files past 4 GiB work on 32-bit targets
+    Windowed {
+        file: File,
+        len: u64,
+    },
+    /// Bytes already in memory (e.g. a browser file buffer)
+    Memory(Vec<u8>),
+}
+
+impl StreamSource {
+    /// Map `path` if it is at most `max_mapped` bytes and mapping succeeds,
+    /// otherwise fall back to windowed reads
+    pub fn open<P: AsRef<Path>>(path: P, max_mapped: u64) -> Result<Self> {
+        let file = File::open(path)?;
+        let len = file.metadata()?.len();
+        if len <= max_mapped {
+            match unsafe { Mmap::map(&file) } {
+                Ok(mmap) => return Ok(StreamSource::Mapped(mmap)),
+                Err(e) => info!(error = %e, "Memory map failed, using windowed reads"),
+            }
+        } else {
+            info!(bytes = len, "File too large to map, using windowed reads");
+        }
+        Ok(StreamSource::Windowed { file, len })
+    }
+
+    /// Total file length
+    pub fn len(&self) -> u64 {
+        match self {
+            StreamSource::Mapped(mmap) => mmap.len() as u64,
+            StreamSource::Windowed { len, .. } => *len,
+            StreamSource::Memory(data) => data.len() as u64,
+        }
+    }
+
+    /// Bytes holding at least the header blocks, starting at offset 0
+    ///
+    /// A mapped file or memory buffer returns everything. A windowed file
+    /// is scanned block by block up to the header end marker; scanning
+    /// stops early on a malformed block so the header parser reports the
+    /// error.
+    pub fn header_bytes(&mut self, max_bytes: usize) -> Result<Cow<'_, [u8]>> {
+        let (file, len) = match self {
+            StreamSource::Mapped(mmap) => return Ok(Cow::Borrowed(&mmap[..])),
+            StreamSource::Memory(data) => return Ok(Cow::Borrowed(&data[..])),
+            StreamSource::Windowed { file, len } => (file, *len),
+        };
+
+        let mut buffer = Vec::new();
+        let mut payload = Vec::new();
+        while (buffer.len() as u64) < len && payload.len() <= max_bytes {
+            let block = read_block(file, buffer.len() as u64, len)?;
+            let complete = block.len() >= BLOCK_HEAD_LEN + BLOCK_TRAILER_LEN
+                && block_len(&block) == Some(block.len());
+            if complete {
+                payload.extend_from_slice(&block[BLOCK_HEAD_LEN..block.len() - BLOCK_TRAILER_LEN]);
+            }
+            buffer.extend_from_slice(&block);
+            if !complete || payload.windows(4).any(|w| w == HEADER_END_MARKER) {
+                break;
+            }
+        }
+        debug!(bytes = buffer.len(), "Header scanned");
+        Ok(Cow::Owned(buffer))
+    }
+
+    /// Bytes starting at `pos` that hold the next block
+    ///
+    /// A mapped file or memory buffer returns the rest of the data; a
+    /// windowed file reads just the block (or what is left of it if the
+    /// file is truncated).
+    pub fn block_at(&mut self, pos: u64) -> Result<Cow<'_, [u8]>> {
+        match self {
+            StreamSource::Mapped(mmap) => Ok(Cow::Borrowed(&mmap[pos as usize..])),
+            StreamSource::Memory(data) => Ok(Cow::Borrowed(&data[pos as usize..])),
+            StreamSource::Windowed { file, len } => read_block(file, pos, *len).map(Cow::Owned),
+        }
+    }
+}
+
+/// Total length of the block whose head starts `block`, if the head is valid
+fn block_len(block: &[u8]) -> Option<usize> {
+    let head = block.get(..BLOCK_HEAD_LEN)?;
+    let size = block_endian(head)?.read_i32([head[12], head[13], head[14], head[15]]);
+    Some(BLOCK_HEAD_LEN + usize::try_from(size).ok()? + BLOCK_TRAILER_LEN)
+}
+
+/// Read the block at `pos`, clamped to the end of the file
+///
+/// If the head is malformed only the head is returned, leaving the error
+/// to the block parser.
+fn read_block(file: &mut File, pos: u64, len: u64) -> Result<Vec<u8>> {
+    let available = len.saturating_sub(pos);
+    let mut head = vec![0u8; available.min(BLOCK_HEAD_LEN as u64) as usize];
+    file.seek(SeekFrom::Start(pos))?;
+    file.read_exact(&mut head)?;
+
+    let Some(total) = block_len(&head) else {
+        return Ok(head);
+    };
+    let total = (total as u64).min(available) as usize;
+
+    let mut block = head;
+    block.resize(total, 0);
+    file.read_exact(&mut block[BLOCK_HEAD_LEN..])?;
+    Ok(block)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{temp_path, SyntheticWaveform};
+
+    #[test]
+    fn test_windowed_blocks_match_mapped() {
+        let path = temp_path("source_blocks.tr0");
+        SyntheticWaveform::new()
+            .signals(3)
+            .points(50)
+            .block_size(16)
+            .write_tr0(&path)
+            .unwrap();
+        let data = std::fs::read(&path).unwrap();
+
+        let mut windowed = StreamSource::open(&path, 0).unwrap();
+        assert!(matches!(windowed, StreamSource::Windowed { .. }));
+        assert_eq!(windowed.len(), data.len() as u64);
+
+        let header = windowed.header_bytes(usize::MAX).unwrap().into_owned();
+        assert!(data.starts_with(&header));
+        let (_, data_start) =
+            crate::parser::parse_header_with_limits(&header, &Default::default()).unwrap();
+        assert_eq!(data_start, header.len());
+
+        // Walk every block and check it against the file contents
+        let mut pos = data_start;
+        while pos < data.len() {
+            let block = windowed.block_at(pos as u64).unwrap().into_owned();
+            assert_eq!(block_len(&block), Some(block.len()));
+            assert_eq!(&data[pos..pos + block.len()], &block[..]);
+            pos += block.len();
+        }
+        assert_eq!(pos, data.len());
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_windowed_truncated_and_invalid_blocks() {
+        let path = temp_path("source_truncated.tr0");
+        let data = SyntheticWaveform::new().points(8).to_tr0_bytes();
+        std::fs::write(&path, &data[..data.len() - 6]).unwrap();
+        let len = data.len() as u64 - 6;
+
+        let mut file = File::open(&path).unwrap();
+        // A block running past the end is cut at the end of the file
+        let header_len = block_len(&data).unwrap();
+        let block = read_block(&mut file, header_len as u64, len).unwrap();
+        assert_eq!(header_len as u64 + block.len() as u64, len);
+        // A head that is not a block head comes back alone
+        let block = read_block(&mut file, 3, len).unwrap();
+        assert_eq!(block.len(), BLOCK_HEAD_LEN);
+        std::fs::remove_file(path).ok();
+    }
+}
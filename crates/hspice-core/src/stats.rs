@@ -0,0 +1,269 @@
+//! Per-signal statistics
+//!
+//! [`VectorData::stats`] computes min, max, mean, RMS and standard deviation
+//! in one pass without copying the data; stepped vectors are summed a step
+//! at a time. Complex vectors are summarized by their magnitude.
+//!
+//! ```rust,no_run
+//! let result = hspice_core::read("sim.tr0").unwrap();
+//! for (var, stats) in result.variables.iter().zip(result.stats()) {
+//!     if let Some(s) = stats {
+//!         println!("{}: mean {} rms {} p-p {}", var.name, s.mean, s.rms, s.peak_to_peak());
+//!     }
+//! }
+//! ```
+
+use crate::types::{DataTable, VectorData, WaveformResult};
+
+/// Summary statistics of one signal
+///
+/// NaN values are skipped. The standard deviation is the population one
+/// (divided by `count`, like NumPy's default).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SignalStats {
+    /// Number of values summarized
+    pub count: usize,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    /// Root mean square
+    pub rms: f64,
+    /// Population standard deviation
+    pub std: f64,
+    /// Whether the values are magnitudes of complex data
+    pub magnitude: bool,
+}
+
+impl SignalStats {
+    /// `max - min`
+    pub fn peak_to_peak(&self) -> f64 {
+        self.max - self.min
+    }
+
+    /// Statistics of the values of `self` and `other` together
+    pub fn merge(&self, other: &SignalStats) -> SignalStats {
+        let mut acc = Accumulator::from_stats(self);
+        acc.merge(&Accumulator::from_stats(other));
+        acc.finish(self.magnitude || other.magnitude)
+            .unwrap_or(*self)
+    }
+}
+
+/// Running sums for one pass (Welford's algorithm, with Chan's update to
+/// add a run of `n` equal values or another accumulator at once)
+#[derive(Debug, Clone, Copy)]
+struct Accumulator {
+    count: usize,
+    mean: f64,
+    /// Sum of squared deviations from the mean
+    m2: f64,
+    sum_squares: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Default for Accumulator {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            mean: 0.0,
+            m2: 0.0,
+            sum_squares: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+}
+
+impl Accumulator {
+    fn from_stats(stats: &SignalStats) -> Self {
+        let n = stats.count as f64;
+        Self {
+            count: stats.count,
+            mean: stats.mean,
+            m2: stats.std * stats.std * n,
+            sum_squares: stats.rms * stats.rms * n,
+            min: stats.min,
+            max: stats.max,
+        }
+    }
+
+    /// Add `n` copies of `x`
+    fn push_run(&mut self, x: f64, n: usize) {
+        if x.is_nan() || n == 0 {
+            return;
+        }
+        self.merge(&Self {
+            count: n,
+            mean: x,
+            m2: 0.0,
+            sum_squares: x * x * n as f64,
+            min: x,
+            max: x,
+        });
+    }
+
+    fn merge(&mut self, other: &Self) {
+        if other.count == 0 {
+            return;
+        }
+        let (n, m) = (self.count as f64, other.count as f64);
+        let total = n + m;
+        let delta = other.mean - self.mean;
+        self.mean += delta * m / total;
+        self.m2 += other.m2 + delta * delta * n * m / total;
+        self.sum_squares += other.sum_squares;
+        self.count += other.count;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+
+    fn finish(&self, magnitude: bool) -> Option<SignalStats> {
+        (self.count > 0).then(|| {
+            let n = self.count as f64;
+            SignalStats {
+                count: self.count,
+                min: self.min,
+                max: self.max,
+                mean: self.mean,
+                rms: (self.sum_squares / n).sqrt(),
+                std: (self.m2 / n).sqrt(),
+                magnitude,
+            }
+        })
+    }
+}
+
+impl VectorData {
+    /// Statistics of the values, or of the magnitudes for complex data;
+    /// None if there is no (non-NaN) value
+    pub fn stats(&self) -> Option<SignalStats> {
+        let mut acc = Accumulator::default();
+        match self {
+            VectorData::Real(v) => v.iter().for_each(|&x| acc.push_run(x, 1)),
+            VectorData::Complex(v) => v.iter().for_each(|z| acc.push_run(z.norm(), 1)),
+            VectorData::Real32(v) => v.iter().for_each(|&x| acc.push_run(x as f64, 1)),
+            VectorData::Stepped { len, steps } => {
+                for (k, &(start, value)) in steps.iter().enumerate() {
+                    let end = steps.get(k + 1).map_or(*len, |&(next, _)| next);
+                    acc.push_run(value, end.saturating_sub(start));
+                }
+            }
+        }
+        acc.finish(self.is_complex())
+    }
+}
+
+impl DataTable {
+    /// Statistics of every vector, scale first (see [`VectorData::stats`])
+    pub fn stats(&self) -> Vec<Option<SignalStats>> {
+        self.vectors.iter().map(VectorData::stats).collect()
+    }
+}
+
+impl WaveformResult {
+    /// Statistics of every variable over all tables, in the order of
+    /// `variables` (scale first)
+    ///
+    /// Use [`DataTable::stats`] for one sweep point.
+    pub fn stats(&self) -> Vec<Option<SignalStats>> {
+        let mut totals: Vec<Option<SignalStats>> = vec![None; self.variables.len()];
+        for table in &self.tables {
+            for (total, stats) in totals.iter_mut().zip(table.stats()) {
+                *total = match (*total, stats) {
+                    (Some(a), Some(b)) => Some(a.merge(&b)),
+                    (a, b) => a.or(b),
+                };
+            }
+        }
+        totals
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Variable;
+    use num_complex::Complex64;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-12 * b.abs().max(1.0)
+    }
+
+    #[test]
+    fn test_real_stats() {
+        let stats = VectorData::Real(vec![1.0, 2.0, 3.0, 4.0, f64::NAN].into())
+            .stats()
+            .unwrap();
+        assert_eq!(stats.count, 4);
+        assert_eq!(
+            (stats.min, stats.max, stats.peak_to_peak()),
+            (1.0, 4.0, 3.0)
+        );
+        assert!(close(stats.mean, 2.5));
+        assert!(close(stats.rms, 7.5f64.sqrt()));
+        assert!(close(stats.std, 1.25f64.sqrt()));
+        assert!(!stats.magnitude);
+
+        assert_eq!(VectorData::Real(vec![].into()).stats(), None);
+    }
+
+    #[test]
+    fn test_stepped_matches_real() {
+        let values: Vec<f64> = (0..64).map(|i| (i / 16) as f64 * 0.5 - 0.3).collect();
+        let stepped = VectorData::compact(values.clone());
+        assert!(stepped.is_stepped());
+        let (a, b) = (
+            stepped.stats().unwrap(),
+            VectorData::Real(values.into()).stats().unwrap(),
+        );
+        assert_eq!(a.count, b.count);
+        assert!(close(a.mean, b.mean) && close(a.rms, b.rms) && close(a.std, b.std));
+    }
+
+    #[test]
+    fn test_complex_magnitude() {
+        let stats =
+            VectorData::Complex(vec![Complex64::new(3.0, 4.0), Complex64::new(0.0, 1.0)].into())
+                .stats()
+                .unwrap();
+        assert!(stats.magnitude);
+        assert_eq!((stats.min, stats.max), (1.0, 5.0));
+        assert!(close(stats.mean, 3.0));
+    }
+
+    #[test]
+    fn test_result_stats_merge_tables() {
+        let table = |values: Vec<f64>| DataTable {
+            sweep_value: None,
+            vectors: vec![
+                VectorData::Real((0..values.len()).map(|i| i as f64).collect()),
+                VectorData::Real(values.into()),
+            ],
+        };
+        let result = WaveformResult {
+            title: String::new(),
+            date: String::new(),
+            analysis: crate::types::AnalysisType::Transient,
+            variables: vec![Variable::new("TIME"), Variable::new("v(out)")],
+            sweep_param: Some("temp".into()),
+            tables: vec![table(vec![1.0, 2.0]), table(vec![3.0, 4.0, 5.0])],
+            truncated: false,
+        };
+        let all = VectorData::Real(vec![1.0, 2.0, 3.0, 4.0, 5.0].into())
+            .stats()
+            .unwrap();
+        let merged = result.stats()[1].unwrap();
+        assert_eq!(merged.count, 5);
+        assert_eq!((merged.min, merged.max), (1.0, 5.0));
+        assert!(close(merged.mean, all.mean));
+        assert!(close(merged.rms, all.rms));
+        assert!(close(merged.std, all.std));
+        assert_eq!(result.tables[1].stats()[1].unwrap().count, 3);
+    }
+}
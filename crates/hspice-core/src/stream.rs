@@ -10,23 +10,41 @@
 //! - Incomplete rows at block boundaries are properly accumulated
 //! - Peak memory is O(chunk_size * num_signals), not O(file_size)
 
-use crate::parser::{parse_header_only, HeaderMetadata};
-use crate::types::{PostVersion, Result, VectorData, COMPLEX_VAR};
-use memmap2::Mmap;
+use crate::decimate::{decimate_chunk, Method};
+use crate::decode::Header;
+use crate::index::WaveformIndex;
+use crate::limits::ParserLimits;
+use crate::monotonic::ScaleFilter;
+use crate::options::{NonMonotonic, ReadOptions};
+use crate::parser::{header_variables, read_header_bytes};
+use crate::progress::Progress;
+use crate::select::SignalPattern;
+use crate::source::{StreamSource, MAX_MAPPED_LEN};
+use crate::types::{PostVersion, Result, Variable, VectorData, WaveformError};
 use num_complex::Complex64;
 use std::collections::{HashMap, HashSet};
-use std::fs::File;
 use std::path::Path;
-use tracing::{info, instrument, trace};
+use std::sync::mpsc::{sync_channel, Receiver};
+use std::sync::Mutex;
+use std::thread::JoinHandle;
+use tracing::{info, instrument, trace, warn};
 
 /// Default chunk size (minimum number of time points per chunk)
 pub const DEFAULT_CHUNK_SIZE: usize = 10000;
 
+/// Minimum distance in bytes between remembered seek positions
+const SEEK_MARK_SPACING: u64 = 1 << 20;
+
 /// A chunk of data from the streaming reader
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DataChunk {
     /// Index of this chunk (0-based)
     pub chunk_index: usize,
+    /// Sweep table the rows belong to (0-based); a chunk never spans tables
+    pub table_index: usize,
+    /// Sweep parameter value of that table, None if the file is not swept
+    pub sweep_value: Option<f64>,
     /// Time range [start, end] for this chunk
     pub time_range: (f64, f64),
     /// Signal data for this chunk
@@ -35,6 +53,7 @@ pub struct DataChunk {
 
 /// Metadata about the streaming file
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StreamMetadata {
     /// File title
     pub title: String,
@@ -50,53 +69,278 @@ pub struct StreamMetadata {
     pub is_complex: bool,
 }
 
+/// User function applied to each chunk before it is yielded
+type ChunkTransform = Box<dyn FnMut(DataChunk) -> DataChunk + Send + Sync>;
+
+/// User function told the progress after each chunk
+type ProgressCallback = Box<dyn FnMut(&Progress) + Send + Sync>;
+
+/// Reader state at the start of a block, remembered so `seek_time` can
+/// restart there instead of at the beginning of the data
+#[derive(Debug, Clone)]
+struct SeekMark {
+    position: u64,
+    first_read: bool,
+    pending_data: Vec<f64>,
+    /// Scale value of the first row that completes in this block
+    first_scale: f64,
+    /// Values at the start of the block to drop, the tail of a row whose
+    /// start is not kept (marks from an index sidecar)
+    lead: usize,
+}
+
+/// Complete rows laid out end to end, `width` values each
+///
+/// Decoding appends to one flat buffer instead of allocating a `Vec` per
+/// row; buffers are cleared and reused from block to block and chunk to
+/// chunk, so a long run allocates only while they grow.
+#[derive(Debug, Default)]
+struct RowBuffer {
+    values: Vec<f64>,
+    width: usize,
+}
+
+impl RowBuffer {
+    fn new(width: usize) -> Self {
+        Self {
+            values: Vec::new(),
+            width,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.values.len().checked_div(self.width).unwrap_or(0)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    fn clear(&mut self) {
+        self.values.clear();
+    }
+
+    fn row(&self, i: usize) -> &[f64] {
+        &self.values[i * self.width..(i + 1) * self.width]
+    }
+
+    fn iter(&self) -> std::slice::ChunksExact<'_, f64> {
+        self.values.chunks_exact(self.width.max(1))
+    }
+
+    /// Scale value of row `i`
+    fn scale(&self, i: usize) -> f64 {
+        self.values[i * self.width]
+    }
+
+    fn first_scale(&self) -> Option<f64> {
+        (!self.is_empty()).then(|| self.scale(0))
+    }
+
+    fn last_scale(&self) -> Option<f64> {
+        (!self.is_empty()).then(|| self.scale(self.len() - 1))
+    }
+
+    /// Number of leading rows whose scale value satisfies `pred`
+    fn partition_point(&self, mut pred: impl FnMut(f64) -> bool) -> usize {
+        let (mut lo, mut hi) = (0, self.len());
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if pred(self.scale(mid)) {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+        lo
+    }
+
+    /// Append the rows of `other`
+    fn extend(&mut self, other: &RowBuffer) {
+        self.values.extend_from_slice(&other.values);
+    }
+
+    /// Drop the first `n` rows
+    fn drain_front(&mut self, n: usize) {
+        self.values.drain(..(n * self.width).min(self.values.len()));
+    }
+
+    /// Keep only the rows `kept`, given in ascending order
+    fn keep_rows(&mut self, kept: &[usize]) {
+        let width = self.width;
+        for (to, &from) in kept.iter().enumerate() {
+            if to != from {
+                self.values
+                    .copy_within(from * width..(from + 1) * width, to * width);
+            }
+        }
+        self.values.truncate(kept.len() * width);
+    }
+
+    /// Keep only the rows whose scale value satisfies `pred`
+    fn retain(&mut self, mut pred: impl FnMut(f64) -> bool) {
+        let kept: Vec<usize> = (0..self.len()).filter(|&i| pred(self.scale(i))).collect();
+        if kept.len() < self.len() {
+            self.keep_rows(&kept);
+        }
+    }
+}
+
+/// Row cleanup for `mode`, or None if rows are kept as written
+fn new_scale_filter(mode: NonMonotonic) -> Option<ScaleFilter> {
+    (mode != NonMonotonic::Keep).then(|| ScaleFilter::new(mode))
+}
+
 /// True streaming reader for HSPICE files
 ///
 /// Only reads header at open() time. Data blocks are read on-demand.
 /// Block boundaries are always preserved - we never split a data_block.
+///
+/// Files are memory-mapped; files too large to map (over 1 GiB on 32-bit
+/// targets) are read one block at a time instead.
 pub struct HspiceStreamReader {
-    /// Memory-mapped file data
-    mmap: Mmap,
+    /// Mapped or windowed file data
+    source: StreamSource,
+    /// Start of the data section
+    data_start: u64,
     /// Current read position in the data section
-    data_position: usize,
+    data_position: u64,
     /// Header metadata
-    metadata: HeaderMetadata,
+    metadata: Header,
     /// Minimum rows per chunk (may exceed if block is larger)
     min_chunk_size: usize,
+    /// Scale span of each chunk, replacing `min_chunk_size` (see
+    /// `chunk_by_time`)
+    chunk_span: Option<f64>,
     /// Current chunk index
     current_chunk: usize,
     /// Signal filter (None = all signals)
     signal_filter: Option<HashSet<String>>,
     /// Whether we've reached end of data
     finished: bool,
+    /// Whether the end-of-data marker has been read
+    end_marker_seen: bool,
     /// Accumulated rows for current chunk
-    row_buffer: Vec<Vec<f64>>,
+    row_buffer: RowBuffer,
+    /// Rows of the last block read, reused for every block
+    block_rows: RowBuffer,
     /// Pending data from incomplete row at block boundary
     pending_data: Vec<f64>,
     /// Number of columns per row (computed once)
     num_columns: usize,
     /// Whether this is the first data read (for sweep handling)
     first_read: bool,
+    /// Sweep table being read (0-based)
+    table_index: usize,
+    /// Sweep value of that table, once read
+    sweep_value: Option<f64>,
+    /// Whether the end marker of the current table has been read
+    table_done: bool,
+    /// Sparse block positions seen so far, in file order
+    seek_marks: Vec<SeekMark>,
+    /// Values to drop from the next block (see `SeekMark::lead`)
+    skip_values: usize,
+    /// Cleanup of rows whose scale steps back, unless they are kept
+    scale_filter: Option<ScaleFilter>,
+    /// Scale range of the rows kept (see `filter_time`)
+    time_filter: Option<(f64, f64)>,
+    /// Chunk transforms, applied in the order they were added
+    transforms: Vec<ChunkTransform>,
+    /// Byte order and trailer checking for data blocks
+    options: ReadOptions,
+    /// Blocks and rows read so far
+    progress: Progress,
+    /// Progress callback (see `with_progress`)
+    on_progress: Option<ProgressCallback>,
 }
 
 impl HspiceStreamReader {
     /// Open a file for true streaming read
     ///
     /// Only parses the header. Data is read on-demand.
-    #[instrument(skip_all, fields(path = %path.as_ref().display()))]
     pub fn open<P: AsRef<Path>>(path: P, min_chunk_size: usize) -> Result<Self> {
-        let file = File::open(path.as_ref())?;
-        let mmap = unsafe { Mmap::map(&file)? };
+        Self::open_with_limits(path, min_chunk_size, &ParserLimits::unlimited())
+    }
+
+    /// Open a file for streaming read, enforcing `limits`
+    ///
+    /// The header is checked against the signal and table limits, and one
+    /// chunk of `min_chunk_size` rows must fit in `max_memory`.
+    pub fn open_with_limits<P: AsRef<Path>>(
+        path: P,
+        min_chunk_size: usize,
+        limits: &ParserLimits,
+    ) -> Result<Self> {
+        Self::open_with_options(path, min_chunk_size, &ReadOptions::from(*limits))
+    }
+
+    /// Open a file for streaming read, overriding format detection and
+    /// enforcing the limits in `options` (see [`ReadOptions`])
+    #[instrument(skip_all, fields(path = %path.as_ref().display()))]
+    pub fn open_with_options<P: AsRef<Path>>(
+        path: P,
+        min_chunk_size: usize,
+        options: &ReadOptions,
+    ) -> Result<Self> {
+        let path = path.as_ref();
+        let source = StreamSource::open(path, MAX_MAPPED_LEN)?;
+        let reader = Self::from_source(source, min_chunk_size, options)?;
+        match WaveformIndex::load(path) {
+            Ok(Some(index)) => Ok(reader.with_index(&index)),
+            Ok(None) => Ok(reader),
+            Err(e) => {
+                warn!(error = %e, "Ignoring unreadable index sidecar");
+                Ok(reader)
+            }
+        }
+    }
 
+    /// Stream from file contents already in memory
+    ///
+    /// For callers without a filesystem, such as WebAssembly in a browser.
+    /// Only the current chunk is decoded, so peak memory beyond `data`
+    /// itself is one chunk.
+    pub fn from_bytes(data: Vec<u8>, min_chunk_size: usize) -> Result<Self> {
+        Self::from_bytes_with_limits(data, min_chunk_size, &ParserLimits::unlimited())
+    }
+
+    /// Stream from file contents in memory, enforcing `limits`
+    pub fn from_bytes_with_limits(
+        data: Vec<u8>,
+        min_chunk_size: usize,
+        limits: &ParserLimits,
+    ) -> Result<Self> {
+        Self::from_bytes_with_options(data, min_chunk_size, &ReadOptions::from(*limits))
+    }
+
+    /// Stream from file contents in memory as `options` say
+    pub fn from_bytes_with_options(
+        data: Vec<u8>,
+        min_chunk_size: usize,
+        options: &ReadOptions,
+    ) -> Result<Self> {
+        Self::from_source(StreamSource::Memory(data), min_chunk_size, options)
+    }
+
+    /// Open a stream over an already opened source
+    pub(crate) fn from_source(
+        mut source: StreamSource,
+        min_chunk_size: usize,
+        options: &ReadOptions,
+    ) -> Result<Self> {
+        let limits = &options.limits;
         // Parse header only - returns metadata and data start position
-        let (metadata, data_position) = parse_header_only(&mmap)?;
+        let (metadata, data_position) =
+            read_header_bytes(&source.header_bytes(limits.max_memory)?, options)?;
+        let data_position = data_position as u64;
 
         // Compute number of columns per row
-        let num_columns = if metadata.var_type == COMPLEX_VAR {
-            metadata.num_vectors + (metadata.num_variables - 1) as usize
-        } else {
-            metadata.num_vectors
-        };
+        let num_columns = metadata.num_columns();
+        ParserLimits::check(
+            "chunk values",
+            min_chunk_size.saturating_mul(num_columns),
+            limits.max_values(),
+        )?;
 
         info!(
             signals = metadata.names.len(),
@@ -106,26 +350,208 @@ impl HspiceStreamReader {
         );
 
         Ok(Self {
-            mmap,
+            source,
+            data_start: data_position,
             data_position,
             metadata,
             min_chunk_size: min_chunk_size.max(1),
+            chunk_span: None,
             current_chunk: 0,
             signal_filter: None,
             finished: false,
-            row_buffer: Vec::new(),
+            end_marker_seen: false,
+            row_buffer: RowBuffer::new(num_columns),
+            block_rows: RowBuffer::new(num_columns),
             pending_data: Vec::new(),
             num_columns,
             first_read: true,
+            table_index: 0,
+            sweep_value: None,
+            table_done: false,
+            seek_marks: Vec::new(),
+            skip_values: 0,
+            scale_filter: new_scale_filter(options.non_monotonic),
+            time_filter: None,
+            transforms: Vec::new(),
+            options: *options,
+            progress: Progress::default(),
+            on_progress: None,
         })
     }
 
     /// Set signal filter to only read specific signals
     pub fn with_signals(mut self, signals: Vec<String>) -> Self {
-        self.signal_filter = Some(signals.into_iter().collect());
+        self.set_signals(Some(signals));
+        self
+    }
+
+    /// Only read the signals whose names match `pattern` (see
+    /// [`SignalPattern`])
+    ///
+    /// Returns an error if the pattern is invalid or matches no signal.
+    pub fn with_signal_pattern(self, pattern: &str) -> Result<Self> {
+        let pattern = SignalPattern::new(pattern)?;
+        let signals: Vec<String> = pattern
+            .filter(&self.metadata.names)
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        if signals.is_empty() {
+            return Err(WaveformError::ParseError(format!(
+                "No signal matches {:?}",
+                pattern.as_str()
+            )));
+        }
+        Ok(self.with_signals(signals))
+    }
+
+    /// Change the signal filter (`None` = all signals)
+    ///
+    /// Takes effect from the next chunk; rows already buffered are decoded
+    /// with the new filter.
+    pub fn set_signals(&mut self, signals: Option<Vec<String>>) {
+        self.signal_filter = signals.map(|s| s.into_iter().collect());
+    }
+
+    /// Apply `f` to every chunk inside the iterator, after decoding and
+    /// before the chunk is yielded
+    ///
+    /// Use it for filtering, decimation or unit conversion while the chunk
+    /// is still in cache. Calls chain in order; transforms stay in place
+    /// across `reset` and `seek_time`.
+    ///
+    /// ```rust,no_run
+    /// use hspice_core::{read_stream, VectorData};
+    ///
+    /// let reader = read_stream("large.tr0").unwrap().map_chunks(|mut chunk| {
+    ///     // Keep every 4th point
+    ///     for vector in chunk.data.values_mut() {
+    ///         if let VectorData::Real(v) = vector {
+    ///             *v = v.iter().step_by(4).copied().collect();
+    ///         }
+    ///     }
+    ///     chunk
+    /// });
+    /// ```
+    pub fn map_chunks<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(DataChunk) -> DataChunk + Send + Sync + 'static,
+    {
+        self.transforms.push(Box::new(f));
+        self
+    }
+
+    /// Apply `f` to every signal of every chunk, the scale excepted
+    ///
+    /// `f` gets the signal name and its vector for the chunk, in place. Runs
+    /// as a chunk transform after those added before it.
+    ///
+    /// ```rust,no_run
+    /// use hspice_core::{read_stream, VectorData};
+    ///
+    /// let reader = read_stream("large.tr0").unwrap().map_signals(|name, vector| {
+    ///     if let (true, VectorData::Real(v)) = (name.starts_with("i("), vector) {
+    ///         v.iter_mut().for_each(|x| *x *= 1e3); // A -> mA
+    ///     }
+    /// });
+    /// ```
+    pub fn map_signals<F>(self, mut f: F) -> Self
+    where
+        F: FnMut(&str, &mut VectorData) + Send + Sync + 'static,
+    {
+        let scale_name = self.metadata.scale_name.clone();
+        self.map_chunks(move |mut chunk| {
+            for (name, vector) in chunk.data.iter_mut() {
+                if *name != scale_name {
+                    f(name, vector);
+                }
+            }
+            chunk
+        })
+    }
+
+    /// Keep only the rows with a scale value in `[start, end]`
+    ///
+    /// Rows are dropped as blocks are decoded, so chunks hold kept rows
+    /// only and chunks without any are skipped. Every table of a swept file
+    /// is filtered. The whole data is still read; `seek_time(start)` skips
+    /// ahead in the first table.
+    pub fn filter_time(mut self, start: f64, end: f64) -> Self {
+        self.time_filter = Some((start, end));
+        self
+    }
+
+    /// Decimate every chunk to about `1 / factor` of its rows with `method`
+    /// (see [`decimate_chunk`])
+    ///
+    /// Each chunk is decimated on its own, so the first and last rows of
+    /// every chunk are kept. Runs as a chunk transform after those added
+    /// before it.
+    pub fn with_decimation(self, method: Method, factor: usize) -> Self {
+        let scale_name = self.metadata.scale_name.clone();
+        self.map_chunks(move |chunk| decimate_chunk(chunk, &scale_name, method, factor))
+    }
+
+    /// Make each chunk cover one window of `duration` in scale units
+    /// instead of a number of rows
+    ///
+    /// Windows are aligned to multiples of `duration` (`[0, 1µs)`,
+    /// `[1µs, 2µs)`, ...), so chunks map onto viewer tiles and windowed
+    /// analysis; windows without rows are skipped. A chunk holds the whole
+    /// window however many rows that is. A `duration` that is not positive
+    /// and finite keeps chunking by rows.
+    pub fn chunk_by_time(mut self, duration: f64) -> Self {
+        self.chunk_span = (duration > 0.0 && duration.is_finite()).then_some(duration);
         self
     }
 
+    /// Call `f` with the progress through the file after each chunk
+    ///
+    /// Rows count the chunks yielded since opening or the last `reset`.
+    pub fn with_progress<F>(mut self, f: F) -> Self
+    where
+        F: FnMut(&Progress) + Send + Sync + 'static,
+    {
+        self.on_progress = Some(Box::new(f));
+        self
+    }
+
+    /// Decode chunks on a background thread, up to `depth` chunks ahead
+    /// of the consumer
+    ///
+    /// Reading and decoding the next chunks then overlaps with whatever the
+    /// consumer does with the current one, which pays off on slow disks and
+    /// network filesystems. Chunk transforms and the progress callback run
+    /// on the background thread. Iteration stops after the first error, as
+    /// it would without prefetching. Needs threads, so not for wasm32.
+    pub fn prefetch(self, depth: usize) -> PrefetchReader {
+        PrefetchReader::new(self, depth)
+    }
+
+    /// Read point by point instead of in chunks (see [`RowIterator`])
+    ///
+    /// Rows already buffered for the next chunk come first.
+    pub fn rows(mut self) -> RowIterator {
+        let rows = std::mem::replace(&mut self.row_buffer, RowBuffer::new(self.num_columns));
+        RowIterator {
+            reader: self,
+            rows,
+            next: 0,
+            failed: false,
+        }
+    }
+
+    /// Parsed header of the file
+    pub(crate) fn header(&self) -> &Header {
+        &self.metadata
+    }
+
+    /// Variables of the file, the scale first, typed as a full read types
+    /// them
+    pub fn variables(&self) -> Vec<Variable> {
+        header_variables(&self.metadata)
+    }
+
     /// Get file metadata
     pub fn metadata(&self) -> StreamMetadata {
         StreamMetadata {
@@ -134,19 +560,166 @@ impl HspiceStreamReader {
             scale_name: self.metadata.scale_name.clone(),
             signal_names: self.metadata.names.clone(),
             post_version: self.metadata.post_version,
-            is_complex: self.metadata.var_type == COMPLEX_VAR,
+            is_complex: self.metadata.is_complex,
         }
     }
 
+    /// Upper estimate of the rows in the file, from its size and row width
+    ///
+    /// Block framing, sweep values and end markers are counted as data, so
+    /// the real count is a little lower. Costs nothing: no data is read.
+    pub fn estimated_rows(&self) -> usize {
+        let item_size = self.metadata.post_version.value_size();
+        let data_bytes = self.source.len().saturating_sub(self.data_start);
+        usize::try_from(data_bytes / (item_size * self.num_columns.max(1)) as u64)
+            .unwrap_or(usize::MAX)
+    }
+
+    /// True once the end-of-data marker of the last sweep table has been
+    /// read
+    ///
+    /// A file the simulator is still writing has no end marker yet, so
+    /// after the last chunk this tells a finished file from a growing one.
+    pub fn is_complete(&self) -> bool {
+        self.end_marker_seen
+    }
+
     /// Reset reader to beginning of data section
     pub fn reset(&mut self) {
-        if let Ok((_, pos)) = parse_header_only(&self.mmap) {
-            self.data_position = pos;
-            self.current_chunk = 0;
-            self.finished = false;
-            self.row_buffer.clear();
-            self.pending_data.clear();
-            self.first_read = true;
+        self.data_position = self.data_start;
+        self.current_chunk = 0;
+        self.finished = false;
+        self.end_marker_seen = false;
+        self.row_buffer.clear();
+        self.pending_data.clear();
+        self.table_index = 0;
+        self.start_table();
+        self.progress = Progress::default();
+    }
+
+    /// Reset the per-table state for the first block of a table
+    fn start_table(&mut self) {
+        self.first_read = true;
+        self.sweep_value = None;
+        self.table_done = false;
+        self.skip_values = 0;
+        self.scale_filter = new_scale_filter(self.options.non_monotonic);
+    }
+
+    /// Seek with the marks of the first table of `index` (see
+    /// [`crate::index`]) instead of only the blocks already passed
+    ///
+    /// Streams opened from a path load a fresh `.idx` sidecar on their own.
+    pub fn with_index(mut self, index: &WaveformIndex) -> Self {
+        if let Some(table) = index.tables.first() {
+            self.seek_marks = table
+                .marks
+                .iter()
+                .map(|mark| SeekMark {
+                    position: mark.offset,
+                    first_read: mark.offset == self.data_start,
+                    pending_data: Vec::new(),
+                    first_scale: mark.first_scale,
+                    lead: mark.lead,
+                })
+                .collect();
+        }
+        self
+    }
+
+    /// Position the reader so the next chunk starts at the first point
+    /// whose scale value is at least `scale`
+    ///
+    /// The scale must be ascending, as TIME is within one table. Seeking
+    /// restarts from the nearest block already passed, so scrolling back
+    /// and forth over a large file only decodes the blocks in between.
+    /// Past the end, the next chunk is `None`. In a swept file this seeks
+    /// within the first table; past its end, the next chunk starts the
+    /// second.
+    pub fn seek_time(&mut self, scale: f64) -> Result<()> {
+        let mark = self
+            .seek_marks
+            .iter()
+            .rposition(|m| m.first_scale <= scale)
+            .map(|i| self.seek_marks[i].clone());
+
+        self.reset();
+        if let Some(mark) = mark {
+            self.data_position = mark.position;
+            self.first_read = mark.first_read;
+            self.pending_data = mark.pending_data;
+            self.skip_values = mark.lead;
+        }
+
+        while self.next_rows()? {
+            let start = self.block_rows.partition_point(|t| t < scale);
+            if start < self.block_rows.len() {
+                self.block_rows.drain_front(start);
+                self.row_buffer.extend(&self.block_rows);
+                break;
+            }
+        }
+        trace!(scale, position = self.data_position, "Seek complete");
+        Ok(())
+    }
+
+    /// Read the next block into `block_rows` as complete rows, remembering
+    /// seek positions
+    ///
+    /// Returns false at the end of the data, after flushing any rows left
+    /// over from the last block.
+    fn next_rows(&mut self) -> Result<bool> {
+        let position = self.data_position;
+        let first_read = self.first_read;
+        // Scales start over in every table, so only the first has marks
+        let spaced = self.table_index == 0
+            && self
+                .seek_marks
+                .last()
+                .is_none_or(|m| position >= m.position + SEEK_MARK_SPACING);
+        let pending_data = spaced.then(|| self.pending_data.clone());
+
+        match self.read_one_block()? {
+            Some(block_data) => self.block_to_rows(&block_data),
+            None if !self.pending_data.is_empty() => self.flush_pending(),
+            None => return Ok(false),
+        }
+        if let Some(filter) = self.scale_filter.as_mut() {
+            let kept = filter.keep(self.block_rows.iter().map(|row| row[0]))?;
+            if kept.len() < self.block_rows.len() {
+                self.block_rows.keep_rows(&kept);
+            }
+        }
+
+        if let (Some(pending_data), Some(first_scale)) =
+            (pending_data, self.block_rows.first_scale())
+        {
+            self.seek_marks.push(SeekMark {
+                position,
+                first_read,
+                pending_data,
+                first_scale,
+                lead: 0,
+            });
+        }
+        if let Some((start, end)) = self.time_filter {
+            self.block_rows.retain(|t| (start..=end).contains(&t));
+        }
+        Ok(true)
+    }
+
+    /// Like `next_rows`, but moving on to the next sweep table at the end of
+    /// one
+    fn next_table_rows(&mut self) -> Result<bool> {
+        loop {
+            if self.next_rows()? {
+                return Ok(true);
+            }
+            if !self.table_done || self.finished {
+                return Ok(false);
+            }
+            self.table_index += 1;
+            self.start_table();
         }
     }
 
@@ -155,20 +728,27 @@ impl HspiceStreamReader {
     fn read_one_block(&mut self) -> Result<Option<Vec<f64>>> {
         use crate::block_reader::BlockReader;
 
-        if self.finished || self.data_position >= self.mmap.len() {
+        if self.finished || self.table_done || self.data_position >= self.source.len() {
             return Ok(None);
         }
 
-        let data_slice = &self.mmap[self.data_position..];
-        let mut block_reader = BlockReader::new(data_slice, self.metadata.post_version);
+        let data_slice = self.source.block_at(self.data_position)?;
+        let mut block_reader = BlockReader::new(&data_slice, self.metadata.post_version)
+            .with_base_offset(self.data_position)
+            .with_options(&self.options);
 
         match block_reader.next_block()? {
             Some(block) => {
                 // Update position
-                self.data_position += block_reader.bytes_consumed();
+                self.data_position += block_reader.bytes_consumed() as u64;
+                self.progress.blocks += 1;
 
                 if block.is_end {
-                    self.finished = true;
+                    self.table_done = true;
+                    if self.table_index + 1 >= self.metadata.sweep_size {
+                        self.finished = true;
+                        self.end_marker_seen = true;
+                    }
                 }
 
                 // Remove end marker if present
@@ -186,62 +766,83 @@ impl HspiceStreamReader {
         }
     }
 
-    /// Parse raw block data into rows, handling incomplete rows at boundaries
-    fn block_to_rows(&mut self, block_data: Vec<f64>) -> Vec<Vec<f64>> {
+    /// Count `rows` more and tell the progress callback, if any
+    fn report_progress(&mut self, rows: usize) {
+        self.progress.rows += rows;
+        self.progress.bytes = self.data_position;
+        self.progress.total_bytes = self.source.len();
+        if let Some(callback) = self.on_progress.as_mut() {
+            callback(&self.progress);
+        }
+    }
+
+    /// Parse raw block data into `block_rows`, handling incomplete rows at
+    /// boundaries
+    fn block_to_rows(&mut self, block_data: &[f64]) {
+        self.block_rows.clear();
         if self.num_columns == 0 {
-            return Vec::new();
+            return;
         }
 
-        // Prepend pending data from previous block
-        let mut raw_data = std::mem::take(&mut self.pending_data);
-        raw_data.extend(block_data);
+        let skip = std::mem::take(&mut self.skip_values).min(block_data.len());
+        let mut block_data = &block_data[skip..];
 
         // Handle sweep value at very first read
-        if self.first_read && self.metadata.sweep_name.is_some() && !raw_data.is_empty() {
-            raw_data.remove(0); // Remove sweep value
+        if self.first_read && self.metadata.sweep_name.is_some() {
+            if let Some((&sweep, rest)) = self.pending_data.split_first() {
+                self.sweep_value = Some(sweep);
+                self.pending_data = rest.to_vec();
+            } else if let Some((&sweep, rest)) = block_data.split_first() {
+                self.sweep_value = Some(sweep);
+                block_data = rest;
+            }
         }
         self.first_read = false;
 
-        // Calculate complete rows
-        let total_values = raw_data.len();
-        let num_complete_rows = total_values / self.num_columns;
-        let complete_values = num_complete_rows * self.num_columns;
+        // Prepend pending data from previous block
+        let values = &mut self.block_rows.values;
+        values.extend_from_slice(&self.pending_data);
+        values.extend_from_slice(block_data);
 
         // Save incomplete row for next block
-        if complete_values < total_values {
-            self.pending_data = raw_data[complete_values..].to_vec();
-        }
-
-        // Convert to rows
-        let mut rows = Vec::with_capacity(num_complete_rows);
-        for i in 0..num_complete_rows {
-            let start = i * self.num_columns;
-            let end = start + self.num_columns;
-            rows.push(raw_data[start..end].to_vec());
-        }
+        let complete_values = values.len() / self.num_columns * self.num_columns;
+        self.pending_data.clear();
+        self.pending_data
+            .extend_from_slice(&values[complete_values..]);
+        values.truncate(complete_values);
+    }
 
-        rows
+    /// End of the time window the buffered rows start in, if chunking by
+    /// time
+    fn window_end(&self) -> Option<f64> {
+        let span = self.chunk_span?;
+        let first = self.row_buffer.first_scale()?;
+        let end = ((first / span).floor() + 1.0) * span;
+        // Rounding can put a row on a boundary into the window before it
+        Some(if end > first { end } else { end + span })
     }
 
-    /// Flush any remaining pending data as a final row (if complete)
-    fn flush_pending(&mut self) -> Vec<Vec<f64>> {
-        if self.pending_data.len() >= self.num_columns && self.num_columns > 0 {
-            let num_rows = self.pending_data.len() / self.num_columns;
-            let mut rows = Vec::with_capacity(num_rows);
-            for i in 0..num_rows {
-                let start = i * self.num_columns;
-                let end = start + self.num_columns;
-                if end <= self.pending_data.len() {
-                    rows.push(self.pending_data[start..end].to_vec());
-                }
-            }
-            self.pending_data.clear();
-            rows
-        } else {
-            Vec::new()
+    /// Whether the buffered rows make a whole chunk
+    fn chunk_full(&self) -> bool {
+        match (self.window_end(), self.row_buffer.last_scale()) {
+            (Some(end), Some(last)) => last >= end,
+            _ if self.chunk_span.is_some() => false,
+            _ => self.row_buffer.len() >= self.min_chunk_size,
         }
     }
 
+    /// Flush any remaining pending data into `block_rows` as final rows (if
+    /// complete)
+    fn flush_pending(&mut self) {
+        self.block_rows.clear();
+        let num_rows = self.pending_data.len().checked_div(self.num_columns);
+        let complete_values = num_rows.unwrap_or(0) * self.num_columns;
+        self.block_rows
+            .values
+            .extend_from_slice(&self.pending_data[..complete_values]);
+        self.pending_data.clear();
+    }
+
     // ========================================================================
     // Helper Methods
     // ========================================================================
@@ -258,8 +859,7 @@ impl HspiceStreamReader {
     /// Check if signal at given index is complex type
     #[inline]
     fn is_complex_signal(&self, signal_index: usize) -> bool {
-        self.metadata.var_type == COMPLEX_VAR
-            && signal_index < (self.metadata.num_variables - 1) as usize
+        signal_index < self.metadata.num_complex()
     }
 
     // ========================================================================
@@ -315,20 +915,18 @@ impl HspiceStreamReader {
     }
 
     /// Build chunk from accumulated rows
-    fn build_chunk(&self, rows: &[Vec<f64>]) -> Option<DataChunk> {
-        if rows.is_empty() {
+    fn build_chunk(&self, rows: &[f64]) -> Option<DataChunk> {
+        let num_rows = rows.len().checked_div(self.num_columns).unwrap_or(0);
+        if num_rows == 0 {
             return None;
         }
 
         // Allocate storage
-        let mut scale_vec: Vec<f64> = Vec::with_capacity(rows.len());
-        let (mut real_vecs, mut complex_vecs) = self.allocate_signal_storage(rows.len());
+        let mut scale_vec: Vec<f64> = Vec::with_capacity(num_rows);
+        let (mut real_vecs, mut complex_vecs) = self.allocate_signal_storage(num_rows);
 
         // Parse all rows
-        for row in rows {
-            if row.is_empty() {
-                continue;
-            }
+        for row in rows.chunks_exact(self.num_columns) {
             scale_vec.push(row[0]);
             self.parse_row_into_signals(row, &mut real_vecs, &mut complex_vecs);
         }
@@ -342,17 +940,23 @@ impl HspiceStreamReader {
         let mut data = HashMap::new();
         data.insert(
             self.metadata.scale_name.clone(),
-            VectorData::Real(scale_vec),
+            VectorData::Real(scale_vec.into()),
+        );
+        data.extend(
+            real_vecs
+                .into_iter()
+                .map(|(k, v)| (k, VectorData::Real(v.into()))),
         );
-        data.extend(real_vecs.into_iter().map(|(k, v)| (k, VectorData::Real(v))));
         data.extend(
             complex_vecs
                 .into_iter()
-                .map(|(k, v)| (k, VectorData::Complex(v))),
+                .map(|(k, v)| (k, VectorData::Complex(v.into()))),
         );
 
         Some(DataChunk {
             chunk_index: self.current_chunk,
+            table_index: self.table_index,
+            sweep_value: self.sweep_value,
             time_range,
             data,
         })
@@ -366,34 +970,44 @@ impl Iterator for HspiceStreamReader {
         if self.finished && self.row_buffer.is_empty() && self.pending_data.is_empty() {
             return None;
         }
+        if self.table_done && self.row_buffer.is_empty() && self.pending_data.is_empty() {
+            self.table_index += 1;
+            self.start_table();
+        }
 
-        // Read complete blocks until we have at least min_chunk_size rows
-        while self.row_buffer.len() < self.min_chunk_size && !self.finished {
-            match self.read_one_block() {
-                Ok(Some(block_data)) => {
-                    let rows = self.block_to_rows(block_data);
-                    self.row_buffer.extend(rows);
-                }
-                Ok(None) => break,
+        // Read complete blocks until the chunk is full or the table ends
+        while !self.chunk_full() && !self.finished && !self.table_done {
+            match self.next_rows() {
+                Ok(true) => self.row_buffer.extend(&self.block_rows),
+                Ok(false) => break,
                 Err(e) => return Some(Err(e)),
             }
         }
 
-        // If finished, flush any pending data
-        if self.finished && !self.pending_data.is_empty() {
-            let final_rows = self.flush_pending();
-            self.row_buffer.extend(final_rows);
+        // At the end of the table, flush any pending data
+        if (self.finished || self.table_done) && !self.pending_data.is_empty() {
+            self.flush_pending();
+            self.row_buffer.extend(&self.block_rows);
         }
 
         if self.row_buffer.is_empty() {
             return None;
         }
 
-        // Take all buffered rows for this chunk
-        let chunk_rows = std::mem::take(&mut self.row_buffer);
+        // Build from the buffered rows of this chunk, then drop them; the
+        // buffer keeps its capacity for the next chunk
+        let num_rows = match self.window_end() {
+            Some(end) => self.row_buffer.partition_point(|t| t < end),
+            None => self.row_buffer.len(),
+        };
+        let chunk = self.build_chunk(&self.row_buffer.values[..num_rows * self.num_columns]);
+        self.row_buffer.drain_front(num_rows);
 
-        match self.build_chunk(&chunk_rows) {
-            Some(chunk) => {
+        match chunk {
+            Some(mut chunk) => {
+                for transform in &mut self.transforms {
+                    chunk = transform(chunk);
+                }
                 trace!(
                     chunk = self.current_chunk,
                     points = chunk.data.values().next().map(|v| v.len()).unwrap_or(0),
@@ -402,6 +1016,7 @@ impl Iterator for HspiceStreamReader {
                     "Chunk built"
                 );
                 self.current_chunk += 1;
+                self.report_progress(num_rows);
                 Some(Ok(chunk))
             }
             None => None,
@@ -409,6 +1024,134 @@ impl Iterator for HspiceStreamReader {
     }
 }
 
+// ============================================================================
+// Rows
+// ============================================================================
+
+/// Point-by-point reader over an [`HspiceStreamReader`] (see
+/// [`HspiceStreamReader::rows`])
+///
+/// Each point is lent as its scale value and the values of all signals in
+/// file order, a complex signal taking two (real, imaginary). The slice
+/// borrows the reader's block buffer, so this is a lending iterator driven
+/// by [`next_row`](Self::next_row) rather than `Iterator`. Signal filters
+/// and chunk transforms do not apply. Reading stops after the first error.
+pub struct RowIterator {
+    reader: HspiceStreamReader,
+    /// Rows of the current block
+    rows: RowBuffer,
+    /// Next row of `rows` to lend
+    next: usize,
+    failed: bool,
+}
+
+impl RowIterator {
+    /// Next point as `(scale, values)`, or None at the end of the data
+    pub fn next_row(&mut self) -> Option<Result<(f64, &[f64])>> {
+        while self.next >= self.rows.len() {
+            if self.failed {
+                return None;
+            }
+            match self.reader.next_table_rows() {
+                Ok(true) => {
+                    // Trade buffers so both keep their capacity
+                    std::mem::swap(&mut self.rows, &mut self.reader.block_rows);
+                    self.reader.report_progress(self.rows.len());
+                    self.next = 0;
+                }
+                Ok(false) => return None,
+                Err(e) => {
+                    self.failed = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+        let row = self.rows.row(self.next);
+        self.next += 1;
+        Some(Ok((row[0], &row[1..])))
+    }
+
+    /// Sweep table of the last point returned (0-based)
+    pub fn table_index(&self) -> usize {
+        self.reader.table_index
+    }
+
+    /// Sweep value of that table, None if the file is not swept
+    pub fn sweep_value(&self) -> Option<f64> {
+        self.reader.sweep_value
+    }
+
+    /// Metadata of the file being read
+    pub fn metadata(&self) -> StreamMetadata {
+        self.reader.metadata()
+    }
+}
+
+// ============================================================================
+// Prefetch
+// ============================================================================
+
+/// Chunks of an [`HspiceStreamReader`] decoded ahead on a background thread
+/// (see [`HspiceStreamReader::prefetch`])
+///
+/// Dropping the reader stops the thread once it finishes its current chunk.
+pub struct PrefetchReader {
+    metadata: StreamMetadata,
+    /// In a mutex only so the reader is `Sync` like `HspiceStreamReader`
+    receiver: Option<Mutex<Receiver<Result<DataChunk>>>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl PrefetchReader {
+    fn new(mut reader: HspiceStreamReader, depth: usize) -> Self {
+        let metadata = reader.metadata();
+        let (sender, receiver) = sync_channel(depth.max(1));
+        let worker = std::thread::Builder::new()
+            .name("hspice-prefetch".into())
+            .spawn(move || {
+                for chunk in reader.by_ref() {
+                    let failed = chunk.is_err();
+                    // A closed channel means the consumer is gone
+                    if sender.send(chunk).is_err() || failed {
+                        break;
+                    }
+                }
+                trace!(chunks = reader.current_chunk, "Prefetch thread done");
+            })
+            .expect("spawning the prefetch thread");
+        Self {
+            metadata,
+            receiver: Some(Mutex::new(receiver)),
+            worker: Some(worker),
+        }
+    }
+
+    /// Metadata of the file being read
+    pub fn metadata(&self) -> StreamMetadata {
+        self.metadata.clone()
+    }
+}
+
+impl Iterator for PrefetchReader {
+    type Item = Result<DataChunk>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // The thread hung up after the last chunk, or panicked
+        let receiver = self.receiver.as_mut()?.get_mut().ok()?;
+        receiver.recv().ok()
+    }
+}
+
+impl Drop for PrefetchReader {
+    fn drop(&mut self) {
+        // Close the channel first so a blocked send fails and the thread ends
+        self.receiver = None;
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
 // ============================================================================
 // Public API
 // ============================================================================
@@ -436,9 +1179,15 @@ pub fn read_stream_signals<P: AsRef<Path>>(
     Ok(reader.with_signals(signals.iter().map(|s| s.to_string()).collect()))
 }
 
+/// Stream file contents held in memory with custom minimum chunk size
+pub fn read_stream_bytes(data: Vec<u8>, chunk_size: usize) -> Result<HspiceStreamReader> {
+    HspiceStreamReader::from_bytes(data, chunk_size)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::{temp_path, SyntheticWaveform};
 
     #[test]
     fn test_stream_reader_basic() {
@@ -461,4 +1210,439 @@ mod tests {
         }
         assert!(chunk_count > 0);
     }
+
+    #[test]
+    fn test_windowed_and_memory_streams_match_mapped() {
+        let path = temp_path("stream_windowed.tr0");
+        SyntheticWaveform::new()
+            .signals(3)
+            .points(100)
+            .block_size(7)
+            .sweep("temp", vec![25.0, 85.0])
+            .write_tr0(&path)
+            .unwrap();
+        let options = ReadOptions::default();
+
+        let collect = |reader: HspiceStreamReader| {
+            reader
+                .map(|chunk| {
+                    let chunk = chunk.unwrap();
+                    let mut data: Vec<_> = chunk.data.into_iter().collect();
+                    data.sort_by(|a, b| a.0.cmp(&b.0));
+                    // VectorData has no PartialEq; compare the debug form
+                    format!("{:?} {:?}", chunk.time_range, data)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mapped = StreamSource::open(&path, u64::MAX).unwrap();
+        assert!(matches!(mapped, StreamSource::Mapped(_)));
+        let expected = collect(HspiceStreamReader::from_source(mapped, 16, &options).unwrap());
+
+        let windowed = StreamSource::open(&path, 0).unwrap();
+        assert!(matches!(windowed, StreamSource::Windowed { .. }));
+        let mut reader = HspiceStreamReader::from_source(windowed, 16, &options).unwrap();
+        assert!(reader.next().is_some());
+        reader.reset();
+        assert_eq!(collect(reader), expected);
+
+        let bytes = std::fs::read(&path).unwrap();
+        let in_memory = HspiceStreamReader::from_bytes(bytes, 16).unwrap();
+        // 2 tables of 100 rows; 7-value blocks make framing a large share
+        let estimate = in_memory.estimated_rows();
+        assert!((200..400).contains(&estimate), "{}", estimate);
+        assert_eq!(collect(in_memory), expected);
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_windowed_seek_past_4gib() {
+        use crate::index::{IndexMark, TableIndex, WaveformIndex};
+
+        let path = temp_path("stream_windowed_sparse.tr0");
+        let synth = SyntheticWaveform::new()
+            .signals(1)
+            .points(50)
+            .block_size(10);
+        let offset = synth.write_sparse_tr0(&path, 5 << 30).unwrap();
+        assert!(offset > u32::MAX as u64);
+        let expected = synth.expected();
+        let scale = expected.tables[0].vectors[0].to_real().unwrap();
+
+        let windowed = StreamSource::open(&path, 0).unwrap();
+        let index = WaveformIndex {
+            file_len: windowed.len(),
+            modified: None,
+            tables: vec![TableIndex {
+                offset,
+                sweep_value: None,
+                rows: 50,
+                scale_range: (scale[0], scale[49]),
+                marks: vec![IndexMark {
+                    offset,
+                    lead: 0,
+                    first_scale: scale[0],
+                }],
+            }],
+        };
+        let mut reader = HspiceStreamReader::from_source(windowed, 20, &ReadOptions::default())
+            .unwrap()
+            .with_index(&index);
+        reader.seek_time(scale[10]).unwrap();
+        let times: Vec<f64> = reader
+            .flat_map(|chunk| chunk.unwrap().data["TIME"].to_real().unwrap().into_owned())
+            .collect();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(times, scale[10..].to_vec());
+    }
+
+    #[test]
+    fn test_seek_time_and_set_signals() {
+        let path = temp_path("stream_seek.tr0");
+        // Big enough (1.6 MB) to leave more than one seek mark
+        let synth = SyntheticWaveform::new().signals(1).points(200_000);
+        synth.write_tr0(&path).unwrap();
+        let expected = synth.expected();
+        let scale = expected.tables[0].vectors[0].to_real().unwrap();
+        let signal = expected.tables[0].vectors[1].to_real().unwrap();
+        let name = expected.variables[1].name.clone();
+
+        let mut reader = HspiceStreamReader::open(&path, 1000).unwrap();
+        let check_seek = |reader: &mut HspiceStreamReader, index: usize| {
+            reader.seek_time(scale[index]).unwrap();
+            let chunk = reader.next().unwrap().unwrap();
+            assert_eq!(chunk.time_range.0, scale[index]);
+            let values = chunk.data[&name].to_real().unwrap().into_owned();
+            assert_eq!(values[..10], signal[index..index + 10]);
+        };
+
+        // Forward, far forward (reads the whole file), then back to a mark
+        check_seek(&mut reader, 1234);
+        check_seek(&mut reader, 190_000);
+        assert!(reader.seek_marks.len() > 1);
+        check_seek(&mut reader, 150_001);
+        check_seek(&mut reader, 0);
+
+        reader.seek_time(f64::MAX).unwrap();
+        assert!(reader.next().is_none());
+
+        reader.set_signals(Some(Vec::new()));
+        reader.reset();
+        let chunk = reader.next().unwrap().unwrap();
+        assert_eq!(chunk.data.len(), 1);
+        assert!(chunk.data.contains_key("TIME"));
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_signal_pattern() {
+        let path = temp_path("stream_pattern.tr0");
+        SyntheticWaveform::new()
+            .signals(12)
+            .points(50)
+            .write_tr0(&path)
+            .unwrap();
+
+        let mut reader = HspiceStreamReader::open(&path, 100)
+            .unwrap()
+            .with_signal_pattern("v(n1?)")
+            .unwrap();
+        let chunk = reader.next().unwrap().unwrap();
+        let mut names: Vec<_> = chunk.data.keys().map(String::as_str).collect();
+        names.sort();
+        assert_eq!(names, ["TIME", "n10", "n11"]);
+
+        let reader = HspiceStreamReader::open(&path, 100).unwrap();
+        assert!(reader.with_signal_pattern("x*").is_err());
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_map_chunks_in_order() {
+        let path = temp_path("stream_map.tr0");
+        let synth = SyntheticWaveform::new().signals(2).points(100);
+        synth.write_tr0(&path).unwrap();
+        let expected = synth.expected();
+        let name = expected.variables[1].name.clone();
+        let signal = expected.tables[0].vectors[1].to_real().unwrap();
+
+        let mut seen = Vec::new();
+        let reader = HspiceStreamReader::open(&path, 30)
+            .unwrap()
+            .map_chunks(|mut chunk| {
+                chunk.data.retain(|name, _| name != "TIME");
+                chunk
+            })
+            .map_chunks(|mut chunk| {
+                for vector in chunk.data.values_mut() {
+                    if let VectorData::Real(v) = vector {
+                        v.iter_mut().for_each(|x| *x *= 2.0);
+                    }
+                }
+                chunk
+            });
+        for chunk in reader {
+            let chunk = chunk.unwrap();
+            assert!(!chunk.data.contains_key("TIME"));
+            seen.extend(chunk.data[&name].to_real().unwrap().iter().copied());
+        }
+
+        let doubled: Vec<f64> = signal.iter().map(|x| x * 2.0).collect();
+        assert_eq!(seen, doubled);
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_with_decimation() {
+        let synth = SyntheticWaveform::new().signals(2).points(1000);
+        let expected = synth.expected();
+        let name = expected.variables[1].name.clone();
+        let reader = HspiceStreamReader::from_bytes(synth.to_tr0_bytes(), 200)
+            .unwrap()
+            .with_decimation(Method::Stride, 10);
+
+        let mut rows = 0;
+        for chunk in reader {
+            let chunk = chunk.unwrap();
+            let time = chunk.data["TIME"].as_real().unwrap();
+            assert_eq!(chunk.data[&name].len(), time.len());
+            assert_eq!(time.first().copied(), Some(chunk.time_range.0));
+            assert_eq!(time.last().copied(), Some(chunk.time_range.1));
+            rows += time.len();
+        }
+        assert!((80..=150).contains(&rows), "{}", rows);
+    }
+
+    #[test]
+    fn test_chunk_by_time() {
+        let path = temp_path("stream_time_chunks.tr0");
+        let synth = SyntheticWaveform::new()
+            .signals(1)
+            .points(1000)
+            .block_size(70);
+        synth.write_tr0(&path).unwrap();
+        let expected = synth.expected();
+        let scale = expected.tables[0].vectors[0].to_real().unwrap();
+        let span = (scale[999] - scale[0]) / 7.5;
+
+        let chunks: Vec<DataChunk> = HspiceStreamReader::open(&path, 10)
+            .unwrap()
+            .chunk_by_time(span)
+            .map(|chunk| chunk.unwrap())
+            .collect();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(chunks.len(), 8);
+        let mut rows = 0;
+        for chunk in &chunks {
+            let window = (chunk.time_range.0 / span).floor();
+            assert!(chunk.time_range.1 < (window + 1.0) * span);
+            rows += chunk.data["TIME"].len();
+        }
+        assert_eq!(rows, 1000);
+        let starts: Vec<f64> = chunks.iter().map(|c| c.time_range.0).collect();
+        assert!(starts.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_swept_chunks_per_table() {
+        let path = temp_path("stream_swept_tables.tr0");
+        let synth = SyntheticWaveform::new()
+            .signals(2)
+            .points(50)
+            .block_size(13)
+            .sweep("temp", vec![25.0, 85.0, 125.0]);
+        synth.write_tr0(&path).unwrap();
+        let expected = synth.expected();
+
+        let mut reader = HspiceStreamReader::open(&path, 16).unwrap();
+        let chunks: Vec<DataChunk> = reader.by_ref().map(|chunk| chunk.unwrap()).collect();
+        assert!(reader.is_complete());
+        std::fs::remove_file(&path).ok();
+
+        for (t, table) in expected.tables.iter().enumerate() {
+            let mut scale = Vec::new();
+            for chunk in chunks.iter().filter(|c| c.table_index == t) {
+                assert_eq!(chunk.sweep_value, table.sweep_value);
+                scale.extend_from_slice(&chunk.data["TIME"].to_real().unwrap());
+            }
+            assert_eq!(scale, *table.vectors[0].to_real().unwrap());
+        }
+        let tables: Vec<usize> = chunks.iter().map(|c| c.table_index).collect();
+        assert!(tables.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(tables.last(), Some(&2));
+    }
+
+    #[test]
+    fn test_filter_time_and_map_signals() {
+        let path = temp_path("stream_filter_time.tr0");
+        let synth = SyntheticWaveform::new()
+            .signals(1)
+            .points(500)
+            .block_size(23);
+        synth.write_tr0(&path).unwrap();
+        let expected = synth.expected();
+        let scale = expected.tables[0].vectors[0].to_real().unwrap();
+        let signal = expected.tables[0].vectors[1].to_real().unwrap();
+        let (start, end) = (scale[100], scale[299]);
+
+        let chunks: Vec<DataChunk> = HspiceStreamReader::open(&path, 64)
+            .unwrap()
+            .filter_time(start, end)
+            .map_signals(|_, vector| {
+                if let VectorData::Real(v) = vector {
+                    v.iter_mut().for_each(|x| *x *= 2.0);
+                }
+            })
+            .map(|chunk| chunk.unwrap())
+            .collect();
+        std::fs::remove_file(&path).ok();
+
+        let mut times = Vec::new();
+        let mut values = Vec::new();
+        for chunk in &chunks {
+            times.extend_from_slice(&chunk.data["TIME"].to_real().unwrap());
+            let name = chunk.data.keys().find(|k| *k != "TIME").unwrap();
+            values.extend_from_slice(&chunk.data[name].to_real().unwrap());
+        }
+        assert_eq!(times, scale[100..300]);
+        let doubled: Vec<f64> = signal[100..300].iter().map(|x| x * 2.0).collect();
+        assert_eq!(values, doubled);
+    }
+
+    #[test]
+    fn test_rows_match_chunks() {
+        let path = temp_path("stream_rows.tr0");
+        let synth = SyntheticWaveform::new()
+            .signals(2)
+            .points(60)
+            .block_size(11)
+            .sweep("temp", vec![25.0, 85.0]);
+        synth.write_tr0(&path).unwrap();
+        let expected = synth.expected();
+
+        let mut rows = HspiceStreamReader::open(&path, 16).unwrap().rows();
+        let mut seen = vec![Vec::new(); 2];
+        while let Some(row) = rows.next_row() {
+            let (scale, values) = row.unwrap();
+            assert_eq!(values.len(), 2);
+            let value = values[1];
+            let t = rows.table_index();
+            seen[t].push(scale);
+            let table = &expected.tables[t];
+            assert_eq!(rows.sweep_value(), table.sweep_value);
+            let i = seen[t].len() - 1;
+            assert_eq!(value, table.vectors[2].to_real().unwrap()[i]);
+        }
+        std::fs::remove_file(&path).ok();
+
+        for (t, table) in expected.tables.iter().enumerate() {
+            assert_eq!(seen[t], *table.vectors[0].to_real().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_progress_per_chunk() {
+        use std::sync::{Arc, Mutex};
+
+        let data = SyntheticWaveform::new()
+            .signals(2)
+            .points(100)
+            .block_size(30)
+            .to_tr0_bytes();
+        let size = data.len() as u64;
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = reports.clone();
+        let reader = HspiceStreamReader::from_bytes(data, 40)
+            .unwrap()
+            .with_progress(move |p| sink.lock().unwrap().push(*p));
+        assert_eq!(reader.count(), 3);
+
+        let reports = reports.lock().unwrap();
+        let rows: Vec<usize> = reports.iter().map(|p| p.rows).collect();
+        assert_eq!(rows, [40, 80, 100]);
+        assert_eq!(reports[2].bytes, size);
+        assert_eq!(reports[2].blocks, 11);
+        assert_eq!(reports[2].fraction(), 1.0);
+    }
+
+    #[test]
+    fn test_prefetch_matches_direct() {
+        let synth = SyntheticWaveform::new()
+            .signals(3)
+            .points(5000)
+            .block_size(256);
+        let bytes = synth.to_tr0_bytes();
+        let direct: Vec<DataChunk> = read_stream_bytes(bytes.clone(), 700)
+            .unwrap()
+            .map(|c| c.unwrap())
+            .collect();
+
+        let reader = read_stream_bytes(bytes.clone(), 700).unwrap().prefetch(2);
+        assert_eq!(reader.metadata().signal_names.len(), 3);
+        let prefetched: Vec<DataChunk> = reader.map(|c| c.unwrap()).collect();
+        assert_eq!(prefetched.len(), direct.len());
+        for (a, b) in prefetched.iter().zip(&direct) {
+            assert_eq!(a.chunk_index, b.chunk_index);
+            assert_eq!(a.time_range, b.time_range);
+            assert_eq!(a.data["TIME"].as_real(), b.data["TIME"].as_real());
+        }
+
+        // Dropping early stops the thread instead of hanging
+        let mut reader = read_stream_bytes(bytes.clone(), 10).unwrap().prefetch(1);
+        assert!(reader.next().unwrap().is_ok());
+        drop(reader);
+
+        // An error ends the iteration
+        let cut = bytes[..bytes.len() / 2].to_vec();
+        let results: Vec<_> = read_stream_bytes(cut, 700).unwrap().prefetch(4).collect();
+        assert!(results.last().unwrap().is_err());
+        assert_eq!(results.iter().filter(|r| r.is_err()).count(), 1);
+    }
+
+    #[test]
+    fn test_is_complete() {
+        let path = temp_path("stream_complete.tr0");
+        SyntheticWaveform::new()
+            .signals(2)
+            .points(500)
+            .block_size(64)
+            .write_tr0(&path)
+            .unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+
+        let mut reader = HspiceStreamReader::from_bytes(bytes.clone(), 100).unwrap();
+        assert!(!reader.is_complete());
+        assert!(reader.by_ref().all(|chunk| chunk.is_ok()));
+        assert!(reader.is_complete());
+
+        // A file still being written stops short of the end marker
+        let partial = bytes[..bytes.len() - 200].to_vec();
+        let mut reader = HspiceStreamReader::from_bytes(partial, 100).unwrap();
+        reader.by_ref().take_while(|chunk| chunk.is_ok()).count();
+        assert!(!reader.is_complete());
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_row_buffer() {
+        let mut rows = RowBuffer::new(2);
+        rows.values
+            .extend([0.0, 10.0, 1.0, 11.0, 2.0, 12.0, 3.0, 13.0]);
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows.partition_point(|t| t < 2.0), 2);
+        assert_eq!(rows.last_scale(), Some(3.0));
+
+        rows.keep_rows(&[1, 3]);
+        assert_eq!(rows.values, [1.0, 11.0, 3.0, 13.0]);
+        rows.retain(|t| t > 1.0);
+        assert_eq!(rows.row(0), [3.0, 13.0]);
+        rows.drain_front(1);
+        assert!(rows.is_empty());
+        assert_eq!(rows.first_scale(), None);
+    }
 }
@@ -0,0 +1,528 @@
+//! Synthetic waveform file generation
+//!
+//! Builds HSPICE binary (.tr0/.ac0/.sw0) and SPICE3 raw files in memory with
+//! a configurable shape, so tests run without the `example/` files and
+//! fuzzers have well-formed seeds.
+//!
+//! Enabled with the `testing` feature.
+//!
+//! ```rust
+//! use hspice_core::testing::SyntheticWaveform;
+//! use hspice_core::{Endian, PostVersion};
+//!
+//! let synth = SyntheticWaveform::new()
+//!     .signals(3)
+//!     .points(100)
+//!     .version(PostVersion::V2001)
+//!     .endian(Endian::Big);
+//!
+//! let bytes = synth.to_tr0_bytes();
+//! let expected = synth.expected();
+//! assert_eq!(expected.len(), 100);
+//! assert!(!bytes.is_empty());
+//! ```
+
+use crate::decode::{normalize_signal_name, CURRENT_TYPE, VOLTAGE_TYPE};
+use crate::parser::signal_variable;
+use crate::types::*;
+use crate::writer::{write_spice3_raw_to, RawEncoding};
+use num_complex::Complex64;
+use std::fs::File;
+use std::io::{Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// Header length before the vector description section
+const HEADER_FIXED_LEN: usize = VECTOR_DESCRIPTION_START_POSITION;
+
+/// Width of each name token in the vector description section
+const NAME_FIELD_WIDTH: usize = 16;
+
+/// Builder for a synthetic waveform file
+#[derive(Debug, Clone)]
+pub struct SyntheticWaveform {
+    title: String,
+    date: String,
+    scale_name: Option<String>,
+    signal_names: Vec<String>,
+    num_points: usize,
+    first_point: usize,
+    sweep: Option<(String, Vec<f64>)>,
+    complex: bool,
+    endian: Endian,
+    version: PostVersion,
+    block_size: Option<usize>,
+    hold: usize,
+}
+
+impl Default for SyntheticWaveform {
+    fn default() -> Self {
+        Self {
+            title: "synthetic".into(),
+            date: "01/01/2025      00:00:00".into(),
+            scale_name: None,
+            signal_names: default_names(2),
+            num_points: 16,
+            first_point: 0,
+            sweep: None,
+            complex: false,
+            endian: Endian::Little,
+            version: PostVersion::V9601,
+            block_size: None,
+            hold: 1,
+        }
+    }
+}
+
+fn default_names(count: usize) -> Vec<String> {
+    (0..count).map(|i| format!("v(n{})", i)).collect()
+}
+
+impl SyntheticWaveform {
+    /// Create a generator with defaults: 2 real signals, 16 points, 9601, little-endian
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the simulation title
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Set the number of signals (excluding the scale), named `v(n0)`, `v(n1)`, ...
+    pub fn signals(mut self, count: usize) -> Self {
+        self.signal_names = default_names(count);
+        self
+    }
+
+    /// Set explicit signal names as they appear in the file header
+    pub fn signal_names(mut self, names: &[&str]) -> Self {
+        self.signal_names = names.iter().map(|s| s.to_string()).collect();
+        self
+    }
+
+    /// Override the scale name (defaults to TIME, or HERTZ for complex data)
+    pub fn scale_name(mut self, name: impl Into<String>) -> Self {
+        self.scale_name = Some(name.into());
+        self
+    }
+
+    /// Set the number of points per table
+    pub fn points(mut self, count: usize) -> Self {
+        self.num_points = count;
+        self
+    }
+
+    /// Start at point `index` of the waveform instead of 0, so one file can
+    /// continue another (like the parts of a split output)
+    pub fn first_point(mut self, index: usize) -> Self {
+        self.first_point = index;
+        self
+    }
+
+    /// Add a one-dimensional sweep with one table per value
+    pub fn sweep(mut self, name: impl Into<String>, values: Vec<f64>) -> Self {
+        self.sweep = Some((name.into(), values));
+        self
+    }
+
+    /// Generate complex (AC) data
+    pub fn complex(mut self, complex: bool) -> Self {
+        self.complex = complex;
+        self
+    }
+
+    /// Set the byte order of the generated file
+    pub fn endian(mut self, endian: Endian) -> Self {
+        self.endian = endian;
+        self
+    }
+
+    /// Set the post format version (9601 = float32, 2001 = float64)
+    pub fn version(mut self, version: PostVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    /// Hold each signal value for `points` samples, like a digital net
+    ///
+    /// Long holds make the parser store real signals as
+    /// [`VectorData::Stepped`] when reading with
+    /// [`crate::ReadOptions::compact_steps`].
+    pub fn hold(mut self, points: usize) -> Self {
+        self.hold = points.max(1);
+        self
+    }
+
+    /// Set the number of values per data block (defaults to 8 KiB blocks)
+    ///
+    /// Small values force rows to straddle block boundaries.
+    pub fn block_size(mut self, values: usize) -> Self {
+        self.block_size = Some(values.max(1));
+        self
+    }
+
+    // ========================================================================
+    // Expected content
+    // ========================================================================
+
+    fn resolved_scale_name(&self) -> String {
+        match &self.scale_name {
+            Some(name) => name.clone(),
+            None if self.complex => "HERTZ".into(),
+            None => "TIME".into(),
+        }
+    }
+
+    fn sweep_values(&self) -> Vec<Option<f64>> {
+        match &self.sweep {
+            Some((_, values)) => values.iter().copied().map(Some).collect(),
+            None => vec![None],
+        }
+    }
+
+    fn scale_value(&self, point: usize) -> f64 {
+        if self.complex {
+            10f64.powf(1.0 + point as f64 * 0.1)
+        } else {
+            point as f64 * 1e-9
+        }
+    }
+
+    fn signal_value(&self, table: usize, signal: usize, point: usize) -> Complex64 {
+        let phase = (point / self.hold * self.hold) as f64 * 0.05 + signal as f64;
+        let gain = 1.0 + table as f64 * 0.5 + signal as f64 * 0.25;
+        if self.complex {
+            Complex64::new(gain * phase.cos(), gain * phase.sin())
+        } else {
+            Complex64::new(gain * phase.sin(), 0.0)
+        }
+    }
+
+    /// Round a value through the on-disk precision
+    fn stored(&self, value: f64) -> f64 {
+        match self.version {
+            PostVersion::V9007 | PostVersion::V9601 => value as f32 as f64,
+            PostVersion::V2001 => value,
+        }
+    }
+
+    /// The result the parser is expected to produce for this file
+    pub fn expected(&self) -> WaveformResult {
+        let scale_name = self.resolved_scale_name();
+
+        let mut variables = Vec::with_capacity(self.signal_names.len() + 1);
+        variables.push(Variable::new(&scale_name));
+        variables.extend(
+            self.signal_names
+                .iter()
+                .map(|n| signal_variable(&normalize_signal_name(n), signal_type(n))),
+        );
+
+        let tables = self
+            .sweep_values()
+            .into_iter()
+            .enumerate()
+            .map(|(t, sweep_value)| self.expected_table(t, sweep_value))
+            .collect();
+
+        let analysis = if self.complex {
+            AnalysisType::AC
+        } else {
+            AnalysisType::from_scale_name(&scale_name)
+        };
+
+        WaveformResult {
+            title: self.title.clone(),
+            date: self.date.clone(),
+            analysis,
+            variables,
+            sweep_param: self.sweep.as_ref().map(|(name, _)| name.clone()),
+            tables,
+            truncated: false,
+        }
+    }
+
+    fn expected_table(&self, table: usize, sweep_value: Option<f64>) -> DataTable {
+        let points = self.first_point..self.first_point + self.num_points;
+        let mut vectors = Vec::with_capacity(self.signal_names.len() + 1);
+        vectors.push(VectorData::Real(
+            points
+                .clone()
+                .map(|p| self.stored(self.scale_value(p)))
+                .collect(),
+        ));
+        for s in 0..self.signal_names.len() {
+            let values = points.clone().map(|p| self.signal_value(table, s, p));
+            vectors.push(if self.complex {
+                VectorData::Complex(
+                    values
+                        .map(|c| Complex64::new(self.stored(c.re), self.stored(c.im)))
+                        .collect(),
+                )
+            } else {
+                VectorData::Real(values.map(|c| self.stored(c.re)).collect())
+            });
+        }
+        DataTable {
+            sweep_value: sweep_value.map(|v| self.stored(v)),
+            vectors,
+        }
+    }
+
+    // ========================================================================
+    // HSPICE binary encoding
+    // ========================================================================
+
+    /// Encode the HSPICE header text (without block framing)
+    fn header_text(&self) -> Vec<u8> {
+        let num_vectors = self.signal_names.len() + 1;
+        let num_sweeps = usize::from(self.sweep.is_some());
+
+        let mut text = format!("{:04}{:04}{:04}{:04}", num_vectors, 0, num_sweeps, 0);
+        text.push_str(match self.version {
+            PostVersion::V9007 => "9007    ",
+            PostVersion::V9601 => "9601    ",
+            PostVersion::V2001 => "00002001",
+        });
+        pad_field(
+            &mut text,
+            TITLE_START_POSITION,
+            &self.title,
+            DATE_START_POSITION,
+        );
+        pad_field(
+            &mut text,
+            DATE_START_POSITION,
+            &self.date,
+            DATE_END_POSITION,
+        );
+
+        let sweep_size = self.sweep.as_ref().map(|(_, v)| v.len()).unwrap_or(0);
+        let sweep_pos = match self.version {
+            PostVersion::V9007 | PostVersion::V9601 => SWEEP_SIZE_POSITION1,
+            PostVersion::V2001 => SWEEP_SIZE_POSITION2,
+        };
+        pad_field(
+            &mut text,
+            sweep_pos,
+            &format!("{:>10}", sweep_size),
+            sweep_pos + 10,
+        );
+        pad_to(&mut text, HEADER_FIXED_LEN);
+
+        // Vector description: type codes, then names, then optional sweep name
+        let scale_type = if self.complex {
+            FREQUENCY_TYPE
+        } else if self.scale_name.is_some() {
+            3
+        } else {
+            1
+        };
+        text.push_str(&format!("{:<8}", scale_type));
+        for name in &self.signal_names {
+            text.push_str(&format!("{:<8}", signal_type(name)));
+        }
+        push_name(&mut text, &self.resolved_scale_name());
+        for name in &self.signal_names {
+            push_name(&mut text, name);
+        }
+        if let Some((name, _)) = &self.sweep {
+            push_name(&mut text, name);
+        }
+        text.push_str("$&%#");
+        let aligned = text.len().div_ceil(8) * 8;
+        pad_to(&mut text, aligned);
+
+        text.into_bytes()
+    }
+
+    /// Flattened on-disk values for one table, including sweep value and end marker
+    fn table_values(&self, table: &DataTable) -> Vec<f64> {
+        let mut values = Vec::new();
+        if let Some(v) = table.sweep_value {
+            values.push(v);
+        }
+        for p in 0..table.len() {
+            for vector in &table.vectors {
+                match vector {
+                    VectorData::Real(_) | VectorData::Real32(_) | VectorData::Stepped { .. } => {
+                        values.push(vector.real_at(p).unwrap_or(0.0))
+                    }
+                    VectorData::Complex(v) => {
+                        values.push(v[p].re);
+                        values.push(v[p].im);
+                    }
+                }
+            }
+        }
+        values.push(match self.version {
+            PostVersion::V9007 | PostVersion::V9601 => END_MARKER_9601 as f64,
+            PostVersion::V2001 => END_MARKER_2001,
+        });
+        values
+    }
+
+    fn item_size(&self) -> usize {
+        self.version.value_size()
+    }
+
+    fn write_i32(&self, out: &mut Vec<u8>, value: i32) {
+        out.extend_from_slice(&match self.endian {
+            Endian::Little => value.to_le_bytes(),
+            Endian::Big => value.to_be_bytes(),
+        });
+    }
+
+    fn write_value(&self, out: &mut Vec<u8>, value: f64) {
+        match (self.version, self.endian) {
+            (PostVersion::V9007 | PostVersion::V9601, Endian::Little) => {
+                out.extend_from_slice(&(value as f32).to_le_bytes())
+            }
+            (PostVersion::V9007 | PostVersion::V9601, Endian::Big) => {
+                out.extend_from_slice(&(value as f32).to_be_bytes())
+            }
+            (PostVersion::V2001, Endian::Little) => out.extend_from_slice(&value.to_le_bytes()),
+            (PostVersion::V2001, Endian::Big) => out.extend_from_slice(&value.to_be_bytes()),
+        }
+    }
+
+    /// Frame a payload as one block: 16-byte head, data, 4-byte tail
+    fn write_block(&self, out: &mut Vec<u8>, items: usize, payload: &[u8]) {
+        self.write_i32(out, 4);
+        self.write_i32(out, items as i32);
+        self.write_i32(out, 4);
+        self.write_i32(out, payload.len() as i32);
+        out.extend_from_slice(payload);
+        self.write_i32(out, payload.len() as i32);
+    }
+
+    /// Encode the complete HSPICE binary file
+    pub fn to_tr0_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+
+        let header = self.header_text();
+        self.write_block(&mut out, header.len() / 8, &header);
+
+        let block_values = self.block_size.unwrap_or(8192 / self.item_size());
+        for table in &self.expected().tables {
+            for chunk in self.table_values(table).chunks(block_values) {
+                let mut payload = Vec::with_capacity(chunk.len() * self.item_size());
+                for &v in chunk {
+                    self.write_value(&mut payload, v);
+                }
+                self.write_block(&mut out, chunk.len(), &payload);
+            }
+        }
+
+        out
+    }
+
+    /// Encode the first table as a SPICE3 binary raw file
+    pub fn to_raw_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        write_spice3_raw_to(&self.expected(), &mut out, RawEncoding::Binary)
+            .expect("writing to a Vec cannot fail");
+        out
+    }
+
+    /// Write the HSPICE binary file to disk
+    pub fn write_tr0<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        std::fs::write(path, self.to_tr0_bytes())?;
+        Ok(())
+    }
+
+    /// Write the SPICE3 raw file to disk
+    pub fn write_raw<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        std::fs::write(path, self.to_raw_bytes())?;
+        Ok(())
+    }
+
+    /// Write the HSPICE binary file with at least `filler` bytes of
+    /// all-zero rows before the data, and return the offset of the first
+    /// block of the real data
+    ///
+    /// The filler payloads are left as holes, so on filesystems with sparse
+    /// files this places data past 4 GiB without writing gigabytes. The
+    /// file then reads as `filler_rows(filler)` zero rows followed by
+    /// [`Self::expected`]. Real, unswept files only.
+    pub fn write_sparse_tr0<P: AsRef<Path>>(&self, path: P, filler: u64) -> Result<u64> {
+        assert!(
+            self.sweep.is_none() && !self.complex,
+            "sparse files are real and unswept"
+        );
+        let bytes = self.to_tr0_bytes();
+        let data_start = 16 + self.header_text().len() + 4;
+        let row = self.row_bytes();
+        // Largest block payload that holds whole rows
+        let max_payload = i32::MAX as u64 / row * row;
+
+        let mut file = File::create(path)?;
+        file.write_all(&bytes[..data_start])?;
+        let mut left = self.filler_rows(filler) as u64 * row;
+        while left > 0 {
+            let payload = left.min(max_payload);
+            let mut head = Vec::with_capacity(16);
+            self.write_i32(&mut head, 4);
+            self.write_i32(&mut head, (payload / self.item_size() as u64) as i32);
+            self.write_i32(&mut head, 4);
+            self.write_i32(&mut head, payload as i32);
+            file.write_all(&head)?;
+            file.seek(SeekFrom::Current(payload as i64))?;
+            let mut trailer = Vec::with_capacity(4);
+            self.write_i32(&mut trailer, payload as i32);
+            file.write_all(&trailer)?;
+            left -= payload;
+        }
+        let offset = file.stream_position()?;
+        file.write_all(&bytes[data_start..])?;
+        Ok(offset)
+    }
+
+    /// Zero rows that [`Self::write_sparse_tr0`] puts before the data
+    pub fn filler_rows(&self, filler: u64) -> usize {
+        filler.div_ceil(self.row_bytes()) as usize
+    }
+
+    fn row_bytes(&self) -> u64 {
+        ((self.signal_names.len() + 1) * self.item_size()) as u64
+    }
+}
+
+/// Pad `text` with spaces up to `len`
+fn pad_to(text: &mut String, len: usize) {
+    while text.len() < len {
+        text.push(' ');
+    }
+}
+
+/// Place `value` at `start`, truncated to end before `end`
+fn pad_field(text: &mut String, start: usize, value: &str, end: usize) {
+    pad_to(text, start);
+    text.extend(value.chars().take(end - start));
+    pad_to(text, end);
+}
+
+/// Append a whitespace-separated name token in a fixed-width field
+fn push_name(text: &mut String, name: &str) {
+    text.push_str(name);
+    let width = NAME_FIELD_WIDTH.max(name.len() + 1);
+    text.extend(std::iter::repeat_n(' ', width - name.len()));
+}
+
+/// Header type code HSPICE writes for a signal: current for `i(...)`
+/// probes, node voltage otherwise
+fn signal_type(name: &str) -> i32 {
+    if name.to_lowercase().starts_with("i(") {
+        CURRENT_TYPE
+    } else {
+        VOLTAGE_TYPE
+    }
+}
+
+/// Unique path in the system temp directory for a generated file
+///
+/// Includes the process id so parallel test binaries never collide.
+pub fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("hspice_synth_{}_{}", std::process::id(), name))
+}
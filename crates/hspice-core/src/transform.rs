@@ -0,0 +1,368 @@
+//! Derived signals (scaling, offset, dB, power) and variable management
+//!
+//! The [`VectorData`] operations work point by point on one vector. The
+//! [`WaveformResult`] methods apply them to every table and append the
+//! output as a new named variable, so it exports like any other signal.
+//! [`WaveformResult::add_derived`] takes any per-table function for
+//! derivations not covered here.
+//!
+//! Renaming, dropping and reordering variables also live here, so
+//! `variables` and the vectors of every table always change together.
+//!
+//! ```rust,no_run
+//! let mut result = hspice_core::read("amp.tr0").unwrap();
+//! result.add_scaled("i(vdd)", "idd_ma", 1e3, 0.0).unwrap();
+//! result.add_power("v(vdd)", "i(vdd)", "p_vdd").unwrap();
+//! let p = result.get("p_vdd").unwrap();
+//! ```
+
+use crate::types::{
+    DataTable, Result, VarType, Variable, VectorData, WaveformError, WaveformResult,
+};
+use num_complex::Complex64;
+
+impl VectorData {
+    /// `gain * x + offset` at every point; the offset is added to the real
+    /// part of complex values
+    pub fn scaled(&self, gain: f64, offset: f64) -> VectorData {
+        let f = |x: f64| gain * x + offset;
+        match self {
+            VectorData::Real(v) => VectorData::Real(v.iter().map(|&x| f(x)).collect()),
+            VectorData::Real32(v) => VectorData::Real(v.iter().map(|&x| f(x as f64)).collect()),
+            VectorData::Complex(v) => {
+                VectorData::Complex(v.iter().map(|z| z * gain + offset).collect())
+            }
+            VectorData::Stepped { len, steps } => VectorData::Stepped {
+                len: *len,
+                steps: steps.iter().map(|&(i, x)| (i, f(x))).collect(),
+            },
+        }
+    }
+
+    /// `20 * log10(|x|)` at every point (dBV for a voltage); zero maps to
+    /// negative infinity
+    pub fn to_db(&self) -> VectorData {
+        let db = |magnitude: f64| 20.0 * magnitude.log10();
+        match self {
+            VectorData::Complex(v) => VectorData::Real(v.iter().map(|z| db(z.norm())).collect()),
+            VectorData::Stepped { len, steps } => VectorData::Stepped {
+                len: *len,
+                steps: steps.iter().map(|&(i, x)| (i, db(x.abs()))).collect(),
+            },
+            VectorData::Real(v) => VectorData::Real(v.iter().map(|x| db(x.abs())).collect()),
+            VectorData::Real32(v) => {
+                VectorData::Real(v.iter().map(|&x| db((x as f64).abs())).collect())
+            }
+        }
+    }
+
+    /// Point-by-point product with `other`, complex if either is
+    pub fn product(&self, other: &VectorData) -> Result<VectorData> {
+        if self.len() != other.len() {
+            return Err(WaveformError::ParseError(format!(
+                "cannot multiply vectors of {} and {} points",
+                self.len(),
+                other.len()
+            )));
+        }
+        if self.is_complex() || other.is_complex() {
+            let (a, b) = (to_complex(self), to_complex(other));
+            return Ok(VectorData::Complex(
+                a.iter().zip(&b).map(|(x, y)| x * y).collect(),
+            ));
+        }
+        let (a, b) = (self.to_real(), other.to_real());
+        let (a, b) = (a.unwrap_or_default(), b.unwrap_or_default());
+        Ok(VectorData::Real(
+            a.iter().zip(b.iter()).map(|(x, y)| x * y).collect(),
+        ))
+    }
+}
+
+fn to_complex(vector: &VectorData) -> Vec<Complex64> {
+    match vector {
+        VectorData::Complex(v) => v.to_vec(),
+        other => other
+            .to_real()
+            .unwrap_or_default()
+            .iter()
+            .map(|&x| Complex64::new(x, 0.0))
+            .collect(),
+    }
+}
+
+impl WaveformResult {
+    /// Append `variable`, computed by `f` from each table
+    ///
+    /// `f` must return a vector as long as its table. Nothing is added if
+    /// the name is taken or `f` fails for any table.
+    pub fn add_derived<F>(&mut self, variable: Variable, mut f: F) -> Result<()>
+    where
+        F: FnMut(&DataTable) -> Result<VectorData>,
+    {
+        if self.var_index(&variable.name).is_some() {
+            return Err(WaveformError::ParseError(format!(
+                "Derived signal {} clashes with an existing signal",
+                variable.name
+            )));
+        }
+        let vectors = self
+            .tables
+            .iter()
+            .map(|table| {
+                let vector = f(table)?;
+                if vector.len() != table.len() {
+                    return Err(WaveformError::ParseError(format!(
+                        "Derived signal {} has {} points, its table {}",
+                        variable.name,
+                        vector.len(),
+                        table.len()
+                    )));
+                }
+                Ok(vector)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        self.variables.push(variable);
+        for (table, vector) in self.tables.iter_mut().zip(vectors) {
+            table.vectors.push(vector);
+        }
+        Ok(())
+    }
+
+    /// Append `name` = `gain * source + offset`, with the type of `source`
+    pub fn add_scaled(&mut self, source: &str, name: &str, gain: f64, offset: f64) -> Result<()> {
+        let index = self.signal_index(source)?;
+        let var_type = self.variables[index].var_type;
+        self.add_derived(Variable::with_type(name, var_type), |table| {
+            Ok(table.vectors[index].scaled(gain, offset))
+        })
+    }
+
+    /// Append `name` = `source` in dB (dBV for a voltage; see
+    /// [`VectorData::to_db`])
+    pub fn add_db(&mut self, source: &str, name: &str) -> Result<()> {
+        let index = self.signal_index(source)?;
+        self.add_derived(Variable::with_type(name, VarType::Unknown), |table| {
+            Ok(table.vectors[index].to_db())
+        })
+    }
+
+    /// Append `name` = `voltage * current`, a power in watts
+    ///
+    /// Both signals must be real; instantaneous power of AC phasors is not
+    /// defined point by point.
+    pub fn add_power(&mut self, voltage: &str, current: &str, name: &str) -> Result<()> {
+        let (v, i) = (self.signal_index(voltage)?, self.signal_index(current)?);
+        self.add_derived(Variable::with_type(name, VarType::Power), |table| {
+            let (v, i) = (&table.vectors[v], &table.vectors[i]);
+            if v.is_complex() || i.is_complex() {
+                return Err(WaveformError::ParseError(format!(
+                    "power of complex signals {} and {}",
+                    voltage, current
+                )));
+            }
+            v.product(i)
+        })
+    }
+
+    fn signal_index(&self, name: &str) -> Result<usize> {
+        self.var_index(name)
+            .ok_or_else(|| WaveformError::ParseError(format!("signal {} not found", name)))
+    }
+}
+
+// ============================================================================
+// Variable management
+// ============================================================================
+
+impl WaveformResult {
+    /// Rename variable `old` to `new`, keeping its type and unit
+    pub fn rename_var(&mut self, old: &str, new: &str) -> Result<()> {
+        let index = self.signal_index(old)?;
+        if old != new && self.var_index(new).is_some() {
+            return Err(WaveformError::ParseError(format!(
+                "cannot rename {} to {}: the name is taken",
+                old, new
+            )));
+        }
+        self.variables[index].name = new.to_string();
+        Ok(())
+    }
+
+    /// Drop the signals for which `drop` returns true; the scale is always
+    /// kept. Returns the number dropped.
+    pub fn drop_vars<F: FnMut(&Variable) -> bool>(&mut self, mut drop: F) -> usize {
+        let keep: Vec<usize> = (0..self.variables.len())
+            .filter(|&i| i == 0 || !drop(&self.variables[i]))
+            .collect();
+        let dropped = self.variables.len() - keep.len();
+        self.permute(&keep);
+        dropped
+    }
+
+    /// Move the signals `names` to the front, right after the scale, in
+    /// that order; the others follow in their current order
+    pub fn reorder(&mut self, names: &[&str]) -> Result<()> {
+        let mut front = Vec::with_capacity(names.len());
+        for name in names {
+            match self.signal_index(name)? {
+                0 => {
+                    return Err(WaveformError::ParseError(format!(
+                        "cannot move the scale {}",
+                        name
+                    )))
+                }
+                index => front.push(index),
+            }
+        }
+        self.move_to_front(front);
+        Ok(())
+    }
+
+    /// Order the signals as in `reference`, e.g. before comparing two runs
+    ///
+    /// Signals `reference` does not have follow in their current order, and
+    /// those only `reference` has are ignored.
+    pub fn reorder_like(&mut self, reference: &WaveformResult) {
+        let front = reference
+            .variables
+            .iter()
+            .skip(1)
+            .filter_map(|v| self.var_index(&v.name))
+            .filter(|&i| i > 0)
+            .collect();
+        self.move_to_front(front);
+    }
+
+    /// Put the signals at `front` right after the scale, the rest after them
+    fn move_to_front(&mut self, mut front: Vec<usize>) {
+        if self.variables.is_empty() {
+            return;
+        }
+        let mut seen = vec![false; self.variables.len()];
+        seen[0] = true;
+        front.retain(|&i| !std::mem::replace(&mut seen[i], true));
+        let rest: Vec<usize> = (0..seen.len()).filter(|&i| !seen[i]).collect();
+        let order: Vec<usize> = std::iter::once(0).chain(front).chain(rest).collect();
+        self.permute(&order);
+    }
+
+    /// Keep the variables at `order`, in that order, in `variables` and in
+    /// every table
+    fn permute(&mut self, order: &[usize]) {
+        let mut variables: Vec<Option<Variable>> = std::mem::take(&mut self.variables)
+            .into_iter()
+            .map(Some)
+            .collect();
+        self.variables = order.iter().filter_map(|&i| variables[i].take()).collect();
+        for table in &mut self.tables {
+            let mut vectors: Vec<Option<VectorData>> = std::mem::take(&mut table.vectors)
+                .into_iter()
+                .map(Some)
+                .collect();
+            table.vectors = order.iter().filter_map(|&i| vectors[i].take()).collect();
+        }
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{AnalysisType, Unit};
+
+    fn supply() -> WaveformResult {
+        let names = ["TIME", "v(vdd)", "i(vdd)"];
+        WaveformResult {
+            title: String::new(),
+            date: String::new(),
+            analysis: AnalysisType::Transient,
+            variables: names.iter().map(|n| Variable::new(*n)).collect(),
+            sweep_param: None,
+            tables: vec![DataTable {
+                sweep_value: None,
+                vectors: vec![
+                    VectorData::Real(vec![0.0, 1.0, 2.0].into()),
+                    VectorData::Real(vec![1.0, 0.1, 10.0].into()),
+                    VectorData::Real(vec![2e-3, 1e-3, 0.0].into()),
+                ],
+            }],
+            truncated: false,
+        }
+    }
+
+    #[test]
+    fn test_scaled_db_and_power() {
+        let mut result = supply();
+        result.add_scaled("i(vdd)", "idd_ma", 1e3, 0.0).unwrap();
+        result.add_db("v(vdd)", "vdd_db").unwrap();
+        result.add_power("v(vdd)", "i(vdd)", "p_vdd").unwrap();
+
+        let idd = result.get("idd_ma").unwrap().as_real().unwrap();
+        assert_eq!(idd, &[2.0, 1.0, 0.0]);
+        assert_eq!(result.variables[3].unit, Some(Unit::Ampere));
+        let db = result.get("vdd_db").unwrap().as_real().unwrap();
+        for (x, want) in db.iter().zip([0.0, -20.0, 20.0]) {
+            assert!((x - want).abs() < 1e-12);
+        }
+        let p = result.get("p_vdd").unwrap().as_real().unwrap();
+        for (x, want) in p.iter().zip([2e-3, 1e-4, 0.0]) {
+            assert!((x - want).abs() < 1e-18);
+        }
+        assert_eq!(result.variables[5].unit, Some(Unit::Watt));
+    }
+
+    #[test]
+    fn test_errors_leave_result_unchanged() {
+        let mut result = supply();
+        assert!(result.add_scaled("v(missing)", "x", 1.0, 0.0).is_err());
+        assert!(result.add_db("v(vdd)", "i(vdd)").is_err());
+        assert!(result
+            .add_derived(Variable::new("short"), |_| Ok(VectorData::Real(
+                vec![1.0].into()
+            )))
+            .is_err());
+        assert_eq!(result.variables.len(), 3);
+        assert_eq!(result.tables[0].vectors.len(), 3);
+    }
+
+    #[test]
+    fn test_rename_drop_reorder() {
+        let mut result = supply();
+        result.rename_var("i(vdd)", "idd").unwrap();
+        assert!(result.rename_var("idd", "v(vdd)").is_err());
+        assert!(result.rename_var("i(vdd)", "x").is_err());
+        assert_eq!(result.variables[2].var_type, VarType::Current);
+
+        let reference = {
+            let mut r = supply();
+            r.rename_var("i(vdd)", "idd").unwrap();
+            r.reorder(&["idd"]).unwrap();
+            r
+        };
+        assert_eq!(reference.var_names(), ["TIME", "idd", "v(vdd)"]);
+        result.reorder_like(&reference);
+        assert_eq!(result.var_names(), ["TIME", "idd", "v(vdd)"]);
+        assert_eq!(result.get("idd").unwrap().real_at(0), Some(2e-3));
+        assert!(result.reorder(&["TIME"]).is_err());
+
+        assert_eq!(result.drop_vars(|v| v.var_type != VarType::Voltage), 1);
+        assert_eq!(result.var_names(), ["TIME", "v(vdd)"]);
+        assert_eq!(result.tables[0].vectors.len(), 2);
+        assert_eq!(result.get("v(vdd)").unwrap().real_at(2), Some(10.0));
+    }
+
+    #[test]
+    fn test_complex_vectors() {
+        let z = VectorData::Complex(vec![Complex64::new(3.0, 4.0)].into());
+        assert_eq!(z.to_db().as_real().unwrap()[0], 20.0 * 5f64.log10());
+        let scaled = z.scaled(2.0, 1.0);
+        assert_eq!(scaled.as_complex().unwrap()[0], Complex64::new(7.0, 8.0));
+        let product = z.product(&VectorData::Real(vec![2.0].into())).unwrap();
+        assert_eq!(product.as_complex().unwrap()[0], Complex64::new(6.0, 8.0));
+    }
+}
@@ -3,81 +3,25 @@
 //! This module provides unified data structures for parsing various SPICE
 //! waveform formats including HSPICE TR0 and SPICE3 raw files.
 
+use crate::decode::{DecodeError, CURRENT_TYPE, VOLTAGE_TYPE};
+use crate::signal_buf::SignalBuf;
 use num_complex::Complex64;
+use std::borrow::Cow;
 
-// ============================================================================
-// Constants (HSPICE format specific)
-// ============================================================================
-
-/// Header character positions (matching C implementation)
-pub const NUM_OF_VARIABLES_POSITION: usize = 0;
-pub const NUM_OF_PROBES_POSITION: usize = 4;
-pub const NUM_OF_SWEEPS_POSITION: usize = 8;
-pub const NUM_OF_SWEEPS_END_POSITION: usize = 12;
-pub const POST_START_POSITION1: usize = 16;
-pub const POST_START_POSITION2: usize = 20;
-pub const DATE_START_POSITION: usize = 88;
-pub const DATE_END_POSITION: usize = 112;
-pub const TITLE_START_POSITION: usize = 24;
-pub const SWEEP_SIZE_POSITION1: usize = 176;
-pub const SWEEP_SIZE_POSITION2: usize = 187;
-pub const VECTOR_DESCRIPTION_START_POSITION: usize = 256;
-
-pub const POST_STRING11: &str = "9007";
-pub const POST_STRING12: &str = "9601";
-pub const POST_STRING21: &str = "2001";
-
-pub const FREQUENCY_TYPE: i32 = 2;
-pub const COMPLEX_VAR: i32 = 1;
-pub const REAL_VAR: i32 = 0;
-
-/// End-of-data marker for 9601 format (float32 representation of ~1e30)
-#[allow(clippy::excessive_precision)]
-pub const END_MARKER_9601: f32 = 1.0000000150474662e+30_f32;
-/// End-of-data marker for 2001 format
-pub const END_MARKER_2001: f64 = 1.0e+30_f64;
+pub use crate::decode::{
+    Endian, PostVersion, COMPLEX_VAR, DATE_END_POSITION, DATE_START_POSITION, END_MARKER_2001,
+    END_MARKER_9601, FREQUENCY_TYPE, NUM_OF_PROBES_POSITION, NUM_OF_SWEEPS_POSITION,
+    NUM_OF_VARIABLES_POSITION, POST_START_POSITION2, POST_STRING21, REAL_VAR, SWEEP_SIZE_POSITION1,
+    SWEEP_SIZE_POSITION2, TITLE_START_POSITION, VECTOR_DESCRIPTION_START_POSITION,
+};
 
 // ============================================================================
 // Enums
 // ============================================================================
 
-/// Endianness detected from file
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum Endian {
-    Little,
-    Big,
-}
-
-/// Generate endian-aware read methods
-macro_rules! impl_endian_read {
-    ($fn_name:ident, $ty:ty) => {
-        #[inline]
-        pub fn $fn_name(&self, bytes: [u8; std::mem::size_of::<$ty>()]) -> $ty {
-            match self {
-                Endian::Little => <$ty>::from_le_bytes(bytes),
-                Endian::Big => <$ty>::from_be_bytes(bytes),
-            }
-        }
-    };
-}
-
-impl Endian {
-    impl_endian_read!(read_i32, i32);
-    impl_endian_read!(read_f32, f32);
-    impl_endian_read!(read_f64, f64);
-}
-
-/// Post format version - determines data precision
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum PostVersion {
-    /// 9007/9601 format: 4-byte float32
-    V9601,
-    /// 2001 format: 8-byte float64 (double precision)
-    V2001,
-}
-
 /// Analysis/simulation type
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AnalysisType {
     /// Transient analysis (.tr0)
     Transient,
@@ -101,6 +45,7 @@ impl AnalysisType {
             "tr0" => AnalysisType::Transient,
             "ac0" => AnalysisType::AC,
             "sw0" => AnalysisType::DC,
+            "ns0" => AnalysisType::Noise,
             _ => AnalysisType::Unknown,
         }
     }
@@ -150,6 +95,7 @@ impl std::str::FromStr for AnalysisType {
 
 /// Variable type (voltage, current, time, etc.)
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum VarType {
     /// Time variable (scale for transient)
     Time,
@@ -159,6 +105,15 @@ pub enum VarType {
     Voltage,
     /// Current signal
     Current,
+    /// Power (`p(...)`)
+    Power,
+    /// User expression (`par(...)`)
+    Parameter,
+    /// Device-internal node or element state variable (`m1#d`, `q(c1)`,
+    /// `lx0(c1)`)
+    Internal,
+    /// Noise spectral density (`onoise`, `inoise`, element contributions)
+    Noise,
     /// Unknown or other type
     #[default]
     Unknown,
@@ -166,20 +121,73 @@ pub enum VarType {
 
 impl VarType {
     /// Infer variable type from signal name
+    ///
+    /// Probe prefixes follow the HSPICE output names: `v(`, `vr(`, `vi(`
+    /// and `vm(` are voltages; `i(`, the terminal currents `i1(`..`i4(`,
+    /// `ix(`, `isub(`, `ir(`, `ii(`, `im(` and ngspice `#branch` vectors
+    /// are currents; `p(` is power and `par(` an expression. Charges
+    /// (`q(`), element templates (`lx*(`, `lv*(`) and device-internal nodes
+    /// (`m1#d`) are internal. `onoise` and `inoise` are noise.
     pub fn from_name(name: &str) -> Self {
         let lower = name.to_lowercase();
-        if lower == "time" {
-            VarType::Time
-        } else if lower == "hertz" || lower == "freq" || lower == "frequency" {
-            VarType::Frequency
-        } else if lower.starts_with("v(") || lower.starts_with("v_") {
+        match lower.as_str() {
+            "time" => return VarType::Time,
+            "hertz" | "freq" | "frequency" => return VarType::Frequency,
+            "onoise" | "inoise" => return VarType::Noise,
+            _ => {}
+        }
+        if let Some((prefix, _)) = lower.split_once('(') {
+            return match prefix {
+                "v" | "vr" | "vi" | "vm" => VarType::Voltage,
+                "i" | "i1" | "i2" | "i3" | "i4" | "ix" | "isub" | "ir" | "ii" | "im" => {
+                    VarType::Current
+                }
+                "p" => VarType::Power,
+                "par" => VarType::Parameter,
+                "q" => VarType::Internal,
+                _ if is_template(prefix) => VarType::Internal,
+                _ => VarType::Unknown,
+            };
+        }
+        if lower.starts_with("v_") {
             VarType::Voltage
-        } else if lower.starts_with("i(") || lower.starts_with("i_") {
+        } else if lower.starts_with("i_") || lower.ends_with("#branch") {
             VarType::Current
+        } else if lower.contains('#') {
+            VarType::Internal
         } else {
             VarType::Unknown
         }
     }
+
+    /// Type of a signal from its HSPICE header type code; `Unknown` for
+    /// codes other than node voltage and current
+    pub(crate) fn from_type_code(code: i32) -> Self {
+        match code {
+            VOLTAGE_TYPE => VarType::Voltage,
+            CURRENT_TYPE => VarType::Current,
+            _ => VarType::Unknown,
+        }
+    }
+
+    /// Unit of values of this type, if it has one
+    pub fn unit(&self) -> Option<Unit> {
+        match self {
+            VarType::Time => Some(Unit::Second),
+            VarType::Frequency => Some(Unit::Hertz),
+            VarType::Voltage => Some(Unit::Volt),
+            VarType::Current => Some(Unit::Ampere),
+            VarType::Power => Some(Unit::Watt),
+            VarType::Parameter | VarType::Internal | VarType::Noise | VarType::Unknown => None,
+        }
+    }
+}
+
+/// Element template output prefix (`lx0`, `lv12`, ...)
+fn is_template(prefix: &str) -> bool {
+    (prefix.starts_with("lx") || prefix.starts_with("lv"))
+        && prefix.len() > 2
+        && prefix[2..].bytes().all(|b| b.is_ascii_digit())
 }
 
 // ============================================================================
@@ -193,6 +201,10 @@ impl std::fmt::Display for VarType {
             VarType::Frequency => "frequency",
             VarType::Voltage => "voltage",
             VarType::Current => "current",
+            VarType::Power => "power",
+            VarType::Parameter => "parameter",
+            VarType::Internal => "internal",
+            VarType::Noise => "noise",
             VarType::Unknown => "unknown",
         };
         write!(f, "{}", s)
@@ -207,11 +219,108 @@ impl std::str::FromStr for VarType {
     }
 }
 
-/// Vector data - either real or complex
+/// Physical unit of a variable's values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Unit {
+    Second,
+    Hertz,
+    Volt,
+    Ampere,
+    /// Power probes (`p(...)`)
+    Watt,
+    /// Charge probes (`q(...)`)
+    Coulomb,
+    /// Noise voltage density (`onoise`)
+    VoltPerRootHertz,
+    /// Noise current density (`inoise` referred to a current source)
+    AmperePerRootHertz,
+    /// Squared noise voltage density (element noise contributions)
+    SquareVoltPerHertz,
+}
+
+impl Unit {
+    /// SI symbol ("s", "Hz", "V", "A", "W", "C"), or HSPICE-style for
+    /// noise densities ("V/rtHz", "A/rtHz", "V^2/Hz")
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Unit::Second => "s",
+            Unit::Hertz => "Hz",
+            Unit::Volt => "V",
+            Unit::Ampere => "A",
+            Unit::Watt => "W",
+            Unit::Coulomb => "C",
+            Unit::VoltPerRootHertz => "V/rtHz",
+            Unit::AmperePerRootHertz => "A/rtHz",
+            Unit::SquareVoltPerHertz => "V^2/Hz",
+        }
+    }
+
+    /// Unit implied by a probe name, e.g. `p(m1)` is a power in watts
+    ///
+    /// Recognizes the scale names, voltage probes (`v(`, `vr(`, `vi(`,
+    /// `vm(`), branch and port currents (`i(`, `i1(`..`i4(`, `ix(`,
+    /// `isub(`, `ir(`, `ii(`, `im(`), power `p(`, charge `q(` and the output
+    /// noise `onoise`. Element
+    /// template outputs (`lx*`, `lv*`) have per-element units and return
+    /// `None`, as do phase, dB and unrecognized names.
+    pub fn from_probe(name: &str) -> Option<Self> {
+        let lower = name.to_lowercase();
+        match lower.as_str() {
+            "time" => return Some(Unit::Second),
+            "hertz" | "freq" | "frequency" => return Some(Unit::Hertz),
+            "onoise" => return Some(Unit::VoltPerRootHertz),
+            _ => {}
+        }
+        let prefix = lower.split_once('(').map_or(lower.as_str(), |(p, _)| p);
+        if lower.contains('(') {
+            return match prefix {
+                "v" | "vr" | "vi" | "vm" => Some(Unit::Volt),
+                "i" | "i1" | "i2" | "i3" | "i4" | "ix" | "isub" | "ir" | "ii" | "im" => {
+                    Some(Unit::Ampere)
+                }
+                "p" => Some(Unit::Watt),
+                "q" => Some(Unit::Coulomb),
+                _ => None,
+            };
+        }
+        if lower.starts_with("v_") {
+            Some(Unit::Volt)
+        } else if lower.starts_with("i_") {
+            Some(Unit::Ampere)
+        } else {
+            None
+        }
+    }
+}
+
+impl std::fmt::Display for Unit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+/// A real signal with at most one change per this many points is stored
+/// as [`VectorData::Stepped`] (each step costs two points of memory)
+pub const STEPPED_MIN_POINTS_PER_CHANGE: usize = 16;
+
+/// Vector data - real, complex, or real stored as steps or in single
+/// precision
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum VectorData {
-    Real(Vec<f64>),
-    Complex(Vec<Complex64>),
+    Real(SignalBuf<f64>),
+    Complex(SignalBuf<Complex64>),
+    /// Real data kept in single precision, half the memory of `Real`; for
+    /// float32 files, which lose nothing (see [`crate::ReadOptions::store_f32`])
+    Real32(SignalBuf<f32>),
+    /// Piecewise-constant real data for signals that rarely change (digital
+    /// control nets). `steps` holds `(index, value)` at index 0 and at every
+    /// change; the value holds until the next step or `len`.
+    Stepped {
+        len: usize,
+        steps: Vec<(usize, f64)>,
+    },
 }
 
 impl VectorData {
@@ -220,6 +329,8 @@ impl VectorData {
         match self {
             VectorData::Real(v) => v.len(),
             VectorData::Complex(v) => v.len(),
+            VectorData::Real32(v) => v.len(),
+            VectorData::Stepped { len, .. } => *len,
         }
     }
 
@@ -233,28 +344,136 @@ impl VectorData {
         matches!(self, VectorData::Complex(_))
     }
 
-    /// Get real data, returns None if complex
+    /// Get real data, returns None if complex, single precision or stepped
+    ///
+    /// Use [`to_real`](Self::to_real) to also accept the others.
     pub fn as_real(&self) -> Option<&Vec<f64>> {
         match self {
             VectorData::Real(v) => Some(v),
-            VectorData::Complex(_) => None,
+            _ => None,
         }
     }
 
     /// Get complex data, returns None if real
     pub fn as_complex(&self) -> Option<&Vec<Complex64>> {
         match self {
-            VectorData::Real(_) => None,
             VectorData::Complex(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Get real data, expanding stepped data and widening single precision;
+    /// returns None if complex
+    pub fn to_real(&self) -> Option<Cow<'_, [f64]>> {
+        match self {
+            VectorData::Real(v) => Some(Cow::Borrowed(v)),
+            VectorData::Complex(_) => None,
+            VectorData::Real32(v) => Some(Cow::Owned(v.iter().map(|&x| x as f64).collect())),
+            VectorData::Stepped { len, steps } => Some(Cow::Owned(expand_steps(*len, steps))),
+        }
+    }
+
+    /// Real value at `index` without expanding stepped data; None if
+    /// complex or out of range
+    pub fn real_at(&self, index: usize) -> Option<f64> {
+        match self {
+            VectorData::Real(v) => v.get(index).copied(),
+            VectorData::Complex(_) => None,
+            VectorData::Real32(v) => v.get(index).map(|&x| x as f64),
+            VectorData::Stepped { len, steps } => {
+                if index >= *len {
+                    return None;
+                }
+                let step = steps.partition_point(|&(i, _)| i <= index);
+                steps.get(step.checked_sub(1)?).map(|&(_, v)| v)
+            }
+        }
+    }
+
+    /// Whether this is stepped data
+    pub fn is_stepped(&self) -> bool {
+        matches!(self, VectorData::Stepped { .. })
+    }
+
+    /// Store real values as steps if they change rarely enough to save
+    /// memory (see [`STEPPED_MIN_POINTS_PER_CHANGE`]), otherwise as `Real`
+    pub fn compact(values: Vec<f64>) -> Self {
+        let max_steps = values.len() / STEPPED_MIN_POINTS_PER_CHANGE;
+        let mut steps = Vec::new();
+        for (i, &v) in values.iter().enumerate() {
+            if steps
+                .last()
+                .is_none_or(|&(_, last): &(usize, f64)| last.to_bits() != v.to_bits())
+            {
+                if steps.len() == max_steps {
+                    return VectorData::Real(values.into());
+                }
+                steps.push((i, v));
+            }
+        }
+        if steps.is_empty() {
+            return VectorData::Real(values.into());
+        }
+        VectorData::Stepped {
+            len: values.len(),
+            steps,
+        }
+    }
+
+    /// Like [`compact`](Self::compact), but keeping values that do not
+    /// step as `Real32`
+    ///
+    /// For values read from float32, which narrow back without loss.
+    pub fn compact_f32(values: Vec<f64>) -> Self {
+        match Self::compact(values) {
+            VectorData::Real(v) => VectorData::Real32(v.into_iter().map(|x| x as f32).collect()),
+            compacted => compacted,
+        }
+    }
+
+    /// Whether this is single precision data
+    pub fn is_real32(&self) -> bool {
+        matches!(self, VectorData::Real32(_))
+    }
+
+    /// Expand stepped data and widen single precision into `Real` in place
+    pub fn materialize(&mut self) {
+        match self {
+            VectorData::Stepped { len, steps } => {
+                *self = VectorData::Real(expand_steps(*len, steps).into());
+            }
+            VectorData::Real32(v) => {
+                *self = VectorData::Real(v.iter().map(|&x| x as f64).collect());
+            }
+            _ => {}
         }
     }
 }
 
+fn expand_steps(len: usize, steps: &[(usize, f64)]) -> Vec<f64> {
+    let mut values = Vec::with_capacity(len);
+    for (k, &(start, value)) in steps.iter().enumerate() {
+        let end = steps.get(k + 1).map_or(len, |&(next, _)| next);
+        values.resize(end.max(start), value);
+    }
+    values
+}
+
 // ============================================================================
 // Error Types
 // ============================================================================
 
 /// Error type for waveform reading operations
+///
+/// Structural problems in the input have their own variants carrying the
+/// byte offset where they were detected, so callers can match on the kind
+/// of failure. `ParseError` and `FormatError` remain for cases without a
+/// dedicated variant.
+///
+/// The readers wrap errors in `Context` layers naming the file and the
+/// operation that failed, so the message reads e.g. `reading a.tr0:
+/// reading sweep table 3 of 10 at offset 0x5f2c10: Block trailer mismatch
+/// ...`. Match on `err.root_cause()` to get the underlying variant.
 #[derive(Debug, thiserror::Error)]
 pub enum WaveformError {
     /// I/O error (file not found, permission denied, etc.)
@@ -268,10 +487,251 @@ pub enum WaveformError {
     /// Format error (unsupported file format, version mismatch)
     #[error("Format error: {0}")]
     FormatError(String),
+
+    /// A configured parser limit was exceeded (see `ParserLimits`)
+    #[error("Limit exceeded: {what} = {found} (limit {limit})")]
+    LimitExceeded {
+        what: &'static str,
+        found: usize,
+        limit: usize,
+    },
+
+    /// Writing an output file failed; `stage` says how far the write got
+    #[error("Writing {path} failed during {stage}: {source}")]
+    WriteFailed {
+        path: String,
+        stage: &'static str,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// Data given to `WaveformResultBuilder` does not fit its variables
+    #[error("Invalid result data: {0}")]
+    InvalidResult(String),
+
+    /// An error annotated with the operation that was in progress
+    #[error("{context}: {source}")]
+    Context {
+        context: String,
+        #[source]
+        source: Box<WaveformError>,
+    },
+
+    // === HSPICE binary structure ===
+    /// The input contains no bytes
+    #[error("File is empty")]
+    EmptyFile,
+
+    /// The input is ASCII post output; only binary is supported
+    #[error("File is ASCII format, only binary supported")]
+    AsciiFormat,
+
+    // The `decode` errors, with offsets that stay exact past 4 GiB on
+    // 32-bit targets
+    /// [`DecodeError::TruncatedBlock`]
+    #[error("Truncated block at byte {offset}: need {needed} bytes, {available} available")]
+    TruncatedBlock {
+        offset: u64,
+        needed: u64,
+        available: u64,
+    },
+
+    /// [`DecodeError::CorruptedBlockHeader`]
+    #[error("Corrupted block header at byte {offset}")]
+    CorruptedBlockHeader { offset: u64 },
+
+    /// [`DecodeError::InvalidBlockSize`]
+    #[error("Invalid block size {size} at byte {offset}")]
+    InvalidBlockSize { size: i32, offset: u64 },
+
+    /// [`DecodeError::TrailerMismatch`]
+    #[error("Block trailer mismatch at byte {offset}: expected {expected}, found {found}")]
+    TrailerMismatch {
+        expected: i32,
+        found: i32,
+        offset: u64,
+    },
+
+    /// [`DecodeError::UnknownPostVersion`]
+    #[error("Unknown post version {value:?}")]
+    UnknownPostVersion { value: String },
+
+    /// [`DecodeError::HeaderTooShort`]
+    #[error("Header too short: {len} bytes")]
+    HeaderTooShort { len: usize },
+
+    /// [`DecodeError::InvalidVectorCount`]
+    #[error("Invalid vector counts: {variables} variables, {probes} probes")]
+    InvalidVectorCount { variables: i32, probes: i32 },
+
+    /// [`DecodeError::UnsupportedSweep`]
+    #[error("Unsupported sweep dimension {count} (only one-dimensional sweeps)")]
+    UnsupportedSweep { count: i32 },
+
+    /// [`DecodeError::MissingVectorNames`]
+    #[error("Header lists {found} vector names, expected {expected}")]
+    MissingVectorNames { expected: usize, found: usize },
+
+    /// [`DecodeError::MissingSweepTable`]
+    #[error("Missing data for sweep table {index} of {total} at byte {offset}")]
+    MissingSweepTable {
+        index: usize,
+        total: usize,
+        offset: u64,
+    },
+
+    /// The scale steps back, and [`crate::NonMonotonic::Error`] was asked for
+    #[error("Scale steps back at row {row}: {previous} then {value}")]
+    NonMonotonicScale {
+        row: usize,
+        previous: f64,
+        value: f64,
+    },
+
+    // === SPICE3 raw structure ===
+    /// No `Binary:` or `Values:` line was found
+    #[error("No data section found in raw file")]
+    MissingDataSection,
+
+    /// `No. Variables` disagrees with the `Variables:` list
+    #[error("Header declares {declared} variables but lists {listed}")]
+    VariableCountMismatch { declared: usize, listed: usize },
+
+    /// The binary data section is smaller than the header declares
+    #[error("Data section is {available} bytes at byte {offset}, header declares {needed}")]
+    DataSizeMismatch {
+        needed: usize,
+        available: usize,
+        offset: u64,
+    },
+
+    /// A size or offset computed from the file does not fit its type
+    #[error("{what} overflows at byte {offset}")]
+    SizeOverflow { what: &'static str, offset: u64 },
+}
+
+impl From<DecodeError> for WaveformError {
+    fn from(e: DecodeError) -> Self {
+        match e {
+            DecodeError::TruncatedBlock {
+                offset,
+                needed,
+                available,
+            } => WaveformError::TruncatedBlock {
+                offset: offset as u64,
+                needed: needed as u64,
+                available: available as u64,
+            },
+            DecodeError::CorruptedBlockHeader { offset } => WaveformError::CorruptedBlockHeader {
+                offset: offset as u64,
+            },
+            DecodeError::InvalidBlockSize { size, offset } => WaveformError::InvalidBlockSize {
+                size,
+                offset: offset as u64,
+            },
+            DecodeError::TrailerMismatch {
+                expected,
+                found,
+                offset,
+            } => WaveformError::TrailerMismatch {
+                expected,
+                found,
+                offset: offset as u64,
+            },
+            DecodeError::UnknownPostVersion { value } => {
+                WaveformError::UnknownPostVersion { value }
+            }
+            DecodeError::HeaderTooShort { len } => WaveformError::HeaderTooShort { len },
+            DecodeError::InvalidVectorCount { variables, probes } => {
+                WaveformError::InvalidVectorCount { variables, probes }
+            }
+            DecodeError::UnsupportedSweep { count } => WaveformError::UnsupportedSweep { count },
+            DecodeError::MissingVectorNames { expected, found } => {
+                WaveformError::MissingVectorNames { expected, found }
+            }
+            DecodeError::MissingSweepTable {
+                index,
+                total,
+                offset,
+            } => WaveformError::MissingSweepTable {
+                index,
+                total,
+                offset: offset as u64,
+            },
+        }
+    }
+}
+
+impl WaveformError {
+    /// Byte offset in the input where the error was detected, if known
+    pub fn offset(&self) -> Option<u64> {
+        match self {
+            WaveformError::TruncatedBlock { offset, .. }
+            | WaveformError::CorruptedBlockHeader { offset }
+            | WaveformError::InvalidBlockSize { offset, .. }
+            | WaveformError::TrailerMismatch { offset, .. }
+            | WaveformError::MissingSweepTable { offset, .. }
+            | WaveformError::DataSizeMismatch { offset, .. }
+            | WaveformError::SizeOverflow { offset, .. } => Some(*offset),
+            WaveformError::Context { source, .. } => source.offset(),
+            _ => None,
+        }
+    }
+
+    /// This error with its offset moved `by` bytes later, for an error
+    /// found in a slice that starts `by` bytes into the input
+    pub(crate) fn shifted(mut self, by: u64) -> Self {
+        match &mut self {
+            WaveformError::TruncatedBlock { offset, .. }
+            | WaveformError::CorruptedBlockHeader { offset }
+            | WaveformError::InvalidBlockSize { offset, .. }
+            | WaveformError::TrailerMismatch { offset, .. }
+            | WaveformError::MissingSweepTable { offset, .. }
+            | WaveformError::DataSizeMismatch { offset, .. }
+            | WaveformError::SizeOverflow { offset, .. } => *offset += by,
+            _ => {}
+        }
+        self
+    }
+
+    /// The error beneath all `Context` layers
+    pub fn root_cause(&self) -> &WaveformError {
+        match self {
+            WaveformError::Context { source, .. } => source.root_cause(),
+            other => other,
+        }
+    }
+
+    /// Wrap this error in a `Context` layer
+    pub fn context(self, context: impl Into<String>) -> Self {
+        WaveformError::Context {
+            context: context.into(),
+            source: Box::new(self),
+        }
+    }
 }
 
 pub type Result<T> = std::result::Result<T, WaveformError>;
 
+/// Attach context to the error of a `Result` (including `io::Result`)
+pub trait ResultExt<T> {
+    /// Wrap an error with a fixed context message
+    fn context(self, context: impl Into<String>) -> Result<T>;
+
+    /// Wrap an error with a context message built only on failure
+    fn with_context<C: Into<String>, F: FnOnce() -> C>(self, f: F) -> Result<T>;
+}
+
+impl<T, E: Into<WaveformError>> ResultExt<T> for std::result::Result<T, E> {
+    fn context(self, context: impl Into<String>) -> Result<T> {
+        self.map_err(|e| e.into().context(context))
+    }
+
+    fn with_context<C: Into<String>, F: FnOnce() -> C>(self, f: F) -> Result<T> {
+        self.map_err(|e| e.into().context(f()))
+    }
+}
+
 // Keep old error name as alias for compatibility during transition
 pub type HspiceError = WaveformError;
 
@@ -280,33 +740,59 @@ pub type HspiceError = WaveformError;
 // ============================================================================
 
 /// Metadata for a single variable/signal
+///
+/// Build one with [`Variable::new`], [`Variable::with_type`] and
+/// [`Variable::with_unit`]; the struct is non-exhaustive so fields can be
+/// added without breaking callers.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub struct Variable {
     /// Signal name (e.g., "TIME", "v(out)", "i(vdd)")
     pub name: String,
     /// Variable type inferred from name
     pub var_type: VarType,
+    /// Unit of the values, from the probe name or the type (`None` if
+    /// unknown or dimensionless)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub unit: Option<Unit>,
 }
 
 impl Variable {
-    /// Create a new variable with type inferred from name
+    /// Create a new variable with type and unit inferred from name
     pub fn new(name: impl Into<String>) -> Self {
         let name = name.into();
         let var_type = VarType::from_name(&name);
-        Self { name, var_type }
+        let unit = Unit::from_probe(&name).or(var_type.unit());
+        Self {
+            name,
+            var_type,
+            unit,
+        }
     }
 
-    /// Create a new variable with explicit type
+    /// Create a new variable with explicit type; the unit follows the
+    /// type, or the probe name if the type has none
     pub fn with_type(name: impl Into<String>, var_type: VarType) -> Self {
+        let name = name.into();
+        let unit = var_type.unit().or_else(|| Unit::from_probe(&name));
         Self {
-            name: name.into(),
+            name,
             var_type,
+            unit,
         }
     }
+
+    /// Override the unit
+    pub fn with_unit(mut self, unit: Option<Unit>) -> Self {
+        self.unit = unit;
+        self
+    }
 }
 
 /// A single data table (one per sweep point, or one if no sweep)
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DataTable {
     /// Sweep parameter value (None if no sweep)
     pub sweep_value: Option<f64>,
@@ -356,7 +842,12 @@ impl DataTable {
 /// // Access by index (faster)
 /// let scale = &result.tables[0].vectors[0];
 /// ```
-#[derive(Debug)]
+///
+/// The struct is non-exhaustive; outside this crate, build one with
+/// [`WaveformResult::new`] or [`crate::WaveformResultBuilder`].
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub struct WaveformResult {
     // === Metadata ===
     /// Simulation title
@@ -377,9 +868,34 @@ pub struct WaveformResult {
     // === Data ===
     /// Data tables (one per sweep point)
     pub tables: Vec<DataTable>,
+    /// Whether the file ended early and only its complete rows were
+    /// recovered (see [`crate::ReadOptions::recover`])
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub truncated: bool,
 }
 
 impl WaveformResult {
+    /// A complete (not truncated) result from its parts; `tables` hold
+    /// their vectors in the order of `variables`
+    pub fn new(
+        title: impl Into<String>,
+        date: impl Into<String>,
+        analysis: AnalysisType,
+        variables: Vec<Variable>,
+        sweep_param: Option<String>,
+        tables: Vec<DataTable>,
+    ) -> Self {
+        Self {
+            title: title.into(),
+            date: date.into(),
+            analysis,
+            variables,
+            sweep_param,
+            tables,
+            truncated: false,
+        }
+    }
+
     /// Get the scale variable name (first variable)
     pub fn scale_name(&self) -> &str {
         self.variables
@@ -433,7 +949,169 @@ impl WaveformResult {
     pub fn has_sweep(&self) -> bool {
         self.sweep_param.is_some() && self.tables.len() > 1
     }
+
+    /// Expand every stepped or single precision vector into plain `Real`
+    /// data
+    pub fn materialize(&mut self) {
+        for table in &mut self.tables {
+            table.vectors.iter_mut().for_each(VectorData::materialize);
+        }
+    }
 }
 
 // Keep old name as alias during transition
 pub type HspiceResult = WaveformResult;
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compact_and_expand() {
+        let mut values = vec![0.0; 40];
+        values[20..].fill(1.5);
+        let vector = VectorData::compact(values.clone());
+        match &vector {
+            VectorData::Stepped { len, steps } => {
+                assert_eq!(*len, 40);
+                assert_eq!(steps, &[(0, 0.0), (20, 1.5)]);
+            }
+            other => panic!("expected stepped, got {:?}", other),
+        }
+        assert_eq!(vector.len(), 40);
+        assert_eq!(vector.to_real().unwrap(), values.as_slice());
+        assert_eq!(vector.real_at(19), Some(0.0));
+        assert_eq!(vector.real_at(20), Some(1.5));
+        assert_eq!(vector.real_at(40), None);
+
+        let mut vector = vector;
+        vector.materialize();
+        assert_eq!(vector.as_real(), Some(&values));
+    }
+
+    #[test]
+    fn test_real32() {
+        let ramp: Vec<f64> = (0..64).map(|i| i as f64 * 0.5).collect();
+        let mut vector = VectorData::compact_f32(ramp.clone());
+        assert!(vector.is_real32());
+        assert_eq!(vector.as_real(), None);
+        assert_eq!(vector.to_real().unwrap(), ramp.as_slice());
+        assert_eq!(vector.real_at(3), Some(1.5));
+        assert_eq!(
+            vector.scaled(2.0, 0.0).as_real(),
+            Some(&ramp.iter().map(|x| 2.0 * x).collect())
+        );
+        vector.materialize();
+        assert_eq!(vector.as_real(), Some(&ramp));
+
+        // Steps still win over single precision
+        assert!(VectorData::compact_f32(vec![1.0; 40]).is_stepped());
+    }
+
+    #[test]
+    fn test_cloned_vectors_share_values() {
+        let vector = VectorData::Real(vec![0.0, 1.0, 2.0].into());
+        let copy = vector.clone();
+        match (&vector, &copy) {
+            (VectorData::Real(a), VectorData::Real(b)) => assert!(a.ptr_eq(b)),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_compact_keeps_busy_and_short_vectors_real() {
+        let ramp: Vec<f64> = (0..64).map(|i| i as f64).collect();
+        assert!(VectorData::compact(ramp).as_real().is_some());
+        assert!(VectorData::compact(vec![1.0; 8]).as_real().is_some());
+        assert!(VectorData::compact(Vec::new()).as_real().is_some());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        use num_complex::Complex64;
+
+        let result = WaveformResult {
+            title: "rt".into(),
+            date: String::new(),
+            analysis: AnalysisType::AC,
+            variables: vec![
+                Variable::new("HERTZ"),
+                Variable::new("v(out)"),
+                Variable::new("en"),
+            ],
+            sweep_param: Some("temp".into()),
+            tables: vec![DataTable {
+                sweep_value: Some(25.0),
+                vectors: vec![
+                    VectorData::Real(vec![1.0, 10.0].into()),
+                    VectorData::Complex(vec![Complex64::new(1.0, -0.5); 2].into()),
+                    VectorData::Stepped {
+                        len: 2,
+                        steps: vec![(0, 3.0)],
+                    },
+                ],
+            }],
+            truncated: false,
+        };
+        let json = serde_json::to_string(&result).unwrap();
+        let back: WaveformResult = serde_json::from_str(&json).unwrap();
+        // VectorData has no PartialEq; compare the debug form
+        assert_eq!(format!("{:?}", back), format!("{:?}", result));
+    }
+
+    #[test]
+    fn test_units_from_probe_names() {
+        let unit = |name: &str| Variable::new(name).unit.map(|u| u.symbol());
+        assert_eq!(unit("TIME"), Some("s"));
+        assert_eq!(unit("v(out)"), Some("V"));
+        assert_eq!(unit("vm(out)"), Some("V"));
+        assert_eq!(unit("ix(x1.in)"), Some("A"));
+        assert_eq!(unit("isub(m1)"), Some("A"));
+        assert_eq!(unit("i2(m1)"), Some("A"));
+        assert_eq!(unit("p(m1)"), Some("W"));
+        assert_eq!(unit("q(c1)"), Some("C"));
+        assert_eq!(unit("lx4(m1)"), None);
+        assert_eq!(unit("vdb(out)"), None);
+        assert_eq!(unit("out"), None);
+        assert_eq!(unit("onoise"), Some("V/rtHz"));
+        assert_eq!(unit("inoise"), None);
+        assert_eq!(
+            Variable::with_type("out", VarType::Voltage).unit,
+            Some(Unit::Volt)
+        );
+    }
+
+    #[test]
+    fn test_var_type_probe_classes() {
+        let cases = [
+            ("TIME", VarType::Time),
+            ("v(out)", VarType::Voltage),
+            ("VR(out)", VarType::Voltage),
+            ("i(vdd)", VarType::Current),
+            ("i1(m1)", VarType::Current),
+            ("isub(m1)", VarType::Current),
+            ("l1#branch", VarType::Current),
+            ("p(m1)", VarType::Power),
+            ("par(v(a)*2)", VarType::Parameter),
+            ("q(c1)", VarType::Internal),
+            ("lx0(c1)", VarType::Internal),
+            ("lv12(m1)", VarType::Internal),
+            ("m1#dbody", VarType::Internal),
+            ("onoise", VarType::Noise),
+            ("INOISE", VarType::Noise),
+            ("lxx(c1)", VarType::Unknown),
+            ("vdb(out)", VarType::Unknown),
+            ("out", VarType::Unknown),
+        ];
+        for (name, expected) in cases {
+            assert_eq!(VarType::from_name(name), expected, "{}", name);
+        }
+        assert_eq!(Variable::new("p(m1)").unit, Some(Unit::Watt));
+        assert_eq!(Variable::new("q(c1)").unit, Some(Unit::Coulomb));
+    }
+}
@@ -0,0 +1,257 @@
+//! Digital VCD export
+//!
+//! [`to_vcd`] digitizes the signals of a transient result against input
+//! thresholds and writes them as an IEEE 1364 value change dump, for
+//! GTKWave or digital verification flows:
+//!
+//! - at or above `vih` a signal is `1`, at or below `vil` it is `0`
+//! - in between it keeps its previous level, so a slow edge switches once
+//!   instead of chattering; a signal that starts in between is `x` until it
+//!   first crosses a threshold
+//!
+//! Every signal of the first table is written as a 1-bit wire; use
+//! [`WaveformResult::select`] to choose which. Times are written in
+//! femtoseconds.
+//!
+//! ```rust,no_run
+//! use hspice_core::vcd::{write_vcd, Thresholds};
+//!
+//! let result = hspice_core::read("counter.tr0").unwrap();
+//! let bus = result.select("q[0-3]").unwrap();
+//! write_vcd(&bus, &Thresholds::from_vdd(1.8), "counter.vcd").unwrap();
+//! ```
+
+use crate::types::{AnalysisType, Result, ResultExt, VectorData, WaveformError, WaveformResult};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// Time unit of the dump, in seconds
+const TIMESCALE: f64 = 1e-15;
+
+/// Input logic thresholds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Thresholds {
+    /// Highest voltage read as `0`
+    pub vil: f64,
+    /// Lowest voltage read as `1`
+    pub vih: f64,
+}
+
+impl Thresholds {
+    pub fn new(vil: f64, vih: f64) -> Self {
+        Self { vil, vih }
+    }
+
+    /// CMOS levels for a supply: 30% and 70% of `vdd`
+    pub fn from_vdd(vdd: f64) -> Self {
+        Self::new(0.3 * vdd, 0.7 * vdd)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.vil.is_finite() && self.vih.is_finite() && self.vil <= self.vih {
+            Ok(())
+        } else {
+            Err(WaveformError::ParseError(format!(
+                "invalid thresholds: vil {} must not exceed vih {}",
+                self.vil, self.vih
+            )))
+        }
+    }
+}
+
+/// Write `result` as VCD to `path`
+pub fn write_vcd<P: AsRef<Path>>(
+    result: &WaveformResult,
+    thresholds: &Thresholds,
+    path: P,
+) -> Result<()> {
+    let path = path.as_ref();
+    let mut writer =
+        BufWriter::new(File::create(path).with_context(|| format!("creating {}", path.display()))?);
+    to_vcd(result, thresholds, &mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Digitize the signals of the first table of `result` and write them as
+/// VCD
+///
+/// Returns an error for results that are not transient or hold complex
+/// signals, and for `vil > vih`.
+pub fn to_vcd<W: Write>(
+    result: &WaveformResult,
+    thresholds: &Thresholds,
+    writer: &mut W,
+) -> Result<()> {
+    thresholds.validate()?;
+    if result.analysis != AnalysisType::Transient {
+        return Err(WaveformError::ParseError(format!(
+            "VCD export needs a transient result, not {}",
+            result.analysis
+        )));
+    }
+    let Some(table) = result.tables.first() else {
+        return Err(WaveformError::ParseError("result has no data".into()));
+    };
+    if table.vectors.iter().any(VectorData::is_complex) {
+        return Err(WaveformError::ParseError(
+            "VCD export needs real signals".into(),
+        ));
+    }
+
+    let signals = &table.vectors[1..];
+    let ids: Vec<String> = (0..signals.len()).map(identifier).collect();
+
+    writeln!(writer, "$date {} $end", result.date.trim())?;
+    writeln!(
+        writer,
+        "$version hspice-core {} $end",
+        env!("CARGO_PKG_VERSION")
+    )?;
+    if !result.title.trim().is_empty() {
+        writeln!(writer, "$comment {} $end", result.title.trim())?;
+    }
+    writeln!(writer, "$timescale 1fs $end")?;
+    writeln!(writer, "$scope module top $end")?;
+    for (var, id) in result.variables[1..].iter().zip(&ids) {
+        writeln!(writer, "$var wire 1 {} {} $end", id, reference(&var.name))?;
+    }
+    writeln!(writer, "$upscope $end")?;
+    writeln!(writer, "$enddefinitions $end")?;
+
+    let scale = &table.vectors[0];
+    let mut levels: Vec<Option<char>> = vec![None; signals.len()];
+    let mut last_time = None;
+    for point in 0..scale.len() {
+        let time = scale.real_at(point).unwrap_or(f64::NAN);
+        if !time.is_finite() {
+            continue;
+        }
+        let time = (time / TIMESCALE).round().max(0.0) as u64;
+
+        let mut changes = Vec::new();
+        for (i, signal) in signals.iter().enumerate() {
+            let value = signal.real_at(point).unwrap_or(f64::NAN);
+            let level = digitize(value, thresholds, levels[i]);
+            if point == 0 || level != levels[i] {
+                levels[i] = level;
+                changes.push(i);
+            }
+        }
+        if changes.is_empty() {
+            continue;
+        }
+
+        if last_time != Some(time) {
+            writeln!(writer, "#{}", time)?;
+            last_time = Some(time);
+        }
+        if point == 0 {
+            writeln!(writer, "$dumpvars")?;
+        }
+        for i in changes {
+            writeln!(writer, "{}{}", levels[i].unwrap_or('x'), ids[i])?;
+        }
+        if point == 0 {
+            writeln!(writer, "$end")?;
+        }
+    }
+    Ok(())
+}
+
+/// Logic level of `value`, keeping `previous` between the thresholds
+fn digitize(value: f64, thresholds: &Thresholds, previous: Option<char>) -> Option<char> {
+    if value >= thresholds.vih {
+        Some('1')
+    } else if value <= thresholds.vil {
+        Some('0')
+    } else {
+        previous
+    }
+}
+
+/// Short identifier code: base 94 over the printable characters `!`..`~`
+fn identifier(mut index: usize) -> String {
+    let mut id = String::new();
+    loop {
+        id.push((b'!' + (index % 94) as u8) as char);
+        index /= 94;
+        if index == 0 {
+            return id;
+        }
+        index -= 1;
+    }
+}
+
+/// Signal name as a VCD reference, which may not contain whitespace
+fn reference(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<_>>().join("_")
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{DataTable, Variable};
+
+    fn result(analysis: AnalysisType, signal: Vec<f64>) -> WaveformResult {
+        let time = (0..signal.len()).map(|i| i as f64 * 1e-9).collect();
+        WaveformResult {
+            title: "* clock".into(),
+            date: "01/02/2024".into(),
+            analysis,
+            variables: vec![Variable::new("TIME"), Variable::new("clk")],
+            sweep_param: None,
+            tables: vec![DataTable {
+                sweep_value: None,
+                vectors: vec![VectorData::Real(time), VectorData::Real(signal.into())],
+            }],
+            truncated: false,
+        }
+    }
+
+    #[test]
+    fn test_digitize_with_hysteresis() {
+        // Starts in between (x), rises, lingers mid-rail, then falls
+        let wave = result(AnalysisType::Transient, vec![0.9, 1.7, 0.9, 1.3, 0.2, 0.9]);
+        let mut out = Vec::new();
+        to_vcd(&wave, &Thresholds::from_vdd(1.8), &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+
+        assert!(text.contains("$timescale 1fs $end"));
+        assert!(text.contains("$var wire 1 ! clk $end"));
+        let body = &text[text.find("$enddefinitions $end\n").unwrap() + 21..];
+        let lines: Vec<_> = body.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "#0",
+                "$dumpvars",
+                "x!",
+                "$end",
+                "#1000000",
+                "1!",
+                "#4000000",
+                "0!"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_errors_and_identifiers() {
+        let wave = result(AnalysisType::AC, vec![0.0, 1.8]);
+        assert!(to_vcd(&wave, &Thresholds::from_vdd(1.8), &mut Vec::new()).is_err());
+        let wave = result(AnalysisType::Transient, vec![0.0, 1.8]);
+        assert!(to_vcd(&wave, &Thresholds::new(1.0, 0.5), &mut Vec::new()).is_err());
+
+        assert_eq!(identifier(0), "!");
+        assert_eq!(identifier(93), "~");
+        assert_eq!(identifier(94), "!!");
+        assert_eq!(identifier(94 + 94 * 94), "!!!");
+        assert_eq!(reference("v(a b)"), "v(a_b)");
+    }
+}
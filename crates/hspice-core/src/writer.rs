@@ -1,133 +1,909 @@
-//! SPICE3 Binary Raw File Writer
+//! SPICE3 Raw File Writer (binary or ASCII)
 
-use crate::types::{AnalysisType, Result, VectorData, WaveformError, WaveformResult};
+use crate::stream::{DataChunk, HspiceStreamReader, StreamMetadata, DEFAULT_CHUNK_SIZE};
+use crate::types::{
+    AnalysisType, DataTable, Result, Unit, VarType, Variable, VectorData, WaveformError,
+    WaveformResult,
+};
+use num_complex::Complex64;
 use std::fs::File;
-use std::io::{BufWriter, Write};
-use tracing::{debug, info, instrument};
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, instrument, warn};
 
-/// Write SPICE3 binary raw file header
-fn write_raw_header<W: Write>(
+/// Width reserved for the point count so it can be patched in place
+const POINTS_FIELD_WIDTH: usize = 20;
+
+/// Summary of a completed conversion
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversionReport {
+    /// Points (rows) written
+    pub points: usize,
+    /// Variables per point, including the scale
+    pub variables: usize,
+    /// Size of the output file in bytes
+    pub bytes: u64,
+    /// Time from creating the output to finishing it
+    pub duration: Duration,
+    /// Input data that the output could not hold (e.g. extra sweep tables)
+    pub warnings: Vec<String>,
+    /// SHA-256 of the output, when a checksum sidecar was requested
+    pub sha256: Option<String>,
+}
+
+/// Encoding of the data section of a SPICE3 raw file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RawEncoding {
+    /// `Binary:` section of little-endian doubles
+    #[default]
+    Binary,
+    /// `Values:` section of text, one value per line in exponent notation
+    /// with `precision` digits after the decimal point
+    Ascii { precision: usize },
+}
+
+impl RawEncoding {
+    /// Digits after the point that round-trip every double (17 significant)
+    pub const DEFAULT_PRECISION: usize = 16;
+
+    /// ASCII with [`DEFAULT_PRECISION`](Self::DEFAULT_PRECISION) digits
+    pub const fn ascii() -> Self {
+        Self::Ascii {
+            precision: Self::DEFAULT_PRECISION,
+        }
+    }
+
+    /// Line that opens the data section
+    fn marker(self) -> &'static str {
+        match self {
+            Self::Binary => "Binary:",
+            Self::Ascii { .. } => "Values:",
+        }
+    }
+}
+
+/// Plot name for an analysis type
+fn plot_name(analysis: AnalysisType) -> &'static str {
+    match analysis {
+        AnalysisType::Transient => "Transient Analysis",
+        AnalysisType::AC => "AC Analysis",
+        AnalysisType::DC => "DC Analysis",
+        AnalysisType::Operating => "Operating Point",
+        AnalysisType::Noise => "Noise Analysis",
+        AnalysisType::Unknown => "Analysis",
+    }
+}
+
+/// Write SPICE3 binary raw file header up to the point count, which the
+/// caller writes
+fn write_raw_header_start<W: Write>(
     writer: &mut W,
     title: &str,
     date: &str,
-    plot_name: &str,
-    result: &WaveformResult,
-    num_points: usize,
+    analysis: AnalysisType,
+    num_variables: usize,
     is_complex: bool,
 ) -> Result<()> {
-    // Write text header
     writeln!(writer, "Title: {}", title)?;
     writeln!(writer, "Date: {}", date)?;
-    writeln!(writer, "Plotname: {}", plot_name)?;
+    writeln!(writer, "Plotname: {}", plot_name(analysis))?;
     writeln!(
         writer,
         "Flags: {}",
         if is_complex { "complex" } else { "real" }
     )?;
-    writeln!(writer, "No. Variables: {}", result.variables.len())?;
-    writeln!(writer, "No. Points: {}", num_points)?;
+    writeln!(writer, "No. Variables: {}", num_variables)?;
+    write!(writer, "No. Points: ")?;
+    Ok(())
+}
+
+/// SPICE3 type of a variable; power and charge have their own ngspice
+/// types, which keeps their unit through a round trip
+fn raw_type_name(var: &Variable) -> String {
+    match (var.var_type, var.unit) {
+        (VarType::Unknown, Some(Unit::Watt)) => "power".to_string(),
+        (VarType::Unknown | VarType::Internal, Some(Unit::Coulomb)) => "charge".to_string(),
+        (var_type, _) => var_type.to_string(),
+    }
+}
+
+/// Write the variable list after the point count; the data marker
+/// (`Binary:` or `Values:`) is left to the caller
+fn write_raw_header_end<W: Write>(writer: &mut W, variables: &[Variable]) -> Result<()> {
     writeln!(writer, "Variables:")?;
+    for (i, var) in variables.iter().enumerate() {
+        writeln!(writer, "\t{}\t{}\t{}", i, var.name, raw_type_name(var))?;
+    }
+    Ok(())
+}
 
-    // Write variables
-    for (i, var) in result.variables.iter().enumerate() {
-        writeln!(writer, "\t{}\t{}\t{}", i, var.name, var.var_type)?;
+/// Bytes of rows assembled before each write of the binary data
+const WRITE_CHUNK_LEN: usize = 1 << 20;
+
+/// A vector as the binary writer reads it
+enum Column<'a> {
+    Real(&'a [f64]),
+    Complex(&'a [Complex64]),
+    /// Compressed vectors, read point by point
+    Other(&'a VectorData),
+}
+
+impl<'a> Column<'a> {
+    fn new(vector: &'a VectorData) -> Self {
+        match vector {
+            VectorData::Real(data) => Column::Real(data),
+            VectorData::Complex(data) => Column::Complex(data),
+            other => Column::Other(other),
+        }
     }
+}
 
-    writeln!(writer, "Binary:")?;
+/// Write SPICE3 binary data rows for the given vectors
+///
+/// Every value of a complex file is a (real, imaginary) pair, so real
+/// vectors such as the scale get a zero imaginary part there. Rows are
+/// assembled in `buffer` and written about a megabyte at a time.
+fn write_raw_data<W: Write>(
+    writer: &mut W,
+    vectors: &[&VectorData],
+    num_points: usize,
+    is_complex: bool,
+    buffer: &mut Vec<u8>,
+) -> Result<()> {
+    let columns: Vec<Column> = vectors.iter().map(|v| Column::new(v)).collect();
+    let value_len = if is_complex { 16 } else { 8 };
+    let row_len = (columns.len() * value_len).max(1);
+    let rows_per_chunk = (WRITE_CHUNK_LEN / row_len).max(1);
+
+    let mut start = 0;
+    while start < num_points {
+        let end = num_points.min(start + rows_per_chunk);
+        buffer.clear();
+        buffer.reserve((end - start) * row_len);
+        for i in start..end {
+            for column in &columns {
+                let (re, im) = match column {
+                    Column::Real(data) => (data.get(i).copied().unwrap_or(0.0), 0.0),
+                    Column::Complex(data) => {
+                        let c = data.get(i).copied().unwrap_or_default();
+                        (c.re, c.im)
+                    }
+                    Column::Other(vector) => (vector.real_at(i).unwrap_or(0.0), 0.0),
+                };
+                buffer.extend_from_slice(&re.to_le_bytes());
+                if is_complex {
+                    buffer.extend_from_slice(&im.to_le_bytes());
+                }
+            }
+        }
+        writer.write_all(buffer)?;
+        start = end;
+    }
 
     Ok(())
 }
 
-/// Write SPICE3 binary data section
-fn write_raw_data<W: Write>(
+/// Write SPICE3 ASCII data rows, numbering points from `first_point`
+///
+/// Each point starts with its index and the scale on one line, followed by
+/// one line per other variable. Values of a complex file are written as
+/// `re,im` pairs, real vectors with a zero imaginary part.
+fn write_ascii_data<W: Write>(
     writer: &mut W,
-    table: &crate::types::DataTable,
+    vectors: &[&VectorData],
     num_points: usize,
+    first_point: usize,
+    precision: usize,
+    is_complex: bool,
 ) -> Result<()> {
     for i in 0..num_points {
-        for vector in &table.vectors {
+        for (v, vector) in vectors.iter().enumerate() {
+            if v == 0 {
+                write!(writer, " {}", first_point + i)?;
+            }
             match vector {
-                VectorData::Real(data) => {
-                    let val = data.get(i).copied().unwrap_or(0.0);
-                    writer.write_all(&val.to_le_bytes())?;
-                }
                 VectorData::Complex(data) => {
-                    // SPICE3 complex format: write real part then imaginary part (16 bytes total)
                     let c = data.get(i).copied().unwrap_or_default();
-                    writer.write_all(&c.re.to_le_bytes())?;
-                    writer.write_all(&c.im.to_le_bytes())?;
+                    write!(
+                        writer,
+                        "\t{},{}",
+                        ascii_value(c.re, precision),
+                        ascii_value(c.im, precision)
+                    )?;
+                }
+                other => {
+                    let val = ascii_value(other.real_at(i).unwrap_or(0.0), precision);
+                    if is_complex {
+                        write!(writer, "\t{},{}", val, ascii_value(0.0, precision))?;
+                    } else {
+                        write!(writer, "\t{}", val)?;
+                    }
                 }
             }
+            writeln!(writer)?;
         }
     }
-
     Ok(())
 }
 
-/// Convert WaveformResult to SPICE3 binary raw format
-#[instrument(skip(result), fields(output = %output_path))]
-pub fn write_spice3_raw(result: &WaveformResult, output_path: &str) -> Result<()> {
-    info!("Writing SPICE3 raw file");
+/// `value` in C-style exponent notation (`1.500e-09`), as SPICE writes it
+fn ascii_value(value: f64, precision: usize) -> String {
+    let text = format!("{:.*e}", precision, value);
+    match text.split_once('e') {
+        Some((mantissa, exponent)) => {
+            let exponent: i32 = exponent.parse().unwrap_or(0);
+            let sign = if exponent < 0 { '-' } else { '+' };
+            format!("{}e{}{:02}", mantissa, sign, exponent.abs())
+        }
+        // NaN and infinities have no exponent
+        None => text,
+    }
+}
 
-    // Get the first data table
-    let table = result
-        .tables
-        .first()
-        .ok_or_else(|| WaveformError::ParseError("No data tables found".into()))?;
+// ============================================================================
+// Streaming Writer
+// ============================================================================
 
-    let num_points = table.len();
-    let num_vars = result.variables.len();
+/// Streaming SPICE3 raw file writer
+///
+/// Data is binary unless [`with_encoding`](Self::with_encoding) picks
+/// ASCII. The header is written up front with room for the point count; rows are
+/// appended as tables or stream chunks arrive. `finish` patches the point
+/// count, flushes and syncs the file, and reports late I/O errors (such as
+/// a full disk) as `WriteFailed` with the output path. Dropping the writer
+/// without calling `finish` loses those errors.
+///
+/// [`create_for_stream`](Self::create_for_stream) takes the header from a
+/// stream reader, so a file of any size converts in constant memory:
+///
+/// ```rust,no_run
+/// use hspice_core::{read_stream, AnalysisType, RawWriter};
+///
+/// let reader = read_stream("large.tr0").unwrap();
+/// let mut writer =
+///     RawWriter::create_for_stream("large.raw", &reader.metadata(), AnalysisType::Transient)
+///         .unwrap();
+/// for chunk in reader {
+///     writer.write_chunk(&chunk.unwrap()).unwrap();
+/// }
+/// writer.finish().unwrap();
+/// ```
+///
+/// Or with the variables given explicitly:
+///
+/// ```rust,no_run
+/// use hspice_core::{read_stream, RawWriter};
+///
+/// let reader = read_stream("large.tr0").unwrap();
+/// let meta = reader.metadata();
+/// let variables: Vec<_> = std::iter::once(&meta.scale_name)
+///     .chain(&meta.signal_names)
+///     .map(|name| hspice_core::Variable::new(name))
+///     .collect();
+///
+/// let mut writer = RawWriter::create(
+///     "large.raw",
+///     &meta.title,
+///     &meta.date,
+///     hspice_core::AnalysisType::Transient,
+///     &variables,
+///     meta.is_complex,
+/// )
+/// .unwrap();
+/// for chunk in reader {
+///     writer.write_chunk(&chunk.unwrap()).unwrap();
+/// }
+/// let report = writer.finish().unwrap();
+/// println!("{} points, {} bytes", report.points, report.bytes);
+/// ```
+pub struct RawWriter {
+    writer: BufWriter<File>,
+    path: String,
+    variables: Vec<String>,
+    points_offset: u64,
+    points: usize,
+    /// Sweep tables seen in stream chunks
+    tables: usize,
+    is_complex: bool,
+    encoding: RawEncoding,
+    /// Whether the data marker has been written
+    data_started: bool,
+    /// Rows assembled for the next binary write, kept between chunks
+    buffer: Vec<u8>,
+    started: Instant,
+    warnings: Vec<String>,
+    #[cfg(feature = "checksum")]
+    checksum: bool,
+}
 
-    debug!(points = num_points, variables = num_vars, "Data info");
+impl RawWriter {
+    /// Create `path` and write the header for `variables` (scale first)
+    pub fn create(
+        path: &str,
+        title: &str,
+        date: &str,
+        analysis: AnalysisType,
+        variables: &[Variable],
+        is_complex: bool,
+    ) -> Result<Self> {
+        let started = Instant::now();
+        let context = |e| write_context(path, "creating the file", e);
 
-    // Check for complex data
-    let is_complex = table.vectors.iter().any(|v| v.is_complex());
+        let file = File::create(path).map_err(WaveformError::IoError)?;
+        let mut writer = BufWriter::new(file);
+        write_raw_header_start(
+            &mut writer,
+            title,
+            date,
+            analysis,
+            variables.len(),
+            is_complex,
+        )
+        .map_err(context)?;
+        let points_offset = writer.stream_position().map_err(WaveformError::IoError)?;
+        writeln!(writer, "{:<width$}", 0, width = POINTS_FIELD_WIDTH)
+            .map_err(|e| context(e.into()))?;
+        write_raw_header_end(&mut writer, variables).map_err(context)?;
 
-    // Create output file
-    let file = File::create(output_path)?;
-    let mut writer = BufWriter::new(file);
+        Ok(Self {
+            writer,
+            path: path.to_string(),
+            variables: variables.iter().map(|v| v.name.clone()).collect(),
+            points_offset,
+            points: 0,
+            tables: 0,
+            is_complex,
+            encoding: RawEncoding::Binary,
+            data_started: false,
+            buffer: Vec::new(),
+            started,
+            warnings: Vec::new(),
+            #[cfg(feature = "checksum")]
+            checksum: false,
+        })
+    }
 
-    // Determine plot name based on analysis type
-    let plot_name = match result.analysis {
-        AnalysisType::Transient => "Transient Analysis",
-        AnalysisType::AC => "AC Analysis",
-        AnalysisType::DC => "DC Analysis",
-        AnalysisType::Operating => "Operating Point",
-        AnalysisType::Noise => "Noise Analysis",
-        AnalysisType::Unknown => "Analysis",
-    };
+    /// Create `path` with the header for the signals of a stream
+    ///
+    /// The variables are the scale and every signal in `metadata`, so the
+    /// stream must not be filtered to fewer signals.
+    pub fn create_for_stream(
+        path: &str,
+        metadata: &StreamMetadata,
+        analysis: AnalysisType,
+    ) -> Result<Self> {
+        let variables: Vec<Variable> = std::iter::once(&metadata.scale_name)
+            .chain(&metadata.signal_names)
+            .map(Variable::new)
+            .collect();
+        Self::create(
+            path,
+            &metadata.title,
+            &metadata.date,
+            analysis,
+            &variables,
+            metadata.is_complex,
+        )
+    }
+
+    /// Write the data as `encoding` (binary by default)
+    ///
+    /// The encoding is fixed by the first row written; a later change is
+    /// ignored with a warning.
+    pub fn with_encoding(mut self, encoding: RawEncoding) -> Self {
+        if self.data_started && encoding != self.encoding {
+            self.warn(format!(
+                "encoding {:?} requested after data was written; kept {:?}",
+                encoding, self.encoding
+            ));
+        } else {
+            self.encoding = encoding;
+        }
+        self
+    }
+
+    /// Write a `.sha256` sidecar next to the output in `finish`
+    ///
+    /// The point count is patched into the header after the data, so the
+    /// finished file is hashed with one sequential read after it is synced.
+    #[cfg(feature = "checksum")]
+    pub fn with_checksum(mut self) -> Self {
+        self.checksum = true;
+        self
+    }
+
+    /// Append every row of `table`; vectors must be in variable order
+    pub fn write_table(&mut self, table: &DataTable) -> Result<()> {
+        if table.vectors.len() != self.variables.len() {
+            return Err(WaveformError::ParseError(format!(
+                "table has {} vectors, writer expects {}",
+                table.vectors.len(),
+                self.variables.len()
+            )));
+        }
+        let vectors: Vec<&VectorData> = table.vectors.iter().collect();
+        self.write_rows(&vectors, table.len())
+    }
+
+    /// Append the rows of a stream chunk, matching vectors by name
+    ///
+    /// Fails if the chunk lacks a variable, e.g. when the stream was
+    /// opened with a signal filter that the writer does not share. SPICE3
+    /// raw holds one table, so chunks of later sweep tables are skipped and
+    /// listed in the report's warnings.
+    pub fn write_chunk(&mut self, chunk: &DataChunk) -> Result<()> {
+        self.tables = self.tables.max(chunk.table_index + 1);
+        if chunk.table_index > 0 {
+            return Ok(());
+        }
+        let vectors = self
+            .variables
+            .iter()
+            .map(|name| {
+                chunk.data.get(name).ok_or_else(|| {
+                    WaveformError::ParseError(format!(
+                        "chunk {} has no signal {}",
+                        chunk.chunk_index, name
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let num_points = vectors.first().map(|v| v.len()).unwrap_or(0);
+        self.write_rows(&vectors, num_points)
+    }
+
+    /// Record a warning to include in the final report
+    pub fn warn(&mut self, message: impl Into<String>) {
+        let message = message.into();
+        warn!(output = %self.path, "{}", message);
+        self.warnings.push(message);
+    }
+
+    /// Patch the point count, flush and sync the file
+    pub fn finish(mut self) -> Result<ConversionReport> {
+        if self.tables > 1 {
+            self.warn(format!(
+                "only the first of {} sweep tables was written",
+                self.tables
+            ));
+        }
+        self.start_data()?;
+        let path = self.path.as_str();
+        let io_context = |stage| move |e: std::io::Error| write_context(path, stage, e.into());
+
+        self.writer.flush().map_err(io_context("flushing data"))?;
+        let bytes = self
+            .writer
+            .stream_position()
+            .map_err(io_context("flushing data"))?;
+
+        let mut file = self
+            .writer
+            .into_inner()
+            .map_err(|e| io_context("flushing data")(e.into_error()))?;
+        file.seek(SeekFrom::Start(self.points_offset))
+            .map_err(io_context("writing the point count"))?;
+        write!(file, "{:<width$}", self.points, width = POINTS_FIELD_WIDTH)
+            .map_err(io_context("writing the point count"))?;
+        file.sync_all().map_err(io_context("syncing to disk"))?;
+        drop(file);
+
+        #[cfg(feature = "checksum")]
+        let sha256 = if self.checksum {
+            let hash = crate::checksum::file_hash(&self.path)
+                .and_then(|hash| crate::checksum::write_sidecar(&self.path, &hash).map(|_| hash))
+                .map_err(|e| write_context(path, "writing the checksum sidecar", e))?;
+            Some(hash.to_string())
+        } else {
+            None
+        };
+        #[cfg(not(feature = "checksum"))]
+        let sha256 = None;
+
+        let report = ConversionReport {
+            points: self.points,
+            variables: self.variables.len(),
+            bytes,
+            duration: self.started.elapsed(),
+            warnings: self.warnings,
+            sha256,
+        };
+        info!(
+            output = %self.path,
+            points = report.points,
+            bytes = report.bytes,
+            "Write complete"
+        );
+        Ok(report)
+    }
+
+    /// Write the data marker before the first row
+    fn start_data(&mut self) -> Result<()> {
+        if !std::mem::replace(&mut self.data_started, true) {
+            writeln!(self.writer, "{}", self.encoding.marker())
+                .map_err(|e| write_context(&self.path, "writing data", e.into()))?;
+        }
+        Ok(())
+    }
+
+    fn write_rows(&mut self, vectors: &[&VectorData], num_points: usize) -> Result<()> {
+        self.start_data()?;
+        match self.encoding {
+            RawEncoding::Binary => write_raw_data(
+                &mut self.writer,
+                vectors,
+                num_points,
+                self.is_complex,
+                &mut self.buffer,
+            ),
+            RawEncoding::Ascii { precision } => write_ascii_data(
+                &mut self.writer,
+                vectors,
+                num_points,
+                self.points,
+                precision,
+                self.is_complex,
+            ),
+        }
+        .map_err(|e| write_context(&self.path, "writing data", e))?;
+        self.points += num_points;
+        Ok(())
+    }
+}
+
+/// Attach the output path and stage to an I/O error
+pub(crate) fn write_context(path: &str, stage: &'static str, err: WaveformError) -> WaveformError {
+    match err {
+        WaveformError::IoError(source) => WaveformError::WriteFailed {
+            path: path.to_string(),
+            stage,
+            source,
+        },
+        other => other,
+    }
+}
+
+// ============================================================================
+// Whole-Result Conversion
+// ============================================================================
+
+/// Convert WaveformResult to SPICE3 binary raw format
+///
+/// SPICE3 raw holds one table, so only the first sweep table is written;
+/// the others are listed in the report's warnings.
+pub fn write_spice3_raw(result: &WaveformResult, output_path: &str) -> Result<ConversionReport> {
+    write_spice3_raw_with(result, output_path, RawEncoding::Binary)
+}
 
-    // Write header
-    write_raw_header(
-        &mut writer,
+/// Convert WaveformResult to SPICE3 raw format with the data in `encoding`
+///
+/// Use [`RawEncoding::ascii`] for the text `Values:` format that legacy
+/// viewers and diff-based regression flows expect.
+#[instrument(skip(result), fields(output = %output_path))]
+pub fn write_spice3_raw_with(
+    result: &WaveformResult,
+    output_path: &str,
+    encoding: RawEncoding,
+) -> Result<ConversionReport> {
+    info!(?encoding, "Writing SPICE3 raw file");
+
+    let table = first_table(result)?;
+    debug!(
+        points = table.len(),
+        variables = result.variables.len(),
+        "Data info"
+    );
+    let mut writer = RawWriter::create(
+        output_path,
         &result.title,
         &result.date,
-        plot_name,
-        result,
-        num_points,
-        is_complex,
-    )?;
+        result.analysis,
+        &result.variables,
+        table.vectors.iter().any(|v| v.is_complex()),
+    )?
+    .with_encoding(encoding);
+    writer.write_table(table)?;
+    if result.tables.len() > 1 {
+        writer.warn(format!(
+            "only the first of {} sweep tables was written",
+            result.tables.len()
+        ));
+    }
+    writer.finish()
+}
 
-    // Write binary data
-    write_raw_data(&mut writer, table, num_points)?;
+/// Write WaveformResult as SPICE3 raw into any writer, data in `encoding`
+///
+/// The in-memory counterpart of [`write_spice3_raw_with`], for targets
+/// without a file system. Only the first sweep table is written.
+pub fn write_spice3_raw_to<W: Write>(
+    result: &WaveformResult,
+    writer: &mut W,
+    encoding: RawEncoding,
+) -> Result<()> {
+    let table = first_table(result)?;
+    let num_points = table.len();
+    let is_complex = table.vectors.iter().any(|v| v.is_complex());
 
-    writer.flush()?;
+    write_raw_header_start(
+        writer,
+        &result.title,
+        &result.date,
+        result.analysis,
+        result.variables.len(),
+        is_complex,
+    )?;
+    writeln!(writer, "{}", num_points)?;
+    write_raw_header_end(writer, &result.variables)?;
+    writeln!(writer, "{}", encoding.marker())?;
 
-    let bytes_written = std::fs::metadata(output_path)?.len();
-    info!(bytes = bytes_written, "Write complete");
+    let vectors: Vec<&VectorData> = table.vectors.iter().collect();
+    match encoding {
+        RawEncoding::Binary => {
+            write_raw_data(writer, &vectors, num_points, is_complex, &mut Vec::new())
+        }
+        RawEncoding::Ascii { precision } => {
+            write_ascii_data(writer, &vectors, num_points, 0, precision, is_complex)
+        }
+    }
+}
 
-    Ok(())
+fn first_table(result: &WaveformResult) -> Result<&DataTable> {
+    result
+        .tables
+        .first()
+        .ok_or_else(|| WaveformError::ParseError("No data tables found".into()))
 }
 
 /// Convert HSPICE .tr0 file to SPICE3 binary raw format
+///
+/// The input is streamed chunk by chunk, so memory use does not grow with
+/// the file size.
 #[instrument(skip_all, fields(input = %input_path, output = %output_path))]
-pub fn hspice_to_raw_impl(input_path: &str, output_path: &str) -> Result<()> {
-    use crate::parser::hspice_read_impl;
+pub fn hspice_to_raw_impl(input_path: &str, output_path: &str) -> Result<ConversionReport> {
+    use crate::parser::{header_analysis, infer_analysis_type};
 
     info!("Converting HSPICE to SPICE3 raw format");
-    let result = hspice_read_impl(input_path)?;
-    write_spice3_raw(&result, output_path)?;
+    let reader = HspiceStreamReader::open(input_path, DEFAULT_CHUNK_SIZE)?;
+    let header = reader.header();
+    let analysis = header_analysis(
+        header.is_complex,
+        &header.scale_name,
+        infer_analysis_type(input_path),
+    );
+    let mut writer = RawWriter::create(
+        output_path,
+        &header.title,
+        &header.date,
+        analysis,
+        &reader.variables(),
+        header.is_complex,
+    )?;
+    for chunk in reader {
+        writer.write_chunk(&chunk?)?;
+    }
+    let report = writer.finish()?;
     info!("Conversion complete");
 
-    Ok(())
+    Ok(report)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{temp_path, SyntheticWaveform};
+
+    #[test]
+    fn test_report_and_sweep_warning() {
+        let path = temp_path("writer_report.raw");
+        let synth = SyntheticWaveform::new()
+            .signals(2)
+            .points(40)
+            .sweep("temp", vec![25.0, 85.0]);
+        let expected = synth.expected();
+
+        let report = write_spice3_raw(&expected, path.to_str().unwrap()).unwrap();
+        assert_eq!(report.points, 40);
+        assert_eq!(report.variables, 3);
+        assert_eq!(report.bytes, std::fs::metadata(&path).unwrap().len());
+        assert_eq!(report.warnings.len(), 1);
+
+        let back = crate::read_raw(path.to_str().unwrap()).unwrap();
+        assert_eq!(back.len(), 40);
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_stream_chunks_to_raw() {
+        let input = temp_path("writer_stream.tr0");
+        let output = temp_path("writer_stream.raw");
+        let synth = SyntheticWaveform::new().signals(3).points(250);
+        synth.write_tr0(&input).unwrap();
+        let expected = synth.expected();
+
+        let reader = crate::read_stream_chunked(&input, 64).unwrap();
+        let mut writer = RawWriter::create(
+            output.to_str().unwrap(),
+            &expected.title,
+            &expected.date,
+            expected.analysis,
+            &expected.variables,
+            false,
+        )
+        .unwrap();
+        for chunk in reader {
+            writer.write_chunk(&chunk.unwrap()).unwrap();
+        }
+        let report = writer.finish().unwrap();
+        assert_eq!(report.points, 250);
+        assert!(report.warnings.is_empty());
+
+        let back = crate::read_raw(output.to_str().unwrap()).unwrap();
+        assert_eq!(
+            format!("{:?}", back.tables[0].vectors[1]),
+            format!("{:?}", expected.tables[0].vectors[1])
+        );
+        std::fs::remove_file(input).ok();
+        std::fs::remove_file(output).ok();
+    }
+
+    #[test]
+    fn test_streamed_conversion_matches_whole_result() {
+        let input = temp_path("writer_convert.tr0");
+        let streamed = temp_path("writer_convert_streamed.raw");
+        let whole = temp_path("writer_convert_whole.raw");
+        SyntheticWaveform::new()
+            .signals(2)
+            .points(300)
+            .block_size(40)
+            .sweep("temp", vec![25.0, 85.0, 125.0])
+            .write_tr0(&input)
+            .unwrap();
+
+        let report =
+            crate::read_and_convert(input.to_str().unwrap(), streamed.to_str().unwrap()).unwrap();
+        assert_eq!(report.points, 300);
+        assert_eq!(
+            report.warnings,
+            ["only the first of 3 sweep tables was written"]
+        );
+        let result = crate::read(input.to_str().unwrap()).unwrap();
+        write_spice3_raw(&result, whole.to_str().unwrap()).unwrap();
+        assert_eq!(
+            std::fs::read(&streamed).unwrap(),
+            std::fs::read(&whole).unwrap()
+        );
+
+        for path in [input, streamed, whole] {
+            std::fs::remove_file(path).ok();
+        }
+    }
+
+    #[test]
+    fn test_binary_rows_span_write_chunks() {
+        // 3 complex columns of 16 bytes: about 2.3 write chunks
+        let synth = SyntheticWaveform::new()
+            .signals(2)
+            .points(50_000)
+            .complex(true);
+        let expected = synth.expected();
+        let mut raw = Vec::new();
+        write_spice3_raw_to(&expected, &mut raw, RawEncoding::Binary).unwrap();
+        assert!(raw.len() > 2 * WRITE_CHUNK_LEN);
+
+        let result = crate::read_raw_bytes(&raw, &crate::ParserLimits::unlimited()).unwrap();
+        // The reader returns every vector of a complex file as complex
+        let pairs = |v: &VectorData| -> Vec<(f64, f64)> {
+            match v.as_complex() {
+                Some(data) => data.iter().map(|c| (c.re, c.im)).collect(),
+                None => (0..v.len()).map(|i| (v.real_at(i).unwrap(), 0.0)).collect(),
+            }
+        };
+        let table = &result.tables[0];
+        assert_eq!(table.len(), 50_000);
+        for (actual, expected) in table.vectors.iter().zip(&expected.tables[0].vectors) {
+            assert!(pairs(actual) == pairs(expected));
+        }
+    }
+
+    #[test]
+    fn test_ascii_round_trip() {
+        let path = temp_path("writer_ascii.raw");
+        for complex in [false, true] {
+            let expected = SyntheticWaveform::new()
+                .signals(2)
+                .points(25)
+                .complex(complex)
+                .expected();
+            write_spice3_raw_with(&expected, path.to_str().unwrap(), RawEncoding::ascii()).unwrap();
+            let text = std::fs::read_to_string(&path).unwrap();
+            assert!(text.contains("\nValues:\n 0\t"));
+
+            let back = crate::read_raw(path.to_str().unwrap()).unwrap();
+            assert_eq!(back.len(), 25);
+            for (vector, want) in back.tables[0]
+                .vectors
+                .iter()
+                .zip(&expected.tables[0].vectors)
+            {
+                match (vector, want) {
+                    (VectorData::Complex(a), VectorData::Complex(b)) => assert_eq!(a, b),
+                    (a, b) if !complex => assert_eq!(a.to_real(), b.to_real()),
+                    (a, b) => {
+                        // The scale of a complex file reads back as complex
+                        let scale: Vec<f64> = match a {
+                            VectorData::Complex(v) => v.iter().map(|c| c.re).collect(),
+                            other => other.to_real().unwrap().into_owned(),
+                        };
+                        assert_eq!(scale, *b.to_real().unwrap());
+                    }
+                }
+            }
+        }
+        std::fs::remove_file(path).ok();
+
+        assert_eq!(ascii_value(1.5e-9, 3), "1.500e-09");
+        assert_eq!(ascii_value(-250.0, 2), "-2.50e+02");
+        assert_eq!(ascii_value(0.0, 1), "0.0e+00");
+    }
+
+    #[test]
+    fn test_checksum_sidecar() {
+        let path = temp_path("writer_checksum.raw");
+        let expected = SyntheticWaveform::new().signals(2).points(30).expected();
+        let mut writer = RawWriter::create(
+            path.to_str().unwrap(),
+            &expected.title,
+            &expected.date,
+            expected.analysis,
+            &expected.variables,
+            false,
+        )
+        .unwrap()
+        .with_checksum();
+        writer.write_table(&expected.tables[0]).unwrap();
+        let report = writer.finish().unwrap();
+
+        let hash = crate::checksum::file_hash(&path).unwrap();
+        assert_eq!(report.sha256, Some(hash.to_string()));
+        assert!(crate::checksum::verify_sidecar(&path).unwrap());
+        std::fs::remove_file(crate::checksum::sidecar_path(&path)).ok();
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_write_errors_carry_path() {
+        let path = temp_path("writer_errors.raw");
+        let expected = SyntheticWaveform::new().signals(2).expected();
+        let mut writer = RawWriter::create(
+            path.to_str().unwrap(),
+            "t",
+            "d",
+            AnalysisType::Transient,
+            &expected.variables[..2],
+            false,
+        )
+        .unwrap();
+        assert!(writer.write_table(&expected.tables[0]).is_err());
+        drop(writer);
+
+        let err = write_context(
+            "out.raw",
+            "syncing to disk",
+            std::io::Error::other("full").into(),
+        );
+        assert!(matches!(
+            err,
+            WaveformError::WriteFailed {
+                stage: "syncing to disk",
+                ..
+            }
+        ));
+        assert!(err.to_string().contains("out.raw"));
+        std::fs::remove_file(path).ok();
+    }
 }
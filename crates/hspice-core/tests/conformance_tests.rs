@@ -0,0 +1,222 @@
+//! Checked-in conformance vectors
+//!
+//! The inputs under `conformance/inputs` come from the synthetic generator
+//! and the golden summaries under `conformance/expected` from the core
+//! parser. To regenerate both after an intentional change:
+//!
+//! ```bash
+//! HSPICE_BLESS=1 cargo test -p hspice-core --test conformance_tests
+//! ```
+//!
+//! Every case, and a batch of randomly shaped synthetic files, must also
+//! survive a round trip through the SPICE3 raw writer and reader.
+
+use hspice_core::conformance::{self, InputFormat, EXPECTED_DIR, INPUTS_DIR};
+use hspice_core::testing::SyntheticWaveform;
+use hspice_core::{
+    read, read_bytes, read_raw, read_raw_bytes, Endian, ParserLimits, PostVersion, RawEncoding,
+    WaveformResult,
+};
+use std::path::PathBuf;
+use std::sync::Once;
+
+static BLESS: Once = Once::new();
+
+fn conformance_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../conformance")
+}
+
+/// Every case: file name, reader, and generated contents
+fn cases() -> Vec<(&'static str, InputFormat, Vec<u8>)> {
+    vec![
+        (
+            "tran_9601_le.tr0",
+            InputFormat::Hspice,
+            SyntheticWaveform::new()
+                .signals(3)
+                .points(40)
+                .to_tr0_bytes(),
+        ),
+        (
+            "legacy_9007.tr0",
+            InputFormat::Hspice,
+            SyntheticWaveform::new()
+                .signals(2)
+                .points(24)
+                .block_size(9)
+                .version(PostVersion::V9007)
+                .to_tr0_bytes(),
+        ),
+        (
+            "tran_2001_be.tr0",
+            InputFormat::Hspice,
+            SyntheticWaveform::new()
+                .signal_names(&["v(OUT)", "i(vdd)"])
+                .points(25)
+                .version(PostVersion::V2001)
+                .endian(Endian::Big)
+                .to_tr0_bytes(),
+        ),
+        (
+            "ac_9601.ac0",
+            InputFormat::Hspice,
+            SyntheticWaveform::new()
+                .signals(2)
+                .points(30)
+                .complex(true)
+                .to_tr0_bytes(),
+        ),
+        (
+            "sweep_9601.tr0",
+            InputFormat::Hspice,
+            SyntheticWaveform::new()
+                .signals(2)
+                .points(12)
+                .sweep("temp", vec![-40.0, 25.0, 125.0])
+                .to_tr0_bytes(),
+        ),
+        (
+            "straddle_2001.tr0",
+            InputFormat::Hspice,
+            SyntheticWaveform::new()
+                .signals(3)
+                .points(33)
+                .block_size(7)
+                .version(PostVersion::V2001)
+                .to_tr0_bytes(),
+        ),
+        (
+            "tran.raw",
+            InputFormat::Spice3,
+            SyntheticWaveform::new()
+                .signals(2)
+                .points(20)
+                .to_raw_bytes(),
+        ),
+    ]
+}
+
+/// Rewrite inputs and golden files (once per run) when HSPICE_BLESS is set
+fn bless_if_requested() {
+    if std::env::var_os("HSPICE_BLESS").is_some() {
+        BLESS.call_once(bless);
+    }
+}
+
+fn bless() {
+    let root = conformance_root();
+    std::fs::create_dir_all(root.join(INPUTS_DIR)).unwrap();
+    std::fs::create_dir_all(root.join(EXPECTED_DIR)).unwrap();
+
+    for (name, format, bytes) in cases() {
+        let input = format!("{}/{}", INPUTS_DIR, name);
+        let path = root.join(&input);
+        std::fs::write(&path, bytes).unwrap();
+
+        let result = match format {
+            InputFormat::Hspice => read(path.to_str().unwrap()),
+            InputFormat::Spice3 => read_raw(path.to_str().unwrap()),
+        }
+        .unwrap();
+        let summary = conformance::summarize(&result, &input, format);
+        let golden = root.join(EXPECTED_DIR).join(format!("{}.json", name));
+        std::fs::write(golden, summary.to_json() + "\n").unwrap();
+    }
+}
+
+#[test]
+fn test_inputs_match_generator() {
+    bless_if_requested();
+    let root = conformance_root();
+    for (name, _, bytes) in cases() {
+        let on_disk = std::fs::read(root.join(INPUTS_DIR).join(name)).unwrap();
+        assert!(on_disk == bytes, "{} differs from the generator", name);
+    }
+}
+
+#[test]
+fn test_verify_conformance() {
+    bless_if_requested();
+    let reports = conformance::verify(conformance_root()).unwrap();
+    assert_eq!(reports.len(), cases().len());
+    for report in &reports {
+        assert!(report.passed(), "{}: {:#?}", report.name, report.mismatches);
+    }
+}
+
+// =============================================================================
+// Round trips through SPICE3 raw
+// =============================================================================
+
+/// Digits kept by the coarse ASCII round trip, and the ULPs that loses
+const COARSE_PRECISION: usize = 6;
+const COARSE_ULPS: u64 = 1 << 33;
+
+fn assert_round_trips(result: &WaveformResult, label: &str) {
+    for encoding in [RawEncoding::Binary, RawEncoding::ascii()] {
+        let diffs = conformance::raw_round_trip(result, encoding, 0).unwrap();
+        assert!(diffs.is_empty(), "{} {:?}: {:#?}", label, encoding, diffs);
+    }
+    let coarse = RawEncoding::Ascii {
+        precision: COARSE_PRECISION,
+    };
+    let diffs = conformance::raw_round_trip(result, coarse, COARSE_ULPS).unwrap();
+    assert!(diffs.is_empty(), "{} {:?}: {:#?}", label, coarse, diffs);
+}
+
+#[test]
+fn test_cases_round_trip_through_raw() {
+    for (name, format, bytes) in cases() {
+        let result = match format {
+            InputFormat::Hspice => read_bytes(&bytes, &ParserLimits::unlimited()),
+            InputFormat::Spice3 => read_raw_bytes(&bytes, &ParserLimits::unlimited()),
+        }
+        .unwrap();
+        assert_round_trips(&result, name);
+    }
+}
+
+/// Small deterministic generator so failures reproduce from the seed
+struct Lcg(u64);
+
+impl Lcg {
+    fn below(&mut self, n: u64) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 33) % n
+    }
+}
+
+#[test]
+fn test_random_shapes_round_trip() {
+    let mut rng = Lcg(0x5eed);
+    for case in 0..64 {
+        let mut synth = SyntheticWaveform::new()
+            .signals(1 + rng.below(6) as usize)
+            .points(1 + rng.below(200) as usize)
+            .block_size(2 + rng.below(300) as usize)
+            .complex(rng.below(3) == 0)
+            .version(if rng.below(2) == 0 {
+                PostVersion::V9601
+            } else {
+                PostVersion::V2001
+            })
+            .endian(if rng.below(2) == 0 {
+                Endian::Little
+            } else {
+                Endian::Big
+            });
+        let tables = rng.below(4);
+        if tables > 0 {
+            synth = synth.sweep("temp", (0..tables).map(|t| t as f64 * 25.0).collect());
+        }
+        let label = format!("case {} {:?}", case, synth);
+
+        let result = read_bytes(&synth.to_tr0_bytes(), &ParserLimits::unlimited()).unwrap();
+        let diffs = conformance::compare_data(&result, &synth.expected(), 0);
+        assert!(diffs.is_empty(), "{}: {:#?}", label, diffs);
+        assert_round_trips(&result, &label);
+    }
+}
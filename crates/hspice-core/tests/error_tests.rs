@@ -0,0 +1,201 @@
+//! Error kinds and byte offsets
+//!
+//! Each malformed input must surface as a specific `WaveformError` variant,
+//! and structural errors must point at the byte where the problem starts.
+
+use hspice_core::testing::{temp_path, SyntheticWaveform};
+use hspice_core::{read, read_bytes, read_raw_bytes, ParserLimits, WaveformError};
+
+/// Offset of the header text inside a synthetic file (after the block head)
+const HEADER_OFFSET: usize = 16;
+
+fn small_file() -> Vec<u8> {
+    SyntheticWaveform::new()
+        .signals(2)
+        .points(10)
+        .block_size(8)
+        .to_tr0_bytes()
+}
+
+/// Offset of the first data block (just past the header block trailer)
+fn data_start(data: &[u8]) -> usize {
+    let header_bytes = i32::from_le_bytes(data[12..16].try_into().unwrap()) as usize;
+    HEADER_OFFSET + header_bytes + 4
+}
+
+fn read_err(data: &[u8]) -> WaveformError {
+    read_bytes(data, &ParserLimits::unlimited()).unwrap_err()
+}
+
+// =============================================================================
+// Test: File-level errors
+// =============================================================================
+
+#[test]
+fn test_empty_and_ascii_files() {
+    assert!(matches!(read_err(&[]), WaveformError::EmptyFile));
+    assert!(matches!(
+        read_err(b"00050000000000009601"),
+        WaveformError::AsciiFormat
+    ));
+}
+
+#[test]
+fn test_unknown_post_version() {
+    let mut data = small_file();
+    data[HEADER_OFFSET + 16..HEADER_OFFSET + 20].copy_from_slice(b"1234");
+    match read_err(&data).root_cause() {
+        WaveformError::UnknownPostVersion { value } => assert_eq!(value, "1234"),
+        other => panic!("expected UnknownPostVersion, got {other}"),
+    }
+}
+
+#[test]
+fn test_missing_vector_names() {
+    let mut data = small_file();
+    // Three variables plus 99 probes the header never names
+    data[HEADER_OFFSET + 4..HEADER_OFFSET + 8].copy_from_slice(b"0099");
+    assert!(matches!(
+        read_err(&data).root_cause(),
+        WaveformError::MissingVectorNames { expected: 103, .. }
+    ));
+}
+
+// =============================================================================
+// Test: Block structure and offsets
+// =============================================================================
+
+#[test]
+fn test_header_trailer_mismatch_offset() {
+    let mut data = small_file();
+    let trailer = data_start(&data) - 4;
+    data[trailer] ^= 0x01;
+    let err = read_err(&data);
+    match err.root_cause() {
+        WaveformError::TrailerMismatch { offset, .. } => {
+            assert_eq!(*offset, trailer as u64);
+            assert_eq!(err.offset(), Some(trailer as u64));
+        }
+        other => panic!("expected TrailerMismatch, got {other}"),
+    }
+    assert!(err.to_string().starts_with("reading header: "));
+}
+
+#[test]
+fn test_truncated_data_block_offset() {
+    let data = small_file();
+    let start = data_start(&data);
+    let truncated = &data[..start + 20];
+    match read_err(truncated).root_cause() {
+        WaveformError::TruncatedBlock {
+            offset,
+            needed,
+            available,
+        } => {
+            assert_eq!(*offset, start as u64 + 16);
+            assert_eq!(*available, 4);
+            assert!(needed > available);
+        }
+        other => panic!("expected TruncatedBlock, got {other}"),
+    }
+}
+
+#[test]
+fn test_missing_sweep_table_offset() {
+    let data = SyntheticWaveform::new()
+        .signals(1)
+        .points(4)
+        .sweep("temp", vec![0.0, 1.0])
+        .to_tr0_bytes();
+    let synth_one = SyntheticWaveform::new()
+        .signals(1)
+        .points(4)
+        .sweep("temp", vec![0.0])
+        .to_tr0_bytes();
+    // The first table ends where the single-table file ends, but the
+    // header still declares two
+    let mut truncated = data[..synth_one.len()].to_vec();
+    truncated[..data_start(&data)].copy_from_slice(&data[..data_start(&data)]);
+    match read_err(&truncated) {
+        WaveformError::MissingSweepTable {
+            index,
+            total,
+            offset,
+        } => {
+            assert_eq!((index, total), (2, 2));
+            assert_eq!(offset, truncated.len() as u64);
+        }
+        other => panic!("expected MissingSweepTable, got {other}"),
+    }
+}
+
+// =============================================================================
+// Test: SPICE3 raw errors
+// =============================================================================
+
+#[test]
+fn test_raw_structure_errors() {
+    let limits = ParserLimits::unlimited();
+
+    let raw = b"Title: t\nNo. Variables: 1\nNo. Points: 1\nVariables:\n\t0\ttime\ttime\n";
+    assert!(matches!(
+        read_raw_bytes(raw, &limits),
+        Err(WaveformError::MissingDataSection)
+    ));
+
+    let raw = b"Title: t\nNo. Variables: 2\nNo. Points: 1\nVariables:\n\t0\ttime\ttime\nValues:\n";
+    assert!(matches!(
+        read_raw_bytes(raw, &limits),
+        Err(WaveformError::VariableCountMismatch {
+            declared: 2,
+            listed: 1
+        })
+    ));
+
+    let raw = b"Title: t\nNo. Variables: 1\nNo. Points: 2\nVariables:\n\t0\ttime\ttime\nBinary:\n\0\0\0\0";
+    match read_raw_bytes(raw, &limits) {
+        Err(WaveformError::DataSizeMismatch {
+            needed,
+            available,
+            offset,
+        }) => {
+            assert_eq!((needed, available), (16, 4));
+            assert_eq!(offset, raw.len() as u64 - 4);
+        }
+        other => panic!("expected DataSizeMismatch, got {other:?}"),
+    }
+}
+
+// =============================================================================
+// Test: Context chains
+// =============================================================================
+
+#[test]
+fn test_context_names_file_and_sweep_table() {
+    let mut data = SyntheticWaveform::new()
+        .signals(1)
+        .points(4)
+        .sweep("temp", vec![0.0, 1.0, 2.0])
+        .to_tr0_bytes();
+    // Cut into the third table's only block
+    data.truncate(data.len() - 8);
+    let path = temp_path("error_context.tr0");
+    std::fs::write(&path, &data).unwrap();
+
+    let err = read(path.to_str().unwrap()).unwrap_err();
+    let message = err.to_string();
+    assert!(
+        message.starts_with(&format!("reading {}: ", path.display())),
+        "{message}"
+    );
+    assert!(
+        message.contains(": reading sweep table 3 of 3 at offset 0x"),
+        "{message}"
+    );
+    assert!(matches!(
+        err.root_cause(),
+        WaveformError::TruncatedBlock { .. }
+    ));
+    assert!(err.offset().is_some());
+    std::fs::remove_file(path).ok();
+}
@@ -6,6 +6,8 @@
 //! - test_stream: Streaming API
 //! - test_convert: SPICE3 raw conversion
 
+use hspice_core::{decode, read_bytes, ParserLimits, Unit, VarType};
+#[allow(deprecated)]
 use hspice_core::{read, read_and_convert, read_debug, AnalysisType, VectorData};
 use hspice_core::{read_stream, read_stream_chunked};
 use std::collections::HashSet;
@@ -129,6 +131,7 @@ fn test_data_consistency() {
 }
 
 #[test]
+#[allow(deprecated)]
 fn test_debug_modes() {
     let path = example_tr0();
     if skip_if_missing(&path) {
@@ -214,6 +217,22 @@ fn test_read_9601_tr0() {
     assert_eq!(data.analysis, AnalysisType::Transient);
 }
 
+#[test]
+fn test_signal_types_from_header() {
+    let path = test_file("test_9601.tr0");
+    if skip_if_missing(&path) {
+        return;
+    }
+
+    let data = read(path.to_str().unwrap()).unwrap();
+    let variable = |name: &str| &data.variables[data.var_index(name).unwrap()];
+    // `v(vo` is normalized to `vo`; its header type code still marks a node voltage
+    assert_eq!(variable("vo").var_type, VarType::Voltage);
+    assert_eq!(variable("vo").unit, Some(Unit::Volt));
+    assert_eq!(variable("i(vs").var_type, VarType::Current);
+    assert_eq!(variable("i(vs").unit, Some(Unit::Ampere));
+}
+
 #[test]
 fn test_read_2001_tr0() {
     let path = test_file("test_2001.tr0");
@@ -301,6 +320,56 @@ fn test_format_comparison_same_length() {
     );
 }
 
+#[test]
+fn test_decode_matches_read_bytes_on_examples() {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(example_dir())
+        .map(|dir| dir.map(|entry| entry.unwrap().path()).collect())
+        .unwrap_or_default();
+    files.retain(|path| {
+        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+        matches!(ext, "tr0" | "ac0" | "sw0")
+    });
+    files.sort();
+
+    for path in files {
+        let data = std::fs::read(&path).unwrap();
+        let (header, tables) = decode::decode(&data).unwrap();
+        let result = read_bytes(&data, &ParserLimits::default()).unwrap();
+        let file = path.display();
+
+        let names: Vec<&str> = result.variables[1..]
+            .iter()
+            .map(|v| v.name.as_str())
+            .collect();
+        assert_eq!(header.scale_name, result.variables[0].name, "{file}");
+        assert_eq!(header.names, names, "{file}");
+        assert_eq!(header.title, result.title, "{file}");
+        assert_eq!(header.sweep_name, result.sweep_param, "{file}");
+        assert_eq!(tables.len(), result.tables.len(), "{file}");
+
+        for (table, expected) in tables.iter().zip(&result.tables) {
+            assert_eq!(table.sweep_value, expected.sweep_value, "{file}");
+            assert_eq!(table.num_rows(&header), expected.len(), "{file}");
+            for (row, values) in table.values.chunks(header.num_columns()).enumerate() {
+                let mut column = 0;
+                for vector in &expected.vectors {
+                    match vector {
+                        VectorData::Complex(v) => {
+                            let value = (values[column], values[column + 1]);
+                            assert_eq!(value, (v[row].re, v[row].im), "{file} row {row}");
+                            column += 2;
+                        }
+                        v => {
+                            assert_eq!(values[column], v.real_at(row).unwrap(), "{file}");
+                            column += 1;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
 // =============================================================================
 // Test: Streaming API
 // =============================================================================
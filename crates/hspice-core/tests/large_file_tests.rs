@@ -0,0 +1,125 @@
+//! Files past 4 GiB
+//!
+//! Built with `SyntheticWaveform::write_sparse_tr0`, whose filler rows are
+//! holes, so each file takes a few kilobytes of disk. Offsets past 4 GiB
+//! must come out exact in block walks, seeks and error reports.
+
+use hspice_core::index::{IndexMark, TableIndex, WaveformIndex};
+use hspice_core::testing::{temp_path, SyntheticWaveform};
+use hspice_core::{HspiceStreamReader, LazyWaveform, WaveformError};
+use std::path::PathBuf;
+
+/// Zero rows before the data: more than one 2 GiB block, and past 4 GiB
+const FILLER: u64 = 5 << 30;
+
+/// A sparse file on disk, removed when dropped
+struct SparseFile {
+    path: PathBuf,
+    synth: SyntheticWaveform,
+    /// Offset of the first block of real data
+    data_offset: u64,
+}
+
+impl SparseFile {
+    fn new(name: &str) -> Self {
+        let path = temp_path(name);
+        let synth = SyntheticWaveform::new()
+            .signals(2)
+            .points(300)
+            .block_size(64);
+        let data_offset = synth.write_sparse_tr0(&path, FILLER).unwrap();
+        assert!(data_offset > u32::MAX as u64);
+        Self {
+            path,
+            synth,
+            data_offset,
+        }
+    }
+
+    fn path(&self) -> &str {
+        self.path.to_str().unwrap()
+    }
+}
+
+impl Drop for SparseFile {
+    fn drop(&mut self) {
+        std::fs::remove_file(&self.path).ok();
+    }
+}
+
+#[test]
+fn test_lazy_walks_blocks_past_4gib() {
+    let file = SparseFile::new("large_lazy.tr0");
+    let lazy = LazyWaveform::open(file.path()).unwrap();
+    assert_eq!(lazy.len(), file.synth.filler_rows(FILLER) + 300);
+}
+
+#[test]
+fn test_stream_seeks_past_4gib() {
+    let file = SparseFile::new("large_seek.tr0");
+    let expected = file.synth.expected();
+    let scale = expected.tables[0].vectors[0].to_real().unwrap();
+    let signal = expected.tables[0].vectors[1].to_real().unwrap();
+    let name = expected.variables[1].name.clone();
+
+    // One mark at the real data, as an index built over the file would have
+    let index = WaveformIndex {
+        file_len: std::fs::metadata(&file.path).unwrap().len(),
+        modified: None,
+        tables: vec![TableIndex {
+            offset: file.data_offset,
+            sweep_value: None,
+            rows: 300,
+            scale_range: (scale[0], scale[299]),
+            marks: vec![IndexMark {
+                offset: file.data_offset,
+                lead: 0,
+                first_scale: scale[0],
+            }],
+        }],
+    };
+    let mut reader = HspiceStreamReader::open(file.path(), 100)
+        .unwrap()
+        .with_index(&index);
+    reader.seek_time(scale[0]).unwrap();
+
+    let mut times: Vec<f64> = Vec::new();
+    let mut values: Vec<f64> = Vec::new();
+    for chunk in reader {
+        let chunk = chunk.unwrap();
+        times.extend(
+            chunk.data[&expected.variables[0].name]
+                .to_real()
+                .unwrap()
+                .iter(),
+        );
+        values.extend(chunk.data[&name].to_real().unwrap().iter());
+    }
+    assert_eq!(times, scale.to_vec());
+    assert_eq!(values, signal.to_vec());
+}
+
+#[test]
+fn test_error_offset_past_4gib() {
+    let file = SparseFile::new("large_truncated.tr0");
+    // Cut the file inside the first real block
+    let cut = file.data_offset + 16 + 8;
+    std::fs::OpenOptions::new()
+        .write(true)
+        .open(&file.path)
+        .unwrap()
+        .set_len(cut)
+        .unwrap();
+
+    let err = LazyWaveform::open(file.path()).err().unwrap();
+    match err.root_cause() {
+        WaveformError::TruncatedBlock {
+            offset, available, ..
+        } => {
+            assert_eq!(*offset, file.data_offset + 16);
+            assert_eq!(*available, 8);
+        }
+        other => panic!("expected TruncatedBlock, got {other}"),
+    }
+    assert_eq!(err.offset(), Some(file.data_offset + 16));
+}
@@ -0,0 +1,214 @@
+//! Parser limits and malformed input
+//!
+//! Every test here must end in an `Err`, never a panic or a runaway
+//! allocation. Inputs start from a valid synthetic file and are then
+//! corrupted or checked against tight limits.
+
+use hspice_core::testing::SyntheticWaveform;
+use hspice_core::{read_bytes, read_raw_bytes, ParserLimits, PostVersion, WaveformError};
+
+/// Offset of the header text inside a synthetic file (after the block head)
+const HEADER_OFFSET: usize = 16;
+
+fn small_file() -> Vec<u8> {
+    SyntheticWaveform::new()
+        .signals(3)
+        .points(20)
+        .block_size(9)
+        .to_tr0_bytes()
+}
+
+/// Overwrite a fixed-width header field
+fn patch_header(data: &mut [u8], start: usize, value: &str) {
+    let field = &mut data[HEADER_OFFSET + start..HEADER_OFFSET + start + value.len()];
+    field.copy_from_slice(value.as_bytes());
+}
+
+fn limit_name(err: WaveformError) -> &'static str {
+    match err.root_cause() {
+        WaveformError::LimitExceeded { what, .. } => what,
+        other => panic!("expected LimitExceeded, got {other}"),
+    }
+}
+
+// =============================================================================
+// Test: Limits
+// =============================================================================
+
+#[test]
+fn test_valid_file_within_limits() {
+    let synth = SyntheticWaveform::new().signals(3).points(20);
+    let result = read_bytes(&synth.to_tr0_bytes(), &ParserLimits::untrusted()).unwrap();
+    assert_eq!(result.var_names(), synth.expected().var_names());
+    assert_eq!(result.len(), 20);
+}
+
+#[test]
+fn test_signal_limit() {
+    let limits = ParserLimits {
+        max_signals: 3,
+        ..ParserLimits::untrusted()
+    };
+    let err = read_bytes(&small_file(), &limits).unwrap_err();
+    assert_eq!(limit_name(err), "signals");
+}
+
+#[test]
+fn test_point_limit() {
+    let limits = ParserLimits {
+        max_points: 19,
+        ..ParserLimits::untrusted()
+    };
+    let err = read_bytes(&small_file(), &limits).unwrap_err();
+    assert_eq!(limit_name(err), "data values");
+
+    let limits = ParserLimits {
+        max_points: 20,
+        ..ParserLimits::untrusted()
+    };
+    assert!(read_bytes(&small_file(), &limits).is_ok());
+}
+
+#[test]
+fn test_memory_limit_spans_sweep_tables() {
+    // Each table fits on its own, all three together do not
+    let data = SyntheticWaveform::new()
+        .signals(1)
+        .points(10)
+        .sweep("temp", vec![0.0, 1.0, 2.0])
+        .to_tr0_bytes();
+    let limits = ParserLimits {
+        max_memory: 8 * 50,
+        ..ParserLimits::untrusted()
+    };
+    let err = read_bytes(&data, &limits).unwrap_err();
+    assert_eq!(limit_name(err), "data values");
+}
+
+#[test]
+fn test_sweep_table_limit() {
+    let data = SyntheticWaveform::new()
+        .signals(1)
+        .points(5)
+        .sweep("temp", vec![0.0, 1.0, 2.0])
+        .to_tr0_bytes();
+    let limits = ParserLimits {
+        max_tables: 2,
+        ..ParserLimits::untrusted()
+    };
+    let err = read_bytes(&data, &limits).unwrap_err();
+    assert_eq!(limit_name(err), "sweep tables");
+}
+
+// =============================================================================
+// Test: Malformed Headers
+// =============================================================================
+
+#[test]
+fn test_negative_counts_rejected() {
+    let mut data = small_file();
+    patch_header(&mut data, 4, "  -1");
+    assert!(matches!(
+        read_bytes(&data, &ParserLimits::unlimited())
+            .unwrap_err()
+            .root_cause(),
+        WaveformError::InvalidVectorCount { probes: -1, .. }
+    ));
+
+    let mut data = small_file();
+    patch_header(&mut data, 0, "   0");
+    assert!(matches!(
+        read_bytes(&data, &ParserLimits::unlimited())
+            .unwrap_err()
+            .root_cause(),
+        WaveformError::InvalidVectorCount { variables: 0, .. }
+    ));
+}
+
+#[test]
+fn test_huge_sweep_count_rejected() {
+    let synth = SyntheticWaveform::new()
+        .signals(1)
+        .points(5)
+        .sweep("temp", vec![0.0])
+        .version(PostVersion::V9601);
+    let mut data = synth.to_tr0_bytes();
+    patch_header(&mut data, 176, "2000000000");
+
+    // Unlimited: runs out of data instead of allocating two billion tables
+    assert!(read_bytes(&data, &ParserLimits::unlimited()).is_err());
+    let err = read_bytes(&data, &ParserLimits::untrusted()).unwrap_err();
+    assert_eq!(limit_name(err), "sweep tables");
+}
+
+#[test]
+fn test_huge_block_size_rejected() {
+    let mut data = small_file();
+    // Data starts right after the header block; claim a 2 GB payload
+    let header_len = i32::from_le_bytes(data[12..16].try_into().unwrap()) as usize;
+    let data_block = HEADER_OFFSET + header_len + 4;
+    data[data_block + 12..data_block + 16].copy_from_slice(&i32::MAX.to_le_bytes());
+    assert!(read_bytes(&data, &ParserLimits::unlimited()).is_err());
+}
+
+#[test]
+fn test_truncated_files_never_panic() {
+    let data = small_file();
+    for len in 0..data.len() {
+        let _ = read_bytes(&data[..len], &ParserLimits::untrusted());
+    }
+}
+
+#[test]
+fn test_byte_flips_never_panic() {
+    let data = small_file();
+    for i in 0..data.len() {
+        for flip in [0x01, 0x80, 0xff] {
+            let mut corrupted = data.clone();
+            corrupted[i] ^= flip;
+            let _ = read_bytes(&corrupted, &ParserLimits::untrusted());
+        }
+    }
+}
+
+// =============================================================================
+// Test: SPICE3 Raw
+// =============================================================================
+
+#[test]
+fn test_raw_within_limits() {
+    let synth = SyntheticWaveform::new().signals(2).points(10);
+    let result = read_raw_bytes(&synth.to_raw_bytes(), &ParserLimits::untrusted()).unwrap();
+    assert_eq!(result.len(), 10);
+}
+
+#[test]
+fn test_raw_point_limit() {
+    let synth = SyntheticWaveform::new().signals(2).points(10);
+    let limits = ParserLimits {
+        max_points: 9,
+        ..ParserLimits::untrusted()
+    };
+    let err = read_raw_bytes(&synth.to_raw_bytes(), &limits).unwrap_err();
+    assert_eq!(limit_name(err), "points");
+}
+
+#[test]
+fn test_raw_huge_point_count_rejected() {
+    let raw = b"Title: t\nNo. Variables: 1\nNo. Points: 999999999999\nVariables:\n\t0\ttime\ttime\nBinary:\n";
+    assert!(matches!(
+        read_raw_bytes(raw, &ParserLimits::unlimited()),
+        Err(WaveformError::DataSizeMismatch { available: 0, .. })
+    ));
+
+    let raw = b"Title: t\nNo. Variables: 99999999999\nNo. Points: 0\nVariables:\nValues:\n";
+    assert!(read_raw_bytes(raw, &ParserLimits::unlimited()).is_err());
+}
+
+#[test]
+fn test_raw_truncated_never_panics() {
+    let data = SyntheticWaveform::new().signals(2).points(5).to_raw_bytes();
+    for len in 0..data.len() {
+        let _ = read_raw_bytes(&data[..len], &ParserLimits::untrusted());
+    }
+}
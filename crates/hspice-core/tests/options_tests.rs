@@ -0,0 +1,221 @@
+//! Read options: overriding detection to recover damaged or foreign files
+//!
+//! Each test damages a valid synthetic file so that the default read fails
+//! or loses data, then checks that the matching `ReadOptions` recovers it.
+
+use hspice_core::testing::SyntheticWaveform;
+use hspice_core::{
+    read_bytes, read_bytes_with_options, Endian, HspiceStreamReader, NonMonotonic, ParserLimits,
+    PostVersion, ReadOptions, TextEncoding, WaveformError,
+};
+
+/// Offset of the header text inside a synthetic file (after the block head)
+const HEADER_OFFSET: usize = 16;
+
+fn synth(endian: Endian) -> SyntheticWaveform {
+    SyntheticWaveform::new()
+        .signals(2)
+        .points(10)
+        .block_size(8)
+        .endian(endian)
+}
+
+/// Offset of the first data block (just past the header block trailer)
+fn data_start(data: &[u8], endian: Endian) -> usize {
+    let size: [u8; 4] = data[12..16].try_into().unwrap();
+    let header_bytes = match endian {
+        Endian::Little => u32::from_le_bytes(size),
+        Endian::Big => u32::from_be_bytes(size),
+    } as usize;
+    HEADER_OFFSET + header_bytes + 4
+}
+
+#[test]
+fn test_lenient_trailer() {
+    let mut data = synth(Endian::Little).to_tr0_bytes();
+    let trailer = data_start(&data, Endian::Little) - 4;
+    data[trailer] ^= 0x01;
+    assert!(matches!(
+        read_bytes(&data, &ParserLimits::unlimited())
+            .unwrap_err()
+            .root_cause(),
+        WaveformError::TrailerMismatch { .. }
+    ));
+
+    let options = ReadOptions::new().lenient(true);
+    let result = read_bytes_with_options(&data, &options).unwrap();
+    assert_eq!(result.len(), 10);
+
+    let stream = HspiceStreamReader::from_bytes_with_options(data, 4, &options).unwrap();
+    let points: usize = stream.map(|chunk| chunk.unwrap().data["TIME"].len()).sum();
+    assert_eq!(points, 10);
+}
+
+#[test]
+fn test_forced_endian_damaged_marker() {
+    let synth = synth(Endian::Big);
+    let mut data = synth.to_tr0_bytes();
+    // Damage the byte order marker of the first data block
+    let start = data_start(&data, Endian::Big);
+    data[start..start + 4].copy_from_slice(&[0xff; 4]);
+
+    let default = read_bytes(&data, &ParserLimits::unlimited()).unwrap();
+    assert!(default.len() < 10);
+
+    let options = ReadOptions::new().endian(Endian::Big).lenient(true);
+    let result = read_bytes_with_options(&data, &options).unwrap();
+    assert_eq!(result.len(), 10);
+    assert_eq!(
+        format!("{:?}", result.tables[0].vectors[1]),
+        format!("{:?}", synth.expected().tables[0].vectors[1])
+    );
+
+    // Forcing the wrong byte order fails instead of guessing
+    let wrong = ReadOptions::new().endian(Endian::Little);
+    assert!(read_bytes_with_options(&data, &wrong).is_err());
+}
+
+#[test]
+fn test_post_version_and_encoding() {
+    let mut data = synth(Endian::Little)
+        .version(PostVersion::V2001)
+        .title("gain 50 uA")
+        .to_tr0_bytes();
+    // Unknown version string, and a Latin-1 micro sign in the title
+    data[HEADER_OFFSET + 20..HEADER_OFFSET + 24].copy_from_slice(b"2099");
+    let title = HEADER_OFFSET + 24 + 8;
+    data[title] = 0xb5;
+
+    assert!(read_bytes(&data, &ParserLimits::unlimited()).is_err());
+
+    let options = ReadOptions::new()
+        .post_version(PostVersion::V2001)
+        .encoding(TextEncoding::Latin1);
+    let result = read_bytes_with_options(&data, &options).unwrap();
+    assert_eq!(result.len(), 10);
+    assert_eq!(result.title, "gain 50 \u{b5}A");
+
+    let limits = ReadOptions::from(ParserLimits {
+        max_signals: 2,
+        ..ParserLimits::untrusted()
+    })
+    .post_version(PostVersion::V2001);
+    assert!(read_bytes_with_options(&data, &limits).is_err());
+}
+
+/// First `rows` values of every vector of a table of `synth`
+fn expected_rows(synth: &SyntheticWaveform, table: usize, rows: usize) -> Vec<Vec<f64>> {
+    synth.expected().tables[table]
+        .vectors
+        .iter()
+        .map(|v| (0..rows).map(|i| v.real_at(i).unwrap()).collect())
+        .collect()
+}
+
+fn rows(result: &hspice_core::WaveformResult, table: usize) -> Vec<Vec<f64>> {
+    result.tables[table]
+        .vectors
+        .iter()
+        .map(|v| (0..v.len()).map(|i| v.real_at(i).unwrap()).collect())
+        .collect()
+}
+
+#[test]
+fn test_recover_cut_off_block() {
+    // 3 columns of f32 in blocks of 8 values; cut 10 bytes into the 3rd block
+    let synth = synth(Endian::Little);
+    let data = synth.to_tr0_bytes();
+    let cut = data_start(&data, Endian::Little) + 2 * (16 + 32 + 4) + 16 + 10;
+    let data = &data[..cut];
+    assert!(read_bytes(data, &ParserLimits::unlimited()).is_err());
+
+    let options = ReadOptions::new().recover(true);
+    let result = read_bytes_with_options(data, &options).unwrap();
+    assert!(result.truncated);
+    // 16 + 2 values read: 6 complete rows
+    assert_eq!(result.len(), 6);
+    assert_eq!(rows(&result, 0), expected_rows(&synth, 0, 6));
+
+    // A complete file reads the same with or without recovery
+    let full = read_bytes_with_options(&synth.to_tr0_bytes(), &options).unwrap();
+    assert!(!full.truncated);
+    assert_eq!(full.len(), 10);
+}
+
+#[test]
+fn test_recover_missing_blocks() {
+    // Crashed between blocks: the end marker block never got written
+    let synth = synth(Endian::Big).version(PostVersion::V2001);
+    let data = synth.to_tr0_bytes();
+    let cut = data_start(&data, Endian::Big) + 3 * (16 + 64 + 4);
+    let result = read_bytes_with_options(&data[..cut], &ReadOptions::new().recover(true)).unwrap();
+    assert!(result.truncated);
+    assert_eq!(result.len(), 8);
+    assert_eq!(rows(&result, 0), expected_rows(&synth, 0, 8));
+}
+
+#[test]
+fn test_recover_sweep() {
+    let synth = synth(Endian::Little).sweep("temp", vec![25.0, 50.0, 75.0]);
+    let data = synth.to_tr0_bytes();
+    // Each table: sweep value, 30 values and the end marker in 4 blocks
+    let table_bytes = 3 * (16 + 32 + 4) + (16 + 32 + 4);
+    let cut = data_start(&data, Endian::Little) + table_bytes + 16 + 20;
+    let data = &data[..cut];
+    assert!(read_bytes(data, &ParserLimits::unlimited()).is_err());
+
+    let result = read_bytes_with_options(data, &ReadOptions::new().recover(true)).unwrap();
+    assert!(result.truncated);
+    assert_eq!(result.tables.len(), 2);
+    assert_eq!(result.tables[1].sweep_value, Some(50.0));
+    assert_eq!(rows(&result, 0), expected_rows(&synth, 0, 10));
+    assert_eq!(rows(&result, 1), expected_rows(&synth, 1, 1));
+}
+
+#[test]
+fn test_buffered_decode_matches() {
+    let synths = [
+        synth(Endian::Little),
+        synth(Endian::Big).version(PostVersion::V2001),
+        synth(Endian::Little).complex(true),
+        synth(Endian::Little).sweep("temp", vec![25.0, 50.0, 75.0]),
+    ];
+    for synth in synths {
+        let data = synth.to_tr0_bytes();
+        // Whole, and cut inside the last data block
+        for data in [&data[..], &data[..data.len() - 10]] {
+            let read = |buffered| {
+                let options = ReadOptions::new()
+                    .recover(true)
+                    .clean_non_monotonic(NonMonotonic::KeepLast)
+                    .buffered_decode(buffered);
+                let result = read_bytes_with_options(data, &options).unwrap();
+                (result.truncated, format!("{:?}", result.tables))
+            };
+            assert_eq!(read(false), read(true));
+        }
+    }
+}
+
+#[test]
+fn test_store_f32() {
+    let synth = synth(Endian::Little).points(200);
+    let data = synth.to_tr0_bytes();
+    let options = ReadOptions::new().store_f32(true);
+    let narrow = read_bytes_with_options(&data, &options).unwrap();
+    let wide = read_bytes_with_options(&data, &ReadOptions::new()).unwrap();
+
+    let vectors = &narrow.tables[0].vectors;
+    assert!(vectors[0].as_real().is_some());
+    assert!(vectors[1..].iter().all(|v| v.is_real32()));
+    assert_eq!(rows(&narrow, 0), rows(&wide, 0));
+    assert_eq!(
+        format!("{:?}", narrow.stats()),
+        format!("{:?}", wide.stats())
+    );
+
+    // Float64 files keep full precision
+    let data = synth.version(PostVersion::V2001).to_tr0_bytes();
+    let result = read_bytes_with_options(&data, &options).unwrap();
+    assert!(!result.tables[0].vectors.iter().any(|v| v.is_real32()));
+}
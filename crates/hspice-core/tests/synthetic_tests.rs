@@ -0,0 +1,389 @@
+//! Hermetic tests driven by the synthetic file generator
+//!
+//! These cover the same ground as integration_tests.rs without depending on
+//! the files in `example/`:
+//! - All post versions and both byte orders
+//! - Real, complex and swept data
+//! - Rows straddling block boundaries
+//! - Streaming and SPICE3 raw round trips
+//! - Cleanup of backtracked time points
+
+use hspice_core::testing::{temp_path, SyntheticWaveform};
+use hspice_core::{
+    parse_header_only, read, read_alters, read_alters_with_options, read_and_convert,
+    read_bytes_with_options, read_raw, read_stream_chunked, read_with_options, read_with_progress,
+    AnalysisType, Endian, HspiceStreamReader, NonMonotonic, PostVersion, Progress, ReadOptions,
+    VectorData, WaveformError, WaveformResult,
+};
+
+// =============================================================================
+// Test helpers
+// =============================================================================
+
+/// Write the synthetic file, parse it, and remove it again
+fn parse_synthetic(synth: &SyntheticWaveform, name: &str) -> WaveformResult {
+    let path = temp_path(name);
+    synth.write_tr0(&path).unwrap();
+    let result = read(path.to_str().unwrap());
+    let _ = std::fs::remove_file(&path);
+    result.unwrap()
+}
+
+fn assert_same_data(actual: &WaveformResult, expected: &WaveformResult) {
+    assert_eq!(actual.var_names(), expected.var_names());
+    assert_eq!(actual.tables.len(), expected.tables.len());
+    for (a, e) in actual.tables.iter().zip(&expected.tables) {
+        assert_eq!(a.sweep_value, e.sweep_value);
+        for (va, ve) in a.vectors.iter().zip(&e.vectors) {
+            match (va, ve) {
+                (VectorData::Real(x), VectorData::Real(y)) => assert_eq!(x, y),
+                (VectorData::Complex(x), VectorData::Complex(y)) => assert_eq!(x, y),
+                (
+                    VectorData::Stepped { len, steps },
+                    VectorData::Stepped {
+                        len: len_e,
+                        steps: steps_e,
+                    },
+                ) => assert_eq!((len, steps), (len_e, steps_e)),
+                _ => panic!("vector kind mismatch"),
+            }
+        }
+    }
+}
+
+// =============================================================================
+// Test: Format Variants
+// =============================================================================
+
+#[test]
+fn test_all_versions_and_endians() {
+    for version in [PostVersion::V9007, PostVersion::V9601, PostVersion::V2001] {
+        for endian in [Endian::Little, Endian::Big] {
+            let synth = SyntheticWaveform::new()
+                .signals(4)
+                .points(50)
+                .version(version)
+                .endian(endian);
+            let name = format!("fmt_{:?}_{:?}.tr0", version, endian);
+            let result = parse_synthetic(&synth, &name);
+
+            assert_eq!(result.analysis, AnalysisType::Transient);
+            assert_eq!(result.title, "synthetic");
+            assert_same_data(&result, &synth.expected());
+        }
+    }
+}
+
+#[test]
+fn test_9007_is_reported() {
+    let synth = SyntheticWaveform::new()
+        .signals(2)
+        .points(20)
+        .block_size(5)
+        .version(PostVersion::V9007);
+    let path = temp_path("legacy_9007.tr0");
+    synth.write_tr0(&path).unwrap();
+    let reader = read_stream_chunked(path.to_str().unwrap(), 8).unwrap();
+    let version = reader.metadata().post_version;
+    let rows: usize = reader
+        .map(|chunk| chunk.unwrap().data.values().next().unwrap().len())
+        .sum();
+    std::fs::remove_file(&path).ok();
+
+    assert_eq!(version, PostVersion::V9007);
+    assert_eq!(version.value_size(), 4);
+    assert_eq!(version.as_str(), "9007");
+    assert_eq!(rows, 20);
+}
+
+#[test]
+fn test_rows_straddle_blocks() {
+    // 7 values per block never lines up with 4-column rows
+    let synth = SyntheticWaveform::new().signals(3).points(40).block_size(7);
+    let result = parse_synthetic(&synth, "straddle.tr0");
+    assert_same_data(&result, &synth.expected());
+}
+
+#[test]
+fn test_complex_data() {
+    let synth = SyntheticWaveform::new().signals(2).points(30).complex(true);
+    let result = parse_synthetic(&synth, "complex.ac0");
+
+    assert_eq!(result.analysis, AnalysisType::AC);
+    assert_eq!(result.scale_name(), "HERTZ");
+    assert!(result.tables[0].vectors[1].is_complex());
+    assert_same_data(&result, &synth.expected());
+}
+
+#[test]
+fn test_header_only() {
+    let bytes = SyntheticWaveform::new()
+        .signals(2)
+        .points(30)
+        .complex(true)
+        .to_tr0_bytes();
+    let (meta, data_start) = parse_header_only(&bytes).unwrap();
+
+    assert!(meta.is_complex());
+    assert_eq!(meta.analysis(), AnalysisType::AC);
+    assert_eq!(meta.num_columns(), 5);
+    let estimate = meta.estimated_rows(bytes.len(), data_start);
+    assert!((30..40).contains(&estimate), "{}", estimate);
+}
+
+#[test]
+fn test_sweep_tables() {
+    let synth = SyntheticWaveform::new()
+        .signals(2)
+        .points(20)
+        .sweep("temp", vec![-40.0, 25.0, 125.0])
+        .block_size(16);
+    let result = parse_synthetic(&synth, "sweep.tr0");
+
+    assert!(result.has_sweep());
+    assert_eq!(result.sweep_param.as_deref(), Some("temp"));
+    assert_eq!(result.num_sweeps(), 3);
+    assert_same_data(&result, &synth.expected());
+}
+
+#[test]
+fn test_alter_runs() {
+    let first = SyntheticWaveform::new()
+        .title("run 1")
+        .signals(2)
+        .points(30);
+    let second = SyntheticWaveform::new()
+        .title("run 2")
+        .signal_names(&["a", "b", "c"])
+        .points(12)
+        .sweep("temp", vec![25.0, 85.0]);
+    let mut bytes = first.to_tr0_bytes();
+    bytes.extend(second.to_tr0_bytes());
+    let path = temp_path("alters.tr0");
+    std::fs::write(&path, &bytes).unwrap();
+    let filename = path.to_str().unwrap();
+
+    let runs = read_alters(filename).unwrap();
+    assert_eq!(runs.len(), 2);
+    assert_eq!(runs[1].title, "run 2");
+    assert_same_data(&runs[0], &first.expected());
+    assert_same_data(&runs[1], &second.expected());
+    // A plain read still stops after the first run
+    assert_same_data(&read(filename).unwrap(), &first.expected());
+
+    // A cut-off last run is an error, or the last run when recovering
+    std::fs::write(&path, &bytes[..bytes.len() - 100]).unwrap();
+    let err = read_alters(filename).unwrap_err();
+    assert!(err.to_string().contains("alter run 2"), "{}", err);
+    let runs = read_alters_with_options(filename, &ReadOptions::new().recover(true)).unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(runs.len(), 2);
+    assert!(!runs[0].truncated && runs[1].truncated);
+}
+
+#[test]
+fn test_signal_names_normalized() {
+    let synth = SyntheticWaveform::new().signal_names(&["v(OUT)", "i(vdd)"]);
+    let result = parse_synthetic(&synth, "names.tr0");
+    assert_eq!(result.var_names(), vec!["TIME", "out", "i(vdd)"]);
+}
+
+#[test]
+fn test_empty_signal_set() {
+    let synth = SyntheticWaveform::new().signals(0).points(10);
+    let result = parse_synthetic(&synth, "scale_only.tr0");
+    assert_eq!(result.num_vars(), 1);
+    assert_same_data(&result, &synth.expected());
+}
+
+#[test]
+fn test_constant_net_reads_as_real() {
+    // One hold over every point: the signal never changes
+    let synth = SyntheticWaveform::new().signals(2).points(256).hold(256);
+    let result = parse_synthetic(&synth, "constant.tr0");
+    assert_same_data(&result, &synth.expected());
+
+    let net = result.tables[0].vectors[1].as_real().unwrap();
+    assert_eq!(net.len(), 256);
+    assert!(net.iter().all(|&v| v == net[0]));
+}
+
+#[test]
+fn test_rarely_changing_signals_are_stepped() {
+    let synth = SyntheticWaveform::new().signals(2).points(256).hold(64);
+    let options = ReadOptions::new().compact_steps(true);
+    let result = read_bytes_with_options(&synth.to_tr0_bytes(), &options).unwrap();
+    let expected = synth.expected();
+
+    let table = &result.tables[0];
+    assert!(!table.vectors[0].is_stepped(), "scale is never stepped");
+    match &table.vectors[1] {
+        VectorData::Stepped { len, steps } => {
+            assert_eq!(*len, 256);
+            assert_eq!(steps.len(), 4);
+            assert_eq!(steps[1].0, 64);
+        }
+        other => panic!("expected stepped data, got {:?}", other),
+    }
+
+    let expanded = table.vectors[1].to_real().unwrap();
+    assert_eq!(expanded, expected.tables[0].vectors[1].to_real().unwrap());
+    assert_eq!(table.vectors[1].real_at(100), Some(expanded[100]));
+    assert_eq!(expanded[63], expanded[0]);
+    assert_ne!(expanded[64], expanded[0]);
+
+    // Dense signals stay plain
+    let dense = SyntheticWaveform::new().points(256).to_tr0_bytes();
+    let dense = read_bytes_with_options(&dense, &options).unwrap();
+    assert!(dense.tables[0].vectors[1].as_real().is_some());
+}
+
+#[test]
+fn test_held_signals_survive_raw_roundtrip() {
+    let synth = SyntheticWaveform::new().signals(1).points(128).hold(32);
+    let path = temp_path("stepped.raw");
+    synth.write_raw(&path).unwrap();
+    let result = read_raw(path.to_str().unwrap());
+    let _ = std::fs::remove_file(&path);
+
+    let result = result.unwrap();
+    assert_same_data(&result, &synth.expected());
+}
+
+// =============================================================================
+// Test: Streaming API
+// =============================================================================
+
+#[test]
+fn test_stream_matches_full_read() {
+    let synth = SyntheticWaveform::new()
+        .signals(3)
+        .points(500)
+        .block_size(33);
+    let path = temp_path("stream.tr0");
+    synth.write_tr0(&path).unwrap();
+
+    let expected = synth.expected();
+    let mut streamed: Vec<f64> = Vec::new();
+    for chunk in read_stream_chunked(&path, 64).unwrap() {
+        let chunk = chunk.unwrap();
+        if let Some(VectorData::Real(v)) = chunk.data.get("n1") {
+            streamed.extend(v);
+        }
+    }
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(
+        expected.get("n1").and_then(|v| v.as_real()),
+        Some(&streamed)
+    );
+}
+
+#[test]
+fn test_non_monotonic_cleanup() {
+    let synth = SyntheticWaveform::new()
+        .points(40)
+        .block_size(25)
+        .version(PostVersion::V2001);
+    let expected = synth.expected();
+    let time = expected.tables[0].vectors[0].to_real().unwrap();
+
+    // Row 20 steps back to the time of row 15
+    let mut bytes = synth.to_tr0_bytes();
+    let needle = time[20].to_le_bytes();
+    let at = bytes.windows(8).position(|w| w == needle).unwrap();
+    bytes[at..at + 8].copy_from_slice(&time[15].to_le_bytes());
+    let path = temp_path("non_monotonic.tr0");
+    std::fs::write(&path, &bytes).unwrap();
+
+    let read_as = |mode| {
+        let options = ReadOptions::new().clean_non_monotonic(mode);
+        read_with_options(path.to_str().unwrap(), &options)
+    };
+    let scale =
+        |result: &WaveformResult| result.tables[0].vectors[0].to_real().unwrap().into_owned();
+    let kept = scale(&read_as(NonMonotonic::Keep).unwrap());
+    assert_eq!((kept.len(), kept[20]), (40, time[15]));
+    let dropped = scale(&read_as(NonMonotonic::Drop).unwrap());
+    assert_eq!(dropped.len(), 39);
+    assert_eq!(dropped[20], time[21]);
+    // Rows 15..20 are superseded by the backtracked row
+    let last = scale(&read_as(NonMonotonic::KeepLast).unwrap());
+    assert_eq!(last.len(), 35);
+    assert_eq!(last[15..17], [time[15], time[21]]);
+    let error = read_as(NonMonotonic::Error).unwrap_err();
+    assert!(matches!(
+        error.root_cause(),
+        WaveformError::NonMonotonicScale { row: 20, .. }
+    ));
+
+    let options = ReadOptions::new().clean_non_monotonic(NonMonotonic::Drop);
+    let streamed: usize = HspiceStreamReader::open_with_options(&path, 8, &options)
+        .unwrap()
+        .map(|chunk| chunk.unwrap().data["TIME"].len())
+        .sum();
+    assert_eq!(streamed, 39);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn test_read_progress() {
+    let synth = SyntheticWaveform::new()
+        .signals(2)
+        .points(400)
+        .block_size(32)
+        .sweep("temp", vec![25.0, 50.0]);
+    let path = temp_path("progress.tr0");
+    synth.write_tr0(&path).unwrap();
+    let size = std::fs::metadata(&path).unwrap().len();
+
+    let mut reports: Vec<Progress> = Vec::new();
+    let result = read_with_progress(path.to_str().unwrap(), &ReadOptions::default(), |p| {
+        reports.push(*p)
+    });
+    let _ = std::fs::remove_file(&path);
+
+    assert_same_data(&result.unwrap(), &synth.expected());
+    // 2 x 1202 values in blocks of 32: one report at the first block, then the final one
+    assert_eq!(reports.len(), 2);
+    assert_eq!(reports[0].blocks, 1);
+    assert_eq!(reports[0].rows, 10);
+    let last = reports[1];
+    assert_eq!((last.bytes, last.total_bytes), (size, size));
+    assert_eq!((last.blocks, last.rows), (76, 800));
+}
+
+// =============================================================================
+// Test: SPICE3 Raw
+// =============================================================================
+
+#[test]
+fn test_raw_roundtrip() {
+    let synth = SyntheticWaveform::new().signals(3).points(25);
+    let path = temp_path("roundtrip.raw");
+    synth.write_raw(&path).unwrap();
+    let result = read_raw(path.to_str().unwrap());
+    let _ = std::fs::remove_file(&path);
+
+    let result = result.unwrap();
+    assert_eq!(result.analysis, AnalysisType::Transient);
+    assert_same_data(&result, &synth.expected());
+}
+
+#[test]
+fn test_convert_synthetic() {
+    let synth = SyntheticWaveform::new()
+        .signals(2)
+        .points(10)
+        .version(PostVersion::V2001);
+    let input = temp_path("convert_in.tr0");
+    let output = temp_path("convert_out.raw");
+    synth.write_tr0(&input).unwrap();
+
+    read_and_convert(input.to_str().unwrap(), output.to_str().unwrap()).unwrap();
+    let result = read_raw(output.to_str().unwrap());
+    let _ = std::fs::remove_file(&input);
+    let _ = std::fs::remove_file(&output);
+
+    assert_same_data(&result.unwrap(), &synth.expected());
+}
@@ -0,0 +1,32 @@
+//! Generates the C header from the exported functions
+//!
+//! The header is written to `include/hspice.h` at the repository root (or
+//! to `OUT_DIR` when building outside the repository). Commit the result
+//! together with any change to the C API.
+
+use std::env;
+use std::path::PathBuf;
+
+fn main() {
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let include_dir = crate_dir.join("../../include");
+    let header = if include_dir.is_dir() {
+        include_dir.join("hspice.h")
+    } else {
+        PathBuf::from(env::var("OUT_DIR").unwrap()).join("hspice.h")
+    };
+
+    println!("cargo:rerun-if-changed=src");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml"))
+        .expect("cbindgen.toml is valid");
+    match cbindgen::generate_with_config(&crate_dir, config) {
+        // Only rewrites the file when the contents change
+        Ok(bindings) => {
+            bindings.write_to_file(header);
+        }
+        // Keep building with the committed header if the source does not parse
+        Err(e) => println!("cargo:warning=Could not generate C header: {}", e),
+    }
+}
@@ -2,13 +2,226 @@
 //!
 //! This module provides a C-compatible API for using the waveform parser
 //! from C, C++, and other languages that support C FFI.
+//!
+//! Every exported function accepts raw pointers from C callers; the shared
+//! safety contract is documented once in the C header rather than per function.
+//!
+//! The header (`include/hspice.h`) is generated from this file by `build.rs`
+//! with cbindgen, so `///` comments on exported items end up in it. Handles
+//! are opaque and constants are plain `#define`s; see [`waveform_abi_version`]
+//! for the compatibility rules.
+//!
+//! Both handle types may be used from any thread. A `CWaveformResult` is
+//! never modified after it is created, so any number of threads can read
+//! it at once; a `CWaveformStream` serializes its calls with a lock, so
+//! concurrent calls run one after another. Only freeing a handle must wait
+//! until no other thread is using it. Error state is per thread.
+
+#![allow(clippy::missing_safety_doc)]
 
+use hspice_core::dsp::{self, Window};
+use hspice_core::measure::{self, Edge};
 use hspice_core::{
-    read, read_raw, read_stream_chunked, DataChunk, HspiceStreamReader, VectorData, WaveformResult,
+    read, read_and_convert, read_bytes_with_options, read_raw, read_stream_chunked,
+    read_with_progress, DataChunk, HspiceStreamReader, Progress, ReadOptions, SignalPattern,
+    VectorData, WaveformError, WaveformResult,
 };
-use std::ffi::{c_char, c_double, c_int, CStr, CString};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::{c_char, c_double, c_int, c_void, CStr, CString};
+use std::fs::File;
+use std::io::{BufWriter, Write};
 use std::ptr;
-use std::sync::Once;
+use std::sync::{Mutex, MutexGuard, Once};
+
+// ============================================================================
+// ABI Version
+// ============================================================================
+
+/// ABI major version: bumped when an existing function, constant or enum
+/// value changes meaning or is removed
+pub const WAVEFORM_ABI_VERSION_MAJOR: u32 = 1;
+
+/// ABI minor version: bumped when functions or constants are added
+pub const WAVEFORM_ABI_VERSION_MINOR: u32 = 11;
+
+/// ABI patch version: bumped for fixes that do not change the interface
+pub const WAVEFORM_ABI_VERSION_PATCH: u32 = 0;
+
+/// Get the ABI version of the loaded library.
+///
+/// Encoded as (major << 16) | (minor << 8) | patch. A program built against
+/// this header can run with the loaded library if the majors match and the
+/// loaded minor is at least WAVEFORM_ABI_VERSION_MINOR:
+///
+///   unsigned v = waveform_abi_version();
+///   if ((v >> 16) != WAVEFORM_ABI_VERSION_MAJOR ||
+///       ((v >> 8) & 0xff) < WAVEFORM_ABI_VERSION_MINOR) { ... }
+#[no_mangle]
+pub extern "C" fn waveform_abi_version() -> u32 {
+    (WAVEFORM_ABI_VERSION_MAJOR << 16)
+        | (WAVEFORM_ABI_VERSION_MINOR << 8)
+        | WAVEFORM_ABI_VERSION_PATCH
+}
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Analysis type constants (waveform_get_analysis_type)
+pub const WAVEFORM_ANALYSIS_TRANSIENT: c_int = 0;
+pub const WAVEFORM_ANALYSIS_AC: c_int = 1;
+pub const WAVEFORM_ANALYSIS_DC: c_int = 2;
+pub const WAVEFORM_ANALYSIS_OPERATING: c_int = 3;
+pub const WAVEFORM_ANALYSIS_NOISE: c_int = 4;
+pub const WAVEFORM_ANALYSIS_UNKNOWN: c_int = -1;
+
+/// Variable type constants (waveform_get_var_type)
+pub const WAVEFORM_VAR_TIME: c_int = 0;
+pub const WAVEFORM_VAR_FREQUENCY: c_int = 1;
+pub const WAVEFORM_VAR_VOLTAGE: c_int = 2;
+pub const WAVEFORM_VAR_CURRENT: c_int = 3;
+pub const WAVEFORM_VAR_POWER: c_int = 4;
+pub const WAVEFORM_VAR_PARAMETER: c_int = 5;
+pub const WAVEFORM_VAR_INTERNAL: c_int = 6;
+pub const WAVEFORM_VAR_NOISE: c_int = 7;
+pub const WAVEFORM_VAR_UNKNOWN: c_int = -1;
+
+/// Crossing edge constants (waveform_crossings)
+pub const WAVEFORM_EDGE_RISING: c_int = 0;
+pub const WAVEFORM_EDGE_FALLING: c_int = 1;
+pub const WAVEFORM_EDGE_EITHER: c_int = 2;
+
+/// FFT window constants (waveform_fft_magnitude, waveform_thd)
+pub const WAVEFORM_WINDOW_RECTANGULAR: c_int = 0;
+pub const WAVEFORM_WINDOW_HANN: c_int = 1;
+
+/// Every sweep table (WaveformCsvOptions.table_index)
+pub const WAVEFORM_ALL_TABLES: c_int = -1;
+
+// ============================================================================
+// Error Reporting
+// ============================================================================
+
+/// Error codes reported by waveform_last_error_code()
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaveformErrorCode {
+    Ok = 0,
+    NullPointer = 1,
+    InvalidArgument = 2,
+    InvalidUtf8 = 3,
+    IndexOutOfRange = 4,
+    TypeMismatch = 5,
+    NotFound = 6,
+    NoResult = 7,
+    Io = 8,
+    Format = 9,
+    LimitExceeded = 10,
+    Parse = 11,
+}
+
+impl From<&WaveformError> for WaveformErrorCode {
+    fn from(err: &WaveformError) -> Self {
+        match err.root_cause() {
+            WaveformError::IoError(_) | WaveformError::WriteFailed { .. } => WaveformErrorCode::Io,
+            WaveformError::LimitExceeded { .. } => WaveformErrorCode::LimitExceeded,
+            WaveformError::ParseError(_) => WaveformErrorCode::Parse,
+            _ => WaveformErrorCode::Format,
+        }
+    }
+}
+
+struct LastError {
+    code: WaveformErrorCode,
+    message: CString,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<LastError>> = const { RefCell::new(None) };
+}
+
+/// Record an error for the calling thread and return `sentinel`
+fn fail<T>(code: WaveformErrorCode, message: impl Into<String>, sentinel: T) -> T {
+    let message = CString::new(message.into().replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = Some(LastError { code, message }));
+    sentinel
+}
+
+/// Record a core error for the calling thread and return `sentinel`
+fn fail_with<T>(context: &str, err: &WaveformError, sentinel: T) -> T {
+    tracing::error!("{} error: {:?}", context, err);
+    fail(err.into(), format!("{}: {}", context, err), sentinel)
+}
+
+/// Clear the calling thread's error and return `value`
+fn succeed<T>(value: T) -> T {
+    LAST_ERROR.with(|e| *e.borrow_mut() = None);
+    value
+}
+
+/// Borrow a C string argument, recording an error on NULL or invalid UTF-8
+unsafe fn str_arg<'a>(s: *const c_char, name: &str) -> Option<&'a str> {
+    if s.is_null() {
+        return fail(
+            WaveformErrorCode::NullPointer,
+            format!("{} is NULL", name),
+            None,
+        );
+    }
+    match CStr::from_ptr(s).to_str() {
+        Ok(s) => Some(s),
+        Err(_) => fail(
+            WaveformErrorCode::InvalidUtf8,
+            format!("{} is not valid UTF-8", name),
+            None,
+        ),
+    }
+}
+
+/// Borrow a handle argument, recording an error on NULL
+unsafe fn handle_arg<'a, T>(handle: *const T, name: &str) -> Option<&'a T> {
+    if handle.is_null() {
+        return fail(
+            WaveformErrorCode::NullPointer,
+            format!("{} is NULL", name),
+            None,
+        );
+    }
+    Some(&*handle)
+}
+
+/// Get the code of the last error on this thread.
+///
+/// @return WaveformErrorCode value, WAVEFORM_ERROR_CODE_OK if the last call
+///         succeeded
+#[no_mangle]
+pub extern "C" fn waveform_last_error_code() -> c_int {
+    LAST_ERROR.with(|e| {
+        e.borrow()
+            .as_ref()
+            .map_or(WaveformErrorCode::Ok, |e| e.code) as c_int
+    })
+}
+
+/// Get the message of the last error on this thread.
+///
+/// @return Null-terminated string, or NULL if the last call succeeded.
+///         Valid until the next waveform_* call on the same thread.
+#[no_mangle]
+pub extern "C" fn waveform_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|e| {
+        e.borrow()
+            .as_ref()
+            .map_or(ptr::null(), |e| e.message.as_ptr())
+    })
+}
+
+/// Clear the last error on this thread.
+#[no_mangle]
+pub extern "C" fn waveform_clear_error() {
+    succeed(())
+}
 
 // ============================================================================
 // Logging Initialization
@@ -16,31 +229,19 @@ use std::sync::Once;
 
 static LOGGING_INIT: Once = Once::new();
 
-/// Initialize logging with specified level.
-///
-/// Call this once before any other waveform functions to enable logging.
+/// Initialize the logging subsystem.
 ///
-/// # Arguments
-/// * `level` - Log level string: "trace", "debug", "info", "warn", "error"
+/// Call this once at application startup before using other functions.
+/// If not called, logging is disabled (only errors to stderr).
 ///
-/// # Returns
-/// * 0 on success
-/// * -1 if level string is null or invalid
+/// @param level Log level string: "trace", "debug", "info", "warn", "error"
+/// @return      0 on success, -1 if level string is null or invalid
 ///
-/// # Example (C)
-/// ```c
-/// waveform_init_logging("info");
-/// void* result = waveform_read("simulation.tr0", 0);
-/// ```
+/// @note Can only be called once; subsequent calls have no effect.
 #[no_mangle]
 pub unsafe extern "C" fn waveform_init_logging(level: *const c_char) -> c_int {
-    if level.is_null() {
+    let Some(level_str) = str_arg(level, "level") else {
         return -1;
-    }
-
-    let level_str = match CStr::from_ptr(level).to_str() {
-        Ok(s) => s,
-        Err(_) => return -1,
     };
 
     LOGGING_INIT.call_once(|| {
@@ -52,15 +253,18 @@ pub unsafe extern "C" fn waveform_init_logging(level: *const c_char) -> c_int {
             .init();
     });
 
-    0
+    succeed(0)
 }
 
 // ============================================================================
 // Opaque Types for C
 // ============================================================================
 
-/// Opaque handle to WaveformResult
-#[repr(C)]
+/// Stepped and single precision vectors expanded for
+/// waveform_get_real_data_ptr, by (table, var)
+type ExpandedVectors = Mutex<HashMap<(usize, usize), Box<[f64]>>>;
+
+/// Opaque handle to a parsed waveform file
 pub struct CWaveformResult {
     inner: Box<WaveformResult>,
     cached_title: CString,
@@ -68,62 +272,161 @@ pub struct CWaveformResult {
     cached_scale_name: CString,
     cached_sweep_param: Option<CString>,
     cached_var_names: Vec<CString>,
+    expanded: ExpandedVectors,
 }
 
+// C callers share handles between threads, so both must stay Send + Sync
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<CWaveformResult>();
+    assert_send_sync::<CWaveformStream>();
+};
+
 // ============================================================================
 // Result Creation and Destruction
 // ============================================================================
 
-/// Read a waveform file and return a result handle.
+/// Box `result` with its C strings cached
+fn into_handle(result: WaveformResult) -> *mut CWaveformResult {
+    let cached_title = CString::new(result.title.clone()).unwrap_or_default();
+    let cached_date = CString::new(result.date.clone()).unwrap_or_default();
+    let cached_scale_name = CString::new(result.scale_name().to_string()).unwrap_or_default();
+    let cached_sweep_param = result
+        .sweep_param
+        .as_ref()
+        .and_then(|s| CString::new(s.clone()).ok());
+    let cached_var_names: Vec<CString> = result
+        .variables
+        .iter()
+        .filter_map(|v| CString::new(v.name.clone()).ok())
+        .collect();
+
+    Box::into_raw(Box::new(CWaveformResult {
+        inner: Box::new(result),
+        cached_title,
+        cached_date,
+        cached_scale_name,
+        cached_sweep_param,
+        cached_var_names,
+        expanded: Mutex::default(),
+    }))
+}
+
+/// Read a waveform file.
 ///
-/// The debug parameter is deprecated and ignored. Use waveform_init_logging() instead.
+/// @param filename Path to the waveform file (.tr0, .ac0, .sw0)
+/// @param debug    Debug level (DEPRECATED: ignored, use waveform_init_logging)
+/// @return         Pointer to result on success, NULL on error
+///
+/// @note The caller must free the result using waveform_free().
 #[no_mangle]
 pub unsafe extern "C" fn waveform_read(
     filename: *const c_char,
     _debug: c_int,
 ) -> *mut CWaveformResult {
-    if filename.is_null() {
+    let Some(filename_cstr) = str_arg(filename, "filename") else {
         return ptr::null_mut();
+    };
+
+    match read(filename_cstr) {
+        Ok(result) => succeed(into_handle(result)),
+        Err(e) => fail_with("waveform_read", &e, ptr::null_mut()),
+    }
+}
+
+/// Borrow a caller byte buffer, recording an error for NULL
+unsafe fn bytes_arg<'a>(data: *const u8, len: usize) -> Option<&'a [u8]> {
+    if data.is_null() {
+        return fail(WaveformErrorCode::NullPointer, "data is NULL", None);
     }
+    Some(std::slice::from_raw_parts(data, len))
+}
 
-    let filename_cstr = match CStr::from_ptr(filename).to_str() {
-        Ok(s) => s,
-        Err(_) => return ptr::null_mut(),
+/// Parse an HSPICE file already in memory.
+///
+/// The buffer is only read during the call, so it may be freed as soon as
+/// this returns. There is no file name to take the analysis type from, so
+/// it comes from the header alone.
+///
+/// @param data Contents of an HSPICE file (.tr0, .ac0, .sw0)
+/// @param len  Size of data in bytes
+/// @return     Pointer to result on success, NULL on error
+///
+/// @note The caller must free the result using waveform_free().
+#[no_mangle]
+pub unsafe extern "C" fn waveform_read_from_memory(
+    data: *const u8,
+    len: usize,
+) -> *mut CWaveformResult {
+    let Some(bytes) = bytes_arg(data, len) else {
+        return ptr::null_mut();
     };
+    match read_bytes_with_options(bytes, &ReadOptions::default()) {
+        Ok(result) => succeed(into_handle(result)),
+        Err(e) => fail_with("waveform_read_from_memory", &e, ptr::null_mut()),
+    }
+}
 
-    match read(filename_cstr) {
-        Ok(result) => {
-            let cached_title = CString::new(result.title.clone()).unwrap_or_default();
-            let cached_date = CString::new(result.date.clone()).unwrap_or_default();
-            let cached_scale_name =
-                CString::new(result.scale_name().to_string()).unwrap_or_default();
-            let cached_sweep_param = result
-                .sweep_param
-                .as_ref()
-                .and_then(|s| CString::new(s.clone()).ok());
-            let cached_var_names: Vec<CString> = result
-                .variables
-                .iter()
-                .filter_map(|v| CString::new(v.name.clone()).ok())
-                .collect();
-
-            Box::into_raw(Box::new(CWaveformResult {
-                inner: Box::new(result),
-                cached_title,
-                cached_date,
-                cached_scale_name,
-                cached_sweep_param,
-                cached_var_names,
-            }))
-        }
-        Err(e) => {
-            tracing::error!("waveform_read error: {:?}", e);
-            ptr::null_mut()
+/// Progress of a read, passed to a WaveformProgressFn
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct WaveformProgress {
+    /// Bytes of the file read so far
+    pub bytes: u64,
+    /// File size in bytes
+    pub total_bytes: u64,
+    /// Data blocks read so far
+    pub blocks: u64,
+    /// Rows (points) read so far, over all sweep tables
+    pub rows: u64,
+}
+
+/// Progress callback: called with the progress and the user_data pointer
+/// given to waveform_read_with_progress()
+pub type WaveformProgressFn =
+    Option<unsafe extern "C" fn(progress: *const WaveformProgress, user_data: *mut c_void)>;
+
+/// Read a waveform file, reporting progress.
+///
+/// The callback is called about once per 1/1000 of the file and once more
+/// when the data is read, on the calling thread.
+///
+/// @param filename  Path to the waveform file (.tr0, .ac0, .sw0)
+/// @param callback  Progress callback, or NULL for none
+/// @param user_data Passed unchanged to the callback
+/// @return          Pointer to result on success, NULL on error
+///
+/// @note The caller must free the result using waveform_free().
+#[no_mangle]
+pub unsafe extern "C" fn waveform_read_with_progress(
+    filename: *const c_char,
+    callback: WaveformProgressFn,
+    user_data: *mut c_void,
+) -> *mut CWaveformResult {
+    let Some(filename_cstr) = str_arg(filename, "filename") else {
+        return ptr::null_mut();
+    };
+
+    let report = |p: &Progress| {
+        if let Some(callback) = callback {
+            let progress = WaveformProgress {
+                bytes: p.bytes,
+                total_bytes: p.total_bytes,
+                blocks: p.blocks as u64,
+                rows: p.rows as u64,
+            };
+            callback(&progress, user_data);
         }
+    };
+    match read_with_progress(filename_cstr, &ReadOptions::default(), report) {
+        Ok(result) => succeed(into_handle(result)),
+        Err(e) => fail_with("waveform_read_with_progress", &e, ptr::null_mut()),
     }
 }
 
 /// Free a waveform result handle.
+///
+/// @param result Pointer returned by waveform_read() or waveform_read_raw()
 #[no_mangle]
 pub unsafe extern "C" fn waveform_free(result: *mut CWaveformResult) {
     if !result.is_null() {
@@ -133,50 +436,73 @@ pub unsafe extern "C" fn waveform_free(result: *mut CWaveformResult) {
 
 /// Read a SPICE3/ngspice raw file (auto-detects binary/ASCII format).
 ///
-/// The debug parameter is deprecated and ignored. Use waveform_init_logging() instead.
+/// @param filename Path to the raw file (.raw)
+/// @param debug    Debug level (DEPRECATED: ignored, use waveform_init_logging)
+/// @return         Pointer to result on success, NULL on error
+///
+/// @note The caller must free the result using waveform_free().
 #[no_mangle]
 pub unsafe extern "C" fn waveform_read_raw(
     filename: *const c_char,
     _debug: c_int,
 ) -> *mut CWaveformResult {
-    if filename.is_null() {
+    let Some(filename_cstr) = str_arg(filename, "filename") else {
         return ptr::null_mut();
+    };
+
+    match read_raw(filename_cstr) {
+        Ok(result) => succeed(into_handle(result)),
+        Err(e) => fail_with("waveform_read_raw", &e, ptr::null_mut()),
     }
+}
 
-    let filename_cstr = match CStr::from_ptr(filename).to_str() {
-        Ok(s) => s,
-        Err(_) => return ptr::null_mut(),
+/// Select signals by pattern into a new result.
+///
+/// The new result holds the scale and every signal whose name matches
+/// pattern, in all tables. Patterns are globs ("v(x1.*)", "i(vdd?)") that
+/// ignore case, or regular expressions when prefixed with "re:". Bare node
+/// names also match their "v(...)" form.
+///
+/// @param result  Result handle
+/// @param pattern Glob or "re:" regex
+/// @return        New result handle, or NULL on error (invalid pattern, or
+///                no signal matches)
+///
+/// @note The caller must free the new result using waveform_free().
+#[no_mangle]
+pub unsafe extern "C" fn waveform_select(
+    result: *const CWaveformResult,
+    pattern: *const c_char,
+) -> *mut CWaveformResult {
+    let Some(r) = handle_arg(result, "result") else {
+        return ptr::null_mut();
+    };
+    let Some(pattern) = str_arg(pattern, "pattern") else {
+        return ptr::null_mut();
     };
+    match r.inner.select(pattern) {
+        Ok(selected) => succeed(into_handle(selected)),
+        Err(e) => fail_with("waveform_select", &e, ptr::null_mut()),
+    }
+}
 
-    match read_raw(filename_cstr) {
-        Ok(result) => {
-            let cached_title = CString::new(result.title.clone()).unwrap_or_default();
-            let cached_date = CString::new(result.date.clone()).unwrap_or_default();
-            let cached_scale_name =
-                CString::new(result.scale_name().to_string()).unwrap_or_default();
-            let cached_sweep_param = result
-                .sweep_param
-                .as_ref()
-                .and_then(|s| CString::new(s.clone()).ok());
-            let cached_var_names: Vec<CString> = result
-                .variables
-                .iter()
-                .filter_map(|v| CString::new(v.name.clone()).ok())
-                .collect();
-
-            Box::into_raw(Box::new(CWaveformResult {
-                inner: Box::new(result),
-                cached_title,
-                cached_date,
-                cached_scale_name,
-                cached_sweep_param,
-                cached_var_names,
-            }))
-        }
-        Err(e) => {
-            tracing::error!("waveform_read_raw error: {:?}", e);
-            ptr::null_mut()
-        }
+/// Copy a result into an independent handle.
+///
+/// The copy shares its signal values with result, so it is cheap, but the
+/// two handles are freed separately and in any order. A worker thread can
+/// hand a copy to another thread and keep or free its own.
+///
+/// @param result Result handle
+/// @return       New result handle, or NULL if result is NULL
+///
+/// @note The caller must free the new result using waveform_free().
+#[no_mangle]
+pub unsafe extern "C" fn waveform_result_clone(
+    result: *const CWaveformResult,
+) -> *mut CWaveformResult {
+    match handle_arg(result, "result") {
+        Some(r) => succeed(into_handle((*r.inner).clone())),
+        None => ptr::null_mut(),
     }
 }
 
@@ -184,199 +510,344 @@ pub unsafe extern "C" fn waveform_read_raw(
 // Metadata Accessors
 // ============================================================================
 
+/// Get the simulation title.
 #[no_mangle]
 pub unsafe extern "C" fn waveform_get_title(result: *const CWaveformResult) -> *const c_char {
-    if result.is_null() {
-        return ptr::null();
+    match handle_arg(result, "result") {
+        Some(r) => succeed(r.cached_title.as_ptr()),
+        None => ptr::null(),
     }
-    (*result).cached_title.as_ptr()
 }
 
+/// Get the simulation date.
 #[no_mangle]
 pub unsafe extern "C" fn waveform_get_date(result: *const CWaveformResult) -> *const c_char {
-    if result.is_null() {
-        return ptr::null();
+    match handle_arg(result, "result") {
+        Some(r) => succeed(r.cached_date.as_ptr()),
+        None => ptr::null(),
     }
-    (*result).cached_date.as_ptr()
 }
 
+/// Get the scale name (e.g., "TIME", "HERTZ").
 #[no_mangle]
 pub unsafe extern "C" fn waveform_get_scale_name(result: *const CWaveformResult) -> *const c_char {
-    if result.is_null() {
-        return ptr::null();
+    match handle_arg(result, "result") {
+        Some(r) => succeed(r.cached_scale_name.as_ptr()),
+        None => ptr::null(),
     }
-    (*result).cached_scale_name.as_ptr()
 }
 
+/// Get the analysis type (WAVEFORM_ANALYSIS_*).
 #[no_mangle]
 pub unsafe extern "C" fn waveform_get_analysis_type(result: *const CWaveformResult) -> c_int {
-    if result.is_null() {
+    let Some(r) = handle_arg(result, "result") else {
         return -1;
-    }
-    match (*result).inner.analysis {
-        hspice_core::AnalysisType::Transient => 0,
-        hspice_core::AnalysisType::AC => 1,
-        hspice_core::AnalysisType::DC => 2,
-        hspice_core::AnalysisType::Operating => 3,
-        hspice_core::AnalysisType::Noise => 4,
-        hspice_core::AnalysisType::Unknown => -1,
-    }
+    };
+    succeed(match r.inner.analysis {
+        hspice_core::AnalysisType::Transient => WAVEFORM_ANALYSIS_TRANSIENT,
+        hspice_core::AnalysisType::AC => WAVEFORM_ANALYSIS_AC,
+        hspice_core::AnalysisType::DC => WAVEFORM_ANALYSIS_DC,
+        hspice_core::AnalysisType::Operating => WAVEFORM_ANALYSIS_OPERATING,
+        hspice_core::AnalysisType::Noise => WAVEFORM_ANALYSIS_NOISE,
+        hspice_core::AnalysisType::Unknown => WAVEFORM_ANALYSIS_UNKNOWN,
+    })
+}
+
+/// Get the analysis type as a lowercase name.
+///
+/// @return "transient", "ac", "dc", "operating", "noise" or "unknown";
+///         a static string, or NULL if result is NULL
+#[no_mangle]
+pub unsafe extern "C" fn waveform_get_analysis_name(
+    result: *const CWaveformResult,
+) -> *const c_char {
+    let Some(r) = handle_arg(result, "result") else {
+        return ptr::null();
+    };
+    let name = match r.inner.analysis {
+        hspice_core::AnalysisType::Transient => c"transient",
+        hspice_core::AnalysisType::AC => c"ac",
+        hspice_core::AnalysisType::DC => c"dc",
+        hspice_core::AnalysisType::Operating => c"operating",
+        hspice_core::AnalysisType::Noise => c"noise",
+        hspice_core::AnalysisType::Unknown => c"unknown",
+    };
+    succeed(name.as_ptr())
 }
 
+/// Get the number of data tables (one per sweep point).
 #[no_mangle]
 pub unsafe extern "C" fn waveform_get_table_count(result: *const CWaveformResult) -> c_int {
-    if result.is_null() {
-        return 0;
+    match handle_arg(result, "result") {
+        Some(r) => succeed(r.inner.tables.len() as c_int),
+        None => 0,
     }
-    (*result).inner.tables.len() as c_int
 }
 
+/// Get the number of variables/signals.
 #[no_mangle]
 pub unsafe extern "C" fn waveform_get_var_count(result: *const CWaveformResult) -> c_int {
-    if result.is_null() {
-        return 0;
+    match handle_arg(result, "result") {
+        Some(r) => succeed(r.inner.variables.len() as c_int),
+        None => 0,
     }
-    (*result).inner.variables.len() as c_int
 }
 
+/// Get the number of data points in the first table.
 #[no_mangle]
 pub unsafe extern "C" fn waveform_get_point_count(result: *const CWaveformResult) -> c_int {
-    if result.is_null() {
-        return 0;
+    match handle_arg(result, "result") {
+        Some(r) => succeed(r.inner.len() as c_int),
+        None => 0,
     }
-    (*result).inner.len() as c_int
+}
+
+/// Get the number of points in one table.
+///
+/// @param result      Result handle
+/// @param table_index Table index (0-based)
+/// @return            Number of points, or -1 on error
+#[no_mangle]
+pub unsafe extern "C" fn waveform_get_table_point_count(
+    result: *const CWaveformResult,
+    table_index: c_int,
+) -> c_int {
+    let Some(r) = handle_arg(result, "result") else {
+        return -1;
+    };
+    let Some(idx) = index_arg(table_index, r.inner.tables.len(), "table") else {
+        return -1;
+    };
+    succeed(r.inner.tables[idx].len() as c_int)
 }
 
 // ============================================================================
 // Variable Accessors
 // ============================================================================
 
+/// Convert a C index, recording an error if it is negative or >= len
+fn index_arg(index: c_int, len: usize, what: &str) -> Option<usize> {
+    if index < 0 || index as usize >= len {
+        return fail(
+            WaveformErrorCode::IndexOutOfRange,
+            format!("{} index {} out of range (count {})", what, index, len),
+            None,
+        );
+    }
+    Some(index as usize)
+}
+
+/// Get variable name by index.
+///
+/// @param result Result handle
+/// @param index  Variable index (0-based)
+/// @return       Null-terminated string, or NULL on error
 #[no_mangle]
 pub unsafe extern "C" fn waveform_get_var_name(
     result: *const CWaveformResult,
     index: c_int,
 ) -> *const c_char {
-    if result.is_null() || index < 0 {
+    let Some(r) = handle_arg(result, "result") else {
         return ptr::null();
-    }
-    let r = &*result;
-    let idx = index as usize;
-    if idx >= r.cached_var_names.len() {
+    };
+    let Some(idx) = index_arg(index, r.cached_var_names.len(), "variable") else {
         return ptr::null();
-    }
-    r.cached_var_names[idx].as_ptr()
+    };
+    succeed(r.cached_var_names[idx].as_ptr())
 }
 
+/// Get variable type by index.
+///
+/// @param result Result handle
+/// @param index  Variable index (0-based)
+/// @return       WAVEFORM_VAR_* constant, or -1 on error
 #[no_mangle]
 pub unsafe extern "C" fn waveform_get_var_type(
     result: *const CWaveformResult,
     index: c_int,
 ) -> c_int {
-    if result.is_null() || index < 0 {
+    let Some(r) = handle_arg(result, "result") else {
         return -1;
-    }
-    let r = &(*result).inner;
-    let idx = index as usize;
-    if idx >= r.variables.len() {
+    };
+    let r = &r.inner;
+    let Some(idx) = index_arg(index, r.variables.len(), "variable") else {
         return -1;
-    }
-    match r.variables[idx].var_type {
-        hspice_core::VarType::Time => 0,
-        hspice_core::VarType::Frequency => 1,
-        hspice_core::VarType::Voltage => 2,
-        hspice_core::VarType::Current => 3,
-        hspice_core::VarType::Unknown => -1,
-    }
+    };
+    succeed(match r.variables[idx].var_type {
+        hspice_core::VarType::Time => WAVEFORM_VAR_TIME,
+        hspice_core::VarType::Frequency => WAVEFORM_VAR_FREQUENCY,
+        hspice_core::VarType::Voltage => WAVEFORM_VAR_VOLTAGE,
+        hspice_core::VarType::Current => WAVEFORM_VAR_CURRENT,
+        hspice_core::VarType::Power => WAVEFORM_VAR_POWER,
+        hspice_core::VarType::Parameter => WAVEFORM_VAR_PARAMETER,
+        hspice_core::VarType::Internal => WAVEFORM_VAR_INTERNAL,
+        hspice_core::VarType::Noise => WAVEFORM_VAR_NOISE,
+        hspice_core::VarType::Unknown => WAVEFORM_VAR_UNKNOWN,
+    })
+}
+
+/// Get the unit symbol of a variable, from its probe name or type.
+///
+/// @param result Result handle
+/// @param index  Variable index (0-based)
+/// @return       "s", "Hz", "V", "A", "W", "C", "V/rtHz", "A/rtHz", "V^2/Hz",
+///               or "" if unknown; a static string, or NULL on error
+#[no_mangle]
+pub unsafe extern "C" fn waveform_get_var_unit(
+    result: *const CWaveformResult,
+    index: c_int,
+) -> *const c_char {
+    let Some(r) = handle_arg(result, "result") else {
+        return ptr::null();
+    };
+    let r = &r.inner;
+    let Some(idx) = index_arg(index, r.variables.len(), "variable") else {
+        return ptr::null();
+    };
+    let unit = match r.variables[idx].unit {
+        Some(hspice_core::Unit::Second) => c"s",
+        Some(hspice_core::Unit::Hertz) => c"Hz",
+        Some(hspice_core::Unit::Volt) => c"V",
+        Some(hspice_core::Unit::Ampere) => c"A",
+        Some(hspice_core::Unit::Watt) => c"W",
+        Some(hspice_core::Unit::Coulomb) => c"C",
+        Some(hspice_core::Unit::VoltPerRootHertz) => c"V/rtHz",
+        Some(hspice_core::Unit::AmperePerRootHertz) => c"A/rtHz",
+        Some(hspice_core::Unit::SquareVoltPerHertz) => c"V^2/Hz",
+        None => c"",
+    };
+    succeed(unit.as_ptr())
 }
 
 // ============================================================================
 // Sweep Accessors
 // ============================================================================
 
+/// Check if the result has sweep data.
+///
+/// @return 1 if has sweep, 0 otherwise
 #[no_mangle]
 pub unsafe extern "C" fn waveform_has_sweep(result: *const CWaveformResult) -> c_int {
-    if result.is_null() {
-        return 0;
-    }
-    if (*result).inner.has_sweep() {
-        1
-    } else {
-        0
+    match handle_arg(result, "result") {
+        Some(r) => succeed(if r.inner.has_sweep() { 1 } else { 0 }),
+        None => 0,
     }
 }
 
+/// Get the sweep parameter name.
+///
+/// @return Null-terminated string, or NULL if no sweep
 #[no_mangle]
 pub unsafe extern "C" fn waveform_get_sweep_param(result: *const CWaveformResult) -> *const c_char {
-    if result.is_null() {
+    let Some(r) = handle_arg(result, "result") else {
         return ptr::null();
-    }
-    match &(*result).cached_sweep_param {
-        Some(s) => s.as_ptr(),
-        None => ptr::null(),
+    };
+    match &r.cached_sweep_param {
+        Some(s) => succeed(s.as_ptr()),
+        None => fail(
+            WaveformErrorCode::NoResult,
+            "result has no sweep",
+            ptr::null(),
+        ),
     }
 }
 
+/// Get the sweep value for a specific table.
+///
+/// @param result      Result handle
+/// @param table_index Table index (0-based)
+/// @return            Sweep value, or 0.0 on error
 #[no_mangle]
 pub unsafe extern "C" fn waveform_get_sweep_value(
     result: *const CWaveformResult,
     table_index: c_int,
 ) -> c_double {
-    if result.is_null() || table_index < 0 {
+    let Some(r) = handle_arg(result, "result") else {
         return 0.0;
-    }
-    let r = &(*result).inner;
-    let idx = table_index as usize;
-    if idx >= r.tables.len() {
+    };
+    let r = &r.inner;
+    let Some(idx) = index_arg(table_index, r.tables.len(), "table") else {
         return 0.0;
-    }
-    r.tables[idx].sweep_value.unwrap_or(0.0)
+    };
+    succeed(r.tables[idx].sweep_value.unwrap_or(0.0))
 }
 
 // ============================================================================
 // Data Accessors
 // ============================================================================
 
+/// Look up one vector, recording an error on a bad handle or index
+unsafe fn vector_arg<'a>(
+    result: *const CWaveformResult,
+    table_index: c_int,
+    var_index: c_int,
+) -> Option<&'a VectorData> {
+    let r = &handle_arg(result, "result")?.inner;
+    let ti = index_arg(table_index, r.tables.len(), "table")?;
+    let vi = index_arg(var_index, r.variables.len(), "variable")?;
+    Some(&r.tables[ti].vectors[vi])
+}
+
+/// Check an output buffer and its capacity, recording an error if unusable
+fn out_arg<T>(buffer: *mut T, max_count: c_int, name: &str) -> Option<usize> {
+    if buffer.is_null() {
+        return fail(
+            WaveformErrorCode::NullPointer,
+            format!("{} is NULL", name),
+            None,
+        );
+    }
+    if max_count <= 0 {
+        return fail(
+            WaveformErrorCode::InvalidArgument,
+            format!("max_count must be positive, got {}", max_count),
+            None,
+        );
+    }
+    Some(max_count as usize)
+}
+
+/// Get the length of data for a variable.
+///
+/// @param result      Result handle
+/// @param table_index Table index (0-based)
+/// @param var_index   Variable index (0-based)
+/// @return            Number of data points, or 0 on error
 #[no_mangle]
 pub unsafe extern "C" fn waveform_get_data_length(
     result: *const CWaveformResult,
     table_index: c_int,
     var_index: c_int,
 ) -> c_int {
-    if result.is_null() || table_index < 0 || var_index < 0 {
-        return 0;
-    }
-    let ti = table_index as usize;
-    let vi = var_index as usize;
-    let r = &(*result).inner;
-    if ti >= r.tables.len() || vi >= r.variables.len() {
-        return 0;
+    match vector_arg(result, table_index, var_index) {
+        Some(vector) => succeed(vector.len() as c_int),
+        None => 0,
     }
-    r.tables[ti].vectors[vi].len() as c_int
 }
 
+/// Check if data is complex.
+///
+/// @return 1 if complex, 0 if real, -1 on error
 #[no_mangle]
 pub unsafe extern "C" fn waveform_is_complex(
     result: *const CWaveformResult,
     table_index: c_int,
     var_index: c_int,
 ) -> c_int {
-    if result.is_null() || table_index < 0 || var_index < 0 {
-        return -1;
-    }
-    let ti = table_index as usize;
-    let vi = var_index as usize;
-    let r = &(*result).inner;
-    if ti >= r.tables.len() || vi >= r.variables.len() {
-        return -1;
-    }
-    if r.tables[ti].vectors[vi].is_complex() {
-        1
-    } else {
-        0
+    match vector_arg(result, table_index, var_index) {
+        Some(vector) => succeed(if vector.is_complex() { 1 } else { 0 }),
+        None => -1,
     }
 }
 
-/// Get real data by variable index.
+/// Get real data for a variable.
+///
+/// @param result      Result handle
+/// @param table_index Table index (0-based)
+/// @param var_index   Variable index (0-based)
+/// @param out_buffer  Output buffer for values
+/// @param max_count   Maximum number of values to copy
+/// @return            Number of values copied, or -1 on error
 #[no_mangle]
 pub unsafe extern "C" fn waveform_get_real_data(
     result: *const CWaveformResult,
@@ -385,96 +856,443 @@ pub unsafe extern "C" fn waveform_get_real_data(
     out_buffer: *mut c_double,
     max_count: c_int,
 ) -> c_int {
-    if result.is_null()
-        || out_buffer.is_null()
-        || table_index < 0
-        || var_index < 0
-        || max_count <= 0
-    {
+    let Some(vector) = vector_arg(result, table_index, var_index) else {
         return -1;
-    }
-    let ti = table_index as usize;
-    let vi = var_index as usize;
-    let r = &(*result).inner;
-    if ti >= r.tables.len() || vi >= r.variables.len() {
+    };
+    let Some(max_count) = out_arg(out_buffer, max_count, "out_buffer") else {
         return -1;
-    }
+    };
 
-    match &r.tables[ti].vectors[vi] {
-        VectorData::Real(vec) => {
-            let count = std::cmp::min(vec.len(), max_count as usize);
+    match vector {
+        VectorData::Real(_) | VectorData::Real32(_) | VectorData::Stepped { .. } => {
+            let vec = vector.to_real().unwrap_or_default();
+            let count = std::cmp::min(vec.len(), max_count);
             std::ptr::copy_nonoverlapping(vec.as_ptr(), out_buffer, count);
-            count as c_int
+            succeed(count as c_int)
         }
-        VectorData::Complex(_) => -1,
+        VectorData::Complex(_) => fail(
+            WaveformErrorCode::TypeMismatch,
+            "vector is complex; use waveform_get_complex_data",
+            -1,
+        ),
     }
 }
 
-/// Get complex data by variable index.
+/// Get the real data of a variable in every sweep table at once.
+///
+/// Table t is copied to out_buffer[t * stride], so the buffer holds one
+/// row of stride values per table. Tables longer than stride are cut off
+/// and shorter ones are padded with NaN. Only whole rows are written: as
+/// many tables as max_count / stride, in table order.
+///
+/// @param result     Result handle
+/// @param var_index  Variable index (0-based)
+/// @param out_buffer Output buffer for the rows
+/// @param stride     Values per row, e.g. the largest
+///                   waveform_get_table_point_count()
+/// @param max_count  Capacity of out_buffer in values
+/// @return           Number of tables copied, or -1 on error
 #[no_mangle]
-pub unsafe extern "C" fn waveform_get_complex_data(
+pub unsafe extern "C" fn waveform_get_signal_all_sweeps(
     result: *const CWaveformResult,
-    table_index: c_int,
     var_index: c_int,
-    out_real: *mut c_double,
-    out_imag: *mut c_double,
+    out_buffer: *mut c_double,
+    stride: c_int,
     max_count: c_int,
 ) -> c_int {
-    if result.is_null() || out_real.is_null() || out_imag.is_null() {
+    let Some(r) = handle_arg(result, "result") else {
         return -1;
-    }
-    if table_index < 0 || var_index < 0 || max_count <= 0 {
+    };
+    let Some(vi) = index_arg(var_index, r.inner.variables.len(), "variable") else {
         return -1;
-    }
-
-    let ti = table_index as usize;
-    let vi = var_index as usize;
-    let r = &(*result).inner;
-    if ti >= r.tables.len() || vi >= r.variables.len() {
+    };
+    let Some(max_count) = out_arg(out_buffer, max_count, "out_buffer") else {
         return -1;
+    };
+    if stride <= 0 {
+        return fail(
+            WaveformErrorCode::InvalidArgument,
+            format!("stride must be positive, got {}", stride),
+            -1,
+        );
+    }
+    let stride = stride as usize;
+    if r.inner.tables.iter().any(|t| t.vectors[vi].is_complex()) {
+        return fail(
+            WaveformErrorCode::TypeMismatch,
+            "vector is complex; use waveform_get_complex_data",
+            -1,
+        );
     }
 
-    match &r.tables[ti].vectors[vi] {
-        VectorData::Complex(vec) => {
-            let count = std::cmp::min(vec.len(), max_count as usize);
-            for (i, c) in vec.iter().take(count).enumerate() {
-                *out_real.add(i) = c.re;
-                *out_imag.add(i) = c.im;
-            }
-            count as c_int
-        }
-        VectorData::Real(_) => -1,
+    let rows = std::slice::from_raw_parts_mut(out_buffer, max_count);
+    let mut copied = 0;
+    for (row, table) in rows.chunks_exact_mut(stride).zip(&r.inner.tables) {
+        let values = table.vectors[vi].to_real().unwrap_or_default();
+        let count = std::cmp::min(values.len(), stride);
+        row[..count].copy_from_slice(&values[..count]);
+        row[count..].fill(f64::NAN);
+        copied += 1;
     }
+    succeed(copied)
 }
 
-// ============================================================================
-// Streaming API
-// ============================================================================
+/// Get complex data for a variable (separate real and imaginary arrays).
+///
+/// @param result      Result handle
+/// @param table_index Table index (0-based)
+/// @param var_index   Variable index (0-based)
+/// @param out_real    Output buffer for real parts
+/// @param out_imag    Output buffer for imaginary parts
+/// @param max_count   Maximum number of complex values to copy
+/// @return            Number of values copied, or -1 on error
+#[no_mangle]
+pub unsafe extern "C" fn waveform_get_complex_data(
+    result: *const CWaveformResult,
+    table_index: c_int,
+    var_index: c_int,
+    out_real: *mut c_double,
+    out_imag: *mut c_double,
+    max_count: c_int,
+) -> c_int {
+    let Some(vector) = vector_arg(result, table_index, var_index) else {
+        return -1;
+    };
+    let Some(max_count) = out_arg(out_real, max_count, "out_real") else {
+        return -1;
+    };
+    if out_imag.is_null() {
+        return fail(WaveformErrorCode::NullPointer, "out_imag is NULL", -1);
+    }
+
+    match vector {
+        VectorData::Complex(vec) => {
+            let count = std::cmp::min(vec.len(), max_count);
+            for (i, c) in vec.iter().take(count).enumerate() {
+                *out_real.add(i) = c.re;
+                *out_imag.add(i) = c.im;
+            }
+            succeed(count as c_int)
+        }
+        VectorData::Real(_) | VectorData::Real32(_) | VectorData::Stepped { .. } => fail(
+            WaveformErrorCode::TypeMismatch,
+            "vector is real; use waveform_get_real_data",
+            -1,
+        ),
+    }
+}
+
+/// Store the vector length in `out_len`, recording an error if it is NULL
+unsafe fn write_len(out_len: *mut c_int, len: usize) -> Option<()> {
+    if out_len.is_null() {
+        return fail(WaveformErrorCode::NullPointer, "out_len is NULL", None);
+    }
+    *out_len = len as c_int;
+    Some(())
+}
+
+/// Get a pointer to the real data of a variable without copying.
+///
+/// @param result      Result handle
+/// @param table_index Table index (0-based)
+/// @param var_index   Variable index (0-based)
+/// @param out_len     Receives the number of values
+/// @return            Pointer to out_len doubles, or NULL on error
+///
+/// @note The data is owned by the result and stays valid until
+///       waveform_free(). Do not write through the pointer.
+#[no_mangle]
+pub unsafe extern "C" fn waveform_get_real_data_ptr(
+    result: *const CWaveformResult,
+    table_index: c_int,
+    var_index: c_int,
+    out_len: *mut c_int,
+) -> *const c_double {
+    let Some(vector) = vector_arg(result, table_index, var_index) else {
+        return ptr::null();
+    };
+
+    let data = match vector {
+        VectorData::Real(vec) => vec.as_ptr(),
+        VectorData::Real32(_) | VectorData::Stepped { .. } => {
+            // Expanded once per vector; the boxed slice never moves while
+            // the handle lives, so the pointer stays valid until free
+            let r = &*result;
+            let mut expanded = r.expanded.lock().unwrap_or_else(|e| e.into_inner());
+            expanded
+                .entry((table_index as usize, var_index as usize))
+                .or_insert_with(|| vector.to_real().unwrap_or_default().into())
+                .as_ptr()
+        }
+        VectorData::Complex(_) => {
+            return fail(
+                WaveformErrorCode::TypeMismatch,
+                "vector is complex; use waveform_get_complex_data_ptr",
+                ptr::null(),
+            )
+        }
+    };
+    match write_len(out_len, vector.len()) {
+        Some(()) => succeed(data),
+        None => ptr::null(),
+    }
+}
+
+/// Get a pointer to the complex data of a variable without copying.
+///
+/// Values are interleaved as re0, im0, re1, im1, ..., matching the layout
+/// of C99 `double complex` and C++ `std::complex<double>`.
+///
+/// @param result      Result handle
+/// @param table_index Table index (0-based)
+/// @param var_index   Variable index (0-based)
+/// @param out_len     Receives the number of complex values (2 * out_len
+///                    doubles)
+/// @return            Pointer to the interleaved data, or NULL on error
+///
+/// @note The data is owned by the result and stays valid until
+///       waveform_free(). Do not write through the pointer.
+#[no_mangle]
+pub unsafe extern "C" fn waveform_get_complex_data_ptr(
+    result: *const CWaveformResult,
+    table_index: c_int,
+    var_index: c_int,
+    out_len: *mut c_int,
+) -> *const c_double {
+    let Some(vector) = vector_arg(result, table_index, var_index) else {
+        return ptr::null();
+    };
+
+    match vector {
+        // Complex64 is #[repr(C)] { re, im }
+        VectorData::Complex(vec) => match write_len(out_len, vec.len()) {
+            Some(()) => succeed(vec.as_ptr().cast()),
+            None => ptr::null(),
+        },
+        VectorData::Real(_) | VectorData::Real32(_) | VectorData::Stepped { .. } => fail(
+            WaveformErrorCode::TypeMismatch,
+            "vector is real; use waveform_get_real_data_ptr",
+            ptr::null(),
+        ),
+    }
+}
+
+/// Summary statistics of one signal, filled by waveform_get_stats()
+///
+/// NaN values are skipped; std is the population standard deviation.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct WaveformStats {
+    /// Number of values summarized
+    pub count: u64,
+    pub min: c_double,
+    pub max: c_double,
+    pub mean: c_double,
+    /// Root mean square
+    pub rms: c_double,
+    /// Population standard deviation
+    pub std: c_double,
+    /// max - min
+    pub peak_to_peak: c_double,
+    /// 1 if computed on the magnitudes of complex data, 0 otherwise
+    pub magnitude: c_int,
+}
+
+/// Compute min, max, mean, RMS, std and peak-to-peak of a variable.
+///
+/// Complex variables are summarized by their magnitude.
+///
+/// @param result      Result handle
+/// @param table_index Table index (0-based), or -1 for all tables together
+/// @param var_index   Variable index (0-based)
+/// @param out_stats   Output statistics
+/// @return            0 on success, -1 on error (WAVEFORM_ERROR_CODE_NO_RESULT
+///                    if the variable has no values)
+#[no_mangle]
+pub unsafe extern "C" fn waveform_get_stats(
+    result: *const CWaveformResult,
+    table_index: c_int,
+    var_index: c_int,
+    out_stats: *mut WaveformStats,
+) -> c_int {
+    let stats = if table_index == -1 {
+        let Some(r) = handle_arg(result, "result") else {
+            return -1;
+        };
+        let Some(vi) = index_arg(var_index, r.inner.variables.len(), "variable") else {
+            return -1;
+        };
+        r.inner
+            .tables
+            .iter()
+            .filter_map(|t| t.vectors[vi].stats())
+            .reduce(|a, b| a.merge(&b))
+    } else {
+        match vector_arg(result, table_index, var_index) {
+            Some(vector) => vector.stats(),
+            None => return -1,
+        }
+    };
+    if out_stats.is_null() {
+        return fail(WaveformErrorCode::NullPointer, "out_stats is NULL", -1);
+    }
+    let Some(s) = stats else {
+        return fail(WaveformErrorCode::NoResult, "variable has no values", -1);
+    };
+    *out_stats = WaveformStats {
+        count: s.count as u64,
+        min: s.min,
+        max: s.max,
+        mean: s.mean,
+        rms: s.rms,
+        std: s.std,
+        peak_to_peak: s.peak_to_peak(),
+        magnitude: s.magnitude as c_int,
+    };
+    succeed(0)
+}
+
+// ============================================================================
+// Conversion and Export
+// ============================================================================
 
+/// Options of waveform_export_csv()
 #[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct WaveformCsvOptions {
+    /// Only the scale and the signals matching this pattern (see
+    /// waveform_select()), or NULL for every signal
+    pub pattern: *const c_char,
+    /// Only this sweep table (0-based), or WAVEFORM_ALL_TABLES
+    pub table_index: c_int,
+}
+
+/// Convert an HSPICE file to a binary SPICE3 raw file.
+///
+/// Data the raw format cannot hold is logged as a warning.
+///
+/// @param input  Path to the HSPICE file (.tr0, .ac0, .sw0)
+/// @param output Path of the .raw file to write
+/// @return       0 on success, -1 on error
+#[no_mangle]
+pub unsafe extern "C" fn waveform_convert_to_raw(
+    input: *const c_char,
+    output: *const c_char,
+) -> c_int {
+    let Some(input) = str_arg(input, "input") else {
+        return -1;
+    };
+    let Some(output) = str_arg(output, "output") else {
+        return -1;
+    };
+    match read_and_convert(input, output) {
+        Ok(report) => {
+            for warning in &report.warnings {
+                tracing::warn!("{}", warning);
+            }
+            succeed(0)
+        }
+        Err(e) => fail_with("waveform_convert_to_raw", &e, -1),
+    }
+}
+
+/// Write a result as CSV.
+///
+/// The layout is the one of the command line tool: a header row, a leading
+/// sweep column when more than one sweep table is written, and
+/// <name>.re/<name>.im columns for complex signals.
+///
+/// @param result  Result handle
+/// @param path    Path of the .csv file to write
+/// @param options Signals and table to write, or NULL for everything
+/// @return        0 on success, -1 on error
+#[no_mangle]
+pub unsafe extern "C" fn waveform_export_csv(
+    result: *const CWaveformResult,
+    path: *const c_char,
+    options: *const WaveformCsvOptions,
+) -> c_int {
+    let Some(r) = handle_arg(result, "result") else {
+        return -1;
+    };
+    let Some(path) = str_arg(path, "path") else {
+        return -1;
+    };
+
+    // Values are shared, so narrowing a copy costs no data
+    let mut export = (*r.inner).clone();
+    if let Some(options) = options.as_ref() {
+        if !options.pattern.is_null() {
+            let Some(pattern) = str_arg(options.pattern, "pattern") else {
+                return -1;
+            };
+            export = match export.select(pattern) {
+                Ok(selected) => selected,
+                Err(e) => return fail_with("waveform_export_csv", &e, -1),
+            };
+        }
+        if options.table_index != WAVEFORM_ALL_TABLES {
+            let Some(ti) = index_arg(options.table_index, export.tables.len(), "table") else {
+                return -1;
+            };
+            export.tables = vec![export.tables.swap_remove(ti)];
+        }
+    }
+
+    let write = || -> hspice_core::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        export.write_csv(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    };
+    match write() {
+        Ok(()) => succeed(0),
+        Err(e) => fail_with(
+            "waveform_export_csv",
+            &e.context(format!("writing {}", path)),
+            -1,
+        ),
+    }
+}
+
+// ============================================================================
+// Streaming API
+// ============================================================================
+
+/// Opaque handle to a streaming reader
 pub struct CWaveformStream {
+    state: Mutex<StreamState>,
+}
+
+/// The reader and its last chunk, locked together so that a chunk always
+/// belongs to the reader position it was read at
+struct StreamState {
     reader: HspiceStreamReader,
     current_chunk: Option<DataChunk>,
-    signal_names: Vec<CString>,
-    scale_name: CString,
 }
 
-/// Open a file for streaming.
+/// Lock a stream handle, recording an error on NULL
+unsafe fn stream_arg<'a>(stream: *const CWaveformStream) -> Option<MutexGuard<'a, StreamState>> {
+    handle_arg(stream, "stream").map(|s| s.state.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// Open a file for streaming read.
 ///
-/// The debug parameter is deprecated and ignored. Use waveform_init_logging() instead.
+/// @param filename   Path to the waveform file
+/// @param chunk_size Minimum points per chunk
+/// @param debug      Debug level (DEPRECATED: ignored, use waveform_init_logging)
+/// @return           Stream handle, or NULL on error
 #[no_mangle]
 pub unsafe extern "C" fn waveform_stream_open(
     filename: *const c_char,
     chunk_size: c_int,
     _debug: c_int,
 ) -> *mut CWaveformStream {
-    if filename.is_null() || chunk_size <= 0 {
+    let Some(filename_str) = str_arg(filename, "filename") else {
+        return ptr::null_mut();
+    };
+    let Some(chunk_size) = chunk_size_arg(chunk_size) else {
         return ptr::null_mut();
-    }
-
-    let filename_str = match CStr::from_ptr(filename).to_str() {
-        Ok(s) => s,
-        Err(_) => return ptr::null_mut(),
     };
 
     tracing::debug!(
@@ -483,30 +1301,66 @@ pub unsafe extern "C" fn waveform_stream_open(
         chunk_size
     );
 
-    let reader = match read_stream_chunked(filename_str, chunk_size as usize) {
-        Ok(r) => r,
-        Err(e) => {
-            tracing::error!("stream open error: {:?}", e);
-            return ptr::null_mut();
-        }
+    match read_stream_chunked(filename_str, chunk_size) {
+        Ok(reader) => succeed(stream_handle(reader)),
+        Err(e) => fail_with("waveform_stream_open", &e, ptr::null_mut()),
+    }
+}
+
+/// Open a stream over an HSPICE file already in memory.
+///
+/// The buffer is copied, so it may be freed as soon as this returns; the
+/// copy is released by waveform_stream_close().
+///
+/// @param data       Contents of an HSPICE file
+/// @param len        Size of data in bytes
+/// @param chunk_size Minimum points per chunk
+/// @return           Stream handle, or NULL on error
+#[no_mangle]
+pub unsafe extern "C" fn waveform_stream_open_from_memory(
+    data: *const u8,
+    len: usize,
+    chunk_size: c_int,
+) -> *mut CWaveformStream {
+    let Some(bytes) = bytes_arg(data, len) else {
+        return ptr::null_mut();
+    };
+    let Some(chunk_size) = chunk_size_arg(chunk_size) else {
+        return ptr::null_mut();
     };
+    match HspiceStreamReader::from_bytes_with_options(
+        bytes.to_vec(),
+        chunk_size,
+        &ReadOptions::default(),
+    ) {
+        Ok(reader) => succeed(stream_handle(reader)),
+        Err(e) => fail_with("waveform_stream_open_from_memory", &e, ptr::null_mut()),
+    }
+}
 
-    let metadata = reader.metadata();
-    let signal_names: Vec<CString> = metadata
-        .signal_names
-        .iter()
-        .filter_map(|s| CString::new(s.clone()).ok())
-        .collect();
-    let scale_name = CString::new(metadata.scale_name.clone()).unwrap_or_default();
+/// Check a chunk size argument, recording an error unless positive
+fn chunk_size_arg(chunk_size: c_int) -> Option<usize> {
+    if chunk_size <= 0 {
+        return fail(
+            WaveformErrorCode::InvalidArgument,
+            format!("chunk_size must be positive, got {}", chunk_size),
+            None,
+        );
+    }
+    Some(chunk_size as usize)
+}
 
+/// Box `reader` into a stream handle with no current chunk
+fn stream_handle(reader: HspiceStreamReader) -> *mut CWaveformStream {
     Box::into_raw(Box::new(CWaveformStream {
-        reader,
-        current_chunk: None,
-        signal_names,
-        scale_name,
+        state: Mutex::new(StreamState {
+            reader,
+            current_chunk: None,
+        }),
     }))
 }
 
+/// Close a streaming reader.
 #[no_mangle]
 pub unsafe extern "C" fn waveform_stream_close(stream: *mut CWaveformStream) {
     if !stream.is_null() {
@@ -514,58 +1368,218 @@ pub unsafe extern "C" fn waveform_stream_close(stream: *mut CWaveformStream) {
     }
 }
 
+/// Read the next chunk.
+///
+/// @return 1 if success, 0 if EOF, -1 on error
 #[no_mangle]
 pub unsafe extern "C" fn waveform_stream_next(stream: *mut CWaveformStream) -> c_int {
-    if stream.is_null() {
+    let Some(mut stream) = stream_arg(stream) else {
         return -1;
-    }
-    let stream = &mut *stream;
+    };
 
     match stream.reader.next() {
         Some(Ok(chunk)) => {
             stream.current_chunk = Some(chunk);
-            1
+            succeed(1)
         }
-        Some(Err(_)) => -1,
-        None => 0,
+        Some(Err(e)) => fail_with("waveform_stream_next", &e, -1),
+        None => succeed(0),
+    }
+}
+
+/// Restrict the stream to a set of signals.
+///
+/// Later chunks contain only these signals plus the scale. Pass count 0 to
+/// read all signals again.
+///
+/// @param stream Stream handle
+/// @param names  Array of count signal names (may be NULL if count is 0)
+/// @param count  Number of names
+/// @return       0 on success, -1 on error (unknown names are an error and
+///               leave the filter unchanged)
+#[no_mangle]
+pub unsafe extern "C" fn waveform_stream_set_signals(
+    stream: *mut CWaveformStream,
+    names: *const *const c_char,
+    count: c_int,
+) -> c_int {
+    let Some(mut stream) = stream_arg(stream) else {
+        return -1;
+    };
+    if count == 0 {
+        stream.reader.set_signals(None);
+        return succeed(0);
+    }
+    let Some(names) = input_slice(names, count, "names") else {
+        return -1;
+    };
+
+    let known = stream.reader.metadata().signal_names;
+    let mut signals = Vec::with_capacity(names.len());
+    for &name in names {
+        let Some(name) = str_arg(name, "signal name") else {
+            return -1;
+        };
+        if !known.iter().any(|k| k == name) {
+            return fail(
+                WaveformErrorCode::NotFound,
+                format!("signal {:?} not in stream", name),
+                -1,
+            );
+        }
+        signals.push(name.to_string());
     }
+    stream.reader.set_signals(Some(signals));
+    succeed(0)
 }
 
+/// Restrict the stream to the signals matching a pattern.
+///
+/// Takes the same patterns as waveform_select(). Later chunks contain only
+/// the matching signals plus the scale.
+///
+/// @param stream  Stream handle
+/// @param pattern Glob or "re:" regex
+/// @return        Number of matching signals, or -1 on error (an invalid
+///                pattern or no match leaves the filter unchanged)
+#[no_mangle]
+pub unsafe extern "C" fn waveform_stream_set_signal_pattern(
+    stream: *mut CWaveformStream,
+    pattern: *const c_char,
+) -> c_int {
+    let Some(mut stream) = stream_arg(stream) else {
+        return -1;
+    };
+    let Some(pattern) = str_arg(pattern, "pattern") else {
+        return -1;
+    };
+    let pattern = match SignalPattern::new(pattern) {
+        Ok(p) => p,
+        Err(e) => return fail_with("waveform_stream_set_signal_pattern", &e, -1),
+    };
+
+    let known = stream.reader.metadata().signal_names;
+    let signals: Vec<String> = pattern
+        .filter(&known)
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    if signals.is_empty() {
+        return fail(
+            WaveformErrorCode::NotFound,
+            format!("no signal matches {:?}", pattern.as_str()),
+            -1,
+        );
+    }
+    let count = signals.len() as c_int;
+    stream.reader.set_signals(Some(signals));
+    succeed(count)
+}
+
+/// Rewind the stream to the first chunk.
+///
+/// @return 0 on success, -1 if stream is NULL
+#[no_mangle]
+pub unsafe extern "C" fn waveform_stream_reset(stream: *mut CWaveformStream) -> c_int {
+    let Some(mut stream) = stream_arg(stream) else {
+        return -1;
+    };
+    stream.reader.reset();
+    stream.current_chunk = None;
+    succeed(0)
+}
+
+/// Move the stream so the next chunk starts at the first point at or
+/// after time t.
+///
+/// Seeking back to a region already read restarts close to it rather than
+/// at the beginning of the file. If t is past the end, the next
+/// waveform_stream_next() returns 0.
+///
+/// @param stream Stream handle
+/// @param t      Scale value (time) to seek to
+/// @return       0 on success, -1 on error
+#[no_mangle]
+pub unsafe extern "C" fn waveform_stream_seek_time(
+    stream: *mut CWaveformStream,
+    t: c_double,
+) -> c_int {
+    let Some(mut stream) = stream_arg(stream) else {
+        return -1;
+    };
+    stream.current_chunk = None;
+    match stream.reader.seek_time(t) {
+        Ok(()) => succeed(0),
+        Err(e) => fail_with("waveform_stream_seek_time", &e, -1),
+    }
+}
+
+/// Get the current chunk's point count.
 #[no_mangle]
 pub unsafe extern "C" fn waveform_stream_get_chunk_size(stream: *const CWaveformStream) -> c_int {
-    if stream.is_null() {
+    let Some(stream) = stream_arg(stream) else {
         return 0;
-    }
-    match &(*stream).current_chunk {
-        Some(chunk) => chunk
-            .data
-            .values()
-            .next()
-            .map(|v| v.len() as c_int)
-            .unwrap_or(0),
-        None => 0,
+    };
+    match &stream.current_chunk {
+        Some(chunk) => succeed(
+            chunk
+                .data
+                .values()
+                .next()
+                .map(|v| v.len() as c_int)
+                .unwrap_or(0),
+        ),
+        None => fail(
+            WaveformErrorCode::NoResult,
+            "no current chunk; call waveform_stream_next first",
+            0,
+        ),
     }
 }
 
+/// Get the current chunk's time range.
+///
+/// @param stream    Stream handle
+/// @param out_start Output for start time
+/// @param out_end   Output for end time
+/// @return          0 on success, -1 on error
 #[no_mangle]
 pub unsafe extern "C" fn waveform_stream_get_time_range(
     stream: *const CWaveformStream,
     out_start: *mut c_double,
     out_end: *mut c_double,
 ) -> c_int {
-    if stream.is_null() || out_start.is_null() || out_end.is_null() {
+    let Some(stream) = stream_arg(stream) else {
         return -1;
+    };
+    if out_start.is_null() || out_end.is_null() {
+        return fail(
+            WaveformErrorCode::NullPointer,
+            "out_start or out_end is NULL",
+            -1,
+        );
     }
-    match &(*stream).current_chunk {
+    match &stream.current_chunk {
         Some(chunk) => {
             *out_start = chunk.time_range.0;
             *out_end = chunk.time_range.1;
-            0
+            succeed(0)
         }
-        None => -1,
+        None => fail(
+            WaveformErrorCode::NoResult,
+            "no current chunk; call waveform_stream_next first",
+            -1,
+        ),
     }
 }
 
+/// Get signal data from the current chunk.
+///
+/// @param stream      Stream handle
+/// @param signal_name Name of the signal
+/// @param out_buffer  Output buffer for values
+/// @param max_count   Maximum number of values to copy
+/// @return            Number of values copied, or -1 on error
 #[no_mangle]
 pub unsafe extern "C" fn waveform_stream_get_signal_data(
     stream: *const CWaveformStream,
@@ -573,34 +1587,284 @@ pub unsafe extern "C" fn waveform_stream_get_signal_data(
     out_buffer: *mut c_double,
     max_count: c_int,
 ) -> c_int {
-    if stream.is_null() || signal_name.is_null() || out_buffer.is_null() || max_count <= 0 {
+    let Some(stream) = stream_arg(stream) else {
+        return -1;
+    };
+    let Some(name) = str_arg(signal_name, "signal_name") else {
+        return -1;
+    };
+    let Some(max_count) = out_arg(out_buffer, max_count, "out_buffer") else {
         return -1;
-    }
-
-    let name = match CStr::from_ptr(signal_name).to_str() {
-        Ok(s) => s,
-        Err(_) => return -1,
     };
 
-    let chunk = match &(*stream).current_chunk {
-        Some(c) => c,
-        None => return -1,
+    let Some(chunk) = &stream.current_chunk else {
+        return fail(
+            WaveformErrorCode::NoResult,
+            "no current chunk; call waveform_stream_next first",
+            -1,
+        );
     };
 
     match chunk.data.get(name) {
-        Some(VectorData::Real(vec)) => {
-            let count = std::cmp::min(vec.len(), max_count as usize);
+        Some(
+            vector @ (VectorData::Real(_) | VectorData::Real32(_) | VectorData::Stepped { .. }),
+        ) => {
+            let vec = vector.to_real().unwrap_or_default();
+            let count = std::cmp::min(vec.len(), max_count);
             std::ptr::copy_nonoverlapping(vec.as_ptr(), out_buffer, count);
-            count as c_int
+            succeed(count as c_int)
         }
         Some(VectorData::Complex(vec)) => {
-            let count = std::cmp::min(vec.len(), max_count as usize);
+            let count = std::cmp::min(vec.len(), max_count);
             for (i, c) in vec.iter().take(count).enumerate() {
                 *out_buffer.add(i) = (c.re * c.re + c.im * c.im).sqrt();
             }
-            count as c_int
+            succeed(count as c_int)
         }
-        None => -1,
+        None => fail(
+            WaveformErrorCode::NotFound,
+            format!("signal {:?} not in stream", name),
+            -1,
+        ),
+    }
+}
+
+// ============================================================================
+// Measurements and DSP
+// ============================================================================
+//
+// These take flat caller-owned arrays, so they work on data from
+// waveform_get_real_data(), stream chunks, or any other source.
+
+/// Borrow a caller array as a slice, recording an error for NULL or a
+/// negative length
+unsafe fn input_slice<'a, T>(data: *const T, len: c_int, name: &str) -> Option<&'a [T]> {
+    if data.is_null() {
+        return fail(
+            WaveformErrorCode::NullPointer,
+            format!("{} is NULL", name),
+            None,
+        );
+    }
+    if len < 0 {
+        return fail(
+            WaveformErrorCode::InvalidArgument,
+            format!("len must not be negative, got {}", len),
+            None,
+        );
+    }
+    Some(std::slice::from_raw_parts(data, len as usize))
+}
+
+/// Copy up to max_count values into out_buffer, returning the full length
+unsafe fn copy_out(values: &[f64], out_buffer: *mut c_double, max_count: c_int) -> c_int {
+    if max_count > 0 && !out_buffer.is_null() {
+        let count = std::cmp::min(values.len(), max_count as usize);
+        ptr::copy_nonoverlapping(values.as_ptr(), out_buffer, count);
+    }
+    succeed(values.len() as c_int)
+}
+
+fn edge_from_c(edge: c_int) -> Option<Edge> {
+    match edge {
+        WAVEFORM_EDGE_RISING => Some(Edge::Rising),
+        WAVEFORM_EDGE_FALLING => Some(Edge::Falling),
+        WAVEFORM_EDGE_EITHER => Some(Edge::Either),
+        _ => fail(
+            WaveformErrorCode::InvalidArgument,
+            format!("unknown edge {}", edge),
+            None,
+        ),
+    }
+}
+
+fn window_from_c(window: c_int) -> Option<Window> {
+    match window {
+        WAVEFORM_WINDOW_RECTANGULAR => Some(Window::Rectangular),
+        WAVEFORM_WINDOW_HANN => Some(Window::Hann),
+        _ => fail(
+            WaveformErrorCode::InvalidArgument,
+            format!("unknown window {}", window),
+            None,
+        ),
+    }
+}
+
+/// Find the points where y crosses a level.
+///
+/// @param level      Threshold value
+/// @param edge       WAVEFORM_EDGE_* constant
+/// @param out_buffer Output for interpolated x positions (may be NULL if
+///                   max_count is 0)
+/// @param max_count  Capacity of out_buffer
+/// @return           Number of crossings found, or -1 on error
+#[no_mangle]
+pub unsafe extern "C" fn waveform_crossings(
+    x: *const c_double,
+    y: *const c_double,
+    len: c_int,
+    level: c_double,
+    edge: c_int,
+    out_buffer: *mut c_double,
+    max_count: c_int,
+) -> c_int {
+    let (Some(x), Some(y), Some(edge)) = (
+        input_slice(x, len, "x"),
+        input_slice(y, len, "y"),
+        edge_from_c(edge),
+    ) else {
+        return -1;
+    };
+    copy_out(
+        &measure::crossings(x, y, level, edge),
+        out_buffer,
+        max_count,
+    )
+}
+
+/// Measure the first rising transition between two fractions of the swing.
+///
+/// @param low_frac  Start level as a fraction of (max - min), e.g. 0.1
+/// @param high_frac End level as a fraction of (max - min), e.g. 0.9
+/// @param out_value Output for the rise time
+/// @return          0 on success, -1 if no transition or on error
+#[no_mangle]
+pub unsafe extern "C" fn waveform_rise_time(
+    x: *const c_double,
+    y: *const c_double,
+    len: c_int,
+    low_frac: c_double,
+    high_frac: c_double,
+    out_value: *mut c_double,
+) -> c_int {
+    let (Some(x), Some(y)) = (input_slice(x, len, "x"), input_slice(y, len, "y")) else {
+        return -1;
+    };
+    write_measurement(
+        measure::rise_time(x, y, low_frac, high_frac),
+        out_value,
+        "no rising transition found",
+    )
+}
+
+/// Measure the first falling transition between two fractions of the swing.
+///
+/// @param high_frac Start level as a fraction of (max - min), e.g. 0.9
+/// @param low_frac  End level as a fraction of (max - min), e.g. 0.1
+/// @param out_value Output for the fall time
+/// @return          0 on success, -1 if no transition or on error
+#[no_mangle]
+pub unsafe extern "C" fn waveform_fall_time(
+    x: *const c_double,
+    y: *const c_double,
+    len: c_int,
+    high_frac: c_double,
+    low_frac: c_double,
+    out_value: *mut c_double,
+) -> c_int {
+    let (Some(x), Some(y)) = (input_slice(x, len, "x"), input_slice(y, len, "y")) else {
+        return -1;
+    };
+    write_measurement(
+        measure::fall_time(x, y, high_frac, low_frac),
+        out_value,
+        "no falling transition found",
+    )
+}
+
+/// Linearly resample y(x) onto out_count evenly spaced points.
+///
+/// @return out_count on success, -1 on error
+#[no_mangle]
+pub unsafe extern "C" fn waveform_resample_uniform(
+    x: *const c_double,
+    y: *const c_double,
+    len: c_int,
+    out_buffer: *mut c_double,
+    out_count: c_int,
+) -> c_int {
+    let (Some(x), Some(y)) = (input_slice(x, len, "x"), input_slice(y, len, "y")) else {
+        return -1;
+    };
+    if out_arg(out_buffer, out_count, "out_buffer").is_none() {
+        return -1;
+    }
+    let values = dsp::resample_uniform(x, y, out_count as usize);
+    if values.is_empty() {
+        return fail(
+            WaveformErrorCode::InvalidArgument,
+            "resampling needs at least two input points",
+            -1,
+        );
+    }
+    copy_out(&values, out_buffer, out_count)
+}
+
+/// Single-sided amplitude spectrum of uniformly sampled data.
+///
+/// Bin k is at k * fs / len. A sine of amplitude A on an exact bin reads A.
+///
+/// @param window     WAVEFORM_WINDOW_* constant
+/// @return           Number of bins (len / 2 + 1), or -1 on error
+#[no_mangle]
+pub unsafe extern "C" fn waveform_fft_magnitude(
+    samples: *const c_double,
+    len: c_int,
+    window: c_int,
+    out_buffer: *mut c_double,
+    max_count: c_int,
+) -> c_int {
+    let (Some(samples), Some(window)) =
+        (input_slice(samples, len, "samples"), window_from_c(window))
+    else {
+        return -1;
+    };
+    copy_out(&dsp::fft_magnitude(samples, window), out_buffer, max_count)
+}
+
+/// Total harmonic distortion as a ratio (multiply by 100 for percent).
+///
+/// @param max_harmonic Highest harmonic included (at least 2)
+/// @param window       WAVEFORM_WINDOW_* constant
+/// @param out_value    Output for the THD ratio
+/// @return             0 on success, -1 on error
+#[no_mangle]
+pub unsafe extern "C" fn waveform_thd(
+    samples: *const c_double,
+    len: c_int,
+    max_harmonic: c_int,
+    window: c_int,
+    out_value: *mut c_double,
+) -> c_int {
+    let (Some(samples), Some(window)) =
+        (input_slice(samples, len, "samples"), window_from_c(window))
+    else {
+        return -1;
+    };
+    if max_harmonic < 2 {
+        return fail(
+            WaveformErrorCode::InvalidArgument,
+            format!("max_harmonic must be at least 2, got {}", max_harmonic),
+            -1,
+        );
+    }
+    write_measurement(
+        dsp::thd(samples, max_harmonic as usize, window),
+        out_value,
+        "no fundamental found",
+    )
+}
+
+unsafe fn write_measurement(value: Option<f64>, out_value: *mut c_double, missing: &str) -> c_int {
+    if out_value.is_null() {
+        return fail(WaveformErrorCode::NullPointer, "out_value is NULL", -1);
+    }
+    match value {
+        Some(v) => {
+            *out_value = v;
+            succeed(0)
+        }
+        None => fail(WaveformErrorCode::NoResult, missing, -1),
     }
 }
 
@@ -608,6 +1872,7 @@ pub unsafe extern "C" fn waveform_stream_get_signal_data(
 // Legacy API aliases
 // ============================================================================
 
+/// Legacy alias for waveform_read()
 #[no_mangle]
 pub unsafe extern "C" fn hspice_read(
     filename: *const c_char,
@@ -616,13 +1881,672 @@ pub unsafe extern "C" fn hspice_read(
     waveform_read(filename, debug)
 }
 
+/// Legacy alias for waveform_free()
 #[no_mangle]
 pub unsafe extern "C" fn hspice_result_free(result: *mut CWaveformResult) {
     waveform_free(result)
 }
 
-/// Legacy alias for waveform_init_logging
+/// Legacy alias for waveform_init_logging()
 #[no_mangle]
 pub unsafe extern "C" fn hspice_init_logging(level: *const c_char) -> c_int {
     waveform_init_logging(level)
 }
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hspice_core::testing::{temp_path, SyntheticWaveform};
+    use hspice_core::{read_bytes_with_options, ReadOptions};
+
+    fn last_message() -> String {
+        let message = waveform_last_error_message();
+        assert!(!message.is_null());
+        unsafe { CStr::from_ptr(message) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn test_abi_version_encoding() {
+        let version = waveform_abi_version();
+        assert_eq!(version >> 16, WAVEFORM_ABI_VERSION_MAJOR);
+        assert_eq!((version >> 8) & 0xff, WAVEFORM_ABI_VERSION_MINOR);
+        assert_eq!(version & 0xff, WAVEFORM_ABI_VERSION_PATCH);
+    }
+
+    #[test]
+    fn test_read_missing_file_sets_io_error() {
+        let path = CString::new("/nonexistent/missing.tr0").unwrap();
+        let result = unsafe { waveform_read(path.as_ptr(), 0) };
+        assert!(result.is_null());
+        assert_eq!(waveform_last_error_code(), WaveformErrorCode::Io as c_int);
+        assert!(
+            last_message().starts_with("waveform_read: opening /nonexistent/missing.tr0: IO error")
+        );
+
+        waveform_clear_error();
+        assert_eq!(waveform_last_error_code(), WaveformErrorCode::Ok as c_int);
+        assert!(waveform_last_error_message().is_null());
+    }
+
+    #[test]
+    fn test_success_clears_error_and_accessors_report_codes() {
+        let path = temp_path("ffi_errors.tr0");
+        SyntheticWaveform::new()
+            .signals(2)
+            .points(5)
+            .write_tr0(&path)
+            .unwrap();
+        let c_path = CString::new(path.to_str().unwrap()).unwrap();
+
+        unsafe {
+            assert_eq!(waveform_get_var_count(ptr::null()), 0);
+            assert_eq!(
+                waveform_last_error_code(),
+                WaveformErrorCode::NullPointer as c_int
+            );
+
+            let result = waveform_read(c_path.as_ptr(), 0);
+            assert!(!result.is_null());
+            assert_eq!(waveform_last_error_code(), WaveformErrorCode::Ok as c_int);
+
+            assert!(waveform_get_var_name(result, 7).is_null());
+            assert_eq!(
+                waveform_last_error_code(),
+                WaveformErrorCode::IndexOutOfRange as c_int
+            );
+            assert_eq!(last_message(), "variable index 7 out of range (count 3)");
+
+            let (mut re, mut im) = ([0.0; 5], [0.0; 5]);
+            let n = waveform_get_complex_data(result, 0, 1, re.as_mut_ptr(), im.as_mut_ptr(), 5);
+            assert_eq!(n, -1);
+            assert_eq!(
+                waveform_last_error_code(),
+                WaveformErrorCode::TypeMismatch as c_int
+            );
+
+            waveform_free(result);
+        }
+        std::fs::remove_file(path).ok();
+    }
+
+    /// Read a synthetic file through the C API
+    fn read_synthetic(name: &str, synth: SyntheticWaveform) -> *mut CWaveformResult {
+        let path = temp_path(name);
+        synth.write_tr0(&path).unwrap();
+        let c_path = CString::new(path.to_str().unwrap()).unwrap();
+        let result = unsafe { waveform_read(c_path.as_ptr(), 0) };
+        std::fs::remove_file(path).ok();
+        assert!(!result.is_null());
+        result
+    }
+
+    unsafe extern "C" fn record_progress(
+        progress: *const WaveformProgress,
+        user_data: *mut c_void,
+    ) {
+        let seen = &mut *(user_data as *mut Vec<WaveformProgress>);
+        seen.push(*progress);
+    }
+
+    #[test]
+    fn test_read_with_progress() {
+        let path = temp_path("ffi_progress.tr0");
+        SyntheticWaveform::new()
+            .signals(2)
+            .points(100)
+            .block_size(30)
+            .write_tr0(&path)
+            .unwrap();
+        let size = std::fs::metadata(&path).unwrap().len();
+        let c_path = CString::new(path.to_str().unwrap()).unwrap();
+
+        let mut seen: Vec<WaveformProgress> = Vec::new();
+        unsafe {
+            let result = waveform_read_with_progress(
+                c_path.as_ptr(),
+                Some(record_progress),
+                &mut seen as *mut _ as *mut c_void,
+            );
+            assert!(!result.is_null());
+            assert_eq!(waveform_get_point_count(result), 100);
+            waveform_free(result);
+
+            let result = waveform_read_with_progress(c_path.as_ptr(), None, ptr::null_mut());
+            assert!(!result.is_null());
+            waveform_free(result);
+        }
+        std::fs::remove_file(path).ok();
+
+        let last = seen.last().unwrap();
+        assert_eq!((last.bytes, last.total_bytes), (size, size));
+        assert_eq!((last.blocks, last.rows), (11, 100));
+    }
+
+    #[test]
+    fn test_stats() {
+        let synth = SyntheticWaveform::new()
+            .signals(2)
+            .points(50)
+            .sweep("temp", vec![25.0, 85.0]);
+        let expected = synth.expected();
+        let result = read_synthetic("ffi_stats.sw0", synth);
+        let mut stats = WaveformStats {
+            count: 0,
+            min: 0.0,
+            max: 0.0,
+            mean: 0.0,
+            rms: 0.0,
+            std: 0.0,
+            peak_to_peak: 0.0,
+            magnitude: -1,
+        };
+        unsafe {
+            assert_eq!(waveform_get_stats(result, 1, 2, &mut stats), 0);
+            let table = expected.tables[1].vectors[2].stats().unwrap();
+            assert_eq!((stats.count, stats.mean), (50, table.mean));
+            assert_eq!(stats.peak_to_peak, table.peak_to_peak());
+            assert_eq!(stats.magnitude, 0);
+
+            assert_eq!(waveform_get_stats(result, -1, 2, &mut stats), 0);
+            assert_eq!(stats.count, 100);
+            assert_eq!(stats.max, expected.stats()[2].unwrap().max);
+
+            assert_eq!(waveform_get_stats(result, 2, 2, &mut stats), -1);
+            assert_eq!(waveform_get_stats(result, 0, 2, ptr::null_mut()), -1);
+            assert_eq!(
+                waveform_last_error_code(),
+                WaveformErrorCode::NullPointer as c_int
+            );
+            waveform_free(result);
+        }
+    }
+
+    #[test]
+    fn test_real_data_ptr_matches_copy() {
+        let synth = SyntheticWaveform::new().signals(2).points(256).hold(64);
+        let expected = synth.expected();
+        let options = ReadOptions::new().compact_steps(true);
+        let result = into_handle(read_bytes_with_options(&synth.to_tr0_bytes(), &options).unwrap());
+
+        unsafe {
+            for var in 0..3 {
+                let mut len = 0;
+                let data = waveform_get_real_data_ptr(result, 0, var, &mut len);
+                assert!(!data.is_null());
+                let values = std::slice::from_raw_parts(data, len as usize);
+                let want = expected.tables[0].vectors[var as usize].to_real().unwrap();
+                assert_eq!(values, &want[..]);
+            }
+
+            // Stepped signals are expanded once and keep their address
+            let handle = &*result;
+            assert!(handle.inner.tables[0].vectors[1].is_stepped());
+            let mut len = 0;
+            let first = waveform_get_real_data_ptr(result, 0, 1, &mut len);
+            let second = waveform_get_real_data_ptr(result, 0, 1, &mut len);
+            assert_eq!(first, second);
+
+            assert!(waveform_get_complex_data_ptr(result, 0, 1, &mut len).is_null());
+            assert_eq!(
+                waveform_last_error_code(),
+                WaveformErrorCode::TypeMismatch as c_int
+            );
+            assert!(waveform_get_real_data_ptr(result, 0, 1, ptr::null_mut()).is_null());
+            waveform_free(result);
+        }
+    }
+
+    #[test]
+    fn test_signal_all_sweeps() {
+        let synth = SyntheticWaveform::new()
+            .signals(2)
+            .points(6)
+            .sweep("temp", vec![0.0, 25.0, 85.0]);
+        let expected = synth.expected();
+        let result = read_synthetic("ffi_all_sweeps.sw0", synth);
+        let row = |t: usize| expected.tables[t].vectors[2].to_real().unwrap().to_vec();
+
+        unsafe {
+            let mut buffer = [0.0; 24];
+            assert_eq!(
+                waveform_get_signal_all_sweeps(result, 2, buffer.as_mut_ptr(), 8, 24),
+                3
+            );
+            for t in 0..3 {
+                assert_eq!(buffer[t * 8..t * 8 + 6], row(t)[..]);
+                assert!(buffer[t * 8 + 6..t * 8 + 8].iter().all(|x| x.is_nan()));
+            }
+
+            // Short rows are cut, and only whole rows fit
+            let mut buffer = [0.0; 10];
+            assert_eq!(
+                waveform_get_signal_all_sweeps(result, 2, buffer.as_mut_ptr(), 4, 10),
+                2
+            );
+            assert_eq!(buffer[4..8], row(1)[..4]);
+            assert_eq!(buffer[8..], [0.0; 2]);
+
+            assert_eq!(
+                waveform_get_signal_all_sweeps(result, 2, buffer.as_mut_ptr(), 0, 10),
+                -1
+            );
+            assert_eq!(
+                waveform_last_error_code(),
+                WaveformErrorCode::InvalidArgument as c_int
+            );
+            assert_eq!(
+                waveform_get_signal_all_sweeps(result, 3, buffer.as_mut_ptr(), 4, 10),
+                -1
+            );
+            waveform_free(result);
+        }
+    }
+
+    #[test]
+    fn test_complex_data_ptr_is_interleaved() {
+        let synth = SyntheticWaveform::new().signals(1).points(8).complex(true);
+        let result = read_synthetic("ffi_ptr_complex.tr0", synth);
+
+        unsafe {
+            let mut len = 0;
+            let data = waveform_get_complex_data_ptr(result, 0, 1, &mut len);
+            assert!(!data.is_null());
+            let (mut re, mut im) = ([0.0; 8], [0.0; 8]);
+            let n = waveform_get_complex_data(result, 0, 1, re.as_mut_ptr(), im.as_mut_ptr(), 8);
+            assert_eq!(n, len);
+
+            let values = std::slice::from_raw_parts(data, 2 * len as usize);
+            for i in 0..len as usize {
+                assert_eq!((values[2 * i], values[2 * i + 1]), (re[i], im[i]));
+            }
+            waveform_free(result);
+        }
+    }
+
+    #[test]
+    fn test_metadata_accessors() {
+        let synth = SyntheticWaveform::new()
+            .signal_names(&["out", "i(vdd)"])
+            .points(4)
+            .sweep("temp", vec![25.0, 85.0]);
+        let result = read_synthetic("ffi_metadata.tr0", synth);
+
+        let text = |s: *const c_char| unsafe { CStr::from_ptr(s) }.to_str().unwrap();
+        unsafe {
+            assert_eq!(text(waveform_get_analysis_name(result)), "transient");
+            assert_eq!(text(waveform_get_var_unit(result, 0)), "s");
+            // Node voltages are stored as bare names, typed from the header
+            assert_eq!(text(waveform_get_var_unit(result, 1)), "V");
+            assert_eq!(text(waveform_get_var_unit(result, 2)), "A");
+            assert!(waveform_get_var_unit(result, 3).is_null());
+
+            assert_eq!(waveform_get_table_point_count(result, 1), 4);
+            assert_eq!(waveform_get_table_point_count(result, 2), -1);
+            assert_eq!(
+                waveform_last_error_code(),
+                WaveformErrorCode::IndexOutOfRange as c_int
+            );
+            waveform_free(result);
+        }
+    }
+
+    #[test]
+    fn test_stream_filter_reset_and_seek() {
+        let path = temp_path("ffi_stream_seek.tr0");
+        let synth = SyntheticWaveform::new().signals(3).points(500);
+        synth.write_tr0(&path).unwrap();
+        let expected = synth.expected();
+        let scale = expected.tables[0].vectors[0].to_real().unwrap();
+        let names: Vec<CString> = expected.variables[1..]
+            .iter()
+            .map(|v| CString::new(v.name.as_str()).unwrap())
+            .collect();
+        let c_path = CString::new(path.to_str().unwrap()).unwrap();
+
+        unsafe {
+            let stream = waveform_stream_open(c_path.as_ptr(), 100, 0);
+            assert!(!stream.is_null());
+
+            let wanted = [names[1].as_ptr()];
+            assert_eq!(waveform_stream_set_signals(stream, wanted.as_ptr(), 1), 0);
+            let unknown = c"nope";
+            let bad = [unknown.as_ptr()];
+            assert_eq!(waveform_stream_set_signals(stream, bad.as_ptr(), 1), -1);
+            assert_eq!(
+                waveform_last_error_code(),
+                WaveformErrorCode::NotFound as c_int
+            );
+
+            assert_eq!(waveform_stream_seek_time(stream, scale[250]), 0);
+            assert_eq!(waveform_stream_next(stream), 1);
+            let (mut start, mut end) = (0.0, 0.0);
+            waveform_stream_get_time_range(stream, &mut start, &mut end);
+            assert_eq!(start, scale[250]);
+
+            let mut buffer = [0.0; 500];
+            let n = waveform_stream_get_signal_data(
+                stream,
+                names[1].as_ptr(),
+                buffer.as_mut_ptr(),
+                500,
+            );
+            assert!(n > 0);
+            let n = waveform_stream_get_signal_data(
+                stream,
+                names[0].as_ptr(),
+                buffer.as_mut_ptr(),
+                500,
+            );
+            assert_eq!(n, -1);
+
+            assert_eq!(waveform_stream_reset(stream), 0);
+            assert_eq!(waveform_stream_get_chunk_size(stream), 0);
+            assert_eq!(waveform_stream_set_signals(stream, ptr::null(), 0), 0);
+            assert_eq!(waveform_stream_next(stream), 1);
+            waveform_stream_get_time_range(stream, &mut start, &mut end);
+            assert_eq!(start, scale[0]);
+            let n = waveform_stream_get_signal_data(
+                stream,
+                names[0].as_ptr(),
+                buffer.as_mut_ptr(),
+                500,
+            );
+            assert!(n > 0);
+
+            waveform_stream_close(stream);
+        }
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_select_by_pattern() {
+        let path = temp_path("ffi_select.tr0");
+        SyntheticWaveform::new()
+            .signals(12)
+            .points(20)
+            .write_tr0(&path)
+            .unwrap();
+        let c_path = CString::new(path.to_str().unwrap()).unwrap();
+
+        unsafe {
+            let result = waveform_read(c_path.as_ptr(), 0);
+            let selected = waveform_select(result, c"v(n1?)".as_ptr());
+            assert!(!selected.is_null());
+            assert_eq!(waveform_get_var_count(selected), 3);
+            let name = CStr::from_ptr(waveform_get_var_name(selected, 1));
+            assert_eq!(name.to_str().unwrap(), "n10");
+            assert!(waveform_select(result, c"re:(".as_ptr()).is_null());
+            assert_eq!(
+                waveform_last_error_code(),
+                WaveformErrorCode::Parse as c_int
+            );
+            waveform_free(selected);
+            waveform_free(result);
+
+            let stream = waveform_stream_open(c_path.as_ptr(), 100, 0);
+            assert_eq!(
+                waveform_stream_set_signal_pattern(stream, c"re:^n[02]$".as_ptr()),
+                2
+            );
+            assert_eq!(
+                waveform_stream_set_signal_pattern(stream, c"x*".as_ptr()),
+                -1
+            );
+            assert_eq!(
+                waveform_last_error_code(),
+                WaveformErrorCode::NotFound as c_int
+            );
+            waveform_stream_close(stream);
+        }
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_clone_outlives_original_across_threads() {
+        let synth = SyntheticWaveform::new().signals(2).points(300).hold(64);
+        let expected = synth.expected();
+        let result = read_synthetic("ffi_clone.tr0", synth);
+
+        let copy = unsafe { waveform_result_clone(result) } as usize;
+        unsafe { waveform_free(result) };
+
+        // Several threads read the copy at once, including the lazily
+        // expanded stepped vector
+        let lens: Vec<c_int> = (0..4)
+            .map(|_| {
+                std::thread::spawn(move || unsafe {
+                    let copy = copy as *const CWaveformResult;
+                    let mut len = 0;
+                    assert!(!waveform_get_real_data_ptr(copy, 0, 1, &mut len).is_null());
+                    len
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|t| t.join().unwrap())
+            .collect();
+        assert_eq!(lens, [300; 4]);
+
+        unsafe {
+            let copy = copy as *mut CWaveformResult;
+            let mut len = 0;
+            let data = waveform_get_real_data_ptr(copy, 0, 2, &mut len);
+            let values = std::slice::from_raw_parts(data, len as usize);
+            assert_eq!(
+                values,
+                &expected.tables[0].vectors[2].to_real().unwrap()[..]
+            );
+            waveform_free(copy);
+
+            assert!(waveform_result_clone(ptr::null()).is_null());
+        }
+    }
+
+    #[test]
+    fn test_stream_calls_from_several_threads() {
+        let path = temp_path("ffi_stream_threads.tr0");
+        SyntheticWaveform::new()
+            .signals(2)
+            .points(1000)
+            .block_size(50)
+            .write_tr0(&path)
+            .unwrap();
+        let chunks = read_stream_chunked(path.to_str().unwrap(), 100)
+            .unwrap()
+            .count() as c_int;
+        assert!(chunks > 4);
+        let c_path = CString::new(path.to_str().unwrap()).unwrap();
+        let stream = unsafe { waveform_stream_open(c_path.as_ptr(), 100, 0) } as usize;
+
+        // Every chunk is handed to exactly one thread
+        let seen: Vec<c_int> = (0..4)
+            .map(|_| {
+                std::thread::spawn(move || unsafe {
+                    let stream = stream as *mut CWaveformStream;
+                    let mut seen = 0;
+                    while waveform_stream_next(stream) == 1 {
+                        seen += 1;
+                    }
+                    seen
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|t| t.join().unwrap())
+            .collect();
+        assert_eq!(seen.iter().sum::<c_int>(), chunks);
+
+        unsafe { waveform_stream_close(stream as *mut CWaveformStream) };
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_convert_to_raw() {
+        let input = temp_path("ffi_convert.tr0");
+        let output = temp_path("ffi_convert.raw");
+        let synth = SyntheticWaveform::new().signals(2).points(40);
+        synth.write_tr0(&input).unwrap();
+        let c_input = CString::new(input.to_str().unwrap()).unwrap();
+        let c_output = CString::new(output.to_str().unwrap()).unwrap();
+
+        unsafe {
+            assert_eq!(
+                waveform_convert_to_raw(c_input.as_ptr(), c_output.as_ptr()),
+                0
+            );
+            let raw = waveform_read_raw(c_output.as_ptr(), 0);
+            assert!(!raw.is_null());
+            assert_eq!(waveform_get_point_count(raw), 40);
+            assert_eq!(waveform_get_var_count(raw), 3);
+            waveform_free(raw);
+
+            assert_eq!(waveform_convert_to_raw(c_input.as_ptr(), ptr::null()), -1);
+            assert_eq!(
+                waveform_last_error_code(),
+                WaveformErrorCode::NullPointer as c_int
+            );
+        }
+        std::fs::remove_file(input).ok();
+        std::fs::remove_file(output).ok();
+    }
+
+    #[test]
+    fn test_export_csv() {
+        let synth = SyntheticWaveform::new()
+            .signals(3)
+            .points(10)
+            .sweep("temp", vec![25.0, 85.0]);
+        let expected = synth.expected();
+        let result = read_synthetic("ffi_export.sw0", synth);
+        let output = temp_path("ffi_export.csv");
+        let c_output = CString::new(output.to_str().unwrap()).unwrap();
+
+        unsafe {
+            assert_eq!(
+                waveform_export_csv(result, c_output.as_ptr(), ptr::null()),
+                0
+            );
+            let mut want = Vec::new();
+            expected.write_csv(&mut want).unwrap();
+            assert_eq!(std::fs::read(&output).unwrap(), want);
+
+            let options = WaveformCsvOptions {
+                pattern: c"n1".as_ptr(),
+                table_index: 1,
+            };
+            assert_eq!(waveform_export_csv(result, c_output.as_ptr(), &options), 0);
+            let csv = std::fs::read_to_string(&output).unwrap();
+            let lines: Vec<&str> = csv.lines().collect();
+            assert_eq!(lines[0], format!("{},n1", expected.scale_name()));
+            assert_eq!(lines.len(), 11);
+            let last: Vec<f64> = lines[10].split(',').map(|f| f.parse().unwrap()).collect();
+            assert_eq!(Some(last[1]), expected.tables[1].vectors[2].real_at(9));
+
+            let options = WaveformCsvOptions {
+                pattern: ptr::null(),
+                table_index: 2,
+            };
+            assert_eq!(waveform_export_csv(result, c_output.as_ptr(), &options), -1);
+            assert_eq!(
+                waveform_last_error_code(),
+                WaveformErrorCode::IndexOutOfRange as c_int
+            );
+            let bad = c"/nonexistent/out.csv";
+            assert_eq!(waveform_export_csv(result, bad.as_ptr(), ptr::null()), -1);
+            assert_eq!(waveform_last_error_code(), WaveformErrorCode::Io as c_int);
+            waveform_free(result);
+        }
+        std::fs::remove_file(output).ok();
+    }
+
+    #[test]
+    fn test_read_from_memory() {
+        let synth = SyntheticWaveform::new()
+            .signals(2)
+            .points(300)
+            .block_size(64);
+        let expected = synth.expected();
+        let bytes = synth.to_tr0_bytes();
+
+        unsafe {
+            let result = waveform_read_from_memory(bytes.as_ptr(), bytes.len());
+            assert!(!result.is_null());
+            assert_eq!(waveform_get_point_count(result), 300);
+            let mut len = 0;
+            let data = waveform_get_real_data_ptr(result, 0, 2, &mut len);
+            let values = std::slice::from_raw_parts(data, len as usize);
+            assert_eq!(
+                values,
+                &expected.tables[0].vectors[2].to_real().unwrap()[..]
+            );
+            waveform_free(result);
+
+            // The stream keeps its own copy of the buffer
+            let copy = bytes.clone();
+            let stream = waveform_stream_open_from_memory(copy.as_ptr(), copy.len(), 100);
+            drop(copy);
+            assert!(!stream.is_null());
+            let mut points = 0;
+            while waveform_stream_next(stream) == 1 {
+                points += waveform_stream_get_chunk_size(stream);
+            }
+            assert_eq!(points, 300);
+            waveform_stream_close(stream);
+
+            assert!(waveform_read_from_memory(bytes.as_ptr(), 40).is_null());
+            assert_eq!(
+                waveform_last_error_code(),
+                WaveformErrorCode::Format as c_int
+            );
+            assert!(waveform_read_from_memory(ptr::null(), 0).is_null());
+            assert_eq!(
+                waveform_last_error_code(),
+                WaveformErrorCode::NullPointer as c_int
+            );
+            assert!(waveform_stream_open_from_memory(bytes.as_ptr(), bytes.len(), 0).is_null());
+        }
+    }
+
+    #[test]
+    fn test_error_state_is_per_thread() {
+        unsafe { waveform_get_title(ptr::null()) };
+        assert_eq!(
+            waveform_last_error_code(),
+            WaveformErrorCode::NullPointer as c_int
+        );
+        let other = std::thread::spawn(|| waveform_last_error_code())
+            .join()
+            .unwrap();
+        assert_eq!(other, WaveformErrorCode::Ok as c_int);
+    }
+
+    #[test]
+    fn test_format_error_code() {
+        let err = WaveformError::TruncatedBlock {
+            offset: 0,
+            needed: 4,
+            available: 0,
+        };
+        assert_eq!(WaveformErrorCode::from(&err), WaveformErrorCode::Format);
+        let err = WaveformError::LimitExceeded {
+            what: "signals",
+            found: 2,
+            limit: 1,
+        };
+        assert_eq!(
+            WaveformErrorCode::from(&err),
+            WaveformErrorCode::LimitExceeded
+        );
+        let err = err.context("reading header").context("reading a.tr0");
+        assert_eq!(
+            WaveformErrorCode::from(&err),
+            WaveformErrorCode::LimitExceeded
+        );
+    }
+}
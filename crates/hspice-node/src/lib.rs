@@ -0,0 +1,339 @@
+//! Node.js bindings for waveform file parser (N-API via napi-rs)
+//!
+//! Files are read natively with memory mapping, so Electron viewers are not
+//! bound by WASM's 4 GB address space. Signals are returned as
+//! `Float64Array`s that take ownership of the decoded Rust buffers instead
+//! of copying them.
+
+use hspice_core::{
+    AnalysisType, DataChunk, HspiceStreamReader, VarType, VectorData, WaveformError,
+    WaveformResult, DEFAULT_CHUNK_SIZE,
+};
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use std::collections::HashMap;
+
+fn to_napi(e: WaveformError) -> Error {
+    Error::from_reason(e.to_string())
+}
+
+// ============================================================================
+// Reading
+// ============================================================================
+
+/// Read an HSPICE binary file (.tr0, .ac0, .sw0)
+#[napi]
+pub fn read(path: String) -> Result<Waveform> {
+    hspice_core::read(&path)
+        .map(Waveform::from)
+        .map_err(to_napi)
+}
+
+/// Read a SPICE3/ngspice raw file (binary or ASCII)
+#[napi]
+pub fn read_raw(path: String) -> Result<Waveform> {
+    hspice_core::read_raw(&path)
+        .map(Waveform::from)
+        .map_err(to_napi)
+}
+
+/// Read an HSPICE or SPICE3 raw file, detecting the format
+#[napi]
+pub fn read_any(path: String) -> Result<Waveform> {
+    hspice_core::read_any(&path)
+        .map(Waveform::from)
+        .map_err(to_napi)
+}
+
+/// Variable metadata
+#[napi(object)]
+pub struct Variable {
+    pub name: String,
+    /// "time" | "frequency" | "voltage" | "current" | "power" | "parameter" |
+    /// "internal" | "noise" | "unknown"
+    pub var_type: String,
+    /// "s" | "Hz" | "V" | "A" | "W" | "C" | "V/rtHz" | "A/rtHz" | "V^2/Hz", or
+    /// `null` if unknown
+    pub unit: Option<String>,
+    pub is_complex: bool,
+}
+
+/// Complex signal as parallel real and imaginary arrays
+#[napi(object)]
+pub struct ComplexSignal {
+    pub re: Float64Array,
+    pub im: Float64Array,
+}
+
+/// Parsed waveform file
+#[napi]
+pub struct Waveform {
+    inner: WaveformResult,
+}
+
+impl From<WaveformResult> for Waveform {
+    fn from(inner: WaveformResult) -> Self {
+        Self { inner }
+    }
+}
+
+#[napi]
+impl Waveform {
+    #[napi(getter)]
+    pub fn title(&self) -> String {
+        self.inner.title.clone()
+    }
+
+    #[napi(getter)]
+    pub fn date(&self) -> String {
+        self.inner.date.clone()
+    }
+
+    /// "transient" | "ac" | "dc" | "operating" | "noise" | "unknown"
+    #[napi(getter)]
+    pub fn analysis(&self) -> String {
+        analysis_name(self.inner.analysis).to_string()
+    }
+
+    #[napi(getter)]
+    pub fn scale_name(&self) -> String {
+        self.inner.scale_name().to_string()
+    }
+
+    #[napi(getter)]
+    pub fn sweep_param(&self) -> Option<String> {
+        self.inner.sweep_param.clone()
+    }
+
+    /// Number of data points in the first table
+    #[napi(getter)]
+    pub fn length(&self) -> u32 {
+        self.inner.len() as u32
+    }
+
+    #[napi(getter)]
+    pub fn num_tables(&self) -> u32 {
+        self.inner.tables.len() as u32
+    }
+
+    /// Sweep value of each table (`null` if not swept)
+    #[napi]
+    pub fn sweep_values(&self) -> Vec<Option<f64>> {
+        self.inner.tables.iter().map(|t| t.sweep_value).collect()
+    }
+
+    #[napi]
+    pub fn variables(&self) -> Vec<Variable> {
+        self.inner
+            .variables
+            .iter()
+            .enumerate()
+            .map(|(i, var)| Variable {
+                name: var.name.clone(),
+                var_type: var_type_name(var.var_type).to_string(),
+                unit: var.unit.map(|u| u.symbol().to_string()),
+                is_complex: self
+                    .inner
+                    .tables
+                    .first()
+                    .is_some_and(|t| t.vectors[i].is_complex()),
+            })
+            .collect()
+    }
+
+    /// All signal names, scale first
+    #[napi]
+    pub fn signal_names(&self) -> Vec<String> {
+        self.inner
+            .variables
+            .iter()
+            .map(|v| v.name.clone())
+            .collect()
+    }
+
+    /// Real signal data from sweep table `table` (default 0)
+    ///
+    /// Throws for unknown or complex signals; use `signalComplex` for AC data.
+    #[napi]
+    pub fn signal(&self, name: String, table: Option<u32>) -> Result<Float64Array> {
+        match self.vector(&name, table)? {
+            VectorData::Complex(_) => Err(Error::from_reason(format!(
+                "{} is complex; use signalComplex()",
+                name
+            ))),
+            vector => Ok(Float64Array::new(
+                vector.to_real().unwrap_or_default().into_owned(),
+            )),
+        }
+    }
+
+    /// Complex signal data as `{re, im}`; real signals get a zero `im`
+    #[napi]
+    pub fn signal_complex(&self, name: String, table: Option<u32>) -> Result<ComplexSignal> {
+        let vector = self.vector(&name, table)?;
+        let (re, im) = match vector {
+            VectorData::Complex(v) => v.iter().map(|c| (c.re, c.im)).unzip(),
+            real => {
+                let re = real.to_real().unwrap_or_default().into_owned();
+                let im = vec![0.0; re.len()];
+                (re, im)
+            }
+        };
+        Ok(ComplexSignal {
+            re: Float64Array::new(re),
+            im: Float64Array::new(im),
+        })
+    }
+
+    fn vector(&self, name: &str, table: Option<u32>) -> Result<&VectorData> {
+        let idx = self
+            .inner
+            .var_index(name)
+            .ok_or_else(|| Error::from_reason(format!("Signal not found: {}", name)))?;
+        let table = table.unwrap_or(0) as usize;
+        let table = self
+            .inner
+            .tables
+            .get(table)
+            .ok_or_else(|| Error::from_reason(format!("No data table {}", table)))?;
+        Ok(&table.vectors[idx])
+    }
+}
+
+// ============================================================================
+// Streaming
+// ============================================================================
+
+/// One chunk from a `WaveformStream`
+#[napi(object)]
+pub struct StreamChunk {
+    pub chunk_index: u32,
+    /// Sweep table the rows belong to
+    pub table_index: u32,
+    /// Sweep value of that table, unset if the file is not swept
+    pub sweep_value: Option<f64>,
+    /// `[start, end]` of the scale in this chunk
+    pub time_range: Vec<f64>,
+    /// Real signals by name; complex signals appear as `<name>.re` and
+    /// `<name>.im`
+    pub signals: HashMap<String, Float64Array>,
+}
+
+/// Open an HSPICE file for chunked reading
+#[napi]
+pub fn stream(
+    path: String,
+    chunk_size: Option<u32>,
+    signals: Option<Vec<String>>,
+) -> Result<WaveformStream> {
+    WaveformStream::new(path, chunk_size, signals)
+}
+
+/// Chunked reader; only one chunk is decoded at a time
+#[napi]
+pub struct WaveformStream {
+    reader: HspiceStreamReader,
+}
+
+#[napi]
+impl WaveformStream {
+    #[napi(constructor)]
+    pub fn new(
+        path: String,
+        chunk_size: Option<u32>,
+        signals: Option<Vec<String>>,
+    ) -> Result<Self> {
+        let chunk_size = chunk_size.map_or(DEFAULT_CHUNK_SIZE, |n| n as usize);
+        let mut reader = HspiceStreamReader::open(&path, chunk_size).map_err(to_napi)?;
+        reader.set_signals(signals);
+        Ok(Self { reader })
+    }
+
+    /// Next chunk, or `null` at the end of the file
+    #[napi]
+    pub fn next_chunk(&mut self) -> Result<Option<StreamChunk>> {
+        match self.reader.next() {
+            Some(chunk) => chunk.map(|c| Some(to_js_chunk(c))).map_err(to_napi),
+            None => Ok(None),
+        }
+    }
+
+    /// Rewind to the first chunk
+    #[napi]
+    pub fn reset(&mut self) {
+        self.reader.reset();
+    }
+
+    /// Make the next chunk start at the first point at or after `time`
+    #[napi]
+    pub fn seek_time(&mut self, time: f64) -> Result<()> {
+        self.reader.seek_time(time).map_err(to_napi)
+    }
+
+    #[napi(getter)]
+    pub fn scale_name(&self) -> String {
+        self.reader.metadata().scale_name
+    }
+
+    /// Names of all signals except the scale
+    #[napi(getter)]
+    pub fn signal_names(&self) -> Vec<String> {
+        self.reader.metadata().signal_names
+    }
+}
+
+fn to_js_chunk(chunk: DataChunk) -> StreamChunk {
+    let mut signals = HashMap::with_capacity(chunk.data.len());
+    for (name, vector) in chunk.data {
+        match vector {
+            VectorData::Complex(v) => {
+                let (re, im): (Vec<f64>, Vec<f64>) = v.iter().map(|c| (c.re, c.im)).unzip();
+                signals.insert(format!("{}.re", name), Float64Array::new(re));
+                signals.insert(format!("{}.im", name), Float64Array::new(im));
+            }
+            VectorData::Real(v) => {
+                signals.insert(name, Float64Array::new(v.into_vec()));
+            }
+            other => {
+                let values = other.to_real().unwrap_or_default().into_owned();
+                signals.insert(name, Float64Array::new(values));
+            }
+        }
+    }
+    StreamChunk {
+        chunk_index: chunk.chunk_index as u32,
+        table_index: chunk.table_index as u32,
+        sweep_value: chunk.sweep_value,
+        time_range: vec![chunk.time_range.0, chunk.time_range.1],
+        signals,
+    }
+}
+
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+fn analysis_name(analysis: AnalysisType) -> &'static str {
+    match analysis {
+        AnalysisType::Transient => "transient",
+        AnalysisType::AC => "ac",
+        AnalysisType::DC => "dc",
+        AnalysisType::Operating => "operating",
+        AnalysisType::Noise => "noise",
+        AnalysisType::Unknown => "unknown",
+    }
+}
+
+fn var_type_name(var_type: VarType) -> &'static str {
+    match var_type {
+        VarType::Time => "time",
+        VarType::Frequency => "frequency",
+        VarType::Voltage => "voltage",
+        VarType::Current => "current",
+        VarType::Power => "power",
+        VarType::Parameter => "parameter",
+        VarType::Internal => "internal",
+        VarType::Noise => "noise",
+        VarType::Unknown => "unknown",
+    }
+}
@@ -2,12 +2,25 @@
 //!
 //! This crate provides PyO3 bindings to expose hspice-core to Python.
 
-use hspice_core::{self, DataTable, Variable, VectorData, WaveformResult};
-use numpy::ndarray::Array1;
-use numpy::IntoPyArray;
+use hspice_core::expr::Expr;
+use hspice_core::mat::to_mat;
+use hspice_core::measure::{self, Edge};
+use hspice_core::measure_file::MeasureResult;
+use hspice_core::npz::to_npz;
+use hspice_core::{
+    self, DataChunk, DataTable, LazyWaveform, NonMonotonic, Progress, RawEncoding, ReadOptions,
+    SignalStats, Variable, VectorData, WaveformResult,
+};
+use numpy::ndarray::ArrayView1;
+use numpy::{IntoPyArray, PyArray1};
+use pyo3::exceptions::{PyIndexError, PyKeyError, PyValueError};
 use pyo3::prelude::*;
 use pyo3::types::{PyDict, PyList};
-use std::sync::Once;
+use pyo3::IntoPyObjectExt;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Once, RwLock};
 
 // ============================================================================
 // Logging Initialization
@@ -45,19 +58,29 @@ pub fn init_logging(level: &str) -> PyResult<()> {
 // ============================================================================
 
 /// Python wrapper for Variable
-#[pyclass(name = "Variable")]
-#[derive(Clone)]
+///
+/// Compares and hashes by value, so variables work as dict keys and in sets.
+#[pyclass(name = "Variable", frozen, eq, hash)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub struct PyVariable {
     #[pyo3(get)]
     pub name: String,
     #[pyo3(get)]
     pub var_type: String,
+    /// Unit symbol ("V", "A", "s", "Hz", "W", "C", "V/rtHz", ...) or None
+    #[pyo3(get)]
+    pub unit: Option<String>,
 }
 
 #[pymethods]
 impl PyVariable {
-    fn __repr__(&self) -> String {
-        format!("Variable(name='{}', type='{}')", self.name, self.var_type)
+    fn __repr__(&self, py: Python) -> PyResult<String> {
+        Ok(format!(
+            "Variable(name={}, var_type={}, unit={})",
+            py_repr(py, &self.name)?,
+            py_repr(py, &self.var_type)?,
+            py_repr(py, &self.unit)?
+        ))
     }
 }
 
@@ -66,26 +89,34 @@ impl From<&Variable> for PyVariable {
         PyVariable {
             name: v.name.clone(),
             var_type: v.var_type.to_string(),
+            unit: v.unit.map(|u| u.symbol().to_string()),
         }
     }
 }
 
 /// Python wrapper for DataTable
-#[pyclass(name = "DataTable")]
+///
+/// Owns the decoded vectors. Arrays returned by `get` are read-only numpy
+/// views of them that keep the table alive, so no sample data is copied.
+/// Tables compare equal when their sweep value, signal names and values
+/// are equal, however the values are stored.
+#[pyclass(name = "DataTable", frozen, eq)]
 pub struct PyDataTable {
     #[pyo3(get)]
     pub sweep_value: Option<f64>,
+    sweep_param: Option<String>,
     vectors: Vec<VectorData>,
-    var_names: Vec<String>,
+    var_names: Arc<[String]>,
 }
 
 #[pymethods]
 impl PyDataTable {
     /// Get signal data by name
-    fn get<'py>(&self, py: Python<'py>, name: &str) -> Option<Py<PyAny>> {
-        let idx = self.var_names.iter().position(|n| n == name)?;
-        let vector = self.vectors.get(idx)?;
-        Some(vector_to_numpy(py, vector))
+    fn get<'py>(slf: &Bound<'py, Self>, name: &str) -> PyResult<Option<Bound<'py, PyAny>>> {
+        match slf.get().var_names.iter().position(|n| n == name) {
+            Some(idx) => vector_view(slf, idx).map(Some),
+            None => Ok(None),
+        }
     }
 
     /// Get number of data points
@@ -95,15 +126,72 @@ impl PyDataTable {
 
     /// Get all signal names
     fn keys(&self) -> Vec<String> {
-        self.var_names.clone()
+        self.var_names.to_vec()
     }
 
-    fn __repr__(&self) -> String {
-        format!(
-            "DataTable(points={}, signals={})",
+    /// Statistics of every signal in this table
+    ///
+    /// Returns:
+    ///     Dict of name -> dict with count, min, max, mean, rms, std, p2p and
+    ///     magnitude (True if computed on complex magnitudes)
+    fn stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let stats: Vec<_> = self.vectors.iter().map(VectorData::stats).collect();
+        stats_dict(py, &self.var_names, &stats)
+    }
+
+    /// Build a pandas DataFrame of this table
+    ///
+    /// Args:
+    ///     long: If True, return one row per (scale, signal) sample with
+    ///         `signal` and `value` columns, plus the sweep value if swept
+    ///
+    /// Returns:
+    ///     DataFrame indexed by the scale with one column per signal
+    #[pyo3(signature = (long=false))]
+    fn to_dataframe<'py>(slf: &Bound<'py, Self>, long: bool) -> PyResult<Bound<'py, PyAny>> {
+        let frame = table_frame(slf)?;
+        if !long {
+            return Ok(frame);
+        }
+        let table = slf.get();
+        let sweep = table
+            .sweep_value
+            .map(|v| (sweep_column(&table.sweep_param), v));
+        long_frame(slf.py(), frame, &table.var_names[0], sweep)
+    }
+
+    fn __repr__(&self, py: Python) -> PyResult<String> {
+        let mut signals = Vec::new();
+        for name in self.var_names.iter().take(REPR_MAX_NAMES) {
+            signals.push(py_repr(py, name)?);
+        }
+        if self.var_names.len() > REPR_MAX_NAMES {
+            signals.push("...".to_string());
+        }
+        Ok(format!(
+            "DataTable(sweep_value={}, points={}, signals=[{}])",
+            py_repr(py, self.sweep_value)?,
             self.__len__(),
-            self.var_names.len()
-        )
+            signals.join(", ")
+        ))
+    }
+}
+
+impl PartialEq for PyDataTable {
+    fn eq(&self, other: &Self) -> bool {
+        let same_values = |a: &VectorData, b: &VectorData| match (a.as_complex(), b.as_complex()) {
+            (Some(a), Some(b)) => a == b,
+            (None, None) => a.to_real() == b.to_real(),
+            _ => false,
+        };
+        self.sweep_value == other.sweep_value
+            && self.var_names == other.var_names
+            && self.vectors.len() == other.vectors.len()
+            && self
+                .vectors
+                .iter()
+                .zip(&other.vectors)
+                .all(|(a, b)| same_values(a, b))
     }
 }
 
@@ -120,9 +208,13 @@ pub struct PyWaveformResult {
     pub scale_name: String,
     #[pyo3(get)]
     pub sweep_param: Option<String>,
+    /// True if the file ended early and only its complete rows were read
+    #[pyo3(get)]
+    pub truncated: bool,
 
     variables: Vec<Variable>,
-    tables: Vec<DataTable>,
+    tables: Vec<Py<PyDataTable>>,
+    closed: bool,
 }
 
 #[pymethods]
@@ -135,27 +227,24 @@ impl PyWaveformResult {
 
     /// Get list of data tables
     #[getter]
-    fn tables(&self) -> Vec<PyDataTable> {
-        self.tables
-            .iter()
-            .map(|t| PyDataTable {
-                sweep_value: t.sweep_value,
-                vectors: t.vectors.clone(),
-                var_names: self.variables.iter().map(|v| v.name.clone()).collect(),
-            })
-            .collect()
+    fn tables(&self, py: Python) -> Vec<Py<PyDataTable>> {
+        self.tables.iter().map(|t| t.clone_ref(py)).collect()
     }
 
     /// Get signal data by name (from first table)
-    fn get<'py>(&self, py: Python<'py>, name: &str) -> Option<Py<PyAny>> {
-        let idx = self.variables.iter().position(|v| v.name == name)?;
-        let vector = self.tables.first()?.vectors.get(idx)?;
-        Some(vector_to_numpy(py, vector))
+    fn get<'py>(&self, py: Python<'py>, name: &str) -> PyResult<Option<Bound<'py, PyAny>>> {
+        let (Some(idx), Some(table)) = (
+            self.variables.iter().position(|v| v.name == name),
+            self.tables.first(),
+        ) else {
+            return Ok(None);
+        };
+        vector_view(table.bind(py), idx).map(Some)
     }
 
     /// Get number of data points
     fn __len__(&self) -> usize {
-        self.tables.first().map(|t| t.len()).unwrap_or(0)
+        self.tables.first().map(|t| t.get().__len__()).unwrap_or(0)
     }
 
     /// Get number of variables
@@ -178,6 +267,316 @@ impl PyWaveformResult {
         self.sweep_param.is_some() && self.tables.len() > 1
     }
 
+    /// Statistics of every signal over all tables
+    ///
+    /// Returns:
+    ///     Dict of name -> dict with count, min, max, mean, rms, std, p2p and
+    ///     magnitude (True if computed on complex magnitudes)
+    fn stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let mut totals: Vec<Option<SignalStats>> = vec![None; self.variables.len()];
+        for table in &self.tables {
+            for (total, vector) in totals.iter_mut().zip(&table.get().vectors) {
+                *total = match (*total, vector.stats()) {
+                    (Some(a), Some(b)) => Some(a.merge(&b)),
+                    (a, b) => a.or(b),
+                };
+            }
+        }
+        stats_dict(py, &self.var_names(), &totals)
+    }
+
+    /// Build a pandas DataFrame of all tables
+    ///
+    /// Args:
+    ///     long: If True, return one row per (sweep, scale, signal) sample
+    ///         with `signal` and `value` columns
+    ///
+    /// Returns:
+    ///     DataFrame indexed by the scale with one column per signal.
+    ///     Swept results are indexed by (sweep value, scale).
+    #[pyo3(signature = (long=false))]
+    fn to_dataframe<'py>(&self, py: Python<'py>, long: bool) -> PyResult<Bound<'py, PyAny>> {
+        let pd = py.import("pandas")?;
+        let names = self.var_names();
+        let sweep_name = sweep_column(&self.sweep_param);
+
+        let mut frames = Vec::with_capacity(self.tables.len());
+        let mut keys = Vec::with_capacity(self.tables.len());
+        for table in &self.tables {
+            let table = table.bind(py);
+            let sweep_value = table.get().sweep_value;
+            let mut frame = table_frame(table)?;
+            if long {
+                let sweep = sweep_value.map(|v| (sweep_name.clone(), v));
+                frame = long_frame(py, frame, &names[0], sweep)?;
+            }
+            frames.push(frame);
+            keys.push(sweep_value);
+        }
+
+        let swept = keys.len() > 1 && keys.iter().all(Option::is_some);
+        let kwargs = PyDict::new(py);
+        match frames.len() {
+            0 => {
+                kwargs.set_item("columns", names.get(1..).unwrap_or_default())?;
+                pd.call_method("DataFrame", (), Some(&kwargs))
+            }
+            1 => Ok(frames.remove(0)),
+            _ => {
+                if long {
+                    kwargs.set_item("ignore_index", true)?;
+                } else if swept {
+                    kwargs.set_item("keys", keys)?;
+                    kwargs.set_item("names", [sweep_name])?;
+                }
+                pd.call_method("concat", (frames,), Some(&kwargs))
+            }
+        }
+    }
+
+    /// Evaluate an expression over signals, e.g. "db(v(out)/v(in))"
+    ///
+    /// Supports + - * / ^, numbers, signal names (v(out) also finds the
+    /// node out) and the functions mag, abs, db, ph, re, im, sqrt, exp,
+    /// ln and log10. Complex signals stay complex until a function makes
+    /// them real.
+    ///
+    /// Args:
+    ///     expr: The expression
+    ///     table: Sweep table to evaluate on (default: the first)
+    ///
+    /// Returns:
+    ///     numpy array, float64 or complex128
+    #[pyo3(signature = (expr, table=0))]
+    fn eval<'py>(&self, py: Python<'py>, expr: &str, table: usize) -> PyResult<Bound<'py, PyAny>> {
+        let vector = self.eval_vector(expr, table)?;
+        Ok(vector_into_numpy(py, vector))
+    }
+
+    /// Measure a signal against the scale
+    ///
+    /// Args:
+    ///     kind: "risetime", "falltime", "delay" or "crossings"
+    ///     signal: Signal name or expression (the trigger for "delay")
+    ///     target: Target signal of "delay"
+    ///     low: Lower fraction of the swing for rise/fall times
+    ///     high: Upper fraction of the swing for rise/fall times
+    ///     frac: Fraction of each signal's swing for "delay"
+    ///     level: Threshold of "crossings"
+    ///     edge: "rising", "falling" or "either", for "crossings"
+    ///     table: Sweep table to measure (default: the first)
+    ///
+    /// Returns:
+    ///     The time as a float, or None if the signal has no such
+    ///     transition; for "crossings" a numpy array of crossing times
+    #[pyo3(signature = (kind, signal, target=None, low=0.1, high=0.9, frac=0.5, level=None, edge="rising", table=0))]
+    #[allow(clippy::too_many_arguments)]
+    fn measure<'py>(
+        &self,
+        py: Python<'py>,
+        kind: &str,
+        signal: &str,
+        target: Option<&str>,
+        low: f64,
+        high: f64,
+        frac: f64,
+        level: Option<f64>,
+        edge: &str,
+        table: usize,
+    ) -> PyResult<Option<Bound<'py, PyAny>>> {
+        let real = |expr: &str| -> PyResult<Vec<f64>> {
+            let vector = self.eval_vector(expr, table)?;
+            if vector.is_complex() {
+                return Err(PyValueError::new_err(format!(
+                    "{} is complex; measure mag({0}) or db({0}) instead",
+                    expr
+                )));
+            }
+            Ok(vector.to_real().unwrap_or_default().into_owned())
+        };
+        let x = real(&self.scale_name)?;
+        let y = real(signal)?;
+        let time = match kind {
+            "risetime" | "rise_time" => measure::rise_time(&x, &y, low, high),
+            "falltime" | "fall_time" => measure::fall_time(&x, &y, high, low),
+            "delay" => {
+                let target =
+                    target.ok_or_else(|| PyValueError::new_err("delay needs a target signal"))?;
+                measure::delay(&x, &y, &real(target)?, frac)
+            }
+            "crossings" => {
+                let level =
+                    level.ok_or_else(|| PyValueError::new_err("crossings needs a level"))?;
+                let edge = match edge {
+                    "rising" => Edge::Rising,
+                    "falling" => Edge::Falling,
+                    "either" => Edge::Either,
+                    other => {
+                        return Err(PyValueError::new_err(format!(
+                            "edge must be 'rising', 'falling' or 'either', not {:?}",
+                            other
+                        )))
+                    }
+                };
+                let times = measure::crossings(&x, &y, level, edge);
+                return Ok(Some(times.into_pyarray(py).into_any()));
+            }
+            other => {
+                return Err(PyValueError::new_err(format!(
+                    "unknown measurement {:?} (use 'risetime', 'falltime', 'delay' or 'crossings')",
+                    other
+                )))
+            }
+        };
+        time.map(|t| t.into_pyobject(py).map(|t| t.into_any()))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    /// Write the result to a file
+    ///
+    /// HDF5 and Parquet are not written here, to keep the wheels free of
+    /// libhdf5 and Arrow; use `to_dataframe().to_hdf()` or `.to_parquet()`.
+    ///
+    /// Args:
+    ///     path: Output file
+    ///     format: "csv", "json", "npz", "mat" or "raw" (default: from the
+    ///         extension of path)
+    ///     signals: Optional glob (or "re:" regex) selecting the signals to
+    ///         write; the scale is always written
+    ///     table: Index of the only sweep table to write (default: all)
+    ///     ascii: Write a raw file with an ASCII Values: section
+    ///     precision: Significant digits of ASCII raw values
+    ///
+    /// Returns:
+    ///     True if the file was written, False otherwise
+    #[pyo3(signature = (path, format=None, signals=None, table=None, ascii=false, precision=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn save(
+        &self,
+        py: Python,
+        path: &str,
+        format: Option<&str>,
+        signals: Option<&str>,
+        table: Option<usize>,
+        ascii: bool,
+        precision: Option<usize>,
+    ) -> PyResult<bool> {
+        let format = match format {
+            Some(format) => format.to_string(),
+            None => Path::new(path)
+                .extension()
+                .and_then(|e| e.to_str())
+                .map(str::to_ascii_lowercase)
+                .unwrap_or_default(),
+        };
+        if !SAVE_FORMATS.contains(&format.as_str()) {
+            return Err(PyValueError::new_err(format!(
+                "unknown format {:?} (use {})",
+                format,
+                SAVE_FORMATS.join(", ")
+            )));
+        }
+        let encoding = match (ascii, precision) {
+            (true, precision) => RawEncoding::Ascii {
+                precision: precision.unwrap_or(RawEncoding::DEFAULT_PRECISION),
+            },
+            (false, _) => RawEncoding::Binary,
+        };
+        self.export(py, path, &format, signals, table, encoding)
+    }
+
+    /// Write as CSV, in the layout of convert_stream (see save)
+    #[pyo3(signature = (path, signals=None, table=None))]
+    fn to_csv(
+        &self,
+        py: Python,
+        path: &str,
+        signals: Option<&str>,
+        table: Option<usize>,
+    ) -> PyResult<bool> {
+        self.export(py, path, "csv", signals, table, RawEncoding::Binary)
+    }
+
+    /// Write as SPICE3 raw, binary or with ascii=True ASCII (see save)
+    #[pyo3(signature = (path, signals=None, table=None, ascii=false, precision=None))]
+    fn to_raw(
+        &self,
+        py: Python,
+        path: &str,
+        signals: Option<&str>,
+        table: Option<usize>,
+        ascii: bool,
+        precision: Option<usize>,
+    ) -> PyResult<bool> {
+        self.save(py, path, Some("raw"), signals, table, ascii, precision)
+    }
+
+    /// Write as a JSON document (see save)
+    #[pyo3(signature = (path, signals=None, table=None))]
+    fn to_json(
+        &self,
+        py: Python,
+        path: &str,
+        signals: Option<&str>,
+        table: Option<usize>,
+    ) -> PyResult<bool> {
+        self.export(py, path, "json", signals, table, RawEncoding::Binary)
+    }
+
+    /// Write as a NumPy .npz archive, loadable with numpy.load (see save)
+    #[pyo3(signature = (path, signals=None, table=None))]
+    fn to_npz(
+        &self,
+        py: Python,
+        path: &str,
+        signals: Option<&str>,
+        table: Option<usize>,
+    ) -> PyResult<bool> {
+        self.export(py, path, "npz", signals, table, RawEncoding::Binary)
+    }
+
+    /// Write as a MATLAB .mat file (see save)
+    #[pyo3(signature = (path, signals=None, table=None))]
+    fn to_mat(
+        &self,
+        py: Python,
+        path: &str,
+        signals: Option<&str>,
+        table: Option<usize>,
+    ) -> PyResult<bool> {
+        self.export(py, path, "mat", signals, table, RawEncoding::Binary)
+    }
+
+    /// Release the result's tables
+    ///
+    /// The result is empty afterwards: `get()` returns None and `len()` is
+    /// 0. Arrays already returned by `get()` keep their own table alive.
+    fn close(&mut self) {
+        self.tables.clear();
+        self.closed = true;
+    }
+
+    /// True once `close()` was called
+    #[getter]
+    fn closed(&self) -> bool {
+        self.closed
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(
+        &mut self,
+        _exc_type: PyObject,
+        _exc_value: PyObject,
+        _traceback: PyObject,
+    ) -> bool {
+        self.close();
+        false
+    }
+
     fn __repr__(&self) -> String {
         format!(
             "WaveformResult(title='{}', analysis='{}', vars={}, points={})",
@@ -189,56 +588,770 @@ impl PyWaveformResult {
     }
 }
 
-impl From<WaveformResult> for PyWaveformResult {
-    fn from(r: WaveformResult) -> Self {
-        // Compute values that depend on &self before move
+impl PyWaveformResult {
+    /// Take ownership of a parsed result, moving each table into a
+    /// Python-owned `DataTable`
+    fn new(py: Python, r: WaveformResult) -> PyResult<Self> {
         let analysis = r.analysis.to_string();
         let scale_name = r.scale_name().to_string();
-        PyWaveformResult {
+        let var_names: Arc<[String]> = r.variables.iter().map(|v| v.name.clone()).collect();
+        let tables = r
+            .tables
+            .into_iter()
+            .map(|t| {
+                let table = PyDataTable {
+                    sweep_value: t.sweep_value,
+                    sweep_param: r.sweep_param.clone(),
+                    vectors: t.vectors,
+                    var_names: var_names.clone(),
+                };
+                Py::new(py, table)
+            })
+            .collect::<PyResult<_>>()?;
+        Ok(PyWaveformResult {
             title: r.title,
             date: r.date,
             analysis,
             scale_name,
             sweep_param: r.sweep_param,
+            truncated: r.truncated,
             variables: r.variables,
-            tables: r.tables,
+            tables,
+            closed: false,
+        })
+    }
+}
+
+// ============================================================================
+// Export
+// ============================================================================
+
+/// Formats of `WaveformResult.save`, by name or file extension
+const SAVE_FORMATS: [&str; 5] = ["csv", "json", "npz", "mat", "raw"];
+
+impl PyWaveformResult {
+    /// The result as hspice-core holds it; its vectors share their values
+    /// with the Python tables
+    fn to_core(&self) -> WaveformResult {
+        let mut result = WaveformResult::new(
+            self.title.clone(),
+            self.date.clone(),
+            self.analysis.parse().unwrap_or_default(),
+            self.variables.clone(),
+            self.sweep_param.clone(),
+            self.tables.iter().map(|t| core_table(t.get())).collect(),
+        );
+        result.truncated = self.truncated;
+        result
+    }
+
+    /// `expr` evaluated on table `table`
+    fn eval_vector(&self, expr: &str, table: usize) -> PyResult<VectorData> {
+        let Some(t) = self.tables.get(table) else {
+            return Err(PyIndexError::new_err(format!(
+                "table {} out of range ({} tables)",
+                table,
+                self.tables.len()
+            )));
+        };
+        let table = core_table(t.get());
+        Expr::parse(expr)
+            .and_then(|expr| expr.eval_table(&self.variables, &table))
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Write `table` (all if `None`) and the signals matching `signals` to
+    /// `path` as `format`, one of `SAVE_FORMATS`
+    fn export(
+        &self,
+        py: Python,
+        path: &str,
+        format: &str,
+        signals: Option<&str>,
+        table: Option<usize>,
+        encoding: RawEncoding,
+    ) -> PyResult<bool> {
+        let mut result = self.to_core();
+        if let Some(table) = table {
+            if table >= result.tables.len() {
+                return Err(PyIndexError::new_err(format!(
+                    "table {} out of range ({} tables)",
+                    table,
+                    result.tables.len()
+                )));
+            }
+            result.tables = vec![result.tables.swap_remove(table)];
+        }
+        let written = py.allow_threads(|| {
+            let result = select_signals(Ok(result), signals)?;
+            write_result(&result, path, format, encoding)
+        });
+        match written {
+            Ok(()) => Ok(true),
+            Err(e) => {
+                tracing::error!("Export error: {:?}", e);
+                Ok(false)
+            }
+        }
+    }
+}
+
+/// A table as hspice-core holds it, sharing the vectors' values
+fn core_table(table: &PyDataTable) -> DataTable {
+    DataTable {
+        sweep_value: table.sweep_value,
+        vectors: table.vectors.clone(),
+    }
+}
+
+fn write_result(
+    result: &WaveformResult,
+    path: &str,
+    format: &str,
+    encoding: RawEncoding,
+) -> hspice_core::Result<()> {
+    if format == "raw" {
+        let report = hspice_core::write_spice3_raw_with(result, path, encoding)?;
+        for warning in &report.warnings {
+            tracing::warn!("{}", warning);
+        }
+        return Ok(());
+    }
+    let mut writer = BufWriter::new(File::create(path)?);
+    match format {
+        "csv" => result.write_csv(&mut writer)?,
+        "json" => result.write_json(&mut writer)?,
+        "npz" => to_npz(result, &mut writer)?,
+        "mat" => to_mat(result, &mut writer)?,
+        _ => unreachable!("formats are checked by save"),
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Lazy iterator over the chunks of a waveform file
+///
+/// Each step decodes the next chunk with the GIL released and yields a dict
+/// with `chunk_index`, `table_index`, `sweep_value`, `time_range` and
+/// `data`. Iteration stops at the end of the file or on the first decoding error (which is logged).
+#[pyclass(name = "WaveformStream")]
+pub struct PyWaveformStream {
+    reader: Option<ChunkIter>,
+}
+
+/// Chunks of a stream, read directly or through a prefetch thread
+type ChunkIter = Box<dyn Iterator<Item = hspice_core::Result<DataChunk>> + Send + Sync>;
+
+#[pymethods]
+impl PyWaveformStream {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<Py<PyDict>>> {
+        let Some(reader) = self.reader.as_mut() else {
+            return Ok(None);
+        };
+        match py.allow_threads(|| reader.next()) {
+            Some(Ok(chunk)) => chunk_to_dict(py, chunk).map(Some),
+            Some(Err(e)) => {
+                tracing::error!("Stream chunk error: {:?}", e);
+                self.reader = None;
+                Ok(None)
+            }
+            None => {
+                self.reader = None;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Stop the stream and close its file (waits for a prefetch thread)
+    ///
+    /// Later `next()` calls end the iteration. Closing twice is harmless.
+    fn close(&mut self, py: Python<'_>) {
+        let reader = self.reader.take();
+        py.allow_threads(|| drop(reader));
+    }
+
+    /// True once the stream is exhausted or closed
+    #[getter]
+    fn closed(&self) -> bool {
+        self.reader.is_none()
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(
+        &mut self,
+        py: Python<'_>,
+        _exc_type: PyObject,
+        _exc_value: PyObject,
+        _traceback: PyObject,
+    ) -> bool {
+        self.close(py);
+        false
+    }
+
+    fn __repr__(&self) -> String {
+        match &self.reader {
+            Some(_) => "WaveformStream(open)".to_string(),
+            None => "WaveformStream(closed)".to_string(),
+        }
+    }
+}
+
+/// Waveform file whose signals are decoded on first access
+///
+/// Only the header and block layout are read when the file is opened.
+/// `wave["v(out)"]` decodes just that column (with the GIL released), and
+/// `signals` lists the names without touching any data. The file stays
+/// mapped until `close()` (or the end of a `with` block) or until the
+/// object is garbage collected.
+#[pyclass(name = "LazyWaveform", frozen)]
+pub struct PyLazyWaveform {
+    /// None once closed; decodes hold their own reference, so closing
+    /// never waits for them
+    inner: RwLock<Option<Arc<LazyWaveform>>>,
+}
+
+impl PyLazyWaveform {
+    fn new(inner: LazyWaveform) -> Self {
+        Self {
+            inner: RwLock::new(Some(Arc::new(inner))),
+        }
+    }
+
+    /// Run `f` on the open file, or raise ValueError if it was closed
+    fn with<R>(&self, f: impl FnOnce(&LazyWaveform) -> R) -> PyResult<R> {
+        let wave = self.inner.read().unwrap_or_else(|e| e.into_inner()).clone();
+        match wave {
+            Some(wave) => Ok(f(&wave)),
+            None => Err(PyValueError::new_err("LazyWaveform is closed")),
         }
     }
 }
 
+#[pymethods]
+impl PyLazyWaveform {
+    #[getter]
+    fn title(&self) -> PyResult<String> {
+        self.with(|w| w.title().to_string())
+    }
+
+    #[getter]
+    fn date(&self) -> PyResult<String> {
+        self.with(|w| w.date().to_string())
+    }
+
+    #[getter]
+    fn analysis(&self) -> PyResult<String> {
+        self.with(|w| w.analysis().to_string())
+    }
+
+    #[getter]
+    fn scale_name(&self) -> PyResult<String> {
+        self.with(|w| w.scale_name().to_string())
+    }
+
+    #[getter]
+    fn sweep_param(&self) -> PyResult<Option<String>> {
+        self.with(|w| w.sweep_param().map(str::to_string))
+    }
+
+    /// All signal names, scale first
+    #[getter]
+    fn signals(&self) -> PyResult<Vec<String>> {
+        self.with(|w| w.variables().iter().map(|v| v.name.clone()).collect())
+    }
+
+    #[getter]
+    fn variables(&self) -> PyResult<Vec<PyVariable>> {
+        self.with(|w| w.variables().iter().map(PyVariable::from).collect())
+    }
+
+    /// Sweep value of each table (None if not swept)
+    #[getter]
+    fn sweep_values(&self) -> PyResult<Vec<Option<f64>>> {
+        self.with(|w| w.sweep_values())
+    }
+
+    #[getter]
+    fn num_tables(&self) -> PyResult<usize> {
+        self.with(|w| w.num_tables())
+    }
+
+    /// Decode a signal from sweep table `table` (default: first)
+    #[pyo3(signature = (name, table=0))]
+    fn get<'py>(
+        &self,
+        py: Python<'py>,
+        name: &str,
+        table: usize,
+    ) -> PyResult<Option<Bound<'py, PyAny>>> {
+        let vector = self.with(|w| {
+            if w.var_index(name).is_none() {
+                return Ok(None);
+            }
+            if table >= w.num_tables() {
+                return Err(PyIndexError::new_err(format!(
+                    "table {} out of range ({} tables)",
+                    table,
+                    w.num_tables()
+                )));
+            }
+            Ok(py.allow_threads(|| w.get_table(name, table)))
+        })??;
+        Ok(vector.map(|v| vector_into_numpy(py, v)))
+    }
+
+    /// Unmap the file
+    ///
+    /// Every other method raises ValueError afterwards. Arrays already
+    /// returned are copies and stay valid. A decode running on another
+    /// thread finishes first and unmaps the file when it is done. Closing
+    /// twice is harmless.
+    fn close(&self) {
+        self.inner.write().unwrap_or_else(|e| e.into_inner()).take();
+    }
+
+    /// True once `close()` was called
+    #[getter]
+    fn closed(&self) -> bool {
+        self.inner
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_none()
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(&self, _exc_type: PyObject, _exc_value: PyObject, _traceback: PyObject) -> bool {
+        self.close();
+        false
+    }
+
+    fn __getitem__<'py>(&self, py: Python<'py>, name: &str) -> PyResult<Bound<'py, PyAny>> {
+        self.get(py, name, 0)?
+            .ok_or_else(|| PyKeyError::new_err(name.to_string()))
+    }
+
+    fn __contains__(&self, name: &str) -> PyResult<bool> {
+        self.with(|w| w.var_index(name).is_some())
+    }
+
+    fn __len__(&self) -> PyResult<usize> {
+        self.with(|w| w.len())
+    }
+
+    fn __repr__(&self) -> String {
+        self.with(|w| {
+            format!(
+                "LazyWaveform(title='{}', analysis='{}', vars={}, points={})",
+                w.title(),
+                w.analysis(),
+                w.variables().len(),
+                w.len()
+            )
+        })
+        .unwrap_or_else(|_| "LazyWaveform(closed)".to_string())
+    }
+}
+
+/// Contents of an HSPICE measure file (.mt0, .ma0, .ms0)
+///
+/// One row per sweep point, in the order of the matching waveform file's
+/// tables. Failed measurements are None in `rows` and NaN in arrays.
+#[pyclass(name = "MeasureResult", frozen)]
+pub struct PyMeasureResult {
+    inner: MeasureResult,
+}
+
+#[pymethods]
+impl PyMeasureResult {
+    #[getter]
+    fn title(&self) -> &str {
+        &self.inner.title
+    }
+
+    #[getter]
+    fn analysis(&self) -> String {
+        self.inner.analysis.to_string()
+    }
+
+    /// Number of leading swept-parameter columns
+    #[getter]
+    fn param_count(&self) -> usize {
+        self.inner.param_count
+    }
+
+    /// All column names, including `temper` and `alter#`
+    #[getter]
+    fn names(&self) -> Vec<String> {
+        self.inner.names.clone()
+    }
+
+    /// Names of the .measure results only
+    #[getter]
+    fn measure_names(&self) -> Vec<&str> {
+        self.inner.measure_names()
+    }
+
+    #[getter]
+    fn rows(&self) -> Vec<Vec<Option<f64>>> {
+        self.inner.rows.clone()
+    }
+
+    /// Column as a float64 array (NaN where the measurement failed)
+    fn get<'py>(&self, py: Python<'py>, name: &str) -> Option<Bound<'py, PyArray1<f64>>> {
+        let column = self.inner.column(name)?;
+        let values: Vec<f64> = column.iter().map(|v| v.unwrap_or(f64::NAN)).collect();
+        Some(values.into_pyarray(py))
+    }
+
+    fn __getitem__<'py>(&self, py: Python<'py>, name: &str) -> PyResult<Bound<'py, PyArray1<f64>>> {
+        self.get(py, name)
+            .ok_or_else(|| PyKeyError::new_err(name.to_string()))
+    }
+
+    /// pandas DataFrame with one column per name and one row per sweep point
+    fn to_dataframe<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let data = PyDict::new(py);
+        for name in &self.inner.names {
+            data.set_item(name, self.get(py, name))?;
+        }
+        py.import("pandas")?.call_method1("DataFrame", (data,))
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "MeasureResult(title='{}', analysis='{}', measures={}, rows={})",
+            self.inner.title,
+            self.inner.analysis,
+            self.inner.measure_names().len(),
+            self.inner.len()
+        )
+    }
+}
+
 // ============================================================================
 // Helper Functions
 // ============================================================================
 
-fn vector_to_numpy(py: Python, vector: &VectorData) -> Py<PyAny> {
+/// Name -> statistics dict, skipping signals without values
+fn stats_dict<'py>(
+    py: Python<'py>,
+    names: &[String],
+    stats: &[Option<SignalStats>],
+) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    for (name, stats) in names.iter().zip(stats) {
+        let Some(s) = stats else { continue };
+        let entry = PyDict::new(py);
+        entry.set_item("count", s.count)?;
+        entry.set_item("min", s.min)?;
+        entry.set_item("max", s.max)?;
+        entry.set_item("mean", s.mean)?;
+        entry.set_item("rms", s.rms)?;
+        entry.set_item("std", s.std)?;
+        entry.set_item("p2p", s.peak_to_peak())?;
+        entry.set_item("magnitude", s.magnitude)?;
+        dict.set_item(name, entry)?;
+    }
+    Ok(dict)
+}
+
+fn chunk_to_dict(py: Python, chunk: DataChunk) -> PyResult<Py<PyDict>> {
+    let chunk_dict = PyDict::new(py);
+    chunk_dict.set_item("chunk_index", chunk.chunk_index)?;
+    chunk_dict.set_item("table_index", chunk.table_index)?;
+    chunk_dict.set_item("sweep_value", chunk.sweep_value)?;
+    chunk_dict.set_item("time_range", (chunk.time_range.0, chunk.time_range.1))?;
+
+    let data_dict = PyDict::new(py);
+    for (name, vector) in chunk.data {
+        data_dict.set_item(name, vector_into_numpy(py, vector))?;
+    }
+    chunk_dict.set_item("data", data_dict)?;
+    Ok(chunk_dict.unbind())
+}
+
+/// Move a vector into a numpy array without copying
+fn vector_into_numpy(py: Python, vector: VectorData) -> Bound<PyAny> {
     match vector {
-        VectorData::Real(v) => Array1::from_vec(v.clone())
-            .into_pyarray(py)
-            .into_any()
-            .unbind(),
-        VectorData::Complex(v) => Array1::from_vec(v.clone())
+        VectorData::Real(v) => v.into_vec().into_pyarray(py).into_any(),
+        VectorData::Complex(v) => v.into_vec().into_pyarray(py).into_any(),
+        VectorData::Real32(_) | VectorData::Stepped { .. } => vector
+            .to_real()
+            .unwrap_or_default()
+            .into_owned()
             .into_pyarray(py)
-            .into_any()
-            .unbind(),
+            .into_any(),
+    }
+}
+
+/// Read-only numpy view of vector `index` of `table`
+///
+/// The array's base is the table, which keeps the data alive. Stepped and
+/// single precision vectors have no flat float64 buffer and are expanded
+/// into a new array.
+fn vector_view<'py>(table: &Bound<'py, PyDataTable>, index: usize) -> PyResult<Bound<'py, PyAny>> {
+    let py = table.py();
+    let container = table.clone().into_any();
+    // SAFETY: `DataTable` is frozen, so its vectors are never modified or
+    // reallocated while the table lives, and the view holds a reference to
+    // the table as its base object.
+    let array = match &table.get().vectors[index] {
+        VectorData::Real(v) => unsafe {
+            PyArray1::borrow_from_array(&ArrayView1::from(v.as_slice()), container).into_any()
+        },
+        VectorData::Complex(v) => unsafe {
+            PyArray1::borrow_from_array(&ArrayView1::from(v.as_slice()), container).into_any()
+        },
+        vector @ (VectorData::Real32(_) | VectorData::Stepped { .. }) => {
+            return Ok(vector_into_numpy(py, vector.clone()));
+        }
+    };
+
+    let kwargs = PyDict::new(py);
+    kwargs.set_item("write", false)?;
+    array.call_method("setflags", (), Some(&kwargs))?;
+    Ok(array)
+}
+
+/// Signal names listed by `DataTable.__repr__` before "..."
+const REPR_MAX_NAMES: usize = 8;
+
+/// Python's `repr()` of `value`
+fn py_repr<'py>(py: Python<'py>, value: impl IntoPyObject<'py>) -> PyResult<String> {
+    let value = value.into_bound_py_any(py)?;
+    Ok(value.repr()?.to_string())
+}
+
+/// Column name for sweep values in long-format frames
+fn sweep_column(sweep_param: &Option<String>) -> String {
+    sweep_param.clone().unwrap_or_else(|| "sweep".to_string())
+}
+
+/// Wide DataFrame: the first vector as a named index, the rest as columns
+///
+/// Complex signals stay complex128.
+fn table_frame<'py>(table: &Bound<'py, PyDataTable>) -> PyResult<Bound<'py, PyAny>> {
+    let py = table.py();
+    let pd = py.import("pandas")?;
+    let data = table.get();
+
+    let columns = PyDict::new(py);
+    for (index, name) in data.var_names.iter().enumerate().skip(1) {
+        columns.set_item(name, vector_view(table, index)?)?;
+    }
+
+    let kwargs = PyDict::new(py);
+    if let (Some(scale_name), false) = (data.var_names.first(), data.vectors.is_empty()) {
+        let index_kwargs = PyDict::new(py);
+        index_kwargs.set_item("name", scale_name)?;
+        let index = pd
+            .getattr("Index")?
+            .call((vector_view(table, 0)?,), Some(&index_kwargs))?;
+        kwargs.set_item("index", index)?;
+    }
+    pd.call_method("DataFrame", (columns,), Some(&kwargs))
+}
+
+/// Melt a wide table frame into `[sweep,] scale, signal, value` rows
+fn long_frame<'py>(
+    py: Python<'py>,
+    frame: Bound<'py, PyAny>,
+    scale_name: &str,
+    sweep: Option<(String, f64)>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let kwargs = PyDict::new(py);
+    kwargs.set_item("id_vars", [scale_name])?;
+    kwargs.set_item("var_name", "signal")?;
+    kwargs.set_item("value_name", "value")?;
+    let long = frame
+        .call_method0("reset_index")?
+        .call_method("melt", (), Some(&kwargs))?;
+    if let Some((name, value)) = sweep {
+        long.call_method1("insert", (0, name, value))?;
     }
+    Ok(long)
 }
 
 // ============================================================================
 // Python Functions
 // ============================================================================
 
+/// Unique corner names for `paths`: the file stems if they differ, else
+/// the paths below their common directory, without the extension if that
+/// keeps them apart
+fn corner_names(paths: &[String]) -> Vec<String> {
+    let unique = |names: &[String]| {
+        let mut seen = std::collections::HashSet::new();
+        names.iter().all(|name| seen.insert(name))
+    };
+    let stems: Vec<String> = paths
+        .iter()
+        .map(|p| {
+            Path::new(p)
+                .file_stem()
+                .map_or(p.clone(), |s| s.to_string_lossy().into_owned())
+        })
+        .collect();
+    if unique(&stems) {
+        return stems;
+    }
+
+    let dirs: Vec<Vec<_>> = paths
+        .iter()
+        .map(|p| {
+            Path::new(p)
+                .parent()
+                .map_or(Vec::new(), |d| d.components().collect())
+        })
+        .collect();
+    let common = (0..)
+        .take_while(|&i| {
+            dirs[0]
+                .get(i)
+                .is_some_and(|c| dirs.iter().all(|d| d.get(i) == Some(c)))
+        })
+        .count();
+    let relative = |p: &String, strip_extension: bool| -> String {
+        let path: PathBuf = Path::new(p).components().skip(common).collect();
+        let path = if strip_extension {
+            path.with_extension("")
+        } else {
+            path
+        };
+        path.to_string_lossy().replace('\\', "/")
+    };
+    let short: Vec<String> = paths.iter().map(|p| relative(p, true)).collect();
+    if unique(&short) {
+        return short;
+    }
+    paths.iter().map(|p| relative(p, false)).collect()
+}
+
+/// Signals to read: a glob/regex pattern or a list of exact names
+#[derive(FromPyObject)]
+pub enum SignalSelection {
+    Pattern(String),
+    Names(Vec<String>),
+}
+
+/// Keep the scale and the signals matching `pattern`, if given
+fn select_signals(
+    result: hspice_core::Result<WaveformResult>,
+    pattern: Option<&str>,
+) -> hspice_core::Result<WaveformResult> {
+    match pattern {
+        Some(pattern) => result?.select(pattern),
+        None => result,
+    }
+}
+
+/// Progress target from Python
+///
+/// A tqdm-like object (one with `update`) gets its `total` set to the file
+/// size and is advanced by the bytes read; anything else is called as
+/// `progress(bytes, total_bytes)`. The first exception it raises stops
+/// further reports and is raised once the read is over.
+struct PyProgress<'py> {
+    target: Bound<'py, PyAny>,
+    tqdm: bool,
+    bytes: u64,
+    error: Option<PyErr>,
+}
+
+impl<'py> PyProgress<'py> {
+    fn new(target: Bound<'py, PyAny>) -> PyResult<Self> {
+        let tqdm = target.hasattr("update")?;
+        Ok(Self {
+            target,
+            tqdm,
+            bytes: 0,
+            error: None,
+        })
+    }
+
+    fn update(&mut self, progress: &Progress) {
+        if self.error.is_some() {
+            return;
+        }
+        let result = if self.tqdm {
+            let delta = progress.bytes.saturating_sub(self.bytes);
+            let total = if self.bytes == 0 {
+                self.target.setattr("total", progress.total_bytes)
+            } else {
+                Ok(())
+            };
+            total.and_then(|_| self.target.call_method1("update", (delta,)).map(drop))
+        } else {
+            self.target
+                .call1((progress.bytes, progress.total_bytes))
+                .map(drop)
+        };
+        self.bytes = progress.bytes;
+        self.error = result.err();
+    }
+
+    fn finish(self) -> PyResult<()> {
+        self.error.map_or(Ok(()), Err)
+    }
+}
+
 /// Read a waveform file
 ///
 /// Args:
 ///     filename: Path to the waveform file (.tr0, .ac0, .sw0)
+///     signals: Optional glob (or "re:" regex) selecting the signals to keep
+///     recover: Salvage the complete rows of a truncated file
+///     progress: tqdm-like object advanced by the bytes read, or a callable
+///         called as progress(bytes, total_bytes)
+///     non_monotonic: Rows whose scale steps back: "keep", "drop",
+///         "keep-last" or "error"
 ///
 /// Returns:
 ///     WaveformResult object or None if failed
 #[pyfunction]
-#[pyo3(signature = (filename))]
-pub fn read(_py: Python, filename: &str) -> PyResult<Option<PyWaveformResult>> {
-    match hspice_core::read(filename) {
-        Ok(result) => Ok(Some(result.into())),
+#[pyo3(signature = (filename, signals=None, recover=false, progress=None, non_monotonic="keep"))]
+pub fn read(
+    py: Python,
+    filename: &str,
+    signals: Option<&str>,
+    recover: bool,
+    progress: Option<Bound<'_, PyAny>>,
+    non_monotonic: &str,
+) -> PyResult<Option<PyWaveformResult>> {
+    let mode = NonMonotonic::from_name(non_monotonic).ok_or_else(|| {
+        PyValueError::new_err(format!(
+            "non_monotonic must be 'keep', 'drop', 'keep-last' or 'error', not {:?}",
+            non_monotonic
+        ))
+    })?;
+    let options = ReadOptions::new()
+        .recover(recover)
+        .clean_non_monotonic(mode);
+    let result = match progress {
+        Some(target) => {
+            let mut sink = PyProgress::new(target)?;
+            let result = hspice_core::read_with_progress(filename, &options, |p| sink.update(p));
+            sink.finish()?;
+            result
+        }
+        None => hspice_core::read_with_options(filename, &options),
+    };
+    match select_signals(result, signals) {
+        Ok(result) => PyWaveformResult::new(py, result).map(Some),
         Err(e) => {
             tracing::error!("Read error: {:?}", e);
             Ok(None)
@@ -251,7 +1364,70 @@ pub fn read(_py: Python, filename: &str) -> PyResult<Option<PyWaveformResult>> {
 #[pyo3(signature = (input_path, output_path))]
 pub fn convert_to_raw(_py: Python, input_path: &str, output_path: &str) -> PyResult<bool> {
     match hspice_core::read_and_convert(input_path, output_path) {
-        Ok(()) => Ok(true),
+        Ok(report) => {
+            tracing::info!(
+                points = report.points,
+                bytes = report.bytes,
+                "Conversion complete"
+            );
+            Ok(true)
+        }
+        Err(e) => {
+            tracing::error!("Conversion error: {:?}", e);
+            Ok(false)
+        }
+    }
+}
+
+/// Convert HSPICE file to CSV or JSON Lines one chunk at a time
+///
+/// Memory use is bounded by `chunk_size` rows however large the file.
+/// `format` is "csv" or "jsonl"; by default it comes from the output
+/// extension (.csv, .jsonl, .ndjson).
+#[pyfunction]
+#[pyo3(signature = (input_path, output_path, format=None, chunk_size=10000, signals=None, time_range=None))]
+pub fn convert_stream(
+    py: Python,
+    input_path: &str,
+    output_path: &str,
+    format: Option<&str>,
+    chunk_size: usize,
+    signals: Option<Vec<String>>,
+    time_range: Option<(f64, f64)>,
+) -> PyResult<bool> {
+    let format = match format {
+        None => hspice_core::ConvertFormat::from_path(output_path),
+        Some("csv") => Some(hspice_core::ConvertFormat::Csv),
+        Some("jsonl") => Some(hspice_core::ConvertFormat::Jsonl),
+        Some(other) => {
+            return Err(PyValueError::new_err(format!(
+                "unknown format {:?} (use \"csv\" or \"jsonl\")",
+                other
+            )))
+        }
+    };
+    let Some(format) = format else {
+        return Err(PyValueError::new_err(format!(
+            "{}: unknown output format (use .csv, .jsonl or .ndjson)",
+            output_path
+        )));
+    };
+    let options = hspice_core::ConvertOptions {
+        chunk_size,
+        signals,
+        time_range,
+    };
+    match py
+        .allow_threads(|| hspice_core::convert_stream(input_path, output_path, format, &options))
+    {
+        Ok(report) => {
+            tracing::info!(
+                points = report.points,
+                bytes = report.bytes,
+                "Conversion complete"
+            );
+            Ok(true)
+        }
         Err(e) => {
             tracing::error!("Conversion error: {:?}", e);
             Ok(false)
@@ -260,76 +1436,183 @@ pub fn convert_to_raw(_py: Python, input_path: &str, output_path: &str) -> PyRes
 }
 
 /// Stream a large waveform file in chunks
+///
+/// Returns a WaveformStream that decodes one chunk per iteration, so only
+/// the current chunk is held in memory. `signals` is a list of names or a
+/// glob (or "re:" regex) pattern. With `prefetch` > 0 a background thread
+/// decodes up to that many chunks ahead of the loop. With `time_span` each
+/// chunk covers one window of that many scale units instead of
+/// `chunk_size` rows.
 #[pyfunction]
-#[pyo3(signature = (filename, chunk_size=10000, signals=None))]
+#[pyo3(signature = (filename, chunk_size=10000, signals=None, prefetch=0, time_span=None))]
 pub fn stream(
-    py: Python,
     filename: &str,
     chunk_size: usize,
-    signals: Option<Vec<String>>,
-) -> PyResult<Py<PyList>> {
+    signals: Option<SignalSelection>,
+    prefetch: usize,
+    time_span: Option<f64>,
+) -> PyWaveformStream {
     use hspice_core::{read_stream_chunked, read_stream_signals};
 
     tracing::debug!("Opening stream: {} (chunk_size={})", filename, chunk_size);
 
-    let reader = if let Some(ref sigs) = signals {
-        let sig_refs: Vec<&str> = sigs.iter().map(|s| s.as_str()).collect();
-        match read_stream_signals(filename, &sig_refs, chunk_size) {
-            Ok(r) => r,
-            Err(e) => {
-                tracing::error!("Stream open error: {:?}", e);
-                return Ok(PyList::empty(py).unbind());
-            }
-        }
-    } else {
-        match read_stream_chunked(filename, chunk_size) {
-            Ok(r) => r,
-            Err(e) => {
-                tracing::error!("Stream open error: {:?}", e);
-                return Ok(PyList::empty(py).unbind());
-            }
+    let reader = match signals {
+        Some(SignalSelection::Names(ref sigs)) => {
+            let sig_refs: Vec<&str> = sigs.iter().map(|s| s.as_str()).collect();
+            read_stream_signals(filename, &sig_refs, chunk_size)
         }
+        Some(SignalSelection::Pattern(ref pattern)) => read_stream_chunked(filename, chunk_size)
+            .and_then(|reader| reader.with_signal_pattern(pattern)),
+        None => read_stream_chunked(filename, chunk_size),
     };
 
-    let chunks_list = PyList::empty(py);
+    let reader = reader.map(|reader| match time_span {
+        Some(span) => reader.chunk_by_time(span),
+        None => reader,
+    });
+    match reader {
+        Ok(reader) if prefetch > 0 => PyWaveformStream {
+            reader: Some(Box::new(reader.prefetch(prefetch))),
+        },
+        Ok(reader) => PyWaveformStream {
+            reader: Some(Box::new(reader)),
+        },
+        Err(e) => {
+            tracing::error!("Stream open error: {:?}", e);
+            PyWaveformStream { reader: None }
+        }
+    }
+}
 
-    for chunk_result in reader {
-        match chunk_result {
-            Ok(chunk) => {
-                let chunk_dict = PyDict::new(py);
-                chunk_dict.set_item("chunk_index", chunk.chunk_index)?;
-                chunk_dict.set_item("time_range", (chunk.time_range.0, chunk.time_range.1))?;
+/// Read every .ALTER run of a waveform file
+///
+/// Args:
+///     filename: Path to the waveform file (.tr0, .ac0, .sw0)
+///     recover: Return a truncated last run instead of failing
+///
+/// Returns:
+///     List of WaveformResult objects, one per run, or None if failed
+#[pyfunction]
+#[pyo3(signature = (filename, recover=false))]
+pub fn read_alters(
+    py: Python,
+    filename: &str,
+    recover: bool,
+) -> PyResult<Option<Vec<PyWaveformResult>>> {
+    let options = ReadOptions::new().recover(recover);
+    match hspice_core::read_alters_with_options(filename, &options) {
+        Ok(runs) => runs
+            .into_iter()
+            .map(|run| PyWaveformResult::new(py, run))
+            .collect::<PyResult<Vec<_>>>()
+            .map(Some),
+        Err(e) => {
+            tracing::error!("Read alters error: {:?}", e);
+            Ok(None)
+        }
+    }
+}
 
-                let data_dict = PyDict::new(py);
-                for (name, vector) in chunk.data {
-                    data_dict.set_item(name, vector_to_numpy(py, &vector))?;
-                }
-                chunk_dict.set_item("data", data_dict)?;
+/// Read an output split over numbered parts (.tr0, .tr1, ...) as one
+///
+/// Args:
+///     filename: Path to the first part
+///
+/// Returns:
+///     WaveformResult with the parts stitched together, or None if failed
+#[pyfunction]
+pub fn read_parts(py: Python, filename: &str) -> PyResult<Option<PyWaveformResult>> {
+    match hspice_core::read_parts(filename) {
+        Ok(result) => PyWaveformResult::new(py, result).map(Some),
+        Err(e) => {
+            tracing::error!("Read parts error: {:?}", e);
+            Ok(None)
+        }
+    }
+}
 
-                chunks_list.append(chunk_dict)?;
-            }
+/// Files to read: a glob pattern or a list of paths
+#[derive(FromPyObject)]
+pub enum CornerFiles {
+    Pattern(String),
+    Paths(Vec<String>),
+}
+
+/// Read many waveform files in parallel, one per corner
+///
+/// Files are read on up to `threads` threads with the GIL released.
+///
+/// Args:
+///     files: Glob pattern such as "runs/*.tr0" (expanded with the glob
+///         module, sorted) or a list of paths
+///     signals: Optional glob (or "re:" regex) selecting the signals to keep
+///     recover: Return what can be read of a truncated file
+///     threads: Number of threads (default: one per CPU)
+///
+/// Returns:
+///     Dict of corner name -> WaveformResult, or None for a file that
+///     could not be read (the error is logged), in file order. Corners are
+///     named by file stem ("tt" for "runs/tt.tr0"), or by the path below
+///     the common directory if stems repeat ("tt/inv" for "runs/tt/inv.tr0")
+#[pyfunction]
+#[pyo3(signature = (files, signals=None, recover=false, threads=None))]
+pub fn read_corners<'py>(
+    py: Python<'py>,
+    files: CornerFiles,
+    signals: Option<&str>,
+    recover: bool,
+    threads: Option<usize>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let mut paths: Vec<String> = match files {
+        CornerFiles::Pattern(pattern) => {
+            let mut paths: Vec<String> = py
+                .import("glob")?
+                .call_method1("glob", (pattern,))?
+                .extract()?;
+            paths.sort();
+            paths
+        }
+        CornerFiles::Paths(paths) => paths,
+    };
+    let mut seen = std::collections::HashSet::new();
+    paths.retain(|path| seen.insert(path.clone()));
+
+    let options = ReadOptions::new().recover(recover);
+    let results = py.allow_threads(|| {
+        hspice_core::read_many_with_progress(&paths, &options, threads.unwrap_or(0), |_| {})
+    });
+
+    let corners = PyDict::new(py);
+    for ((name, path), result) in corner_names(&paths).into_iter().zip(&paths).zip(results) {
+        let result = match select_signals(result, signals) {
+            Ok(result) => Some(PyWaveformResult::new(py, result)?),
             Err(e) => {
-                tracing::error!("Stream chunk error: {:?}", e);
-                break;
+                tracing::error!("Read error in {}: {:?}", path, e);
+                None
             }
-        }
+        };
+        corners.set_item(name, result)?;
     }
-
-    Ok(chunks_list.unbind())
+    Ok(corners)
 }
 
 /// Read a SPICE3/ngspice raw file (auto-detects binary/ASCII format)
 ///
 /// Args:
 ///     filename: Path to the raw file (.raw)
+///     signals: Optional glob (or "re:" regex) selecting the signals to keep
 ///
 /// Returns:
 ///     WaveformResult object or None if failed
 #[pyfunction]
-#[pyo3(signature = (filename))]
-pub fn read_raw(_py: Python, filename: &str) -> PyResult<Option<PyWaveformResult>> {
-    match hspice_core::read_raw(filename) {
-        Ok(result) => Ok(Some(result.into())),
+#[pyo3(signature = (filename, signals=None))]
+pub fn read_raw(
+    py: Python,
+    filename: &str,
+    signals: Option<&str>,
+) -> PyResult<Option<PyWaveformResult>> {
+    match select_signals(hspice_core::read_raw(filename), signals) {
+        Ok(result) => PyWaveformResult::new(py, result).map(Some),
         Err(e) => {
             tracing::error!("Read raw error: {:?}", e);
             Ok(None)
@@ -337,6 +1620,169 @@ pub fn read_raw(_py: Python, filename: &str) -> PyResult<Option<PyWaveformResult
     }
 }
 
+/// Read an HSPICE or SPICE3/ngspice raw file, detecting the format
+///
+/// The format is detected from the file contents, falling back to the
+/// extension (.tr0/.ac0/.sw0 or .raw).
+///
+/// Args:
+///     filename: Path to the waveform file
+///     signals: Optional glob (or "re:" regex) selecting the signals to keep
+///
+/// Returns:
+///     WaveformResult object or None if failed
+#[pyfunction]
+#[pyo3(signature = (filename, signals=None))]
+pub fn read_any(
+    py: Python,
+    filename: &str,
+    signals: Option<&str>,
+) -> PyResult<Option<PyWaveformResult>> {
+    match select_signals(hspice_core::read_any(filename), signals) {
+        Ok(result) => PyWaveformResult::new(py, result).map(Some),
+        Err(e) => {
+            tracing::error!("Read error: {:?}", e);
+            Ok(None)
+        }
+    }
+}
+
+/// Read the operating points of an HSPICE listing (.lis)
+///
+/// Args:
+///     filename: Path to the listing
+///
+/// Returns:
+///     WaveformResult object with one table per `.op` time, or None if
+///     failed
+#[pyfunction]
+#[pyo3(signature = (filename))]
+pub fn read_lis(py: Python, filename: &str) -> PyResult<Option<PyWaveformResult>> {
+    match hspice_core::read_lis(filename) {
+        Ok(result) => PyWaveformResult::new(py, result).map(Some),
+        Err(e) => {
+            tracing::error!("Read lis error: {:?}", e);
+            Ok(None)
+        }
+    }
+}
+
+/// Read the noise analysis results of an HSPICE listing (.lis)
+///
+/// Args:
+///     filename: Path to the listing
+///
+/// Returns:
+///     WaveformResult object with `onoise`, `inoise` and the element noise
+///     contributions against frequency, or None if failed
+#[pyfunction]
+#[pyo3(signature = (filename))]
+pub fn read_noise(py: Python, filename: &str) -> PyResult<Option<PyWaveformResult>> {
+    match hspice_core::read_noise(filename) {
+        Ok(result) => PyWaveformResult::new(py, result).map(Some),
+        Err(e) => {
+            tracing::error!("Read noise error: {:?}", e);
+            Ok(None)
+        }
+    }
+}
+
+/// Read the `.print` tables of an HSPICE listing (.lis) or .printtr0 file
+///
+/// Args:
+///     filename: Path to the listing or print file
+///
+/// Returns:
+///     WaveformResult object with one table per sweep, or None if failed
+#[pyfunction]
+#[pyo3(signature = (filename))]
+pub fn read_print(py: Python, filename: &str) -> PyResult<Option<PyWaveformResult>> {
+    match hspice_core::read_print(filename) {
+        Ok(result) => PyWaveformResult::new(py, result).map(Some),
+        Err(e) => {
+            tracing::error!("Read print error: {:?}", e);
+            Ok(None)
+        }
+    }
+}
+
+/// Check every data block of an HSPICE file without loading the data
+///
+/// Args:
+///     filename: Path to the HSPICE file (.tr0, .ac0, .sw0)
+///
+/// Returns:
+///     Dict with 'ok', 'blocks', 'tables', 'expected_tables', 'rows',
+///     'trailing_bytes' and 'issues' (dicts with 'offset', 'table' and
+///     'message'), or None if the file or its header cannot be read
+#[pyfunction]
+#[pyo3(signature = (filename))]
+pub fn verify(py: Python, filename: &str) -> PyResult<Option<Py<PyDict>>> {
+    let report = match py.allow_threads(|| hspice_core::integrity::verify(filename)) {
+        Ok(report) => report,
+        Err(e) => {
+            tracing::error!("Verify error: {:?}", e);
+            return Ok(None);
+        }
+    };
+    let dict = PyDict::new(py);
+    dict.set_item("ok", report.is_ok())?;
+    dict.set_item("blocks", report.blocks)?;
+    dict.set_item("tables", report.tables)?;
+    dict.set_item("expected_tables", report.expected_tables)?;
+    dict.set_item("rows", report.rows)?;
+    dict.set_item("trailing_bytes", report.trailing_bytes)?;
+    let issues = PyList::empty(py);
+    for issue in &report.issues {
+        let entry = PyDict::new(py);
+        entry.set_item("offset", issue.offset)?;
+        entry.set_item("table", issue.table)?;
+        entry.set_item("message", issue.kind.to_string())?;
+        issues.append(entry)?;
+    }
+    dict.set_item("issues", issues)?;
+    Ok(Some(dict.into()))
+}
+
+/// Open a waveform file for on-demand signal access
+///
+/// Args:
+///     filename: Path to the HSPICE file (.tr0, .ac0, .sw0)
+///
+/// Returns:
+///     LazyWaveform object or None if failed
+#[pyfunction]
+#[pyo3(signature = (filename))]
+pub fn read_lazy(py: Python, filename: &str) -> Option<PyLazyWaveform> {
+    match py.allow_threads(|| LazyWaveform::open(filename)) {
+        Ok(inner) => Some(PyLazyWaveform::new(inner)),
+        Err(e) => {
+            tracing::error!("Read error: {:?}", e);
+            None
+        }
+    }
+}
+
+/// Read an HSPICE measure file (.mt0, .ma0, .ms0)
+///
+/// Args:
+///     filename: Path to the measure file; the analysis is taken from the
+///         extension
+///
+/// Returns:
+///     MeasureResult object or None if failed
+#[pyfunction]
+#[pyo3(signature = (filename))]
+pub fn read_measure(filename: &str) -> Option<PyMeasureResult> {
+    match hspice_core::measure_file::read_measure(filename) {
+        Ok(inner) => Some(PyMeasureResult { inner }),
+        Err(e) => {
+            tracing::error!("Read measure error: {:?}", e);
+            None
+        }
+    }
+}
+
 // ============================================================================
 // Module Definition
 // ============================================================================
@@ -346,14 +1792,28 @@ pub fn hspicetr0parser(m: &Bound<'_, PyModule>) -> PyResult<()> {
     // Functions
     m.add_function(wrap_pyfunction!(init_logging, m)?)?;
     m.add_function(wrap_pyfunction!(read, m)?)?;
+    m.add_function(wrap_pyfunction!(read_alters, m)?)?;
+    m.add_function(wrap_pyfunction!(read_parts, m)?)?;
+    m.add_function(wrap_pyfunction!(read_corners, m)?)?;
     m.add_function(wrap_pyfunction!(read_raw, m)?)?;
+    m.add_function(wrap_pyfunction!(read_any, m)?)?;
+    m.add_function(wrap_pyfunction!(read_lis, m)?)?;
+    m.add_function(wrap_pyfunction!(read_noise, m)?)?;
+    m.add_function(wrap_pyfunction!(read_print, m)?)?;
+    m.add_function(wrap_pyfunction!(read_lazy, m)?)?;
+    m.add_function(wrap_pyfunction!(verify, m)?)?;
+    m.add_function(wrap_pyfunction!(read_measure, m)?)?;
     m.add_function(wrap_pyfunction!(convert_to_raw, m)?)?;
+    m.add_function(wrap_pyfunction!(convert_stream, m)?)?;
     m.add_function(wrap_pyfunction!(stream, m)?)?;
 
     // Classes
     m.add_class::<PyWaveformResult>()?;
     m.add_class::<PyVariable>()?;
     m.add_class::<PyDataTable>()?;
+    m.add_class::<PyWaveformStream>()?;
+    m.add_class::<PyLazyWaveform>()?;
+    m.add_class::<PyMeasureResult>()?;
 
     Ok(())
 }
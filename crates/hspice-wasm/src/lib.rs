@@ -2,30 +2,158 @@
 //!
 //! Provides JavaScript-friendly API for parsing HSPICE binary files in the browser.
 
-use hspice_core::{AnalysisType, VarType, VectorData, WaveformResult};
-use js_sys::{Array, Float64Array, Object, Reflect};
-use std::io::Write;
+use hspice_core::{
+    detect_format_bytes, parse_header_only, parse_header_variables, read_bytes_with_options,
+    read_raw_bytes, write_spice3_raw_to, AnalysisType, DataChunk, DataTable, FileFormat,
+    HspiceStreamReader, ParserLimits, RawEncoding, ReadOptions, SignalPattern, VarType, Variable,
+    VectorData, WaveformResult, DEFAULT_CHUNK_SIZE,
+};
+use js_sys::{Array, Float64Array, Object, Reflect, SharedArrayBuffer, Uint8Array};
+use serde::Deserialize;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use wasm_bindgen::prelude::*;
 
+// ============================================================================
+// TypeScript Definitions
+// ============================================================================
+
+// Shapes of the plain objects built with `Reflect::set` below; functions
+// name them with `unchecked_return_type` so the generated .d.ts is typed
+#[wasm_bindgen(typescript_custom_section)]
+const TS_TYPES: &'static str = r#"
+/** Variable metadata */
+export interface Variable {
+  /** Variable name (e.g., "TIME", "v(out)") */
+  name: string;
+  type: "time" | "frequency" | "voltage" | "current" | "power" | "parameter" | "internal" | "noise" | "unknown";
+  /** Unit symbol ("s", "Hz", "V", "A", "W", "C", "V/rtHz", "A/rtHz", "V^2/Hz"), or null if unknown */
+  unit: string | null;
+  /** Whether the signal is complex (AC); its data is then a `ComplexArray` */
+  isComplex: boolean;
+}
+
+/** Complex signal data as parallel real and imaginary arrays */
+export interface ComplexArray {
+  re: Float64Array;
+  im: Float64Array;
+}
+
+/** Real signal data, or `{re, im}` for complex signals */
+export type SignalData = Float64Array | ComplexArray;
+
+export type AnalysisName = "transient" | "ac" | "dc" | "operating" | "noise" | "unknown";
+
+/** Data table (one per sweep point) */
+export interface DataTable {
+  /** Sweep value, or null if the file is not swept */
+  sweepValue: number | null;
+  /** Signal data by name, scale included */
+  signals: Record<string, SignalData>;
+}
+
+/** Parsed waveform result */
+export interface WaveformResult {
+  title: string;
+  date: string;
+  /** Scale variable name (e.g., "TIME", "HERTZ") */
+  scaleName: string;
+  analysis: AnalysisName;
+  /** Variables, scale first */
+  variables: Variable[];
+  /** Sweep parameter name, or null if the file is not swept */
+  sweepParam: string | null;
+  tables: DataTable[];
+  /** Points in the first table */
+  numPoints: number;
+  numVars: number;
+  numSweeps: number;
+}
+
+/** Result of `parsePreview` */
+export interface PreviewResult extends WaveformResult {
+  /** One point kept per `stride` points of the file */
+  stride: number;
+  /** Number of signals in the file, before `maxSignals` was applied */
+  totalSignals: number;
+}
+
+/** Header summary from `parseMetadata` */
+export interface Metadata {
+  title: string;
+  date: string;
+  /** "unknown" when the header does not tell (most DC sweeps) */
+  analysis: AnalysisName;
+  scaleName: string;
+  /** Every name, scale first */
+  signals: string[];
+  variables: Variable[];
+  sweepParam: string | null;
+  numSweeps: number;
+  isComplex: boolean;
+  /** Upper estimate of the rows over all sweep tables */
+  estimatedPoints: number;
+}
+
+/** One chunk from a streaming reader */
+export interface StreamChunk {
+  chunkIndex: number;
+  /** Sweep table the rows belong to; a chunk never spans tables */
+  tableIndex: number;
+  sweepValue: number | null;
+  /** First and last scale value in the chunk */
+  timeRange: [number, number];
+  /** Signal data by name, scale included */
+  signals: Record<string, SignalData>;
+}
+
+/** Min/max decimated signal: one entry per non-empty bucket */
+export interface MinMaxSignal {
+  /** Bucket start on the scale axis */
+  time: Float64Array;
+  min: Float64Array;
+  max: Float64Array;
+}
+
+/** What `toCsv` writes */
+export interface CsvOptions {
+  /** Signals to write (all if missing); the scale is always written */
+  signals?: string[];
+  /** Only rows whose scale is at least this */
+  tStart?: number;
+  /** Only rows whose scale is at most this */
+  tStop?: number;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    /// `CsvOptions` object passed to `toCsv`
+    #[wasm_bindgen(typescript_type = "CsvOptions")]
+    pub type JsCsvOptions;
+}
+
 // ============================================================================
 // JavaScript Result Types
 // ============================================================================
 
-/// Parse HSPICE binary data from a Uint8Array
+/// Parse HSPICE binary or SPICE3/ngspice raw data from a Uint8Array
+///
+/// The format is detected from the leading bytes, so one call handles both.
 ///
 /// # Arguments
-/// * `data` - Binary file content as Uint8Array
+/// * `data` - File content as Uint8Array
 ///
 /// # Returns
 /// JavaScript object with parsed waveform data
-#[wasm_bindgen(js_name = parseHspice)]
+#[wasm_bindgen(js_name = parseHspice, unchecked_return_type = "WaveformResult")]
 pub fn parse_hspice(data: &[u8]) -> Result<JsValue, JsValue> {
     let result = parse_from_bytes(data)?;
     create_js_result(&result)
 }
 
 /// Get all signal names from parsed result
-#[wasm_bindgen(js_name = getSignalNames)]
+#[wasm_bindgen(js_name = getSignalNames, unchecked_return_type = "string[]")]
 pub fn get_signal_names(data: &[u8]) -> Result<Array, JsValue> {
     let result = parse_from_bytes(data)?;
 
@@ -37,7 +165,7 @@ pub fn get_signal_names(data: &[u8]) -> Result<Array, JsValue> {
 }
 
 /// Get signal data by name
-#[wasm_bindgen(js_name = getSignalData)]
+#[wasm_bindgen(js_name = getSignalData, unchecked_return_type = "SignalData")]
 pub fn get_signal_data(data: &[u8], signal_name: &str) -> Result<JsValue, JsValue> {
     let result = parse_from_bytes(data)?;
 
@@ -53,6 +181,637 @@ pub fn get_signal_data(data: &[u8], signal_name: &str) -> Result<JsValue, JsValu
     vector_to_js(&table.vectors[idx])
 }
 
+// ============================================================================
+// Header-Only Metadata
+// ============================================================================
+
+/// Summary of HSPICE binary data from its header alone
+///
+/// No data block is decoded, so this returns at once even for large files:
+/// `{title, date, analysis, scaleName, signals, variables, sweepParam,
+/// numSweeps, isComplex, estimatedPoints}`. `signals` lists every name,
+/// scale first, and `variables` has the `parseHspice` shape. `analysis` is
+/// "unknown" when the header does not tell (most DC sweeps), and
+/// `estimatedPoints` is an upper estimate of the rows over all sweep
+/// tables, from the data size.
+#[wasm_bindgen(js_name = parseMetadata, unchecked_return_type = "Metadata")]
+pub fn parse_metadata(data: &[u8]) -> Result<JsValue, JsValue> {
+    let (meta, data_start) =
+        parse_header_only(data).map_err(|e| JsValue::from_str(&format!("Parse error: {}", e)))?;
+    let names: Vec<&String> = std::iter::once(&meta.scale_name)
+        .chain(&meta.names)
+        .collect();
+
+    let result = Object::new();
+    Reflect::set(&result, &"title".into(), &meta.title.clone().into())?;
+    Reflect::set(&result, &"date".into(), &meta.date.clone().into())?;
+    Reflect::set(
+        &result,
+        &"analysis".into(),
+        &analysis_name(meta.analysis()).into(),
+    )?;
+    Reflect::set(
+        &result,
+        &"scaleName".into(),
+        &meta.scale_name.clone().into(),
+    )?;
+
+    let signals: Array = names.iter().map(|name| JsValue::from_str(name)).collect();
+    Reflect::set(&result, &"signals".into(), &signals)?;
+    let variables = Array::new();
+    let typed = parse_header_variables(data)
+        .map_err(|e| JsValue::from_str(&format!("Parse error: {}", e)))?;
+    for (i, var) in typed.iter().enumerate() {
+        variables.push(&variable_to_js(var, i > 0 && meta.is_complex())?);
+    }
+    Reflect::set(&result, &"variables".into(), &variables)?;
+
+    let sweep_param = meta.sweep_name.clone().map_or(JsValue::NULL, JsValue::from);
+    Reflect::set(&result, &"sweepParam".into(), &sweep_param)?;
+    Reflect::set(
+        &result,
+        &"numSweeps".into(),
+        &(meta.sweep_size.max(1) as u32).into(),
+    )?;
+    Reflect::set(&result, &"isComplex".into(), &meta.is_complex().into())?;
+    let points = meta.estimated_rows(data.len(), data_start);
+    Reflect::set(&result, &"estimatedPoints".into(), &(points as f64).into())?;
+    Ok(result.into())
+}
+
+// ============================================================================
+// Bounded Preview
+// ============================================================================
+
+/// Parse a bounded, decimated preview of HSPICE binary data
+///
+/// Keeps the first `max_signals` signals and every n-th point, with n
+/// estimated from the file size so that at most `max_points` points remain.
+/// Chunks are decimated as they are decoded, so memory use is set by the
+/// preview size rather than the file size. The result has the `parseHspice`
+/// shape plus `stride` (one point kept per `stride`) and `totalSignals`.
+#[wasm_bindgen(js_name = parsePreview, unchecked_return_type = "PreviewResult")]
+pub fn parse_preview(
+    data: Vec<u8>,
+    max_points: usize,
+    max_signals: usize,
+) -> Result<JsValue, JsValue> {
+    let to_js = |e: hspice_core::WaveformError| JsValue::from_str(&format!("Parse error: {}", e));
+    let reader = HspiceStreamReader::from_bytes(data, DEFAULT_CHUNK_SIZE).map_err(to_js)?;
+    let total_signals = reader.metadata().signal_names.len();
+    let (preview, stride) = preview(reader, max_points.max(1), max_signals).map_err(to_js)?;
+
+    let result = create_js_result(&preview)?;
+    Reflect::set(&result, &"stride".into(), &(stride as u32).into())?;
+    Reflect::set(
+        &result,
+        &"totalSignals".into(),
+        &(total_signals as u32).into(),
+    )?;
+    Ok(result)
+}
+
+/// Decimated result of the first `max_signals` signals, and the stride used
+fn preview(
+    reader: HspiceStreamReader,
+    max_points: usize,
+    max_signals: usize,
+) -> hspice_core::Result<(WaveformResult, usize)> {
+    let meta = reader.metadata();
+    let mut stride = reader.estimated_rows().div_ceil(max_points).max(1);
+    let mut variables = reader.variables();
+    variables.truncate(max_signals.saturating_add(1));
+    let names: Vec<String> = variables.iter().map(|v| v.name.clone()).collect();
+    let reader = reader.with_signals(names[1..].to_vec());
+
+    let mut columns: Vec<Option<VectorData>> = vec![None; names.len()];
+    let mut row = 0;
+    for chunk in reader {
+        let chunk = chunk?;
+        // Keep rows whose index in the whole file is a multiple of stride
+        let first = (stride - row % stride) % stride;
+        for (column, name) in columns.iter_mut().zip(&names) {
+            if let Some(vector) = chunk.data.get(name) {
+                append_every(column, vector, first, stride);
+            }
+        }
+        row += chunk.data.get(&meta.scale_name).map_or(0, VectorData::len);
+
+        // The size estimate was low: drop every other kept point
+        while columns[0].as_ref().map_or(0, VectorData::len) > max_points {
+            columns.iter_mut().flatten().for_each(keep_even);
+            stride *= 2;
+        }
+    }
+
+    let analysis = if meta.is_complex {
+        AnalysisType::AC
+    } else {
+        AnalysisType::from_scale_name(&meta.scale_name)
+    };
+    let (variables, vectors) = variables
+        .into_iter()
+        .zip(columns)
+        .filter_map(|(var, column)| Some((var, column?)))
+        .unzip();
+    let table = DataTable {
+        sweep_value: None,
+        vectors,
+    };
+    let result = WaveformResult::new(
+        meta.title,
+        meta.date,
+        analysis,
+        variables,
+        None,
+        vec![table],
+    );
+    Ok((result, stride))
+}
+
+/// Append every `stride`-th value of `source`, starting at `first`
+fn append_every(target: &mut Option<VectorData>, source: &VectorData, first: usize, stride: usize) {
+    let target = target.get_or_insert_with(|| match source {
+        VectorData::Complex(_) => VectorData::Complex(Vec::new().into()),
+        _ => VectorData::Real(Vec::new().into()),
+    });
+    match (target, source) {
+        (VectorData::Real(t), VectorData::Real(s)) => {
+            t.extend(s.iter().skip(first).step_by(stride))
+        }
+        (VectorData::Complex(t), VectorData::Complex(s)) => {
+            t.extend(s.iter().skip(first).step_by(stride))
+        }
+        _ => {}
+    }
+}
+
+fn keep_even(vector: &mut VectorData) {
+    fn thin<T: Copy>(values: &mut Vec<T>) {
+        *values = values.iter().step_by(2).copied().collect();
+    }
+    match vector {
+        VectorData::Real(v) => thin(v),
+        VectorData::Complex(v) => thin(v),
+        VectorData::Real32(v) => thin(v),
+        VectorData::Stepped { .. } => {}
+    }
+}
+
+// ============================================================================
+// Selective Parse
+// ============================================================================
+
+/// Parse only the named signals of HSPICE binary data, optionally within
+/// `[tStart, tStop]` of the scale
+///
+/// Only the requested columns are decoded and rows outside the window are
+/// dropped as blocks are read, so memory is set by the selection rather
+/// than the file size. For transient data the reader first seeks to
+/// `tStart`. The scale is always included and every sweep table is kept.
+/// SPICE3/ngspice raw data is parsed whole and then cut down. The result
+/// has the `parseHspice` shape; an unknown name is an error.
+#[wasm_bindgen(js_name = parseSignals, unchecked_return_type = "WaveformResult")]
+pub fn parse_signals(
+    data: Vec<u8>,
+    names: Vec<String>,
+    t_start: Option<f64>,
+    t_stop: Option<f64>,
+) -> Result<JsValue, JsValue> {
+    let window = (
+        t_start.unwrap_or(f64::NEG_INFINITY),
+        t_stop.unwrap_or(f64::INFINITY),
+    );
+    create_js_result(&select_from_bytes(data, Some(names), window)?)
+}
+
+/// The scale and `names` (all signals if `None`) of HSPICE binary or
+/// SPICE3/ngspice raw data, keeping rows with a scale value in `window`
+fn select_from_bytes(
+    data: Vec<u8>,
+    names: Option<Vec<String>>,
+    window: (f64, f64),
+) -> Result<WaveformResult, JsValue> {
+    let check_names =
+        |names: &[String], known: &[&String]| match names.iter().find(|name| !known.contains(name))
+        {
+            Some(name) => Err(JsValue::from_str(&format!("Signal not found: {}", name))),
+            None => Ok(()),
+        };
+
+    if detect_format_bytes(&data) == Some(FileFormat::SpiceRaw) {
+        let result = parse_raw_from_bytes(&data)?;
+        let known: Vec<&String> = result.variables.iter().map(|v| &v.name).collect();
+        let names = names.unwrap_or_else(|| known[1..].iter().map(|n| n.to_string()).collect());
+        check_names(&names, &known)?;
+        return Ok(restrict(&result, &names, window));
+    }
+
+    let to_js = |e: hspice_core::WaveformError| JsValue::from_str(&format!("Parse error: {}", e));
+    let sweep_param = parse_header_only(&data).map_err(to_js)?.0.sweep_name;
+    let reader = HspiceStreamReader::from_bytes(data, DEFAULT_CHUNK_SIZE).map_err(to_js)?;
+    let meta = reader.metadata();
+    let names = names.unwrap_or_else(|| meta.signal_names.clone());
+    let known: Vec<&String> = std::iter::once(&meta.scale_name)
+        .chain(&meta.signal_names)
+        .collect();
+    check_names(&names, &known)?;
+
+    let mut result = select_window(reader, names, window).map_err(to_js)?;
+    result.sweep_param = sweep_param;
+    Ok(result)
+}
+
+/// The scale and `names` of every table, keeping rows with a scale value in
+/// `window`
+fn select_window(
+    reader: HspiceStreamReader,
+    names: Vec<String>,
+    window: (f64, f64),
+) -> hspice_core::Result<WaveformResult> {
+    let meta = reader.metadata();
+    let names: Vec<String> = std::iter::once(meta.scale_name.clone())
+        .chain(names.into_iter().filter(|name| *name != meta.scale_name))
+        .collect();
+    let typed = reader.variables();
+    let variables = names
+        .iter()
+        .map(|name| {
+            typed
+                .iter()
+                .find(|v| v.name == *name)
+                .cloned()
+                .unwrap_or_else(|| Variable::new(name))
+        })
+        .collect();
+    let mut reader = reader
+        .with_signals(names[1..].to_vec())
+        .filter_time(window.0, window.1);
+    // Only a transient scale is known to ascend, as seeking needs
+    if meta.scale_name.eq_ignore_ascii_case("TIME") && window.0.is_finite() {
+        reader.seek_time(window.0)?;
+    }
+
+    // Tables without rows in the window yield no chunk and are left out
+    let mut tables: Vec<(usize, Option<f64>, Vec<Option<VectorData>>)> = Vec::new();
+    for chunk in reader {
+        let chunk = chunk?;
+        if tables
+            .last()
+            .is_none_or(|table| table.0 != chunk.table_index)
+        {
+            tables.push((
+                chunk.table_index,
+                chunk.sweep_value,
+                vec![None; names.len()],
+            ));
+        }
+        let columns = &mut tables.last_mut().expect("pushed above").2;
+        for (column, name) in columns.iter_mut().zip(&names) {
+            if let Some(vector) = chunk.data.get(name) {
+                append_every(column, vector, 0, 1);
+            }
+        }
+    }
+
+    // An empty window still reports the requested signals
+    let is_complex = meta.is_complex;
+    let empty = |i: usize| {
+        if i > 0 && is_complex {
+            VectorData::Complex(Vec::new().into())
+        } else {
+            VectorData::Real(Vec::new().into())
+        }
+    };
+    if tables.is_empty() {
+        tables.push((0, None, vec![None; names.len()]));
+    }
+    let analysis = if meta.is_complex {
+        AnalysisType::AC
+    } else {
+        AnalysisType::from_scale_name(&meta.scale_name)
+    };
+    let tables = tables
+        .into_iter()
+        .map(|(_, sweep_value, columns)| DataTable {
+            sweep_value,
+            vectors: columns
+                .into_iter()
+                .enumerate()
+                .map(|(i, column)| column.unwrap_or_else(|| empty(i)))
+                .collect(),
+        })
+        .collect();
+    Ok(WaveformResult::new(
+        meta.title, meta.date, analysis, variables, None, tables,
+    ))
+}
+
+/// `result` cut down to the scale and `names`, keeping rows with a scale
+/// value in `window`
+fn restrict(result: &WaveformResult, names: &[String], window: (f64, f64)) -> WaveformResult {
+    let indices: Vec<usize> = std::iter::once(0)
+        .chain(
+            names
+                .iter()
+                .filter_map(|name| result.var_index(name))
+                .filter(|&i| i > 0),
+        )
+        .collect();
+    let in_window = |value: f64| (window.0..=window.1).contains(&value);
+
+    let tables = result
+        .tables
+        .iter()
+        .map(|table| {
+            // A complex scale (AC frequency in raw files) is real valued
+            let keep: Vec<bool> = match &table.vectors[0] {
+                VectorData::Complex(values) => values.iter().map(|c| in_window(c.re)).collect(),
+                scale => scale
+                    .to_real()
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|&t| in_window(t))
+                    .collect(),
+            };
+            DataTable {
+                sweep_value: table.sweep_value,
+                vectors: indices
+                    .iter()
+                    .map(|&i| keep_rows(&table.vectors[i], &keep))
+                    .collect(),
+            }
+        })
+        .collect();
+
+    let variables = indices
+        .iter()
+        .map(|&i| result.variables[i].clone())
+        .collect();
+    let mut restricted = WaveformResult::new(
+        result.title.clone(),
+        result.date.clone(),
+        result.analysis,
+        variables,
+        result.sweep_param.clone(),
+        tables,
+    );
+    restricted.truncated = result.truncated;
+    restricted
+}
+
+/// The rows of `vector` where `keep` is true
+fn keep_rows(vector: &VectorData, keep: &[bool]) -> VectorData {
+    fn filter<T: Copy>(values: &[T], keep: &[bool]) -> Vec<T> {
+        values
+            .iter()
+            .zip(keep)
+            .filter(|(_, &k)| k)
+            .map(|(&v, _)| v)
+            .collect()
+    }
+    match vector {
+        VectorData::Complex(values) => VectorData::Complex(filter(values, keep).into()),
+        other => VectorData::Real(filter(&other.to_real().unwrap_or_default(), keep).into()),
+    }
+}
+
+// ============================================================================
+// Browser Conversion
+// ============================================================================
+
+/// What `toCsv` writes (`CsvOptions` in TypeScript)
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct CsvOptions {
+    /// Signals to write (all if missing); the scale is always written
+    signals: Option<Vec<String>>,
+    /// Only rows whose scale is at least this
+    t_start: Option<f64>,
+    /// Only rows whose scale is at most this
+    t_stop: Option<f64>,
+}
+
+/// Convert HSPICE binary or SPICE3/ngspice raw data to CSV text
+///
+/// `options` is `{signals?, tStart?, tStop?}`, read like `parseSignals`
+/// arguments. The layout is the one of the CLI's `export`: a header row, a
+/// leading sweep column for swept files and `<name>.re`/`<name>.im`
+/// columns for complex signals.
+#[wasm_bindgen(js_name = toCsv)]
+pub fn to_csv(data: Vec<u8>, options: Option<JsCsvOptions>) -> Result<String, JsValue> {
+    let options: CsvOptions = match options {
+        Some(options) => serde_wasm_bindgen::from_value(options.into())
+            .map_err(|e| JsValue::from_str(&format!("Invalid options: {}", e)))?,
+        None => CsvOptions::default(),
+    };
+    let window = (
+        options.t_start.unwrap_or(f64::NEG_INFINITY),
+        options.t_stop.unwrap_or(f64::INFINITY),
+    );
+    let result = select_from_bytes(data, options.signals, window)?;
+
+    let mut csv = Vec::new();
+    result
+        .write_csv(&mut csv)
+        .map_err(|e| JsValue::from_str(&format!("Write error: {}", e)))?;
+    String::from_utf8(csv).map_err(|e| JsValue::from_str(&format!("Write error: {}", e)))
+}
+
+/// Convert HSPICE binary or SPICE3/ngspice raw data to a SPICE3 raw file
+///
+/// The data is binary unless `ascii` is true. SPICE3 raw holds one table,
+/// so only the first sweep table of a swept file is written.
+#[wasm_bindgen(js_name = toSpice3Raw)]
+pub fn to_spice3_raw(data: &[u8], ascii: Option<bool>) -> Result<Uint8Array, JsValue> {
+    let result = parse_from_bytes(data)?;
+    let encoding = if ascii.unwrap_or(false) {
+        RawEncoding::ascii()
+    } else {
+        RawEncoding::Binary
+    };
+    let mut raw = Vec::new();
+    write_spice3_raw_to(&result, &mut raw, encoding)
+        .map_err(|e| JsValue::from_str(&format!("Write error: {}", e)))?;
+    Ok(Uint8Array::from(raw.as_slice()))
+}
+
+// ============================================================================
+// Parsed File Handle
+// ============================================================================
+
+/// Stepped and single precision signals expanded for `signalView`, by
+/// (table, var)
+type ExpandedVectors = RefCell<HashMap<(usize, usize), Box<[f64]>>>;
+
+/// HSPICE file parsed once for repeated signal lookups
+///
+/// `getSignalNames` and `getSignalData` parse the whole buffer on every
+/// call; a viewer that pulls out many signals should hold one of these.
+#[wasm_bindgen]
+pub struct HspiceFile {
+    result: WaveformResult,
+    expanded: ExpandedVectors,
+}
+
+#[wasm_bindgen]
+impl HspiceFile {
+    /// Parse HSPICE binary data from a Uint8Array
+    #[wasm_bindgen(constructor)]
+    pub fn new(data: &[u8]) -> Result<HspiceFile, JsValue> {
+        Ok(HspiceFile {
+            result: parse_from_bytes(data)?,
+            expanded: RefCell::new(HashMap::new()),
+        })
+    }
+
+    /// All signal names, scale first
+    #[wasm_bindgen(js_name = signalNames, unchecked_return_type = "string[]")]
+    pub fn signal_names(&self) -> Array {
+        self.result
+            .variables
+            .iter()
+            .map(|var| JsValue::from_str(&var.name))
+            .collect()
+    }
+
+    /// New file holding the scale and the signals matching `pattern`: a
+    /// glob such as `"v(x1.*)"` (case-insensitive) or a `"re:"` regex
+    pub fn select(&self, pattern: &str) -> Result<HspiceFile, JsValue> {
+        Ok(HspiceFile {
+            result: self
+                .result
+                .select(pattern)
+                .map_err(|e| JsValue::from_str(&format!("Select error: {}", e)))?,
+            expanded: RefCell::new(HashMap::new()),
+        })
+    }
+
+    /// Signal data by name from sweep table `table` (default 0)
+    #[wasm_bindgen(unchecked_return_type = "SignalData")]
+    pub fn signal(&self, name: &str, table: Option<usize>) -> Result<JsValue, JsValue> {
+        let (table, idx) = self.locate(name, table)?;
+        vector_to_js(&self.result.tables[table].vectors[idx])
+    }
+
+    /// Zero-copy view of a signal over WebAssembly memory
+    ///
+    /// The view is only valid until the next call into this module that
+    /// allocates (any parse, lookup or chunk) and until `free()`; after that
+    /// it may silently read other data. Copy it with `.slice()` to keep it.
+    #[wasm_bindgen(js_name = signalView)]
+    pub fn signal_view(&self, name: &str, table: Option<usize>) -> Result<Float64Array, JsValue> {
+        let (table, idx) = self.locate(name, table)?;
+        let vector = &self.result.tables[table].vectors[idx];
+        if let VectorData::Real(values) = vector {
+            // SAFETY: the slice lives as long as `self`; the lifetime
+            // limits on the JS side are documented above
+            return Ok(unsafe { Float64Array::view(values) });
+        }
+
+        if vector.is_complex() {
+            return Err(JsValue::from_str(&format!(
+                "{} is complex; use signal() for its re/im arrays",
+                name
+            )));
+        }
+
+        let mut expanded = self.expanded.borrow_mut();
+        let values = expanded.entry((table, idx)).or_insert_with(|| {
+            vector
+                .to_real()
+                .unwrap_or_default()
+                .into_owned()
+                .into_boxed_slice()
+        });
+        // SAFETY: boxed slices are never removed or reallocated while
+        // `self` is alive
+        Ok(unsafe { Float64Array::view(values) })
+    }
+
+    /// Per-bucket `{time, min, max}` of a signal (see `getDecimatedSignal`)
+    #[wasm_bindgen(js_name = decimatedSignal, unchecked_return_type = "MinMaxSignal")]
+    pub fn decimated_signal(
+        &self,
+        name: &str,
+        num_buckets: usize,
+        table: Option<usize>,
+    ) -> Result<JsValue, JsValue> {
+        let (table, idx) = self.locate(name, table)?;
+        let vectors = &self.result.tables[table].vectors;
+        let scale = vectors[0]
+            .to_real()
+            .ok_or_else(|| JsValue::from_str("Scale is not real"))?;
+        let values = match &vectors[idx] {
+            VectorData::Complex(vec) => vec.iter().map(|c| c.norm()).collect(),
+            other => other.to_real().unwrap_or_default(),
+        };
+
+        let (time, min, max) = min_max_buckets(&scale, &values, num_buckets.max(1));
+        let result = Object::new();
+        Reflect::set(&result, &"time".into(), &f64_array(&time))?;
+        Reflect::set(&result, &"min".into(), &f64_array(&min))?;
+        Reflect::set(&result, &"max".into(), &f64_array(&max))?;
+        Ok(result.into())
+    }
+
+    /// (table, variable) index of a signal, with errors for JS
+    fn locate(&self, name: &str, table: Option<usize>) -> Result<(usize, usize), JsValue> {
+        let idx = self
+            .result
+            .var_index(name)
+            .ok_or_else(|| JsValue::from_str(&format!("Signal not found: {}", name)))?;
+        let table = table.unwrap_or(0);
+        if table >= self.result.tables.len() {
+            return Err(JsValue::from_str(&format!("No data table {}", table)));
+        }
+        Ok((table, idx))
+    }
+
+    /// Whole result as returned by `parseHspice`
+    #[wasm_bindgen(js_name = toObject, unchecked_return_type = "WaveformResult")]
+    pub fn to_object(&self) -> Result<JsValue, JsValue> {
+        create_js_result(&self.result)
+    }
+
+    /// Simulation title
+    #[wasm_bindgen(getter)]
+    pub fn title(&self) -> String {
+        self.result.title.clone()
+    }
+
+    /// Scale variable name (e.g. "TIME")
+    #[wasm_bindgen(getter, js_name = scaleName)]
+    pub fn scale_name(&self) -> String {
+        self.result.scale_name().to_string()
+    }
+
+    /// Number of data points
+    #[wasm_bindgen(getter, js_name = numPoints)]
+    pub fn num_points(&self) -> usize {
+        self.result.len()
+    }
+
+    /// Number of sweep tables
+    #[wasm_bindgen(getter, js_name = numTables)]
+    pub fn num_tables(&self) -> usize {
+        self.result.tables.len()
+    }
+}
+
+/// Back every Float64Array output with a SharedArrayBuffer
+///
+/// Shared outputs can be posted to worker threads without a copy. The page
+/// must be cross-origin isolated for `SharedArrayBuffer` to exist.
+#[wasm_bindgen(js_name = setSharedOutput)]
+pub fn set_shared_output(enabled: bool) {
+    SHARED_OUTPUT.set(enabled);
+}
+
+thread_local! {
+    static SHARED_OUTPUT: Cell<bool> = const { Cell::new(false) };
+}
+
 // ============================================================================
 // SPICE3 Raw File Parser
 // ============================================================================
@@ -64,66 +823,209 @@ pub fn get_signal_data(data: &[u8], signal_name: &str) -> Result<JsValue, JsValu
 ///
 /// # Returns
 /// JavaScript object with parsed waveform data
-#[wasm_bindgen(js_name = parseRaw)]
+#[wasm_bindgen(js_name = parseRaw, unchecked_return_type = "WaveformResult")]
 pub fn parse_raw(data: &[u8]) -> Result<JsValue, JsValue> {
     let result = parse_raw_from_bytes(data)?;
     create_js_result(&result)
 }
 
 // ============================================================================
-// Internal Helpers
+// Min/Max Decimation
 // ============================================================================
 
-fn parse_raw_from_bytes(data: &[u8]) -> Result<WaveformResult, JsValue> {
-    // Create temp file for parsing
-    let temp_dir = std::env::temp_dir();
-    let temp_path = temp_dir.join("raw_wasm_temp.raw");
+/// Per-bucket min/max of a signal, for drawing it `num_buckets` pixels wide
+///
+/// Returns `{time, min, max}`: the scale range is split into equal-width
+/// buckets and each non-empty bucket gives its start time and the extremes
+/// of the signal inside it, so peaks survive any amount of decimation.
+/// Complex signals are decimated by magnitude.
+#[wasm_bindgen(js_name = getDecimatedSignal, unchecked_return_type = "MinMaxSignal")]
+pub fn get_decimated_signal(
+    data: &[u8],
+    signal_name: &str,
+    num_buckets: usize,
+) -> Result<JsValue, JsValue> {
+    HspiceFile::new(data)?.decimated_signal(signal_name, num_buckets, None)
+}
 
-    let mut file = std::fs::File::create(&temp_path)
-        .map_err(|e| JsValue::from_str(&format!("Failed to create temp file: {}", e)))?;
+/// `(start, min, max)` of each non-empty equal-width bucket of `x`
+///
+/// With no more points than buckets, every point is its own bucket.
+fn min_max_buckets(x: &[f64], y: &[f64], num_buckets: usize) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+    let n = x.len().min(y.len());
+    if n <= num_buckets || n < 2 {
+        return (x[..n].to_vec(), y[..n].to_vec(), y[..n].to_vec());
+    }
 
-    file.write_all(data)
-        .map_err(|e| JsValue::from_str(&format!("Failed to write data: {}", e)))?;
+    let (x0, span) = (x[0], x[n - 1] - x[0]);
+    let width = span / num_buckets as f64;
+    let mut starts = Vec::with_capacity(num_buckets);
+    let mut mins = Vec::with_capacity(num_buckets);
+    let mut maxs = Vec::with_capacity(num_buckets);
+    let mut current = usize::MAX;
+    for (&xi, &yi) in x[..n].iter().zip(&y[..n]) {
+        let bucket = if width > 0.0 {
+            (((xi - x0) / width) as usize).min(num_buckets - 1)
+        } else {
+            0
+        };
+        if bucket != current {
+            current = bucket;
+            starts.push(x0 + bucket as f64 * width);
+            mins.push(yi);
+            maxs.push(yi);
+        } else {
+            let last = mins.len() - 1;
+            mins[last] = mins[last].min(yi);
+            maxs[last] = maxs[last].max(yi);
+        }
+    }
+    (starts, mins, maxs)
+}
 
-    drop(file);
+// ============================================================================
+// Complex Helpers
+// ============================================================================
 
-    let temp_path_str = temp_path
-        .to_str()
-        .ok_or_else(|| JsValue::from_str("Invalid temp path"))?;
+/// Magnitude of a complex signal given as `{re, im}` arrays
+#[wasm_bindgen(js_name = complexMagnitude)]
+pub fn complex_magnitude(re: &[f64], im: &[f64]) -> Float64Array {
+    let mag: Vec<f64> = re.iter().zip(im).map(|(re, im)| re.hypot(*im)).collect();
+    f64_array(&mag)
+}
 
-    let result = hspice_core::read_raw(temp_path_str)
-        .map_err(|e| JsValue::from_str(&format!("Parse raw error: {:?}", e)))?;
+/// Phase of a complex signal given as `{re, im}` arrays, in degrees
+/// (or radians if `degrees` is false)
+#[wasm_bindgen(js_name = complexPhase)]
+pub fn complex_phase(re: &[f64], im: &[f64], degrees: Option<bool>) -> Float64Array {
+    let to_unit = if degrees.unwrap_or(true) {
+        f64::to_degrees
+    } else {
+        std::convert::identity
+    };
+    let phase: Vec<f64> = re
+        .iter()
+        .zip(im)
+        .map(|(re, im)| to_unit(im.atan2(*re)))
+        .collect();
+    f64_array(&phase)
+}
 
-    // Cleanup
-    let _ = std::fs::remove_file(&temp_path);
+// ============================================================================
+// Streaming Reader
+// ============================================================================
 
-    Ok(result)
+/// Chunked reader over HSPICE file contents
+///
+/// Decodes one chunk per `nextChunk()` call, so a viewer can render
+/// progressively without holding every signal of a large file as
+/// Float64Arrays at once.
+#[wasm_bindgen]
+pub struct WasmStreamReader {
+    reader: HspiceStreamReader,
 }
 
-fn parse_from_bytes(data: &[u8]) -> Result<WaveformResult, JsValue> {
-    // Create temp file for parsing (WASM can't access filesystem)
-    let temp_dir = std::env::temp_dir();
-    let temp_path = temp_dir.join("hspice_wasm_temp.tr0");
+/// Open a chunked reader over HSPICE binary data
+///
+/// # Arguments
+/// * `data` - Binary file content as Uint8Array
+/// * `chunk_size` - Minimum number of points per chunk
+#[wasm_bindgen(js_name = openStream)]
+pub fn open_stream(data: Vec<u8>, chunk_size: usize) -> Result<WasmStreamReader, JsValue> {
+    let reader = HspiceStreamReader::from_bytes(data, chunk_size)
+        .map_err(|e| JsValue::from_str(&format!("Stream open error: {}", e)))?;
+    Ok(WasmStreamReader { reader })
+}
 
-    let mut file = std::fs::File::create(&temp_path)
-        .map_err(|e| JsValue::from_str(&format!("Failed to create temp file: {}", e)))?;
+#[wasm_bindgen]
+impl WasmStreamReader {
+    /// Next chunk as `{chunkIndex, timeRange, signals}`, or null at the end
+    #[wasm_bindgen(js_name = nextChunk, unchecked_return_type = "StreamChunk | null")]
+    pub fn next_chunk(&mut self) -> Result<JsValue, JsValue> {
+        match self.reader.next() {
+            Some(Ok(chunk)) => create_js_chunk(&chunk),
+            Some(Err(e)) => Err(JsValue::from_str(&format!("Stream error: {}", e))),
+            None => Ok(JsValue::NULL),
+        }
+    }
 
-    file.write_all(data)
-        .map_err(|e| JsValue::from_str(&format!("Failed to write data: {}", e)))?;
+    /// Restrict later chunks to these signals (the scale is always included)
+    #[wasm_bindgen(js_name = setSignals)]
+    pub fn set_signals(&mut self, names: Vec<String>) {
+        self.reader.set_signals(Some(names));
+    }
+
+    /// Restrict later chunks to the signals matching `pattern` (see
+    /// `HspiceFile.select`); returns the number of matching signals
+    #[wasm_bindgen(js_name = setSignalPattern)]
+    pub fn set_signal_pattern(&mut self, pattern: &str) -> Result<usize, JsValue> {
+        let pattern = SignalPattern::new(pattern)
+            .map_err(|e| JsValue::from_str(&format!("Select error: {}", e)))?;
+        let names = self.reader.metadata().signal_names;
+        let signals: Vec<String> = pattern
+            .filter(&names)
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        if signals.is_empty() {
+            return Err(JsValue::from_str(&format!(
+                "No signal matches {:?}",
+                pattern.as_str()
+            )));
+        }
+        let count = signals.len();
+        self.reader.set_signals(Some(signals));
+        Ok(count)
+    }
 
-    drop(file);
+    /// Rewind to the first chunk
+    pub fn reset(&mut self) {
+        self.reader.reset();
+    }
 
-    let temp_path_str = temp_path
-        .to_str()
-        .ok_or_else(|| JsValue::from_str("Invalid temp path"))?;
+    /// Position the reader so the next chunk starts at `time`
+    #[wasm_bindgen(js_name = seekTime)]
+    pub fn seek_time(&mut self, time: f64) -> Result<(), JsValue> {
+        self.reader
+            .seek_time(time)
+            .map_err(|e| JsValue::from_str(&format!("Seek error: {}", e)))
+    }
 
-    let result = hspice_core::read(temp_path_str)
-        .map_err(|e| JsValue::from_str(&format!("Parse error: {:?}", e)))?;
+    /// Scale variable name (e.g. "TIME")
+    #[wasm_bindgen(getter, js_name = scaleName)]
+    pub fn scale_name(&self) -> String {
+        self.reader.metadata().scale_name
+    }
 
-    // Cleanup
-    let _ = std::fs::remove_file(&temp_path);
+    /// Names of all signals except the scale
+    #[wasm_bindgen(getter, js_name = signalNames, unchecked_return_type = "string[]")]
+    pub fn signal_names(&self) -> Array {
+        self.reader
+            .metadata()
+            .signal_names
+            .iter()
+            .map(|name| JsValue::from_str(name))
+            .collect()
+    }
+}
 
-    Ok(result)
+// ============================================================================
+// Internal Helpers
+// ============================================================================
+
+fn parse_raw_from_bytes(data: &[u8]) -> Result<WaveformResult, JsValue> {
+    read_raw_bytes(data, &ParserLimits::unlimited())
+        .map_err(|e| JsValue::from_str(&format!("Parse raw error: {}", e)))
+}
+
+/// Parse HSPICE binary or SPICE3/ngspice raw data, detected from the
+/// leading bytes
+fn parse_from_bytes(data: &[u8]) -> Result<WaveformResult, JsValue> {
+    if detect_format_bytes(data) == Some(FileFormat::SpiceRaw) {
+        return parse_raw_from_bytes(data);
+    }
+    read_bytes_with_options(data, &ReadOptions::new())
+        .map_err(|e| JsValue::from_str(&format!("Parse error: {}", e)))
 }
 
 fn create_js_result(data: &WaveformResult) -> Result<JsValue, JsValue> {
@@ -135,30 +1037,20 @@ fn create_js_result(data: &WaveformResult) -> Result<JsValue, JsValue> {
     Reflect::set(&result, &"scaleName".into(), &data.scale_name().into())?;
 
     // Analysis type
-    let analysis = match data.analysis {
-        AnalysisType::Transient => "transient",
-        AnalysisType::AC => "ac",
-        AnalysisType::DC => "dc",
-        AnalysisType::Operating => "operating",
-        AnalysisType::Noise => "noise",
-        AnalysisType::Unknown => "unknown",
-    };
-    Reflect::set(&result, &"analysis".into(), &analysis.into())?;
+    Reflect::set(
+        &result,
+        &"analysis".into(),
+        &analysis_name(data.analysis).into(),
+    )?;
 
     // Variables
     let variables = Array::new();
-    for var in &data.variables {
-        let var_obj = Object::new();
-        Reflect::set(&var_obj, &"name".into(), &var.name.clone().into())?;
-        let var_type = match var.var_type {
-            VarType::Time => "time",
-            VarType::Frequency => "frequency",
-            VarType::Voltage => "voltage",
-            VarType::Current => "current",
-            VarType::Unknown => "unknown",
-        };
-        Reflect::set(&var_obj, &"type".into(), &var_type.into())?;
-        variables.push(&var_obj);
+    for (i, var) in data.variables.iter().enumerate() {
+        let is_complex = data
+            .tables
+            .first()
+            .is_some_and(|t| t.vectors[i].is_complex());
+        variables.push(&variable_to_js(var, is_complex)?);
     }
     Reflect::set(&result, &"variables".into(), &variables)?;
 
@@ -203,27 +1095,226 @@ fn create_js_result(data: &WaveformResult) -> Result<JsValue, JsValue> {
     Ok(result.into())
 }
 
+fn analysis_name(analysis: AnalysisType) -> &'static str {
+    match analysis {
+        AnalysisType::Transient => "transient",
+        AnalysisType::AC => "ac",
+        AnalysisType::DC => "dc",
+        AnalysisType::Operating => "operating",
+        AnalysisType::Noise => "noise",
+        AnalysisType::Unknown => "unknown",
+    }
+}
+
+/// `{name, type, unit, isComplex}` of a variable
+fn variable_to_js(var: &Variable, is_complex: bool) -> Result<JsValue, JsValue> {
+    let var_obj = Object::new();
+    Reflect::set(&var_obj, &"name".into(), &var.name.clone().into())?;
+    let var_type = match var.var_type {
+        VarType::Time => "time",
+        VarType::Frequency => "frequency",
+        VarType::Voltage => "voltage",
+        VarType::Current => "current",
+        VarType::Power => "power",
+        VarType::Parameter => "parameter",
+        VarType::Internal => "internal",
+        VarType::Noise => "noise",
+        VarType::Unknown => "unknown",
+    };
+    Reflect::set(&var_obj, &"type".into(), &var_type.into())?;
+    let unit = var.unit.map_or(JsValue::NULL, |u| u.symbol().into());
+    Reflect::set(&var_obj, &"unit".into(), &unit)?;
+    Reflect::set(&var_obj, &"isComplex".into(), &is_complex.into())?;
+    Ok(var_obj.into())
+}
+
+fn create_js_chunk(chunk: &DataChunk) -> Result<JsValue, JsValue> {
+    let result = Object::new();
+    Reflect::set(
+        &result,
+        &"chunkIndex".into(),
+        &(chunk.chunk_index as u32).into(),
+    )?;
+    Reflect::set(
+        &result,
+        &"tableIndex".into(),
+        &(chunk.table_index as u32).into(),
+    )?;
+    let sweep_value = chunk.sweep_value.map_or(JsValue::NULL, JsValue::from);
+    Reflect::set(&result, &"sweepValue".into(), &sweep_value)?;
+
+    let time_range = Array::of2(&chunk.time_range.0.into(), &chunk.time_range.1.into());
+    Reflect::set(&result, &"timeRange".into(), &time_range)?;
+
+    let signals = Object::new();
+    for (name, vector) in &chunk.data {
+        Reflect::set(&signals, &name.clone().into(), &vector_to_js(vector)?)?;
+    }
+    Reflect::set(&result, &"signals".into(), &signals)?;
+
+    Ok(result.into())
+}
+
 fn vector_to_js(vector: &VectorData) -> Result<JsValue, JsValue> {
     match vector {
-        VectorData::Real(vec) => {
-            let array = Float64Array::new_with_length(vec.len() as u32);
-            for (i, &v) in vec.iter().enumerate() {
-                array.set_index(i as u32, v);
-            }
-            Ok(array.into())
-        }
+        VectorData::Real(values) => Ok(f64_array(values).into()),
         VectorData::Complex(vec) => {
-            // Return magnitude for complex data
-            let array = Float64Array::new_with_length(vec.len() as u32);
-            for (i, c) in vec.iter().enumerate() {
-                array.set_index(i as u32, (c.re * c.re + c.im * c.im).sqrt());
-            }
-            Ok(array.into())
+            let re: Vec<f64> = vec.iter().map(|c| c.re).collect();
+            let im: Vec<f64> = vec.iter().map(|c| c.im).collect();
+            let result = Object::new();
+            Reflect::set(&result, &"re".into(), &f64_array(&re))?;
+            Reflect::set(&result, &"im".into(), &f64_array(&im))?;
+            Ok(result.into())
         }
+        other => Ok(f64_array(&other.to_real().unwrap_or_default()).into()),
+    }
+}
+
+/// Copy `values` into a new Float64Array in one bulk copy
+fn f64_array(values: &[f64]) -> Float64Array {
+    if SHARED_OUTPUT.get() {
+        let buffer = SharedArrayBuffer::new((values.len() * 8) as u32);
+        let array = Float64Array::new(&buffer);
+        array.copy_from(values);
+        array
+    } else {
+        Float64Array::from(values)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    // Tests require wasm-pack test, not regular cargo test
+    // Functions returning JS values require wasm-pack test; the pure Rust
+    // helpers are tested here
+    use super::*;
+    use hspice_core::testing::SyntheticWaveform;
+
+    #[test]
+    fn test_min_max_buckets() {
+        let x: Vec<f64> = (0..1000).map(|i| i as f64).collect();
+        let mut y: Vec<f64> = x.iter().map(|v| (v / 10.0).sin()).collect();
+        y[555] = 42.0;
+
+        let (time, min, max) = min_max_buckets(&x, &y, 10);
+        assert_eq!(time.len(), 10);
+        assert_eq!(time[5], 499.5);
+        // The spike survives in its bucket
+        assert_eq!(max[5], 42.0);
+        assert!(min.iter().zip(&max).all(|(lo, hi)| lo <= hi));
+
+        // Fewer points than buckets: unchanged
+        let (time, min, _) = min_max_buckets(&x[..5], &y[..5], 10);
+        assert_eq!(time, &x[..5]);
+        assert_eq!(min, &y[..5]);
+    }
+
+    #[test]
+    fn test_preview_is_bounded() {
+        let synth = SyntheticWaveform::new().signals(5).points(25_000);
+        let expected = synth.expected();
+        let reader = HspiceStreamReader::from_bytes(synth.to_tr0_bytes(), 1000).unwrap();
+
+        let (preview, stride) = preview(reader, 1000, 2).unwrap();
+        assert_eq!(preview.variables.len(), 3);
+        let time = preview.tables[0].vectors[0].as_real().unwrap();
+        assert!(time.len() <= 1000 && time.len() > 500, "{}", time.len());
+
+        // Kept points are every stride-th point of the full data
+        let full = expected.tables[0].vectors[2].to_real().unwrap();
+        let kept = preview.tables[0].vectors[2].to_real().unwrap();
+        assert_eq!(kept[3], full[3 * stride]);
+    }
+
+    #[test]
+    fn test_select_window() {
+        let synth = SyntheticWaveform::new()
+            .signals(4)
+            .points(5000)
+            .block_size(512)
+            .sweep("TEMP", vec![25.0, 85.0]);
+        let expected = synth.expected();
+        let reader = HspiceStreamReader::from_bytes(synth.to_tr0_bytes(), 100).unwrap();
+        let names = reader.metadata().signal_names;
+        let time = expected.tables[1].vectors[0].to_real().unwrap();
+        let (start, stop) = (time[1000], time[2999]);
+
+        let result = select_window(
+            reader,
+            vec![names[2].clone(), names[0].clone()],
+            (start, stop),
+        )
+        .unwrap();
+        assert_eq!(result.variables.len(), 3);
+        assert_eq!(result.variables[1].name, names[2]);
+        assert_eq!(result.tables.len(), 2);
+        assert_eq!(result.tables[1].sweep_value, Some(85.0));
+
+        let table = &result.tables[1];
+        assert_eq!(table.vectors[0].to_real().unwrap(), &time[1000..3000]);
+        let full = expected.tables[1].vectors[3].to_real().unwrap();
+        assert_eq!(table.vectors[1].to_real().unwrap(), &full[1000..3000]);
+    }
+
+    #[test]
+    fn test_select_empty_window() {
+        let synth = SyntheticWaveform::new().signals(2).points(100);
+        let reader = HspiceStreamReader::from_bytes(synth.to_tr0_bytes(), 10).unwrap();
+        let names = reader.metadata().signal_names;
+
+        let result = select_window(reader, names, (1e9, 2e9)).unwrap();
+        assert_eq!(result.variables.len(), 3);
+        assert!(result.tables[0].vectors.iter().all(VectorData::is_empty));
+    }
+
+    #[test]
+    fn test_parse_detects_format() {
+        let synth = SyntheticWaveform::new().signals(3).points(200);
+        let expected = synth.expected();
+
+        // Errors build JsValues, which only work on wasm32, so unwrap only Ok
+        let hspice = parse_from_bytes(&synth.to_tr0_bytes()).ok().unwrap();
+        let raw = parse_from_bytes(&synth.to_raw_bytes()).ok().unwrap();
+        for result in [hspice, raw] {
+            assert_eq!(result.num_vars(), expected.num_vars());
+            assert_eq!(result.len(), expected.len());
+            assert_eq!(
+                result.tables[0].vectors[2].to_real().unwrap(),
+                expected.tables[0].vectors[2].to_real().unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_raw_selection_matches_hspice() {
+        let synth = SyntheticWaveform::new().signals(3).points(500);
+        let names = vec![synth.expected().variables[2].name.clone()];
+        let window = (1e-7, 3e-7);
+
+        let hspice = select_from_bytes(synth.to_tr0_bytes(), Some(names.clone()), window)
+            .ok()
+            .unwrap();
+        let raw = select_from_bytes(synth.to_raw_bytes(), Some(names), window)
+            .ok()
+            .unwrap();
+        assert_eq!(raw.variables.len(), 2);
+        assert_eq!(raw.variables[1].name, hspice.variables[1].name);
+        assert!(!raw.is_empty() && raw.len() < 500);
+        for i in 0..2 {
+            assert_eq!(
+                raw.tables[0].vectors[i].to_real().unwrap(),
+                hspice.tables[0].vectors[i].to_real().unwrap()
+            );
+        }
+
+        // No names: every signal
+        let all = select_from_bytes(
+            synth.to_raw_bytes(),
+            None,
+            (f64::NEG_INFINITY, f64::INFINITY),
+        )
+        .ok()
+        .unwrap();
+        assert_eq!(all.num_vars(), 4);
+        assert_eq!(all.len(), 500);
+    }
 }
@@ -0,0 +1,178 @@
+//! Browser tests of the JavaScript API
+//!
+//! Run with `wasm-pack test --headless --firefox crates/hspice-wasm` (or
+//! `--chrome`). They check the object shapes that the TypeScript
+//! definitions promise, on the example files.
+
+#![cfg(target_arch = "wasm32")]
+
+use hspice_wasm::*;
+use js_sys::{Array, Float64Array, Reflect, Uint8Array};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+const TR0_9601: &[u8] = include_bytes!("../../../example/test_9601.tr0");
+const TR0_2001: &[u8] = include_bytes!("../../../example/test_2001.tr0");
+const AC0_9601: &[u8] = include_bytes!("../../../example/test_9601.ac0");
+const SW0_9601: &[u8] = include_bytes!("../../../example/test_9601.sw0");
+
+/// Number of rows in the example transient files
+const TRAN_POINTS: u32 = 2605;
+
+fn get(object: &JsValue, key: &str) -> JsValue {
+    Reflect::get(object, &key.into()).unwrap()
+}
+
+fn get_f64(object: &JsValue, key: &str) -> f64 {
+    get(object, key).as_f64().unwrap()
+}
+
+fn get_string(object: &JsValue, key: &str) -> String {
+    get(object, key).as_string().unwrap()
+}
+
+/// `tables[table].signals[name]`
+fn signal(result: &JsValue, table: u32, name: &str) -> JsValue {
+    let tables: Array = get(result, "tables").unchecked_into();
+    get(&get(&tables.get(table), "signals"), name)
+}
+
+fn assert_transient(result: &JsValue) {
+    assert_eq!(get_string(result, "analysis"), "transient");
+    assert_eq!(get_string(result, "scaleName"), "TIME");
+    assert_eq!(get_f64(result, "numPoints") as u32, TRAN_POINTS);
+    assert_eq!(get_f64(result, "numVars"), 5.0);
+    assert_eq!(get_f64(result, "numSweeps"), 1.0);
+    assert!(get(result, "sweepParam").is_null());
+
+    let variables: Array = get(result, "variables").unchecked_into();
+    let scale = variables.get(0);
+    assert_eq!(get_string(&scale, "name"), "TIME");
+    assert_eq!(get_string(&scale, "type"), "time");
+    assert_eq!(get(&scale, "isComplex"), JsValue::FALSE);
+
+    let vo = signal(result, 0, "vo");
+    assert!(vo.is_instance_of::<Float64Array>());
+    assert_eq!(vo.unchecked_into::<Float64Array>().length(), TRAN_POINTS);
+}
+
+#[wasm_bindgen_test]
+fn parse_9601_transient() {
+    assert_transient(&parse_hspice(TR0_9601).unwrap());
+}
+
+#[wasm_bindgen_test]
+fn parse_2001_transient() {
+    let result = parse_hspice(TR0_2001).unwrap();
+    assert_transient(&result);
+
+    // Same circuit as the 9601 file, at lower precision
+    let time_2001: Float64Array = signal(&result, 0, "TIME").unchecked_into();
+    let result_9601 = parse_hspice(TR0_9601).unwrap();
+    let time_9601: Float64Array = signal(&result_9601, 0, "TIME").unchecked_into();
+    let last = TRAN_POINTS - 1;
+    assert!((time_2001.get_index(last) - time_9601.get_index(last)).abs() < 1e-9);
+}
+
+#[wasm_bindgen_test]
+fn parse_complex_ac() {
+    let result = parse_hspice(AC0_9601).unwrap();
+    assert_eq!(get_string(&result, "analysis"), "ac");
+    assert_eq!(get_string(&result, "scaleName"), "HERTZ");
+
+    let variables: Array = get(&result, "variables").unchecked_into();
+    assert_eq!(get(&variables.get(0), "isComplex"), JsValue::FALSE);
+    assert_eq!(get(&variables.get(2), "isComplex"), JsValue::TRUE);
+
+    let vo = signal(&result, 0, "vo");
+    let re: Float64Array = get(&vo, "re").unchecked_into();
+    let im: Float64Array = get(&vo, "im").unchecked_into();
+    assert_eq!(re.length(), 41);
+    assert_eq!(im.length(), 41);
+
+    let magnitude = complex_magnitude(&re.to_vec(), &im.to_vec());
+    assert_eq!(magnitude.length(), 41);
+    assert!((magnitude.get_index(0) - 1.0).abs() < 1e-3);
+}
+
+#[wasm_bindgen_test]
+fn parse_dc_sweep() {
+    let result = parse_hspice(SW0_9601).unwrap();
+    assert_eq!(get_string(&result, "scaleName"), "r1");
+    assert_eq!(get_f64(&result, "numPoints"), 10.0);
+}
+
+#[wasm_bindgen_test]
+fn metadata_matches_full_parse() {
+    let meta = parse_metadata(TR0_9601).unwrap();
+    assert_eq!(get_string(&meta, "scaleName"), "TIME");
+    assert_eq!(get(&meta, "isComplex"), JsValue::FALSE);
+    let signals: Array = get(&meta, "signals").unchecked_into();
+    assert_eq!(signals.length(), 5);
+    assert!(get_f64(&meta, "estimatedPoints") >= TRAN_POINTS as f64);
+
+    let ac = parse_metadata(AC0_9601).unwrap();
+    assert_eq!(get(&ac, "isComplex"), JsValue::TRUE);
+}
+
+#[wasm_bindgen_test]
+fn signal_names_and_data() {
+    let names = get_signal_names(TR0_9601).unwrap();
+    assert_eq!(names.get(0).as_string().unwrap(), "TIME");
+    assert_eq!(names.length(), 5);
+
+    let vo: Float64Array = get_signal_data(TR0_9601, "vo").unwrap().unchecked_into();
+    assert_eq!(vo.length(), TRAN_POINTS);
+}
+
+#[wasm_bindgen_test]
+fn selective_parse() {
+    let result =
+        parse_signals(TR0_9601.to_vec(), vec!["vo".into()], Some(1e-3), Some(2e-3)).unwrap();
+    let variables: Array = get(&result, "variables").unchecked_into();
+    assert_eq!(variables.length(), 2);
+
+    let time: Float64Array = signal(&result, 0, "TIME").unchecked_into();
+    assert!(time.length() > 0 && time.length() < TRAN_POINTS);
+    assert!(time.to_vec().iter().all(|t| (1e-3..=2e-3).contains(t)));
+}
+
+#[wasm_bindgen_test]
+fn file_handle() {
+    let file = HspiceFile::new(TR0_9601).unwrap();
+    assert_eq!(file.num_points(), TRAN_POINTS as usize);
+    assert_eq!(file.scale_name(), "TIME");
+    let view = file.signal_view("vo", None).unwrap();
+    assert_eq!(view.length(), TRAN_POINTS);
+    let decimated = file.decimated_signal("vo", 100, None).unwrap();
+    assert!(get(&decimated, "max").is_instance_of::<Float64Array>());
+}
+
+#[wasm_bindgen_test]
+fn stream_covers_every_row() {
+    let mut reader = open_stream(TR0_9601.to_vec(), 500).unwrap();
+    let mut rows = 0;
+    loop {
+        let chunk = reader.next_chunk().unwrap();
+        if chunk.is_null() {
+            break;
+        }
+        assert!(get(&chunk, "timeRange").is_instance_of::<Array>());
+        let time: Float64Array = get(&get(&chunk, "signals"), "TIME").unchecked_into();
+        rows += time.length();
+    }
+    assert_eq!(rows, TRAN_POINTS);
+}
+
+#[wasm_bindgen_test]
+fn conversions() {
+    let csv = to_csv(TR0_9601.to_vec(), None).unwrap();
+    assert!(csv.starts_with("TIME,"));
+    assert_eq!(csv.lines().count() as u32, TRAN_POINTS + 1);
+
+    let raw: Uint8Array = to_spice3_raw(TR0_9601, None).unwrap();
+    let reparsed = parse_hspice(&raw.to_vec()).unwrap();
+    assert_eq!(get_f64(&reparsed, "numPoints") as u32, TRAN_POINTS);
+}
@@ -0,0 +1,47 @@
+//! Write seed inputs for the fuzz targets into `corpus/`
+//!
+//! Run with `cargo run --example seed_corpus` from the `fuzz` directory.
+
+use hspice_core::testing::SyntheticWaveform;
+use hspice_core::{Endian, PostVersion};
+use std::fs;
+
+fn main() -> std::io::Result<()> {
+    fs::create_dir_all("corpus/read_tr0")?;
+    fs::create_dir_all("corpus/read_raw")?;
+    fs::create_dir_all("corpus/round_trip")?;
+
+    let seeds = [
+        ("tran", SyntheticWaveform::new().signals(3).points(16)),
+        (
+            "tran_2001_be",
+            SyntheticWaveform::new()
+                .signals(2)
+                .points(8)
+                .version(PostVersion::V2001)
+                .endian(Endian::Big),
+        ),
+        (
+            "ac",
+            SyntheticWaveform::new().signals(2).points(8).complex(true),
+        ),
+        (
+            "sweep",
+            SyntheticWaveform::new()
+                .signals(1)
+                .points(4)
+                .sweep("temp", vec![0.0, 50.0]),
+        ),
+        (
+            "straddle",
+            SyntheticWaveform::new().signals(2).points(10).block_size(5),
+        ),
+    ];
+
+    for (name, synth) in &seeds {
+        fs::write(format!("corpus/read_tr0/{name}"), synth.to_tr0_bytes())?;
+        fs::write(format!("corpus/read_raw/{name}"), synth.to_raw_bytes())?;
+        fs::write(format!("corpus/round_trip/{name}"), synth.to_tr0_bytes())?;
+    }
+    Ok(())
+}
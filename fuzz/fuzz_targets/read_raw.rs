@@ -0,0 +1,10 @@
+//! Fuzz the SPICE3 raw parser with untrusted-input limits
+
+#![no_main]
+
+use hspice_core::{read_raw_bytes, ParserLimits};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = read_raw_bytes(data, &ParserLimits::untrusted());
+});
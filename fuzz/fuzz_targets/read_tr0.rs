@@ -0,0 +1,10 @@
+//! Fuzz the HSPICE binary parser with untrusted-input limits
+
+#![no_main]
+
+use hspice_core::{read_bytes, ParserLimits};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = read_bytes(data, &ParserLimits::untrusted());
+});
@@ -0,0 +1,29 @@
+//! Fuzz writer/reader consistency: whatever the HSPICE parser accepts must
+//! come back unchanged from SPICE3 raw, binary and ASCII
+
+#![no_main]
+
+use hspice_core::conformance::raw_round_trip;
+use hspice_core::{read_bytes, ParserLimits, RawEncoding};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(result) = read_bytes(data, &ParserLimits::untrusted()) else {
+        return;
+    };
+    // Raw headers are line- and tab-delimited, so such text cannot survive
+    let unrepresentable = |text: &str| text.contains(['\n', '\r', '\t']);
+    if unrepresentable(&result.title)
+        || unrepresentable(&result.date)
+        || result
+            .variables
+            .iter()
+            .any(|v| v.name.is_empty() || v.name.contains(char::is_whitespace))
+    {
+        return;
+    }
+    for encoding in [RawEncoding::Binary, RawEncoding::ascii()] {
+        let diffs = raw_round_trip(&result, encoding, 0).expect("raw output reads back");
+        assert!(diffs.is_empty(), "{:?}: {:#?}", encoding, diffs);
+    }
+});
@@ -5,12 +5,15 @@ Rust implementation with PyO3 by Haiwen Zhang
 
 import hspicetr0parser as _lib
 
-__all__ = ['read', 'read_raw', 'convert_to_raw', 'stream', 'init_logging', 'WaveformResult', 'Variable', 'DataTable']
+__all__ = ['read', 'read_alters', 'read_parts', 'read_corners', 'read_raw', 'read_any', 'read_lis', 'read_noise', 'read_print', 'read_lazy', 'read_measure', 'verify', 'convert_to_raw', 'convert_stream', 'stream', 'init_logging', 'WaveformResult', 'Variable', 'DataTable', 'WaveformStream', 'LazyWaveform', 'MeasureResult']
 
 # Re-export classes
 WaveformResult = _lib.WaveformResult
 Variable = _lib.Variable
 DataTable = _lib.DataTable
+WaveformStream = _lib.WaveformStream
+LazyWaveform = _lib.LazyWaveform
+MeasureResult = _lib.MeasureResult
 
 
 def init_logging(level="info"):
@@ -27,13 +30,25 @@ def init_logging(level="info"):
     return _lib.init_logging(level)
 
 
-def read(filename, debug=0):
+def read(filename, debug=0, signals=None, recover=False, progress=None,
+         non_monotonic='keep'):
     """
     Read HSPICE/waveform binary file.
     
     Args:
         filename: Path to the waveform file (.tr0, .ac0, .sw0)
         debug: Debug level (deprecated, use init_logging() instead)
+        signals: Optional glob pattern selecting the signals to keep, e.g.
+            "v(x1.*)"; prefix with "re:" for a regular expression. The
+            scale is always kept.
+        recover: Salvage the complete rows of a file cut off by a crashed
+            simulation instead of failing; result.truncated tells if it was.
+        progress: A tqdm progress bar, advanced by the bytes read, or a
+            callable called as progress(bytes, total_bytes).
+        non_monotonic: What to do with backtracked time points left by
+            rejected timesteps: 'keep' them, 'drop' them, 'keep-last' to
+            let them replace the points they step back over, or 'error'
+            to fail the read.
     
     Returns:
         WaveformResult object with the following attributes:
@@ -44,6 +59,7 @@ def read(filename, debug=0):
         - variables: List of Variable objects with name and var_type
         - tables: List of DataTable objects
         - sweep_param: Sweep parameter name (if swept)
+        - truncated: True if the file ended early (with recover=True)
         
         Returns None if an error occurs.
     
@@ -60,12 +76,15 @@ def read(filename, debug=0):
         >>> # List all variables
         >>> for var in result.variables:
         ...     print(f"{var.name}: {var.var_type}")
+        >>> 
+        >>> # Only the nodes of instance x1
+        >>> x1 = read('simulation.tr0', signals='v(x1.*)')
     """
     # Enable logging if debug > 0 for backward compatibility
     if debug > 0:
         levels = {1: "info", 2: "debug"}
         _lib.init_logging(levels.get(debug, "info"))
-    return _lib.read(filename)
+    return _lib.read(filename, signals, recover, progress, non_monotonic)
 
 
 def convert_to_raw(input_path, output_path, debug=0):
@@ -92,18 +111,63 @@ def convert_to_raw(input_path, output_path, debug=0):
     return _lib.convert_to_raw(input_path, output_path)
 
 
-def stream(filename, chunk_size=10000, signals=None, debug=0):
+def convert_stream(input_path, output_path, format=None, chunk_size=10000,
+                   signals=None, time_range=None):
+    """
+    Convert HSPICE binary file to CSV or JSON Lines in constant memory.
+    
+    The file is read and written one chunk at a time, so files much larger
+    than RAM convert in a single pass.
+    
+    Args:
+        input_path: Path to the input HSPICE file
+        output_path: Path for the output file
+        format: "csv" or "jsonl" (default: from the output extension,
+            .csv, .jsonl or .ndjson)
+        chunk_size: Minimum points per chunk (default: 10000)
+        signals: Optional list of signal names to write; the scale is
+            always written
+        time_range: Optional (start, end) tuple; only points whose scale
+            lies in it are written
+    
+    Returns:
+        True if conversion succeeded, False otherwise.
+    
+    Example:
+        >>> from hspice_tr0_parser import convert_stream
+        >>> convert_stream('huge.tr0', 'huge.csv', signals=['v(out)'])
+        True
+    """
+    return _lib.convert_stream(input_path, output_path, format, chunk_size,
+                               signals, time_range)
+
+
+def stream(filename, chunk_size=10000, signals=None, debug=0, prefetch=0,
+           time_span=None):
     """
     Stream HSPICE binary file in chunks for memory-efficient processing.
     
+    Chunks are decoded lazily as the stream is iterated, so only the
+    current chunk is held in memory.
+    
     Args:
         filename: Path to the waveform file
         chunk_size: Minimum points per chunk (default: 10000)
-        signals: Optional list of signal names to filter
+        signals: Optional list of signal names, or a glob (or "re:" regex)
+            pattern such as "v(x1.*)"
         debug: Debug level (deprecated, use init_logging() instead)
+        prefetch: Decode up to this many chunks ahead on a background
+            thread, overlapping file reads with the loop body (0: off)
+        time_span: Make each chunk one window of this many scale units
+            (e.g. 1e-6 for 1 us tiles), aligned to multiples of it, instead
+            of chunk_size points
     
-    Yields:
-        dict: Chunk with 'chunk_index', 'time_range', 'data'
+    Returns:
+        WaveformStream iterator yielding dicts with 'chunk_index',
+        'table_index', 'sweep_value', 'time_range' and 'data'. Swept
+        files are streamed table by table. Empty if the file cannot be
+        opened. close() (or a with block) releases the file before the
+        end is reached.
     
     Example:
         >>> from hspice_tr0_parser import stream
@@ -115,18 +179,90 @@ def stream(filename, chunk_size=10000, signals=None, debug=0):
     if debug > 0:
         levels = {1: "info", 2: "debug"}
         _lib.init_logging(levels.get(debug, "info"))
-    chunks = _lib.stream(filename, chunk_size, signals)
-    for chunk in chunks:
-        yield chunk
+    return _lib.stream(filename, chunk_size, signals, prefetch, time_span)
+
+
+def read_alters(filename, recover=False):
+    """
+    Read every .ALTER run of a waveform file.
+    
+    Netlists with .ALTER statements can leave several complete result sets
+    back to back in one file; read() returns only the first.
+    
+    Args:
+        filename: Path to the waveform file (.tr0, .ac0, .sw0)
+        recover: Return a truncated last run instead of failing
+    
+    Returns:
+        List of WaveformResult objects, one per run in file order, or None
+        if an error occurs.
+    
+    Example:
+        >>> from hspice_tr0_parser import read_alters
+        >>> runs = read_alters('corners.tr0')
+        >>> [run.title for run in runs]
+    """
+    return _lib.read_alters(filename, recover)
+
+
+def read_parts(filename):
+    """
+    Read an output HSPICE split over numbered parts as one result.
+    
+    The parts after filename (sim.tr0, sim.tr1, sim.tr2, ...) are found
+    and stitched together. Points at the start of a part that repeat the
+    end of the previous one are dropped.
+    
+    Args:
+        filename: Path to the first part
+    
+    Returns:
+        WaveformResult object, or None if a part cannot be read or its
+        signals differ from the first.
+    
+    Example:
+        >>> from hspice_tr0_parser import read_parts
+        >>> result = read_parts('sim.tr0')
+    """
+    return _lib.read_parts(filename)
+
 
+def read_corners(files, signals=None, recover=False, threads=None):
+    """
+    Read many waveform files in parallel, one per corner.
+    
+    Files are parsed on several threads with the GIL released, so reading
+    a directory of corner runs takes about as long as the largest file.
+    
+    Args:
+        files: Glob pattern such as "runs/*.tr0" (sorted) or a list of paths
+        signals: Optional glob (or "re:" regex) pattern selecting the
+            signals to keep in every file
+        recover: Return what can be read of a truncated file
+        threads: Number of threads (default: one per CPU)
+    
+    Returns:
+        Dict of corner name -> WaveformResult in file order, with None for
+        files that cannot be read (the error is logged). Corners are named
+        by file stem, or by the path below the common directory if stems
+        repeat (runs/tt/inv.tr0 -> "tt/inv").
+    
+    Example:
+        >>> from hspice_tr0_parser import read_corners
+        >>> corners = read_corners('runs/inv_*.tr0', signals='v(out)')
+        >>> for name, result in corners.items():
+        ...     print(name, result.get('v(out)').max())
+    """
+    return _lib.read_corners(files, signals, recover, threads)
 
-def read_raw(filename, debug=0):
+def read_raw(filename, debug=0, signals=None):
     """
     Read SPICE3/ngspice raw file (auto-detects binary/ASCII format).
     
     Args:
         filename: Path to the raw file (.raw)
         debug: Debug level (deprecated, use init_logging() instead)
+        signals: Optional glob (or "re:" regex) pattern, as for read()
     
     Returns:
         WaveformResult object with the following attributes:
@@ -150,4 +286,182 @@ def read_raw(filename, debug=0):
     if debug > 0:
         levels = {1: "info", 2: "debug"}
         _lib.init_logging(levels.get(debug, "info"))
-    return _lib.read_raw(filename)
\ No newline at end of file
+    return _lib.read_raw(filename, signals)
+
+
+def read_any(filename, debug=0, signals=None):
+    """
+    Read an HSPICE (.tr0/.ac0/.sw0) or SPICE3/ngspice raw file.
+    
+    The format is detected from the file contents, falling back to the
+    extension, so one call handles every supported format.
+    
+    Args:
+        filename: Path to the waveform file
+        debug: Debug level (deprecated, use init_logging() instead)
+        signals: Optional glob (or "re:" regex) pattern, as for read()
+    
+    Returns:
+        WaveformResult object (same as read() and read_raw()), or None if
+        an error occurs.
+    
+    Example:
+        >>> from hspice_tr0_parser import read_any
+        >>> for path in ['sim.tr0', 'sim.raw']:
+        ...     result = read_any(path)
+        ...     print(result.scale_name, len(result))
+    """
+    if debug > 0:
+        levels = {1: "info", 2: "debug"}
+        _lib.init_logging(levels.get(debug, "info"))
+    return _lib.read_any(filename, signals)
+
+
+def read_lazy(filename):
+    """
+    Open an HSPICE file for on-demand signal access.
+    
+    Only the header and block layout are read up front. Indexing the
+    result decodes just that signal, so picking a few signals out of a
+    post-layout run with thousands of nodes is fast.
+    
+    Args:
+        filename: Path to the HSPICE file (.tr0, .ac0, .sw0)
+    
+    Returns:
+        LazyWaveform object, or None if the file cannot be read:
+        - signals: All signal names, scale first (no data is decoded)
+        - wave[name]: numpy array of the signal (first sweep table);
+          raises KeyError for unknown names
+        - wave.get(name, table=0): array from any sweep table, or None
+        - title, date, analysis, scale_name, sweep_param, sweep_values
+        - close(): unmap the file; also done at the end of a with block
+    
+    Example:
+        >>> from hspice_tr0_parser import read_lazy
+        >>> with read_lazy('postlayout.tr0') as wave:
+        ...     print(len(wave.signals))
+        ...     vout = wave['v(out)']
+        10001
+    """
+    return _lib.read_lazy(filename)
+
+
+
+def read_lis(filename):
+    """
+    Read the DC operating points of an HSPICE listing (.lis).
+    
+    Args:
+        filename: Path to the listing
+    
+    Returns:
+        WaveformResult with analysis 'operating', or None if the listing
+        cannot be read or has no operating point. Each .op time is one
+        table holding a single point; the scale is TEMP. Node voltages are
+        named after the node ('out'), element values '<row>(<element>)',
+        e.g. 'i(vdd)', 'id(mn1)' or 'gm(mn1)'.
+    
+    Example:
+        >>> from hspice_tr0_parser import read_lis
+        >>> op = read_lis('inv.lis')
+        >>> op.get('out')[0]
+        1.7999
+    """
+    return _lib.read_lis(filename)
+
+def read_noise(filename):
+    """
+    Read the noise analysis results of an HSPICE listing (.lis).
+    
+    Args:
+        filename: Path to the listing
+    
+    Returns:
+        WaveformResult with analysis 'noise' and the scale HERTZ, or None if
+        the listing cannot be read or has no noise results. 'onoise' is the
+        output noise in V/rtHz, 'inoise' the equivalent input noise, and
+        element contributions are '<row>(<element>)' in V^2/Hz, e.g.
+        'total(m1)'. Each restarted sweep is one more table.
+    
+    Example:
+        >>> from hspice_tr0_parser import read_noise
+        >>> noise = read_noise('lna.lis')
+        >>> noise.get('onoise')[0]
+        9.4868e-08
+    """
+    return _lib.read_noise(filename)
+
+def read_print(filename):
+    """
+    Read the .print tables of an HSPICE listing (.lis) or .printtr0 file.
+    
+    Args:
+        filename: Path to the listing or print file
+    
+    Returns:
+        WaveformResult, or None if the file cannot be read or has no table
+        with a column header. Listing voltages are named after the node
+        ('out') and currents 'i(<element>)'. Each sweep is one table.
+    
+    Example:
+        >>> from hspice_tr0_parser import read_print
+        >>> printed = read_print('inv.lis')
+        >>> printed.get('out')[0]
+        1.8
+    """
+    return _lib.read_print(filename)
+
+def verify(filename):
+    """
+    Check every data block of an HSPICE file without loading the data.
+    
+    Catches files cut off by a full disk or NFS, or left behind by a
+    crashed simulator, before a flow spends time reading them.
+    
+    Args:
+        filename: Path to the HSPICE file (.tr0, .ac0, .sw0)
+    
+    Returns:
+        dict, or None if the file or its header cannot be read:
+        - ok: True if no problem was found
+        - blocks, rows: Data blocks and complete rows read
+        - tables, expected_tables: Tables with an end marker, and how many
+          the header declares
+        - trailing_bytes: Bytes after the last table (e.g. .ALTER runs)
+        - issues: dicts with 'offset', 'table' and 'message'
+    
+    Example:
+        >>> from hspice_tr0_parser import verify
+        >>> report = verify('sim.tr0')
+        >>> report['ok']
+        True
+    """
+    return _lib.verify(filename)
+
+def read_measure(filename):
+    """
+    Read an HSPICE measure file (.mt0, .ma0, .ms0).
+    
+    Args:
+        filename: Path to the measure file; the analysis type is taken from
+            the extension (.mt# transient, .ma# AC, .ms# DC)
+    
+    Returns:
+        MeasureResult object, or None if the file cannot be read:
+        - names: All column names, including 'temper' and 'alter#'
+        - measure_names: The .measure results only
+        - rows: One list per sweep point; None where HSPICE wrote "failed"
+        - m[name]: numpy array of a column (NaN for failed measurements)
+        - to_dataframe(): pandas DataFrame with one column per name
+    
+    Row i belongs to sweep table i of the matching waveform file.
+    
+    Example:
+        >>> from hspice_tr0_parser import read_measure
+        >>> m = read_measure('inv.mt0')
+        >>> m.measure_names
+        ['tpd', 'trise', 'tfall']
+        >>> tpd = m['tpd']
+    """
+    return _lib.read_measure(filename)
\ No newline at end of file
@@ -0,0 +1,285 @@
+"""Type stubs for the hspicetr0parser extension module
+
+Kept in step with crates/hspice-python/src/lib.rs by tests/test_stubs.py;
+maturin ships this file and a py.typed marker with the wheel.
+"""
+
+from collections.abc import Callable, Iterator
+from types import TracebackType
+from typing import Any, Literal, Protocol, TypedDict, final
+
+import numpy as np
+import numpy.typing as npt
+from typing_extensions import Self
+
+Signal = npt.NDArray[np.float64] | npt.NDArray[np.complex128]
+"""A signal's values: float64, or complex128 for AC data"""
+
+SaveFormat = Literal["csv", "json", "npz", "mat", "raw"]
+MeasureKind = Literal["risetime", "rise_time", "falltime", "fall_time", "delay", "crossings"]
+EdgeKind = Literal["rising", "falling", "either"]
+NonMonotonic = Literal["keep", "drop", "keep-last", "error"]
+LogLevel = Literal["trace", "debug", "info", "warn", "error"]
+
+class _ProgressBar(Protocol):
+    """A tqdm-like progress bar"""
+
+    total: int | float | None
+
+    def update(self, n: int, /) -> Any: ...
+
+Progress = _ProgressBar | Callable[[int, int], Any]
+
+class SignalStats(TypedDict):
+    count: int
+    min: float
+    max: float
+    mean: float
+    rms: float
+    std: float
+    p2p: float
+    magnitude: bool
+
+class Chunk(TypedDict):
+    chunk_index: int
+    table_index: int
+    sweep_value: float | None
+    time_range: tuple[float, float]
+    data: dict[str, Signal]
+
+class VerifyIssue(TypedDict):
+    offset: int
+    table: int
+    message: str
+
+class VerifyReport(TypedDict):
+    ok: bool
+    blocks: int
+    tables: int
+    expected_tables: int
+    rows: int
+    trailing_bytes: int
+    issues: list[VerifyIssue]
+
+# ============================================================================
+# Classes
+# ============================================================================
+
+@final
+class Variable:
+    """Variable/signal metadata"""
+
+    @property
+    def name(self) -> str: ...
+    @property
+    def var_type(self) -> str: ...
+    @property
+    def unit(self) -> str | None: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+
+@final
+class DataTable:
+    """Signals of one sweep point"""
+
+    @property
+    def sweep_value(self) -> float | None: ...
+    def get(self, name: str) -> Signal | None: ...
+    def keys(self) -> list[str]: ...
+    def stats(self) -> dict[str, SignalStats]: ...
+    def to_dataframe(self, long: bool = False) -> Any: ...
+    def __len__(self) -> int: ...
+    def __eq__(self, other: object) -> bool: ...
+
+@final
+class WaveformResult:
+    """A whole waveform file, returned by read() and friends"""
+
+    @property
+    def title(self) -> str: ...
+    @property
+    def date(self) -> str: ...
+    @property
+    def analysis(self) -> str: ...
+    @property
+    def scale_name(self) -> str: ...
+    @property
+    def sweep_param(self) -> str | None: ...
+    @property
+    def truncated(self) -> bool: ...
+    @property
+    def variables(self) -> list[Variable]: ...
+    @property
+    def tables(self) -> list[DataTable]: ...
+    @property
+    def closed(self) -> bool: ...
+    def get(self, name: str) -> Signal | None: ...
+    def num_vars(self) -> int: ...
+    def num_sweeps(self) -> int: ...
+    def var_names(self) -> list[str]: ...
+    def has_sweep(self) -> bool: ...
+    def stats(self) -> dict[str, SignalStats]: ...
+    def to_dataframe(self, long: bool = False) -> Any: ...
+    def eval(self, expr: str, table: int = 0) -> Signal: ...
+    def measure(
+        self,
+        kind: MeasureKind,
+        signal: str,
+        target: str | None = None,
+        low: float = 0.1,
+        high: float = 0.9,
+        frac: float = 0.5,
+        level: float | None = None,
+        edge: EdgeKind = "rising",
+        table: int = 0,
+    ) -> float | npt.NDArray[np.float64] | None: ...
+    def save(
+        self,
+        path: str,
+        format: SaveFormat | None = None,
+        signals: str | None = None,
+        table: int | None = None,
+        ascii: bool = False,
+        precision: int | None = None,
+    ) -> bool: ...
+    def to_csv(self, path: str, signals: str | None = None, table: int | None = None) -> bool: ...
+    def to_raw(
+        self,
+        path: str,
+        signals: str | None = None,
+        table: int | None = None,
+        ascii: bool = False,
+        precision: int | None = None,
+    ) -> bool: ...
+    def to_json(self, path: str, signals: str | None = None, table: int | None = None) -> bool: ...
+    def to_npz(self, path: str, signals: str | None = None, table: int | None = None) -> bool: ...
+    def to_mat(self, path: str, signals: str | None = None, table: int | None = None) -> bool: ...
+    def close(self) -> None: ...
+    def __enter__(self) -> Self: ...
+    def __exit__(
+        self,
+        exc_type: type[BaseException] | None,
+        exc_value: BaseException | None,
+        traceback: TracebackType | None,
+    ) -> bool: ...
+    def __len__(self) -> int: ...
+
+@final
+class WaveformStream(Iterator[Chunk]):
+    """Chunks of a file, decoded as they are iterated"""
+
+    @property
+    def closed(self) -> bool: ...
+    def __iter__(self) -> Self: ...
+    def __next__(self) -> Chunk: ...
+    def close(self) -> None: ...
+    def __enter__(self) -> Self: ...
+    def __exit__(
+        self,
+        exc_type: type[BaseException] | None,
+        exc_value: BaseException | None,
+        traceback: TracebackType | None,
+    ) -> bool: ...
+
+@final
+class LazyWaveform:
+    """A memory-mapped file whose signals are decoded on access"""
+
+    @property
+    def title(self) -> str: ...
+    @property
+    def date(self) -> str: ...
+    @property
+    def analysis(self) -> str: ...
+    @property
+    def scale_name(self) -> str: ...
+    @property
+    def sweep_param(self) -> str | None: ...
+    @property
+    def signals(self) -> list[str]: ...
+    @property
+    def variables(self) -> list[Variable]: ...
+    @property
+    def sweep_values(self) -> list[float | None]: ...
+    @property
+    def num_tables(self) -> int: ...
+    @property
+    def closed(self) -> bool: ...
+    def get(self, name: str, table: int = 0) -> Signal | None: ...
+    def close(self) -> None: ...
+    def __enter__(self) -> Self: ...
+    def __exit__(
+        self,
+        exc_type: type[BaseException] | None,
+        exc_value: BaseException | None,
+        traceback: TracebackType | None,
+    ) -> bool: ...
+    def __getitem__(self, name: str) -> Signal: ...
+    def __contains__(self, name: str) -> bool: ...
+    def __len__(self) -> int: ...
+
+@final
+class MeasureResult:
+    """Contents of an HSPICE .measure file (.mt0, .ma0, .ms0)"""
+
+    @property
+    def title(self) -> str: ...
+    @property
+    def analysis(self) -> str: ...
+    @property
+    def param_count(self) -> int: ...
+    @property
+    def names(self) -> list[str]: ...
+    @property
+    def measure_names(self) -> list[str]: ...
+    @property
+    def rows(self) -> list[list[float | None]]: ...
+    def get(self, name: str) -> npt.NDArray[np.float64] | None: ...
+    def to_dataframe(self) -> Any: ...
+    def __getitem__(self, name: str) -> npt.NDArray[np.float64]: ...
+    def __len__(self) -> int: ...
+
+# ============================================================================
+# Functions
+# ============================================================================
+
+def init_logging(level: LogLevel | str = "info") -> None: ...
+def read(
+    filename: str,
+    signals: str | None = None,
+    recover: bool = False,
+    progress: Progress | None = None,
+    non_monotonic: NonMonotonic = "keep",
+) -> WaveformResult | None: ...
+def read_alters(filename: str, recover: bool = False) -> list[WaveformResult] | None: ...
+def read_parts(filename: str) -> WaveformResult | None: ...
+def read_corners(
+    files: str | list[str],
+    signals: str | None = None,
+    recover: bool = False,
+    threads: int | None = None,
+) -> dict[str, WaveformResult | None]: ...
+def read_raw(filename: str, signals: str | None = None) -> WaveformResult | None: ...
+def read_any(filename: str, signals: str | None = None) -> WaveformResult | None: ...
+def read_lis(filename: str) -> WaveformResult | None: ...
+def read_noise(filename: str) -> WaveformResult | None: ...
+def read_print(filename: str) -> WaveformResult | None: ...
+def read_lazy(filename: str) -> LazyWaveform | None: ...
+def verify(filename: str) -> VerifyReport | None: ...
+def read_measure(filename: str) -> MeasureResult | None: ...
+def convert_to_raw(input_path: str, output_path: str) -> bool: ...
+def convert_stream(
+    input_path: str,
+    output_path: str,
+    format: Literal["csv", "jsonl"] | None = None,
+    chunk_size: int = 10000,
+    signals: list[str] | None = None,
+    time_range: tuple[float, float] | None = None,
+) -> bool: ...
+def stream(
+    filename: str,
+    chunk_size: int = 10000,
+    signals: str | list[str] | None = None,
+    prefetch: int = 0,
+    time_span: float | None = None,
+) -> WaveformStream: ...
@@ -0,0 +1,851 @@
+/**
+ * Waveform Parser - C API
+ *
+ * High-performance library for reading HSPICE binary waveform files.
+ *
+ * Usage:
+ *   1. Link with libhspicetr0parser.a (static) or libhspicetr0parser.so
+ *      (dynamic)
+ *   2. Include this header
+ *   3. (Optional) Call waveform_init_logging() to enable logging
+ *   4. Call waveform_read() to parse a file
+ *   5. Use accessor functions to retrieve data
+ *   6. Call waveform_free() when done
+ *
+ * Example:
+ *   CWaveformResult* result = waveform_read("simulation.tr0", 0);
+ *   if (!result) {
+ *       fprintf(stderr, "read failed (%d): %s\n", waveform_last_error_code(),
+ *               waveform_last_error_message());
+ *   } else {
+ *       printf("Title: %s\n", waveform_get_title(result));
+ *       printf("Variables: %d\n", waveform_get_var_count(result));
+ *
+ *       int len = waveform_get_data_length(result, 0, 0);
+ *       double* data = malloc(len * sizeof(double));
+ *       waveform_get_real_data(result, 0, 0, data, len);
+ *
+ *       waveform_free(result);
+ *   }
+ *
+ * Pointer arguments must be NULL or valid for the duration of the call;
+ * handles must come from this library and must not be used after being
+ * freed. Returned strings are owned by the handle and stay valid until it
+ * is freed.
+ *
+ * Threads: handles may be used from any thread. Results are read-only and
+ * can be read by several threads at once; stream calls are serialized by
+ * a lock. Free a handle only once no other thread is using it; use
+ * waveform_result_clone() to give another thread a handle of its own.
+ *
+ * ABI versioning: see waveform_abi_version().
+ */
+
+#ifndef HSPICE_H
+#define HSPICE_H
+
+/* Generated by cbindgen from crates/hspice-ffi. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * ABI major version: bumped when an existing function, constant or enum
+ * value changes meaning or is removed
+ */
+#define WAVEFORM_ABI_VERSION_MAJOR 1
+
+/**
+ * ABI minor version: bumped when functions or constants are added
+ */
+#define WAVEFORM_ABI_VERSION_MINOR 11
+
+/**
+ * ABI patch version: bumped for fixes that do not change the interface
+ */
+#define WAVEFORM_ABI_VERSION_PATCH 0
+
+/**
+ * Analysis type constants (waveform_get_analysis_type)
+ */
+#define WAVEFORM_ANALYSIS_TRANSIENT 0
+
+#define WAVEFORM_ANALYSIS_AC 1
+
+#define WAVEFORM_ANALYSIS_DC 2
+
+#define WAVEFORM_ANALYSIS_OPERATING 3
+
+#define WAVEFORM_ANALYSIS_NOISE 4
+
+#define WAVEFORM_ANALYSIS_UNKNOWN -1
+
+/**
+ * Variable type constants (waveform_get_var_type)
+ */
+#define WAVEFORM_VAR_TIME 0
+
+#define WAVEFORM_VAR_FREQUENCY 1
+
+#define WAVEFORM_VAR_VOLTAGE 2
+
+#define WAVEFORM_VAR_CURRENT 3
+
+#define WAVEFORM_VAR_POWER 4
+
+#define WAVEFORM_VAR_PARAMETER 5
+
+#define WAVEFORM_VAR_INTERNAL 6
+
+#define WAVEFORM_VAR_NOISE 7
+
+#define WAVEFORM_VAR_UNKNOWN -1
+
+/**
+ * Crossing edge constants (waveform_crossings)
+ */
+#define WAVEFORM_EDGE_RISING 0
+
+#define WAVEFORM_EDGE_FALLING 1
+
+#define WAVEFORM_EDGE_EITHER 2
+
+/**
+ * FFT window constants (waveform_fft_magnitude, waveform_thd)
+ */
+#define WAVEFORM_WINDOW_RECTANGULAR 0
+
+#define WAVEFORM_WINDOW_HANN 1
+
+/**
+ * Every sweep table (WaveformCsvOptions.table_index)
+ */
+#define WAVEFORM_ALL_TABLES -1
+
+/**
+ * Error codes reported by waveform_last_error_code()
+ */
+typedef enum WaveformErrorCode {
+  WAVEFORM_ERROR_CODE_OK = 0,
+  WAVEFORM_ERROR_CODE_NULL_POINTER = 1,
+  WAVEFORM_ERROR_CODE_INVALID_ARGUMENT = 2,
+  WAVEFORM_ERROR_CODE_INVALID_UTF8 = 3,
+  WAVEFORM_ERROR_CODE_INDEX_OUT_OF_RANGE = 4,
+  WAVEFORM_ERROR_CODE_TYPE_MISMATCH = 5,
+  WAVEFORM_ERROR_CODE_NOT_FOUND = 6,
+  WAVEFORM_ERROR_CODE_NO_RESULT = 7,
+  WAVEFORM_ERROR_CODE_IO = 8,
+  WAVEFORM_ERROR_CODE_FORMAT = 9,
+  WAVEFORM_ERROR_CODE_LIMIT_EXCEEDED = 10,
+  WAVEFORM_ERROR_CODE_PARSE = 11,
+} WaveformErrorCode;
+
+/**
+ * Opaque handle to a parsed waveform file
+ */
+typedef struct CWaveformResult CWaveformResult;
+
+/**
+ * Opaque handle to a streaming reader
+ */
+typedef struct CWaveformStream CWaveformStream;
+
+/**
+ * Progress of a read, passed to a WaveformProgressFn
+ */
+typedef struct WaveformProgress {
+  /**
+   * Bytes of the file read so far
+   */
+  uint64_t bytes;
+  /**
+   * File size in bytes
+   */
+  uint64_t total_bytes;
+  /**
+   * Data blocks read so far
+   */
+  uint64_t blocks;
+  /**
+   * Rows (points) read so far, over all sweep tables
+   */
+  uint64_t rows;
+} WaveformProgress;
+
+/**
+ * Progress callback: called with the progress and the user_data pointer
+ * given to waveform_read_with_progress()
+ */
+typedef void (*WaveformProgressFn)(const struct WaveformProgress *progress, void *user_data);
+
+/**
+ * Summary statistics of one signal, filled by waveform_get_stats()
+ *
+ * NaN values are skipped; std is the population standard deviation.
+ */
+typedef struct WaveformStats {
+  /**
+   * Number of values summarized
+   */
+  uint64_t count;
+  double min;
+  double max;
+  double mean;
+  /**
+   * Root mean square
+   */
+  double rms;
+  /**
+   * Population standard deviation
+   */
+  double std;
+  /**
+   * max - min
+   */
+  double peak_to_peak;
+  /**
+   * 1 if computed on the magnitudes of complex data, 0 otherwise
+   */
+  int magnitude;
+} WaveformStats;
+
+/**
+ * Options of waveform_export_csv()
+ */
+typedef struct WaveformCsvOptions {
+  /**
+   * Only the scale and the signals matching this pattern (see
+   * waveform_select()), or NULL for every signal
+   */
+  const char *pattern;
+  /**
+   * Only this sweep table (0-based), or WAVEFORM_ALL_TABLES
+   */
+  int table_index;
+} WaveformCsvOptions;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Get the ABI version of the loaded library.
+ *
+ * Encoded as (major << 16) | (minor << 8) | patch. A program built against
+ * this header can run with the loaded library if the majors match and the
+ * loaded minor is at least WAVEFORM_ABI_VERSION_MINOR:
+ *
+ *   unsigned v = waveform_abi_version();
+ *   if ((v >> 16) != WAVEFORM_ABI_VERSION_MAJOR ||
+ *       ((v >> 8) & 0xff) < WAVEFORM_ABI_VERSION_MINOR) { ... }
+ */
+uint32_t waveform_abi_version(void);
+
+/**
+ * Get the code of the last error on this thread.
+ *
+ * @return WaveformErrorCode value, WAVEFORM_ERROR_CODE_OK if the last call
+ *         succeeded
+ */
+int waveform_last_error_code(void);
+
+/**
+ * Get the message of the last error on this thread.
+ *
+ * @return Null-terminated string, or NULL if the last call succeeded.
+ *         Valid until the next waveform_* call on the same thread.
+ */
+const char *waveform_last_error_message(void);
+
+/**
+ * Clear the last error on this thread.
+ */
+void waveform_clear_error(void);
+
+/**
+ * Initialize the logging subsystem.
+ *
+ * Call this once at application startup before using other functions.
+ * If not called, logging is disabled (only errors to stderr).
+ *
+ * @param level Log level string: "trace", "debug", "info", "warn", "error"
+ * @return      0 on success, -1 if level string is null or invalid
+ *
+ * @note Can only be called once; subsequent calls have no effect.
+ */
+int waveform_init_logging(const char *level);
+
+/**
+ * Read a waveform file.
+ *
+ * @param filename Path to the waveform file (.tr0, .ac0, .sw0)
+ * @param debug    Debug level (DEPRECATED: ignored, use waveform_init_logging)
+ * @return         Pointer to result on success, NULL on error
+ *
+ * @note The caller must free the result using waveform_free().
+ */
+struct CWaveformResult *waveform_read(const char *filename, int _debug);
+
+/**
+ * Parse an HSPICE file already in memory.
+ *
+ * The buffer is only read during the call, so it may be freed as soon as
+ * this returns. There is no file name to take the analysis type from, so
+ * it comes from the header alone.
+ *
+ * @param data Contents of an HSPICE file (.tr0, .ac0, .sw0)
+ * @param len  Size of data in bytes
+ * @return     Pointer to result on success, NULL on error
+ *
+ * @note The caller must free the result using waveform_free().
+ */
+struct CWaveformResult *waveform_read_from_memory(const uint8_t *data, size_t len);
+
+/**
+ * Read a waveform file, reporting progress.
+ *
+ * The callback is called about once per 1/1000 of the file and once more
+ * when the data is read, on the calling thread.
+ *
+ * @param filename  Path to the waveform file (.tr0, .ac0, .sw0)
+ * @param callback  Progress callback, or NULL for none
+ * @param user_data Passed unchanged to the callback
+ * @return          Pointer to result on success, NULL on error
+ *
+ * @note The caller must free the result using waveform_free().
+ */
+struct CWaveformResult *waveform_read_with_progress(const char *filename,
+                                                    WaveformProgressFn callback,
+                                                    void *user_data);
+
+/**
+ * Free a waveform result handle.
+ *
+ * @param result Pointer returned by waveform_read() or waveform_read_raw()
+ */
+void waveform_free(struct CWaveformResult *result);
+
+/**
+ * Read a SPICE3/ngspice raw file (auto-detects binary/ASCII format).
+ *
+ * @param filename Path to the raw file (.raw)
+ * @param debug    Debug level (DEPRECATED: ignored, use waveform_init_logging)
+ * @return         Pointer to result on success, NULL on error
+ *
+ * @note The caller must free the result using waveform_free().
+ */
+struct CWaveformResult *waveform_read_raw(const char *filename, int _debug);
+
+/**
+ * Select signals by pattern into a new result.
+ *
+ * The new result holds the scale and every signal whose name matches
+ * pattern, in all tables. Patterns are globs ("v(x1.*)", "i(vdd?)") that
+ * ignore case, or regular expressions when prefixed with "re:". Bare node
+ * names also match their "v(...)" form.
+ *
+ * @param result  Result handle
+ * @param pattern Glob or "re:" regex
+ * @return        New result handle, or NULL on error (invalid pattern, or
+ *                no signal matches)
+ *
+ * @note The caller must free the new result using waveform_free().
+ */
+struct CWaveformResult *waveform_select(const struct CWaveformResult *result, const char *pattern);
+
+/**
+ * Copy a result into an independent handle.
+ *
+ * The copy shares its signal values with result, so it is cheap, but the
+ * two handles are freed separately and in any order. A worker thread can
+ * hand a copy to another thread and keep or free its own.
+ *
+ * @param result Result handle
+ * @return       New result handle, or NULL if result is NULL
+ *
+ * @note The caller must free the new result using waveform_free().
+ */
+struct CWaveformResult *waveform_result_clone(const struct CWaveformResult *result);
+
+/**
+ * Get the simulation title.
+ */
+const char *waveform_get_title(const struct CWaveformResult *result);
+
+/**
+ * Get the simulation date.
+ */
+const char *waveform_get_date(const struct CWaveformResult *result);
+
+/**
+ * Get the scale name (e.g., "TIME", "HERTZ").
+ */
+const char *waveform_get_scale_name(const struct CWaveformResult *result);
+
+/**
+ * Get the analysis type (WAVEFORM_ANALYSIS_*).
+ */
+int waveform_get_analysis_type(const struct CWaveformResult *result);
+
+/**
+ * Get the analysis type as a lowercase name.
+ *
+ * @return "transient", "ac", "dc", "operating", "noise" or "unknown";
+ *         a static string, or NULL if result is NULL
+ */
+const char *waveform_get_analysis_name(const struct CWaveformResult *result);
+
+/**
+ * Get the number of data tables (one per sweep point).
+ */
+int waveform_get_table_count(const struct CWaveformResult *result);
+
+/**
+ * Get the number of variables/signals.
+ */
+int waveform_get_var_count(const struct CWaveformResult *result);
+
+/**
+ * Get the number of data points in the first table.
+ */
+int waveform_get_point_count(const struct CWaveformResult *result);
+
+/**
+ * Get the number of points in one table.
+ *
+ * @param result      Result handle
+ * @param table_index Table index (0-based)
+ * @return            Number of points, or -1 on error
+ */
+int waveform_get_table_point_count(const struct CWaveformResult *result, int table_index);
+
+/**
+ * Get variable name by index.
+ *
+ * @param result Result handle
+ * @param index  Variable index (0-based)
+ * @return       Null-terminated string, or NULL on error
+ */
+const char *waveform_get_var_name(const struct CWaveformResult *result, int index);
+
+/**
+ * Get variable type by index.
+ *
+ * @param result Result handle
+ * @param index  Variable index (0-based)
+ * @return       WAVEFORM_VAR_* constant, or -1 on error
+ */
+int waveform_get_var_type(const struct CWaveformResult *result, int index);
+
+/**
+ * Get the unit symbol of a variable, from its probe name or type.
+ *
+ * @param result Result handle
+ * @param index  Variable index (0-based)
+ * @return       "s", "Hz", "V", "A", "W", "C", "V/rtHz", "A/rtHz", "V^2/Hz",
+ *               or "" if unknown; a static string, or NULL on error
+ */
+const char *waveform_get_var_unit(const struct CWaveformResult *result, int index);
+
+/**
+ * Check if the result has sweep data.
+ *
+ * @return 1 if has sweep, 0 otherwise
+ */
+int waveform_has_sweep(const struct CWaveformResult *result);
+
+/**
+ * Get the sweep parameter name.
+ *
+ * @return Null-terminated string, or NULL if no sweep
+ */
+const char *waveform_get_sweep_param(const struct CWaveformResult *result);
+
+/**
+ * Get the sweep value for a specific table.
+ *
+ * @param result      Result handle
+ * @param table_index Table index (0-based)
+ * @return            Sweep value, or 0.0 on error
+ */
+double waveform_get_sweep_value(const struct CWaveformResult *result, int table_index);
+
+/**
+ * Get the length of data for a variable.
+ *
+ * @param result      Result handle
+ * @param table_index Table index (0-based)
+ * @param var_index   Variable index (0-based)
+ * @return            Number of data points, or 0 on error
+ */
+int waveform_get_data_length(const struct CWaveformResult *result, int table_index, int var_index);
+
+/**
+ * Check if data is complex.
+ *
+ * @return 1 if complex, 0 if real, -1 on error
+ */
+int waveform_is_complex(const struct CWaveformResult *result, int table_index, int var_index);
+
+/**
+ * Get real data for a variable.
+ *
+ * @param result      Result handle
+ * @param table_index Table index (0-based)
+ * @param var_index   Variable index (0-based)
+ * @param out_buffer  Output buffer for values
+ * @param max_count   Maximum number of values to copy
+ * @return            Number of values copied, or -1 on error
+ */
+int waveform_get_real_data(const struct CWaveformResult *result,
+                           int table_index,
+                           int var_index,
+                           double *out_buffer,
+                           int max_count);
+
+/**
+ * Get the real data of a variable in every sweep table at once.
+ *
+ * Table t is copied to out_buffer[t * stride], so the buffer holds one
+ * row of stride values per table. Tables longer than stride are cut off
+ * and shorter ones are padded with NaN. Only whole rows are written: as
+ * many tables as max_count / stride, in table order.
+ *
+ * @param result     Result handle
+ * @param var_index  Variable index (0-based)
+ * @param out_buffer Output buffer for the rows
+ * @param stride     Values per row, e.g. the largest
+ *                   waveform_get_table_point_count()
+ * @param max_count  Capacity of out_buffer in values
+ * @return           Number of tables copied, or -1 on error
+ */
+int waveform_get_signal_all_sweeps(const struct CWaveformResult *result,
+                                   int var_index,
+                                   double *out_buffer,
+                                   int stride,
+                                   int max_count);
+
+/**
+ * Get complex data for a variable (separate real and imaginary arrays).
+ *
+ * @param result      Result handle
+ * @param table_index Table index (0-based)
+ * @param var_index   Variable index (0-based)
+ * @param out_real    Output buffer for real parts
+ * @param out_imag    Output buffer for imaginary parts
+ * @param max_count   Maximum number of complex values to copy
+ * @return            Number of values copied, or -1 on error
+ */
+int waveform_get_complex_data(const struct CWaveformResult *result,
+                              int table_index,
+                              int var_index,
+                              double *out_real,
+                              double *out_imag,
+                              int max_count);
+
+/**
+ * Get a pointer to the real data of a variable without copying.
+ *
+ * @param result      Result handle
+ * @param table_index Table index (0-based)
+ * @param var_index   Variable index (0-based)
+ * @param out_len     Receives the number of values
+ * @return            Pointer to out_len doubles, or NULL on error
+ *
+ * @note The data is owned by the result and stays valid until
+ *       waveform_free(). Do not write through the pointer.
+ */
+const double *waveform_get_real_data_ptr(const struct CWaveformResult *result,
+                                         int table_index,
+                                         int var_index,
+                                         int *out_len);
+
+/**
+ * Get a pointer to the complex data of a variable without copying.
+ *
+ * Values are interleaved as re0, im0, re1, im1, ..., matching the layout
+ * of C99 `double complex` and C++ `std::complex<double>`.
+ *
+ * @param result      Result handle
+ * @param table_index Table index (0-based)
+ * @param var_index   Variable index (0-based)
+ * @param out_len     Receives the number of complex values (2 * out_len
+ *                    doubles)
+ * @return            Pointer to the interleaved data, or NULL on error
+ *
+ * @note The data is owned by the result and stays valid until
+ *       waveform_free(). Do not write through the pointer.
+ */
+const double *waveform_get_complex_data_ptr(const struct CWaveformResult *result,
+                                            int table_index,
+                                            int var_index,
+                                            int *out_len);
+
+/**
+ * Compute min, max, mean, RMS, std and peak-to-peak of a variable.
+ *
+ * Complex variables are summarized by their magnitude.
+ *
+ * @param result      Result handle
+ * @param table_index Table index (0-based), or -1 for all tables together
+ * @param var_index   Variable index (0-based)
+ * @param out_stats   Output statistics
+ * @return            0 on success, -1 on error (WAVEFORM_ERROR_CODE_NO_RESULT
+ *                    if the variable has no values)
+ */
+int waveform_get_stats(const struct CWaveformResult *result,
+                       int table_index,
+                       int var_index,
+                       struct WaveformStats *out_stats);
+
+/**
+ * Convert an HSPICE file to a binary SPICE3 raw file.
+ *
+ * Data the raw format cannot hold is logged as a warning.
+ *
+ * @param input  Path to the HSPICE file (.tr0, .ac0, .sw0)
+ * @param output Path of the .raw file to write
+ * @return       0 on success, -1 on error
+ */
+int waveform_convert_to_raw(const char *input, const char *output);
+
+/**
+ * Write a result as CSV.
+ *
+ * The layout is the one of the command line tool: a header row, a leading
+ * sweep column when more than one sweep table is written, and
+ * <name>.re/<name>.im columns for complex signals.
+ *
+ * @param result  Result handle
+ * @param path    Path of the .csv file to write
+ * @param options Signals and table to write, or NULL for everything
+ * @return        0 on success, -1 on error
+ */
+int waveform_export_csv(const struct CWaveformResult *result,
+                        const char *path,
+                        const struct WaveformCsvOptions *options);
+
+/**
+ * Open a file for streaming read.
+ *
+ * @param filename   Path to the waveform file
+ * @param chunk_size Minimum points per chunk
+ * @param debug      Debug level (DEPRECATED: ignored, use waveform_init_logging)
+ * @return           Stream handle, or NULL on error
+ */
+struct CWaveformStream *waveform_stream_open(const char *filename, int chunk_size, int _debug);
+
+/**
+ * Open a stream over an HSPICE file already in memory.
+ *
+ * The buffer is copied, so it may be freed as soon as this returns; the
+ * copy is released by waveform_stream_close().
+ *
+ * @param data       Contents of an HSPICE file
+ * @param len        Size of data in bytes
+ * @param chunk_size Minimum points per chunk
+ * @return           Stream handle, or NULL on error
+ */
+struct CWaveformStream *waveform_stream_open_from_memory(const uint8_t *data,
+                                                         size_t len,
+                                                         int chunk_size);
+
+/**
+ * Close a streaming reader.
+ */
+void waveform_stream_close(struct CWaveformStream *stream);
+
+/**
+ * Read the next chunk.
+ *
+ * @return 1 if success, 0 if EOF, -1 on error
+ */
+int waveform_stream_next(struct CWaveformStream *stream);
+
+/**
+ * Restrict the stream to a set of signals.
+ *
+ * Later chunks contain only these signals plus the scale. Pass count 0 to
+ * read all signals again.
+ *
+ * @param stream Stream handle
+ * @param names  Array of count signal names (may be NULL if count is 0)
+ * @param count  Number of names
+ * @return       0 on success, -1 on error (unknown names are an error and
+ *               leave the filter unchanged)
+ */
+int waveform_stream_set_signals(struct CWaveformStream *stream,
+                                const char *const *names,
+                                int count);
+
+/**
+ * Restrict the stream to the signals matching a pattern.
+ *
+ * Takes the same patterns as waveform_select(). Later chunks contain only
+ * the matching signals plus the scale.
+ *
+ * @param stream  Stream handle
+ * @param pattern Glob or "re:" regex
+ * @return        Number of matching signals, or -1 on error (an invalid
+ *                pattern or no match leaves the filter unchanged)
+ */
+int waveform_stream_set_signal_pattern(struct CWaveformStream *stream, const char *pattern);
+
+/**
+ * Rewind the stream to the first chunk.
+ *
+ * @return 0 on success, -1 if stream is NULL
+ */
+int waveform_stream_reset(struct CWaveformStream *stream);
+
+/**
+ * Move the stream so the next chunk starts at the first point at or
+ * after time t.
+ *
+ * Seeking back to a region already read restarts close to it rather than
+ * at the beginning of the file. If t is past the end, the next
+ * waveform_stream_next() returns 0.
+ *
+ * @param stream Stream handle
+ * @param t      Scale value (time) to seek to
+ * @return       0 on success, -1 on error
+ */
+int waveform_stream_seek_time(struct CWaveformStream *stream, double t);
+
+/**
+ * Get the current chunk's point count.
+ */
+int waveform_stream_get_chunk_size(const struct CWaveformStream *stream);
+
+/**
+ * Get the current chunk's time range.
+ *
+ * @param stream    Stream handle
+ * @param out_start Output for start time
+ * @param out_end   Output for end time
+ * @return          0 on success, -1 on error
+ */
+int waveform_stream_get_time_range(const struct CWaveformStream *stream,
+                                   double *out_start,
+                                   double *out_end);
+
+/**
+ * Get signal data from the current chunk.
+ *
+ * @param stream      Stream handle
+ * @param signal_name Name of the signal
+ * @param out_buffer  Output buffer for values
+ * @param max_count   Maximum number of values to copy
+ * @return            Number of values copied, or -1 on error
+ */
+int waveform_stream_get_signal_data(const struct CWaveformStream *stream,
+                                    const char *signal_name,
+                                    double *out_buffer,
+                                    int max_count);
+
+/**
+ * Find the points where y crosses a level.
+ *
+ * @param level      Threshold value
+ * @param edge       WAVEFORM_EDGE_* constant
+ * @param out_buffer Output for interpolated x positions (may be NULL if
+ *                   max_count is 0)
+ * @param max_count  Capacity of out_buffer
+ * @return           Number of crossings found, or -1 on error
+ */
+int waveform_crossings(const double *x,
+                       const double *y,
+                       int len,
+                       double level,
+                       int edge,
+                       double *out_buffer,
+                       int max_count);
+
+/**
+ * Measure the first rising transition between two fractions of the swing.
+ *
+ * @param low_frac  Start level as a fraction of (max - min), e.g. 0.1
+ * @param high_frac End level as a fraction of (max - min), e.g. 0.9
+ * @param out_value Output for the rise time
+ * @return          0 on success, -1 if no transition or on error
+ */
+int waveform_rise_time(const double *x,
+                       const double *y,
+                       int len,
+                       double low_frac,
+                       double high_frac,
+                       double *out_value);
+
+/**
+ * Measure the first falling transition between two fractions of the swing.
+ *
+ * @param high_frac Start level as a fraction of (max - min), e.g. 0.9
+ * @param low_frac  End level as a fraction of (max - min), e.g. 0.1
+ * @param out_value Output for the fall time
+ * @return          0 on success, -1 if no transition or on error
+ */
+int waveform_fall_time(const double *x,
+                       const double *y,
+                       int len,
+                       double high_frac,
+                       double low_frac,
+                       double *out_value);
+
+/**
+ * Linearly resample y(x) onto out_count evenly spaced points.
+ *
+ * @return out_count on success, -1 on error
+ */
+int waveform_resample_uniform(const double *x,
+                              const double *y,
+                              int len,
+                              double *out_buffer,
+                              int out_count);
+
+/**
+ * Single-sided amplitude spectrum of uniformly sampled data.
+ *
+ * Bin k is at k * fs / len. A sine of amplitude A on an exact bin reads A.
+ *
+ * @param window     WAVEFORM_WINDOW_* constant
+ * @return           Number of bins (len / 2 + 1), or -1 on error
+ */
+int waveform_fft_magnitude(const double *samples,
+                           int len,
+                           int window,
+                           double *out_buffer,
+                           int max_count);
+
+/**
+ * Total harmonic distortion as a ratio (multiply by 100 for percent).
+ *
+ * @param max_harmonic Highest harmonic included (at least 2)
+ * @param window       WAVEFORM_WINDOW_* constant
+ * @param out_value    Output for the THD ratio
+ * @return             0 on success, -1 on error
+ */
+int waveform_thd(const double *samples, int len, int max_harmonic, int window, double *out_value);
+
+/**
+ * Legacy alias for waveform_read()
+ */
+struct CWaveformResult *hspice_read(const char *filename, int debug);
+
+/**
+ * Legacy alias for waveform_free()
+ */
+void hspice_result_free(struct CWaveformResult *result);
+
+/**
+ * Legacy alias for waveform_init_logging()
+ */
+int hspice_init_logging(const char *level);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* HSPICE_H */
@@ -0,0 +1,257 @@
+#!/usr/bin/env python3
+"""
+Benchmark this parser against other Python waveform readers.
+
+Each parser reads each file in a fresh subprocess so wall time and peak
+RSS are not skewed by earlier runs. Values are compared signal by signal
+against this crate's result.
+
+Parsers:
+    hspicetr0parser  this crate (Python bindings), always the reference
+    hspicefile       PyOPUS-style HSPICE reader (pip install hspicefile)
+    spicelib         ngspice/LTspice raw reader, .raw files only
+
+Parsers that are not installed or do not support a file are reported as
+skipped.
+
+Usage:
+    python scripts/compare_bench.py example/*.tr0 --repeat 5
+    python scripts/compare_bench.py big.tr0 --json results.json
+"""
+
+import argparse
+import json
+import os
+import pickle
+import platform
+import re
+import resource
+import subprocess
+import sys
+import tempfile
+import time
+
+REFERENCE = "hspicetr0parser"
+
+
+# =============================================================================
+# Parser adapters (run inside the worker process)
+#
+# Each returns {signal name: numpy array} for the first sweep table.
+# =============================================================================
+
+def load_hspicetr0parser(path):
+    import numpy as np
+    from hspice_tr0_parser import read, read_raw
+
+    result = read_raw(path) if path.lower().endswith(".raw") else read(path)
+    if result is None:
+        raise RuntimeError("read returned None")
+    return {v.name: np.asarray(result.get(v.name)) for v in result.variables}
+
+
+def load_hspicefile(path):
+    import numpy as np
+    from hspicefile import hspice_read
+
+    if path.lower().endswith(".raw"):
+        raise NotImplementedError("HSPICE binary files only")
+    data = hspice_read(path)
+    if data is None:
+        raise RuntimeError("hspice_read returned None")
+    # [(sweep name, sweep values, [one dict per table]), scale, ...]
+    tables = data[0][2]
+    return {name: np.asarray(values) for name, values in tables[0].items()}
+
+
+def load_spicelib(path):
+    import numpy as np
+    from spicelib import RawRead
+
+    if not path.lower().endswith(".raw"):
+        raise NotImplementedError("raw files only")
+    raw = RawRead(path)
+    return {
+        name: np.asarray(raw.get_trace(name).get_wave(0))
+        for name in raw.get_trace_names()
+    }
+
+
+ADAPTERS = {
+    "hspicetr0parser": ("hspice_tr0_parser", load_hspicetr0parser),
+    "hspicefile": ("hspicefile", load_hspicefile),
+    "spicelib": ("spicelib", load_spicelib),
+}
+
+
+def peak_rss_bytes():
+    """Peak resident set size of this process"""
+    peak = resource.getrusage(resource.RUSAGE_SELF).ru_maxrss
+    # Linux reports KiB, macOS bytes
+    return peak if platform.system() == "Darwin" else peak * 1024
+
+
+def run_worker(parser, path, repeat, out_path):
+    """Time `repeat` reads and save the last result for comparison"""
+    module, load = ADAPTERS[parser]
+    report = {"parser": parser, "file": path}
+    try:
+        __import__(module)
+    except ImportError:
+        report["skipped"] = "not installed"
+    else:
+        baseline = peak_rss_bytes()
+        times = []
+        try:
+            for _ in range(repeat):
+                start = time.perf_counter()
+                signals = load(path)
+                times.append(time.perf_counter() - start)
+        except NotImplementedError as e:
+            report["skipped"] = str(e)
+        except Exception as e:  # report parser failures, keep benchmarking
+            report["error"] = f"{type(e).__name__}: {e}"
+        else:
+            report["times"] = times
+            report["rss_bytes"] = peak_rss_bytes() - baseline
+            report["signals"] = len(signals)
+            with open(out_path, "wb") as f:
+                pickle.dump(signals, f)
+    with open(out_path + ".json", "w") as f:
+        json.dump(report, f)
+
+
+# =============================================================================
+# Driver
+# =============================================================================
+
+def normalize(name):
+    """Match names across parsers: case-insensitive, v(node) == node"""
+    name = name.strip().lower()
+    match = re.fullmatch(r"v\((.*)\)", name)
+    return match.group(1) if match else name
+
+
+def compare(reference, other):
+    """Largest absolute and relative difference over common signals"""
+    import numpy as np
+
+    ref = {normalize(k): v for k, v in reference.items()}
+    oth = {normalize(k): v for k, v in other.items()}
+    common = sorted(ref.keys() & oth.keys())
+    max_abs = max_rel = 0.0
+    length_mismatch = []
+    for name in common:
+        a, b = ref[name], oth[name]
+        if a.shape != b.shape:
+            length_mismatch.append(name)
+            continue
+        diff = np.abs(a - b)
+        if diff.size:
+            max_abs = max(max_abs, float(diff.max()))
+            scale = np.maximum(np.abs(a), np.finfo(float).tiny)
+            max_rel = max(max_rel, float((diff / scale).max()))
+    return {
+        "common": len(common),
+        "missing": sorted(ref.keys() - oth.keys()),
+        "extra": sorted(oth.keys() - ref.keys()),
+        "length_mismatch": length_mismatch,
+        "max_abs_delta": max_abs,
+        "max_rel_delta": max_rel,
+    }
+
+
+def bench_file(path, parsers, repeat, workdir):
+    reports = []
+    signals = {}
+    for parser in parsers:
+        out_path = os.path.join(workdir, f"{parser}.pickle")
+        subprocess.run(
+            [sys.executable, __file__, "--worker", parser, path, str(repeat), out_path],
+            check=True,
+        )
+        with open(out_path + ".json") as f:
+            report = json.load(f)
+        if "times" in report:
+            with open(out_path, "rb") as f:
+                signals[parser] = pickle.load(f)
+        reports.append(report)
+
+    for report in reports:
+        parser = report["parser"]
+        if parser != REFERENCE and parser in signals and REFERENCE in signals:
+            report["delta"] = compare(signals[REFERENCE], signals[parser])
+    return reports
+
+
+def format_report(report):
+    name = f"  {report['parser']:<16}"
+    if "skipped" in report:
+        return f"{name} skipped ({report['skipped']})"
+    if "error" in report:
+        return f"{name} failed: {report['error']}"
+    times = sorted(report["times"])
+    line = (
+        f"{name} best {times[0] * 1e3:9.2f} ms  median {times[len(times) // 2] * 1e3:9.2f} ms"
+        f"  rss +{report['rss_bytes'] / 1048576:7.1f} MiB  {report['signals']} signals"
+    )
+    delta = report.get("delta")
+    if delta:
+        line += (
+            f"\n  {'':<16} max |delta| {delta['max_abs_delta']:.3e}"
+            f" (rel {delta['max_rel_delta']:.3e}) over {delta['common']} signals"
+        )
+        if delta["missing"] or delta["extra"] or delta["length_mismatch"]:
+            line += (
+                f", {len(delta['missing'])} missing, {len(delta['extra'])} extra,"
+                f" {len(delta['length_mismatch'])} length mismatches"
+            )
+    return line
+
+
+def main():
+    if len(sys.argv) > 1 and sys.argv[1] == "--worker":
+        parser, path, repeat, out_path = sys.argv[2:6]
+        run_worker(parser, path, int(repeat), out_path)
+        return
+
+    ap = argparse.ArgumentParser(description=__doc__.split("\n\n")[1])
+    ap.add_argument("files", nargs="+", help="waveform files to read")
+    ap.add_argument(
+        "--parsers",
+        default=",".join(ADAPTERS),
+        help="comma-separated parsers (default: all)",
+    )
+    ap.add_argument("--repeat", type=int, default=3, help="reads per parser and file")
+    ap.add_argument("--json", metavar="PATH", help="also write results as JSON")
+    args = ap.parse_args()
+
+    parsers = [p.strip() for p in args.parsers.split(",") if p.strip()]
+    unknown = [p for p in parsers if p not in ADAPTERS]
+    if unknown:
+        ap.error(f"unknown parsers: {', '.join(unknown)}")
+    if REFERENCE not in parsers:
+        parsers.insert(0, REFERENCE)
+
+    results = {
+        "python": sys.version.split()[0],
+        "platform": platform.platform(),
+        "repeat": args.repeat,
+        "files": [],
+    }
+    for path in args.files:
+        size = os.path.getsize(path)
+        print(f"{path} ({size / 1048576:.1f} MiB)")
+        with tempfile.TemporaryDirectory() as workdir:
+            reports = bench_file(path, parsers, max(args.repeat, 1), workdir)
+        for report in reports:
+            print(format_report(report))
+        results["files"].append({"file": path, "bytes": size, "results": reports})
+
+    if args.json:
+        with open(args.json, "w") as f:
+            json.dump(results, f, indent=2)
+
+
+if __name__ == "__main__":
+    main()
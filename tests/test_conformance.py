@@ -0,0 +1,89 @@
+"""
+Conformance tests against the checked-in golden summaries.
+
+Each input under conformance/inputs is parsed through the Python bindings and
+compared with conformance/expected/<name>.json (see conformance/README.md).
+"""
+
+import hashlib
+import json
+
+import numpy as np
+import pytest
+
+from tests.conftest import PROJECT_ROOT
+
+CONFORMANCE_DIR = PROJECT_ROOT / "conformance"
+GOLDEN_FILES = sorted((CONFORMANCE_DIR / "expected").glob("*.json"))
+
+
+def load_case(golden_path):
+    """Load a golden summary and parse its input with the matching reader"""
+    from hspice_tr0_parser import read, read_raw
+
+    with open(golden_path) as f:
+        expected = json.load(f)
+    input_path = str(CONFORMANCE_DIR / expected["input"])
+    reader = read if expected["format"] == "hspice" else read_raw
+    return reader(input_path), expected
+
+
+def vector_sha256(values):
+    """SHA-256 of a vector as little-endian doubles (complex: re, im pairs)"""
+    dtype = "<c16" if np.iscomplexobj(values) else "<f8"
+    return hashlib.sha256(np.asarray(values, dtype=dtype).tobytes()).hexdigest()
+
+
+@pytest.fixture(params=GOLDEN_FILES, ids=[p.stem for p in GOLDEN_FILES])
+def conformance_case(request):
+    """
+    Parametrized fixture over all conformance cases.
+    Yields: (result, expected_summary)
+    """
+    result, expected = load_case(request.param)
+    assert result is not None, f"Failed to read {expected['input']}"
+    return result, expected
+
+
+class TestConformance:
+    """Python bindings must reproduce every golden summary"""
+
+    def test_cases_present(self):
+        """Test that the conformance directory is populated"""
+        assert len(GOLDEN_FILES) > 0
+
+    def test_metadata(self, conformance_case):
+        """Test title, date, analysis and sweep parameter"""
+        result, expected = conformance_case
+        assert result.title == expected["title"]
+        assert result.date == expected["date"]
+        assert result.analysis == expected["analysis"]
+        assert result.sweep_param == expected["sweep_param"]
+
+    def test_variables(self, conformance_case):
+        """Test variable names and types in order"""
+        result, expected = conformance_case
+        names = [v["name"] for v in expected["variables"]]
+        types = [v["var_type"] for v in expected["variables"]]
+        assert [v.name for v in result.variables] == names
+        assert [v.var_type for v in result.variables] == types
+
+    def test_tables(self, conformance_case):
+        """Test every vector's hash and spot samples"""
+        result, expected = conformance_case
+        names = [v["name"] for v in expected["variables"]]
+        assert len(result.tables) == len(expected["tables"])
+
+        for table, golden in zip(result.tables, expected["tables"]):
+            assert table.sweep_value == golden["sweep_value"]
+            for name, vector in zip(names, golden["vectors"]):
+                values = table.get(name)
+                assert len(values) == golden["points"]
+                assert np.iscomplexobj(values) == vector["complex"]
+                assert vector_sha256(values) == vector["sha256"], name
+                for sample in vector["samples"]:
+                    value = values[sample["index"]]
+                    if vector["complex"]:
+                        assert [value.real, value.imag] == sample["value"]
+                    else:
+                        assert [value] == sample["value"]
@@ -154,5 +154,44 @@ class TestDataIntegrity:
                     f"Variable count mismatch: {raw_variables} vs {original_variables}"
 
 
+class TestSave:
+    """Tests for writing a result already in memory"""
+
+    @pytest.fixture(autouse=True)
+    def setup(self):
+        """Setup: verify test file exists"""
+        if not EXAMPLE_TR0.exists():
+            pytest.skip(f"Test file not found: {EXAMPLE_TR0}")
+
+    def test_to_raw_round_trip(self, temp_raw_file):
+        """A saved raw file reads back with the same data"""
+        from hspice_tr0_parser import read_raw
+
+        result = read_waveform(EXAMPLE_TR0)
+        assert result.to_raw(temp_raw_file) is True
+        raw = read_raw(temp_raw_file)
+        assert raw.var_names() == result.var_names()
+        np.testing.assert_allclose(raw.get(result.scale_name),
+                                   result.get(result.scale_name))
+
+    def test_save_csv_with_signals(self, tmp_path):
+        """save() picks the format from the extension and keeps the scale"""
+        result = read_waveform(EXAMPLE_TR0)
+        signal = result.var_names()[1]
+        path = tmp_path / "out.csv"
+        assert result.save(str(path), signals=signal) is True
+        lines = path.read_text().splitlines()
+        assert lines[0] == f"{result.scale_name},{signal}"
+        assert len(lines) == len(result) + 1
+
+    def test_save_rejects_unknown_format(self, tmp_path):
+        """An unknown extension is an error, not a silent default"""
+        result = read_waveform(EXAMPLE_TR0)
+        with pytest.raises(ValueError):
+            result.save(str(tmp_path / "out.xyz"))
+        with pytest.raises(IndexError):
+            result.to_csv(str(tmp_path / "out.csv"), table=5)
+
+
 if __name__ == "__main__":
     pytest.main([__file__, "-v"])
@@ -0,0 +1,78 @@
+"""
+Test suite for pandas DataFrame conversion.
+
+Skipped when pandas is not installed.
+"""
+
+import pytest
+import numpy as np
+
+from tests.conftest import read_waveform, EXAMPLE_DIR
+
+pd = pytest.importorskip("pandas")
+
+
+def read_example(filename):
+    filepath = EXAMPLE_DIR / filename
+    if not filepath.exists():
+        pytest.skip(f"Test file not found: {filepath}")
+    result = read_waveform(filepath)
+    assert result is not None
+    return result
+
+
+class TestWideFormat:
+    """Scale as index, one column per signal"""
+
+    def test_transient_frame(self):
+        result = read_example("test_9601.tr0")
+        df = result.to_dataframe()
+
+        names = result.var_names()
+        assert df.index.name == result.scale_name
+        assert list(df.columns) == names[1:]
+        assert len(df) == len(result)
+        np.testing.assert_array_equal(df.index.to_numpy(), result.get(names[0]))
+        np.testing.assert_array_equal(df[names[1]].to_numpy(), result.get(names[1]))
+
+    def test_ac_keeps_complex(self):
+        result = read_example("test_9601.ac0")
+        df = result.to_dataframe()
+
+        complex_columns = [c for c in df.columns if np.iscomplexobj(result.get(c))]
+        assert complex_columns, "AC example should have complex signals"
+        for column in complex_columns:
+            assert df[column].dtype == np.complex128
+
+    def test_table_frame(self):
+        result = read_example("test_9601.tr0")
+        table = result.tables[0]
+        df = table.to_dataframe()
+        assert list(df.columns) == table.keys()[1:]
+        assert len(df) == len(table)
+
+
+class TestLongFormat:
+    """One row per sample with signal and value columns"""
+
+    def test_long_frame(self):
+        result = read_example("test_9601.tr0")
+        df = result.to_dataframe(long=True)
+
+        names = result.var_names()
+        assert set(df["signal"]) == set(names[1:])
+        assert len(df) == len(result) * (len(names) - 1)
+
+        rows = df[df["signal"] == names[1]]
+        np.testing.assert_array_equal(rows[result.scale_name].to_numpy(), result.get(names[0]))
+        np.testing.assert_array_equal(rows["value"].to_numpy(), result.get(names[1]))
+
+    def test_long_frame_sweep_column(self):
+        result = read_example("test_9601.sw0")
+        df = result.to_dataframe(long=True)
+        if result.tables[0].sweep_value is None:
+            assert "value" in df.columns
+            return
+        sweep = result.sweep_param or "sweep"
+        assert df.columns[0] == sweep
+        assert set(df[sweep]) == {t.sweep_value for t in result.tables}
@@ -149,6 +149,45 @@ class TestFormatComparison:
             f"Data length differs: 9601={len(result_9601)}, 2001={len(result_2001)}"
 
 
+class TestReadAny:
+    """Tests for read_any format auto-detection"""
+
+    def test_read_any_hspice(self, format_testcase):
+        """Test that read_any matches read() on HSPICE files"""
+        from hspice_tr0_parser import read_any
+        filepath, _, analysis_type = format_testcase
+
+        result = read_any(filepath)
+        expected = read_waveform(filepath)
+        assert result is not None, f"read_any failed on {filepath}"
+        assert result.analysis == analysis_type
+        assert [v.name for v in result.variables] == [v.name for v in expected.variables]
+        assert len(result) == len(expected)
+
+    def test_read_any_raw(self, tmp_path):
+        """Test that read_any reads a SPICE3 raw file, ignoring the extension"""
+        from hspice_tr0_parser import read_any, read_raw, convert_to_raw
+        source = EXAMPLE_DIR / "test_9601.tr0"
+        if not source.exists():
+            pytest.skip(f"Test file not found: {source}")
+
+        # Misleading extension: detection goes by content first
+        output = tmp_path / "converted.tr0"
+        assert convert_to_raw(str(source), str(output))
+
+        result = read_any(str(output))
+        expected = read_raw(str(output))
+        assert result is not None
+        np.testing.assert_array_equal(
+            result.get(result.scale_name), expected.get(expected.scale_name)
+        )
+
+    def test_read_any_missing_file(self):
+        """Test that read_any returns None for a missing file"""
+        from hspice_tr0_parser import read_any
+        assert read_any("/nonexistent/path/file.tr0") is None
+
+
 class TestDebugOutput:
     """Tests for debug output functionality"""
     
@@ -0,0 +1,66 @@
+"""
+Test suite for expressions and measurements on a WaveformResult.
+"""
+
+import pytest
+import numpy as np
+
+from tests.conftest import read_waveform, EXAMPLE_TR0
+
+
+@pytest.fixture
+def result():
+    """The example transient result"""
+    if not EXAMPLE_TR0.exists():
+        pytest.skip(f"Test file not found: {EXAMPLE_TR0}")
+    return read_waveform(EXAMPLE_TR0)
+
+
+class TestEval:
+    """Tests for result.eval()"""
+
+    def test_signal_and_arithmetic(self, result):
+        """A bare name evaluates to the signal, arithmetic works point-wise"""
+        name = result.var_names()[1]
+        values = result.get(name)
+        np.testing.assert_allclose(result.eval(name), values)
+        np.testing.assert_allclose(result.eval(f"2*{name} - 1"), 2 * values - 1)
+        np.testing.assert_allclose(result.eval(f"mag(-{name})"), np.abs(values))
+
+    def test_invalid_expression(self, result):
+        """Parse errors and unknown signals raise ValueError"""
+        with pytest.raises(ValueError):
+            result.eval("1 +")
+        with pytest.raises(ValueError):
+            result.eval("v(no_such_node)")
+        with pytest.raises(IndexError):
+            result.eval("1", table=10)
+
+
+class TestMeasure:
+    """Tests for result.measure()"""
+
+    def test_crossings_match_levels(self, result):
+        """Crossings of the mid level lie within the scale"""
+        names = [n for n in result.var_names()[1:] if np.ptp(result.get(n)) > 0]
+        if not names:
+            pytest.skip("no signal changes")
+        name = names[0]
+        values = result.get(name)
+        level = (values.min() + values.max()) / 2
+        times = result.measure("crossings", name, level=level, edge="either")
+        scale = result.get(result.scale_name)
+        assert len(times) > 0
+        assert np.all((times >= scale[0]) & (times <= scale[-1]))
+
+    def test_unknown_kind(self, result):
+        """An unknown measurement raises ValueError"""
+        name = result.var_names()[1]
+        with pytest.raises(ValueError):
+            result.measure("slewrate", name)
+        with pytest.raises(ValueError):
+            result.measure("delay", name)
+
+
+if __name__ == "__main__":
+    pytest.main([__file__, "-v"])
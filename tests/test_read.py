@@ -165,5 +165,239 @@ class TestEdgeCases:
         assert result.analysis == "transient", f"Expected 'transient', got '{result.analysis}'"
 
 
+class TestZeroCopyArrays:
+    """Arrays are read-only views of the parsed data, not copies"""
+
+    def test_arrays_share_table_memory(self):
+        """Test that repeated gets view the same buffer"""
+        result = read_waveform(EXAMPLE_TR0)
+        name = result.variables[1].name
+
+        first = result.get(name)
+        again = result.tables[0].get(name)
+        assert np.shares_memory(first, again)
+        assert first.base is not None
+
+    def test_arrays_are_read_only(self):
+        """Test that views cannot modify the parsed data"""
+        result = read_waveform(EXAMPLE_TR0)
+        values = result.get(result.variables[0].name)
+
+        assert not values.flags.writeable
+        with pytest.raises(ValueError):
+            values[0] = 1.0
+
+        # A copy is writable
+        copy = values.copy()
+        copy[0] = 1.0
+
+    def test_view_outlives_result(self):
+        """Test that a view keeps its data alive after the result is dropped"""
+        result = read_waveform(EXAMPLE_TR0)
+        name = result.variables[0].name
+        expected = result.get(name).copy()
+        values = result.get(name)
+        del result
+
+        np.testing.assert_array_equal(values, expected)
+
+
+class TestLazyWaveform:
+    """Tests for on-demand signal access"""
+
+    @pytest.fixture(autouse=True)
+    def setup(self):
+        """Setup: verify test file exists"""
+        if not EXAMPLE_TR0.exists():
+            pytest.skip(f"Test file not found: {EXAMPLE_TR0}")
+
+    def test_signals_match_read(self):
+        """Test that signal names match the eager reader"""
+        from hspice_tr0_parser import read_lazy
+        wave = read_lazy(str(EXAMPLE_TR0))
+        result = read_waveform(EXAMPLE_TR0)
+
+        assert wave.signals == [v.name for v in result.variables]
+        assert wave.scale_name == result.scale_name
+        assert len(wave) == len(result)
+
+    def test_getitem_decodes_signal(self):
+        """Test that indexing decodes the same data as read()"""
+        from hspice_tr0_parser import read_lazy
+        wave = read_lazy(str(EXAMPLE_TR0))
+        result = read_waveform(EXAMPLE_TR0)
+
+        for name in wave.signals[:5]:
+            assert name in wave
+            np.testing.assert_array_equal(wave[name], result.get(name))
+
+    def test_unknown_signal(self):
+        """Test KeyError from indexing and None from get()"""
+        from hspice_tr0_parser import read_lazy
+        wave = read_lazy(str(EXAMPLE_TR0))
+
+        assert "no_such_signal" not in wave
+        assert wave.get("no_such_signal") is None
+        with pytest.raises(KeyError):
+            wave["no_such_signal"]
+
+    def test_missing_file(self):
+        """Test that a missing file returns None"""
+        from hspice_tr0_parser import read_lazy
+        assert read_lazy("/nonexistent/path/file.tr0") is None
+
+    def test_context_manager_closes(self):
+        """Test that leaving a with block unmaps the file"""
+        from hspice_tr0_parser import read_lazy
+        with read_lazy(str(EXAMPLE_TR0)) as wave:
+            assert not wave.closed
+            scale = wave[wave.scale_name]
+
+        assert wave.closed
+        assert len(scale) > 0
+        with pytest.raises(ValueError):
+            wave[wave.signals[0]]
+        with pytest.raises(ValueError):
+            len(wave)
+        wave.close()
+
+
+class TestClose:
+    """Tests for WaveformResult.close()"""
+
+    def test_close_empties_result(self):
+        """Test that close() releases tables but not returned arrays"""
+        if not EXAMPLE_TR0.exists():
+            pytest.skip(f"Test file not found: {EXAMPLE_TR0}")
+        with read_waveform(EXAMPLE_TR0) as result:
+            name = result.var_names()[0]
+            values = result.get(name)
+            expected = values.copy()
+
+        assert result.closed
+        assert len(result) == 0
+        assert result.get(name) is None
+        np.testing.assert_array_equal(values, expected)
+
+
+class TestProgress:
+    """Tests for progress reporting during read()"""
+
+    @pytest.fixture(autouse=True)
+    def setup(self):
+        """Setup: verify test file exists"""
+        if not EXAMPLE_TR0.exists():
+            pytest.skip(f"Test file not found: {EXAMPLE_TR0}")
+
+    def test_callable(self):
+        """Test that a callable gets (bytes, total_bytes) up to the file size"""
+        from hspice_tr0_parser import read
+        calls = []
+        result = read(str(EXAMPLE_TR0), progress=lambda done, total: calls.append((done, total)))
+
+        assert result is not None
+        size = EXAMPLE_TR0.stat().st_size
+        assert calls[-1] == (size, size)
+        assert [done for done, _ in calls] == sorted(done for done, _ in calls)
+
+    def test_tqdm_like(self):
+        """Test that an object with update() is advanced by the bytes read"""
+        from hspice_tr0_parser import read
+
+        class Bar:
+            total = None
+            n = 0
+
+            def update(self, n):
+                self.n += n
+
+        bar = Bar()
+        read(str(EXAMPLE_TR0), progress=bar)
+        size = EXAMPLE_TR0.stat().st_size
+        assert bar.total == size
+        assert bar.n == size
+
+    def test_callback_error_raised(self):
+        """Test that an exception from the callback propagates"""
+        from hspice_tr0_parser import read
+
+        def fail(done, total):
+            raise RuntimeError("stop")
+
+        with pytest.raises(RuntimeError):
+            read(str(EXAMPLE_TR0), progress=fail)
+
+
+class TestStats:
+    """Tests for per-signal statistics"""
+
+    @pytest.fixture(autouse=True)
+    def setup(self):
+        """Setup: verify test file exists"""
+        if not EXAMPLE_TR0.exists():
+            pytest.skip(f"Test file not found: {EXAMPLE_TR0}")
+
+    def test_stats_match_numpy(self):
+        """Test that stats() agrees with numpy on every signal"""
+        result = read_waveform(EXAMPLE_TR0)
+        stats = result.stats()
+
+        for name in result.var_names()[1:6]:
+            values = result.get(name)
+            s = stats[name]
+            assert s["count"] == len(values)
+            assert s["min"] == values.min()
+            assert s["max"] == values.max()
+            assert s["p2p"] == pytest.approx(np.ptp(values))
+            assert s["mean"] == pytest.approx(values.mean())
+            assert s["rms"] == pytest.approx(np.sqrt(np.mean(values ** 2)))
+            assert s["std"] == pytest.approx(values.std())
+            assert s["magnitude"] is False
+
+    def test_table_stats(self):
+        """Test that a single table gives the same stats as the result"""
+        result = read_waveform(EXAMPLE_TR0)
+        assert result.tables[0].stats() == result.stats()
+
+
+class TestReadCorners:
+    """Tests for reading many files in parallel"""
+
+    @pytest.fixture(autouse=True)
+    def setup(self):
+        """Setup: verify test files exist"""
+        if not (EXAMPLE_DIR / "test_9601.tr0").exists():
+            pytest.skip(f"Test files not found in {EXAMPLE_DIR}")
+
+    def test_glob_matches_read(self):
+        """Test that a glob reads each file as read() does"""
+        from hspice_tr0_parser import read, read_corners
+        corners = read_corners(str(EXAMPLE_DIR / "test_*.tr0"), threads=2)
+
+        assert list(corners) == ["test_2001", "test_9601"]
+        for name, result in corners.items():
+            expected = read(str(EXAMPLE_DIR / f"{name}.tr0"))
+            assert result.tables == expected.tables
+
+    def test_names_keep_corners_apart(self):
+        """Test that repeated stems fall back to the file name"""
+        from hspice_tr0_parser import read_corners
+        paths = [str(EXAMPLE_DIR / "test_9601.tr0"), str(EXAMPLE_DIR / "test_9601.ac0")]
+        corners = read_corners(paths)
+
+        assert list(corners) == ["test_9601.tr0", "test_9601.ac0"]
+        assert corners["test_9601.ac0"].analysis == "ac"
+
+    def test_unreadable_file_is_none(self):
+        """Test that a failed file maps to None without failing the rest"""
+        from hspice_tr0_parser import read_corners
+        corners = read_corners(
+            [str(EXAMPLE_DIR / "test_9601.tr0"), "/nonexistent/path/ff.tr0"]
+        )
+
+        assert corners["ff"] is None
+        assert corners["test_9601"] is not None
+
+
 if __name__ == "__main__":
     pytest.main([__file__, "-v"])
@@ -252,3 +252,41 @@ class TestStreamingMemoryEfficiency:
         
         # Should have read exactly 2 chunks (or fewer if file is small)
         assert chunks_read <= 2
+
+    def test_stream_is_lazy_iterator(self):
+        """Test that stream returns a WaveformStream decoding on demand"""
+        from hspice_tr0_parser import stream, WaveformStream
+        
+        result = stream(str(EXAMPLE_TR0), chunk_size=10)
+        assert isinstance(result, WaveformStream)
+        assert iter(result) is result
+        
+        # Chunks come one at a time, and the iterator ends cleanly
+        first = next(result)
+        assert first['chunk_index'] == 0
+        remaining = list(result)
+        assert all(c['chunk_index'] > 0 for c in remaining)
+        with pytest.raises(StopIteration):
+            next(result)
+
+
+class TestStreamClose:
+    """Test closing a stream before the end"""
+
+    def test_close_stops_iteration(self):
+        """Test that close() ends the iteration"""
+        from hspice_tr0_parser import stream
+        chunks = stream(str(EXAMPLE_TR0), chunk_size=10)
+        next(chunks)
+        chunks.close()
+        assert chunks.closed
+        assert list(chunks) == []
+        chunks.close()
+
+    def test_context_manager_closes(self):
+        """Test that leaving a with block closes the stream"""
+        from hspice_tr0_parser import stream
+        with stream(str(EXAMPLE_TR0), chunk_size=10, prefetch=2) as chunks:
+            assert not chunks.closed
+            next(chunks)
+        assert chunks.closed
@@ -0,0 +1,107 @@
+"""
+Tests for the type stubs and the value semantics of small classes.
+"""
+
+import ast
+import inspect
+
+import pytest
+
+import hspicetr0parser
+from tests.conftest import PROJECT_ROOT, EXAMPLE_TR0, read_waveform
+
+STUB = PROJECT_ROOT / "hspicetr0parser.pyi"
+
+
+def stub_members():
+    """Top-level functions and classes of the stub, with class members"""
+    tree = ast.parse(STUB.read_text())
+    functions, classes = set(), {}
+    for node in tree.body:
+        if isinstance(node, ast.FunctionDef):
+            functions.add(node.name)
+        elif isinstance(node, ast.ClassDef) and not node.name.startswith("_"):
+            classes[node.name] = {
+                item.name for item in node.body if isinstance(item, ast.FunctionDef)
+            }
+    return functions, classes
+
+
+class TestStub:
+    """Test that the stub matches the compiled module"""
+
+    def test_functions_match(self):
+        """Test that every module function is stubbed and vice versa"""
+        functions, _ = stub_members()
+        module = {
+            name for name, value in vars(hspicetr0parser).items()
+            if callable(value) and not inspect.isclass(value) and not name.startswith("_")
+        }
+        assert functions == module
+
+    def test_class_members_match(self):
+        """Test that each class stub lists the public members of the class"""
+        _, classes = stub_members()
+        for name, value in vars(hspicetr0parser).items():
+            if not inspect.isclass(value) or name.startswith("_"):
+                continue
+            assert name in classes, f"{name} missing from the stub"
+            members = {m for m in vars(value) if not m.startswith("_")}
+            assert members <= classes[name], f"{name}: {members - classes[name]} not stubbed"
+
+    def test_function_defaults_match(self):
+        """Test that stubbed keyword defaults match the runtime signatures"""
+        tree = ast.parse(STUB.read_text())
+        for node in tree.body:
+            if not isinstance(node, ast.FunctionDef):
+                continue
+            runtime = inspect.signature(getattr(hspicetr0parser, node.name)).parameters
+            args = node.args.args
+            defaults = [None] * (len(args) - len(node.args.defaults)) + node.args.defaults
+            assert [a.arg for a in args] == list(runtime), node.name
+            for arg, default in zip(args, defaults):
+                if default is not None:
+                    assert ast.literal_eval(default) == runtime[arg.arg].default, \
+                        f"{node.name}({arg.arg})"
+
+
+class TestValueSemantics:
+    """Test reprs and equality of Variable and DataTable"""
+
+    @pytest.fixture(autouse=True)
+    def setup(self):
+        """Setup: verify test file exists"""
+        if not EXAMPLE_TR0.exists():
+            pytest.skip(f"Test file not found: {EXAMPLE_TR0}")
+
+    def test_variable_eq_and_hash(self):
+        """Test that variables compare and hash by value"""
+        a = read_waveform(EXAMPLE_TR0).variables
+        b = read_waveform(EXAMPLE_TR0).variables
+        assert a == b
+        assert len(set(a)) == len({v.name for v in a})
+        assert a[0] != a[1]
+
+    def test_variable_repr(self):
+        """Test that the repr names the attributes"""
+        var = read_waveform(EXAMPLE_TR0).variables[0]
+        assert repr(var) == (
+            f"Variable(name={var.name!r}, var_type={var.var_type!r}, unit={var.unit!r})"
+        )
+
+    def test_table_eq(self):
+        """Test that tables from two reads are equal"""
+        a = read_waveform(EXAMPLE_TR0).tables[0]
+        b = read_waveform(EXAMPLE_TR0).tables[0]
+        assert a == b
+
+        from hspice_tr0_parser import read
+        fewer = read(str(EXAMPLE_TR0), signals="no_such_signal*").tables[0]
+        assert a != fewer
+
+    def test_table_repr(self):
+        """Test that the repr shows the sweep value, size and names"""
+        table = read_waveform(EXAMPLE_TR0).tables[0]
+        text = repr(table)
+        assert text.startswith(f"DataTable(sweep_value={table.sweep_value!r}, points={len(table)}")
+        assert repr(table.keys()[0]) in text